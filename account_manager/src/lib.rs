@@ -3,6 +3,7 @@ pub mod validator;
 pub mod wallet;
 
 use clap::App;
+use clap::Arg;
 use clap::ArgMatches;
 use environment::Environment;
 use types::EthSpec;
@@ -12,11 +13,22 @@ pub const SECRETS_DIR_FLAG: &str = "secrets-dir";
 pub const VALIDATOR_DIR_FLAG: &str = "validator-dir";
 pub const VALIDATOR_DIR_FLAG_ALIAS: &str = "validators-dir";
 pub const WALLETS_DIR_FLAG: &str = "wallets-dir";
+pub const OFFLINE_FLAG: &str = "offline";
 
 pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
     App::new(CMD)
         .visible_aliases(&["a", "am", "account", CMD])
         .about("Utilities for generating and managing Ethereum 2.0 accounts.")
+        .arg(
+            Arg::with_name(OFFLINE_FLAG)
+                .long(OFFLINE_FLAG)
+                .global(true)
+                .help(
+                    "Only permit the commands which never access the network, for use on an \
+                    air-gapped machine. When creating validators, --deposit-output-dir must be \
+                    supplied so that deposits can be submitted later from another machine.",
+                ),
+        )
         .subcommand(wallet::cli_app())
         .subcommand(validator::cli_app())
 }
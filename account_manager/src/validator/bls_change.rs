@@ -0,0 +1,458 @@
+use crate::common::read_mnemonic_from_cli;
+use crate::wallet::create::STDIN_INPUTS_FLAG;
+use bls::Keypair;
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use eth2::{
+    types::{StateId, ValidatorId},
+    BeaconNodeHttpClient, Timeouts,
+};
+use eth2_keystore::{keypair_from_secret, Keystore};
+use eth2_wallet::bip39::Seed;
+use eth2_wallet::{recover_validator_secret_from_mnemonic, KeyType};
+use ethereum_hashing::hash;
+use sensitive_url::SensitiveUrl;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use types::{
+    Address, BlsToExecutionChange, ChainSpec, EthSpec, Hash256, PublicKeyBytes, Validator,
+};
+
+pub const CMD: &str = "bls-change";
+pub const VALIDATOR_INDEX_FLAG: &str = "validator-index";
+pub const EXECUTION_ADDRESS_FLAG: &str = "execution-address";
+pub const KEYSTORE_FLAG: &str = "withdrawal-keystore";
+pub const PASSWORD_FILE_FLAG: &str = "password-file";
+pub const MNEMONIC_FLAG: &str = "mnemonic-path";
+pub const DERIVATION_INDEX_FLAG: &str = "derivation-index";
+pub const BEACON_SERVER_FLAG: &str = "beacon-node";
+pub const DRY_RUN_FLAG: &str = "dry-run";
+pub const NO_CONFIRMATION: &str = "no-confirmation";
+pub const PASSWORD_PROMPT: &str = "Enter the withdrawal keystore password";
+
+pub const DEFAULT_BEACON_NODE: &str = "http://localhost:5052/";
+pub const CONFIRMATION_PHRASE: &str = "Change my withdrawal credentials";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Signs a BlsToExecutionChange for a validator with BLS withdrawal credentials and \
+            submits it to the beacon chain. The withdrawal key is read from a keystore or derived \
+            from a BIP-39 mnemonic.",
+        )
+        .arg(
+            Arg::with_name(VALIDATOR_INDEX_FLAG)
+                .long(VALIDATOR_INDEX_FLAG)
+                .value_name("VALIDATOR_INDEX")
+                .help("The index of the validator whose withdrawal credentials will be changed")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(EXECUTION_ADDRESS_FLAG)
+                .long(EXECUTION_ADDRESS_FLAG)
+                .value_name("EXECUTION_ADDRESS")
+                .help(
+                    "The execution address to which withdrawals will be sent. THIS CANNOT BE \
+                    CHANGED ONCE THE MESSAGE HAS BEEN INCLUDED IN THE BEACON CHAIN.",
+                )
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(KEYSTORE_FLAG)
+                .long(KEYSTORE_FLAG)
+                .value_name("KEYSTORE_PATH")
+                .help(
+                    "The path to the EIP-2335 withdrawal keystore for the validator. If omitted, \
+                    the withdrawal key will be derived from a mnemonic.",
+                )
+                .takes_value(true)
+                .conflicts_with(MNEMONIC_FLAG),
+        )
+        .arg(
+            Arg::with_name(PASSWORD_FILE_FLAG)
+                .long(PASSWORD_FILE_FLAG)
+                .value_name("PASSWORD_FILE_PATH")
+                .help("The path to the password file which unlocks the withdrawal keystore")
+                .takes_value(true)
+                .requires(KEYSTORE_FLAG),
+        )
+        .arg(
+            Arg::with_name(MNEMONIC_FLAG)
+                .long(MNEMONIC_FLAG)
+                .value_name("MNEMONIC_PATH")
+                .help(
+                    "If present, the mnemonic will be read in from this file. Otherwise the user \
+                    will be prompted for the mnemonic (unless a keystore is provided).",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(DERIVATION_INDEX_FLAG)
+                .long(DERIVATION_INDEX_FLAG)
+                .value_name("DERIVATION_INDEX")
+                .help(
+                    "The EIP-2334 key index of the validator when deriving the withdrawal key from \
+                    a mnemonic. This is not the same as the validator index.",
+                )
+                .takes_value(true)
+                .default_value("0"),
+        )
+        .arg(
+            Arg::with_name(BEACON_SERVER_FLAG)
+                .long(BEACON_SERVER_FLAG)
+                .value_name("NETWORK_ADDRESS")
+                .help("Address to a beacon node HTTP API")
+                .default_value(DEFAULT_BEACON_NODE)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(DRY_RUN_FLAG)
+                .long(DRY_RUN_FLAG)
+                .help(
+                    "Print the signed message as JSON to stdout instead of submitting it to the \
+                    beacon node. The beacon node is not contacted.",
+                ),
+        )
+        .arg(
+            Arg::with_name(NO_CONFIRMATION)
+                .long(NO_CONFIRMATION)
+                .help(
+                    "Submits the message without prompting for confirmation. This should be used \
+                    with caution",
+                ),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .takes_value(false)
+                .hidden(cfg!(windows))
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty."),
+        )
+}
+
+pub fn cli_run<E: EthSpec>(matches: &ArgMatches, env: Environment<E>) -> Result<(), String> {
+    let validator_index: u64 = clap_utils::parse_required(matches, VALIDATOR_INDEX_FLAG)?;
+    let execution_address: Address = clap_utils::parse_required(matches, EXECUTION_ADDRESS_FLAG)?;
+    let keystore_path: Option<PathBuf> = clap_utils::parse_optional(matches, KEYSTORE_FLAG)?;
+    let password_file_path: Option<PathBuf> =
+        clap_utils::parse_optional(matches, PASSWORD_FILE_FLAG)?;
+    let mnemonic_path: Option<PathBuf> = clap_utils::parse_optional(matches, MNEMONIC_FLAG)?;
+    let derivation_index: u32 = clap_utils::parse_required(matches, DERIVATION_INDEX_FLAG)?;
+    let server_url: String = clap_utils::parse_required(matches, BEACON_SERVER_FLAG)?;
+
+    let stdin_inputs = cfg!(windows) || matches.is_present(STDIN_INPUTS_FLAG);
+    let dry_run = matches.is_present(DRY_RUN_FLAG);
+    let no_confirmation = matches.is_present(NO_CONFIRMATION);
+
+    let spec = env.eth2_config().spec.clone();
+    let genesis_validators_root = env
+        .eth2_network_config
+        .as_ref()
+        .ok_or("Unable to determine network configuration")?
+        .beacon_state::<E>()
+        .map_err(|e| format!("Unable to load genesis state: {}", e))?
+        .genesis_validators_root();
+
+    let keypair = match keystore_path {
+        Some(path) => load_withdrawal_keypair(&path, password_file_path.as_ref(), stdin_inputs)?,
+        None => derive_withdrawal_keypair(mnemonic_path, derivation_index, stdin_inputs)?,
+    };
+
+    let withdrawal_pubkey = keypair.pk.compress();
+    let sign = || {
+        BlsToExecutionChange {
+            validator_index,
+            from_bls_pubkey: withdrawal_pubkey,
+            to_execution_address: execution_address,
+        }
+        .sign(&keypair.sk, genesis_validators_root, &spec)
+    };
+
+    if dry_run {
+        let signed_change = sign();
+        let json = serde_json::to_string_pretty(&signed_change)
+            .map_err(|e| format!("Unable to serialize message: {:?}", e))?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    let client = BeaconNodeHttpClient::new(
+        SensitiveUrl::parse(&server_url)
+            .map_err(|e| format!("Failed to parse beacon http server: {:?}", e))?,
+        Timeouts::set_all(Duration::from_secs(spec.seconds_per_slot)),
+    );
+
+    env.runtime().block_on(async {
+        verify_with_beacon_node(
+            &client,
+            validator_index,
+            &withdrawal_pubkey,
+            genesis_validators_root,
+            &spec,
+        )
+        .await?;
+        let signed_change = sign();
+
+        eprintln!(
+            "Changing the withdrawal credentials of validator {} to execution address {:?}\n",
+            validator_index, execution_address
+        );
+
+        if !no_confirmation {
+            eprintln!("WARNING: THIS IS AN IRREVERSIBLE OPERATION\n");
+            eprintln!(
+                "Enter the phrase \"{}\" to confirm the change: ",
+                CONFIRMATION_PHRASE
+            );
+            let confirmation = account_utils::read_input_from_user(stdin_inputs)?;
+            if confirmation != CONFIRMATION_PHRASE {
+                eprintln!(
+                    "Did not submit the change for validator {}. Please check that you entered \
+                    the correct phrase.",
+                    validator_index
+                );
+                return Ok(());
+            }
+        }
+
+        client
+            .post_beacon_pool_bls_to_execution_changes(&[signed_change])
+            .await
+            .map_err(|e| format!("Failed to publish BLS to execution change: {}", e))?;
+
+        eprintln!(
+            "Successfully validated and published BLS to execution change for validator {}",
+            validator_index
+        );
+
+        Ok(())
+    })
+}
+
+/// Check that the beacon node is on the same network as the message and that the validator still
+/// has BLS withdrawal credentials for `withdrawal_pubkey`.
+async fn verify_with_beacon_node(
+    client: &BeaconNodeHttpClient,
+    validator_index: u64,
+    withdrawal_pubkey: &PublicKeyBytes,
+    genesis_validators_root: Hash256,
+    spec: &ChainSpec,
+) -> Result<(), String> {
+    let genesis_data = client
+        .get_beacon_genesis()
+        .await
+        .map_err(|e| format!("Failed to get beacon genesis: {}", e))?
+        .data;
+
+    if genesis_data.genesis_validators_root != genesis_validators_root {
+        return Err(
+            "Invalid genesis state. Please ensure that your beacon node is on the same network \
+                 as the validator you are publishing a change for"
+                .to_string(),
+        );
+    }
+
+    let validator = client
+        .get_beacon_states_validator_id(StateId::Head, &ValidatorId::Index(validator_index))
+        .await
+        .map_err(|e| format!("Failed to get validator details: {:?}", e))?
+        .ok_or_else(|| {
+            format!(
+                "Validator {} is not present in the beacon state. \
+                Please ensure that your beacon node is synced.",
+                validator_index
+            )
+        })?
+        .data
+        .validator;
+
+    verify_withdrawal_credentials(&validator, validator_index, withdrawal_pubkey, spec)
+}
+
+/// Check that the BLS withdrawal credentials of `validator` commit to `withdrawal_pubkey`, since
+/// the beacon chain rejects a change signed by any other key.
+fn verify_withdrawal_credentials(
+    validator: &Validator,
+    validator_index: u64,
+    withdrawal_pubkey: &PublicKeyBytes,
+    spec: &ChainSpec,
+) -> Result<(), String> {
+    if validator.has_eth1_withdrawal_credential(spec) {
+        return Err(format!(
+            "Validator {} already has execution withdrawal credentials",
+            validator_index
+        ));
+    }
+
+    let withdrawal_credentials = validator.withdrawal_credentials.as_bytes();
+    if withdrawal_credentials.first() != Some(&spec.bls_withdrawal_prefix_byte) {
+        return Err(format!(
+            "Validator {} does not have BLS withdrawal credentials",
+            validator_index
+        ));
+    }
+
+    let pubkey_hash = hash(withdrawal_pubkey.as_serialized());
+    if withdrawal_credentials.get(1..) != pubkey_hash.get(1..) {
+        return Err(format!(
+            "The withdrawal key {:?} does not match the withdrawal credentials of validator {}. \
+            Please check the keystore or the derivation index.",
+            withdrawal_pubkey, validator_index
+        ));
+    }
+
+    Ok(())
+}
+
+/// Load the withdrawal keypair by loading and decrypting the keystore.
+///
+/// If the `password_file_path` is Some, unlock keystore using password in given file
+/// otherwise, prompts user for a password to unlock the keystore.
+fn load_withdrawal_keypair(
+    keystore_path: &Path,
+    password_file_path: Option<&PathBuf>,
+    stdin_inputs: bool,
+) -> Result<Keypair, String> {
+    let keystore = Keystore::from_json_file(keystore_path)
+        .map_err(|e| format!("Unable to read keystore JSON {:?}: {:?}", keystore_path, e))?;
+
+    if let Some(password_file) = password_file_path {
+        validator_dir::unlock_keypair_from_password_path(keystore_path, password_file)
+            .map_err(|e| format!("Error while decrypting keypair: {:?}", e))
+    } else {
+        eprintln!();
+        eprintln!("{} for {:?}: ", PASSWORD_PROMPT, keystore_path);
+        let password = account_utils::read_password_from_user(stdin_inputs)?;
+        match keystore.decrypt_keypair(password.as_ref()) {
+            Ok(keypair) => {
+                eprintln!("Password is correct.");
+                eprintln!();
+                Ok(keypair)
+            }
+            Err(eth2_keystore::Error::InvalidPassword) => Err("Invalid password".to_string()),
+            Err(e) => Err(format!("Error while decrypting keypair: {:?}", e)),
+        }
+    }
+}
+
+/// Derive the withdrawal keypair at `derivation_index` from a BIP-39 mnemonic.
+fn derive_withdrawal_keypair(
+    mnemonic_path: Option<PathBuf>,
+    derivation_index: u32,
+    stdin_inputs: bool,
+) -> Result<Keypair, String> {
+    let mnemonic = read_mnemonic_from_cli(mnemonic_path, stdin_inputs)?;
+    let seed = Seed::new(&mnemonic, "");
+
+    let (secret, _) = recover_validator_secret_from_mnemonic(
+        seed.as_bytes(),
+        derivation_index,
+        KeyType::Withdrawal,
+    )
+    .map_err(|e| format!("Unable to recover withdrawal key: {:?}", e))?;
+
+    keypair_from_secret(secret.as_bytes())
+        .map_err(|e| format!("Unable to build withdrawal keypair: {:?}", e))
+}
+
+#[cfg(test)]
+#[cfg(not(debug_assertions))]
+mod tests {
+    use super::*;
+    use eth2_keystore::KeystoreBuilder;
+    use std::fs::{self, File};
+    use tempfile::tempdir;
+    use types::{Epoch, MainnetEthSpec};
+
+    const VALIDATOR_INDEX: u64 = 42;
+    const PASSWORD: &str = "cats";
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon \
+                            abandon abandon abandon about";
+
+    fn validator_with_bls_credentials(
+        withdrawal_pubkey: &PublicKeyBytes,
+        spec: &ChainSpec,
+    ) -> Validator {
+        let mut withdrawal_credentials = hash(withdrawal_pubkey.as_serialized());
+        withdrawal_credentials[0] = spec.bls_withdrawal_prefix_byte;
+        Validator {
+            pubkey: PublicKeyBytes::empty(),
+            withdrawal_credentials: Hash256::from_slice(&withdrawal_credentials),
+            effective_balance: spec.max_effective_balance,
+            slashed: false,
+            activation_eligibility_epoch: Epoch::new(0),
+            activation_epoch: Epoch::new(0),
+            exit_epoch: spec.far_future_epoch,
+            withdrawable_epoch: spec.far_future_epoch,
+        }
+    }
+
+    #[test]
+    fn mnemonic_withdrawal_key() {
+        let spec = MainnetEthSpec::default_spec();
+        let dir = tempdir().unwrap();
+        let mnemonic_path = dir.path().join("mnemonic.txt");
+        fs::write(&mnemonic_path, MNEMONIC).unwrap();
+
+        let keypair = derive_withdrawal_keypair(Some(mnemonic_path.clone()), 0, false).unwrap();
+        let validator = validator_with_bls_credentials(&keypair.pk.compress(), &spec);
+        verify_withdrawal_credentials(&validator, VALIDATOR_INDEX, &keypair.pk.compress(), &spec)
+            .unwrap();
+
+        // The key at another derivation index does not match the withdrawal credentials.
+        let wrong_keypair = derive_withdrawal_keypair(Some(mnemonic_path), 1, false).unwrap();
+        verify_withdrawal_credentials(
+            &validator,
+            VALIDATOR_INDEX,
+            &wrong_keypair.pk.compress(),
+            &spec,
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn keystore_withdrawal_key() {
+        let spec = MainnetEthSpec::default_spec();
+        let dir = tempdir().unwrap();
+        let keystore_path = dir.path().join("keystore.json");
+        let password_path = dir.path().join("password.pass");
+
+        let keystore = KeystoreBuilder::new(&Keypair::random(), PASSWORD.as_bytes(), "".into())
+            .unwrap()
+            .build()
+            .unwrap();
+        keystore
+            .to_json_writer(File::create(&keystore_path).unwrap())
+            .unwrap();
+        fs::write(&password_path, PASSWORD).unwrap();
+
+        let keypair = load_withdrawal_keypair(&keystore_path, Some(&password_path), false).unwrap();
+        let validator = validator_with_bls_credentials(&keypair.pk.compress(), &spec);
+        verify_withdrawal_credentials(&validator, VALIDATOR_INDEX, &keypair.pk.compress(), &spec)
+            .unwrap();
+
+        // The withdrawal credentials of another validator do not match the keystore.
+        let other_validator =
+            validator_with_bls_credentials(&Keypair::random().pk.compress(), &spec);
+        verify_withdrawal_credentials(
+            &other_validator,
+            VALIDATOR_INDEX,
+            &keypair.pk.compress(),
+            &spec,
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn execution_withdrawal_credentials() {
+        let spec = MainnetEthSpec::default_spec();
+        let keypair = Keypair::random();
+        let mut validator = validator_with_bls_credentials(&keypair.pk.compress(), &spec);
+        validator.change_withdrawal_credentials(&Address::repeat_byte(1), &spec);
+
+        verify_withdrawal_credentials(&validator, VALIDATOR_INDEX, &keypair.pk.compress(), &spec)
+            .unwrap_err();
+    }
+}
@@ -1,6 +1,6 @@
 use crate::common::read_wallet_name_from_cli;
 use crate::wallet::create::STDIN_INPUTS_FLAG;
-use crate::{SECRETS_DIR_FLAG, WALLETS_DIR_FLAG};
+use crate::{OFFLINE_FLAG, SECRETS_DIR_FLAG, WALLETS_DIR_FLAG};
 use account_utils::{
     random_password, read_password_from_user, strip_off_newlines, validator_definitions, PlainText,
 };
@@ -10,12 +10,15 @@ use directory::{
 };
 use environment::Environment;
 use eth2_wallet_manager::WalletManager;
+use serde::Serialize;
 use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
 use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
-use types::EthSpec;
-use validator_dir::Builder as ValidatorDirBuilder;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tree_hash::TreeHash;
+use types::{ChainSpec, EthSpec};
+use validator_dir::{Builder as ValidatorDirBuilder, Eth1DepositData};
 
 pub const CMD: &str = "create";
 pub const WALLET_NAME_FLAG: &str = "wallet-name";
@@ -24,6 +27,7 @@ pub const DEPOSIT_GWEI_FLAG: &str = "deposit-gwei";
 pub const STORE_WITHDRAW_FLAG: &str = "store-withdrawal-keystore";
 pub const COUNT_FLAG: &str = "count";
 pub const AT_MOST_FLAG: &str = "at-most";
+pub const DEPOSIT_OUTPUT_DIR_FLAG: &str = "deposit-output-dir";
 pub const WALLET_PASSWORD_PROMPT: &str = "Enter your wallet's password:";
 
 pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
@@ -103,6 +107,17 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .conflicts_with("count")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name(DEPOSIT_OUTPUT_DIR_FLAG)
+                .long(DEPOSIT_OUTPUT_DIR_FLAG)
+                .value_name("DEPOSIT_OUTPUT_DIR")
+                .help(
+                    "If present, write the deposits of the new validators to this directory, as \
+                    a deposit data JSON file for the staking launchpad and as a JSON file of \
+                    deposit contract transactions. Required with --offline.",
+                )
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name(STDIN_INPUTS_FLAG)
                 .takes_value(false)
@@ -139,6 +154,15 @@ pub fn cli_run<T: EthSpec>(
         .unwrap_or(spec.max_effective_balance);
     let count: Option<usize> = clap_utils::parse_optional(matches, COUNT_FLAG)?;
     let at_most: Option<usize> = clap_utils::parse_optional(matches, AT_MOST_FLAG)?;
+    let deposit_output_dir: Option<PathBuf> =
+        clap_utils::parse_optional(matches, DEPOSIT_OUTPUT_DIR_FLAG)?;
+
+    if matches.is_present(OFFLINE_FLAG) && deposit_output_dir.is_none() {
+        return Err(format!(
+            "--{} is required with --{}",
+            DEPOSIT_OUTPUT_DIR_FLAG, OFFLINE_FLAG
+        ));
+    }
 
     // The command will always fail if the wallet dir does not exist.
     if !wallet_base_dir.exists() {
@@ -212,6 +236,8 @@ pub fn cli_run<T: EthSpec>(
         )
     })?;
 
+    let mut deposits = vec![];
+
     for i in 0..n {
         let voting_password = random_password();
         let withdrawal_password = random_password();
@@ -241,7 +267,7 @@ pub fn cli_run<T: EthSpec>(
                 )
             })?;
 
-        ValidatorDirBuilder::new(validator_dir.clone())
+        let validator = ValidatorDirBuilder::new(validator_dir.clone())
             .password_dir(secrets_dir.clone())
             .voting_keystore(keystores.voting, voting_password.as_bytes())
             .withdrawal_keystore(keystores.withdrawal, withdrawal_password.as_bytes())
@@ -250,12 +276,118 @@ pub fn cli_run<T: EthSpec>(
             .build()
             .map_err(|e| format!("Unable to build validator directory: {:?}", e))?;
 
+        if deposit_output_dir.is_some() {
+            let deposit = validator
+                .eth1_deposit_data()
+                .map_err(|e| format!("Unable to read deposit data: {:?}", e))?
+                .ok_or("Validator directory is missing deposit data")?;
+            deposits.push(deposit);
+        }
+
         println!("{}/{}\t{}", i + 1, n, voting_pubkey.as_hex_string());
     }
 
+    if let Some(deposit_output_dir) = deposit_output_dir {
+        write_deposit_files(&deposit_output_dir, &deposits, &spec)?;
+    }
+
+    Ok(())
+}
+
+/// An entry in a deposit data file, in the format produced by the `staking-deposit-cli` and
+/// accepted by the staking launchpad.
+#[derive(Serialize)]
+struct LaunchpadDepositData {
+    pubkey: String,
+    withdrawal_credentials: String,
+    amount: u64,
+    signature: String,
+    deposit_message_root: String,
+    deposit_data_root: String,
+    fork_version: String,
+    network_name: String,
+    deposit_cli_version: String,
+}
+
+/// A transaction to the deposit contract, for submission with any Eth1 wallet.
+#[derive(Serialize)]
+struct DepositTransaction {
+    pubkey: String,
+    to: String,
+    value_wei: String,
+    data: String,
+}
+
+/// Write the `deposits` as a launchpad deposit data file and as a file of deposit contract
+/// transactions to `output_dir`, without any network access.
+fn write_deposit_files(
+    output_dir: &Path,
+    deposits: &[Eth1DepositData],
+    spec: &ChainSpec,
+) -> Result<(), String> {
+    ensure_dir_exists(output_dir)?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| format!("Unable to get time: {:?}", e))?
+        .as_secs();
+    let network_name = spec.config_name.clone().unwrap_or_else(|| "unknown".into());
+    // Report the version of Lighthouse which produced the deposits, rather than impersonating the
+    // `staking-deposit-cli`.
+    let deposit_cli_version = lighthouse_version::VERSION
+        .trim_start_matches("Lighthouse/v")
+        .to_string();
+
+    let launchpad_deposits = deposits
+        .iter()
+        .map(|deposit| {
+            let data = &deposit.deposit_data;
+            LaunchpadDepositData {
+                pubkey: hex::encode(data.pubkey.serialize()),
+                withdrawal_credentials: hex::encode(data.withdrawal_credentials),
+                amount: data.amount,
+                signature: hex::encode(data.signature.serialize()),
+                deposit_message_root: hex::encode(data.as_deposit_message().tree_hash_root()),
+                deposit_data_root: hex::encode(deposit.root),
+                fork_version: hex::encode(spec.genesis_fork_version),
+                network_name: network_name.clone(),
+                deposit_cli_version: deposit_cli_version.clone(),
+            }
+        })
+        .collect::<Vec<_>>();
+    let path = output_dir.join(format!("deposit_data-{}.json", timestamp));
+    write_json(&path, &launchpad_deposits)?;
+    eprintln!(
+        "Deposit data for the staking launchpad written to {:?}",
+        path
+    );
+
+    let transactions = deposits
+        .iter()
+        .map(|deposit| DepositTransaction {
+            pubkey: deposit.deposit_data.pubkey.as_hex_string(),
+            to: format!("{:?}", spec.deposit_contract_address),
+            value_wei: (u128::from(deposit.deposit_data.amount) * 1_000_000_000).to_string(),
+            data: format!("0x{}", hex::encode(&deposit.rlp)),
+        })
+        .collect::<Vec<_>>();
+    let path = output_dir.join(format!("deposit_transactions-{}.json", timestamp));
+    write_json(&path, &transactions)?;
+    eprintln!("Deposit contract transactions written to {:?}", path);
+
     Ok(())
 }
 
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let file = fs::File::options()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|e| format!("Unable to create {:?}: {:?}", path, e))?;
+    serde_json::to_writer_pretty(file, value)
+        .map_err(|e| format!("Unable to write {:?}: {:?}", path, e))
+}
+
 /// Returns the number of validators that exist in the given `validator_dir`.
 ///
 /// This function just assumes all files and directories, excluding the validator definitions YAML
@@ -269,6 +401,10 @@ fn existing_validator_count<P: AsRef<Path>>(validator_dir: P) -> Result<usize, S
                     e.file_name() != OsStr::new(validator_definitions::CONFIG_FILENAME)
                         && e.file_name()
                             != OsStr::new(slashing_protection::SLASHING_PROTECTION_FILENAME)
+                        && !e
+                            .file_name()
+                            .to_string_lossy()
+                            .starts_with(slashing_protection::SLASHING_PROTECTION_BACKUP_PREFIX)
                 })
                 .count()
         })
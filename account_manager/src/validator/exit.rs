@@ -1,4 +1,6 @@
+use super::exit_export::{self, local_keystore, write_exit_file};
 use crate::wallet::create::STDIN_INPUTS_FLAG;
+use account_utils::validator_definitions::ValidatorDefinitions;
 use bls::{Keypair, PublicKey};
 use clap::{App, Arg, ArgMatches};
 use environment::Environment;
@@ -18,10 +20,14 @@ use types::{ChainSpec, Epoch, EthSpec, Fork, VoluntaryExit};
 
 pub const CMD: &str = "exit";
 pub const KEYSTORE_FLAG: &str = "keystore";
+pub const ALL_FLAG: &str = "all";
 pub const PASSWORD_FILE_FLAG: &str = "password-file";
 pub const BEACON_SERVER_FLAG: &str = "beacon-node";
 pub const NO_WAIT: &str = "no-wait";
 pub const NO_CONFIRMATION: &str = "no-confirmation";
+pub const PRESIGN_FLAG: &str = "presign";
+pub const EPOCH_FLAG: &str = "epoch";
+pub const OUTPUT_DIR_FLAG: &str = "output-dir";
 pub const PASSWORD_PROMPT: &str = "Enter the keystore password";
 
 pub const DEFAULT_BEACON_NODE: &str = "http://localhost:5052/";
@@ -30,20 +36,41 @@ pub const WEBSITE_URL: &str = "https://lighthouse-book.sigmaprime.io/voluntary-e
 
 pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
     App::new("exit")
-        .about("Submits a VoluntaryExit to the beacon chain for a given validator keystore.")
+        .about(
+            "Submits a VoluntaryExit to the beacon chain for each of the given validator \
+            keystores, or writes pre-signed exits to files with --presign.",
+        )
         .arg(
             Arg::with_name(KEYSTORE_FLAG)
                 .long(KEYSTORE_FLAG)
                 .value_name("KEYSTORE_PATH")
-                .help("The path to the EIP-2335 voting keystore for the validator")
+                .help(
+                    "The path to the EIP-2335 voting keystore for the validator. May be supplied \
+                    multiple times to exit several validators at once.",
+                )
                 .takes_value(true)
-                .required(true),
+                .multiple(true)
+                .number_of_values(1)
+                .required_unless(ALL_FLAG),
+        )
+        .arg(
+            Arg::with_name(ALL_FLAG)
+                .long(ALL_FLAG)
+                .help(
+                    "Exit every enabled local keystore validator in the validator directory, \
+                    using the passwords from the validator definitions file",
+                )
+                .conflicts_with_all(&[KEYSTORE_FLAG, PASSWORD_FILE_FLAG]),
         )
         .arg(
             Arg::with_name(PASSWORD_FILE_FLAG)
                 .long(PASSWORD_FILE_FLAG)
                 .value_name("PASSWORD_FILE_PATH")
-                .help("The path to the password file which unlocks the validator voting keystore")
+                .help(
+                    "The path to the password file which unlocks the validator voting \
+                    keystores. If multiple keystores are supplied they must all share this \
+                    password.",
+                )
                 .takes_value(true),
         )
         .arg(
@@ -64,6 +91,38 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .long(NO_CONFIRMATION)
                 .help("Exits without prompting for confirmation that you understand the implications of a voluntary exit. This should be used with caution")
         )
+        .arg(
+            Arg::with_name(PRESIGN_FLAG)
+                .long(PRESIGN_FLAG)
+                .help(
+                    "Write the signed voluntary exits to files in --output-dir instead of \
+                    publishing them. Each file contains a SignedVoluntaryExit which can be \
+                    published later via the beacon node API.",
+                )
+                .requires(EPOCH_FLAG),
+        )
+        .arg(
+            Arg::with_name(EPOCH_FLAG)
+                .long(EPOCH_FLAG)
+                .value_name("EPOCH")
+                .help(
+                    "The epoch from which the pre-signed exits will be valid. Only used with \
+                    --presign.",
+                )
+                .takes_value(true)
+                .requires(PRESIGN_FLAG),
+        )
+        .arg(
+            Arg::with_name(OUTPUT_DIR_FLAG)
+                .long(OUTPUT_DIR_FLAG)
+                .value_name("OUTPUT_DIRECTORY")
+                .help(
+                    "The directory in which pre-signed exits are written. Defaults to the \
+                    current directory.",
+                )
+                .takes_value(true)
+                .requires(PRESIGN_FLAG),
+        )
         .arg(
             Arg::with_name(STDIN_INPUTS_FLAG)
                 .takes_value(false)
@@ -73,13 +132,56 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         )
 }
 
-pub fn cli_run<E: EthSpec>(matches: &ArgMatches, env: Environment<E>) -> Result<(), String> {
-    let keystore_path: PathBuf = clap_utils::parse_required(matches, KEYSTORE_FLAG)?;
-    let password_file_path: Option<PathBuf> =
-        clap_utils::parse_optional(matches, PASSWORD_FILE_FLAG)?;
+/// Where the keypairs of the validators to be exited are loaded from.
+enum ExitKeystores {
+    /// The keystores given on the command line, unlocked with a shared password file or by
+    /// prompting the user for each keystore.
+    Paths {
+        keystore_paths: Vec<PathBuf>,
+        password_file_path: Option<PathBuf>,
+    },
+    /// Every enabled local keystore validator in the validator directory.
+    All { validator_dir: PathBuf },
+}
+
+/// What to do with the signed voluntary exits.
+enum ExitAction {
+    /// Publish the exits to the beacon chain, optionally waiting for them to be included.
+    Publish { no_wait: bool },
+    /// Write exits valid from `epoch` to `output_dir`, without publishing them.
+    Presign { epoch: Epoch, output_dir: PathBuf },
+}
+
+pub fn cli_run<E: EthSpec>(
+    matches: &ArgMatches,
+    env: Environment<E>,
+    validator_dir: PathBuf,
+) -> Result<(), String> {
+    let keystores = if matches.is_present(ALL_FLAG) {
+        ExitKeystores::All { validator_dir }
+    } else {
+        ExitKeystores::Paths {
+            keystore_paths: matches
+                .values_of(KEYSTORE_FLAG)
+                .ok_or_else(|| format!("--{} or --{} is required", KEYSTORE_FLAG, ALL_FLAG))?
+                .map(PathBuf::from)
+                .collect(),
+            password_file_path: clap_utils::parse_optional(matches, PASSWORD_FILE_FLAG)?,
+        }
+    };
+    let action = if matches.is_present(PRESIGN_FLAG) {
+        ExitAction::Presign {
+            epoch: Epoch::new(clap_utils::parse_required(matches, EPOCH_FLAG)?),
+            output_dir: clap_utils::parse_optional(matches, OUTPUT_DIR_FLAG)?
+                .unwrap_or_else(|| PathBuf::from(".")),
+        }
+    } else {
+        ExitAction::Publish {
+            no_wait: matches.is_present(NO_WAIT),
+        }
+    };
 
     let stdin_inputs = cfg!(windows) || matches.is_present(STDIN_INPUTS_FLAG);
-    let no_wait = matches.is_present(NO_WAIT);
     let no_confirmation = matches.is_present(NO_CONFIRMATION);
 
     let spec = env.eth2_config().spec.clone();
@@ -95,30 +197,28 @@ pub fn cli_run<E: EthSpec>(matches: &ArgMatches, env: Environment<E>) -> Result<
         .clone()
         .expect("network should have a valid config");
 
-    env.runtime().block_on(publish_voluntary_exit::<E>(
-        &keystore_path,
-        password_file_path.as_ref(),
+    env.runtime().block_on(publish_voluntary_exits::<E>(
+        &keystores,
+        &action,
         &client,
         &spec,
         stdin_inputs,
         &eth2_network_config,
-        no_wait,
         no_confirmation,
     ))?;
 
     Ok(())
 }
 
-/// Gets the keypair and validator_index for every validator and calls `publish_voluntary_exit` on it.
-#[allow(clippy::too_many_arguments)]
-async fn publish_voluntary_exit<E: EthSpec>(
-    keystore_path: &Path,
-    password_file_path: Option<&PathBuf>,
+/// Gets the keypair and validator_index for every validator, then signs a voluntary exit for each
+/// of them after a single confirmation from the user.
+async fn publish_voluntary_exits<E: EthSpec>(
+    keystores: &ExitKeystores,
+    action: &ExitAction,
     client: &BeaconNodeHttpClient,
     spec: &ChainSpec,
     stdin_inputs: bool,
     eth2_network_config: &Eth2NetworkConfig,
-    no_wait: bool,
     no_confirmation: bool,
 ) -> Result<(), String> {
     let genesis_data = get_geneisis_data(client).await?;
@@ -142,29 +242,74 @@ async fn publish_voluntary_exit<E: EthSpec>(
         return Err("Beacon node is still syncing".to_string());
     }
 
-    let keypair = load_voting_keypair(keystore_path, password_file_path, stdin_inputs)?;
-
-    let epoch = get_current_epoch::<E>(genesis_data.genesis_time, spec)
-        .ok_or("Failed to get current epoch. Please check your system time")?;
-    let validator_index = get_validator_index_for_exit(client, &keypair.pk, epoch, spec).await?;
+    let keypairs = match keystores {
+        ExitKeystores::Paths {
+            keystore_paths,
+            password_file_path,
+        } => keystore_paths
+            .iter()
+            .map(|path| load_voting_keypair(path, password_file_path.as_ref(), stdin_inputs))
+            .collect::<Result<Vec<_>, _>>()?,
+        ExitKeystores::All { validator_dir } => {
+            load_all_voting_keypairs(validator_dir, stdin_inputs)?
+        }
+    };
+    if keypairs.is_empty() {
+        return Err("No validators to exit".to_string());
+    }
 
-    let fork = get_beacon_state_fork(client).await?;
-    let voluntary_exit = VoluntaryExit {
-        epoch,
-        validator_index,
+    let (epoch, fork) = match action {
+        // Exits are signed over the fork at their epoch, rather than the current fork.
+        ExitAction::Presign { epoch, .. } => (*epoch, spec.fork_at_epoch(*epoch)),
+        ExitAction::Publish { .. } => (
+            get_current_epoch::<E>(genesis_data.genesis_time, spec)
+                .ok_or("Failed to get current epoch. Please check your system time")?,
+            get_beacon_state_fork(client).await?,
+        ),
     };
 
-    eprintln!(
-        "Publishing a voluntary exit for validator: {} \n",
-        keypair.pk
-    );
+    // Check every validator before asking for confirmation, so that the batch is not exited
+    // partially.
+    let mut voluntary_exits = Vec::with_capacity(keypairs.len());
+    for keypair in &keypairs {
+        let validator_index =
+            get_validator_index_for_exit(client, &keypair.pk, epoch, spec).await?;
+        voluntary_exits.push(VoluntaryExit {
+            epoch,
+            validator_index,
+        });
+    }
+
+    match action {
+        ExitAction::Presign { epoch, .. } => {
+            eprintln!(
+                "WARNING: pre-signed exits are written unencrypted. Anyone who can read them can \
+                exit your validators. Use `lighthouse account validator {}` to write \
+                password-encrypted exits instead.\n",
+                exit_export::CMD
+            );
+            eprintln!(
+                "Signing voluntary exits valid from epoch {} for {} validators:",
+                epoch,
+                keypairs.len()
+            )
+        }
+        ExitAction::Publish { .. } => eprintln!(
+            "Publishing voluntary exits for {} validators:",
+            keypairs.len()
+        ),
+    }
+    for keypair in &keypairs {
+        eprintln!(" - {}", keypair.pk);
+    }
+    eprintln!();
     if !no_confirmation {
         eprintln!("WARNING: THIS IS AN IRREVERSIBLE OPERATION\n");
         eprintln!(
             "PLEASE VISIT {} TO MAKE SURE YOU UNDERSTAND THE IMPLICATIONS OF A VOLUNTARY EXIT.",
             WEBSITE_URL
         );
-        eprintln!("Enter the exit phrase from the above URL to confirm the voluntary exit: ");
+        eprintln!("Enter the exit phrase from the above URL to confirm the voluntary exits: ");
     }
 
     let confirmation = if !no_confirmation {
@@ -173,50 +318,73 @@ async fn publish_voluntary_exit<E: EthSpec>(
         CONFIRMATION_PHRASE.to_string()
     };
 
-    if confirmation == CONFIRMATION_PHRASE {
-        // Sign and publish the voluntary exit to network
+    if confirmation != CONFIRMATION_PHRASE {
+        eprintln!(
+            "Did not sign voluntary exits. Please check that you entered the correct exit phrase."
+        );
+        return Ok(());
+    }
+
+    for (keypair, voluntary_exit) in keypairs.iter().zip(voluntary_exits) {
+        let validator_index = voluntary_exit.validator_index;
         let signed_voluntary_exit = voluntary_exit.sign(
             &keypair.sk,
             &fork,
             genesis_data.genesis_validators_root,
             spec,
         );
-        client
-            .post_beacon_pool_voluntary_exits(&signed_voluntary_exit)
-            .await
-            .map_err(|e| format!("Failed to publish voluntary exit: {}", e))?;
-        tokio::time::sleep(std::time::Duration::from_secs(1)).await; // Provides nicer UX.
-        eprintln!(
-            "Successfully validated and published voluntary exit for validator {}",
-            keypair.pk
-        );
-    } else {
-        eprintln!(
-            "Did not publish voluntary exit for validator {}. Please check that you entered the correct exit phrase.",
-            keypair.pk
-        );
-        return Ok(());
+
+        match action {
+            ExitAction::Presign { output_dir, .. } => {
+                let path =
+                    write_exit_file(output_dir, validator_index, epoch, &signed_voluntary_exit)?;
+                eprintln!(
+                    "Wrote pre-signed voluntary exit for validator {} to {:?}",
+                    keypair.pk, path
+                );
+            }
+            ExitAction::Publish { .. } => {
+                client
+                    .post_beacon_pool_voluntary_exits(&signed_voluntary_exit)
+                    .await
+                    .map_err(|e| format!("Failed to publish voluntary exit: {}", e))?;
+                tokio::time::sleep(std::time::Duration::from_secs(1)).await; // Provides nicer UX.
+                eprintln!(
+                    "Successfully validated and published voluntary exit for validator {}",
+                    keypair.pk
+                );
+            }
+        }
     }
 
-    if no_wait {
-        return Ok(());
+    if let ExitAction::Publish { no_wait: false } = action {
+        for keypair in &keypairs {
+            wait_for_exit::<E>(client, &keypair.pk, genesis_data.genesis_time, spec).await?;
+        }
     }
 
-    loop {
-        // Sleep for a slot duration and then check if voluntary exit was processed
-        // by checking the validator status.
-        sleep(Duration::from_secs(spec.seconds_per_slot)).await;
+    Ok(())
+}
 
-        let validator_data = get_validator_data(client, &keypair.pk).await?;
+/// Wait until the voluntary exit of the validator with `validator_pubkey` has been included in
+/// the beacon chain.
+async fn wait_for_exit<E: EthSpec>(
+    client: &BeaconNodeHttpClient,
+    validator_pubkey: &PublicKey,
+    genesis_time: u64,
+    spec: &ChainSpec,
+) -> Result<(), String> {
+    loop {
+        let validator_data = get_validator_data(client, validator_pubkey).await?;
         match validator_data.status {
             ValidatorStatus::ActiveExiting => {
                 let exit_epoch = validator_data.validator.exit_epoch;
                 let withdrawal_epoch = validator_data.validator.withdrawable_epoch;
-                let current_epoch = get_current_epoch::<E>(genesis_data.genesis_time, spec)
+                let current_epoch = get_current_epoch::<E>(genesis_time, spec)
                     .ok_or("Failed to get current epoch. Please check your system time")?;
-                eprintln!("Voluntary exit has been accepted into the beacon chain, but not yet finalized. \
+                eprintln!("Voluntary exit for validator {} has been accepted into the beacon chain, but not yet finalized. \
                         Finalization may take several minutes or longer. Before finalization there is a low \
-                        probability that the exit may be reverted.");
+                        probability that the exit may be reverted.", validator_pubkey);
                 eprintln!(
                     "Current epoch: {}, Exit epoch: {}, Withdrawable epoch: {}",
                     current_epoch, exit_epoch, withdrawal_epoch
@@ -226,20 +394,21 @@ async fn publish_voluntary_exit<E: EthSpec>(
                     "Exit epoch in approximately {} secs",
                     (exit_epoch - current_epoch) * spec.seconds_per_slot * E::slots_per_epoch()
                 );
-                break;
+                return Ok(());
             }
             ValidatorStatus::ExitedSlashed | ValidatorStatus::ExitedUnslashed => {
                 eprintln!(
-                    "Validator has exited on epoch: {}",
-                    validator_data.validator.exit_epoch
+                    "Validator {} has exited on epoch: {}",
+                    validator_pubkey, validator_data.validator.exit_epoch
                 );
-                break;
+                return Ok(());
             }
             _ => eprintln!("Waiting for voluntary exit to be accepted into the beacon chain..."),
         }
-    }
 
-    Ok(())
+        // Sleep for a slot duration and then check again if voluntary exit was processed.
+        sleep(Duration::from_secs(spec.seconds_per_slot)).await;
+    }
 }
 
 /// Get the validator index of a given the validator public key by querying the beacon node endpoint.
@@ -379,10 +548,43 @@ fn load_voting_keypair(
     }
 }
 
+/// Load the voting keypair of every enabled local keystore validator in `validator_dir`.
+///
+/// Keystores are unlocked with the password from the validator definitions if there is one,
+/// otherwise the user is prompted for a password.
+fn load_all_voting_keypairs(
+    validator_dir: &Path,
+    stdin_inputs: bool,
+) -> Result<Vec<Keypair>, String> {
+    let defs = ValidatorDefinitions::open(validator_dir).map_err(|e| {
+        format!(
+            "Unable to open {}: {:?}",
+            account_utils::validator_definitions::CONFIG_FILENAME,
+            e
+        )
+    })?;
+
+    let mut keypairs = vec![];
+    for def in defs.as_slice().iter().filter(|def| def.enabled) {
+        match local_keystore(def)? {
+            Some((voting_keystore_path, password)) => keypairs.push(
+                exit_export::load_voting_keypair(voting_keystore_path, password, stdin_inputs)?,
+            ),
+            None => eprintln!(
+                "Skipping {}: remote signers are not supported",
+                def.voting_public_key
+            ),
+        }
+    }
+
+    Ok(keypairs)
+}
+
 #[cfg(test)]
 #[cfg(not(debug_assertions))]
 mod tests {
     use super::*;
+    use account_utils::{validator_definitions::ValidatorDefinition, ZeroizeString};
     use eth2_keystore::KeystoreBuilder;
     use std::fs::File;
     use std::io::Write;
@@ -425,4 +627,62 @@ mod tests {
 
         assert_eq!(expected_pk, kp.pk.into());
     }
+
+    #[test]
+    fn test_load_all_voting_keypairs() {
+        let dir = tempdir().unwrap();
+        let expected_pk = create_and_save_keystore(&dir, false);
+
+        let def = ValidatorDefinition::new_keystore_with_password(
+            dir.path().join(KEYSTORE_NAME),
+            Some(ZeroizeString::from(PASSWORD.to_string())),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let mut disabled = def.clone();
+        disabled.enabled = false;
+        ValidatorDefinitions::from(vec![def, disabled])
+            .save(dir.path())
+            .unwrap();
+
+        // The password is taken from the definition, and disabled validators are not exited.
+        let keypairs = load_all_voting_keypairs(dir.path(), false).unwrap();
+        assert_eq!(keypairs.len(), 1);
+        assert_eq!(expected_pk, keypairs[0].pk);
+    }
+
+    #[test]
+    fn test_presigned_exit_file() {
+        let dir = tempdir().unwrap();
+        let exit = VoluntaryExit {
+            epoch: Epoch::new(1024),
+            validator_index: 42,
+        }
+        .sign(
+            &Keypair::random().sk,
+            &Fork::default(),
+            Default::default(),
+            &ChainSpec::mainnet(),
+        );
+
+        let path = write_exit_file(dir.path(), 42, Epoch::new(1024), &exit).unwrap();
+        assert_eq!(path, dir.path().join("exit-42-1024.json"));
+        let decoded: types::SignedVoluntaryExit =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(decoded, exit);
+
+        // The unencrypted exit is only readable by the current user.
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // Existing exits are never overwritten.
+        assert!(write_exit_file(dir.path(), 42, Epoch::new(1024), &exit).is_err());
+    }
 }
@@ -0,0 +1,539 @@
+use crate::wallet::create::STDIN_INPUTS_FLAG;
+use account_utils::{
+    eth2_keystore::json_keystore::{
+        Aes128Ctr, ChecksumModule, Cipher, CipherModule, Crypto, EmptyMap, EmptyString, KdfModule,
+        Sha256Checksum,
+    },
+    is_password_sufficiently_complex, read_password, read_password_from_user,
+    validator_definitions::{
+        read_voting_keystore_password, SigningDefinition, ValidatorDefinition, ValidatorDefinitions,
+    },
+    PlainText,
+};
+use bls::Keypair;
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use eth2::{
+    types::{StateId, ValidatorId},
+    BeaconNodeHttpClient, Timeouts,
+};
+use eth2_keystore::{decrypt, default_kdf, encrypt, Keystore, IV_SIZE, SALT_SIZE};
+use filesystem::create_with_600_perms;
+use rand::prelude::*;
+use safe_arith::SafeArith;
+use sensitive_url::SensitiveUrl;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use types::{
+    ChainSpec, Epoch, EthSpec, Hash256, PublicKeyBytes, SignedVoluntaryExit, VoluntaryExit,
+};
+
+pub const CMD: &str = "exit-export";
+pub const EPOCH_FLAG: &str = "epoch";
+pub const OUTPUT_DIR_FLAG: &str = "output-dir";
+pub const PASSWORD_FILE_FLAG: &str = "password-file";
+pub const DECRYPT_FLAG: &str = "decrypt";
+pub const BEACON_SERVER_FLAG: &str = "beacon-node";
+pub const PASSWORD_PROMPT: &str = "Enter the password used to encrypt the exported exits";
+pub const CONFIRM_PASSWORD_PROMPT: &str = "Confirm the password";
+pub const KEYSTORE_PASSWORD_PROMPT: &str = "Enter the keystore password";
+
+pub const DEFAULT_BEACON_NODE: &str = "http://localhost:5052/";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Signs a VoluntaryExit for every enabled local keystore validator, valid from a given \
+            epoch, and writes each one to a password-encrypted file. Nothing is published to the \
+            beacon chain. The files can be decrypted later with --decrypt.",
+        )
+        .arg(
+            Arg::with_name(EPOCH_FLAG)
+                .long(EPOCH_FLAG)
+                .value_name("EPOCH")
+                .help(
+                    "The epoch from which the exits will be valid. Validators which will not be \
+                    eligible to exit by this epoch are skipped.",
+                )
+                .takes_value(true)
+                .required_unless(DECRYPT_FLAG),
+        )
+        .arg(
+            Arg::with_name(OUTPUT_DIR_FLAG)
+                .long(OUTPUT_DIR_FLAG)
+                .value_name("OUTPUT_DIRECTORY")
+                .help("The directory in which the encrypted exits will be written")
+                .takes_value(true)
+                .required_unless(DECRYPT_FLAG),
+        )
+        .arg(
+            Arg::with_name(PASSWORD_FILE_FLAG)
+                .long(PASSWORD_FILE_FLAG)
+                .value_name("PASSWORD_FILE_PATH")
+                .help(
+                    "The path to a file containing the password used to encrypt (or decrypt) the \
+                    exits. If omitted, the user will be prompted for a password.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(DECRYPT_FLAG)
+                .long(DECRYPT_FLAG)
+                .value_name("EXIT_PATH")
+                .help(
+                    "Decrypt a previously exported exit and print the SignedVoluntaryExit as JSON \
+                    to stdout. The beacon node is not contacted.",
+                )
+                .takes_value(true)
+                .conflicts_with_all(&[EPOCH_FLAG, OUTPUT_DIR_FLAG]),
+        )
+        .arg(
+            Arg::with_name(BEACON_SERVER_FLAG)
+                .long(BEACON_SERVER_FLAG)
+                .value_name("NETWORK_ADDRESS")
+                .help("Address to a beacon node HTTP API, used to look up validator indices")
+                .default_value(DEFAULT_BEACON_NODE)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .takes_value(false)
+                .hidden(cfg!(windows))
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty."),
+        )
+}
+
+/// A `SignedVoluntaryExit` encrypted with a password, using the same KDF, cipher and checksum
+/// modules as an EIP-2335 keystore.
+///
+/// The public key, index and epoch are stored in the clear so that files can be identified
+/// without being decrypted.
+#[derive(Serialize, Deserialize)]
+pub struct EncryptedExit {
+    pub pubkey: PublicKeyBytes,
+    pub validator_index: u64,
+    pub epoch: Epoch,
+    pub crypto: Crypto,
+}
+
+impl EncryptedExit {
+    /// Encrypt `signed_exit` with `password`, using a random salt and IV.
+    pub fn encrypt(
+        signed_exit: &SignedVoluntaryExit,
+        pubkey: PublicKeyBytes,
+        password: &[u8],
+    ) -> Result<Self, String> {
+        let salt = rand::thread_rng().gen::<[u8; SALT_SIZE]>();
+        let iv = rand::thread_rng().gen::<[u8; IV_SIZE]>().to_vec().into();
+        let kdf = default_kdf(salt.to_vec());
+        let cipher = Cipher::Aes128Ctr(Aes128Ctr { iv });
+
+        let plain_text = PlainText::from(
+            serde_json::to_vec(signed_exit)
+                .map_err(|e| format!("Unable to serialize exit: {:?}", e))?,
+        );
+        let (cipher_text, checksum) = encrypt(plain_text.as_bytes(), password, &kdf, &cipher)
+            .map_err(|e| format!("Unable to encrypt exit: {:?}", e))?;
+
+        Ok(Self {
+            pubkey,
+            validator_index: signed_exit.message.validator_index,
+            epoch: signed_exit.message.epoch,
+            crypto: Crypto {
+                kdf: KdfModule {
+                    function: kdf.function(),
+                    params: kdf,
+                    message: EmptyString,
+                },
+                checksum: ChecksumModule {
+                    function: Sha256Checksum::function(),
+                    params: EmptyMap,
+                    message: checksum.to_vec().into(),
+                },
+                cipher: CipherModule {
+                    function: cipher.function(),
+                    params: cipher,
+                    message: cipher_text.into(),
+                },
+            },
+        })
+    }
+
+    /// Decrypt the exit with `password`, checking that it matches the unencrypted fields.
+    pub fn decrypt(&self, password: &[u8]) -> Result<SignedVoluntaryExit, String> {
+        let plain_text = decrypt(password, &self.crypto).map_err(|e| match e {
+            eth2_keystore::Error::InvalidPassword => "Invalid password".to_string(),
+            e => format!("Unable to decrypt exit: {:?}", e),
+        })?;
+        let signed_exit: SignedVoluntaryExit = serde_json::from_slice(plain_text.as_bytes())
+            .map_err(|e| format!("Unable to parse decrypted exit: {:?}", e))?;
+
+        if signed_exit.message.validator_index != self.validator_index
+            || signed_exit.message.epoch != self.epoch
+        {
+            return Err("Decrypted exit does not match the validator index or epoch".to_string());
+        }
+
+        Ok(signed_exit)
+    }
+}
+
+pub fn cli_run<E: EthSpec>(
+    matches: &ArgMatches,
+    env: Environment<E>,
+    validator_dir: PathBuf,
+) -> Result<(), String> {
+    let password_file_path: Option<PathBuf> =
+        clap_utils::parse_optional(matches, PASSWORD_FILE_FLAG)?;
+    let decrypt_path: Option<PathBuf> = clap_utils::parse_optional(matches, DECRYPT_FLAG)?;
+    let stdin_inputs = cfg!(windows) || matches.is_present(STDIN_INPUTS_FLAG);
+
+    if let Some(path) = decrypt_path {
+        let bytes =
+            fs::read(&path).map_err(|e| format!("Unable to read exit {:?}: {:?}", path, e))?;
+        let encrypted_exit: EncryptedExit = serde_json::from_slice(&bytes)
+            .map_err(|e| format!("Unable to parse exit {:?}: {:?}", path, e))?;
+        let password = read_export_password(password_file_path.as_ref(), stdin_inputs, false)?;
+        let signed_exit = encrypted_exit.decrypt(password.as_bytes())?;
+
+        let json = serde_json::to_string_pretty(&signed_exit)
+            .map_err(|e| format!("Unable to serialize exit: {:?}", e))?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    let epoch = Epoch::new(clap_utils::parse_required(matches, EPOCH_FLAG)?);
+    let output_dir: PathBuf = clap_utils::parse_required(matches, OUTPUT_DIR_FLAG)?;
+    let server_url: String = clap_utils::parse_required(matches, BEACON_SERVER_FLAG)?;
+
+    let spec = env.eth2_config().spec.clone();
+    let genesis_validators_root = env
+        .eth2_network_config
+        .as_ref()
+        .ok_or("Unable to determine network configuration")?
+        .beacon_state::<E>()
+        .map_err(|e| format!("Unable to load genesis state: {}", e))?
+        .genesis_validators_root();
+
+    let defs = ValidatorDefinitions::open(&validator_dir).map_err(|e| {
+        format!(
+            "Unable to open {}: {:?}",
+            account_utils::validator_definitions::CONFIG_FILENAME,
+            e
+        )
+    })?;
+
+    if !output_dir.exists() {
+        fs::create_dir_all(&output_dir)
+            .map_err(|e| format!("Unable to create {:?}: {:?}", output_dir, e))?;
+    }
+
+    let password = read_export_password(password_file_path.as_ref(), stdin_inputs, true)?;
+
+    let client = BeaconNodeHttpClient::new(
+        SensitiveUrl::parse(&server_url)
+            .map_err(|e| format!("Failed to parse beacon http server: {:?}", e))?,
+        Timeouts::set_all(Duration::from_secs(spec.seconds_per_slot)),
+    );
+
+    // Exits are signed over the fork at their epoch, so they stop being valid once the chain
+    // has moved two forks beyond that epoch.
+    let fork = spec.fork_at_epoch(epoch);
+
+    env.runtime().block_on(async {
+        verify_genesis_validators_root(&client, genesis_validators_root).await?;
+
+        let mut exported = 0;
+        let mut skipped = 0;
+
+        for def in defs.as_slice().iter().filter(|def| def.enabled) {
+            let pubkey = def.voting_public_key.compress();
+
+            let (voting_keystore_path, voting_keystore_password) = match local_keystore(def)? {
+                Some(keystore) => keystore,
+                None => {
+                    eprintln!("Skipping {:?}: remote signers are not supported", pubkey);
+                    skipped += 1;
+                    continue;
+                }
+            };
+
+            let validator_index = match get_validator_index(&client, &pubkey, epoch, &spec).await {
+                Ok(index) => index,
+                Err(e) => {
+                    eprintln!("Skipping {:?}: {}", pubkey, e);
+                    skipped += 1;
+                    continue;
+                }
+            };
+
+            let keypair =
+                load_voting_keypair(voting_keystore_path, voting_keystore_password, stdin_inputs)?;
+
+            let signed_exit = VoluntaryExit {
+                epoch,
+                validator_index,
+            }
+            .sign(&keypair.sk, &fork, genesis_validators_root, &spec);
+            let encrypted_exit = EncryptedExit::encrypt(&signed_exit, pubkey, password.as_bytes())?;
+
+            let path = write_exit_file(&output_dir, validator_index, epoch, &encrypted_exit)?;
+
+            eprintln!(
+                "Exported exit for validator {} to {:?}",
+                validator_index, path
+            );
+            exported += 1;
+        }
+
+        eprintln!(
+            "Exported {} exits valid from epoch {}, skipped {} validators",
+            exported, epoch, skipped
+        );
+
+        Ok(())
+    })
+}
+
+/// Write `exit` as JSON to a file in `output_dir` named after the validator index and epoch,
+/// readable only by the current user.
+///
+/// Returns the path of the file. Existing files are never overwritten.
+pub fn write_exit_file<T: Serialize>(
+    output_dir: &Path,
+    validator_index: u64,
+    epoch: Epoch,
+    exit: &T,
+) -> Result<PathBuf, String> {
+    let path = output_dir.join(format!("exit-{}-{}.json", validator_index, epoch));
+    if path.exists() {
+        return Err(format!("Refusing to overwrite existing exit {:?}", path));
+    }
+    let json = serde_json::to_vec_pretty(exit)
+        .map_err(|e| format!("Unable to serialize exit: {:?}", e))?;
+    create_with_600_perms(&path, &json)
+        .map_err(|e| format!("Unable to write exit {:?}: {:?}", path, e))?;
+    Ok(path)
+}
+
+/// Returns the voting keystore path of a local keystore validator, along with its password if
+/// the definition provides one (either inline or via a password file).
+///
+/// Returns `None` for remote signers, which cannot sign exits locally.
+pub fn local_keystore(
+    def: &ValidatorDefinition,
+) -> Result<Option<(&Path, Option<PlainText>)>, String> {
+    match &def.signing_definition {
+        SigningDefinition::LocalKeystore {
+            voting_keystore_path,
+            voting_keystore_password_path,
+            voting_keystore_password,
+        } => {
+            let password = if let Some(password) = voting_keystore_password {
+                Some(PlainText::from(password.as_ref().to_vec()))
+            } else if let Some(path) = voting_keystore_password_path {
+                Some(
+                    read_voting_keystore_password(path, &def.voting_public_key)
+                        .map_err(|e| format!("Unable to read password from {:?}: {:?}", path, e))?,
+                )
+            } else {
+                None
+            };
+            Ok(Some((voting_keystore_path, password)))
+        }
+        SigningDefinition::Web3Signer(_) => Ok(None),
+    }
+}
+
+/// Read the password which protects the exported exits, either from `password_file_path` or by
+/// prompting the user.
+///
+/// When `confirm` is true a prompted password must be entered twice and sufficiently complex.
+fn read_export_password(
+    password_file_path: Option<&PathBuf>,
+    stdin_inputs: bool,
+    confirm: bool,
+) -> Result<PlainText, String> {
+    if let Some(path) = password_file_path {
+        return read_password(path)
+            .map_err(|e| format!("Unable to read password file {:?}: {:?}", path, e));
+    }
+
+    loop {
+        eprintln!();
+        eprintln!("{}: ", PASSWORD_PROMPT);
+        let password = read_password_from_user(stdin_inputs)?;
+        if !confirm {
+            return Ok(PlainText::from(password.as_ref().to_vec()));
+        }
+
+        if let Err(e) = is_password_sufficiently_complex(password.as_ref()) {
+            eprintln!("{}", e);
+            continue;
+        }
+
+        eprintln!("{}: ", CONFIRM_PASSWORD_PROMPT);
+        let confirmation = read_password_from_user(stdin_inputs)?;
+        if password.as_str() == confirmation.as_str() {
+            return Ok(PlainText::from(password.as_ref().to_vec()));
+        }
+        eprintln!("Passwords do not match");
+    }
+}
+
+/// Check that the beacon node is on the same network as the exits being signed.
+async fn verify_genesis_validators_root(
+    client: &BeaconNodeHttpClient,
+    genesis_validators_root: Hash256,
+) -> Result<(), String> {
+    let genesis_data = client
+        .get_beacon_genesis()
+        .await
+        .map_err(|e| format!("Failed to get beacon genesis: {}", e))?
+        .data;
+
+    if genesis_data.genesis_validators_root != genesis_validators_root {
+        return Err(
+            "Invalid genesis state. Please ensure that your beacon node is on the same network \
+                 as the validators you are exporting exits for"
+                .to_string(),
+        );
+    }
+
+    Ok(())
+}
+
+/// Get the index of the validator with `pubkey`, returning an error if it cannot exit at `epoch`.
+async fn get_validator_index(
+    client: &BeaconNodeHttpClient,
+    pubkey: &PublicKeyBytes,
+    epoch: Epoch,
+    spec: &ChainSpec,
+) -> Result<u64, String> {
+    let validator_data = client
+        .get_beacon_states_validator_id(StateId::Head, &ValidatorId::PublicKey(*pubkey))
+        .await
+        .map_err(|e| format!("Failed to get validator details: {:?}", e))?
+        .ok_or("validator is not present in the beacon state")?
+        .data;
+
+    if validator_data.validator.exit_epoch != spec.far_future_epoch {
+        return Err(format!(
+            "validator has already initiated an exit (status: {:?})",
+            validator_data.status
+        ));
+    }
+
+    let eligible_epoch = validator_data
+        .validator
+        .activation_epoch
+        .safe_add(spec.shard_committee_period)
+        .map_err(|_| {
+            format!(
+                "validator activation epoch is not yet known (status: {:?})",
+                validator_data.status
+            )
+        })?;
+
+    if epoch < eligible_epoch {
+        return Err(format!(
+            "validator will not be eligible to exit until epoch {}",
+            eligible_epoch
+        ));
+    }
+
+    Ok(validator_data.index)
+}
+
+/// Load the voting keypair from `voting_keystore_path`, prompting for a password if the
+/// validator definition does not provide one.
+pub fn load_voting_keypair(
+    voting_keystore_path: &Path,
+    password: Option<PlainText>,
+    stdin_inputs: bool,
+) -> Result<Keypair, String> {
+    let keystore = Keystore::from_json_file(voting_keystore_path).map_err(|e| {
+        format!(
+            "Unable to read keystore JSON {:?}: {:?}",
+            voting_keystore_path, e
+        )
+    })?;
+
+    let password = match password {
+        Some(password) => password,
+        None => {
+            eprintln!();
+            eprintln!(
+                "{} for {:?}: ",
+                KEYSTORE_PASSWORD_PROMPT, voting_keystore_path
+            );
+            PlainText::from(read_password_from_user(stdin_inputs)?.as_ref().to_vec())
+        }
+    };
+
+    keystore
+        .decrypt_keypair(password.as_bytes())
+        .map_err(|e| match e {
+            eth2_keystore::Error::InvalidPassword => {
+                format!("Invalid password for {:?}", voting_keystore_path)
+            }
+            e => format!("Error while decrypting keypair: {:?}", e),
+        })
+}
+
+#[cfg(test)]
+#[cfg(not(debug_assertions))]
+mod tests {
+    use super::*;
+    use types::Signature;
+
+    const PASSWORD: &[u8] = b"correct horse battery staple";
+
+    fn signed_exit() -> SignedVoluntaryExit {
+        SignedVoluntaryExit {
+            message: VoluntaryExit {
+                epoch: Epoch::new(1024),
+                validator_index: 42,
+            },
+            signature: Signature::empty(),
+        }
+    }
+
+    #[test]
+    fn encrypted_exit_round_trip() {
+        let pubkey = Keypair::random().pk.compress();
+        let signed_exit = signed_exit();
+
+        let encrypted = EncryptedExit::encrypt(&signed_exit, pubkey, PASSWORD).unwrap();
+        let json = serde_json::to_string(&encrypted).unwrap();
+        let decoded: EncryptedExit = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(decoded.pubkey, pubkey);
+        assert_eq!(decoded.validator_index, 42);
+        assert_eq!(decoded.epoch, Epoch::new(1024));
+        assert_eq!(decoded.decrypt(PASSWORD).unwrap(), signed_exit);
+    }
+
+    #[test]
+    fn encrypted_exit_wrong_password() {
+        let pubkey = Keypair::random().pk.compress();
+        let encrypted = EncryptedExit::encrypt(&signed_exit(), pubkey, PASSWORD).unwrap();
+
+        assert_eq!(
+            encrypted.decrypt(b"wrong password").unwrap_err(),
+            "Invalid password"
+        );
+    }
+
+    #[test]
+    fn encrypted_exit_tampered_index() {
+        let pubkey = Keypair::random().pk.compress();
+        let mut encrypted = EncryptedExit::encrypt(&signed_exit(), pubkey, PASSWORD).unwrap();
+        encrypted.validator_index = 43;
+
+        assert!(encrypted.decrypt(PASSWORD).is_err());
+    }
+}
@@ -10,9 +10,11 @@ use account_utils::{
     ZeroizeString,
 };
 use clap::{App, Arg, ArgMatches};
+use rayon::prelude::*;
 use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
 use std::fs;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::thread::sleep;
 use std::time::Duration;
 
@@ -20,6 +22,7 @@ pub const CMD: &str = "import";
 pub const KEYSTORE_FLAG: &str = "keystore";
 pub const DIR_FLAG: &str = "directory";
 pub const REUSE_PASSWORD_FLAG: &str = "reuse-password";
+pub const WORKERS_FLAG: &str = "workers";
 
 pub const PASSWORD_PROMPT: &str = "Enter the keystore password, or press enter to omit it:";
 pub const KEYSTORE_REUSE_WARNING: &str = "DO NOT USE THE ORIGINAL KEYSTORES TO VALIDATE WITH \
@@ -81,6 +84,17 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 )
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name(WORKERS_FLAG)
+                .long(WORKERS_FLAG)
+                .value_name("WORKERS")
+                .requires(REUSE_PASSWORD_FLAG)
+                .help(
+                    "The number of threads used to decrypt keystores when checking the password \
+                    provided with `--reuse-password`. Defaults to the number of CPUs.",
+                )
+                .takes_value(true),
+        )
 }
 
 pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), String> {
@@ -90,6 +104,10 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
     let reuse_password = matches.is_present(REUSE_PASSWORD_FLAG);
     let keystore_password_path: Option<PathBuf> =
         clap_utils::parse_optional(matches, PASSWORD_FLAG)?;
+    let workers: Option<usize> = clap_utils::parse_optional(matches, WORKERS_FLAG)?;
+    if workers == Some(0) {
+        return Err(format!("--{} must be greater than 0", WORKERS_FLAG));
+    }
 
     let mut defs = ValidatorDefinitions::open_or_create(&validator_dir)
         .map_err(|e| format!("Unable to open {}: {:?}", CONFIG_FILENAME, e))?;
@@ -138,6 +156,41 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
 
     eprintln!("WARNING: {}", KEYSTORE_REUSE_WARNING);
 
+    let keystores = keystore_paths
+        .iter()
+        .map(|path| {
+            Keystore::from_json_file(path)
+                .map(|keystore| (path, keystore))
+                .map_err(|e| format!("Unable to read keystore JSON {:?}: {:?}", path, e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // If the same password is used for all keystores, obtain it once and then check it against
+    // all keystores in parallel. Keystores which were imported by a previous (possibly
+    // interrupted) run are not decrypted again.
+    let reused_password = if reuse_password {
+        let pending = keystores
+            .iter()
+            .map(|(_, keystore)| keystore)
+            .filter(|keystore| requires_password(&defs, keystore))
+            .collect::<Vec<_>>();
+
+        let password = match pending.split_first() {
+            Some((first, rest)) => {
+                let password = read_password(first, keystore_password_path.as_ref(), stdin_inputs)?;
+                if let Some(password) = &password {
+                    verify_passwords(rest, password, workers)?;
+                }
+                password
+            }
+            None => None,
+        };
+
+        Some(password)
+    } else {
+        None
+    };
+
     // For each keystore:
     //
     // - Obtain the keystore password, if the user desires.
@@ -148,12 +201,8 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
     // Skip keystores that already exist, but exit early if any operation fails.
     // Reuses the same password for all keystores if the `REUSE_PASSWORD_FLAG` flag is set.
     let mut num_imported_keystores = 0;
-    let mut previous_password: Option<ZeroizeString> = None;
-
-    for src_keystore in &keystore_paths {
-        let keystore = Keystore::from_json_file(src_keystore)
-            .map_err(|e| format!("Unable to read keystore JSON {:?}: {:?}", src_keystore, e))?;
 
+    for (src_keystore, keystore) in &keystores {
         eprintln!();
         eprintln!("Keystore found at {:?}:", src_keystore);
         eprintln!();
@@ -166,70 +215,38 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
             CONFIG_FILENAME
         );
 
-        let password_opt = loop {
-            if let Some(password) = previous_password.clone() {
+        let voting_pubkey = keystore
+            .public_key()
+            .ok_or_else(|| format!("Keystore public key is invalid: {}", keystore.pubkey()))?;
+
+        let password_opt = match &reused_password {
+            Some(password) => {
                 eprintln!("Reuse previous password.");
-                break Some(password);
+                password.clone()
             }
-            eprintln!();
-            eprintln!("{}", PASSWORD_PROMPT);
-
-            let password = match keystore_password_path.as_ref() {
-                Some(path) => {
-                    let password_from_file: ZeroizeString = fs::read_to_string(path)
-                        .map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?
-                        .into();
-                    password_from_file.without_newlines()
-                }
-                None => {
-                    let password_from_user = read_password_from_user(stdin_inputs)?;
-                    if password_from_user.as_ref().is_empty() {
-                        eprintln!("Continuing without password.");
-                        sleep(Duration::from_secs(1)); // Provides nicer UX.
-                        break None;
-                    }
-                    password_from_user
-                }
-            };
-
-            match keystore.decrypt_keypair(password.as_ref()) {
-                Ok(_) => {
-                    eprintln!("Password is correct.");
-                    eprintln!();
-                    sleep(Duration::from_secs(1)); // Provides nicer UX.
-                    if reuse_password {
-                        previous_password = Some(password.clone());
-                    }
-                    break Some(password);
-                }
-                Err(eth2_keystore::Error::InvalidPassword) => {
-                    eprintln!("Invalid password");
-                }
-                Err(e) => return Err(format!("Error whilst decrypting keypair: {:?}", e)),
+            None if requires_password(&defs, keystore) => {
+                read_password(keystore, keystore_password_path.as_ref(), stdin_inputs)?
             }
+            None => None,
         };
 
-        let voting_pubkey = keystore
-            .public_key()
-            .ok_or_else(|| format!("Keystore public key is invalid: {}", keystore.pubkey()))?;
-
         // The keystore is placed in a directory that matches the name of the public key. This
         // provides some loose protection against adding the same keystore twice.
         let dest_dir = validator_dir.join(format!("0x{}", keystore.pubkey()));
-        if dest_dir.exists() {
+        let old_validator_def_opt = defs
+            .as_mut_slice()
+            .iter_mut()
+            .find(|def| def.voting_public_key == voting_pubkey);
+        if let Some(old_validator_def) = old_validator_def_opt {
             // Check if we should update password for existing validator in case if it was provided via reimport: #2854
-            let old_validator_def_opt = defs
-                .as_mut_slice()
-                .iter_mut()
-                .find(|def| def.voting_public_key == voting_pubkey);
-            if let Some(ValidatorDefinition {
+            if let ValidatorDefinition {
                 signing_definition:
                     SigningDefinition::LocalKeystore {
                         voting_keystore_password: ref mut old_passwd,
                         ..
                     },
                 ..
-            }) = old_validator_def_opt
+            } = old_validator_def
             {
                 if old_passwd.is_none() && password_opt.is_some() {
                     *old_passwd = password_opt;
@@ -243,6 +260,10 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
                 src_keystore
             );
             continue;
+        } else if dest_dir.exists() {
+            // The keystore was copied by a previous run which was interrupted before the
+            // validator definitions were saved. The remaining steps are idempotent, so resume.
+            eprintln!("Resuming interrupted import of keystore.");
         }
 
         fs::create_dir_all(&dest_dir)
@@ -304,3 +325,123 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
 
     Ok(())
 }
+
+/// Returns `true` if `keystore` has not been imported yet, or if it was imported without a
+/// password.
+fn requires_password(defs: &ValidatorDefinitions, keystore: &Keystore) -> bool {
+    let voting_pubkey = keystore.public_key();
+    !defs.as_slice().iter().any(|def| {
+        Some(&def.voting_public_key) == voting_pubkey.as_ref()
+            && !matches!(
+                def.signing_definition,
+                SigningDefinition::LocalKeystore {
+                    voting_keystore_password: None,
+                    ..
+                }
+            )
+    })
+}
+
+/// Read the password for `keystore` from `password_path` or, if `None`, prompt the user for it.
+///
+/// The user is prompted again if the password does not decrypt the keystore. Returns `None` if
+/// the user chooses to omit the password.
+fn read_password(
+    keystore: &Keystore,
+    password_path: Option<&PathBuf>,
+    stdin_inputs: bool,
+) -> Result<Option<ZeroizeString>, String> {
+    loop {
+        eprintln!();
+        eprintln!("{}", PASSWORD_PROMPT);
+
+        let password = match password_path {
+            Some(path) => {
+                let password_from_file: ZeroizeString = fs::read_to_string(path)
+                    .map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?
+                    .into();
+                password_from_file.without_newlines()
+            }
+            None => {
+                let password_from_user = read_password_from_user(stdin_inputs)?;
+                if password_from_user.as_ref().is_empty() {
+                    eprintln!("Continuing without password.");
+                    sleep(Duration::from_secs(1)); // Provides nicer UX.
+                    return Ok(None);
+                }
+                password_from_user
+            }
+        };
+
+        match keystore.decrypt_keypair(password.as_ref()) {
+            Ok(_) => {
+                eprintln!("Password is correct.");
+                eprintln!();
+                sleep(Duration::from_secs(1)); // Provides nicer UX.
+                return Ok(Some(password));
+            }
+            Err(eth2_keystore::Error::InvalidPassword) if password_path.is_some() => {
+                return Err(format!("Invalid password in {:?}", password_path));
+            }
+            Err(eth2_keystore::Error::InvalidPassword) => {
+                eprintln!("Invalid password");
+            }
+            Err(e) => return Err(format!("Error whilst decrypting keypair: {:?}", e)),
+        }
+    }
+}
+
+/// Check that `password` decrypts each of `keystores`, decrypting up to `workers` keystores in
+/// parallel.
+///
+/// Returns an error listing the public keys of any keystores which could not be decrypted.
+fn verify_passwords(
+    keystores: &[&Keystore],
+    password: &ZeroizeString,
+    workers: Option<usize>,
+) -> Result<(), String> {
+    if keystores.is_empty() {
+        return Ok(());
+    }
+
+    let mut pool_builder = rayon::ThreadPoolBuilder::new();
+    if let Some(workers) = workers {
+        pool_builder = pool_builder.num_threads(workers);
+    }
+    let pool = pool_builder
+        .build()
+        .map_err(|e| format!("Unable to start decryption threads: {:?}", e))?;
+
+    let total = keystores.len();
+    let decrypted = AtomicUsize::new(0);
+    eprintln!(
+        "Checking the password against {} keystores using {} threads",
+        total,
+        pool.current_num_threads()
+    );
+
+    let failures = pool.install(|| {
+        keystores
+            .par_iter()
+            .filter_map(|keystore| {
+                let result = keystore.decrypt_keypair(password.as_ref());
+                let count = decrypted.fetch_add(1, Ordering::Relaxed) + 1;
+                eprint!("\rDecrypted {}/{} keystores", count, total);
+                result
+                    .err()
+                    .map(|e| format!("0x{} ({:?})", keystore.pubkey(), e))
+            })
+            .collect::<Vec<_>>()
+    });
+    eprintln!();
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Unable to decrypt {} keystores with the provided password: {}",
+            failures.len(),
+            failures.join(", ")
+        ))
+    }
+}
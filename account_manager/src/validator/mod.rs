@@ -1,12 +1,14 @@
+pub mod bls_change;
 pub mod create;
 pub mod exit;
+pub mod exit_export;
 pub mod import;
 pub mod list;
 pub mod modify;
 pub mod recover;
 pub mod slashing_protection;
 
-use crate::{VALIDATOR_DIR_FLAG, VALIDATOR_DIR_FLAG_ALIAS};
+use crate::{OFFLINE_FLAG, VALIDATOR_DIR_FLAG, VALIDATOR_DIR_FLAG_ALIAS};
 use clap::{App, Arg, ArgMatches};
 use directory::{parse_path_or_default_with_flag, DEFAULT_VALIDATOR_DIR};
 use environment::Environment;
@@ -15,6 +17,17 @@ use types::EthSpec;
 
 pub const CMD: &str = "validator";
 
+/// The only commands permitted with `--offline`, since they never access the network. New commands
+/// must be added here explicitly to be usable on an air-gapped machine.
+pub const OFFLINE_COMMANDS: &[&str] = &[
+    create::CMD,
+    modify::CMD,
+    import::CMD,
+    list::CMD,
+    recover::CMD,
+    slashing_protection::CMD,
+];
+
 pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
     App::new(CMD)
         .about("Provides commands for managing Eth2 validators.")
@@ -37,6 +50,8 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         .subcommand(recover::cli_app())
         .subcommand(slashing_protection::cli_app())
         .subcommand(exit::cli_app())
+        .subcommand(exit_export::cli_app())
+        .subcommand(bls_change::cli_app())
 }
 
 pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<(), String> {
@@ -48,6 +63,16 @@ pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<
     };
     eprintln!("validator-dir path: {:?}", validator_base_dir);
 
+    if matches.is_present(OFFLINE_FLAG) {
+        let cmd = matches.subcommand_name().unwrap_or_default();
+        if !OFFLINE_COMMANDS.contains(&cmd) {
+            return Err(format!(
+                "The {} command requires network access and cannot be used with --{}",
+                cmd, OFFLINE_FLAG
+            ));
+        }
+    }
+
     match matches.subcommand() {
         (create::CMD, Some(matches)) => create::cli_run::<T>(matches, env, validator_base_dir),
         (modify::CMD, Some(matches)) => modify::cli_run(matches, validator_base_dir),
@@ -57,7 +82,9 @@ pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<
         (slashing_protection::CMD, Some(matches)) => {
             slashing_protection::cli_run(matches, env, validator_base_dir)
         }
-        (exit::CMD, Some(matches)) => exit::cli_run(matches, env),
+        (exit::CMD, Some(matches)) => exit::cli_run(matches, env, validator_base_dir),
+        (exit_export::CMD, Some(matches)) => exit_export::cli_run(matches, env, validator_base_dir),
+        (bls_change::CMD, Some(matches)) => bls_change::cli_run(matches, env),
         (unknown, _) => Err(format!(
             "{} does not have a {} command. See --help",
             CMD, unknown
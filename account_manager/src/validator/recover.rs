@@ -4,12 +4,16 @@ use crate::validator::create::COUNT_FLAG;
 use crate::wallet::create::STDIN_INPUTS_FLAG;
 use crate::SECRETS_DIR_FLAG;
 use account_utils::eth2_keystore::{keypair_from_secret, Keystore, KeystoreBuilder};
-use account_utils::random_password;
+use account_utils::validator_definitions::{
+    SigningDefinition, ValidatorDefinition, ValidatorDefinitions, CONFIG_FILENAME,
+};
+use account_utils::{default_keystore_password_path, random_password};
 use clap::{App, Arg, ArgMatches};
 use directory::ensure_dir_exists;
 use directory::{parse_path_or_default_with_flag, DEFAULT_SECRET_DIR};
 use eth2_wallet::bip39::Seed;
 use eth2_wallet::{recover_validator_secret_from_mnemonic, KeyType, ValidatorKeystores};
+use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
 use std::path::PathBuf;
 use validator_dir::Builder as ValidatorDirBuilder;
 pub const CMD: &str = "recover";
@@ -22,7 +26,10 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             "Recovers validator private keys given a BIP-39 mnemonic phrase. \
             If you did not specify a `--first-index` or count `--count`, by default this will \
             only recover the keys associated with the validator at index 0 for an HD wallet \
-            in accordance with the EIP-2333 spec.")
+            in accordance with the EIP-2333 spec. Recovered validators are registered in \
+            validator_definitions.yml and with slashing protection. Validators which are \
+            already registered are skipped, so a mnemonic may be split across machines by \
+            recovering a different index range on each.")
         .arg(
             Arg::with_name(FIRST_INDEX_FLAG)
                 .long(FIRST_INDEX_FLAG)
@@ -90,11 +97,41 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
     let mnemonic_path: Option<PathBuf> = clap_utils::parse_optional(matches, MNEMONIC_FLAG)?;
     let stdin_inputs = cfg!(windows) || matches.is_present(STDIN_INPUTS_FLAG);
 
+    let end_index = first_index.checked_add(count).ok_or_else(|| {
+        format!(
+            "--{} plus --{} must not exceed {}",
+            FIRST_INDEX_FLAG,
+            COUNT_FLAG,
+            u32::MAX
+        )
+    })?;
+
     eprintln!("secrets-dir path: {:?}", secrets_dir);
 
     ensure_dir_exists(&validator_dir)?;
     ensure_dir_exists(&secrets_dir)?;
 
+    let mut defs = ValidatorDefinitions::open_or_create(&validator_dir)
+        .map_err(|e| format!("Unable to open {}: {:?}", CONFIG_FILENAME, e))?;
+
+    let slashing_protection_path = validator_dir.join(SLASHING_PROTECTION_FILENAME);
+    let slashing_protection =
+        SlashingDatabase::open_or_create(&slashing_protection_path).map_err(|e| {
+            format!(
+                "Unable to open or create slashing protection database at {}: {:?}",
+                slashing_protection_path.display(),
+                e
+            )
+        })?;
+
+    // Create an empty transaction and drop it. Used to test if the database is locked.
+    slashing_protection.test_transaction().map_err(|e| {
+        format!(
+            "Cannot recover keys while the validator client is running: {:?}",
+            e
+        )
+    })?;
+
     eprintln!();
     eprintln!("WARNING: KEY RECOVERY CAN LEAD TO DUPLICATING VALIDATORS KEYS, WHICH CAN LEAD TO SLASHING.");
     eprintln!();
@@ -103,7 +140,7 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
 
     let seed = Seed::new(&mnemonic, "");
 
-    for index in first_index..first_index + count {
+    for index in first_index..end_index {
         let voting_password = random_password();
         let withdrawal_password = random_password();
 
@@ -127,8 +164,40 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
         };
 
         let voting_pubkey = keystores.voting.pubkey().to_string();
+        let voting_public_key = keystores.voting.public_key().ok_or_else(|| {
+            format!(
+                "Keystore public key is invalid: {}",
+                keystores.voting.pubkey()
+            )
+        })?;
+
+        if defs
+            .as_slice()
+            .iter()
+            .any(|def| def.voting_public_key == voting_public_key)
+        {
+            eprintln!(
+                "Skipping validator at index {} (0x{}), it is already registered in {}",
+                index, voting_pubkey, CONFIG_FILENAME
+            );
+            continue;
+        }
+
+        let description = keystores.voting.description().unwrap_or("").to_string();
+        let voting_keystore_password_path =
+            default_keystore_password_path(&keystores.voting, &secrets_dir);
+
+        slashing_protection
+            .register_validator(voting_public_key.compress())
+            .map_err(|e| {
+                format!(
+                    "Error registering validator {}: {:?}",
+                    voting_public_key.as_hex_string(),
+                    e
+                )
+            })?;
 
-        ValidatorDirBuilder::new(validator_dir.clone())
+        let validator = ValidatorDirBuilder::new(validator_dir.clone())
             .password_dir(secrets_dir.clone())
             .voting_keystore(keystores.voting, voting_password.as_bytes())
             .withdrawal_keystore(keystores.withdrawal, withdrawal_password.as_bytes())
@@ -136,10 +205,28 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
             .build()
             .map_err(|e| format!("Unable to build validator directory: {:?}", e))?;
 
+        defs.push(ValidatorDefinition {
+            enabled: true,
+            voting_public_key,
+            description,
+            graffiti: None,
+            suggested_fee_recipient: None,
+            gas_limit: None,
+            builder_proposals: None,
+            builder_boost_factor: None,
+            signing_definition: SigningDefinition::LocalKeystore {
+                voting_keystore_path: validator.voting_keystore_path(),
+                voting_keystore_password_path: Some(voting_keystore_password_path),
+                voting_keystore_password: None,
+            },
+        });
+        defs.save(&validator_dir)
+            .map_err(|e| format!("Unable to save {}: {:?}", CONFIG_FILENAME, e))?;
+
         println!(
             "{}/{}\tIndex: {}\t0x{}",
-            index - first_index,
-            count - first_index,
+            index - first_index + 1,
+            count,
             index,
             voting_pubkey
         );
@@ -380,7 +380,7 @@ fn process_slash_info<T: BeaconChainTypes>(
 ) -> Error {
     use AttestationSlashInfo::*;
 
-    if let Some(slasher) = chain.slasher.as_ref() {
+    if chain.slasher_enabled() {
         let (indexed_attestation, check_signature, err) = match slash_info {
             SignatureNotChecked(attestation, err) => {
                 match obtain_indexed_attestation_and_committees_per_slot(chain, attestation) {
@@ -413,7 +413,7 @@ fn process_slash_info<T: BeaconChainTypes>(
         }
 
         // Supply to slasher.
-        slasher.accept_attestation(indexed_attestation);
+        chain.slasher_accept_attestation(indexed_attestation);
 
         err
     } else {
@@ -435,8 +435,9 @@ impl<'a, T: BeaconChainTypes> IndexedAggregatedAttestation<'a, T> {
     ) -> Result<Self, Error> {
         Self::verify_slashable(signed_aggregate, chain)
             .map(|verified_aggregate| {
-                if let Some(slasher) = chain.slasher.as_ref() {
-                    slasher.accept_attestation(verified_aggregate.indexed_attestation.clone());
+                if chain.slasher_enabled() {
+                    chain
+                        .slasher_accept_attestation(verified_aggregate.indexed_attestation.clone());
                 }
                 verified_aggregate
             })
@@ -640,8 +641,8 @@ impl<'a, T: BeaconChainTypes> VerifiedAggregatedAttestation<'a, T> {
     }
 
     fn apply_to_slasher(self, chain: &BeaconChain<T>) -> Self {
-        if let Some(slasher) = chain.slasher.as_ref() {
-            slasher.accept_attestation(self.indexed_attestation.clone());
+        if chain.slasher_enabled() {
+            chain.slasher_accept_attestation(self.indexed_attestation.clone());
         }
         self
     }
@@ -805,8 +806,10 @@ impl<'a, T: BeaconChainTypes> IndexedUnaggregatedAttestation<'a, T> {
     ) -> Result<Self, Error> {
         Self::verify_slashable(attestation, subnet_id, chain)
             .map(|verified_unaggregated| {
-                if let Some(slasher) = chain.slasher.as_ref() {
-                    slasher.accept_attestation(verified_unaggregated.indexed_attestation.clone());
+                if chain.slasher_enabled() {
+                    chain.slasher_accept_attestation(
+                        verified_unaggregated.indexed_attestation.clone(),
+                    );
                 }
                 verified_unaggregated
             })
@@ -911,8 +914,8 @@ impl<'a, T: BeaconChainTypes> VerifiedUnaggregatedAttestation<'a, T> {
     }
 
     fn apply_to_slasher(self, chain: &BeaconChain<T>) -> Self {
-        if let Some(slasher) = chain.slasher.as_ref() {
-            slasher.accept_attestation(self.indexed_attestation.clone());
+        if chain.slasher_enabled() {
+            chain.slasher_accept_attestation(self.indexed_attestation.clone());
         }
         self
     }
@@ -153,7 +153,7 @@ fn reconstruct_blocks<E: EthSpec>(
                             reconstructed_transactions_root: header_from_payload
                                 .transactions_root(),
                         };
-                        debug!(log, "Failed to reconstruct block"; "root" => ?root, "error" => ?error);
+                        debug!(log, "Failed to reconstruct block"; "block_root" => ?root, "error" => ?error);
                         block_map.insert(root, Arc::new(Err(error)));
                     }
                 }
@@ -375,7 +375,7 @@ impl<E: EthSpec> EngineRequest<E> {
                 log,
                 "Please notify the devs";
                 "beacon_block_streamer" => "block_result not found in request",
-                "root" => ?root,
+                "block_root" => ?root,
             );
             Arc::new(Err(Error::BlockResultNotFound.into()))
         })
@@ -526,7 +526,7 @@ impl<T: BeaconChainTypes> BeaconBlockStreamer<T> {
                     self.beacon_chain.log,
                     "Please notify the devs";
                     "beacon_block_streamer" => "request not found",
-                    "root" => ?root,
+                    "block_root" => ?root,
                 );
                 no_request
                     .push_block_result(
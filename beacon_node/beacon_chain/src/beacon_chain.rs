@@ -45,11 +45,13 @@ use crate::observed_block_producers::ObservedBlockProducers;
 use crate::observed_operations::{ObservationOutcome, ObservedOperations};
 use crate::persisted_beacon_chain::{PersistedBeaconChain, DUMMY_CANONICAL_HEAD_BLOCK_ROOT};
 use crate::persisted_fork_choice::PersistedForkChoice;
+use crate::persisted_graffiti::PersistedGraffiti;
 use crate::pre_finalization_cache::PreFinalizationBlockCache;
 use crate::shuffling_cache::{BlockShufflingIds, ShufflingCache};
 use crate::snapshot_cache::{BlockProductionPreState, SnapshotCache};
 use crate::sync_committee_verification::{
-    Error as SyncCommitteeError, VerifiedSyncCommitteeMessage, VerifiedSyncContribution,
+    batch_verify_sync_committee_messages, Error as SyncCommitteeError,
+    VerifiedSyncCommitteeMessage, VerifiedSyncContribution,
 };
 use crate::timeout_rw_lock::TimeoutRwLock;
 use crate::validator_monitor::{
@@ -132,6 +134,8 @@ pub const BEACON_CHAIN_DB_KEY: Hash256 = Hash256::zero();
 pub const OP_POOL_DB_KEY: Hash256 = Hash256::zero();
 pub const ETH1_CACHE_DB_KEY: Hash256 = Hash256::zero();
 pub const FORK_CHOICE_DB_KEY: Hash256 = Hash256::zero();
+/// The graffiti shares the `BeaconChain` column with the persisted chain, so it needs its own key.
+pub const GRAFFITI_DB_KEY: Hash256 = Hash256::repeat_byte(0x01);
 
 /// Defines how old a block can be before it's no longer a candidate for the early attester cache.
 const EARLY_ATTESTER_CACHE_HISTORIC_SLOTS: u64 = 4;
@@ -414,8 +418,8 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub shutdown_sender: Sender<ShutdownReason>,
     /// Logging to CLI, etc.
     pub(crate) log: Logger,
-    /// Arbitrary bytes included in the blocks.
-    pub(crate) graffiti: Graffiti,
+    /// Arbitrary bytes included in the blocks, unless the validator client supplies its own.
+    pub(crate) graffiti: RwLock<Graffiti>,
     /// Optional slasher.
     pub slasher: Option<Arc<Slasher<T::EthSpec>>>,
     /// Provides monitoring of a set of explicitly defined validators.
@@ -424,7 +428,15 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub genesis_backfill_slot: Slot,
 }
 
-type BeaconBlockAndState<T, Payload> = (BeaconBlock<T, Payload>, BeaconState<T>);
+/// A block produced by `BeaconChain::produce_block_on_state`, alongside the post-state.
+pub struct BeaconBlockResponse<T: EthSpec, Payload: AbstractExecPayload<T>> {
+    pub block: BeaconBlock<T, Payload>,
+    pub state: BeaconState<T>,
+    /// The value paid to the proposer by the execution payload, in wei.
+    ///
+    /// This is `None` for blocks without an execution payload.
+    pub execution_payload_value: Option<Uint256>,
+}
 
 impl<T: BeaconChainTypes> BeaconChain<T> {
     /// Checks if a block is finalized.
@@ -1078,13 +1090,47 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .map(Some)
     }
 
+    /// Returns the block at the given root in blinded form, if any.
+    ///
+    /// Blinded blocks contain only the header of the execution payload, so this function never
+    /// queries the execution layer. It should be preferred over `Self::get_block` by all consumers
+    /// which do not require the payload's transactions or withdrawals.
+    ///
+    /// ## Errors
+    ///
+    /// May return a database error.
     pub fn get_blinded_block(
         &self,
         block_root: &Hash256,
     ) -> Result<Option<SignedBlindedBeaconBlock<T::EthSpec>>, Error> {
+        // The head block is held in memory, there's no need to read it from the database.
+        let head_snapshot = self.head_snapshot();
+        if head_snapshot.beacon_block_root == *block_root {
+            return Ok(Some(head_snapshot.beacon_block.clone_as_blinded()));
+        }
+
         Ok(self.store.get_blinded_block(block_root)?)
     }
 
+    /// Returns the graffiti included in blocks produced by this node, unless the validator client
+    /// supplies its own.
+    pub fn graffiti(&self) -> Graffiti {
+        *self.graffiti.read()
+    }
+
+    /// Sets the graffiti returned by `Self::graffiti`, persisting it so that it is retained across
+    /// restarts which don't set `--graffiti`.
+    ///
+    /// ## Errors
+    ///
+    /// May return a database error.
+    pub fn set_graffiti(&self, graffiti: Graffiti) -> Result<(), Error> {
+        self.store
+            .put_item(&GRAFFITI_DB_KEY, &PersistedGraffiti { graffiti })?;
+        *self.graffiti.write() = graffiti;
+        Ok(())
+    }
+
     /// Returns the state at the given root, if any.
     ///
     /// ## Errors
@@ -1859,6 +1905,27 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         })
     }
 
+    /// Performs the same validation as `Self::verify_sync_committee_message_for_gossip`, but for
+    /// multiple messages using batch BLS verification.
+    pub fn batch_verify_sync_committee_messages_for_gossip(
+        &self,
+        sync_messages: Vec<(SyncCommitteeMessage, SyncSubnetId)>,
+    ) -> Result<Vec<Result<VerifiedSyncCommitteeMessage, SyncCommitteeError>>, SyncCommitteeError>
+    {
+        metrics::inc_counter_by(
+            &metrics::SYNC_MESSAGE_PROCESSING_REQUESTS,
+            sync_messages.len() as u64,
+        );
+        let _timer = metrics::start_timer(&metrics::SYNC_MESSAGE_BATCH_GOSSIP_VERIFICATION_TIMES);
+
+        let results = batch_verify_sync_committee_messages(sync_messages, self)?;
+        metrics::inc_counter_by(
+            &metrics::SYNC_MESSAGE_PROCESSING_SUCCESSES,
+            results.iter().filter(|result| result.is_ok()).count() as u64,
+        );
+        Ok(results)
+    }
+
     /// Accepts some `SignedContributionAndProof` from the network and attempts to verify it,
     /// returning `Ok(_)` if it is valid to be (re)broadcast on the gossip network.
     pub fn verify_sync_contribution_for_gossip(
@@ -2506,6 +2573,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     ///
     /// This method is generally much more efficient than importing each block using
     /// `Self::process_block`.
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(num_blocks = chain_segment.len())
+    )]
     pub async fn process_chain_segment(
         self: &Arc<Self>,
         chain_segment: Vec<Arc<SignedBeaconBlock<T::EthSpec>>>,
@@ -2625,7 +2697,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                                 "Successfully verified gossip block";
                                 "graffiti" => graffiti_string,
                                 "slot" => slot,
-                                "root" => ?verified.block_root(),
+                                "block_root" => ?verified.block_root(),
                             );
 
                             Ok(verified)
@@ -2662,6 +2734,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     ///
     /// Returns an `Err` if the given block was invalid, or an error was encountered during
     /// verification.
+    #[tracing::instrument(level = "debug", skip_all, fields(block_root = ?block_root))]
     pub async fn process_block<B: IntoExecutionPendingBlock<T>>(
         self: &Arc<Self>,
         block_root: Hash256,
@@ -2743,6 +2816,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     ///
     /// An error is returned if the block was unable to be imported. It may be partially imported
     /// (i.e., this function is not atomic).
+    #[tracing::instrument(level = "debug", skip_all)]
     pub async fn import_execution_pending_block(
         self: Arc<Self>,
         execution_pending_block: ExecutionPendingBlock<T>,
@@ -3191,9 +3265,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
 
         // Allow the validator monitor to learn about a new valid state.
-        self.validator_monitor
-            .write()
-            .process_valid_state(current_slot.epoch(T::EthSpec::slots_per_epoch()), state);
+        self.validator_monitor.write().process_valid_state(
+            current_slot.epoch(T::EthSpec::slots_per_epoch()),
+            state,
+            &self.spec,
+        );
 
         let validator_monitor = self.validator_monitor.read();
 
@@ -3331,6 +3407,42 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
     }
 
+    /// Returns `true` if there is a slasher to supply with attestations and block headers, either
+    /// one running in this process or one subscribed to the slasher event streams.
+    pub fn slasher_enabled(&self) -> bool {
+        self.slasher.is_some()
+            || self.event_handler.as_ref().map_or(false, |event_handler| {
+                event_handler.has_slasher_attestation_subscribers()
+                    || event_handler.has_slasher_block_header_subscribers()
+            })
+    }
+
+    /// Supply an attestation with a valid signature to the slasher(s).
+    pub fn slasher_accept_attestation(&self, indexed_attestation: IndexedAttestation<T::EthSpec>) {
+        if let Some(event_handler) = self.event_handler.as_ref() {
+            if event_handler.has_slasher_attestation_subscribers() {
+                event_handler.register(EventKind::SlasherAttestation(Box::new(
+                    indexed_attestation.clone(),
+                )));
+            }
+        }
+        if let Some(slasher) = self.slasher.as_ref() {
+            slasher.accept_attestation(indexed_attestation);
+        }
+    }
+
+    /// Supply a block header with a valid signature to the slasher(s).
+    pub fn slasher_accept_block_header(&self, block_header: SignedBeaconBlockHeader) {
+        if let Some(event_handler) = self.event_handler.as_ref() {
+            if event_handler.has_slasher_block_header_subscribers() {
+                event_handler.register(EventKind::SlasherBlockHeader(block_header.clone()));
+            }
+        }
+        if let Some(slasher) = self.slasher.as_ref() {
+            slasher.accept_block_header(block_header);
+        }
+    }
+
     /// If a slasher is configured, provide the attestations from the block.
     fn import_block_update_slasher(
         &self,
@@ -3338,7 +3450,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         state: &BeaconState<T::EthSpec>,
         ctxt: &mut ConsensusContext<T::EthSpec>,
     ) {
-        if let Some(slasher) = self.slasher.as_ref() {
+        if self.slasher_enabled() {
             for attestation in block.body().attestations() {
                 let indexed_attestation = match ctxt.get_indexed_attestation(state, attestation) {
                     Ok(indexed) => indexed,
@@ -3353,7 +3465,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                         continue;
                     }
                 };
-                slasher.accept_attestation(indexed_attestation.clone());
+                self.slasher_accept_attestation(indexed_attestation.clone());
             }
         }
     }
@@ -3592,17 +3704,19 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         randao_reveal: Signature,
         slot: Slot,
         validator_graffiti: Option<Graffiti>,
-    ) -> Result<BeaconBlockAndState<T::EthSpec, Payload>, BlockProductionError> {
+    ) -> Result<BeaconBlockResponse<T::EthSpec, Payload>, BlockProductionError> {
         self.produce_block_with_verification(
             randao_reveal,
             slot,
             validator_graffiti,
             ProduceBlockVerification::VerifyRandao,
+            None,
         )
         .await
     }
 
-    /// Same as `produce_block` but allowing for configuration of RANDAO-verification.
+    /// Same as `produce_block` but allowing for configuration of RANDAO-verification and the
+    /// proposer's `builder_boost_factor` (see `BuilderParams`).
     pub async fn produce_block_with_verification<
         Payload: AbstractExecPayload<T::EthSpec> + 'static,
     >(
@@ -3611,7 +3725,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         slot: Slot,
         validator_graffiti: Option<Graffiti>,
         verification: ProduceBlockVerification,
-    ) -> Result<BeaconBlockAndState<T::EthSpec, Payload>, BlockProductionError> {
+        builder_boost_factor: Option<u64>,
+    ) -> Result<BeaconBlockResponse<T::EthSpec, Payload>, BlockProductionError> {
         // Part 1/2 (blocking)
         //
         // Load the parent state from disk.
@@ -3636,6 +3751,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             randao_reveal,
             validator_graffiti,
             verification,
+            builder_boost_factor,
         )
         .await
     }
@@ -4209,7 +4325,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         randao_reveal: Signature,
         validator_graffiti: Option<Graffiti>,
         verification: ProduceBlockVerification,
-    ) -> Result<BeaconBlockAndState<T::EthSpec, Payload>, BlockProductionError> {
+        builder_boost_factor: Option<u64>,
+    ) -> Result<BeaconBlockResponse<T::EthSpec, Payload>, BlockProductionError> {
         // Part 1/3 (blocking)
         //
         // Perform the state advance and block-packing functions.
@@ -4224,6 +4341,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                         produce_at_slot,
                         randao_reveal,
                         validator_graffiti,
+                        builder_boost_factor,
                     )
                 },
                 "produce_partial_beacon_block",
@@ -4274,6 +4392,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         produce_at_slot: Slot,
         randao_reveal: Signature,
         validator_graffiti: Option<Graffiti>,
+        builder_boost_factor: Option<u64>,
     ) -> Result<PartialBeaconBlock<T::EthSpec, Payload>, BlockProductionError> {
         let eth1_chain = self
             .eth1_chain
@@ -4321,6 +4440,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             chain_health: self
                 .is_healthy(&parent_root)
                 .map_err(BlockProductionError::BeaconChain)?,
+            builder_boost_factor,
         };
 
         // If required, start the process of loading an execution payload from the EL early. This
@@ -4368,7 +4488,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // Override the beacon node's graffiti with graffiti from the validator, if present.
         let graffiti = match validator_graffiti {
             Some(graffiti) => graffiti,
-            None => self.graffiti,
+            None => self.graffiti(),
         };
 
         let attestation_packing_timer =
@@ -4389,6 +4509,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 &state,
                 prev_attestation_filter,
                 curr_attestation_filter,
+                Duration::from_millis(self.config.attestation_packing_time_budget_ms),
                 &self.spec,
             )
             .map_err(BlockProductionError::OpPoolError)?;
@@ -4511,7 +4632,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         partial_beacon_block: PartialBeaconBlock<T::EthSpec, Payload>,
         block_contents: Option<BlockProposalContents<T::EthSpec, Payload>>,
         verification: ProduceBlockVerification,
-    ) -> Result<BeaconBlockAndState<T::EthSpec, Payload>, BlockProductionError> {
+    ) -> Result<BeaconBlockResponse<T::EthSpec, Payload>, BlockProductionError> {
         let PartialBeaconBlock {
             mut state,
             slot,
@@ -4533,6 +4654,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             bls_to_execution_changes,
         } = partial_beacon_block;
 
+        let execution_payload_value = block_contents
+            .as_ref()
+            .map(|contents| *contents.block_value());
+
         let inner_block = match &state {
             BeaconState::Base(_) => BeaconBlock::Base(BeaconBlockBase {
                 slot,
@@ -4673,7 +4798,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             "slot" => block.slot()
         );
 
-        Ok((block, state))
+        Ok(BeaconBlockResponse {
+            block,
+            state,
+            execution_payload_value,
+        })
     }
 
     /// This method must be called whenever an execution engine indicates that a payload is
@@ -493,7 +493,7 @@ fn process_block_slash_info<T: BeaconChainTypes>(
     chain: &BeaconChain<T>,
     slash_info: BlockSlashInfo<BlockError<T::EthSpec>>,
 ) -> BlockError<T::EthSpec> {
-    if let Some(slasher) = chain.slasher.as_ref() {
+    if chain.slasher_enabled() {
         let (verified_header, error) = match slash_info {
             BlockSlashInfo::SignatureNotChecked(header, e) => {
                 if verify_header_signature(chain, &header).is_ok() {
@@ -506,7 +506,7 @@ fn process_block_slash_info<T: BeaconChainTypes>(
             BlockSlashInfo::SignatureValid(header, e) => (header, e),
         };
 
-        slasher.accept_block_header(verified_header);
+        chain.slasher_accept_block_header(verified_header);
         error
     } else {
         match slash_info {
@@ -679,8 +679,9 @@ pub trait IntoExecutionPendingBlock<T: BeaconChainTypes>: Sized {
         self.into_execution_pending_block_slashable(block_root, chain, notify_execution_layer)
             .map(|execution_pending| {
                 // Supply valid block to slasher.
-                if let Some(slasher) = chain.slasher.as_ref() {
-                    slasher.accept_block_header(execution_pending.block.signed_block_header());
+                if chain.slasher_enabled() {
+                    chain
+                        .slasher_accept_block_header(execution_pending.block.signed_block_header());
                 }
                 execution_pending
             })
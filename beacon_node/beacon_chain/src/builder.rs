@@ -1,11 +1,15 @@
-use crate::beacon_chain::{CanonicalHead, BEACON_CHAIN_DB_KEY, ETH1_CACHE_DB_KEY, OP_POOL_DB_KEY};
+use crate::beacon_chain::{
+    CanonicalHead, BEACON_CHAIN_DB_KEY, ETH1_CACHE_DB_KEY, GRAFFITI_DB_KEY, OP_POOL_DB_KEY,
+};
 use crate::eth1_chain::{CachingEth1Backend, SszEth1};
 use crate::eth1_finalization_cache::Eth1FinalizationCache;
 use crate::fork_choice_signal::ForkChoiceSignalTx;
 use crate::fork_revert::{reset_fork_choice_to_finalization, revert_to_fork_boundary};
 use crate::head_tracker::HeadTracker;
 use crate::migrate::{BackgroundMigrator, MigratorConfig};
+use crate::observed_attesters::{ObservedAggregators, ObservedAttesters};
 use crate::persisted_beacon_chain::PersistedBeaconChain;
+use crate::persisted_graffiti::PersistedGraffiti;
 use crate::shuffling_cache::{BlockShufflingIds, ShufflingCache};
 use crate::snapshot_cache::{SnapshotCache, DEFAULT_SNAPSHOT_CACHE_SIZE};
 use crate::timeout_rw_lock::TimeoutRwLock;
@@ -89,6 +93,7 @@ pub struct BeaconChainBuilder<T: BeaconChainTypes> {
     chain_config: ChainConfig,
     log: Option<Logger>,
     graffiti: Graffiti,
+    graffiti_set_explicitly: bool,
     slasher: Option<Arc<Slasher<T::EthSpec>>>,
     validator_monitor: Option<ValidatorMonitor<T::EthSpec>>,
     // Pending I/O batch that is constructed during building and should be executed atomically
@@ -130,6 +135,7 @@ where
             chain_config: ChainConfig::default(),
             log: None,
             graffiti: Graffiti::default(),
+            graffiti_set_explicitly: false,
             slasher: None,
             validator_monitor: None,
             pending_io_batch: vec![],
@@ -572,6 +578,13 @@ where
         self
     }
 
+    /// Sets whether the `graffiti` was provided by the user, in which case it replaces any
+    /// graffiti set via the HTTP API.
+    pub fn graffiti_set_explicitly(mut self, graffiti_set_explicitly: bool) -> Self {
+        self.graffiti_set_explicitly = graffiti_set_explicitly;
+        self
+    }
+
     /// Sets the `ChainConfig` that determines `BeaconChain` runtime behaviour.
     pub fn chain_config(mut self, config: ChainConfig) -> Self {
         self.chain_config = config;
@@ -584,6 +597,7 @@ where
     pub fn monitor_validators(
         mut self,
         auto_register: bool,
+        auto_register_limit: Option<usize>,
         validators: Vec<PublicKeyBytes>,
         individual_metrics_threshold: usize,
         log: Logger,
@@ -591,6 +605,7 @@ where
         self.validator_monitor = Some(ValidatorMonitor::new(
             validators,
             auto_register,
+            auto_register_limit,
             individual_metrics_threshold,
             log.clone(),
         ));
@@ -627,6 +642,7 @@ where
         let mut validator_monitor = self
             .validator_monitor
             .ok_or("Cannot build without a validator monitor")?;
+        validator_monitor.set_event_handler(self.event_handler.clone());
         let head_tracker = Arc::new(self.head_tracker.unwrap_or_default());
 
         let current_slot = if slot_clock
@@ -736,6 +752,7 @@ where
             validator_monitor.process_valid_state(
                 slot.epoch(TEthSpec::slots_per_epoch()),
                 &head_snapshot.beacon_state,
+                &self.spec,
             );
         }
 
@@ -775,8 +792,53 @@ where
         let canonical_head = CanonicalHead::new(fork_choice, Arc::new(head_snapshot));
         let shuffling_cache_size = self.chain_config.shuffling_cache_size;
 
+        // A graffiti set via the HTTP API takes precedence over the default one, but is replaced
+        // by a graffiti provided by the user so that `--graffiti` always takes effect.
+        let graffiti = match store
+            .get_item::<PersistedGraffiti>(&GRAFFITI_DB_KEY)
+            .map_err(|e| format!("Unable to load persisted graffiti: {:?}", e))?
+        {
+            Some(persisted) if self.graffiti_set_explicitly => {
+                info!(
+                    log,
+                    "Removing graffiti from database";
+                    "graffiti" => persisted.graffiti.as_utf8_lossy(),
+                    "configured" => self.graffiti.as_utf8_lossy(),
+                );
+                store
+                    .hot_db
+                    .delete::<PersistedGraffiti>(&GRAFFITI_DB_KEY)
+                    .map_err(|e| format!("Unable to remove persisted graffiti: {:?}", e))?;
+                self.graffiti
+            }
+            Some(persisted) => {
+                if persisted.graffiti != self.graffiti {
+                    info!(
+                        log,
+                        "Using graffiti from database";
+                        "graffiti" => persisted.graffiti.as_utf8_lossy(),
+                        "configured" => self.graffiti.as_utf8_lossy(),
+                    );
+                }
+                persisted.graffiti
+            }
+            None => self.graffiti,
+        };
+
         // Calculate the weak subjectivity point in which to backfill blocks to.
-        let genesis_backfill_slot = if self.chain_config.genesis_backfill {
+        let genesis_backfill_slot = if self.chain_config.disable_backfill {
+            // Treat the oldest block in the database as the backfill target, so that backfill is
+            // considered complete from the outset.
+            let oldest_block_slot = store
+                .get_anchor_info()
+                .map_or(Slot::new(0), |anchor| anchor.oldest_block_slot);
+            info!(
+                log,
+                "Backfill sync disabled";
+                "oldest_block_slot" => oldest_block_slot,
+            );
+            oldest_block_slot
+        } else if self.chain_config.genesis_backfill {
             Slot::new(0)
         } else {
             let backfill_epoch_range = (self.spec.min_validator_withdrawability_delay
@@ -817,13 +879,22 @@ where
             // TODO: allow for persisting and loading the pool from disk.
             observed_sync_contributions: <_>::default(),
             // TODO: allow for persisting and loading the pool from disk.
-            observed_gossip_attesters: <_>::default(),
+            observed_gossip_attesters: RwLock::new(ObservedAttesters::new(
+                self.chain_config.observed_gossip_attesters_epochs,
+                self.chain_config.observed_gossip_attesters_capacity,
+            )),
             // TODO: allow for persisting and loading the pool from disk.
-            observed_block_attesters: <_>::default(),
+            observed_block_attesters: RwLock::new(ObservedAttesters::new(
+                self.chain_config.observed_block_attesters_epochs,
+                self.chain_config.observed_block_attesters_capacity,
+            )),
             // TODO: allow for persisting and loading the pool from disk.
             observed_sync_contributors: <_>::default(),
             // TODO: allow for persisting and loading the pool from disk.
-            observed_aggregators: <_>::default(),
+            observed_aggregators: RwLock::new(ObservedAggregators::new(
+                self.chain_config.observed_aggregators_epochs,
+                self.chain_config.observed_aggregators_capacity,
+            )),
             // TODO: allow for persisting and loading the pool from disk.
             observed_sync_aggregators: <_>::default(),
             // TODO: allow for persisting and loading the pool from disk.
@@ -865,7 +936,7 @@ where
                 .shutdown_sender
                 .ok_or("Cannot build without a shutdown sender.")?,
             log: log.clone(),
-            graffiti: self.graffiti,
+            graffiti: RwLock::new(graffiti),
             slasher: self.slasher.clone(),
             validator_monitor: RwLock::new(validator_monitor),
             genesis_backfill_slot,
@@ -1087,6 +1158,7 @@ mod test {
             .shutdown_sender(shutdown_tx)
             .monitor_validators(
                 true,
+                None,
                 vec![],
                 DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD,
                 log.clone(),
@@ -53,10 +53,15 @@ use itertools::process_results;
 use parking_lot::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use slog::{crit, debug, error, warn, Logger};
 use slot_clock::SlotClock;
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use std::time::{Duration, Instant};
 use store::{iter::StateRootsIterator, KeyValueStoreOp, StoreItem};
 use task_executor::{JoinHandle, ShutdownReason};
+use tokio::sync::broadcast;
+use tracing::debug_span;
 use types::*;
 
 /// Simple wrapper around `RwLock` that uses private visibility to prevent any other modules from
@@ -248,6 +253,32 @@ pub struct CanonicalHead<T: BeaconChainTypes> {
     ///
     /// This lock **should not be made public**, it should only be used inside this module.
     recompute_head_lock: Mutex<()>,
+    /// The number of calls to `BeaconChain::recompute_head_at_slot` which have not yet completed.
+    recompute_head_in_progress: AtomicUsize,
+    /// Sends a message each time a call to `BeaconChain::recompute_head_at_slot` completes.
+    recompute_head_finished_tx: broadcast::Sender<()>,
+}
+
+/// Decrements the count of in-progress head recomputations and notifies any subscribers when
+/// dropped, so that both remain correct if the recomputing future is cancelled.
+struct RecomputeHeadInProgress<'a> {
+    count: &'a AtomicUsize,
+    finished_tx: &'a broadcast::Sender<()>,
+}
+
+impl<'a> RecomputeHeadInProgress<'a> {
+    fn new(count: &'a AtomicUsize, finished_tx: &'a broadcast::Sender<()>) -> Self {
+        count.fetch_add(1, Ordering::SeqCst);
+        Self { count, finished_tx }
+    }
+}
+
+impl<'a> Drop for RecomputeHeadInProgress<'a> {
+    fn drop(&mut self) {
+        self.count.fetch_sub(1, Ordering::SeqCst);
+        // An error only indicates that there are no subscribers.
+        let _ = self.finished_tx.send(());
+    }
 }
 
 impl<T: BeaconChainTypes> CanonicalHead<T> {
@@ -271,6 +302,10 @@ impl<T: BeaconChainTypes> CanonicalHead<T> {
             fork_choice: CanonicalHeadRwLock::new(fork_choice),
             cached_head: CanonicalHeadRwLock::new(cached_head),
             recompute_head_lock: Mutex::new(()),
+            recompute_head_in_progress: AtomicUsize::new(0),
+            // Subscribers only need to know that *a* recompute has finished, so a lagging
+            // subscriber loses nothing of value.
+            recompute_head_finished_tx: broadcast::channel(1).0,
         }
     }
 
@@ -380,17 +415,17 @@ impl<T: BeaconChainTypes> CanonicalHead<T> {
     /// This function is **not safe** to be public. See the module-level documentation for more
     /// information about protecting from deadlocks.
     fn cached_head_write_lock(&self) -> RwLockWriteGuard<CachedHead<T::EthSpec>> {
-        self.cached_head.write()
+        debug_span!("cached_head_write_lock").in_scope(|| self.cached_head.write())
     }
 
     /// Access a read-lock for fork choice.
     pub fn fork_choice_read_lock(&self) -> RwLockReadGuard<BeaconForkChoice<T>> {
-        self.fork_choice.read()
+        debug_span!("fork_choice_read_lock").in_scope(|| self.fork_choice.read())
     }
 
     /// Access a write-lock for fork choice.
     pub fn fork_choice_write_lock(&self) -> RwLockWriteGuard<BeaconForkChoice<T>> {
-        self.fork_choice.write()
+        debug_span!("fork_choice_write_lock").in_scope(|| self.fork_choice.write())
     }
 }
 
@@ -496,9 +531,16 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     /// such a case it's critical that the `BeaconChain` keeps importing blocks so that the
     /// situation can be rectified. We avoid returning an error here so that calling functions
     /// can't abort block import because an error is returned here.
+    #[tracing::instrument(level = "debug", skip_all, fields(slot = %current_slot))]
     pub async fn recompute_head_at_slot(self: &Arc<Self>, current_slot: Slot) {
         metrics::inc_counter(&metrics::FORK_CHOICE_REQUESTS);
         let _timer = metrics::start_timer(&metrics::FORK_CHOICE_TIMES);
+        let start = Instant::now();
+        let _in_progress = RecomputeHeadInProgress::new(
+            &self.canonical_head.recompute_head_in_progress,
+            &self.canonical_head.recompute_head_finished_tx,
+        );
+        let started_before_deadline = self.is_before_attestation_deadline(current_slot);
 
         let chain = self.clone();
         match self
@@ -550,6 +592,57 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 );
             }
         }
+
+        metrics::set_gauge(
+            &metrics::FORK_CHOICE_LAST_RUN_MILLISECONDS,
+            start.elapsed().as_millis() as i64,
+        );
+
+        // Attestations produced after a run which crosses the deadline may not vote for the
+        // latest head.
+        if started_before_deadline && !self.is_before_attestation_deadline(current_slot) {
+            metrics::inc_counter(&metrics::FORK_CHOICE_ATTESTATION_DEADLINE_MISSES);
+            warn!(
+                self.log,
+                "Head recomputed after attestation deadline";
+                "slot" => current_slot,
+                "duration_ms" => start.elapsed().as_millis() as u64,
+            );
+        }
+    }
+
+    /// Returns `true` if `slot` is the current slot and its attestation deadline (1/3 of the way
+    /// through the slot) has not yet passed.
+    fn is_before_attestation_deadline(&self, slot: Slot) -> bool {
+        self.slot_clock.now() == Some(slot)
+            && self
+                .slot_clock
+                .seconds_from_current_slot_start()
+                .map_or(false, |elapsed| {
+                    elapsed < self.slot_clock.unagg_attestation_production_delay()
+                })
+    }
+
+    /// Returns `true` if the head is being recomputed and the attestation deadline of the current
+    /// slot has not yet passed.
+    ///
+    /// Lower-priority work should be deferred whilst this is the case, so that the head is updated
+    /// in time for attestation production.
+    pub fn is_recomputing_head_before_attestation_deadline(&self) -> bool {
+        self.canonical_head
+            .recompute_head_in_progress
+            .load(Ordering::SeqCst)
+            > 0
+            && self
+                .slot_clock
+                .now()
+                .map_or(false, |slot| self.is_before_attestation_deadline(slot))
+    }
+
+    /// Returns a receiver which is sent a message each time a call to `recompute_head_at_slot`
+    /// completes, regardless of where it was called from.
+    pub fn subscribe_to_recompute_head_finished(&self) -> broadcast::Receiver<()> {
+        self.canonical_head.recompute_head_finished_tx.subscribe()
     }
 
     /// A non-async (blocking) function which recomputes the canonical head and spawns async tasks.
@@ -699,12 +792,27 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 finalized_hash: new_forkchoice_update_parameters.finalized_hash,
             };
 
+            // Build the server-sent event before taking the lock, so that it can be registered
+            // atomically with the update to the cached head.
+            let head_event = self.head_event(
+                &old_cached_head.snapshot,
+                &new_cached_head.snapshot,
+                &new_head_proto_block,
+            );
+
             let new_head = {
                 // Now the new snapshot has been obtained, take a write-lock on the cached head so
                 // we can update it quickly.
                 let mut cached_head_write_lock = self.canonical_head.cached_head_write_lock();
                 // Enshrine the new head as the canonical cached head.
                 *cached_head_write_lock = new_cached_head;
+                // Register the server-sent event for the new head whilst holding the lock, so
+                // that subscribers never observe a head without its event (or vice versa).
+                if let (Some(event_handler), Some(head_event)) =
+                    (self.event_handler.as_ref(), head_event)
+                {
+                    event_handler.register(EventKind::Head(head_event));
+                }
                 // Take a clone of the cached head for later use. It is cloned whilst
                 // holding the write-lock to ensure we get exactly the head we just enshrined.
                 cached_head_write_lock.clone()
@@ -785,6 +893,48 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(Some(el_update_handle))
     }
 
+    /// Returns the server-sent event for a change of head from `old_snapshot` to `new_snapshot`, or
+    /// `None` if there are no subscribers.
+    fn head_event(
+        &self,
+        old_snapshot: &BeaconSnapshot<T::EthSpec>,
+        new_snapshot: &BeaconSnapshot<T::EthSpec>,
+        new_head_proto_block: &ProtoBlock,
+    ) -> Option<SseHead> {
+        self.event_handler
+            .as_ref()
+            .filter(|handler| handler.has_head_subscribers())?;
+
+        let dependent_root = new_snapshot
+            .beacon_state
+            .proposer_shuffling_decision_root(self.genesis_block_root);
+        let prev_dependent_root = new_snapshot
+            .beacon_state
+            .attester_shuffling_decision_root(self.genesis_block_root, RelativeEpoch::Current);
+
+        match (dependent_root, prev_dependent_root) {
+            (Ok(current_duty_dependent_root), Ok(previous_duty_dependent_root)) => Some(SseHead {
+                slot: new_snapshot.beacon_state.slot(),
+                block: new_snapshot.beacon_block_root,
+                state: new_snapshot.beacon_state_root(),
+                current_duty_dependent_root,
+                previous_duty_dependent_root,
+                epoch_transition: is_epoch_transition(old_snapshot, new_snapshot),
+                execution_optimistic: new_head_proto_block
+                    .execution_status
+                    .is_optimistic_or_invalid(),
+            }),
+            (Err(e), _) | (_, Err(e)) => {
+                warn!(
+                    self.log,
+                    "Unable to find dependent roots, cannot register head event";
+                    "error" => ?e
+                );
+                None
+            }
+        }
+    }
+
     /// Perform updates to caches and other components after the canonical head has been changed.
     fn after_new_head(
         self: &Arc<Self>,
@@ -809,25 +959,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             &self.log,
         );
 
-        // Determine if the new head is in a later epoch to the previous head.
-        let is_epoch_transition = old_snapshot
-            .beacon_block
-            .slot()
-            .epoch(T::EthSpec::slots_per_epoch())
-            < new_snapshot
-                .beacon_state
-                .slot()
-                .epoch(T::EthSpec::slots_per_epoch());
-
-        // These fields are used for server-sent events.
-        let state_root = new_snapshot.beacon_state_root();
+        // This field is used for server-sent events.
         let head_slot = new_snapshot.beacon_state.slot();
-        let dependent_root = new_snapshot
-            .beacon_state
-            .proposer_shuffling_decision_root(self.genesis_block_root);
-        let prev_dependent_root = new_snapshot
-            .beacon_state
-            .attester_shuffling_decision_root(self.genesis_block_root, RelativeEpoch::Current);
 
         // Update the snapshot cache with the latest head value.
         //
@@ -892,39 +1025,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             &self.log,
         );
 
-        if is_epoch_transition || reorg_distance.is_some() {
+        if is_epoch_transition(old_snapshot, new_snapshot) || reorg_distance.is_some() {
             self.persist_head_and_fork_choice()?;
             self.op_pool.prune_attestations(self.epoch()?);
         }
 
-        // Register server-sent-events for a new head.
-        if let Some(event_handler) = self
-            .event_handler
-            .as_ref()
-            .filter(|handler| handler.has_head_subscribers())
-        {
-            match (dependent_root, prev_dependent_root) {
-                (Ok(current_duty_dependent_root), Ok(previous_duty_dependent_root)) => {
-                    event_handler.register(EventKind::Head(SseHead {
-                        slot: head_slot,
-                        block: new_snapshot.beacon_block_root,
-                        state: state_root,
-                        current_duty_dependent_root,
-                        previous_duty_dependent_root,
-                        epoch_transition: is_epoch_transition,
-                        execution_optimistic: new_head_is_optimistic,
-                    }));
-                }
-                (Err(e), _) | (_, Err(e)) => {
-                    warn!(
-                        self.log,
-                        "Unable to find dependent roots, cannot register head event";
-                        "error" => ?e
-                    );
-                }
-            }
-        }
-
         // Register a server-sent-event for a reorg (if necessary).
         if let Some(depth) = reorg_distance {
             if let Some(event_handler) = self
@@ -1221,6 +1326,15 @@ fn spawn_execution_layer_updates<T: BeaconChainTypes>(
         .ok_or(Error::RuntimeShutdown)
 }
 
+/// Determine if the new head is in a later epoch to the previous head.
+fn is_epoch_transition<E: EthSpec>(
+    old_snapshot: &BeaconSnapshot<E>,
+    new_snapshot: &BeaconSnapshot<E>,
+) -> bool {
+    old_snapshot.beacon_block.slot().epoch(E::slots_per_epoch())
+        < new_snapshot.beacon_state.slot().epoch(E::slots_per_epoch())
+}
+
 /// Attempt to detect if the new head is not on the same chain as the previous block
 /// (i.e., a re-org).
 ///
@@ -1410,6 +1524,39 @@ fn observe_head_block_delays<E: EthSpec, S: SlotClock>(
                 .unwrap_or_else(|| Duration::from_secs(0)),
         );
 
+        // Observe how long was left to attest to the block after it was set as head.
+        let attestation_deadline = slot_clock.unagg_attestation_production_delay();
+        if let Some(headroom) = attestation_deadline.checked_sub(block_delay_total) {
+            metrics::observe_duration(
+                &metrics::BEACON_BLOCK_HEAD_ATTESTATION_DEADLINE_HEADROOM_TIME,
+                headroom,
+            );
+        }
+
+        // Record a breakdown of each phase for the most recent head, so that the cause of a late
+        // head can be identified.
+        let millis = |duration: Option<Duration>| duration.map_or(0, |d| d.as_millis() as i64);
+        for (phase, value) in [
+            ("observed", millis(block_delays.observed)),
+            ("imported", millis(block_delays.imported)),
+            ("set_as_head", millis(block_delays.set_as_head)),
+            ("total", millis(Some(block_delay_total))),
+            (
+                "attestation_deadline_headroom",
+                attestation_deadline.as_millis() as i64 - block_delay_total.as_millis() as i64,
+            ),
+        ] {
+            metrics::set_gauge_vec(
+                &metrics::BEACON_BLOCK_LAST_HEAD_PHASE_MILLISECONDS,
+                &[phase],
+                value,
+            );
+        }
+        metrics::set_gauge(
+            &metrics::BEACON_BLOCK_LAST_HEAD_SLOT,
+            head_block_slot.as_u64() as i64,
+        );
+
         // If the block was enshrined as head too late for attestations to be created for it,
         // log a debug warning and increment a metric.
         if late_head {
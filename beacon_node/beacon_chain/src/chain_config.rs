@@ -1,3 +1,6 @@
+use crate::observed_attesters::{
+    DEFAULT_AGGREGATORS_CAPACITY, DEFAULT_ATTESTERS_CAPACITY, MAX_CACHED_EPOCHS,
+};
 pub use proto_array::{DisallowedReOrgOffsets, ReOrgThreshold};
 use serde_derive::{Deserialize, Serialize};
 use std::time::Duration;
@@ -9,12 +12,19 @@ pub const DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION: Epoch = Epoch::new(2);
 pub const DEFAULT_RE_ORG_CUTOFF_DENOMINATOR: u32 = 12;
 pub const DEFAULT_FORK_CHOICE_BEFORE_PROPOSAL_TIMEOUT: u64 = 250;
 
+/// Default time budget for improving the packing of attestations into a block, in milliseconds.
+pub const DEFAULT_ATTESTATION_PACKING_TIME_BUDGET: u64 = 50;
+
 /// Default fraction of a slot lookahead for payload preparation (12/3 = 4 seconds on mainnet).
 pub const DEFAULT_PREPARE_PAYLOAD_LOOKAHEAD_FACTOR: u32 = 3;
 
 /// Fraction of a slot lookahead for fork choice in the state advance timer (500ms on mainnet).
 pub const FORK_CHOICE_LOOKAHEAD_FACTOR: u32 = 24;
 
+/// Default fraction of a slot lookahead for the state advance (3s, i.e. 9s into the slot on
+/// mainnet).
+pub const DEFAULT_STATE_ADVANCE_LOOKAHEAD_DENOMINATOR: u32 = 4;
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 pub struct ChainConfig {
     /// Maximum number of slots to skip when importing an attestation.
@@ -61,6 +71,12 @@ pub struct ChainConfig {
     pub always_reset_payload_statuses: bool,
     /// Whether to apply paranoid checks to blocks proposed by this beacon node.
     pub paranoid_block_proposal: bool,
+    /// The maximum time to spend improving the greedy packing of attestations into a block.
+    pub attestation_packing_time_budget_ms: u64,
+    /// Delay after the start of the slot at which to advance the head state to the next slot.
+    pub state_advance_offset_millis: Option<u64>,
+    /// Whether to disable advancing the head state to the next slot ahead of time.
+    pub disable_state_advance: bool,
     /// Optionally set timeout for calls to checkpoint sync endpoint.
     pub checkpoint_sync_url_timeout: u64,
     /// The offset before the start of a proposal slot at which payload attributes should be sent.
@@ -72,9 +88,26 @@ pub struct ChainConfig {
     pub optimistic_finalized_sync: bool,
     /// The size of the shuffling cache,
     pub shuffling_cache_size: usize,
+    /// The number of epochs of unaggregated attestation gossip to remember the attesters of.
+    pub observed_gossip_attesters_epochs: u64,
+    /// An allocation hint for the first epoch of `observed_gossip_attesters`, in validators. It does not
+    /// bound the size of the cache.
+    pub observed_gossip_attesters_capacity: usize,
+    /// The number of epochs of attestations included in blocks to remember the attesters of.
+    pub observed_block_attesters_epochs: u64,
+    /// An allocation hint for the first epoch of `observed_block_attesters`, in validators. It does not
+    /// bound the size of the cache.
+    pub observed_block_attesters_capacity: usize,
+    /// The number of epochs of aggregate gossip to remember the aggregators of.
+    pub observed_aggregators_epochs: u64,
+    /// An allocation hint for the first epoch of `observed_aggregators`, in validators. It does not
+    /// bound the size of the cache.
+    pub observed_aggregators_capacity: usize,
     /// If using a weak-subjectivity sync, whether we should download blocks all the way back to
     /// genesis.
     pub genesis_backfill: bool,
+    /// If using a weak-subjectivity sync, whether to skip downloading historic blocks entirely.
+    pub disable_backfill: bool,
     /// Whether to send payload attributes every slot, regardless of connected proposers.
     ///
     /// This is useful for block builders and testing.
@@ -105,12 +138,22 @@ impl Default for ChainConfig {
             builder_fallback_disable_checks: false,
             always_reset_payload_statuses: false,
             paranoid_block_proposal: false,
+            attestation_packing_time_budget_ms: DEFAULT_ATTESTATION_PACKING_TIME_BUDGET,
+            state_advance_offset_millis: None,
+            disable_state_advance: false,
             checkpoint_sync_url_timeout: 60,
             prepare_payload_lookahead: Duration::from_secs(4),
             // This value isn't actually read except in tests.
             optimistic_finalized_sync: true,
             shuffling_cache_size: crate::shuffling_cache::DEFAULT_CACHE_SIZE,
+            observed_gossip_attesters_epochs: MAX_CACHED_EPOCHS,
+            observed_gossip_attesters_capacity: DEFAULT_ATTESTERS_CAPACITY,
+            observed_block_attesters_epochs: MAX_CACHED_EPOCHS,
+            observed_block_attesters_capacity: DEFAULT_ATTESTERS_CAPACITY,
+            observed_aggregators_epochs: MAX_CACHED_EPOCHS,
+            observed_aggregators_capacity: DEFAULT_AGGREGATORS_CAPACITY,
             genesis_backfill: false,
+            disable_backfill: false,
             always_prepare_payload: false,
             enable_backfill_rate_limiting: true,
             progressive_balances_mode: ProgressiveBalancesMode::Checked,
@@ -127,4 +170,14 @@ impl ChainConfig {
                 Duration::from_secs(seconds_per_slot) / DEFAULT_RE_ORG_CUTOFF_DENOMINATOR
             })
     }
+
+    /// The delay after the start of the slot at which to advance the head state.
+    pub fn state_advance_offset(&self, seconds_per_slot: u64) -> Duration {
+        self.state_advance_offset_millis
+            .map(Duration::from_millis)
+            .unwrap_or_else(|| {
+                let slot_duration = Duration::from_secs(seconds_per_slot);
+                slot_duration - slot_duration / DEFAULT_STATE_ADVANCE_LOOKAHEAD_DENOMINATOR
+            })
+    }
 }
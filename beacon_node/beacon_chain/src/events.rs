@@ -1,11 +1,18 @@
-pub use eth2::types::{EventKind, SseBlock, SseFinalizedCheckpoint, SseHead};
+pub use eth2::types::{
+    EventKind, SseBlock, SseFinalizedCheckpoint, SseHead, SseMissedAttestation, SseMissedBlock,
+    SseSyncState,
+};
 use slog::{trace, Logger};
 use tokio::sync::broadcast;
 use tokio::sync::broadcast::{error::SendError, Receiver, Sender};
 use types::EthSpec;
 
 const DEFAULT_CHANNEL_CAPACITY: usize = 16;
+/// The slasher streams carry every attestation seen on gossip, so they need far more room than the
+/// other streams for a remote slasher not to fall behind and miss messages.
+const MIN_SLASHER_CHANNEL_CAPACITY: usize = 4_096;
 
+#[derive(Clone)]
 pub struct ServerSentEventHandler<T: EthSpec> {
     attestation_tx: Sender<EventKind<T>>,
     block_tx: Sender<EventKind<T>>,
@@ -17,6 +24,11 @@ pub struct ServerSentEventHandler<T: EthSpec> {
     payload_attributes_tx: Sender<EventKind<T>>,
     late_head: Sender<EventKind<T>>,
     block_reward_tx: Sender<EventKind<T>>,
+    sync_state_tx: Sender<EventKind<T>>,
+    missed_block_tx: Sender<EventKind<T>>,
+    missed_attestation_tx: Sender<EventKind<T>>,
+    slasher_attestation_tx: Sender<EventKind<T>>,
+    slasher_block_header_tx: Sender<EventKind<T>>,
     log: Logger,
 }
 
@@ -36,6 +48,13 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
         let (payload_attributes_tx, _) = broadcast::channel(capacity);
         let (late_head, _) = broadcast::channel(capacity);
         let (block_reward_tx, _) = broadcast::channel(capacity);
+        let (sync_state_tx, _) = broadcast::channel(capacity);
+        let (missed_block_tx, _) = broadcast::channel(capacity);
+        let (missed_attestation_tx, _) = broadcast::channel(capacity);
+        let (slasher_attestation_tx, _) =
+            broadcast::channel(capacity.max(MIN_SLASHER_CHANNEL_CAPACITY));
+        let (slasher_block_header_tx, _) =
+            broadcast::channel(capacity.max(MIN_SLASHER_CHANNEL_CAPACITY));
 
         Self {
             attestation_tx,
@@ -48,6 +67,11 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
             payload_attributes_tx,
             late_head,
             block_reward_tx,
+            sync_state_tx,
+            missed_block_tx,
+            missed_attestation_tx,
+            slasher_attestation_tx,
+            slasher_block_header_tx,
             log,
         }
     }
@@ -102,6 +126,26 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
                 .block_reward_tx
                 .send(kind)
                 .map(|count| log_count("block reward", count)),
+            EventKind::SyncState(_) => self
+                .sync_state_tx
+                .send(kind)
+                .map(|count| log_count("sync state", count)),
+            EventKind::MissedBlock(_) => self
+                .missed_block_tx
+                .send(kind)
+                .map(|count| log_count("missed block", count)),
+            EventKind::MissedAttestation(_) => self
+                .missed_attestation_tx
+                .send(kind)
+                .map(|count| log_count("missed attestation", count)),
+            EventKind::SlasherAttestation(_) => self
+                .slasher_attestation_tx
+                .send(kind)
+                .map(|count| log_count("slasher attestation", count)),
+            EventKind::SlasherBlockHeader(_) => self
+                .slasher_block_header_tx
+                .send(kind)
+                .map(|count| log_count("slasher block header", count)),
         };
         if let Err(SendError(event)) = result {
             trace!(self.log, "No receivers registered to listen for event"; "event" => ?event);
@@ -148,6 +192,26 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
         self.block_reward_tx.subscribe()
     }
 
+    pub fn subscribe_sync_state(&self) -> Receiver<EventKind<T>> {
+        self.sync_state_tx.subscribe()
+    }
+
+    pub fn subscribe_missed_block(&self) -> Receiver<EventKind<T>> {
+        self.missed_block_tx.subscribe()
+    }
+
+    pub fn subscribe_missed_attestation(&self) -> Receiver<EventKind<T>> {
+        self.missed_attestation_tx.subscribe()
+    }
+
+    pub fn subscribe_slasher_attestation(&self) -> Receiver<EventKind<T>> {
+        self.slasher_attestation_tx.subscribe()
+    }
+
+    pub fn subscribe_slasher_block_header(&self) -> Receiver<EventKind<T>> {
+        self.slasher_block_header_tx.subscribe()
+    }
+
     pub fn has_attestation_subscribers(&self) -> bool {
         self.attestation_tx.receiver_count() > 0
     }
@@ -187,4 +251,24 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
     pub fn has_block_reward_subscribers(&self) -> bool {
         self.block_reward_tx.receiver_count() > 0
     }
+
+    pub fn has_sync_state_subscribers(&self) -> bool {
+        self.sync_state_tx.receiver_count() > 0
+    }
+
+    pub fn has_missed_block_subscribers(&self) -> bool {
+        self.missed_block_tx.receiver_count() > 0
+    }
+
+    pub fn has_missed_attestation_subscribers(&self) -> bool {
+        self.missed_attestation_tx.receiver_count() > 0
+    }
+
+    pub fn has_slasher_attestation_subscribers(&self) -> bool {
+        self.slasher_attestation_tx.receiver_count() > 0
+    }
+
+    pub fn has_slasher_block_header_subscribers(&self) -> bool {
+        self.slasher_block_header_tx.receiver_count() > 0
+    }
 }
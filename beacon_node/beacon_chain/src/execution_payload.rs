@@ -165,7 +165,7 @@ async fn notify_new_payload<'a, T: BeaconChainTypes>(
                     "validation_error" => ?validation_error,
                     "latest_valid_hash" => ?latest_valid_hash,
                     "execution_block_hash" => ?execution_payload.block_hash(),
-                    "root" => ?block.tree_hash_root(),
+                    "block_root" => ?block.tree_hash_root(),
                     "graffiti" => block.body().graffiti().as_utf8_lossy(),
                     "proposer_index" => block.proposer_index(),
                     "slot" => block.slot(),
@@ -211,7 +211,7 @@ async fn notify_new_payload<'a, T: BeaconChainTypes>(
                     "Invalid execution payload block hash";
                     "validation_error" => ?validation_error,
                     "execution_block_hash" => ?execution_payload.block_hash(),
-                    "root" => ?block.tree_hash_root(),
+                    "block_root" => ?block.tree_hash_root(),
                     "graffiti" => block.body().graffiti().as_utf8_lossy(),
                     "proposer_index" => block.proposer_index(),
                     "slot" => block.slot(),
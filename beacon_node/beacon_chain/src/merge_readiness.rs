@@ -1,8 +1,10 @@
 //! Provides tools for checking if a node is ready for the Bellatrix upgrade and following merge
 //! transition.
 
-use crate::{BeaconChain, BeaconChainTypes};
+use crate::{BeaconChain, BeaconChainError as Error, BeaconChainTypes};
 use serde::{Deserialize, Serialize, Serializer};
+use slog::{debug, warn};
+use state_processing::per_block_processing::is_merge_transition_complete;
 use std::fmt;
 use std::fmt::Write;
 use types::*;
@@ -152,6 +154,56 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
     }
 
+    /// Returns `true` if the genesis state already contains a non-default execution payload
+    /// header, i.e. the chain was started post-merge and there is no transition to watch for.
+    pub fn is_merge_complete_at_genesis(&self) -> Result<bool, Error> {
+        let genesis_state = self
+            .store
+            .get_state(&self.genesis_state_root, Some(self.spec.genesis_slot))?
+            .ok_or(Error::MissingBeaconState(self.genesis_state_root))?;
+        Ok(is_merge_transition_complete(&genesis_state))
+    }
+
+    /// Returns `true` if the head state contains a non-default execution payload header.
+    ///
+    /// The head state is checked rather than the head block, since a chain with a post-merge
+    /// genesis has a default payload in its genesis block.
+    pub fn is_merge_complete_at_head(&self) -> bool {
+        is_merge_transition_complete(&self.canonical_head.cached_head().snapshot.beacon_state)
+    }
+
+    /// Spawns a routine that polls the `exchange_transition_configuration` endpoint of the
+    /// execution layer, unless the chain started post-merge and there is no transition to watch
+    /// for.
+    ///
+    /// Returns `true` if the routine was spawned.
+    pub fn spawn_transition_configuration_poll(&self) -> bool {
+        let execution_layer = match self.execution_layer.as_ref() {
+            Some(execution_layer) => execution_layer,
+            None => return false,
+        };
+
+        match self.is_merge_complete_at_genesis() {
+            Ok(true) => {
+                debug!(
+                    self.log,
+                    "Skipping transition configuration poll";
+                    "reason" => "merge complete at genesis"
+                );
+                return false;
+            }
+            Ok(false) => (),
+            Err(e) => warn!(
+                self.log,
+                "Unable to read genesis state";
+                "error" => ?e
+            ),
+        }
+
+        execution_layer.spawn_transition_configuration_poll(self.spec.clone());
+        true
+    }
+
     /// Attempts to connect to the EL and confirm that it is ready for the merge.
     pub async fn check_merge_readiness(&self) -> MergeReadiness {
         if let Some(el) = self.execution_layer.as_ref() {
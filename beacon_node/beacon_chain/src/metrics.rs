@@ -117,6 +117,11 @@ lazy_static! {
         "beacon_block_production_slot_process_seconds",
         "Time taken to advance the state to the block production slot"
     );
+    pub static ref STATE_ADVANCE_SKIPPED: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_state_advance_skipped_total",
+        "Count of pre-emptive head state advances skipped, by reason",
+        &["reason"]
+    );
     pub static ref BLOCK_PRODUCTION_UNAGGREGATED_TIMES: Result<Histogram> = try_create_histogram(
         "beacon_block_production_unaggregated_seconds",
         "Time taken to import the naive aggregation pool for block production"
@@ -209,6 +214,21 @@ lazy_static! {
         "beacon_attestation_processing_agg_pool_create_map",
         "Time spent for creating a map for a new slot"
     );
+    pub static ref ATTESTATION_PROCESSING_AGG_POOL_INSERTIONS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_attestation_processing_agg_pool_insertions_total",
+        "Count of attestations inserted into the agg pool, by outcome",
+        &["outcome"]
+    );
+    pub static ref ATTESTATION_PROCESSING_AGG_POOL_EVICTIONS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_attestation_processing_agg_pool_evictions_total",
+        "Count of aggregates evicted from the agg pool because it was full, by the limit reached",
+        &["reason"]
+    );
+    pub static ref ATTESTATION_PROCESSING_AGG_POOL_AGGREGATION_DEGREE: Result<Histogram> = try_create_histogram_with_buckets(
+        "beacon_attestation_processing_agg_pool_aggregation_degree",
+        "Number of signatures in each aggregate when it leaves the agg pool",
+        exponential_buckets(1.0, 2.0, 12)
+    );
     pub static ref ATTESTATION_PROCESSING_APPLY_TO_OP_POOL: Result<Histogram> = try_create_histogram(
         "beacon_attestation_processing_apply_to_op_pool",
         "Time spent applying an attestation to the block inclusion pool"
@@ -337,6 +357,15 @@ lazy_static! {
         "Full runtime of fork choice",
         linear_buckets(10e-3, 20e-3, 10)
     );
+    pub static ref FORK_CHOICE_LAST_RUN_MILLISECONDS: Result<IntGauge> = try_create_int_gauge(
+        "beacon_fork_choice_last_run_milliseconds",
+        "Duration of the most recent run of fork choice",
+    );
+    pub static ref FORK_CHOICE_ATTESTATION_DEADLINE_MISSES: Result<IntCounter> = try_create_int_counter(
+        "beacon_fork_choice_attestation_deadline_misses_total",
+        "Count of head recomputations which started before the attestation deadline and \
+         completed after it",
+    );
     pub static ref FORK_CHOICE_OVERRIDE_FCU_TIMES: Result<Histogram> = try_create_histogram_with_buckets(
         "beacon_fork_choice_override_fcu_seconds",
         "Time taken to compute the optional forkchoiceUpdated override",
@@ -364,6 +393,14 @@ lazy_static! {
         "beacon_fork_choice_set_head_lag_times",
         "Time taken between finding the head and setting the canonical head value"
     );
+    pub static ref OPTIMISTIC_BLOCKS_REPROCESSED_VALID: Result<IntCounter> = try_create_int_counter(
+        "beacon_optimistic_blocks_reprocessed_valid_total",
+        "Count of optimistically imported blocks later verified as valid by the EL"
+    );
+    pub static ref OPTIMISTIC_BLOCKS_REPROCESSED_INVALID: Result<IntCounter> = try_create_int_counter(
+        "beacon_optimistic_blocks_reprocessed_invalid_total",
+        "Count of optimistically imported blocks later found to be invalid by the EL"
+    );
     pub static ref BALANCES_CACHE_HITS: Result<IntCounter> =
         try_create_int_counter("beacon_balances_cache_hits_total", "Count of times balances cache fulfils request");
     pub static ref BALANCES_CACHE_MISSES: Result<IntCounter> =
@@ -596,6 +633,19 @@ lazy_static! {
             "The attestation inclusion distance calculated during per epoch processing",
             &["validator"]
         );
+    pub static ref VALIDATOR_MONITOR_PREV_EPOCH_BALANCE_DELTA_GWEI: Result<IntGaugeVec> =
+        try_create_int_gauge_vec(
+            "validator_monitor_prev_epoch_balance_delta_gwei",
+            "The change in the validator's balance over the previous epoch",
+            &["validator"]
+        );
+    pub static ref VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_SYNC_SIGNATURE_MISS: Result<IntCounterVec> =
+        try_create_int_counter_vec(
+            "validator_monitor_prev_epoch_on_chain_sync_signature_miss",
+            "Incremented for each slot of the previous epoch in which the validator was a sync \
+            committee member but its signature was not included in a block",
+            &["validator"]
+        );
     pub static ref VALIDATOR_MONITOR_PREV_EPOCH_ATTESTATIONS_TOTAL: Result<IntGaugeVec> =
         try_create_int_gauge_vec(
             "validator_monitor_prev_epoch_attestations_total",
@@ -788,6 +838,11 @@ lazy_static! {
         "The excess slots (beyond the minimum delay) between the attestation slot and the block slot.",
         &["src", "validator"]
     );
+    pub static ref VALIDATOR_MONITOR_MISSED_BLOCKS_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "validator_monitor_missed_blocks_total",
+        "Number of non-finalized blocks missed",
+        &["validator"]
+    );
     pub static ref VALIDATOR_MONITOR_BEACON_BLOCK_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
         "validator_monitor_beacon_block_total",
         "Number of beacon blocks seen",
@@ -846,6 +901,24 @@ lazy_static! {
         "Triggered when the duration between the start of the block's slot and the current time \
         will result in failed attestations.",
     );
+    pub static ref BEACON_BLOCK_HEAD_ATTESTATION_DEADLINE_HEADROOM_TIME: Result<Histogram> = try_create_histogram_with_buckets(
+        "beacon_block_head_attestation_deadline_headroom_time",
+        "Duration between the time the block was set as head and the attestation deadline. Not \
+        observed for blocks set as head after the deadline.",
+        // [0.01, 0.02, 0.05, 0.1, 0.2, 0.5, 1, 2, 5]
+        decimal_buckets(-2,0)
+    );
+    pub static ref BEACON_BLOCK_LAST_HEAD_PHASE_MILLISECONDS: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "beacon_block_last_head_phase_milliseconds",
+        "Timing breakdown of the most recent head block. `observed`, `imported` and `set_as_head` \
+        are the durations of each phase, `total` is the delay from the start of the slot and \
+        `attestation_deadline_headroom` is negative if the block was late for attestations.",
+        &["phase"]
+    );
+    pub static ref BEACON_BLOCK_LAST_HEAD_SLOT: Result<IntGauge> = try_create_int_gauge(
+        "beacon_block_last_head_slot",
+        "Slot of the block described by beacon_block_last_head_phase_milliseconds",
+    );
 
     /*
      * General block metrics
@@ -874,6 +947,10 @@ lazy_static! {
         "beacon_sync_committee_message_gossip_verification_seconds",
         "Full runtime of sync contribution gossip verification"
     );
+    pub static ref SYNC_MESSAGE_BATCH_GOSSIP_VERIFICATION_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_sync_committee_message_batch_gossip_verification_seconds",
+        "Full runtime of batch sync message gossip verification"
+    );
     pub static ref SYNC_MESSAGE_EQUIVOCATIONS: Result<IntCounter> = try_create_int_counter(
         "sync_message_equivocations_total",
         "Number of sync messages with the same validator index for different blocks"
@@ -926,6 +1003,21 @@ lazy_static! {
         "beacon_sync_contribution_processing_agg_pool_create_map",
         "Time spent for creating a map for a new slot"
     );
+    pub static ref SYNC_CONTRIBUTION_PROCESSING_AGG_POOL_INSERTIONS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_sync_contribution_processing_agg_pool_insertions_total",
+        "Count of sync contributions inserted into the agg pool, by outcome",
+        &["outcome"]
+    );
+    pub static ref SYNC_CONTRIBUTION_PROCESSING_AGG_POOL_EVICTIONS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_sync_contribution_processing_agg_pool_evictions_total",
+        "Count of contributions evicted from the agg pool because it was full, by the limit reached",
+        &["reason"]
+    );
+    pub static ref SYNC_CONTRIBUTION_PROCESSING_AGG_POOL_AGGREGATION_DEGREE: Result<Histogram> = try_create_histogram_with_buckets(
+        "beacon_sync_contribution_processing_agg_pool_aggregation_degree",
+        "Number of signatures in each contribution when it leaves the agg pool",
+        exponential_buckets(1.0, 2.0, 8)
+    );
     pub static ref SYNC_CONTRIBUTION_PROCESSING_APPLY_TO_OP_POOL: Result<Histogram> = try_create_histogram(
         "beacon_sync_contribution_processing_apply_to_op_pool",
         "Time spent applying a sync contribution to the block inclusion pool"
@@ -950,6 +1042,14 @@ lazy_static! {
         "beacon_sync_committee_message_processing_signature_seconds",
         "Time spent on the signature verification of sync message processing"
     );
+    pub static ref SYNC_MESSAGE_PROCESSING_BATCH_SIGNATURE_SETUP_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_sync_committee_message_processing_batch_signature_setup_seconds",
+        "Time spent on setting up for the signature verification of batch sync message processing"
+    );
+    pub static ref SYNC_MESSAGE_PROCESSING_BATCH_SIGNATURE_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_sync_committee_message_processing_batch_signature_seconds",
+        "Time spent on the signature verification of batch sync message processing"
+    );
 
     /*
      * Checkpoint sync & backfill
@@ -153,12 +153,23 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
     }
 
     pub fn run_reconstruction(db: Arc<HotColdDB<E, Hot, Cold>>, log: &Logger) {
-        if let Err(e) = db.reconstruct_historic_states() {
-            error!(
-                log,
-                "State reconstruction failed";
-                "error" => ?e,
-            );
+        match db.reconstruct_historic_states() {
+            Ok(()) => {}
+            Err(Error::StateReconstructionPaused { slot }) => {
+                warn!(
+                    log,
+                    "State reconstruction paused";
+                    "reason" => "low disk space",
+                    "slot" => slot,
+                );
+            }
+            Err(e) => {
+                error!(
+                    log,
+                    "State reconstruction failed";
+                    "error" => ?e,
+                );
+            }
         }
     }
 
@@ -1,7 +1,8 @@
 use crate::metrics;
+use lru::LruCache;
 use std::collections::HashMap;
+use std::hash::Hash;
 use tree_hash::TreeHash;
-use types::consts::altair::SYNC_COMMITTEE_SUBNET_COUNT;
 use types::slot_data::SlotData;
 use types::sync_committee_contribution::SyncContributionData;
 use types::{Attestation, AttestationData, EthSpec, Hash256, Slot, SyncCommitteeContribution};
@@ -21,6 +22,22 @@ const SLOTS_RETAINED: usize = 3;
 /// This is a DoS protection measure.
 const MAX_ATTESTATIONS_PER_SLOT: usize = 16_384;
 
+/// The maximum number of distinct `AttestationData` that will be stored across all slots.
+///
+/// This is lower than `MAX_ATTESTATIONS_PER_SLOT * SLOTS_RETAINED`, so that a period of
+/// non-finality with many distinct votes can't fill every slot to its limit.
+const MAX_ATTESTATIONS_TOTAL: usize = 32_768;
+
+/// Label values for the outcome of an insertion into the pool.
+const INSERT_NEW_ITEM: &str = "new_item";
+const INSERT_SIGNATURE_AGGREGATED: &str = "signature_aggregated";
+const INSERT_SIGNATURE_ALREADY_KNOWN: &str = "signature_already_known";
+const INSERT_ERROR: &str = "error";
+
+/// Label values for the reason an item was evicted from the pool.
+const EVICTED_SLOT_LIMIT: &str = "slot_limit";
+const EVICTED_TOTAL_LIMIT: &str = "total_limit";
+
 /// Returned upon successfully inserting an item into the pool.
 #[derive(Debug, PartialEq)]
 pub enum InsertOutcome {
@@ -46,9 +63,6 @@ pub enum Error {
     /// The given `aggregation_bits` field had more than one signature. The number of
     /// signatures found is included.
     MoreThanOneAggregationBitSet(usize),
-    /// We have reached the maximum number of unique items that can be stored in a
-    /// slot. This is a DoS protection function.
-    ReachedMaxItemsPerSlot(usize),
     /// The given `aggregation_bits` field had a different length to the one currently
     /// stored. This indicates a fairly serious error somewhere in the code that called this
     /// function.
@@ -59,9 +73,12 @@ pub enum Error {
 
 /// Implemented for items in the `NaiveAggregationPool`. Requires that items implement `SlotData`,
 /// which means they have an associated slot. This handles aggregation of items that are inserted.
+///
+/// Each map holds at most `max_items_per_slot()` items. Once full, inserting an item with new
+/// `Data` evicts the least recently inserted or aggregated item.
 pub trait AggregateMap {
     /// `Key` should be a hash of `Data`.
-    type Key;
+    type Key: Hash + Eq;
 
     /// The item stored in the map
     type Value: Clone + SlotData;
@@ -69,8 +86,8 @@ pub trait AggregateMap {
     /// The unique fields of `Value`, hashed to create `Key`.
     type Data: SlotData;
 
-    /// Create a new `AggregateMap` with capacity `initial_capacity`.
-    fn new(initial_capacity: usize) -> Self;
+    /// Create a new, empty `AggregateMap`.
+    fn new() -> Self;
 
     /// Insert a `Value` into `Self`, returning a result.
     fn insert(&mut self, value: &Self::Value) -> Result<InsertOutcome, Error>;
@@ -78,15 +95,24 @@ pub trait AggregateMap {
     /// Get a `Value` from `Self` based on `Data`.
     fn get(&self, data: &Self::Data) -> Option<Self::Value>;
 
-    /// Get a reference to the inner `HashMap`.
-    fn get_map(&self) -> &HashMap<Self::Key, Self::Value>;
+    /// Get a reference to the inner `LruCache`.
+    fn get_map(&self) -> &LruCache<Self::Key, Self::Value>;
 
     /// Get a `Value` from `Self` based on `Key`, which is a hash of `Data`.
     fn get_by_root(&self, root: &Self::Key) -> Option<&Self::Value>;
 
+    /// Remove and return the least recently used `Value`, if any.
+    fn pop_lru(&mut self) -> Option<Self::Value>;
+
     /// The number of items store in `Self`.
     fn len(&self) -> usize;
 
+    /// The maximum number of items stored in a single slot.
+    fn max_items_per_slot() -> usize;
+
+    /// The maximum number of items stored across all slots.
+    fn max_items_total() -> usize;
+
     /// Start a timer observing inserts.
     fn start_insert_timer() -> Option<metrics::HistogramTimer>;
 
@@ -96,14 +122,20 @@ pub trait AggregateMap {
     /// Start a timer observing the time it takes to prune the pool.
     fn start_prune_timer() -> Option<metrics::HistogramTimer>;
 
-    /// The default capacity of `Self`.
-    fn default_capacity() -> usize;
+    /// Count an insertion, labelled by its outcome.
+    fn observe_insert(outcome: &str);
+
+    /// Count an eviction, labelled by its reason.
+    fn observe_eviction(reason: &str);
+
+    /// Observe the number of signatures aggregated into a `Value` as it leaves the pool.
+    fn observe_aggregation_degree(value: &Self::Value);
 }
 
 /// A collection of `Attestation` objects, keyed by their `attestation.data`. Enforces that all
 /// `attestation` are from the same slot.
 pub struct AggregatedAttestationMap<E: EthSpec> {
-    map: HashMap<AttestationDataRoot, Attestation<E>>,
+    map: LruCache<AttestationDataRoot, Attestation<E>>,
 }
 
 impl<E: EthSpec> AggregateMap for AggregatedAttestationMap<E> {
@@ -111,10 +143,10 @@ impl<E: EthSpec> AggregateMap for AggregatedAttestationMap<E> {
     type Value = Attestation<E>;
     type Data = AttestationData;
 
-    /// Create an empty collection with the given `initial_capacity`.
-    fn new(initial_capacity: usize) -> Self {
+    /// Create an empty collection.
+    fn new() -> Self {
         Self {
-            map: HashMap::with_capacity(initial_capacity),
+            map: LruCache::unbounded(),
         }
     }
 
@@ -157,11 +189,14 @@ impl<E: EthSpec> AggregateMap for AggregatedAttestationMap<E> {
                 Ok(InsertOutcome::SignatureAggregated { committee_index })
             }
         } else {
-            if self.map.len() >= MAX_ATTESTATIONS_PER_SLOT {
-                return Err(Error::ReachedMaxItemsPerSlot(MAX_ATTESTATIONS_PER_SLOT));
+            if self.map.len() >= Self::max_items_per_slot() {
+                if let Some(evicted) = self.pop_lru() {
+                    Self::observe_eviction(EVICTED_SLOT_LIMIT);
+                    Self::observe_aggregation_degree(&evicted);
+                }
             }
 
-            self.map.insert(attestation_data_root, a.clone());
+            self.map.put(attestation_data_root, a.clone());
             Ok(InsertOutcome::NewItemInserted { committee_index })
         }
     }
@@ -170,22 +205,34 @@ impl<E: EthSpec> AggregateMap for AggregatedAttestationMap<E> {
     ///
     /// The given `a.data.slot` must match the slot that `self` was initialized with.
     fn get(&self, data: &Self::Data) -> Option<Self::Value> {
-        self.map.get(&data.tree_hash_root()).cloned()
+        self.map.peek(&data.tree_hash_root()).cloned()
     }
 
-    fn get_map(&self) -> &HashMap<Self::Key, Self::Value> {
+    fn get_map(&self) -> &LruCache<Self::Key, Self::Value> {
         &self.map
     }
 
     /// Returns an aggregated `Attestation` with the given `root`, if any.
     fn get_by_root(&self, root: &Self::Key) -> Option<&Self::Value> {
-        self.map.get(root)
+        self.map.peek(root)
+    }
+
+    fn pop_lru(&mut self) -> Option<Self::Value> {
+        self.map.pop_lru().map(|(_root, attestation)| attestation)
     }
 
     fn len(&self) -> usize {
         self.map.len()
     }
 
+    fn max_items_per_slot() -> usize {
+        MAX_ATTESTATIONS_PER_SLOT
+    }
+
+    fn max_items_total() -> usize {
+        MAX_ATTESTATIONS_TOTAL
+    }
+
     fn start_insert_timer() -> Option<metrics::HistogramTimer> {
         metrics::start_timer(&metrics::ATTESTATION_PROCESSING_AGG_POOL_INSERT)
     }
@@ -198,18 +245,32 @@ impl<E: EthSpec> AggregateMap for AggregatedAttestationMap<E> {
         metrics::start_timer(&metrics::ATTESTATION_PROCESSING_AGG_POOL_PRUNE)
     }
 
-    /// Use the `TARGET_COMMITTEE_SIZE`.
-    ///
-    /// Note: hard-coded until `TARGET_COMMITTEE_SIZE` is available via `EthSpec`.
-    fn default_capacity() -> usize {
-        128
+    fn observe_insert(outcome: &str) {
+        metrics::inc_counter_vec(
+            &metrics::ATTESTATION_PROCESSING_AGG_POOL_INSERTIONS,
+            &[outcome],
+        );
+    }
+
+    fn observe_eviction(reason: &str) {
+        metrics::inc_counter_vec(
+            &metrics::ATTESTATION_PROCESSING_AGG_POOL_EVICTIONS,
+            &[reason],
+        );
+    }
+
+    fn observe_aggregation_degree(attestation: &Self::Value) {
+        metrics::observe(
+            &metrics::ATTESTATION_PROCESSING_AGG_POOL_AGGREGATION_DEGREE,
+            attestation.aggregation_bits.num_set_bits() as f64,
+        );
     }
 }
 
 /// A collection of `SyncCommitteeContribution`, keyed by their `SyncContributionData`. Enforces that all
 /// contributions are from the same slot.
 pub struct SyncContributionAggregateMap<E: EthSpec> {
-    map: HashMap<SyncDataRoot, SyncCommitteeContribution<E>>,
+    map: LruCache<SyncDataRoot, SyncCommitteeContribution<E>>,
 }
 
 impl<E: EthSpec> AggregateMap for SyncContributionAggregateMap<E> {
@@ -217,10 +278,10 @@ impl<E: EthSpec> AggregateMap for SyncContributionAggregateMap<E> {
     type Value = SyncCommitteeContribution<E>;
     type Data = SyncContributionData;
 
-    /// Create an empty collection with the given `initial_capacity`.
-    fn new(initial_capacity: usize) -> Self {
+    /// Create an empty collection.
+    fn new() -> Self {
         Self {
-            map: HashMap::with_capacity(initial_capacity),
+            map: LruCache::unbounded(),
         }
     }
 
@@ -268,11 +329,14 @@ impl<E: EthSpec> AggregateMap for SyncContributionAggregateMap<E> {
                 Ok(InsertOutcome::SignatureAggregated { committee_index })
             }
         } else {
-            if self.map.len() >= E::sync_committee_size() {
-                return Err(Error::ReachedMaxItemsPerSlot(E::sync_committee_size()));
+            if self.map.len() >= Self::max_items_per_slot() {
+                if let Some(evicted) = self.pop_lru() {
+                    Self::observe_eviction(EVICTED_SLOT_LIMIT);
+                    Self::observe_aggregation_degree(&evicted);
+                }
             }
 
-            self.map.insert(sync_data_root, contribution.clone());
+            self.map.put(sync_data_root, contribution.clone());
             Ok(InsertOutcome::NewItemInserted { committee_index })
         }
     }
@@ -281,22 +345,35 @@ impl<E: EthSpec> AggregateMap for SyncContributionAggregateMap<E> {
     ///
     /// The given `data.slot` must match the slot that `self` was initialized with.
     fn get(&self, data: &SyncContributionData) -> Option<SyncCommitteeContribution<E>> {
-        self.map.get(&data.tree_hash_root()).cloned()
+        self.map.peek(&data.tree_hash_root()).cloned()
     }
 
-    fn get_map(&self) -> &HashMap<SyncDataRoot, SyncCommitteeContribution<E>> {
+    fn get_map(&self) -> &LruCache<SyncDataRoot, SyncCommitteeContribution<E>> {
         &self.map
     }
 
     /// Returns an aggregated `SyncCommitteeContribution` with the given `root`, if any.
     fn get_by_root(&self, root: &SyncDataRoot) -> Option<&SyncCommitteeContribution<E>> {
-        self.map.get(root)
+        self.map.peek(root)
+    }
+
+    fn pop_lru(&mut self) -> Option<SyncCommitteeContribution<E>> {
+        self.map.pop_lru().map(|(_root, contribution)| contribution)
     }
 
     fn len(&self) -> usize {
         self.map.len()
     }
 
+    /// One contribution per member of the sync committee.
+    fn max_items_per_slot() -> usize {
+        E::sync_committee_size()
+    }
+
+    fn max_items_total() -> usize {
+        E::sync_committee_size() * 2
+    }
+
     fn start_insert_timer() -> Option<metrics::HistogramTimer> {
         metrics::start_timer(&metrics::SYNC_CONTRIBUTION_PROCESSING_AGG_POOL_INSERT)
     }
@@ -309,9 +386,25 @@ impl<E: EthSpec> AggregateMap for SyncContributionAggregateMap<E> {
         metrics::start_timer(&metrics::SYNC_CONTRIBUTION_PROCESSING_AGG_POOL_PRUNE)
     }
 
-    /// Default to `SYNC_COMMITTEE_SUBNET_COUNT`.
-    fn default_capacity() -> usize {
-        SYNC_COMMITTEE_SUBNET_COUNT as usize
+    fn observe_insert(outcome: &str) {
+        metrics::inc_counter_vec(
+            &metrics::SYNC_CONTRIBUTION_PROCESSING_AGG_POOL_INSERTIONS,
+            &[outcome],
+        );
+    }
+
+    fn observe_eviction(reason: &str) {
+        metrics::inc_counter_vec(
+            &metrics::SYNC_CONTRIBUTION_PROCESSING_AGG_POOL_EVICTIONS,
+            &[reason],
+        );
+    }
+
+    fn observe_aggregation_degree(contribution: &Self::Value) {
+        metrics::observe(
+            &metrics::SYNC_CONTRIBUTION_PROCESSING_AGG_POOL_AGGREGATION_DEGREE,
+            contribution.aggregation_bits.num_set_bits() as f64,
+        );
     }
 }
 
@@ -336,6 +429,9 @@ impl<E: EthSpec> AggregateMap for SyncContributionAggregateMap<E> {
 /// `current_slot - SLOTS_RETAINED` will be removed and any future item with a slot lower
 /// than that will also be refused. Pruning is done automatically based upon the items it
 /// receives and it can be triggered manually.
+///
+/// The number of items in each slot and in the pool as a whole is bounded. When a bound is
+/// reached, the least recently used items are evicted, starting from the oldest slot.
 pub struct NaiveAggregationPool<T: AggregateMap> {
     lowest_permissible_slot: Slot,
     maps: HashMap<Slot, T>,
@@ -365,6 +461,7 @@ impl<T: AggregateMap> NaiveAggregationPool<T> {
 
         // Reject any items that are too old.
         if slot < lowest_permissible_slot {
+            T::observe_insert(INSERT_ERROR);
             return Err(Error::SlotTooLow {
                 slot,
                 lowest_permissible_slot,
@@ -375,27 +472,22 @@ impl<T: AggregateMap> NaiveAggregationPool<T> {
             map.insert(item)
         } else {
             let _timer = T::start_create_map_timer();
-            // To avoid re-allocations, try and determine a rough initial capacity for the new item
-            // by obtaining the mean size of all items in earlier epoch.
-            let (count, sum) = self
-                .maps
-                .iter()
-                // Only include epochs that are less than the given slot in the average. This should
-                // generally avoid including recent epochs that are still "filling up".
-                .filter(|(map_slot, _item)| **map_slot < slot)
-                .map(|(_slot, map)| map.len())
-                .fold((0, 0), |(count, sum), len| (count + 1, sum + len));
-
-            let initial_capacity = sum.checked_div(count).unwrap_or_else(T::default_capacity);
-
-            let mut aggregate_map = T::new(initial_capacity);
+            let mut aggregate_map = T::new();
             let outcome = aggregate_map.insert(item);
             self.maps.insert(slot, aggregate_map);
 
             outcome
         };
 
+        T::observe_insert(match &outcome {
+            Ok(InsertOutcome::NewItemInserted { .. }) => INSERT_NEW_ITEM,
+            Ok(InsertOutcome::SignatureAggregated { .. }) => INSERT_SIGNATURE_AGGREGATED,
+            Ok(InsertOutcome::SignatureAlreadyKnown { .. }) => INSERT_SIGNATURE_ALREADY_KNOWN,
+            Err(_) => INSERT_ERROR,
+        });
+
         self.prune(slot);
+        self.enforce_total_limit();
 
         outcome
     }
@@ -421,7 +513,9 @@ impl<T: AggregateMap> NaiveAggregationPool<T> {
 
     /// Iterate all items in all slots of `self`.
     pub fn iter(&self) -> impl Iterator<Item = &T::Value> {
-        self.maps.values().flat_map(|map| map.get_map().values())
+        self.maps
+            .values()
+            .flat_map(|map| map.get_map().iter().map(|(_key, value)| value))
     }
 
     /// Removes any items with a slot lower than `current_slot` and bars any future
@@ -442,22 +536,57 @@ impl<T: AggregateMap> NaiveAggregationPool<T> {
         self.lowest_permissible_slot = lowest_permissible_slot;
 
         // Remove any maps that are definitely expired.
-        self.maps
-            .retain(|slot, _map| *slot >= lowest_permissible_slot);
+        let mut slots = self.maps.keys().copied().collect::<Vec<_>>();
+        // Sort is generally pretty slow, however `SLOTS_RETAINED` is quite low so it should be
+        // negligible.
+        slots.sort_unstable();
 
         // If we have too many maps, remove the lowest amount to ensure we only have
         // `SLOTS_RETAINED` left.
-        if self.maps.len() > SLOTS_RETAINED {
-            let mut slots = self.maps.keys().copied().collect::<Vec<_>>();
-            // Sort is generally pretty slow, however `SLOTS_RETAINED` is quite low so it should be
-            // negligible.
-            slots.sort_unstable();
-            slots
-                .into_iter()
-                .take(self.maps.len().saturating_sub(SLOTS_RETAINED))
-                .for_each(|slot| {
-                    self.maps.remove(&slot);
-                })
+        let num_excess = self.maps.len().saturating_sub(SLOTS_RETAINED);
+        for (i, slot) in slots.into_iter().enumerate() {
+            if i < num_excess || slot < lowest_permissible_slot {
+                self.remove_map(slot);
+            }
+        }
+    }
+
+    /// Evicts the least recently used items from the oldest slots until the total number of
+    /// items is within `T::max_items_total()`.
+    fn enforce_total_limit(&mut self) {
+        let mut num_items = self.num_items();
+        if num_items <= T::max_items_total() {
+            return;
+        }
+
+        let mut slots = self.maps.keys().copied().collect::<Vec<_>>();
+        slots.sort_unstable();
+
+        for slot in slots {
+            if let Some(map) = self.maps.get_mut(&slot) {
+                while num_items > T::max_items_total() {
+                    match map.pop_lru() {
+                        Some(evicted) => {
+                            T::observe_eviction(EVICTED_TOTAL_LIMIT);
+                            T::observe_aggregation_degree(&evicted);
+                            num_items -= 1;
+                        }
+                        None => break,
+                    }
+                }
+            }
+            if num_items <= T::max_items_total() {
+                break;
+            }
+        }
+    }
+
+    /// Removes the map for `slot`, observing the aggregation degree of its items.
+    fn remove_map(&mut self, slot: Slot) {
+        if let Some(map) = self.maps.remove(&slot) {
+            for (_key, value) in map.get_map().iter() {
+                T::observe_aggregation_degree(value);
+            }
         }
     }
 }
@@ -581,7 +710,8 @@ mod tests {
             $block_root_comparator: ident,
             $key_getter: ident,
             $map_type: ident,
-            $item_limit: expr
+            $item_limit: expr,
+            $total_limit: expr
         ) => {
             #[cfg(test)]
             mod $mod_name {
@@ -741,24 +871,87 @@ mod tests {
                     let mut pool: NaiveAggregationPool<$map_type<E>> =
                         NaiveAggregationPool::default();
 
+                    let mut first = base.clone();
+                    $block_root_mutator(&mut first, Hash256::from_low_u64_be(0));
+
                     for i in 0..=$item_limit {
                         let mut a = base.clone();
                         $block_root_mutator(&mut a, Hash256::from_low_u64_be(i as u64));
 
-                        if i < $item_limit {
+                        assert_eq!(
+                            pool.insert(&a),
+                            Ok(InsertOutcome::NewItemInserted { committee_index: 0 }),
+                            "should accept item"
+                        );
+
+                        // Aggregating into the first item keeps it from being evicted.
+                        if i == $item_limit - 1 {
+                            let mut first_aggregate = first.clone();
+                            $unset_method_name(&mut first_aggregate, 0);
+                            $sign_method_name(&mut first_aggregate, 1, Hash256::random());
                             assert_eq!(
-                                pool.insert(&a),
-                                Ok(InsertOutcome::NewItemInserted { committee_index: 0 }),
-                                "should accept item below limit"
+                                pool.insert(&first_aggregate),
+                                Ok(InsertOutcome::SignatureAggregated { committee_index: 1 }),
+                                "should aggregate into the first item"
                             );
-                        } else {
+                        }
+                    }
+
+                    assert_eq!(pool.num_items(), $item_limit, "should not exceed limit");
+                    assert!(
+                        pool.get(&$key_getter(&first)).is_some(),
+                        "recently used item should be retained"
+                    );
+
+                    let mut second = base.clone();
+                    $block_root_mutator(&mut second, Hash256::from_low_u64_be(1));
+                    assert!(
+                        pool.get(&$key_getter(&second)).is_none(),
+                        "least recently used item should be evicted"
+                    );
+                }
+
+                #[test]
+                fn max_items_total() {
+                    let mut base = $get_method_name(Slot::new(0));
+                    $sign_method_name(&mut base, 0, Hash256::random());
+
+                    let mut pool: NaiveAggregationPool<$map_type<E>> =
+                        NaiveAggregationPool::default();
+
+                    let mut items = vec![];
+                    for slot in 0..SLOTS_RETAINED {
+                        let num_items = std::cmp::min($item_limit, $total_limit - items.len());
+                        for i in 0..num_items {
+                            let mut a = base.clone();
+                            $slot_mutator(&mut a, Slot::from(slot));
+                            $block_root_mutator(&mut a, Hash256::from_low_u64_be(i as u64));
                             assert_eq!(
                                 pool.insert(&a),
-                                Err(Error::ReachedMaxItemsPerSlot($item_limit)),
-                                "should not accept item above limit"
+                                Ok(InsertOutcome::NewItemInserted { committee_index: 0 }),
+                                "should accept item"
                             );
+                            items.push(a);
                         }
                     }
+                    assert_eq!(pool.num_items(), $total_limit);
+
+                    let mut a = base.clone();
+                    $slot_mutator(&mut a, Slot::from(SLOTS_RETAINED - 1));
+                    $block_root_mutator(&mut a, Hash256::repeat_byte(0xff));
+                    assert_eq!(
+                        pool.insert(&a),
+                        Ok(InsertOutcome::NewItemInserted { committee_index: 0 }),
+                        "should accept item above the total limit"
+                    );
+
+                    assert_eq!(pool.num_items(), $total_limit, "should not exceed limit");
+                    assert!(
+                        pool.get(&$key_getter(&items[0])).is_none(),
+                        "oldest item should be evicted"
+                    );
+                    assert!(pool.get(&$key_getter(&items[1])).is_some());
+                    assert!(pool.get(&$key_getter(&a)).is_some());
                 }
             }
         };
@@ -774,7 +967,8 @@ mod tests {
         attestation_block_root_comparator,
         key_from_attestation,
         AggregatedAttestationMap,
-        MAX_ATTESTATIONS_PER_SLOT
+        MAX_ATTESTATIONS_PER_SLOT,
+        MAX_ATTESTATIONS_TOTAL
     }
 
     test_suite! {
@@ -787,6 +981,7 @@ mod tests {
         sync_contribution_block_root_comparator,
         key_from_sync_contribution,
         SyncContributionAggregateMap,
-        E::sync_committee_size()
+        E::sync_committee_size(),
+        E::sync_committee_size() * 2
     }
 }
@@ -37,6 +37,13 @@ use types::{Epoch, EthSpec, Hash256, Slot, Unsigned};
 /// from at least one slot in the previous epoch.
 pub const MAX_CACHED_EPOCHS: u64 = 3;
 
+/// The default initial capacity of an `EpochBitfield`, equal to the number of genesis validators.
+pub const DEFAULT_ATTESTERS_CAPACITY: usize = 16_384;
+
+/// The default initial capacity of an `EpochHashSet`, equal to the target number of aggregators
+/// per committee (16) multiplied by the expected max committee count (64).
+pub const DEFAULT_AGGREGATORS_CAPACITY: usize = 16 * 64;
+
 pub type ObservedAttesters<E> = AutoPruningEpochContainer<EpochBitfield, E>;
 pub type ObservedSyncContributors<E> =
     AutoPruningSlotContainer<SlotSubcommitteeIndex, Hash256, SyncContributorSlotHashSet<E>, E>;
@@ -97,7 +104,7 @@ impl Item<()> for EpochBitfield {
 
     /// Uses a default size that equals the number of genesis validators.
     fn default_capacity() -> usize {
-        16_384
+        DEFAULT_ATTESTERS_CAPACITY
     }
 
     fn len(&self) -> usize {
@@ -153,7 +160,7 @@ impl Item<()> for EpochHashSet {
     /// Defaults to the target number of aggregators per committee (16) multiplied by the expected
     /// max committee count (64).
     fn default_capacity() -> usize {
-        16 * 64
+        DEFAULT_AGGREGATORS_CAPACITY
     }
 
     fn len(&self) -> usize {
@@ -267,20 +274,35 @@ impl Item<()> for SyncAggregatorSlotHashSet {
 pub struct AutoPruningEpochContainer<T, E: EthSpec> {
     lowest_permissible_epoch: Epoch,
     items: HashMap<Epoch, T>,
+    max_cached_epochs: u64,
+    initial_capacity: usize,
     _phantom: PhantomData<E>,
 }
 
-impl<T, E: EthSpec> Default for AutoPruningEpochContainer<T, E> {
+impl<T: Item<()>, E: EthSpec> Default for AutoPruningEpochContainer<T, E> {
     fn default() -> Self {
+        Self::new(MAX_CACHED_EPOCHS, T::default_capacity())
+    }
+}
+
+impl<T: Item<()>, E: EthSpec> AutoPruningEpochContainer<T, E> {
+    /// Instantiate a container which retains `max_cached_epochs` epochs of observations.
+    ///
+    /// `initial_capacity` is the size allocated for an epoch when there are no earlier epochs
+    /// from which to estimate it. It is only an allocation hint, epochs grow beyond it as required.
+    ///
+    /// Retaining fewer than `MAX_CACHED_EPOCHS` epochs will cause some valid gossip to be
+    /// rejected with `Error::EpochTooLow`.
+    pub fn new(max_cached_epochs: u64, initial_capacity: usize) -> Self {
         Self {
             lowest_permissible_epoch: Epoch::new(0),
             items: HashMap::new(),
+            max_cached_epochs,
+            initial_capacity,
             _phantom: PhantomData,
         }
     }
-}
 
-impl<T: Item<()>, E: EthSpec> AutoPruningEpochContainer<T, E> {
     /// Observe that `validator_index` has produced attestation `a`. Returns `Ok(true)` if `a` has
     /// previously been observed for `validator_index`.
     ///
@@ -311,7 +333,7 @@ impl<T: Item<()>, E: EthSpec> AutoPruningEpochContainer<T, E> {
                 .map(|(_epoch, item)| item.len())
                 .fold((0, 0), |(count, sum), len| (count + 1, sum + len));
 
-            let initial_capacity = sum.checked_div(count).unwrap_or_else(T::default_capacity);
+            let initial_capacity = sum.checked_div(count).unwrap_or(self.initial_capacity);
 
             let mut item = T::with_capacity(initial_capacity);
             item.insert(validator_index, ());
@@ -367,7 +389,7 @@ impl<T: Item<()>, E: EthSpec> AutoPruningEpochContainer<T, E> {
 
     /// The maximum number of epochs stored in `self`.
     fn max_capacity(&self) -> u64 {
-        MAX_CACHED_EPOCHS
+        self.max_cached_epochs
     }
 
     /// Updates `self` with the current epoch, removing all attestations that become expired
@@ -887,6 +909,30 @@ mod tests {
                         );
                     }
                 }
+
+                #[test]
+                fn custom_max_cached_epochs() {
+                    let max_cap = MAX_CACHED_EPOCHS + 2;
+                    let mut store = $type::new(max_cap, 1);
+
+                    for i in 0..max_cap * 2 {
+                        single_period_test(&mut store, Epoch::new(i));
+                        assert!(
+                            store.items.len() <= max_cap as usize,
+                            "store size should not exceed max"
+                        );
+                    }
+
+                    assert_eq!(store.items.len(), max_cap as usize);
+                    assert_eq!(store.get_lowest_permissible(), Epoch::new(max_cap));
+                    assert_eq!(
+                        store.validator_has_been_observed(Epoch::new(max_cap - 1), 0),
+                        Err(Error::EpochTooLow {
+                            epoch: Epoch::new(max_cap - 1),
+                            lowest_permissible_epoch: Epoch::new(max_cap),
+                        })
+                    );
+                }
             }
         };
     }
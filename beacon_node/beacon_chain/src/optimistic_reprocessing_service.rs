@@ -0,0 +1,259 @@
+//! Re-sends optimistically imported blocks to the execution layer once it reports as synced.
+//!
+//! Blocks are imported optimistically when the EL responds `SYNCING` or `ACCEPTED` to
+//! `newPayload`. Blocks on the canonical chain are eventually verified via `forkchoiceUpdated`,
+//! however blocks on other branches (or ancestors of a head which hasn't changed since the EL
+//! finished syncing) would otherwise remain optimistic until a descendant is imported. This
+//! service drains those blocks by calling `newPayload` again and applying the result to fork
+//! choice.
+
+use crate::{metrics, BeaconChain, BeaconChainError, BeaconChainTypes};
+use execution_layer::PayloadStatus;
+use fork_choice::InvalidationOperation;
+use slog::{debug, error, info, warn};
+use slot_clock::SlotClock;
+use std::sync::Arc;
+use task_executor::TaskExecutor;
+use tokio::time::sleep;
+use types::{ExecutionBlockHash, Hash256};
+
+/// The maximum number of blocks which will be sent to the EL during a single run of the service.
+///
+/// This avoids hammering the EL when there is a long backlog of optimistic blocks, such as after
+/// an optimistic sync.
+const MAX_BLOCKS_PER_RUN: usize = 64;
+
+/// Run the service at `slot_duration / RUN_DELAY_FACTOR` after the start of each slot, well clear
+/// of block and attestation processing at the start of the slot.
+const RUN_DELAY_FACTOR: u32 = 2;
+
+/// Spawns a routine which re-processes optimistically imported blocks once the EL is synced.
+///
+/// This routine will run once per slot, at `slot_duration / RUN_DELAY_FACTOR` after the start of
+/// each slot.
+///
+/// The service will not be started if there is no `execution_layer` on the `chain`.
+pub fn start_optimistic_reprocessing_service<T: BeaconChainTypes>(
+    executor: TaskExecutor,
+    chain: Arc<BeaconChain<T>>,
+) {
+    // Avoid spawning the service if there's no EL, it'll just error anyway.
+    if chain.execution_layer.is_some() {
+        executor.spawn(
+            async move { optimistic_reprocessing_service(chain).await },
+            "optimistic_reprocessing_service",
+        );
+    }
+}
+
+/// Loop indefinitely, calling `reprocess_optimistic_blocks` at an interval.
+async fn optimistic_reprocessing_service<T: BeaconChainTypes>(chain: Arc<BeaconChain<T>>) {
+    let slot_duration = chain.slot_clock.slot_duration();
+
+    loop {
+        match chain.slot_clock.duration_to_next_slot() {
+            Some(duration) => {
+                sleep(duration + slot_duration / RUN_DELAY_FACTOR).await;
+
+                debug!(
+                    chain.log,
+                    "Optimistic reprocessing routine firing";
+                );
+
+                if let Err(e) = reprocess_optimistic_blocks(&chain).await {
+                    warn!(
+                        chain.log,
+                        "Optimistic reprocessing routine failed";
+                        "error" => ?e
+                    );
+                }
+            }
+            None => {
+                error!(chain.log, "Failed to read slot clock");
+                // If we can't read the slot clock, just wait another slot.
+                sleep(slot_duration).await;
+            }
+        }
+    }
+}
+
+/// Send strictly optimistic blocks from fork choice to `newPayload`, marking them as valid or
+/// invalid according to the response.
+///
+/// Nothing is done unless the EL reports itself as synced. Blocks are processed in the order they
+/// were inserted into fork choice (i.e. parents before children). A block for which the EL returns
+/// `SYNCING` or `ACCEPTED` is skipped and retried on the next run, so that a single block the EL
+/// can't verify (e.g. on a side-fork) doesn't hold back the others. Processing only stops early if
+/// the EL fails to respond at all.
+///
+/// Returns the number of blocks whose execution status was updated.
+pub async fn reprocess_optimistic_blocks<T: BeaconChainTypes>(
+    chain: &Arc<BeaconChain<T>>,
+) -> Result<usize, BeaconChainError> {
+    let execution_layer = chain
+        .execution_layer
+        .as_ref()
+        .ok_or(BeaconChainError::ExecutionLayerMissing)?;
+
+    if !execution_layer.is_synced().await {
+        return Ok(0);
+    }
+
+    let candidates = optimistic_block_roots(chain);
+    if candidates.is_empty() {
+        return Ok(0);
+    }
+
+    let mut num_updated = 0;
+    let mut num_valid = 0;
+
+    for block_root in candidates {
+        // A previous iteration may have updated the status of this block, either by invalidating
+        // one of its ancestors or by it being pruned from fork choice.
+        if !is_strictly_optimistic(chain, &block_root) {
+            continue;
+        }
+
+        let block = match chain.get_block(&block_root).await {
+            Ok(Some(block)) => block,
+            Ok(None) => continue,
+            Err(e) => {
+                debug!(
+                    chain.log,
+                    "Unable to load optimistic block";
+                    "block_root" => ?block_root,
+                    "error" => ?e
+                );
+                continue;
+            }
+        };
+        let execution_payload = match block.message().execution_payload() {
+            Ok(payload) => payload,
+            // Pre-merge blocks are never optimistic.
+            Err(_) => continue,
+        };
+
+        let status = match execution_layer
+            .notify_new_payload(&execution_payload.into())
+            .await
+        {
+            Ok(status) => status,
+            Err(e) => {
+                debug!(
+                    chain.log,
+                    "Failed to reprocess optimistic block";
+                    "block_root" => ?block_root,
+                    "error" => ?e
+                );
+                break;
+            }
+        };
+
+        match status {
+            PayloadStatus::Valid => {
+                chain
+                    .canonical_head
+                    .fork_choice_write_lock()
+                    .on_valid_execution_payload(block_root)
+                    .map_err(BeaconChainError::ForkChoiceError)?;
+                metrics::inc_counter(&metrics::OPTIMISTIC_BLOCKS_REPROCESSED_VALID);
+                num_updated += 1;
+                num_valid += 1;
+            }
+            PayloadStatus::Invalid {
+                latest_valid_hash, ..
+            } => {
+                // Unlike during block import, the block is already in fork choice so it must
+                // always be invalidated along with any ancestors after `latest_valid_hash`.
+                let op = match latest_valid_hash.filter(|hash| *hash != ExecutionBlockHash::zero())
+                {
+                    Some(latest_valid_ancestor) => InvalidationOperation::InvalidateMany {
+                        head_block_root: block_root,
+                        always_invalidate_head: true,
+                        latest_valid_ancestor,
+                    },
+                    None => InvalidationOperation::InvalidateOne { block_root },
+                };
+                warn!(
+                    chain.log,
+                    "Optimistic block found to be invalid";
+                    "block_root" => ?block_root,
+                    "slot" => block.slot(),
+                    "latest_valid_hash" => ?latest_valid_hash,
+                );
+                chain.process_invalid_execution_payload(&op).await?;
+                metrics::inc_counter(&metrics::OPTIMISTIC_BLOCKS_REPROCESSED_INVALID);
+                num_updated += 1;
+            }
+            PayloadStatus::InvalidBlockHash { .. } => {
+                warn!(
+                    chain.log,
+                    "Optimistic block has an invalid block hash";
+                    "block_root" => ?block_root,
+                    "slot" => block.slot(),
+                );
+                chain
+                    .process_invalid_execution_payload(&InvalidationOperation::InvalidateOne {
+                        block_root,
+                    })
+                    .await?;
+                metrics::inc_counter(&metrics::OPTIMISTIC_BLOCKS_REPROCESSED_INVALID);
+                num_updated += 1;
+            }
+            // The EL is still unable to verify this payload, try it again on the next run.
+            PayloadStatus::Syncing | PayloadStatus::Accepted => {
+                debug!(
+                    chain.log,
+                    "Optimistic block still unverified";
+                    "block_root" => ?block_root,
+                    "slot" => block.slot(),
+                    "status" => ?status,
+                );
+                continue;
+            }
+        }
+    }
+
+    if num_valid > 0 {
+        // Verifying blocks may have changed the execution status of the head.
+        chain.recompute_head_at_current_slot().await;
+    }
+
+    if num_updated > 0 {
+        info!(
+            chain.log,
+            "Reprocessed optimistic blocks";
+            "updated" => num_updated,
+            "valid" => num_valid,
+        );
+    }
+
+    Ok(num_updated)
+}
+
+/// Returns the roots of up to `MAX_BLOCKS_PER_RUN` strictly optimistic blocks in fork choice,
+/// parents first.
+fn optimistic_block_roots<T: BeaconChainTypes>(chain: &BeaconChain<T>) -> Vec<Hash256> {
+    chain
+        .canonical_head
+        .fork_choice_read_lock()
+        .proto_array()
+        .core_proto_array()
+        .nodes
+        .iter()
+        .filter(|node| node.execution_status.is_strictly_optimistic())
+        .map(|node| node.root)
+        .take(MAX_BLOCKS_PER_RUN)
+        .collect()
+}
+
+fn is_strictly_optimistic<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    block_root: &Hash256,
+) -> bool {
+    chain
+        .canonical_head
+        .fork_choice_read_lock()
+        .get_block_execution_status(block_root)
+        .map_or(false, |status| status.is_strictly_optimistic())
+}
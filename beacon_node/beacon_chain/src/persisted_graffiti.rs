@@ -0,0 +1,24 @@
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use store::{DBColumn, Error as StoreError, StoreItem};
+use types::Graffiti;
+
+/// The default graffiti for blocks produced by this node, as last set via the HTTP API.
+#[derive(Clone, Encode, Decode)]
+pub struct PersistedGraffiti {
+    pub graffiti: Graffiti,
+}
+
+impl StoreItem for PersistedGraffiti {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconChain
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        Self::from_ssz_bytes(bytes).map_err(Into::into)
+    }
+}
@@ -17,6 +17,7 @@ use crate::validator_monitor::HISTORIC_EPOCHS as VALIDATOR_MONITOR_HISTORIC_EPOC
 use crate::{
     beacon_chain::{ATTESTATION_CACHE_LOCK_TIMEOUT, BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT},
     chain_config::FORK_CHOICE_LOOKAHEAD_FACTOR,
+    metrics,
     snapshot_cache::StateAdvance,
     BeaconChain, BeaconChainError, BeaconChainTypes,
 };
@@ -45,6 +46,11 @@ const MAX_ADVANCE_DISTANCE: u64 = 4;
 /// impact whilst having 8 epochs without a block is a comfortable grace period.
 const MAX_FORK_CHOICE_DISTANCE: u64 = 256;
 
+/// If the timer fires more than `1 / MAX_TIMER_LATENESS_DENOMINATOR` of a slot (500ms on mainnet)
+/// after it was scheduled, the executor is likely starved of CPU and the state advance is skipped
+/// to avoid competing with more important work.
+const MAX_TIMER_LATENESS_DENOMINATOR: u32 = 24;
+
 #[derive(Debug)]
 enum Error {
     BeaconChain(BeaconChainError),
@@ -111,6 +117,32 @@ async fn state_advance_timer<T: BeaconChainTypes>(
     let is_running = Lock::new();
     let slot_clock = &beacon_chain.slot_clock;
     let slot_duration = slot_clock.slot_duration();
+    let max_timer_lateness = slot_duration / MAX_TIMER_LATENESS_DENOMINATOR;
+
+    // The state advance must complete before the fork choice lookahead, which relies on it.
+    let fork_choice_offset = slot_duration / FORK_CHOICE_LOOKAHEAD_FACTOR;
+    let configured_offset = beacon_chain
+        .config
+        .state_advance_offset(beacon_chain.spec.seconds_per_slot);
+    let state_advance_lookahead = slot_duration
+        .saturating_sub(configured_offset)
+        .max(fork_choice_offset);
+    if slot_duration.saturating_sub(configured_offset) < fork_choice_offset {
+        warn!(
+            log,
+            "State advance offset too late";
+            "msg" => "the state advance will run just before the fork choice lookahead",
+            "configured_ms" => configured_offset.as_millis() as u64,
+            "used_ms" => (slot_duration - state_advance_lookahead).as_millis() as u64,
+        );
+    }
+    if beacon_chain.config.disable_state_advance {
+        warn!(
+            log,
+            "State advance disabled";
+            "msg" => "block imports will be slower, especially at the start of an epoch"
+        );
+    }
 
     loop {
         let duration_to_next_slot = match beacon_chain.slot_clock.duration_to_next_slot() {
@@ -123,19 +155,17 @@ async fn state_advance_timer<T: BeaconChainTypes>(
             }
         };
 
-        // Run the state advance 3/4 of the way through the slot (9s on mainnet).
-        let state_advance_offset = slot_duration / 4;
-        let state_advance_instant = if duration_to_next_slot > state_advance_offset {
-            Instant::now() + duration_to_next_slot - state_advance_offset
+        // Run the state advance at the configured offset into the slot (9s on mainnet by default).
+        let state_advance_instant = if duration_to_next_slot > state_advance_lookahead {
+            Instant::now() + duration_to_next_slot - state_advance_lookahead
         } else {
             // Skip the state advance for the current slot and wait until the next one.
-            Instant::now() + duration_to_next_slot + slot_duration - state_advance_offset
+            Instant::now() + duration_to_next_slot + slot_duration - state_advance_lookahead
         };
 
         // Run fork choice 23/24s of the way through the slot (11.5s on mainnet).
         // We need to run after the state advance, so use the same condition as above.
-        let fork_choice_offset = slot_duration / FORK_CHOICE_LOOKAHEAD_FACTOR;
-        let fork_choice_instant = if duration_to_next_slot > state_advance_offset {
+        let fork_choice_instant = if duration_to_next_slot > state_advance_lookahead {
             Instant::now() + duration_to_next_slot - fork_choice_offset
         } else {
             Instant::now() + duration_to_next_slot + slot_duration - fork_choice_offset
@@ -161,8 +191,20 @@ async fn state_advance_timer<T: BeaconChainTypes>(
             }
         };
 
-        // Only spawn the state advance task if the lock was previously free.
-        if !is_running.lock() {
+        let timer_lateness = Instant::now().saturating_duration_since(state_advance_instant);
+
+        if beacon_chain.config.disable_state_advance {
+            debug!(log, "Skipped state advance as it is disabled");
+        } else if timer_lateness > max_timer_lateness {
+            metrics::inc_counter_vec(&metrics::STATE_ADVANCE_SKIPPED, &["late_timer"]);
+            warn!(
+                log,
+                "Skipped state advance on late timer";
+                "msg" => "system resources may be overloaded",
+                "lateness_ms" => timer_lateness.as_millis() as u64,
+            );
+        } else if !is_running.lock() {
+            // Only spawn the state advance task if the lock was previously free.
             let log = log.clone();
             let beacon_chain = beacon_chain.clone();
             let is_running = is_running.clone();
@@ -203,6 +245,7 @@ async fn state_advance_timer<T: BeaconChainTypes>(
                 "state_advance_blocking",
             );
         } else {
+            metrics::inc_counter_vec(&metrics::STATE_ADVANCE_SKIPPED, &["overloaded"]);
             warn!(
                 log,
                 "State advance routine overloaded";
@@ -283,6 +326,7 @@ fn advance_head<T: BeaconChainTypes>(
 
         // Don't run this when syncing or if lagging too far behind.
         if head_slot + MAX_ADVANCE_DISTANCE < current_slot {
+            metrics::inc_counter_vec(&metrics::STATE_ADVANCE_SKIPPED, &["syncing"]);
             return Err(Error::MaxDistanceExceeded {
                 current_slot,
                 head_slot,
@@ -35,7 +35,7 @@ use crate::{
 };
 use bls::{verify_signature_sets, PublicKeyBytes};
 use derivative::Derivative;
-use safe_arith::ArithError;
+use safe_arith::{ArithError, SafeArith};
 use slot_clock::SlotClock;
 use ssz_derive::{Decode, Encode};
 use state_processing::per_block_processing::errors::SyncCommitteeMessageValidationError;
@@ -45,7 +45,8 @@ use state_processing::signature_sets::{
     sync_committee_message_set_from_pubkeys,
 };
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{hash_map::Entry, HashMap};
+use std::sync::Arc;
 use strum::AsRefStr;
 use tree_hash::TreeHash;
 use tree_hash_derive::TreeHash;
@@ -54,8 +55,8 @@ use types::slot_data::SlotData;
 use types::sync_committee::Error as SyncCommitteeError;
 use types::{
     sync_committee_contribution::Error as ContributionError, AggregateSignature, BeaconStateError,
-    EthSpec, Hash256, SignedContributionAndProof, Slot, SyncCommitteeContribution,
-    SyncCommitteeMessage, SyncSelectionProof, SyncSubnetId,
+    Epoch, EthSpec, Hash256, SignedContributionAndProof, Slot, SyncCommittee,
+    SyncCommitteeContribution, SyncCommitteeMessage, SyncSelectionProof, SyncSubnetId,
 };
 
 /// Returned when a sync committee contribution was not successfully verified. It might not have been verified for
@@ -445,15 +446,38 @@ impl<T: BeaconChainTypes> VerifiedSyncContribution<T> {
     }
 }
 
-impl VerifiedSyncCommitteeMessage {
-    /// Returns `Ok(Self)` if the `sync_message` is valid to be (re)published on the gossip
-    /// network.
+/// Used to avoid double-checking signatures.
+#[derive(Copy, Clone)]
+enum CheckSyncMessageSignature {
+    Yes,
+    No,
+}
+
+/// Sync committees keyed by the epoch of the slot following the messages they verify.
+///
+/// Messages in a batch almost always share a slot, so this avoids reading the sync committee from
+/// the head once per message.
+type SyncCommitteeCache<E> = HashMap<Epoch, Arc<SyncCommittee<E>>>;
+
+/// Wraps a `SyncCommitteeMessage` that has passed all gossip checks besides signature
+/// verification.
+struct IndexedSyncCommitteeMessage {
+    sync_message: SyncCommitteeMessage,
+    subnet_id: SyncSubnetId,
+    pubkey: PublicKeyBytes,
+    subnet_positions: HashMap<SyncSubnetId, Vec<usize>>,
+    head_root: Hash256,
+}
+
+impl IndexedSyncCommitteeMessage {
+    /// Run all gossip checks on `sync_message` except signature verification.
     ///
-    /// `subnet_id` is the subnet from which we received this sync message. This function will
-    /// verify that it was received on the correct subnet.
-    pub fn verify<T: BeaconChainTypes>(
+    /// The sync committee is read from `committee_cache` if present, otherwise it is loaded from
+    /// the `chain` and added to the cache.
+    fn verify<T: BeaconChainTypes>(
         sync_message: SyncCommitteeMessage,
         subnet_id: SyncSubnetId,
+        committee_cache: &mut SyncCommitteeCache<T::EthSpec>,
         chain: &BeaconChain<T>,
     ) -> Result<Self, Error> {
         // Ensure sync committee message is for the current slot (within a
@@ -469,7 +493,16 @@ impl VerifiedSyncCommitteeMessage {
                 sync_message.validator_index as usize,
             ))?;
 
-        let sync_committee = chain.sync_committee_at_next_slot(sync_message.get_slot())?;
+        let next_slot_epoch = sync_message
+            .get_slot()
+            .safe_add(1)?
+            .epoch(T::EthSpec::slots_per_epoch());
+        let sync_committee = match committee_cache.entry(next_slot_epoch) {
+            Entry::Occupied(entry) => entry.get().clone(),
+            Entry::Vacant(entry) => entry
+                .insert(chain.sync_committee_at_epoch(next_slot_epoch)?)
+                .clone(),
+        };
         let subnet_positions = sync_committee.subcommittee_positions_for_public_key(&pubkey)?;
 
         if !subnet_positions.contains_key(&subnet_id) {
@@ -484,21 +517,6 @@ impl VerifiedSyncCommitteeMessage {
         let validator_index = sync_message.validator_index;
         let head_root = chain.canonical_head.cached_head().head_block_root();
         let new_root = sync_message.beacon_block_root;
-        let should_override_prev = |prev_root: &Hash256, new_root: &Hash256| {
-            let roots_differ = new_root != prev_root;
-            let new_elects_head = new_root == &head_root;
-
-            if roots_differ {
-                // Track sync committee messages that differ from each other.
-                metrics::inc_counter(&metrics::SYNC_MESSAGE_EQUIVOCATIONS);
-                if new_elects_head {
-                    // Track sync committee messages that swap from an old block to a new block.
-                    metrics::inc_counter(&metrics::SYNC_MESSAGE_EQUIVOCATIONS_TO_HEAD);
-                }
-            }
-
-            roots_differ && new_elects_head
-        };
         if let Some(prev_root) = chain
             .observed_sync_contributors
             .read()
@@ -508,7 +526,7 @@ impl VerifiedSyncCommitteeMessage {
             )
             .map_err(BeaconChainError::from)?
         {
-            if !should_override_prev(&prev_root, &new_root) {
+            if !should_override_prev(&prev_root, &new_root, &head_root) {
                 return Err(Error::PriorSyncCommitteeMessageKnown {
                     validator_index,
                     slot: sync_message.slot,
@@ -518,8 +536,55 @@ impl VerifiedSyncCommitteeMessage {
             }
         }
 
+        Ok(Self {
+            sync_message,
+            subnet_id,
+            pubkey,
+            subnet_positions,
+            head_root,
+        })
+    }
+}
+
+impl VerifiedSyncCommitteeMessage {
+    /// Returns `Ok(Self)` if the `sync_message` is valid to be (re)published on the gossip
+    /// network.
+    ///
+    /// `subnet_id` is the subnet from which we received this sync message. This function will
+    /// verify that it was received on the correct subnet.
+    pub fn verify<T: BeaconChainTypes>(
+        sync_message: SyncCommitteeMessage,
+        subnet_id: SyncSubnetId,
+        chain: &BeaconChain<T>,
+    ) -> Result<Self, Error> {
+        let indexed = IndexedSyncCommitteeMessage::verify(
+            sync_message,
+            subnet_id,
+            &mut HashMap::new(),
+            chain,
+        )?;
+        Self::from_indexed(indexed, chain, CheckSyncMessageSignature::Yes)
+    }
+
+    /// Complete the verification of a sync committee message which has passed all checks besides
+    /// signature verification.
+    fn from_indexed<T: BeaconChainTypes>(
+        indexed: IndexedSyncCommitteeMessage,
+        chain: &BeaconChain<T>,
+        check_signature: CheckSyncMessageSignature,
+    ) -> Result<Self, Error> {
+        let IndexedSyncCommitteeMessage {
+            sync_message,
+            subnet_id,
+            pubkey,
+            subnet_positions,
+            head_root,
+        } = indexed;
+
         // The aggregate signature of the sync committee message is valid.
-        verify_sync_committee_message(chain, &sync_message, &pubkey)?;
+        if let CheckSyncMessageSignature::Yes = check_signature {
+            verify_sync_committee_message(chain, &sync_message, &pubkey)?;
+        }
 
         // Now that the sync committee message has been fully verified, store that we have received a valid
         // sync committee message from this validator.
@@ -527,6 +592,8 @@ impl VerifiedSyncCommitteeMessage {
         // It's important to double check that the sync committee message still hasn't been observed, since
         // there can be a race-condition if we receive two sync committee messages at the same time and
         // process them in different threads.
+        let validator_index = sync_message.validator_index;
+        let new_root = sync_message.beacon_block_root;
         if let Some(prev_root) = chain
             .observed_sync_contributors
             .write()
@@ -534,7 +601,7 @@ impl VerifiedSyncCommitteeMessage {
                 SlotSubcommitteeIndex::new(sync_message.slot, subnet_id.into()),
                 validator_index as usize,
                 sync_message.beacon_block_root,
-                should_override_prev,
+                |prev_root, new_root| should_override_prev(prev_root, new_root, &head_root),
             )
             .map_err(BeaconChainError::from)?
         {
@@ -569,6 +636,134 @@ impl VerifiedSyncCommitteeMessage {
     }
 }
 
+/// Returns `true` if a sync committee message for `new_root` should replace a previously observed
+/// message for `prev_root` from the same validator. This is only the case when the new message
+/// switches to the current `head_root`.
+fn should_override_prev(prev_root: &Hash256, new_root: &Hash256, head_root: &Hash256) -> bool {
+    let roots_differ = new_root != prev_root;
+    let new_elects_head = new_root == head_root;
+
+    if roots_differ {
+        // Track sync committee messages that differ from each other.
+        metrics::inc_counter(&metrics::SYNC_MESSAGE_EQUIVOCATIONS);
+        if new_elects_head {
+            // Track sync committee messages that swap from an old block to a new block.
+            metrics::inc_counter(&metrics::SYNC_MESSAGE_EQUIVOCATIONS_TO_HEAD);
+        }
+    }
+
+    roots_differ && new_elects_head
+}
+
+/// Verify sync committee messages using batch BLS signature verification.
+///
+/// Each message is first subjected to all gossip checks besides signature verification, sharing
+/// the sync committee between messages of the same epoch. The signatures of all messages which
+/// pass those checks are then verified in a single batch. If the batch fails, every signature is
+/// verified independently.
+///
+/// The outcome is a `Vec<Result>` with a one-to-one mapping to the supplied messages, with no loss
+/// of fidelity when compared to individual verification.
+pub fn batch_verify_sync_committee_messages<T, I>(
+    messages: I,
+    chain: &BeaconChain<T>,
+) -> Result<Vec<Result<VerifiedSyncCommitteeMessage, Error>>, Error>
+where
+    T: BeaconChainTypes,
+    I: IntoIterator<Item = (SyncCommitteeMessage, SyncSubnetId)>,
+{
+    let mut committee_cache = HashMap::new();
+    let mut num_indexed = 0;
+
+    // Perform the non-signature checks on all messages, collecting the results.
+    let indexing_results = messages
+        .into_iter()
+        .map(|(sync_message, subnet_id)| {
+            let result = IndexedSyncCommitteeMessage::verify(
+                sync_message,
+                subnet_id,
+                &mut committee_cache,
+                chain,
+            );
+            if result.is_ok() {
+                num_indexed += 1;
+            }
+            result
+        })
+        .collect::<Vec<_>>();
+
+    // May be set to `No` if batch verification succeeds.
+    let mut check_signatures = CheckSyncMessageSignature::Yes;
+
+    // Perform batch BLS verification, if any message signatures are worth checking.
+    if num_indexed > 0 {
+        let signature_setup_timer =
+            metrics::start_timer(&metrics::SYNC_MESSAGE_PROCESSING_BATCH_SIGNATURE_SETUP_TIMES);
+
+        let pubkey_cache = chain
+            .validator_pubkey_cache
+            .try_read_for(VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT)
+            .ok_or(BeaconChainError::ValidatorPubkeyCacheLockTimeout)?;
+
+        // Signature sets borrow their signatures, so convert them all up-front.
+        let signatures = indexing_results
+            .iter()
+            .flatten()
+            .map(|indexed| AggregateSignature::from(&indexed.sync_message.signature))
+            .collect::<Vec<_>>();
+
+        let mut signature_sets = Vec::with_capacity(num_indexed);
+        for (indexed, signature) in indexing_results.iter().flatten().zip(&signatures) {
+            let sync_message = &indexed.sync_message;
+            let pubkey = match pubkey_cache.get_pubkey_from_pubkey_bytes(&indexed.pubkey) {
+                Some(pubkey) => pubkey,
+                // Leave it to individual verification to produce an error for this message.
+                None => break,
+            };
+            let next_slot_epoch =
+                (sync_message.get_slot() + 1).epoch(T::EthSpec::slots_per_epoch());
+            let fork = chain.spec.fork_at_epoch(next_slot_epoch);
+
+            signature_sets.push(
+                sync_committee_message_set_from_pubkeys::<T::EthSpec>(
+                    Cow::Borrowed(pubkey),
+                    signature,
+                    sync_message.slot.epoch(T::EthSpec::slots_per_epoch()),
+                    sync_message.beacon_block_root,
+                    &fork,
+                    chain.genesis_validators_root,
+                    &chain.spec,
+                )
+                .map_err(BeaconChainError::SignatureSetError)?,
+            );
+        }
+
+        metrics::stop_timer(signature_setup_timer);
+
+        let _signature_verification_timer =
+            metrics::start_timer(&metrics::SYNC_MESSAGE_PROCESSING_BATCH_SIGNATURE_TIMES);
+
+        if signature_sets.len() == num_indexed && verify_signature_sets(signature_sets.iter()) {
+            // Since all the signatures verified in a batch, there's no reason for them to be
+            // checked again later.
+            check_signatures = CheckSyncMessageSignature::No
+        }
+    }
+
+    // Complete the verification, potentially verifying all signatures independently.
+    let final_results = indexing_results
+        .into_iter()
+        .map(|result| match result {
+            Ok(indexed) => {
+                VerifiedSyncCommitteeMessage::from_indexed(indexed, chain, check_signatures)
+            }
+            Err(e) => Err(e),
+        })
+        .collect();
+
+    Ok(final_results)
+}
+
 /// Verify that the `sync_contribution` is within the acceptable gossip propagation range, with reference
 /// to the current slot of the `chain`.
 ///
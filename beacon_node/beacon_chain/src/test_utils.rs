@@ -10,8 +10,8 @@ pub use crate::{
 use crate::{
     builder::{BeaconChainBuilder, Witness},
     eth1_chain::CachingEth1Backend,
-    BeaconChain, BeaconChainTypes, BlockError, ChainConfig, ServerSentEventHandler,
-    StateSkipConfig,
+    BeaconBlockResponse, BeaconChain, BeaconChainTypes, BlockError, ChainConfig,
+    ServerSentEventHandler, StateSkipConfig,
 };
 use bls::get_withdrawal_credentials;
 use execution_layer::{
@@ -532,7 +532,13 @@ where
                 log.clone(),
                 5,
             )))
-            .monitor_validators(true, vec![], DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD, log);
+            .monitor_validators(
+                true,
+                None,
+                vec![],
+                DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD,
+                log,
+            );
 
         builder = if let Some(mutator) = self.initial_mutator {
             mutator(builder)
@@ -765,7 +771,7 @@ where
 
         let randao_reveal = self.sign_randao_reveal(&state, proposer_index, slot);
 
-        let (block, state) = self
+        let BeaconBlockResponse { block, state, .. } = self
             .chain
             .produce_block_on_state(
                 state,
@@ -774,6 +780,7 @@ where
                 randao_reveal,
                 Some(graffiti),
                 ProduceBlockVerification::VerifyRandao,
+                None,
             )
             .await
             .unwrap();
@@ -814,7 +821,7 @@ where
 
         let pre_state = state.clone();
 
-        let (block, state) = self
+        let BeaconBlockResponse { block, state, .. } = self
             .chain
             .produce_block_on_state(
                 state,
@@ -823,6 +830,7 @@ where
                 randao_reveal,
                 Some(graffiti),
                 ProduceBlockVerification::VerifyRandao,
+                None,
             )
             .await
             .unwrap();
@@ -2,6 +2,7 @@
 //!
 //! This component should not affect consensus.
 
+use crate::events::{EventKind, ServerSentEventHandler, SseMissedAttestation, SseMissedBlock};
 use crate::metrics;
 use parking_lot::RwLock;
 use slog::{crit, debug, info, Logger};
@@ -9,7 +10,7 @@ use slot_clock::SlotClock;
 use state_processing::per_epoch_processing::{
     errors::EpochProcessingError, EpochProcessingSummary,
 };
-use std::collections::{HashMap, HashSet};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
 use std::convert::TryFrom;
 use std::io;
 use std::marker::PhantomData;
@@ -37,6 +38,13 @@ pub const HISTORIC_EPOCHS: usize = 10;
 /// Prometheus cardinality and log volume.
 pub const DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD: usize = 64;
 
+/// A skipped slot is only considered to be a missed block once the head is this many slots beyond
+/// it. This avoids reporting blocks which arrive late or are briefly re-orged out.
+pub const MISSED_BLOCK_LAG_SLOTS: u64 = 4;
+
+/// The number of epochs prior to the current epoch which are searched for missed blocks.
+pub const MISSED_BLOCK_LOOKBACK_EPOCHS: u64 = 1;
+
 #[derive(Debug)]
 pub enum Error {
     InvalidPubkey(String),
@@ -338,11 +346,22 @@ pub struct ValidatorMonitor<T> {
     indices: HashMap<u64, PublicKeyBytes>,
     /// If true, allow the automatic registration of validators.
     auto_register: bool,
+    /// If set, validators will not be automatically registered once this many validators are
+    /// being monitored.
+    auto_register_limit: Option<usize>,
     /// Once the number of monitored validators goes above this threshold, we
     /// will stop tracking metrics/logs on a per-validator basis. This prevents
     /// large validator counts causing infeasibly high cardinailty for
     /// Prometheus and high log volumes.
     individual_tracking_threshold: usize,
+    /// The proposer of each slot of an epoch, keyed by the epoch and the root of the block which
+    /// decided its proposer shuffling.
+    proposers: HashMap<(Epoch, Hash256), Vec<usize>>,
+    /// The `(slot, validator_index)` of each block missed by a monitored validator within the
+    /// lookback period.
+    missed_blocks: HashSet<(Slot, u64)>,
+    /// Used to publish missed block and attestation events to the HTTP API.
+    event_handler: Option<ServerSentEventHandler<T>>,
     log: Logger,
     _phantom: PhantomData<T>,
 }
@@ -351,6 +370,7 @@ impl<T: EthSpec> ValidatorMonitor<T> {
     pub fn new(
         pubkeys: Vec<PublicKeyBytes>,
         auto_register: bool,
+        auto_register_limit: Option<usize>,
         individual_tracking_threshold: usize,
         log: Logger,
     ) -> Self {
@@ -358,7 +378,11 @@ impl<T: EthSpec> ValidatorMonitor<T> {
             validators: <_>::default(),
             indices: <_>::default(),
             auto_register,
+            auto_register_limit,
             individual_tracking_threshold,
+            proposers: <_>::default(),
+            missed_blocks: <_>::default(),
+            event_handler: None,
             log,
             _phantom: PhantomData,
         };
@@ -368,6 +392,11 @@ impl<T: EthSpec> ValidatorMonitor<T> {
         s
     }
 
+    /// Set the handler used to publish server-sent events about monitored validators.
+    pub fn set_event_handler(&mut self, event_handler: Option<ServerSentEventHandler<T>>) {
+        self.event_handler = event_handler;
+    }
+
     /// Returns `true` when the validator count is sufficiently low enough to
     /// emit metrics and logs on a per-validator basis (rather than just an
     /// aggregated basis).
@@ -396,7 +425,12 @@ impl<T: EthSpec> ValidatorMonitor<T> {
 
     /// Reads information from the given `state`. The `state` *must* be valid (i.e, able to be
     /// imported).
-    pub fn process_valid_state(&mut self, current_epoch: Epoch, state: &BeaconState<T>) {
+    pub fn process_valid_state(
+        &mut self,
+        current_epoch: Epoch,
+        state: &BeaconState<T>,
+        spec: &ChainSpec,
+    ) {
         // Add any new validator indices.
         state
             .validators()
@@ -411,6 +445,11 @@ impl<T: EthSpec> ValidatorMonitor<T> {
                 self.indices.insert(i, validator.pubkey);
             });
 
+        // Add any blocks missed by monitored validators.
+        if !self.validators.is_empty() {
+            self.add_validators_missed_blocks(state, spec);
+        }
+
         // Update metrics for individual validators.
         for monitored_validator in self.validators.values() {
             if let Some(i) = monitored_validator.index {
@@ -491,6 +530,104 @@ impl<T: EthSpec> ValidatorMonitor<T> {
         }
     }
 
+    /// Returns the root of the block which decided the proposer shuffling for `epoch`, according to
+    /// `state`.
+    fn proposer_shuffling_decision_root(state: &BeaconState<T>, epoch: Epoch) -> Option<Hash256> {
+        let decision_slot = epoch.start_slot(T::slots_per_epoch()).saturating_sub(1_u64);
+        state.get_block_root(decision_slot).ok().copied()
+    }
+
+    /// Searches the recent block roots of `state` for skipped slots which should have been proposed
+    /// by a monitored validator, registering each of them as a missed block.
+    fn add_validators_missed_blocks(&mut self, state: &BeaconState<T>, spec: &ChainSpec) {
+        let slots_per_epoch = T::slots_per_epoch();
+        let current_epoch = state.current_epoch();
+
+        // Proposers can only be computed for the current epoch of a state, so cache them for use
+        // once that epoch becomes the lookback epoch.
+        if let Some(decision_root) = Self::proposer_shuffling_decision_root(state, current_epoch) {
+            if let Entry::Vacant(entry) = self.proposers.entry((current_epoch, decision_root)) {
+                match state.get_beacon_proposer_indices(spec) {
+                    Ok(proposers) => {
+                        entry.insert(proposers);
+                    }
+                    Err(e) => debug!(
+                        self.log,
+                        "Unable to compute proposers for monitoring";
+                        "epoch" => current_epoch,
+                        "error" => ?e,
+                    ),
+                }
+            }
+        }
+
+        // Prune data which is outside of the lookback period.
+        let lookback_epoch = current_epoch.saturating_sub(MISSED_BLOCK_LOOKBACK_EPOCHS);
+        let start_slot = lookback_epoch.start_slot(slots_per_epoch);
+        self.proposers
+            .retain(|(epoch, _), _| *epoch >= lookback_epoch);
+        self.missed_blocks.retain(|(slot, _)| *slot >= start_slot);
+
+        // A block is missed if the block root at `slot` is the same as the one at `slot - 1`. The
+        // genesis slot is skipped since it has no predecessor.
+        let end_slot = state.slot().saturating_sub(MISSED_BLOCK_LAG_SLOTS);
+        for slot in (std::cmp::max(start_slot.as_u64(), 1)..=end_slot.as_u64()).map(Slot::new) {
+            let (block_root, prev_block_root) =
+                match (state.get_block_root(slot), state.get_block_root(slot - 1)) {
+                    (Ok(block_root), Ok(prev_block_root)) => (block_root, prev_block_root),
+                    _ => continue,
+                };
+            if block_root != prev_block_root {
+                continue;
+            }
+
+            let epoch = slot.epoch(slots_per_epoch);
+            let proposer_index = Self::proposer_shuffling_decision_root(state, epoch)
+                .and_then(|decision_root| self.proposers.get(&(epoch, decision_root)))
+                .and_then(|proposers| proposers.get(slot.as_usize() % slots_per_epoch as usize))
+                .map(|index| *index as u64);
+            let (validator_index, id) = match proposer_index
+                .and_then(|index| Some((index, self.get_validator(index)?.id.clone())))
+            {
+                Some(proposer) => proposer,
+                None => continue,
+            };
+
+            if self.missed_blocks.insert((slot, validator_index)) {
+                self.aggregatable_metric(&id, |label| {
+                    metrics::inc_counter_vec(
+                        &metrics::VALIDATOR_MONITOR_MISSED_BLOCKS_TOTAL,
+                        &[label],
+                    );
+                });
+                info!(
+                    self.log,
+                    "Validator missed a block";
+                    "parent_block_root" => ?prev_block_root,
+                    "slot" => slot,
+                    "validator" => id,
+                );
+                if let Some(event_handler) = &self.event_handler {
+                    if event_handler.has_missed_block_subscribers() {
+                        event_handler.register(EventKind::MissedBlock(SseMissedBlock {
+                            slot,
+                            validator_index,
+                        }));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the number of blocks missed by the validator with `validator_index` within the
+    /// lookback period.
+    pub fn get_monitored_validator_missed_block_count(&self, validator_index: u64) -> u64 {
+        self.missed_blocks
+            .iter()
+            .filter(|(_, index)| *index == validator_index)
+            .count() as u64
+    }
+
     /// Run `func` with the `TOTAL_LABEL` and optionally the
     /// `individual_id`.
     ///
@@ -520,6 +657,8 @@ impl<T: EthSpec> ValidatorMonitor<T> {
         let mut head_miss = Vec::new();
         let mut target_miss = Vec::new();
         let mut suboptimal_inclusion = Vec::new();
+        let mut balance_decrease = Vec::new();
+        let mut total_balance_delta: Option<i64> = None;
 
         // We subtract two from the state of the epoch that generated these summaries.
         //
@@ -605,6 +744,16 @@ impl<T: EthSpec> ValidatorMonitor<T> {
                         );
                     });
                     attestation_miss.push(id);
+                    if let Some(event_handler) = &self.event_handler {
+                        if event_handler.has_missed_attestation_subscribers() {
+                            event_handler.register(EventKind::MissedAttestation(
+                                SseMissedAttestation {
+                                    epoch: prev_epoch,
+                                    validator_index: i as u64,
+                                },
+                            ));
+                        }
+                    }
                     if self.individual_tracking() {
                         debug!(
                             self.log,
@@ -731,6 +880,18 @@ impl<T: EthSpec> ValidatorMonitor<T> {
                                 "epoch" => current_epoch,
                                 "validator" => id,
                             );
+
+                            // Skipped slots are counted as misses, in line with the `expected`
+                            // value logged above.
+                            let missed = T::slots_per_epoch()
+                                .saturating_sub(summary.sync_signature_block_inclusions as u64);
+                            self.aggregatable_metric(id, |label| {
+                                metrics::inc_counter_vec_by(
+                                    &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_SYNC_SIGNATURE_MISS,
+                                    &[label],
+                                    missed,
+                                );
+                            });
                         }
                     } else if self.individual_tracking() {
                         metrics::set_int_gauge(
@@ -746,9 +907,60 @@ impl<T: EthSpec> ValidatorMonitor<T> {
                         );
                     }
                 }
+
+                // Indicates the change in balance over the current epoch (state.epoch - 1), using
+                // the latest balances observed in each epoch. This includes the rewards and
+                // penalties applied at the start of the epoch as well as any withdrawals.
+                if let (Some(balance), Some(previous_balance)) = (
+                    monitored_validator.get_total_balance(current_epoch),
+                    monitored_validator.get_total_balance(current_epoch - 1),
+                ) {
+                    let delta = u64_to_i64(balance).saturating_sub(u64_to_i64(previous_balance));
+                    *total_balance_delta.get_or_insert(0) += delta;
+                    if delta < 0 {
+                        balance_decrease.push(id);
+                    }
+                    if self.individual_tracking() {
+                        metrics::set_int_gauge(
+                            &metrics::VALIDATOR_MONITOR_PREV_EPOCH_BALANCE_DELTA_GWEI,
+                            &[id],
+                            delta,
+                        );
+                        debug!(
+                            self.log,
+                            "Current epoch balance change";
+                            "delta_gwei" => delta,
+                            "balance_gwei" => balance,
+                            "epoch" => current_epoch,
+                            "validator" => id,
+                        );
+                    }
+                }
             }
         }
 
+        if let Some(total_balance_delta) = total_balance_delta {
+            metrics::set_int_gauge(
+                &metrics::VALIDATOR_MONITOR_PREV_EPOCH_BALANCE_DELTA_GWEI,
+                &[TOTAL_LABEL],
+                total_balance_delta,
+            );
+            info!(
+                self.log,
+                "Current epoch balance change";
+                "total_delta_gwei" => total_balance_delta,
+                "epoch" => epoch - 1,
+            );
+        }
+        if !balance_decrease.is_empty() {
+            info!(
+                self.log,
+                "Current epoch balance(s) decreased";
+                "epoch" => epoch - 1,
+                "validators" => ?balance_decrease,
+            );
+        }
+
         // Aggregate logging for attestation success/failures over an epoch
         // for all validators managed by the validator monitor.
         if !attestation_success.is_empty() {
@@ -824,6 +1036,8 @@ impl<T: EthSpec> ValidatorMonitor<T> {
 
     /// If `self.auto_register == true`, add the `validator_index` to `self.monitored_validators`.
     /// Otherwise, do nothing.
+    ///
+    /// No validators are added once `self.auto_register_limit` is reached.
     pub fn auto_register_local_validator(&mut self, validator_index: u64) {
         if !self.auto_register {
             return;
@@ -831,6 +1045,18 @@ impl<T: EthSpec> ValidatorMonitor<T> {
 
         if let Some(pubkey) = self.indices.get(&validator_index) {
             if !self.validators.contains_key(pubkey) {
+                if let Some(limit) = self.auto_register_limit {
+                    if self.validators.len() >= limit {
+                        debug!(
+                            self.log,
+                            "Not monitoring validator, limit reached";
+                            "limit" => limit,
+                            "validator" => %validator_index,
+                        );
+                        return;
+                    }
+                }
+
                 info!(
                     self.log,
                     "Started monitoring validator";
@@ -896,7 +1122,7 @@ impl<T: EthSpec> ValidatorMonitor<T> {
             info!(
                 self.log,
                 "Block from API";
-                "root" => ?block_root,
+                "block_root" => ?block_root,
                 "delay" => %delay.as_millis(),
                 "slot" => %block.slot(),
                 "src" => src,
@@ -4,7 +4,8 @@ use beacon_chain::test_utils::{
     AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
 };
 use beacon_chain::{
-    BeaconSnapshot, BlockError, ChainSegmentResult, IntoExecutionPendingBlock, NotifyExecutionLayer,
+    events::EventKind, BeaconSnapshot, BlockError, ChainSegmentResult, IntoExecutionPendingBlock,
+    NotifyExecutionLayer,
 };
 use lazy_static::lazy_static;
 use logging::test_logger;
@@ -1014,6 +1015,69 @@ async fn verify_block_for_gossip_slashing_detection() {
     slasher_dir.close().unwrap();
 }
 
+#[tokio::test]
+async fn verify_block_for_gossip_slasher_event_stream() {
+    // No slasher runs in-process, the block headers reach it via the event stream instead.
+    let harness = get_harness(VALIDATOR_COUNT);
+    let mut block_header_events = harness
+        .chain
+        .event_handler
+        .as_ref()
+        .unwrap()
+        .subscribe_slasher_block_header();
+
+    let state = harness.get_current_state();
+    let (block1, _) = harness.make_block(state.clone(), Slot::new(1)).await;
+    let (block2, _) = harness.make_block(state, Slot::new(1)).await;
+
+    let verified_block = harness
+        .chain
+        .verify_block_for_gossip(Arc::new(block1.clone()))
+        .await
+        .unwrap();
+    harness
+        .chain
+        .process_block(
+            verified_block.block_root,
+            verified_block,
+            NotifyExecutionLayer::Yes,
+            || Ok(()),
+        )
+        .await
+        .unwrap();
+    // The second proposal is rejected by gossip verification, but still published.
+    unwrap_err(
+        harness
+            .chain
+            .verify_block_for_gossip(Arc::new(block2.clone()))
+            .await,
+    );
+
+    let mut block_headers = vec![];
+    while let Ok(event) = block_header_events.try_recv() {
+        match event {
+            EventKind::SlasherBlockHeader(block_header) => block_headers.push(block_header),
+            other => panic!("expected a slasher block header event, got {:?}", other),
+        }
+    }
+    assert!(block_headers.contains(&block1.signed_block_header()));
+    assert!(block_headers.contains(&block2.signed_block_header()));
+
+    // A slasher fed from the event stream finds the slashing.
+    let slasher_dir = tempdir().unwrap();
+    let slasher: Slasher<E> =
+        Slasher::open(SlasherConfig::new(slasher_dir.path().into()), test_logger()).unwrap();
+    for block_header in block_headers {
+        slasher.accept_block_header(block_header);
+    }
+    slasher.process_queued(Epoch::new(0)).unwrap();
+    let proposer_slashings = slasher.get_proposer_slashings();
+    assert_eq!(proposer_slashings.len(), 1);
+    // windows won't delete the temporary directory if you don't do this..
+    drop(slasher);
+    slasher_dir.close().unwrap();
+}
+
 #[tokio::test]
 async fn verify_block_for_gossip_doppelganger_detection() {
     let harness = get_harness(VALIDATOR_COUNT);
@@ -1,7 +1,12 @@
 #![cfg(not(debug_assertions))] // Tests run too slow in debug.
 
-use beacon_chain::test_utils::BeaconChainHarness;
-use execution_layer::test_utils::{generate_pow_block, Block, DEFAULT_TERMINAL_BLOCK};
+use beacon_chain::test_utils::{BeaconChainHarness, HARNESS_GENESIS_TIME};
+use beacon_chain::ExecutionStatus;
+use execution_layer::test_utils::{
+    generate_pow_block, Block, ExecutionBlockGenerator, DEFAULT_TERMINAL_BLOCK,
+};
+use genesis::{interop_genesis_state, DEFAULT_ETH1_BLOCK_HASH};
+use types::test_utils::generate_deterministic_keypairs;
 use types::*;
 
 const VALIDATOR_COUNT: usize = 32;
@@ -220,3 +225,130 @@ async fn base_altair_merge_with_terminal_block_after_fork() {
 
     verify_execution_payload_chain(execution_payloads.as_slice());
 }
+
+#[tokio::test]
+async fn merge_complete_at_genesis() {
+    let mut spec = E::default_spec();
+    spec.altair_fork_epoch = Some(Epoch::new(0));
+    spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+
+    // The mock execution layer always generates the same PoW chain, so its terminal block can be
+    // found before the harness is built.
+    let mut generator = ExecutionBlockGenerator::<E>::new(
+        spec.terminal_total_difficulty,
+        DEFAULT_TERMINAL_BLOCK,
+        spec.terminal_block_hash,
+        None,
+    );
+    generator.move_to_terminal_block().unwrap();
+    let terminal_block = generator.latest_execution_block().unwrap();
+
+    // Start from a genesis state which already contains the terminal block's header.
+    let keypairs = generate_deterministic_keypairs(VALIDATOR_COUNT);
+    let genesis_state = interop_genesis_state::<E>(
+        &keypairs,
+        HARNESS_GENESIS_TIME,
+        Hash256::from_slice(DEFAULT_ETH1_BLOCK_HASH),
+        Some(ExecutionPayloadHeader::Merge(ExecutionPayloadHeaderMerge {
+            block_hash: terminal_block.block_hash,
+            block_number: terminal_block.block_number,
+            timestamp: terminal_block.timestamp,
+            ..<_>::default()
+        })),
+        &spec,
+    )
+    .unwrap();
+
+    let harness = BeaconChainHarness::builder(E::default())
+        .spec(spec)
+        .logger(logging::test_logger())
+        .keypairs(keypairs)
+        .genesis_state_ephemeral_store(genesis_state)
+        .mock_execution_layer()
+        .build();
+
+    harness
+        .execution_block_generator()
+        .move_to_terminal_block()
+        .unwrap();
+
+    /*
+     * The genesis block has a default payload, but the merge is already complete.
+     */
+
+    let genesis_block = &harness.chain.head_snapshot().beacon_block;
+    assert!(genesis_block
+        .message()
+        .body()
+        .execution_payload()
+        .unwrap()
+        .is_default_with_empty_roots());
+    assert!(harness.chain.is_merge_complete_at_genesis().unwrap());
+    assert!(harness.chain.is_merge_complete_at_head());
+
+    // The anchor is trusted to have the execution status of the genesis payload header.
+    assert_eq!(
+        harness
+            .chain
+            .canonical_head
+            .fork_choice_read_lock()
+            .get_block_execution_status(&harness.chain.genesis_block_root),
+        Some(ExecutionStatus::Valid(terminal_block.block_hash))
+    );
+
+    // There is no transition to watch for.
+    assert!(!harness.chain.spawn_transition_configuration_poll());
+
+    /*
+     * Every block after genesis builds upon the genesis payload header.
+     */
+
+    let mut execution_payloads = vec![];
+    for _ in 0..E::slots_per_epoch() {
+        harness.extend_slots(1).await;
+
+        let block = &harness.chain.head_snapshot().beacon_block;
+        execution_payloads.push(
+            block
+                .message()
+                .body()
+                .execution_payload()
+                .unwrap()
+                .clone()
+                .into(),
+        );
+    }
+
+    assert_eq!(
+        execution_payloads[0].parent_hash(),
+        terminal_block.block_hash
+    );
+    verify_execution_payload_chain(execution_payloads.as_slice());
+}
+
+#[tokio::test]
+async fn merge_incomplete_at_genesis() {
+    let mut spec = E::default_spec();
+    spec.altair_fork_epoch = Some(Epoch::new(0));
+    spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+
+    let harness = BeaconChainHarness::builder(E::default())
+        .spec(spec)
+        .logger(logging::test_logger())
+        .deterministic_keypairs(VALIDATOR_COUNT)
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+
+    assert!(!harness.chain.is_merge_complete_at_genesis().unwrap());
+    assert!(!harness.chain.is_merge_complete_at_head());
+    assert_eq!(
+        harness
+            .chain
+            .canonical_head
+            .fork_choice_read_lock()
+            .get_block_execution_status(&harness.chain.genesis_block_root),
+        Some(ExecutionStatus::irrelevant())
+    );
+    assert!(harness.chain.spawn_transition_configuration_poll());
+}
@@ -1,11 +1,13 @@
 #![cfg(not(debug_assertions))]
 
+use beacon_chain::optimistic_reprocessing_service::reprocess_optimistic_blocks;
 use beacon_chain::otb_verification_service::{
     load_optimistic_transition_blocks, validate_optimistic_transition_blocks,
     OptimisticTransitionBlock,
 };
 use beacon_chain::{
     canonical_head::{CachedHead, CanonicalHead},
+    events::EventKind,
     test_utils::{BeaconChainHarness, EphemeralHarnessType},
     BeaconChainError, BlockError, ExecutionPayloadError, NotifyExecutionLayer,
     OverrideForkchoiceUpdate, StateSkipConfig, WhenSlotSkipped,
@@ -15,7 +17,7 @@ use beacon_chain::{
 use execution_layer::{
     json_structures::{JsonForkchoiceStateV1, JsonPayloadAttributes, JsonPayloadAttributesV1},
     test_utils::ExecutionBlockGenerator,
-    ExecutionLayer, ForkchoiceState, PayloadAttributes,
+    ExecutionLayer, ForkchoiceState, PayloadAttributes, PayloadStatusV1, PayloadStatusV1Status,
 };
 use fork_choice::{Error as ForkChoiceError, InvalidationOperation, PayloadVerificationStatus};
 use logging::test_logger;
@@ -371,6 +373,12 @@ impl InvalidPayloadRig {
         block_root
     }
 
+    async fn reprocess_optimistic_blocks(&self) -> usize {
+        reprocess_optimistic_blocks(&self.harness.chain)
+            .await
+            .unwrap()
+    }
+
     async fn invalidate_manually(&self, block_root: Hash256) {
         self.harness
             .chain
@@ -1149,6 +1157,82 @@ async fn payload_preparation_before_transition_block() {
     assert_eq!(fork_choice_state.head_block_hash, latest_block_hash);
 }
 
+/// Ensure that the head event reports optimistic heads, the duty dependent roots and epoch
+/// transitions.
+#[tokio::test]
+async fn head_events() {
+    let mut rig = InvalidPayloadRig::new();
+    rig.move_to_terminal_block();
+
+    let mut head_events = rig
+        .harness
+        .chain
+        .event_handler
+        .as_ref()
+        .unwrap()
+        .subscribe_head();
+
+    // Returns the root of the block which decided the shuffling for `epoch`.
+    let dependent_root = |rig: &InvalidPayloadRig, epoch: Epoch| {
+        if epoch == 0 {
+            rig.harness.chain.genesis_block_root
+        } else {
+            rig.block_root_at_slot(epoch.start_slot(E::slots_per_epoch()) - 1)
+                .unwrap()
+        }
+    };
+
+    // Cross two epoch boundaries, so that both dependent roots are non-genesis by the end.
+    let num_blocks = E::slots_per_epoch() * 2;
+    for i in 0..num_blocks {
+        // The first block is the merge transition block, which can't be imported optimistically.
+        let is_valid = if i % 4 == 3 {
+            Payload::Syncing
+        } else {
+            Payload::Valid
+        };
+        let block_root = rig.import_block(is_valid).await;
+        let slot = rig.cached_head().head_slot();
+        let epoch = slot.epoch(E::slots_per_epoch());
+
+        let event = match head_events.try_recv() {
+            Ok(EventKind::Head(event)) => event,
+            other => panic!("expected a head event, got {:?}", other),
+        };
+        assert!(head_events.try_recv().is_err());
+
+        assert_eq!(event.slot, slot);
+        assert_eq!(event.block, block_root);
+        assert_eq!(event.state, rig.cached_head().head_state_root());
+        assert_eq!(
+            event.current_duty_dependent_root,
+            dependent_root(&rig, epoch)
+        );
+        assert_eq!(
+            event.previous_duty_dependent_root,
+            dependent_root(&rig, epoch.saturating_sub(1_u64))
+        );
+        assert_eq!(
+            event.epoch_transition,
+            slot % E::slots_per_epoch() == 0,
+            "slot {}",
+            slot
+        );
+        assert_eq!(
+            event.execution_optimistic,
+            is_valid == Payload::Syncing,
+            "slot {}",
+            slot
+        );
+    }
+
+    assert_eq!(
+        rig.cached_head().head_slot(),
+        Slot::new(num_blocks),
+        "the chain should have crossed two epoch boundaries"
+    );
+}
+
 #[tokio::test]
 async fn attesting_to_optimistic_head() {
     let mut rig = InvalidPayloadRig::new();
@@ -2033,3 +2117,115 @@ async fn weights_after_resetting_optimistic_status() {
         rig.import_block(Payload::Valid).await;
     }
 }
+
+/// Optimistic blocks are held until the EL is synced, then re-sent to `newPayload`.
+#[tokio::test]
+async fn reprocess_optimistic_blocks_once_el_synced() {
+    let mut rig = InvalidPayloadRig::new().enable_attestations();
+    rig.move_to_terminal_block();
+    rig.import_block(Payload::Valid).await; // Import a valid transition block.
+
+    let roots = rig.build_blocks(2, Payload::Syncing).await;
+    let mock_execution_layer = rig.harness.mock_execution_layer.as_ref().unwrap();
+    mock_execution_layer
+        .server
+        .all_payloads_valid_on_new_payload();
+
+    // Nothing is re-sent whilst the EL is syncing.
+    mock_execution_layer.server.set_syncing_response(Ok(true));
+    rig.execution_layer().upcheck().await;
+    assert!(!rig.execution_layer().is_synced().await);
+    assert_eq!(rig.reprocess_optimistic_blocks().await, 0);
+    for root in &roots {
+        assert!(rig.execution_status(*root).is_strictly_optimistic());
+    }
+
+    // All blocks are verified once the EL is synced.
+    mock_execution_layer.server.set_syncing_response(Ok(false));
+    rig.execution_layer().upcheck().await;
+    assert!(rig.execution_layer().is_synced().await);
+    assert_eq!(rig.reprocess_optimistic_blocks().await, 2);
+    for root in &roots {
+        assert!(rig.execution_status(*root).is_valid_and_post_bellatrix());
+    }
+    assert_eq!(rig.harness.head_block_root(), roots[1]);
+    assert!(rig
+        .canonical_head()
+        .head_execution_status()
+        .unwrap()
+        .is_valid_and_post_bellatrix());
+
+    // There is nothing left to reprocess.
+    assert_eq!(rig.reprocess_optimistic_blocks().await, 0);
+}
+
+/// Optimistic blocks remain optimistic while the EL is unable to verify their payloads.
+#[tokio::test]
+async fn reprocess_optimistic_blocks_el_still_syncing() {
+    let mut rig = InvalidPayloadRig::new().enable_attestations();
+    rig.move_to_terminal_block();
+    rig.import_block(Payload::Valid).await; // Import a valid transition block.
+
+    let roots = rig.build_blocks(2, Payload::Syncing).await;
+    rig.execution_layer().upcheck().await;
+
+    // The EL is synced but still responds `SYNCING` to `newPayload`.
+    assert_eq!(rig.reprocess_optimistic_blocks().await, 0);
+    for root in &roots {
+        assert!(rig.execution_status(*root).is_strictly_optimistic());
+    }
+}
+
+/// An invalid optimistic block is invalidated along with its descendants, which are not re-sent.
+#[tokio::test]
+async fn reprocess_optimistic_blocks_invalid() {
+    let mut rig = InvalidPayloadRig::new().enable_attestations();
+    rig.move_to_terminal_block();
+    let transition_root = rig.import_block(Payload::Valid).await;
+
+    let roots = rig.build_blocks(2, Payload::Syncing).await;
+    rig.execution_layer().upcheck().await;
+    rig.harness
+        .mock_execution_layer
+        .as_ref()
+        .unwrap()
+        .server
+        .all_payloads_invalid_on_new_payload(rig.block_hash(transition_root));
+
+    assert_eq!(rig.reprocess_optimistic_blocks().await, 1);
+    assert!(rig
+        .execution_status(transition_root)
+        .is_valid_and_post_bellatrix());
+    for root in &roots {
+        assert!(rig.execution_status(*root).is_invalid());
+    }
+    assert_eq!(rig.harness.head_block_root(), transition_root);
+}
+
+/// A block which the EL can't verify doesn't prevent later blocks from being verified.
+#[tokio::test]
+async fn reprocess_optimistic_blocks_skips_stuck_block() {
+    let mut rig = InvalidPayloadRig::new().enable_attestations();
+    rig.move_to_terminal_block();
+    rig.import_block(Payload::Valid).await; // Import a valid transition block.
+
+    let roots = rig.build_blocks(2, Payload::Syncing).await;
+    rig.execution_layer().upcheck().await;
+
+    // The EL keeps answering `ACCEPTED` for the first block, but can verify the second.
+    let mock_execution_layer = rig.harness.mock_execution_layer.as_ref().unwrap();
+    mock_execution_layer
+        .server
+        .all_payloads_valid_on_new_payload();
+    mock_execution_layer.server.set_new_payload_status(
+        rig.block_hash(roots[0]),
+        PayloadStatusV1 {
+            status: PayloadStatusV1Status::Accepted,
+            latest_valid_hash: None,
+            validation_error: None,
+        },
+    );
+
+    assert_eq!(rig.reprocess_optimistic_blocks().await, 1);
+    assert!(rig.execution_status(roots[1]).is_valid_and_post_bellatrix());
+}
@@ -98,6 +98,43 @@ async fn full_participation_no_skips() {
     check_iterators(&harness);
 }
 
+#[tokio::test]
+async fn get_blinded_block_matches_store() {
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            E::slots_per_epoch() as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let head = harness.chain.head_snapshot();
+    let parent_root = head.beacon_block.parent_root();
+    for block_root in [head.beacon_block_root, parent_root] {
+        let blinded_block = harness
+            .chain
+            .get_blinded_block(&block_root)
+            .unwrap()
+            .expect("block should exist");
+        assert_eq!(blinded_block.canonical_root(), block_root);
+        assert_eq!(
+            Some(blinded_block),
+            store.get_blinded_block(&block_root).unwrap()
+        );
+    }
+    assert_eq!(
+        harness
+            .chain
+            .get_blinded_block(&Hash256::repeat_byte(0xff))
+            .unwrap(),
+        None
+    );
+}
+
 #[tokio::test]
 async fn randomised_skips() {
     let num_slots = E::slots_per_epoch() * 5;
@@ -2124,7 +2161,13 @@ async fn weak_subjectivity_sync() {
                 log.clone(),
                 1,
             )))
-            .monitor_validators(true, vec![], DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD, log)
+            .monitor_validators(
+                true,
+                None,
+                vec![],
+                DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD,
+                log,
+            )
             .build()
             .expect("should build"),
     );
@@ -2243,6 +2286,15 @@ async fn weak_subjectivity_sync() {
     // Anchor slot is still set to the starting slot.
     assert_eq!(store.get_anchor_slot(), Some(wss_slot));
 
+    // Reconstruction is paused while the database is low on disk space.
+    assert!(!store.set_low_disk_space(true));
+    assert!(matches!(
+        store.clone().reconstruct_historic_states(),
+        Err(store::Error::StateReconstructionPaused { .. })
+    ));
+    assert_eq!(store.get_anchor_slot(), Some(wss_slot));
+    assert!(store.set_low_disk_space(false));
+
     // Reconstruct states.
     store.clone().reconstruct_historic_states().unwrap();
     assert_eq!(store.get_anchor_slot(), None);
@@ -2829,3 +2881,47 @@ fn get_blocks(
         .map(|checkpoint| checkpoint.beacon_block_root.into())
         .collect()
 }
+
+#[tokio::test]
+async fn graffiti_flag_replaces_persisted_graffiti() {
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    let persisted_graffiti = Graffiti::from([1; 32]);
+    let configured_graffiti = Graffiti::from([2; 32]);
+    harness.chain.set_graffiti(persisted_graffiti).unwrap();
+    harness
+        .chain
+        .persist_head_and_fork_choice()
+        .expect("should persist the head and fork choice");
+    drop(harness);
+
+    let resume = |graffiti_set_explicitly: bool| {
+        BeaconChainHarness::builder(MinimalEthSpec)
+            .default_spec()
+            .keypairs(KEYPAIRS[0..LOW_VALIDATOR_COUNT].to_vec())
+            .logger(store.logger().clone())
+            .resumed_disk_store(store.clone())
+            .initial_mutator(Box::new(move |builder| {
+                builder
+                    .graffiti(configured_graffiti)
+                    .graffiti_set_explicitly(graffiti_set_explicitly)
+            }))
+            .mock_execution_layer()
+            .build()
+    };
+
+    // The graffiti set via the HTTP API takes precedence over the default graffiti.
+    let harness = resume(false);
+    assert_eq!(harness.chain.graffiti(), persisted_graffiti);
+    drop(harness);
+
+    // A graffiti provided by the user takes precedence, and removes the persisted graffiti.
+    let harness = resume(true);
+    assert_eq!(harness.chain.graffiti(), configured_graffiti);
+    drop(harness);
+
+    let harness = resume(false);
+    assert_eq!(harness.chain.graffiti(), configured_graffiti);
+}
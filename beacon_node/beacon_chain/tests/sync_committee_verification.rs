@@ -813,3 +813,90 @@ async fn unaggregated_gossip_verification() {
         if received == subnet_id && !expected.contains(&subnet_id)
     );
 }
+
+/// Ensure that batch verification of sync committee messages produces the same results as
+/// individual verification, including when the batch contains an invalid signature.
+#[tokio::test]
+async fn batch_unaggregated_gossip_verification() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let state = harness.get_current_state();
+
+    harness
+        .add_attested_blocks_at_slots(
+            state,
+            Hash256::zero(),
+            &[Slot::new(1), Slot::new(2)],
+            (0..VALIDATOR_COUNT).collect::<Vec<_>>().as_slice(),
+        )
+        .await;
+
+    let current_slot = harness.chain.slot().expect("should get slot");
+    let head_state = harness.chain.head_beacon_state_cloned();
+    let head_block_root = harness.chain.head_snapshot().beacon_block_root;
+    let subnet_id = SyncSubnetId::new(0);
+
+    // Take one message per validator from the first subnet, as a validator may appear more than
+    // once in a subcommittee.
+    let mut seen_validators = std::collections::HashSet::new();
+    let mut sync_messages = harness
+        .make_sync_committee_messages(
+            &head_state,
+            head_block_root,
+            current_slot,
+            RelativeSyncCommittee::Current,
+        )
+        .remove(0)
+        .into_iter()
+        .map(|(sync_message, _)| sync_message)
+        .filter(|sync_message| seen_validators.insert(sync_message.validator_index))
+        .collect::<Vec<_>>();
+    assert!(
+        sync_messages.len() > 2,
+        "test pre-condition: multiple messages"
+    );
+
+    // Poison the batch with a message signed by the wrong validator.
+    let invalid_index = sync_messages.len() - 1;
+    sync_messages[invalid_index].signature = sync_messages[0].signature.clone();
+
+    let results = harness
+        .chain
+        .batch_verify_sync_committee_messages_for_gossip(
+            sync_messages
+                .iter()
+                .cloned()
+                .map(|sync_message| (sync_message, subnet_id))
+                .collect(),
+        )
+        .expect("batch verification should not error");
+
+    assert_eq!(results.len(), sync_messages.len());
+    for (i, result) in results.iter().enumerate() {
+        if i == invalid_index {
+            assert!(
+                matches!(result, Err(SyncCommitteeError::InvalidSignature)),
+                "poisoned message should be invalid"
+            );
+        } else {
+            assert!(result.is_ok(), "message {} should be valid", i);
+        }
+    }
+
+    // Messages which were verified in the batch must be observed, so they are rejected if seen
+    // again.
+    let results = harness
+        .chain
+        .batch_verify_sync_committee_messages_for_gossip(
+            sync_messages[..invalid_index]
+                .iter()
+                .cloned()
+                .map(|sync_message| (sync_message, subnet_id))
+                .collect(),
+        )
+        .expect("batch verification should not error");
+
+    assert!(results.iter().all(|result| matches!(
+        result,
+        Err(SyncCommitteeError::PriorSyncCommitteeMessageKnown { .. })
+    )));
+}
@@ -0,0 +1,122 @@
+use beacon_chain::events::EventKind;
+use beacon_chain::test_utils::{
+    generate_deterministic_keypairs, BeaconChainHarness, EphemeralHarnessType,
+};
+use beacon_chain::types::{Epoch, EthSpec, Keypair, MinimalEthSpec, Slot};
+use beacon_chain::validator_monitor::{
+    ValidatorMonitor, DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD, MISSED_BLOCK_LAG_SLOTS,
+};
+use lazy_static::lazy_static;
+
+pub const VALIDATOR_COUNT: usize = 48;
+
+type E = MinimalEthSpec;
+
+lazy_static! {
+    static ref KEYPAIRS: Vec<Keypair> = generate_deterministic_keypairs(VALIDATOR_COUNT);
+}
+
+fn get_harness() -> BeaconChainHarness<EphemeralHarnessType<E>> {
+    let harness = BeaconChainHarness::builder(E::default())
+        .default_spec()
+        .keypairs(KEYPAIRS.to_vec())
+        .fresh_ephemeral_store()
+        .build();
+
+    harness.advance_slot();
+
+    harness
+}
+
+#[tokio::test]
+async fn produces_missed_blocks() {
+    let harness = get_harness();
+    let slots_per_epoch = E::slots_per_epoch();
+
+    {
+        let mut validator_monitor = harness.chain.validator_monitor.write();
+        for validator_index in 0..VALIDATOR_COUNT as u64 {
+            validator_monitor.auto_register_local_validator(validator_index);
+        }
+    }
+
+    let mut missed_block_events = harness
+        .chain
+        .event_handler
+        .as_ref()
+        .unwrap()
+        .subscribe_missed_block();
+
+    // Fill the first epoch so that the head state is in the epoch containing the missed slot.
+    let (state, state_root) = harness.get_current_state_and_root();
+    let first_epoch_slots = (1..=slots_per_epoch).map(Slot::new).collect::<Vec<_>>();
+    let (_, _, _, state) = harness
+        .add_attested_blocks_at_slots(
+            state,
+            state_root,
+            &first_epoch_slots,
+            &harness.get_all_validators(),
+        )
+        .await;
+    assert_eq!(state.current_epoch(), Epoch::new(1));
+
+    let missed_slot = Slot::new(slots_per_epoch + 2);
+    let proposer_index = state
+        .get_beacon_proposer_index(missed_slot, &harness.spec)
+        .unwrap() as u64;
+
+    // Skip `missed_slot` and then build far enough beyond it for the miss to be registered.
+    let slots = (slots_per_epoch + 1..=missed_slot.as_u64() + MISSED_BLOCK_LAG_SLOTS)
+        .map(Slot::new)
+        .filter(|slot| *slot != missed_slot)
+        .collect::<Vec<_>>();
+    let state_root = state.canonical_root();
+    harness
+        .add_attested_blocks_at_slots(state, state_root, &slots, &harness.get_all_validators())
+        .await;
+
+    let validator_monitor = harness.chain.validator_monitor.read();
+    assert_eq!(
+        validator_monitor.get_monitored_validator_missed_block_count(proposer_index),
+        1
+    );
+    let total_missed: u64 = (0..VALIDATOR_COUNT as u64)
+        .map(|i| validator_monitor.get_monitored_validator_missed_block_count(i))
+        .sum();
+    assert_eq!(total_missed, 1);
+
+    match missed_block_events.try_recv() {
+        Ok(EventKind::MissedBlock(event)) => {
+            assert_eq!(event.slot, missed_slot);
+            assert_eq!(event.validator_index, proposer_index);
+        }
+        other => panic!("expected a missed block event, got {:?}", other),
+    }
+    assert!(missed_block_events.try_recv().is_err());
+}
+
+#[test]
+fn auto_register_limit() {
+    let harness = get_harness();
+    let mut validator_monitor = ValidatorMonitor::<E>::new(
+        vec![],
+        true,
+        Some(2),
+        DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD,
+        harness.logger().clone(),
+    );
+    validator_monitor.process_valid_state(
+        Epoch::new(0),
+        &harness.get_current_state(),
+        &harness.spec,
+    );
+
+    for validator_index in 0..4 {
+        validator_monitor.auto_register_local_validator(validator_index);
+    }
+    assert_eq!(validator_monitor.num_validators(), 2);
+
+    // Validators which are already monitored are unaffected by the limit.
+    validator_monitor.auto_register_local_validator(0);
+    assert_eq!(validator_monitor.num_validators(), 2);
+}
@@ -1,7 +1,9 @@
 use crate::address_change_broadcast::broadcast_address_changes_at_capella;
 use crate::config::{ClientGenesis, Config as ClientConfig};
+use crate::disk_monitor::spawn_disk_monitor;
 use crate::notifier::spawn_notifier;
 use crate::Client;
+use beacon_chain::optimistic_reprocessing_service::start_optimistic_reprocessing_service;
 use beacon_chain::otb_verification_service::start_otb_verification_service;
 use beacon_chain::proposer_prep_service::start_proposer_prep_service;
 use beacon_chain::schema_change::migrate_schema;
@@ -23,7 +25,7 @@ use execution_layer::ExecutionLayer;
 use genesis::{interop_genesis_state, Eth1GenesisService, DEFAULT_ETH1_BLOCK_HASH};
 use lighthouse_network::{prometheus_client::registry::Registry, NetworkGlobals};
 use monitoring_api::{MonitoringHttpClient, ProcessType};
-use network::{NetworkConfig, NetworkSenders, NetworkService};
+use network::{BeaconProcessorConfig, NetworkConfig, NetworkSenders, NetworkService};
 use slasher::Slasher;
 use slasher_service::SlasherService;
 use slog::{debug, info, warn, Logger};
@@ -137,6 +139,7 @@ where
         let eth_spec_instance = self.eth_spec_instance.clone();
         let chain_config = config.chain.clone();
         let graffiti = config.graffiti;
+        let graffiti_set_explicitly = config.graffiti_set_explicitly;
 
         let store = store.ok_or("beacon_chain_start_method requires a store")?;
         let runtime_context =
@@ -169,10 +172,12 @@ where
             .custom_spec(spec.clone())
             .chain_config(chain_config)
             .graffiti(graffiti)
+            .graffiti_set_explicitly(graffiti_set_explicitly)
             .event_handler(event_handler)
             .execution_layer(execution_layer)
             .monitor_validators(
                 config.validator_monitor_auto,
+                config.validator_monitor_auto_limit,
                 config.validator_monitor_pubkeys.clone(),
                 config.validator_monitor_individual_tracking_threshold,
                 runtime_context
@@ -231,23 +236,25 @@ where
                 )?;
                 builder.genesis_state(genesis_state).map(|v| (v, None))?
             }
-            ClientGenesis::SszBytes {
-                genesis_state_bytes,
-            } => {
+            ClientGenesis::GenesisState => {
                 info!(
                     context.log(),
                     "Starting from known genesis state";
                 );
 
-                let genesis_state = BeaconState::from_ssz_bytes(&genesis_state_bytes, &spec)
-                    .map_err(|e| format!("Unable to parse genesis state SSZ: {:?}", e))?;
+                let genesis_state = load_genesis_state(
+                    &runtime_context,
+                    config.genesis_state_url.as_deref(),
+                    config.genesis_state_url_timeout,
+                    context.log(),
+                )
+                .await?;
 
                 builder.genesis_state(genesis_state).map(|v| (v, None))?
             }
             ClientGenesis::WeakSubjSszBytes {
                 anchor_state_bytes,
                 anchor_block_bytes,
-                genesis_state_bytes,
             } => {
                 info!(context.log(), "Starting checkpoint sync");
                 if config.chain.genesis_backfill {
@@ -261,17 +268,19 @@ where
                     .map_err(|e| format!("Unable to parse weak subj state SSZ: {:?}", e))?;
                 let anchor_block = SignedBeaconBlock::from_ssz_bytes(&anchor_block_bytes, &spec)
                     .map_err(|e| format!("Unable to parse weak subj block SSZ: {:?}", e))?;
-                let genesis_state = BeaconState::from_ssz_bytes(&genesis_state_bytes, &spec)
-                    .map_err(|e| format!("Unable to parse genesis state SSZ: {:?}", e))?;
+                let genesis_state = load_genesis_state(
+                    &runtime_context,
+                    config.genesis_state_url.as_deref(),
+                    config.genesis_state_url_timeout,
+                    context.log(),
+                )
+                .await?;
 
                 builder
                     .weak_subjectivity_state(anchor_state, anchor_block, genesis_state)
                     .map(|v| (v, None))?
             }
-            ClientGenesis::CheckpointSyncUrl {
-                genesis_state_bytes,
-                url,
-            } => {
+            ClientGenesis::CheckpointSyncUrl { url } => {
                 info!(
                     context.log(),
                     "Starting checkpoint sync";
@@ -404,8 +413,13 @@ where
 
                 debug!(context.log(), "Downloaded finalized state");
 
-                let genesis_state = BeaconState::from_ssz_bytes(&genesis_state_bytes, &spec)
-                    .map_err(|e| format!("Unable to parse genesis state SSZ: {:?}", e))?;
+                let genesis_state = load_genesis_state(
+                    &runtime_context,
+                    config.genesis_state_url.as_deref(),
+                    config.genesis_state_url_timeout,
+                    context.log(),
+                )
+                .await?;
 
                 info!(
                     context.log(),
@@ -543,7 +557,11 @@ where
     }
 
     /// Starts the networking stack.
-    pub async fn network(mut self, config: &NetworkConfig) -> Result<Self, String> {
+    pub async fn network(
+        mut self,
+        config: &NetworkConfig,
+        beacon_processor_config: BeaconProcessorConfig,
+    ) -> Result<Self, String> {
         let beacon_chain = self
             .beacon_chain
             .clone()
@@ -564,6 +582,7 @@ where
         let (network_globals, network_senders) = NetworkService::start(
             beacon_chain,
             config,
+            beacon_processor_config,
             context.executor,
             gossipsub_registry
                 .as_mut()
@@ -677,6 +696,35 @@ where
         Ok(self)
     }
 
+    /// Immediately starts the service that periodically checks the free space available to the
+    /// database and, if enabled, the slasher database.
+    pub fn disk_monitor(self) -> Result<Self, String> {
+        let context = self
+            .runtime_context
+            .as_ref()
+            .ok_or("disk_monitor requires a runtime_context")?
+            .service_context("disk_monitor".into());
+        let beacon_chain = self
+            .beacon_chain
+            .clone()
+            .ok_or("disk_monitor requires a beacon chain")?;
+        let db_paths = self
+            .db_path
+            .iter()
+            .chain(self.freezer_db_path.iter())
+            .cloned()
+            .chain(
+                self.slasher
+                    .as_ref()
+                    .map(|slasher| slasher.config().database_path.clone()),
+            )
+            .collect();
+
+        spawn_disk_monitor(context.executor, beacon_chain, db_paths);
+
+        Ok(self)
+    }
+
     /// Consumes the builder, returning a `Client` if all necessary components have been
     /// specified.
     ///
@@ -808,8 +856,9 @@ where
                         beacon_chain.slot_clock.clone(),
                     );
 
-                    // Spawns a routine that polls the `exchange_transition_configuration` endpoint.
-                    execution_layer.spawn_transition_configuration_poll(beacon_chain.spec.clone());
+                    // Spawns a routine that polls the `exchange_transition_configuration` endpoint,
+                    // unless the chain started post-merge and there is no transition to watch for.
+                    beacon_chain.spawn_transition_configuration_poll();
                 }
 
                 // Spawn a service to publish BLS to execution changes at the Capella fork.
@@ -834,6 +883,10 @@ where
 
             start_proposer_prep_service(runtime_context.executor.clone(), beacon_chain.clone());
             start_otb_verification_service(runtime_context.executor.clone(), beacon_chain.clone());
+            start_optimistic_reprocessing_service(
+                runtime_context.executor.clone(),
+                beacon_chain.clone(),
+            );
         }
 
         Ok(Client {
@@ -1077,3 +1130,29 @@ where
         Ok(self)
     }
 }
+
+/// Obtain the genesis state from the network config, downloading it from `genesis_state_url` if
+/// the network config doesn't include it.
+async fn load_genesis_state<T: EthSpec>(
+    context: &RuntimeContext<T>,
+    genesis_state_url: Option<&str>,
+    genesis_state_url_timeout: Duration,
+    log: &Logger,
+) -> Result<BeaconState<T>, String> {
+    let eth2_network_config = context
+        .eth2_network_config
+        .as_ref()
+        .ok_or("An eth2_network_config is required to obtain the genesis state")?;
+
+    if let (Some(url), false) = (
+        genesis_state_url,
+        eth2_network_config.beacon_state_is_known(),
+    ) {
+        info!(log, "Downloading genesis state"; "url" => url);
+    }
+
+    eth2_network_config
+        .genesis_state::<T>(genesis_state_url, genesis_state_url_timeout)
+        .await?
+        .ok_or_else(|| "Genesis state is unknown".to_string())
+}
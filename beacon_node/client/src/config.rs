@@ -1,11 +1,12 @@
 use beacon_chain::validator_monitor::DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD;
 use directory::DEFAULT_ROOT_DIR;
 use environment::LoggerConfig;
-use network::NetworkConfig;
+use network::{BeaconProcessorConfig, NetworkConfig};
 use sensitive_url::SensitiveUrl;
 use serde_derive::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
 use types::{Graffiti, PublicKeyBytes};
 /// Default directory name for the freezer database under the top-level data dir.
 const DEFAULT_FREEZER_DB_DIR: &str = "freezer_db";
@@ -24,18 +25,17 @@ pub enum ClientGenesis {
     /// contract.
     #[default]
     DepositContract,
-    /// Loads the genesis state from SSZ-encoded `BeaconState` bytes.
+    /// Starts from the network's genesis state.
     ///
-    /// We include the bytes instead of the `BeaconState<E>` because the `EthSpec` type
-    /// parameter would be very annoying.
-    SszBytes { genesis_state_bytes: Vec<u8> },
+    /// The genesis state is read from the network config or, if it isn't included there,
+    /// downloaded from `Config::genesis_state_url`. The same applies to the checkpoint sync
+    /// variants below.
+    GenesisState,
     WeakSubjSszBytes {
-        genesis_state_bytes: Vec<u8>,
         anchor_state_bytes: Vec<u8>,
         anchor_block_bytes: Vec<u8>,
     },
     CheckpointSyncUrl {
-        genesis_state_bytes: Vec<u8>,
         url: SensitiveUrl,
     },
 }
@@ -56,8 +56,13 @@ pub struct Config {
     pub sync_eth1_chain: bool,
     /// Graffiti to be inserted everytime we create a block.
     pub graffiti: Graffiti,
+    /// True if the graffiti was provided via `--graffiti`.
+    pub graffiti_set_explicitly: bool,
     /// When true, automatically monitor validators using the HTTP API.
     pub validator_monitor_auto: bool,
+    /// The maximum number of validators which may be monitored before automatic monitoring stops
+    /// enrolling new validators.
+    pub validator_monitor_auto_limit: Option<usize>,
     /// A list of validator pubkeys to monitor.
     pub validator_monitor_pubkeys: Vec<PublicKeyBytes>,
     /// Once the number of monitored validators goes above this threshold, we
@@ -69,8 +74,13 @@ pub struct Config {
     /// The `genesis` field is not serialized or deserialized by `serde` to ensure it is defined
     /// via the CLI at runtime, instead of from a configuration file saved to disk.
     pub genesis: ClientGenesis,
+    /// URL to download the genesis state from, for networks which don't include a genesis state
+    /// in their network config.
+    pub genesis_state_url: Option<String>,
+    pub genesis_state_url_timeout: Duration,
     pub store: store::StoreConfig,
     pub network: network::NetworkConfig,
+    pub beacon_processor: BeaconProcessorConfig,
     pub chain: beacon_chain::ChainConfig,
     pub eth1: eth1::Config,
     pub execution_layer: Option<execution_layer::Config>,
@@ -90,19 +100,24 @@ impl Default for Config {
             freezer_db_path: None,
             log_file: PathBuf::from(""),
             genesis: <_>::default(),
+            genesis_state_url: None,
+            genesis_state_url_timeout: Duration::from_secs(180),
             store: <_>::default(),
             network: NetworkConfig::default(),
+            beacon_processor: <_>::default(),
             chain: <_>::default(),
             dummy_eth1_backend: false,
             sync_eth1_chain: false,
             eth1: <_>::default(),
             execution_layer: None,
             graffiti: Graffiti::default(),
+            graffiti_set_explicitly: false,
             http_api: <_>::default(),
             http_metrics: <_>::default(),
             monitoring_api: None,
             slasher: None,
             validator_monitor_auto: false,
+            validator_monitor_auto_limit: None,
             validator_monitor_pubkeys: vec![],
             validator_monitor_individual_tracking_threshold: DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD,
             logger_config: LoggerConfig::default(),
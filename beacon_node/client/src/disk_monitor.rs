@@ -0,0 +1,104 @@
+//! Periodically checks the free space on the volumes holding the database and slasher database.
+//!
+//! When the free space drops below the configured threshold the database is placed in low disk
+//! space mode, which pauses non-essential writes (backfill sync and historic state
+//! reconstruction) until space is freed. This avoids the node failing part-way through a write.
+use crate::metrics;
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use slog::{crit, info, Logger};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use sysinfo::{DiskExt, RefreshKind, System, SystemExt};
+use task_executor::TaskExecutor;
+use tokio::time::sleep;
+
+/// The interval between checks of the available disk space.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Spawns a service which monitors the free space on the volumes containing `db_paths`.
+pub fn spawn_disk_monitor<T: BeaconChainTypes>(
+    executor: TaskExecutor,
+    beacon_chain: Arc<BeaconChain<T>>,
+    db_paths: Vec<PathBuf>,
+) {
+    let threshold = beacon_chain.store.get_config().low_disk_space_threshold;
+    let log = executor.log().clone();
+    let mut system = System::new_with_specifics(RefreshKind::new().with_disks_list());
+
+    let monitor_future = async move {
+        loop {
+            system.refresh_disks();
+
+            // The hot, cold and slasher databases may be on different volumes, use the fullest one.
+            let free_space = db_paths
+                .iter()
+                .filter_map(|path| available_space(&system, path))
+                .min();
+
+            if let Some(free_space) = free_space {
+                metrics::set_gauge(&metrics::DISK_FREE_SPACE, free_space as i64);
+                if threshold > 0 {
+                    update_low_disk_space(&beacon_chain, free_space, threshold, &log);
+                }
+            }
+
+            sleep(CHECK_INTERVAL).await;
+        }
+    };
+
+    executor.spawn(monitor_future, "disk_monitor");
+}
+
+/// Enters or leaves low disk space mode depending on the `free_space`.
+fn update_low_disk_space<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    free_space: u64,
+    threshold: u64,
+    log: &Logger,
+) {
+    let low_disk_space = free_space < threshold;
+    let was_low_disk_space = beacon_chain.store.set_low_disk_space(low_disk_space);
+    metrics::set_gauge(&metrics::LOW_DISK_SPACE, low_disk_space as i64);
+
+    if low_disk_space {
+        // Log on every check rather than only on entry, so that the condition is hard to miss.
+        crit!(
+            log,
+            "Low disk space";
+            "free_mb" => free_space / BYTES_PER_MB,
+            "threshold_mb" => threshold / BYTES_PER_MB,
+            "info" => "backfill sync and state reconstruction are paused until space is freed",
+        );
+    } else if was_low_disk_space {
+        info!(
+            log,
+            "Disk space recovered";
+            "free_mb" => free_space / BYTES_PER_MB,
+            "threshold_mb" => threshold / BYTES_PER_MB,
+        );
+
+        // Reconstruction only runs when triggered, so restart it if it was interrupted.
+        let reconstruction_pending = beacon_chain
+            .store
+            .get_anchor_info()
+            .map_or(false, |anchor| anchor.oldest_block_slot == 0);
+        if beacon_chain.config.reconstruct_historic_states && reconstruction_pending {
+            beacon_chain.store_migrator.process_reconstruction();
+        }
+    }
+}
+
+/// Returns the space available on the volume containing `path`, i.e. the disk with the longest
+/// mount point which is a prefix of `path`.
+fn available_space(system: &System, path: &Path) -> Option<u64> {
+    let path = path.canonicalize().ok()?;
+    system
+        .disks()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
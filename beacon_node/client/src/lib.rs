@@ -2,6 +2,7 @@ extern crate slog;
 
 mod address_change_broadcast;
 pub mod config;
+mod disk_monitor;
 mod metrics;
 mod notifier;
 
@@ -16,4 +16,14 @@ lazy_static! {
         "notifier_head_slot",
         "The head slot sourced from the beacon chain notifier"
     );
+
+    pub static ref DISK_FREE_SPACE: Result<IntGauge> = try_create_int_gauge(
+        "store_disk_free_space",
+        "Free space on the fullest volume containing the database (bytes)"
+    );
+
+    pub static ref LOW_DISK_SPACE: Result<IntGauge> = try_create_int_gauge(
+        "store_low_disk_space",
+        "Set to 1 while the database is in low disk space mode and non-essential writes are paused"
+    );
 }
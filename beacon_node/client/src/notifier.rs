@@ -332,17 +332,7 @@ async fn merge_readiness_logging<T: BeaconChainTypes>(
     beacon_chain: &BeaconChain<T>,
     log: &Logger,
 ) {
-    let merge_completed = beacon_chain
-        .canonical_head
-        .cached_head()
-        .snapshot
-        .beacon_block
-        .message()
-        .body()
-        .execution_payload()
-        .map_or(false, |payload| {
-            payload.parent_hash() != ExecutionBlockHash::zero()
-        });
+    let merge_completed = beacon_chain.is_merge_complete_at_head();
 
     let has_execution_layer = beacon_chain.execution_layer.is_some();
 
@@ -247,6 +247,11 @@ impl DepositCache {
         self.finalized_deposit_count
     }
 
+    /// Returns the root of the deposit tree containing every deposit in the cache.
+    pub fn deposit_root(&self) -> Hash256 {
+        self.deposit_tree.root()
+    }
+
     /// Finalizes the cache up to `eth1_block.deposit_count`.
     pub fn finalize(&mut self, eth1_block: Eth1Block) -> Result<(), Error> {
         let deposits_to_finalize = eth1_block.deposit_count.ok_or_else(|| {
@@ -0,0 +1,245 @@
+//! Provides a list of eth1 endpoints along with the health of each, allowing the eth1 service to
+//! fail over to another endpoint when the one in use is unreachable, far behind or on the wrong
+//! chain.
+
+use crate::metrics;
+use crate::service::EndpointError;
+use execution_layer::HttpJsonRpc;
+use parking_lot::RwLock;
+use std::cmp::Ordering;
+
+/// The success and error counts of a single endpoint.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EndpointHealth {
+    /// The number of results recorded for the endpoint.
+    pub requests: u64,
+    /// The number of recorded results which were errors.
+    pub errors: u64,
+    /// The number of errors recorded since the last success.
+    pub consecutive_errors: u64,
+    /// The most recent error, cleared upon success.
+    pub last_error: Option<EndpointError>,
+}
+
+impl EndpointHealth {
+    /// Returns the fraction of recorded results which were errors.
+    pub fn error_rate(&self) -> f64 {
+        if self.requests == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.requests as f64
+        }
+    }
+
+    /// Returns `true` if the endpoint last reported a chain id other than the one configured.
+    pub fn is_wrong_chain(&self) -> bool {
+        matches!(self.last_error, Some(EndpointError::WrongChainId))
+    }
+
+    /// Orders endpoints such that healthier endpoints are `Less`.
+    ///
+    /// Endpoints on the wrong chain are always least preferred, followed by the endpoints which
+    /// have failed the most times in a row and then those with the highest error rate.
+    fn cmp_health(&self, other: &Self) -> Ordering {
+        self.is_wrong_chain()
+            .cmp(&other.is_wrong_chain())
+            .then_with(|| self.consecutive_errors.cmp(&other.consecutive_errors))
+            .then_with(|| {
+                self.error_rate()
+                    .partial_cmp(&other.error_rate())
+                    .unwrap_or(Ordering::Equal)
+            })
+    }
+}
+
+struct Endpoint {
+    client: HttpJsonRpc,
+    health: RwLock<EndpointHealth>,
+}
+
+/// A non-empty list of endpoints, in order of preference, one of which is currently in use.
+///
+/// The first endpoint is the primary and the rest are fallbacks. Clients may use different
+/// authentication, e.g. an authenticated primary with non-authenticated fallbacks.
+pub struct Endpoints {
+    endpoints: Vec<Endpoint>,
+    current: RwLock<usize>,
+}
+
+impl Endpoints {
+    /// Creates a new list of endpoints, using the first endpoint whenever it is healthy.
+    pub fn new(clients: Vec<HttpJsonRpc>) -> Result<Self, String> {
+        if clients.is_empty() {
+            return Err("At least one eth1 endpoint is required".to_string());
+        }
+
+        Ok(Self {
+            endpoints: clients
+                .into_iter()
+                .map(|client| Endpoint {
+                    client,
+                    health: RwLock::new(EndpointHealth::default()),
+                })
+                .collect(),
+            current: RwLock::new(0),
+        })
+    }
+
+    /// Returns the index of the endpoint currently in use.
+    pub fn current_index(&self) -> usize {
+        *self.current.read()
+    }
+
+    /// Returns the endpoint currently in use.
+    pub fn current(&self) -> &HttpJsonRpc {
+        self.get(self.current_index())
+    }
+
+    /// Returns the endpoint at `index`.
+    ///
+    /// ## Panics
+    ///
+    /// If `index` is out of bounds. Only indices obtained from `self` should be used.
+    pub fn get(&self, index: usize) -> &HttpJsonRpc {
+        &self.endpoints[index].client
+    }
+
+    /// Uses the endpoint at `index` for future requests, returning the index of the endpoint
+    /// previously in use.
+    pub fn set_current(&self, index: usize) -> usize {
+        std::mem::replace(&mut *self.current.write(), index)
+    }
+
+    /// Returns the indices of all endpoints in the order they should be tried.
+    ///
+    /// The primary endpoint is always tried first, so that it is used again as soon as it
+    /// recovers. The fallbacks follow, healthiest first, with fallbacks of equal health ordered as
+    /// they were configured.
+    pub fn candidates(&self) -> Vec<usize> {
+        let health = self.health();
+        let mut fallbacks = (1..self.endpoints.len()).collect::<Vec<_>>();
+        fallbacks.sort_by(|a, b| health[*a].1.cmp_health(&health[*b].1));
+        std::iter::once(0).chain(fallbacks).collect()
+    }
+
+    /// Records a successful request to the endpoint at `index`.
+    pub fn record_success(&self, index: usize) {
+        let endpoint = &self.endpoints[index];
+        let mut health = endpoint.health.write();
+        health.requests += 1;
+        health.consecutive_errors = 0;
+        health.last_error = None;
+
+        metrics::inc_counter_vec(&metrics::ENDPOINT_REQUESTS, &[&endpoint.client.to_string()]);
+    }
+
+    /// Records a failed request to the endpoint at `index`.
+    pub fn record_error(&self, index: usize, error: EndpointError) {
+        let endpoint = &self.endpoints[index];
+        let mut health = endpoint.health.write();
+        health.requests += 1;
+        health.errors += 1;
+        health.consecutive_errors += 1;
+        health.last_error = Some(error);
+
+        let endpoint_label = endpoint.client.to_string();
+        metrics::inc_counter_vec(&metrics::ENDPOINT_REQUESTS, &[&endpoint_label]);
+        metrics::inc_counter_vec(&metrics::ENDPOINT_ERRORS, &[&endpoint_label]);
+    }
+
+    /// Returns each endpoint, with any secrets redacted, alongside its health.
+    pub fn health(&self) -> Vec<(String, EndpointHealth)> {
+        self.endpoints
+            .iter()
+            .map(|endpoint| (endpoint.client.to_string(), endpoint.health.read().clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sensitive_url::SensitiveUrl;
+
+    fn endpoints(count: usize) -> Endpoints {
+        Endpoints::new(
+            (0..count)
+                .map(|i| {
+                    let url =
+                        SensitiveUrl::parse(&format!("http://localhost:{}", 8545 + i)).unwrap();
+                    HttpJsonRpc::new(url, None).unwrap()
+                })
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn empty_endpoints() {
+        assert!(Endpoints::new(vec![]).is_err());
+    }
+
+    #[test]
+    fn candidates_in_configured_order() {
+        let endpoints = endpoints(3);
+        assert_eq!(endpoints.current_index(), 0);
+        assert_eq!(endpoints.candidates(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn primary_endpoint_is_always_tried_first() {
+        let endpoints = endpoints(3);
+
+        endpoints.record_error(0, EndpointError::RequestFailed("error".into()));
+        endpoints.record_error(0, EndpointError::WrongChainId);
+        assert_eq!(endpoints.candidates(), vec![0, 1, 2]);
+        assert!(endpoints.health()[0].1.is_wrong_chain());
+    }
+
+    #[test]
+    fn failing_fallback_is_deprioritised() {
+        let endpoints = endpoints(4);
+
+        endpoints.record_error(1, EndpointError::RequestFailed("error".into()));
+        assert_eq!(endpoints.candidates(), vec![0, 2, 3, 1]);
+
+        // The fallback with the fewest consecutive errors is preferred.
+        endpoints.record_error(2, EndpointError::FarBehind);
+        endpoints.record_error(2, EndpointError::FarBehind);
+        assert_eq!(endpoints.candidates(), vec![0, 3, 1, 2]);
+
+        // A success resets the consecutive errors, but not the error rate.
+        endpoints.record_success(2);
+        endpoints.record_success(3);
+        assert_eq!(endpoints.candidates(), vec![0, 3, 2, 1]);
+
+        let health = endpoints.health();
+        assert_eq!(health[2].1.requests, 3);
+        assert_eq!(health[2].1.errors, 2);
+        assert_eq!(health[2].1.consecutive_errors, 0);
+        assert_eq!(health[2].1.last_error, None);
+    }
+
+    #[test]
+    fn wrong_chain_fallback_is_least_preferred() {
+        let endpoints = endpoints(4);
+
+        endpoints.record_error(1, EndpointError::WrongChainId);
+        for _ in 0..4 {
+            endpoints.record_error(2, EndpointError::FarBehind);
+        }
+        assert_eq!(endpoints.candidates(), vec![0, 3, 2, 1]);
+        assert!(endpoints.health()[1].1.is_wrong_chain());
+    }
+
+    #[test]
+    fn set_current_endpoint() {
+        let endpoints = endpoints(2);
+        assert_eq!(endpoints.set_current(1), 0);
+        assert_eq!(endpoints.current_index(), 1);
+        assert_eq!(
+            endpoints.current().to_string(),
+            endpoints.get(1).to_string()
+        );
+    }
+}
@@ -1,10 +1,10 @@
-use crate::service::endpoint_from_config;
+use crate::endpoints::Endpoints;
+use crate::service::endpoints_from_config;
 use crate::Config;
 use crate::{
     block_cache::{BlockCache, Eth1Block},
     deposit_cache::{DepositCache, SszDepositCache, SszDepositCacheV1, SszDepositCacheV13},
 };
-use execution_layer::HttpJsonRpc;
 use parking_lot::RwLock;
 use ssz::four_byte_option_impl;
 use ssz::{Decode, Encode};
@@ -46,7 +46,7 @@ impl DepositUpdater {
 pub struct Inner {
     pub block_cache: RwLock<BlockCache>,
     pub deposit_cache: RwLock<DepositUpdater>,
-    pub endpoint: HttpJsonRpc,
+    pub endpoints: Endpoints,
     // this gets set to Some(Eth1Data) when the deposit finalization conditions are met
     pub to_finalize: RwLock<Option<Eth1Data>>,
     pub config: RwLock<Config>,
@@ -122,8 +122,8 @@ impl SszEth1Cache {
                 cache: self.deposit_cache.to_deposit_cache()?,
                 last_processed_block: self.last_processed_block,
             }),
-            endpoint: endpoint_from_config(&config)
-                .map_err(|e| format!("Failed to create endpoint: {:?}", e))?,
+            endpoints: endpoints_from_config(&config)
+                .map_err(|e| format!("Failed to create endpoints: {:?}", e))?,
             to_finalize: RwLock::new(None),
             // Set the remote head_block zero when creating a new instance. We only care about
             // present and future eth1 nodes.
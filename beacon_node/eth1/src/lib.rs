@@ -3,15 +3,17 @@ extern crate lazy_static;
 
 mod block_cache;
 mod deposit_cache;
+mod endpoints;
 mod inner;
 mod metrics;
 mod service;
 
 pub use block_cache::{BlockCache, Eth1Block};
 pub use deposit_cache::{DepositCache, SszDepositCache, SszDepositCacheV1, SszDepositCacheV13};
+pub use endpoints::EndpointHealth;
 pub use execution_layer::http::deposit_log::DepositLog;
 pub use inner::{SszEth1Cache, SszEth1CacheV1, SszEth1CacheV13};
 pub use service::{
-    BlockCacheUpdateOutcome, Config, DepositCacheUpdateOutcome, Error, Eth1Endpoint, Service,
-    DEFAULT_CHAIN_ID,
+    BlockCacheUpdateOutcome, Config, DepositCacheUpdateOutcome, EndpointError, Error, Eth1Endpoint,
+    Service, DEFAULT_CHAIN_ID,
 };
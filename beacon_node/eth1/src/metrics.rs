@@ -24,5 +24,11 @@ lazy_static! {
     pub static ref ETH1_CONNECTED: Result<IntGauge> = try_create_int_gauge(
         "sync_eth1_connected", "Set to 1 if connected to an eth1 node, otherwise set to 0"
     );
+    pub static ref ENDPOINT_ERRORS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "eth1_endpoint_errors", "The number of eth1 request errors for each endpoint", &["endpoint"]
+    );
+    pub static ref ENDPOINT_REQUESTS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "eth1_endpoint_requests", "The number of eth1 requests for each endpoint", &["endpoint"]
+    );
 
 }
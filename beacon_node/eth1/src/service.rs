@@ -2,6 +2,7 @@ use crate::metrics;
 use crate::{
     block_cache::{BlockCache, Error as BlockCacheError, Eth1Block},
     deposit_cache::{DepositCacheInsertOutcome, Error as DepositCacheError},
+    endpoints::{EndpointHealth, Endpoints},
     inner::{DepositUpdater, Inner},
 };
 use execution_layer::auth::Auth;
@@ -238,8 +239,9 @@ pub struct DepositCacheUpdateOutcome {
     pub logs_imported: usize,
 }
 
-/// Supports either one authenticated jwt JSON-RPC endpoint **or**
-/// multiple non-authenticated endpoints with fallback.
+/// Supports either one authenticated jwt JSON-RPC endpoint **or** one non-authenticated endpoint.
+///
+/// Additional non-authenticated endpoints may be provided via `Config::fallback_endpoints`.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Eth1Endpoint {
     Auth {
@@ -264,6 +266,9 @@ impl Eth1Endpoint {
 pub struct Config {
     /// An Eth1 node (e.g., Geth) running a HTTP JSON-RPC endpoint.
     pub endpoint: Eth1Endpoint,
+    /// Non-authenticated endpoints to use whenever `endpoint` is unreachable, far behind or on the
+    /// wrong chain. `endpoint` may be authenticated.
+    pub fallback_endpoints: Vec<SensitiveUrl>,
     /// The address the `BlockCache` and `DepositCache` should assume is the canonical deposit contract.
     pub deposit_contract_address: String,
     /// The eth1 chain id where the deposit contract is deployed (Goerli/Mainnet).
@@ -345,6 +350,7 @@ impl Default for Config {
                 SensitiveUrl::parse(DEFAULT_ETH1_ENDPOINT)
                     .expect("The default Eth1 endpoint must always be a valid URL."),
             ),
+            fallback_endpoints: vec![],
             deposit_contract_address: "0x0000000000000000000000000000000000000000".into(),
             chain_id: DEFAULT_CHAIN_ID,
             deposit_contract_deploy_block: 1,
@@ -363,7 +369,22 @@ impl Default for Config {
     }
 }
 
-pub fn endpoint_from_config(config: &Config) -> Result<HttpJsonRpc, String> {
+/// Returns the primary endpoint followed by the fallback endpoints from `config`.
+pub fn endpoints_from_config(config: &Config) -> Result<Endpoints, String> {
+    let primary = endpoint_from_config(config)?;
+    let fallbacks = config
+        .fallback_endpoints
+        .iter()
+        .map(|endpoint| {
+            HttpJsonRpc::new(endpoint.clone(), Some(config.execution_timeout_multiplier))
+                .map_err(|e| format!("Failed to create eth1 json rpc client: {:?}", e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Endpoints::new(std::iter::once(primary).chain(fallbacks).collect())
+}
+
+fn endpoint_from_config(config: &Config) -> Result<HttpJsonRpc, String> {
     match config.endpoint.clone() {
         Eth1Endpoint::Auth {
             endpoint,
@@ -404,7 +425,7 @@ impl Service {
                 deposit_cache: RwLock::new(DepositUpdater::new(
                     config.deposit_contract_deploy_block,
                 )),
-                endpoint: endpoint_from_config(&config)?,
+                endpoints: endpoints_from_config(&config)?,
                 to_finalize: RwLock::new(None),
                 remote_head_block: RwLock::new(None),
                 config: RwLock::new(config),
@@ -414,8 +435,14 @@ impl Service {
         })
     }
 
+    /// Returns the endpoint currently used to update the caches.
     pub fn client(&self) -> &HttpJsonRpc {
-        &self.inner.endpoint
+        self.inner.endpoints.current()
+    }
+
+    /// Returns each configured endpoint, with any secrets redacted, alongside its health.
+    pub fn endpoint_health(&self) -> Vec<(String, EndpointHealth)> {
+        self.inner.endpoints.health()
     }
 
     /// Creates a new service, initializing the deposit tree from a snapshot.
@@ -433,7 +460,7 @@ impl Service {
             inner: Arc::new(Inner {
                 block_cache: <_>::default(),
                 deposit_cache: RwLock::new(deposit_cache),
-                endpoint: endpoint_from_config(&config)
+                endpoints: endpoints_from_config(&config)
                     .map_err(Error::FailedToInitializeFromSnapshot)?,
                 to_finalize: RwLock::new(None),
                 remote_head_block: RwLock::new(None),
@@ -609,8 +636,48 @@ impl Service {
         self.inner.config.write().lowest_cached_block_number = block_number;
     }
 
+    /// Checks the primary endpoint and then the fallbacks, healthiest first, and switches to the
+    /// first which is reachable and on the configured chain.
+    ///
+    /// Endpoints which fail the check have the failure recorded against their health, so that
+    /// failing fallbacks are tried last during subsequent updates.
+    pub async fn select_endpoint(&self) -> Result<(), String> {
+        let endpoints = &self.inner.endpoints;
+        let chain_id = self.config().chain_id.clone();
+        let mut errors = vec![];
+
+        for index in endpoints.candidates() {
+            let client = endpoints.get(index);
+            match endpoint_state(client, &chain_id, &self.log).await {
+                Ok(()) => {
+                    crate::metrics::set_gauge(&metrics::ETH1_CONNECTED, 1);
+                    let previous = endpoints.set_current(index);
+                    if previous != index {
+                        warn!(
+                            self.log,
+                            "Switched eth1 endpoint";
+                            "endpoint" => %client,
+                            "previous_endpoint" => %endpoints.get(previous),
+                        );
+                    }
+                    return Ok(());
+                }
+                Err(e) => {
+                    errors.push(format!("{}: {:?}", client, e));
+                    endpoints.record_error(index, e);
+                }
+            }
+        }
+
+        crate::metrics::set_gauge(&metrics::ETH1_CONNECTED, 0);
+        Err(format!("No usable eth1 endpoint: [{}]", errors.join(", ")))
+    }
+
     /// Update the deposit and block cache, returning an error if either fail.
     ///
+    /// The update uses the endpoint chosen by `Self::select_endpoint`. The
+    /// outcome of the update is recorded against the health of that endpoint.
+    ///
     /// ## Returns
     ///
     /// - Ok(_) if the update was successful (the cache may or may not have been modified).
@@ -619,19 +686,26 @@ impl Service {
     /// Emits logs for debugging and errors.
     pub async fn update(
         &self,
+    ) -> Result<(DepositCacheUpdateOutcome, BlockCacheUpdateOutcome), String> {
+        self.select_endpoint().await?;
+
+        let endpoints = &self.inner.endpoints;
+        let index = endpoints.current_index();
+        let result = self.update_from_current_endpoint().await;
+        match &result {
+            Ok(_) => endpoints.record_success(index),
+            Err(e) => endpoints.record_error(index, EndpointError::RequestFailed(e.clone())),
+        }
+        result
+    }
+
+    /// Update the deposit and block cache using the endpoint currently in use.
+    async fn update_from_current_endpoint(
+        &self,
     ) -> Result<(DepositCacheUpdateOutcome, BlockCacheUpdateOutcome), String> {
         let client = self.client();
-        let log = self.log.clone();
-        let chain_id = self.config().chain_id.clone();
         let node_far_behind_seconds = self.inner.config.read().node_far_behind_seconds;
 
-        match endpoint_state(client, &chain_id, &log).await {
-            Ok(()) => crate::metrics::set_gauge(&metrics::ETH1_CONNECTED, 1),
-            Err(e) => {
-                crate::metrics::set_gauge(&metrics::ETH1_CONNECTED, 0);
-                return Err(format!("Invalid endpoint state: {:?}", e));
-            }
-        }
         let (remote_head_block, new_block_numbers_deposit, new_block_numbers_block_cache) =
             get_remote_head_and_new_block_ranges(client, self, node_far_behind_seconds)
                 .await
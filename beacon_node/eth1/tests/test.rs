@@ -849,3 +849,70 @@ mod persist {
         .await;
     }
 }
+
+mod fallback {
+    use super::*;
+
+    #[tokio::test]
+    async fn update_uses_fallback_endpoint() {
+        async {
+            let log = null_logger();
+
+            let eth1 = new_anvil_instance()
+                .await
+                .expect("should start eth1 environment");
+            let deposit_contract = &eth1.deposit_contract;
+            let anvil_client = eth1.json_rpc_client();
+
+            let now = get_block_number(&anvil_client).await;
+            let config = Config {
+                // Nothing should be listening on this port.
+                endpoint: Eth1Endpoint::NoAuth(SensitiveUrl::parse("http://127.0.0.1:1").unwrap()),
+                fallback_endpoints: vec![SensitiveUrl::parse(eth1.endpoint().as_str()).unwrap()],
+                deposit_contract_address: deposit_contract.address(),
+                deposit_contract_deploy_block: now,
+                lowest_cached_block_number: now,
+                follow_distance: 0,
+                block_cache_truncation: None,
+                ..Config::default()
+            };
+            let service =
+                Service::new(config, log.clone(), MainnetEthSpec::default_spec()).unwrap();
+
+            let n = 4;
+            for _ in 0..n {
+                deposit_contract
+                    .deposit(random_deposit_data())
+                    .await
+                    .expect("should perform a deposit");
+            }
+
+            service
+                .update()
+                .await
+                .expect("should update using the fallback endpoint");
+
+            assert!(
+                service.deposit_cache_len() >= n,
+                "should have imported n deposits"
+            );
+
+            let health = service.endpoint_health();
+            assert_eq!(health.len(), 2);
+            assert_eq!(health[0].1.errors, 1, "primary should have failed");
+            assert_eq!(health[1].1.errors, 0, "fallback should not have failed");
+            assert_eq!(health[1].1.requests, 1, "fallback should have been used");
+
+            // The primary is retried first on every update, before falling back again.
+            service
+                .update()
+                .await
+                .expect("should update using the fallback endpoint");
+            let health = service.endpoint_health();
+            assert_eq!(health[0].1.requests, 2);
+            assert_eq!(health[0].1.errors, 2);
+            assert_eq!(health[1].1.requests, 2);
+        }
+        .await;
+    }
+}
@@ -193,6 +193,9 @@ pub struct BuilderParams {
     pub pubkey: PublicKeyBytes,
     pub slot: Slot,
     pub chain_health: ChainHealth,
+    /// Percentage multiplier applied to the builder's bid before comparing it to the local
+    /// payload's value. `Some(0)` disables the builder for this proposal entirely.
+    pub builder_boost_factor: Option<u64>,
 }
 
 pub enum ChainHealth {
@@ -742,9 +745,17 @@ impl<T: EthSpec> ExecutionLayer<T> {
         current_fork: ForkName,
         spec: &ChainSpec,
     ) -> Result<ProvenancedPayload<BlockProposalContents<T, Payload>>, Error> {
-        if let Some(builder) = self.builder() {
+        if builder_params.builder_boost_factor == Some(0) {
+            info!(
+                self.log(),
+                "Builder disabled for proposal, using local payload";
+                "info" => "the proposer's builder boost factor is zero",
+                "slot" => ?builder_params.slot,
+            );
+        } else if let Some(builder) = self.builder() {
             let slot = builder_params.slot;
             let pubkey = builder_params.pubkey;
+            let builder_boost_factor = builder_params.builder_boost_factor;
 
             match builder_params.chain_health {
                 ChainHealth::Healthy => {
@@ -826,13 +837,23 @@ impl<T: EthSpec> ExecutionLayer<T> {
 
                             let relay_value = relay.data.message.value;
                             let local_value = *local.block_value();
+                            // Scale the relay bid by the proposer's boost factor (a percentage),
+                            // if one was provided.
+                            let boosted_relay_value = match builder_boost_factor {
+                                Some(factor) => {
+                                    relay_value.saturating_mul(Uint256::from(factor))
+                                        / Uint256::from(100)
+                                }
+                                None => relay_value,
+                            };
                             if !self.inner.always_prefer_builder_payload {
-                                if local_value >= relay_value {
+                                if local_value >= boosted_relay_value {
                                     info!(
                                         self.log(),
                                         "Local block is more profitable than relay block";
                                         "local_block_value" => %local_value,
-                                        "relay_value" => %relay_value
+                                        "relay_value" => %relay_value,
+                                        "builder_boost_factor" => ?builder_boost_factor,
                                     );
                                     return Ok(ProvenancedPayload::Local(local));
                                 } else {
@@ -840,7 +861,8 @@ impl<T: EthSpec> ExecutionLayer<T> {
                                         self.log(),
                                         "Relay block is more profitable than local block";
                                         "local_block_value" => %local_value,
-                                        "relay_value" => %relay_value
+                                        "relay_value" => %relay_value,
+                                        "builder_boost_factor" => ?builder_boost_factor,
                                     );
                                 }
                             }
@@ -1773,7 +1795,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
         debug!(
             self.log(),
             "Sending block to builder";
-            "root" => ?block_root,
+            "block_root" => ?block_root,
         );
 
         if let Some(builder) = self.builder() {
@@ -130,6 +130,7 @@ impl<T: EthSpec> MockExecutionLayer<T> {
             pubkey: PublicKeyBytes::empty(),
             slot,
             chain_health: ChainHealth::Healthy,
+            builder_boost_factor: None,
         };
         let suggested_fee_recipient = self.el.get_suggested_fee_recipient(validator_index).await;
         let payload_attributes =
@@ -165,6 +166,7 @@ impl<T: EthSpec> MockExecutionLayer<T> {
             pubkey: PublicKeyBytes::empty(),
             slot,
             chain_health: ChainHealth::Healthy,
+            builder_boost_factor: None,
         };
         let suggested_fee_recipient = self.el.get_suggested_fee_recipient(validator_index).await;
         let payload_attributes =
@@ -18,6 +18,7 @@ use std::future::Future;
 use std::marker::PhantomData;
 use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::{runtime, sync::oneshot};
 use types::{EthSpec, ExecutionBlockHash, Uint256};
 use warp::{http::StatusCode, Filter, Rejection};
@@ -521,6 +522,8 @@ impl<T: EthSpec> Context<T> {
 pub struct Config {
     pub listen_addr: Ipv4Addr,
     pub listen_port: u16,
+    /// Delay applied to every JSON-RPC response, to simulate a slow execution engine.
+    pub response_latency: Option<Duration>,
 }
 
 impl Default for Config {
@@ -528,6 +531,7 @@ impl Default for Config {
         Self {
             listen_addr: Ipv4Addr::new(127, 0, 0, 1),
             listen_port: 0,
+            response_latency: None,
         }
     }
 }
@@ -630,6 +634,11 @@ pub fn serve<T: EthSpec>(
                 .get("id")
                 .and_then(serde_json::Value::as_u64)
                 .ok_or_else(|| warp::reject::custom(MissingIdField))?;
+
+            if let Some(latency) = ctx.config.response_latency {
+                tokio::time::sleep(latency).await;
+            }
+
             let preloaded_response = {
                 let mut preloaded_responses = ctx.preloaded_responses.lock();
                 if !preloaded_responses.is_empty() {
@@ -1,24 +1,27 @@
 //! Contains the handler for the `GET validator/duties/attester/{epoch}` endpoint.
 
 use crate::state_id::StateId;
+use crate::DUTIES_CHUNK_SIZE;
 use beacon_chain::{
     BeaconChain, BeaconChainError, BeaconChainTypes, MAXIMUM_GOSSIP_CLOCK_DISPARITY,
 };
 use eth2::types::{self as api_types};
 use slot_clock::SlotClock;
 use state_processing::state_advance::partial_state_advance;
+use std::sync::Arc;
 use types::{
     AttestationDuty, BeaconState, ChainSpec, CloneConfig, Epoch, EthSpec, Hash256, RelativeEpoch,
 };
+use warp_utils::task::blocking_task;
 
 /// The struct that is returned to the requesting HTTP client.
 type ApiDuties = api_types::DutiesResponse<Vec<api_types::AttesterData>>;
 
 /// Handles a request from the HTTP API for attester duties.
-pub fn attester_duties<T: BeaconChainTypes>(
+pub async fn attester_duties<T: BeaconChainTypes>(
     request_epoch: Epoch,
-    request_indices: &[u64],
-    chain: &BeaconChain<T>,
+    request_indices: Vec<u64>,
+    chain: Arc<BeaconChain<T>>,
 ) -> Result<ApiDuties, warp::reject::Rejection> {
     let current_epoch = chain
         .epoch()
@@ -41,7 +44,7 @@ pub fn attester_duties<T: BeaconChainTypes>(
         || request_epoch == current_epoch + 1
         || request_epoch == tolerant_current_epoch + 1
     {
-        cached_attestation_duties(request_epoch, request_indices, chain)
+        cached_attestation_duties(request_epoch, request_indices, chain).await
     } else if request_epoch > current_epoch + 1 {
         Err(warp_utils::reject::custom_bad_request(format!(
             "request epoch {} is more than one epoch past the current epoch {}",
@@ -49,28 +52,64 @@ pub fn attester_duties<T: BeaconChainTypes>(
         )))
     } else {
         // request_epoch < current_epoch
-        compute_historic_attester_duties(request_epoch, request_indices, chain)
+        blocking_task(move || {
+            compute_historic_attester_duties(request_epoch, &request_indices, &chain)
+        })
+        .await
     }
 }
 
-fn cached_attestation_duties<T: BeaconChainTypes>(
+/// Compute duties from the shuffling cache.
+///
+/// Each chunk of indices is computed in its own blocking task, so that very large requests don't
+/// tie up a single worker thread or monopolise the shuffling cache. The head block root is read
+/// once up front, so every chunk is computed against the same shuffling and dependent root.
+async fn cached_attestation_duties<T: BeaconChainTypes>(
     request_epoch: Epoch,
-    request_indices: &[u64],
-    chain: &BeaconChain<T>,
+    request_indices: Vec<u64>,
+    chain: Arc<BeaconChain<T>>,
 ) -> Result<ApiDuties, warp::reject::Rejection> {
     let head_block_root = chain.canonical_head.cached_head().head_block_root();
 
-    let (duties, dependent_root, execution_status) = chain
-        .validator_attestation_duties(request_indices, request_epoch, head_block_root)
-        .map_err(warp_utils::reject::beacon_chain_error)?;
+    // An empty request still needs one (empty) chunk to determine the dependent root.
+    let chunks = if request_indices.is_empty() {
+        vec![vec![]]
+    } else {
+        request_indices
+            .chunks(DUTIES_CHUNK_SIZE)
+            .map(<[u64]>::to_vec)
+            .collect()
+    };
 
-    convert_to_api_response(
-        duties,
-        request_indices,
-        dependent_root,
-        execution_status.is_optimistic_or_invalid(),
-        chain,
-    )
+    let mut duties = Vec::with_capacity(request_indices.len());
+    let mut dependent_root = None;
+    let mut execution_optimistic = false;
+    for chunk in chunks {
+        let chunk_chain = chain.clone();
+        let (chunk_duties, chunk_dependent_root, execution_status) = blocking_task(move || {
+            chunk_chain
+                .validator_attestation_duties(&chunk, request_epoch, head_block_root)
+                .map_err(warp_utils::reject::beacon_chain_error)
+        })
+        .await?;
+        duties.extend(chunk_duties);
+        dependent_root = Some(chunk_dependent_root);
+        execution_optimistic |= execution_status.is_optimistic_or_invalid();
+    }
+    let dependent_root = dependent_root.ok_or_else(|| {
+        warp_utils::reject::custom_server_error("no duties chunks were computed".into())
+    })?;
+
+    blocking_task(move || {
+        convert_to_api_response(
+            duties,
+            &request_indices,
+            dependent_root,
+            execution_optimistic,
+            &chain,
+        )
+    })
+    .await
 }
 
 /// Compute some attester duties by reading a `BeaconState` from disk, completely ignoring the
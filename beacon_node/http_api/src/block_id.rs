@@ -1,10 +1,13 @@
-use crate::{state_id::checkpoint_slot_and_execution_optimistic, ExecutionOptimistic};
+use crate::state_id::{
+    checkpoint_slot_and_execution_optimistic, slot_execution_optimistic_and_finalized,
+};
+use crate::ExecutionOptimistic;
 use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes, WhenSlotSkipped};
 use eth2::types::BlockId as CoreBlockId;
 use std::fmt;
 use std::str::FromStr;
 use std::sync::Arc;
-use types::{EthSpec, Hash256, SignedBeaconBlock, SignedBlindedBeaconBlock, Slot};
+use types::{Hash256, SignedBeaconBlock, SignedBlindedBeaconBlock, Slot};
 
 /// Wraps `eth2::types::BlockId` and provides a simple way to obtain a block or root for a given
 /// `BlockId`.
@@ -55,27 +58,14 @@ impl BlockId {
                 Ok((justified_checkpoint.root, execution_optimistic, false))
             }
             CoreBlockId::Slot(slot) => {
-                let execution_optimistic = chain
-                    .is_optimistic_or_invalid_head()
-                    .map_err(warp_utils::reject::beacon_chain_error)?;
+                let (execution_optimistic, finalized) =
+                    slot_execution_optimistic_and_finalized(chain, *slot)?;
                 let root = chain
                     .block_root_at_slot(*slot, WhenSlotSkipped::None)
                     .map_err(warp_utils::reject::beacon_chain_error)
                     .and_then(|root_opt| {
-                        root_opt.ok_or_else(|| {
-                            warp_utils::reject::custom_not_found(format!(
-                                "beacon block at slot {}",
-                                slot
-                            ))
-                        })
+                        root_opt.ok_or_else(|| block_at_slot_not_found(chain, *slot))
                     })?;
-                let finalized = *slot
-                    <= chain
-                        .canonical_head
-                        .cached_head()
-                        .finalized_checkpoint()
-                        .epoch
-                        .start_slot(T::EthSpec::slots_per_epoch());
                 Ok((root, execution_optimistic, finalized))
             }
             CoreBlockId::Root(root) => {
@@ -122,7 +112,7 @@ impl BlockId {
         }
     }
 
-    /// Return the `SignedBeaconBlock` identified by `self`.
+    /// Return the `SignedBlindedBeaconBlock` identified by `self`.
     pub fn blinded_block<T: BeaconChainTypes>(
         &self,
         chain: &BeaconChain<T>,
@@ -140,46 +130,37 @@ impl BlockId {
                     .canonical_head
                     .head_and_execution_status()
                     .map_err(warp_utils::reject::beacon_chain_error)?;
-                Ok((
-                    cached_head.snapshot.beacon_block.clone_as_blinded(),
-                    execution_status.is_optimistic_or_invalid(),
-                    false,
-                ))
-            }
-            CoreBlockId::Slot(slot) => {
-                let (root, execution_optimistic, finalized) = self.root(chain)?;
-                chain
-                    .get_blinded_block(&root)
-                    .map_err(warp_utils::reject::beacon_chain_error)
-                    .and_then(|block_opt| match block_opt {
-                        Some(block) => {
-                            if block.slot() != *slot {
-                                return Err(warp_utils::reject::custom_not_found(format!(
-                                    "slot {} was skipped",
-                                    slot
-                                )));
-                            }
-                            Ok((block, execution_optimistic, finalized))
-                        }
-                        None => Err(warp_utils::reject::custom_not_found(format!(
+                let head_block_root = cached_head.head_block_root();
+                let block = chain
+                    .get_blinded_block(&head_block_root)
+                    .map_err(warp_utils::reject::beacon_chain_error)?
+                    .ok_or_else(|| {
+                        warp_utils::reject::custom_not_found(format!(
                             "beacon block with root {}",
-                            root
-                        ))),
-                    })
+                            head_block_root
+                        ))
+                    })?;
+                Ok((block, execution_status.is_optimistic_or_invalid(), false))
             }
             _ => {
                 let (root, execution_optimistic, finalized) = self.root(chain)?;
                 let block = chain
                     .get_blinded_block(&root)
-                    .map_err(warp_utils::reject::beacon_chain_error)
-                    .and_then(|root_opt| {
-                        root_opt.ok_or_else(|| {
-                            warp_utils::reject::custom_not_found(format!(
-                                "beacon block with root {}",
-                                root
-                            ))
-                        })
+                    .map_err(warp_utils::reject::beacon_chain_error)?
+                    .ok_or_else(|| {
+                        warp_utils::reject::custom_not_found(format!(
+                            "beacon block with root {}",
+                            root
+                        ))
                     })?;
+                if let CoreBlockId::Slot(slot) = &self.0 {
+                    if block.slot() != *slot {
+                        return Err(warp_utils::reject::custom_not_found(format!(
+                            "slot {} was skipped",
+                            slot
+                        )));
+                    }
+                }
                 Ok((block, execution_optimistic, finalized))
             }
         }
@@ -252,6 +233,25 @@ impl BlockId {
     }
 }
 
+/// Returns a "not found" rejection for a block at `slot`, explaining when the block is missing
+/// because it is older than the oldest block stored by this node (i.e. backfill sync is incomplete
+/// or disabled).
+fn block_at_slot_not_found<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    slot: Slot,
+) -> warp::Rejection {
+    match chain.store.get_anchor_info() {
+        Some(anchor) if slot != chain.spec.genesis_slot && slot < anchor.oldest_block_slot => {
+            warp_utils::reject::custom_not_found(format!(
+                "beacon block at slot {} is unavailable, the oldest block stored by this node \
+                 is at slot {}",
+                slot, anchor.oldest_block_slot
+            ))
+        }
+        _ => warp_utils::reject::custom_not_found(format!("beacon block at slot {}", slot)),
+    }
+}
+
 impl FromStr for BlockId {
     type Err = String;
 
@@ -12,8 +12,11 @@ mod block_packing_efficiency;
 mod block_rewards;
 mod database;
 mod metrics;
+mod op_pool;
+mod produce_block;
 mod proposer_duties;
 mod publish_blocks;
+mod slasher;
 mod standard_block_rewards;
 mod state_id;
 mod sync_committee_rewards;
@@ -21,6 +24,7 @@ mod sync_committees;
 pub mod test_utils;
 mod ui;
 mod validator_inclusion;
+mod validators;
 mod version;
 
 use beacon_chain::{
@@ -61,7 +65,7 @@ use tokio_stream::{wrappers::BroadcastStream, StreamExt};
 use types::{
     Attestation, AttestationData, AttestationShufflingId, AttesterSlashing, BeaconStateError,
     BlindedPayload, CommitteeCache, ConfigAndPreset, Epoch, EthSpec, ForkName, FullPayload,
-    ProposerPreparationData, ProposerSlashing, RelativeEpoch, SignedAggregateAndProof,
+    Graffiti, ProposerPreparationData, ProposerSlashing, RelativeEpoch, SignedAggregateAndProof,
     SignedBeaconBlock, SignedBlindedBeaconBlock, SignedBlsToExecutionChange,
     SignedContributionAndProof, SignedValidatorRegistrationData, SignedVoluntaryExit, Slot,
     SyncCommitteeMessage, SyncContributionData,
@@ -69,6 +73,7 @@ use types::{
 use version::{
     add_consensus_version_header, execution_optimistic_finalized_fork_versioned_response,
     fork_versioned_response, inconsistent_fork_rejection, unsupported_version_rejection, V1, V2,
+    V3,
 };
 use warp::http::StatusCode;
 use warp::sse::Event;
@@ -89,6 +94,10 @@ const API_PREFIX: &str = "eth";
 /// finalized head.
 const SYNC_TOLERANCE_EPOCHS: u64 = 8;
 
+/// The maximum number of validator indices to process in a single blocking task when computing
+/// duties. Larger requests are split into chunks, each computed in its own task.
+const DUTIES_CHUNK_SIZE: usize = 8_192;
+
 /// A custom type which allows for both unsecured and TLS-enabled HTTP servers.
 type HttpServer = (SocketAddr, Pin<Box<dyn Future<Output = ()> + Send>>);
 
@@ -601,47 +610,31 @@ pub fn serve<T: BeaconChainTypes>(
              query_res: Result<api_types::ValidatorBalancesQuery, warp::Rejection>| {
                 blocking_json_task(move || {
                     let query = query_res?;
-                    let (data, execution_optimistic, finalized) = state_id
-                        .map_state_and_execution_optimistic_and_finalized(
-                            &chain,
-                            |state, execution_optimistic, finalized| {
-                                Ok((
-                                    state
-                                        .validators()
-                                        .iter()
-                                        .zip(state.balances().iter())
-                                        .enumerate()
-                                        // filter by validator id(s) if provided
-                                        .filter(|(index, (validator, _))| {
-                                            query.id.as_ref().map_or(true, |ids| {
-                                                ids.iter().any(|id| match id {
-                                                    ValidatorId::PublicKey(pubkey) => {
-                                                        &validator.pubkey == pubkey
-                                                    }
-                                                    ValidatorId::Index(param_index) => {
-                                                        *param_index == *index as u64
-                                                    }
-                                                })
-                                            })
-                                        })
-                                        .map(|(index, (_, balance))| {
-                                            Some(api_types::ValidatorBalanceData {
-                                                index: index as u64,
-                                                balance: *balance,
-                                            })
-                                        })
-                                        .collect::<Vec<_>>(),
-                                    execution_optimistic,
-                                    finalized,
-                                ))
-                            },
-                        )?;
+                    validators::get_beacon_state_validator_balances(
+                        state_id,
+                        chain,
+                        query.id.as_deref(),
+                    )
+                })
+            },
+        );
 
-                    Ok(api_types::ExecutionOptimisticFinalizedResponse {
-                        data,
-                        execution_optimistic: Some(execution_optimistic),
-                        finalized: Some(finalized),
-                    })
+    // POST beacon/states/{state_id}/validator_balances
+    let post_beacon_state_validator_balances = beacon_states_path
+        .clone()
+        .and(warp::path("validator_balances"))
+        .and(warp::path::end())
+        .and(warp::body::bytes())
+        .and_then(
+            |state_id: StateId, chain: Arc<BeaconChain<T>>, body: warp::hyper::body::Bytes| {
+                blocking_response_task(move || {
+                    let ids = validators::parse_validator_ids(&body)?;
+                    let response = validators::get_beacon_state_validator_balances(
+                        state_id,
+                        chain,
+                        ids.as_deref(),
+                    )?;
+                    validators::stream_validator_balances(response)
                 })
             },
         );
@@ -1089,9 +1082,19 @@ pub fn serve<T: BeaconChainTypes>(
                                     .canonical_head
                                     .head_and_execution_status()
                                     .map_err(warp_utils::reject::beacon_chain_error)?;
+                                let root = cached_head.head_block_root();
+                                let block = chain
+                                    .get_blinded_block(&root)
+                                    .map_err(warp_utils::reject::beacon_chain_error)?
+                                    .ok_or_else(|| {
+                                        warp_utils::reject::custom_not_found(format!(
+                                            "beacon block with root {}",
+                                            root
+                                        ))
+                                    })?;
                                 (
-                                    cached_head.head_block_root(),
-                                    cached_head.snapshot.beacon_block.clone_as_blinded(),
+                                    root,
+                                    block,
                                     execution_status.is_optimistic_or_invalid(),
                                     false,
                                 )
@@ -2069,12 +2072,18 @@ pub fn serve<T: BeaconChainTypes>(
                                 e
                             )),
                         })?;
-                    let execution_optimistic =
-                        chain.is_optimistic_or_invalid_head().unwrap_or_default();
+                    // Rewards are computed from the state at the end of the following epoch.
+                    let (execution_optimistic, finalized) =
+                        state_id::slot_execution_optimistic_and_finalized(
+                            &chain,
+                            (epoch + 1).end_slot(T::EthSpec::slots_per_epoch()),
+                        )?;
 
                     Ok(attestation_rewards)
                         .map(api_types::GenericResponse::from)
-                        .map(|resp| resp.add_execution_optimistic(execution_optimistic))
+                        .map(|resp| {
+                            resp.add_execution_optimistic_finalized(execution_optimistic, finalized)
+                        })
                 })
             },
         );
@@ -2685,15 +2694,29 @@ pub fn serve<T: BeaconChainTypes>(
                         ProduceBlockVerification::VerifyRandao
                     };
 
-                let (block, _) = chain
+                if endpoint_version == V3 {
+                    return produce_block::produce_block_v3(
+                        chain,
+                        slot,
+                        randao_reveal,
+                        query.graffiti.map(Into::into),
+                        randao_verification,
+                        query.builder_boost_factor,
+                    )
+                    .await;
+                }
+
+                let block = chain
                     .produce_block_with_verification::<FullPayload<T::EthSpec>>(
                         randao_reveal,
                         slot,
                         query.graffiti.map(Into::into),
                         randao_verification,
+                        None,
                     )
                     .await
-                    .map_err(warp_utils::reject::block_production_error)?;
+                    .map_err(warp_utils::reject::block_production_error)?
+                    .block;
                 let fork_name = block
                     .to_ref()
                     .fork_name(&chain.spec)
@@ -2741,15 +2764,17 @@ pub fn serve<T: BeaconChainTypes>(
                         ProduceBlockVerification::VerifyRandao
                     };
 
-                let (block, _) = chain
+                let block = chain
                     .produce_block_with_verification::<BlindedPayload<T::EthSpec>>(
                         randao_reveal,
                         slot,
                         query.graffiti.map(Into::into),
                         randao_verification,
+                        query.builder_boost_factor,
                     )
                     .await
-                    .map_err(warp_utils::reject::block_production_error)?;
+                    .map_err(warp_utils::reject::block_production_error)?
+                    .block;
                 let fork_name = block
                     .to_ref()
                     .fork_name(&chain.spec)
@@ -2841,9 +2866,12 @@ pub fn serve<T: BeaconChainTypes>(
         .and(chain_filter.clone())
         .and_then(
             |epoch: Epoch, indices: api_types::ValidatorIndexData, chain: Arc<BeaconChain<T>>| {
-                blocking_json_task(move || {
-                    attester_duties::attester_duties(epoch, &indices.0, &chain)
-                })
+                let indices = indices.0;
+                async move {
+                    attester_duties::attester_duties(epoch, indices, chain)
+                        .await
+                        .map(|duties| warp::reply::json(&duties).into_response())
+                }
             },
         );
 
@@ -2863,9 +2891,12 @@ pub fn serve<T: BeaconChainTypes>(
         .and(chain_filter.clone())
         .and_then(
             |epoch: Epoch, indices: api_types::ValidatorIndexData, chain: Arc<BeaconChain<T>>| {
-                blocking_json_task(move || {
-                    sync_committees::sync_committee_duties(epoch, &indices.0, &chain)
-                })
+                let indices = indices.0;
+                async move {
+                    sync_committees::sync_committee_duties(epoch, indices, chain)
+                        .await
+                        .map(|duties| warp::reply::json(&duties).into_response())
+                }
             },
         );
 
@@ -3116,6 +3147,14 @@ pub fn serve<T: BeaconChainTypes>(
                     "count" => preparation_data.len(),
                 );
 
+                {
+                    let mut validator_monitor = chain.validator_monitor.write();
+                    for preparation in &preparation_data {
+                        validator_monitor
+                            .auto_register_local_validator(preparation.validator_index);
+                    }
+                }
+
                 execution_layer
                     .update_proposer_preparation(current_epoch, &preparation_data)
                     .await;
@@ -3206,6 +3245,14 @@ pub fn serve<T: BeaconChainTypes>(
                     })
                     .unzip();
 
+                {
+                    let mut validator_monitor = chain.validator_monitor.write();
+                    for preparation in &preparation_data {
+                        validator_monitor
+                            .auto_register_local_validator(preparation.validator_index);
+                    }
+                }
+
                 // Update the prepare beacon proposer cache based on this request.
                 execution_layer
                     .update_proposer_preparation(current_epoch, &preparation_data)
@@ -3360,6 +3407,65 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/health/ready
+    let get_lighthouse_health_ready = warp::path("lighthouse")
+        .and(warp::path("health"))
+        .and(warp::path("ready"))
+        .and(warp::path::end())
+        .and(network_globals.clone())
+        .and(chain_filter.clone())
+        .and_then(
+            |network_globals: Arc<NetworkGlobals<T::EthSpec>>, chain: Arc<BeaconChain<T>>| {
+                async move {
+                    let el_offline = if let Some(el) = &chain.execution_layer {
+                        el.is_offline_or_erroring().await
+                    } else {
+                        true
+                    };
+
+                    blocking_response_task(move || {
+                        let is_optimistic = chain
+                            .is_optimistic_or_invalid_head()
+                            .map_err(warp_utils::reject::beacon_chain_error)?;
+                        let is_synced = network_globals.sync_state.read().is_synced();
+                        let is_ready = is_synced && !is_optimistic && !el_offline;
+
+                        let readiness = eth2::lighthouse::Readiness {
+                            is_ready,
+                            is_synced,
+                            is_optimistic,
+                            el_offline,
+                            head_slot: chain.canonical_head.cached_head().head_slot(),
+                            current_slot: chain.slot_clock.now(),
+                        };
+
+                        // Return 503 so that load balancers and orchestrators stop routing
+                        // traffic to a node which can't serve duties.
+                        let status = if is_ready {
+                            warp::http::StatusCode::OK
+                        } else {
+                            warp::http::StatusCode::SERVICE_UNAVAILABLE
+                        };
+
+                        Ok(warp::reply::with_status(
+                            warp::reply::json(&api_types::GenericResponse::from(readiness)),
+                            status,
+                        ))
+                    })
+                    .await
+                }
+            },
+        );
+
+    // GET lighthouse/health/live
+    //
+    // Responding at all shows that the HTTP server and blocking thread pool are responsive.
+    let get_lighthouse_health_live = warp::path("lighthouse")
+        .and(warp::path("health"))
+        .and(warp::path("live"))
+        .and(warp::path::end())
+        .and_then(|| blocking_json_task(move || Ok(api_types::GenericResponse::from(true))));
+
     // GET lighthouse/ui/health
     let get_lighthouse_ui_health = warp::path("lighthouse")
         .and(warp::path("ui"))
@@ -3480,7 +3586,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("peers"))
         .and(warp::path("connected"))
         .and(warp::path::end())
-        .and(network_globals)
+        .and(network_globals.clone())
         .and_then(|network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
             blocking_json_task(move || {
                 Ok(network_globals
@@ -3495,6 +3601,37 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/peers/{peer_id}
+    let get_lighthouse_peer = warp::path("lighthouse")
+        .and(warp::path("peers"))
+        .and(warp::path::param::<String>())
+        .and(warp::path::end())
+        .and(network_globals)
+        .and_then(
+            |requested_peer_id: String, network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                blocking_json_task(move || {
+                    let peer_id = requested_peer_id.parse::<PeerId>().map_err(|e| {
+                        warp_utils::reject::custom_bad_request(format!("invalid peer id: {}", e))
+                    })?;
+
+                    let peers = network_globals.peers.read();
+                    let peer_info = peers.peer_info(&peer_id).ok_or_else(|| {
+                        warp_utils::reject::custom_not_found("peer not found.".to_string())
+                    })?;
+
+                    Ok(eth2::lighthouse::PeerDetails {
+                        peer_id: peer_id.to_string(),
+                        score: peer_info.score().details(),
+                        gossipsub_subnets: peer_info.subnets().copied().collect(),
+                        long_lived_subnets: peer_info.long_lived_subnets(),
+                        protocols: peer_info.protocols().to_vec(),
+                        peer_actions: peer_info.peer_actions().cloned().collect(),
+                        connection_history: peer_info.connection_history().cloned().collect(),
+                    })
+                })
+            },
+        );
+
     // GET lighthouse/proto_array
     let get_lighthouse_proto_array = warp::path("lighthouse")
         .and(warp::path("proto_array"))
@@ -3591,12 +3728,35 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/eth1/block_cache/summary
+    let get_lighthouse_eth1_block_cache_summary = warp::path("lighthouse")
+        .and(warp::path("eth1"))
+        .and(warp::path("block_cache"))
+        .and(warp::path("summary"))
+        .and(warp::path::end())
+        .and(eth1_service_filter.clone())
+        .and_then(|eth1_service: eth1::Service| {
+            blocking_json_task(move || {
+                let block_cache = eth1_service.blocks().read();
+                Ok(api_types::GenericResponse::from(
+                    eth2::lighthouse::Eth1BlockCacheSummary {
+                        block_count: block_cache.len() as u64,
+                        lowest_block_number: block_cache.lowest_block_number(),
+                        highest_block_number: block_cache.highest_block_number(),
+                        earliest_block_timestamp: block_cache.earliest_block_timestamp(),
+                        latest_block_timestamp: block_cache.latest_block_timestamp(),
+                        latest_block: block_cache.latest_block().cloned(),
+                    },
+                ))
+            })
+        });
+
     // GET lighthouse/eth1/deposit_cache
     let get_lighthouse_eth1_deposit_cache = warp::path("lighthouse")
         .and(warp::path("eth1"))
         .and(warp::path("deposit_cache"))
         .and(warp::path::end())
-        .and(eth1_service_filter)
+        .and(eth1_service_filter.clone())
         .and_then(|eth1_service: eth1::Service| {
             blocking_json_task(move || {
                 Ok(api_types::GenericResponse::from(
@@ -3611,6 +3771,28 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/eth1/deposit_cache/summary
+    let get_lighthouse_eth1_deposit_cache_summary = warp::path("lighthouse")
+        .and(warp::path("eth1"))
+        .and(warp::path("deposit_cache"))
+        .and(warp::path("summary"))
+        .and(warp::path::end())
+        .and(eth1_service_filter)
+        .and_then(|eth1_service: eth1::Service| {
+            blocking_json_task(move || {
+                let deposits = eth1_service.deposits().read();
+                Ok(api_types::GenericResponse::from(
+                    eth2::lighthouse::Eth1DepositCacheSummary {
+                        deposit_count: deposits.cache.len() as u64,
+                        finalized_deposit_count: deposits.cache.finalized_deposit_count(),
+                        deposit_root: deposits.cache.deposit_root(),
+                        latest_deposit_block_number: deposits.cache.latest_block_number(),
+                        last_processed_block: deposits.last_processed_block,
+                    },
+                ))
+            })
+        });
+
     // GET lighthouse/beacon/states/{state_id}/ssz
     let get_lighthouse_beacon_states_ssz = warp::path("lighthouse")
         .and(warp::path("beacon"))
@@ -3655,6 +3837,51 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/graffiti
+    let get_lighthouse_graffiti = warp::path("lighthouse")
+        .and(warp::path("graffiti"))
+        .and(warp::path::end())
+        .and(chain_filter.clone())
+        .and_then(|chain: Arc<BeaconChain<T>>| {
+            blocking_json_task(move || {
+                Ok(api_types::GenericResponse::from(
+                    eth2::lighthouse::GraffitiData {
+                        graffiti: chain.graffiti(),
+                    },
+                ))
+            })
+        });
+
+    // POST lighthouse/graffiti
+    let post_lighthouse_graffiti = warp::path("lighthouse")
+        .and(warp::path("graffiti"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(chain_filter.clone())
+        .and(log_filter.clone())
+        .and_then(
+            |request: eth2::lighthouse::UpdateGraffitiRequest,
+             chain: Arc<BeaconChain<T>>,
+             log: Logger| {
+                blocking_json_task(move || {
+                    let graffiti: Graffiti = request.graffiti.into();
+                    chain.set_graffiti(graffiti).map_err(|e| {
+                        warp_utils::reject::custom_server_error(format!(
+                            "unable to persist graffiti: {:?}",
+                            e
+                        ))
+                    })?;
+                    info!(
+                        log,
+                        "Updated graffiti";
+                        "graffiti" => graffiti.as_utf8_lossy(),
+                        "source" => "http_api"
+                    );
+                    Ok(())
+                })
+            },
+        );
+
     let database_path = warp::path("lighthouse").and(warp::path("database"));
 
     // GET lighthouse/database/info
@@ -3664,6 +3891,21 @@ pub fn serve<T: BeaconChainTypes>(
         .and(chain_filter.clone())
         .and_then(|chain: Arc<BeaconChain<T>>| blocking_json_task(move || database::info(chain)));
 
+    // GET lighthouse/slasher/stats
+    let get_lighthouse_slasher_stats = warp::path("lighthouse")
+        .and(warp::path("slasher"))
+        .and(warp::path("stats"))
+        .and(warp::path::end())
+        .and(chain_filter.clone())
+        .and_then(|chain: Arc<BeaconChain<T>>| blocking_json_task(move || slasher::stats(chain)));
+
+    // GET lighthouse/op_pool
+    let get_lighthouse_op_pool = warp::path("lighthouse")
+        .and(warp::path("op_pool"))
+        .and(warp::path::end())
+        .and(chain_filter.clone())
+        .and_then(|chain: Arc<BeaconChain<T>>| blocking_json_task(move || op_pool::info(chain)));
+
     // POST lighthouse/database/reconstruct
     let post_lighthouse_database_reconstruct = database_path
         .and(warp::path("reconstruct"))
@@ -3801,9 +4043,24 @@ pub fn serve<T: BeaconChainTypes>(
                                 api_types::EventTopic::LateHead => {
                                     event_handler.subscribe_late_head()
                                 }
+                                api_types::EventTopic::SyncState => {
+                                    event_handler.subscribe_sync_state()
+                                }
+                                api_types::EventTopic::MissedBlock => {
+                                    event_handler.subscribe_missed_block()
+                                }
+                                api_types::EventTopic::MissedAttestation => {
+                                    event_handler.subscribe_missed_attestation()
+                                }
                                 api_types::EventTopic::BlockReward => {
                                     event_handler.subscribe_block_reward()
                                 }
+                                api_types::EventTopic::SlasherAttestation => {
+                                    event_handler.subscribe_slasher_attestation()
+                                }
+                                api_types::EventTopic::SlasherBlockHeader => {
+                                    event_handler.subscribe_slasher_block_header()
+                                }
                             };
 
                             receivers.push(BroadcastStream::new(receiver).map(|msg| {
@@ -3921,21 +4178,29 @@ pub fn serve<T: BeaconChainTypes>(
                 .uor(get_validator_aggregate_attestation)
                 .uor(get_validator_sync_committee_contribution)
                 .uor(get_lighthouse_health)
+                .uor(get_lighthouse_health_ready)
+                .uor(get_lighthouse_health_live)
                 .uor(get_lighthouse_ui_health)
                 .uor(get_lighthouse_ui_validator_count)
                 .uor(get_lighthouse_syncing)
                 .uor(get_lighthouse_nat)
                 .uor(get_lighthouse_peers)
                 .uor(get_lighthouse_peers_connected)
+                .uor(get_lighthouse_peer)
                 .uor(get_lighthouse_proto_array)
                 .uor(get_lighthouse_validator_inclusion_global)
                 .uor(get_lighthouse_validator_inclusion)
                 .uor(get_lighthouse_eth1_syncing)
                 .uor(get_lighthouse_eth1_block_cache)
+                .uor(get_lighthouse_eth1_block_cache_summary)
                 .uor(get_lighthouse_eth1_deposit_cache)
+                .uor(get_lighthouse_eth1_deposit_cache_summary)
                 .uor(get_lighthouse_beacon_states_ssz)
                 .uor(get_lighthouse_staking)
+                .uor(get_lighthouse_graffiti)
                 .uor(get_lighthouse_database_info)
+                .uor(get_lighthouse_slasher_stats)
+                .uor(get_lighthouse_op_pool)
                 .uor(get_lighthouse_block_rewards)
                 .uor(get_lighthouse_attestation_performance)
                 .uor(get_lighthouse_block_packing_efficiency)
@@ -3948,6 +4213,7 @@ pub fn serve<T: BeaconChainTypes>(
         .uor(
             warp::post().and(
                 post_beacon_blocks
+                    .uor(post_beacon_state_validator_balances)
                     .uor(post_beacon_blinded_blocks)
                     .uor(post_beacon_blocks_v2)
                     .uor(post_beacon_blinded_blocks_v2)
@@ -3968,6 +4234,7 @@ pub fn serve<T: BeaconChainTypes>(
                     .uor(post_validator_prepare_beacon_proposer)
                     .uor(post_validator_register_validator)
                     .uor(post_lighthouse_liveness)
+                    .uor(post_lighthouse_graffiti)
                     .uor(post_lighthouse_database_reconstruct)
                     .uor(post_lighthouse_database_historical_blocks)
                     .uor(post_lighthouse_block_rewards)
@@ -3979,6 +4246,8 @@ pub fn serve<T: BeaconChainTypes>(
         .recover(warp_utils::reject::handle_rejection)
         .with(slog_logging(log.clone()))
         .with(prometheus_metrics())
+        // Create a `tracing` span for each request.
+        .with(warp::trace::request())
         // Add a `Server` header.
         .map(|reply| warp::reply::with_header(reply, "Server", &version_with_platform()))
         .with(cors_builder.build())
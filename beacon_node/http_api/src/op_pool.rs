@@ -0,0 +1,24 @@
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{AttestationPoolInfo, OperationPoolInfo};
+use std::sync::Arc;
+
+pub fn info<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+) -> Result<OperationPoolInfo, warp::Rejection> {
+    let op_pool = &chain.op_pool;
+    let attestation_stats = op_pool.attestation_stats();
+
+    Ok(OperationPoolInfo {
+        attestations: AttestationPoolInfo {
+            num_attestations: attestation_stats.num_attestations as u64,
+            num_attestation_data: attestation_stats.num_attestation_data as u64,
+            max_aggregates_per_data: attestation_stats.max_aggregates_per_data as u64,
+            num_attesting_indices: attestation_stats.num_attesting_indices as u64,
+        },
+        num_sync_contributions: op_pool.num_sync_contributions() as u64,
+        num_attester_slashings: op_pool.num_attester_slashings() as u64,
+        num_proposer_slashings: op_pool.num_proposer_slashings() as u64,
+        num_voluntary_exits: op_pool.num_voluntary_exits() as u64,
+        num_bls_to_execution_changes: op_pool.num_bls_to_execution_changes() as u64,
+    })
+}
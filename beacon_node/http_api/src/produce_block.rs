@@ -0,0 +1,110 @@
+//! Handler for the `GET v3/validator/blocks/{slot}` endpoint.
+
+use crate::version::{
+    add_consensus_version_header, add_execution_payload_blinded_header,
+    add_execution_payload_value_header, inconsistent_fork_rejection,
+};
+use beacon_chain::{BeaconBlockResponse, BeaconChain, BeaconChainTypes, ProduceBlockVerification};
+use eth2::types::ProduceBlockV3Response;
+use serde::Serialize;
+use std::sync::Arc;
+use tree_hash::TreeHash;
+use types::{
+    AbstractExecPayload, BlindedPayload, ExecPayload, ExecutionBlockHash, ForkName, FullPayload,
+    Graffiti, Signature, SignedBeaconBlock, Slot, Uint256,
+};
+use warp::{reply::Response, Rejection};
+
+/// Produce a block at `slot`, returning a full block if the payload was built by the local
+/// execution engine and a blinded block if it was built by a builder.
+///
+/// The block is always produced as a blinded block, since that is the only flow in which the
+/// execution layer weighs builder and local payloads against each other. Local payloads are kept
+/// in the execution layer's payload cache, which lets us recover the full block afterwards.
+pub async fn produce_block_v3<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    slot: Slot,
+    randao_reveal: Signature,
+    graffiti: Option<Graffiti>,
+    randao_verification: ProduceBlockVerification,
+    builder_boost_factor: Option<u64>,
+) -> Result<Response, Rejection> {
+    let BeaconBlockResponse {
+        block,
+        execution_payload_value,
+        ..
+    } = chain
+        .produce_block_with_verification::<BlindedPayload<T::EthSpec>>(
+            randao_reveal,
+            slot,
+            graffiti,
+            randao_verification,
+            builder_boost_factor,
+        )
+        .await
+        .map_err(warp_utils::reject::block_production_error)?;
+    let fork_name = block
+        .to_ref()
+        .fork_name(&chain.spec)
+        .map_err(inconsistent_fork_rejection)?;
+    let execution_payload_value = execution_payload_value.unwrap_or_default();
+
+    let full_payload_opt = match block.body().execution_payload() {
+        // Blocks prior to the merge fork have no payload to hide.
+        Err(_) => Some(None),
+        // Blocks prior to the merge transition contain an empty payload.
+        Ok(payload_header) if payload_header.block_hash() == ExecutionBlockHash::zero() => {
+            let payload = FullPayload::<T::EthSpec>::default_at_fork(fork_name).map_err(|e| {
+                warp_utils::reject::custom_server_error(format!(
+                    "Default payload construction error: {e:?}"
+                ))
+            })?;
+            Some(Some(payload.into()))
+        }
+        // Payloads from the local execution engine are cached; builder payloads are not.
+        Ok(payload_header) => chain
+            .execution_layer
+            .as_ref()
+            .and_then(|el| el.get_payload_by_root(&payload_header.tree_hash_root()))
+            .map(Some),
+    };
+
+    let full_block_opt = full_payload_opt.and_then(|payload_opt| {
+        SignedBeaconBlock::from_block(block.clone(), Signature::empty())
+            .try_into_full_block(payload_opt)
+    });
+
+    if let Some(full_block) = full_block_opt {
+        let (full_block, _) = full_block.deconstruct();
+        Ok(build_response(
+            fork_name,
+            false,
+            execution_payload_value,
+            full_block,
+        ))
+    } else {
+        Ok(build_response(
+            fork_name,
+            true,
+            execution_payload_value,
+            block,
+        ))
+    }
+}
+
+fn build_response<B: Serialize>(
+    fork_name: ForkName,
+    execution_payload_blinded: bool,
+    execution_payload_value: Uint256,
+    block: B,
+) -> Response {
+    let response = ProduceBlockV3Response {
+        version: fork_name,
+        execution_payload_blinded,
+        execution_payload_value,
+        data: block,
+    };
+    let reply = add_consensus_version_header(warp::reply::json(&response), fork_name);
+    let reply = add_execution_payload_blinded_header(reply, execution_payload_blinded);
+    add_execution_payload_value_header(reply, execution_payload_value)
+}
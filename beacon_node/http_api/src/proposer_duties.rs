@@ -1,5 +1,6 @@
 //! Contains the handler for the `GET validator/duties/proposer/{epoch}` endpoint.
 
+use crate::metrics;
 use crate::state_id::StateId;
 use beacon_chain::{
     beacon_proposer_cache::{compute_proposer_duties_from_head, ensure_state_is_in_epoch},
@@ -37,12 +38,22 @@ pub fn proposer_duties<T: BeaconChainTypes>(
         .ok_or_else(|| warp_utils::reject::custom_server_error("unable to read slot clock".into()))?
         .epoch(T::EthSpec::slots_per_epoch());
 
-    if request_epoch == current_epoch || request_epoch == tolerant_current_epoch {
-        // If we could consider ourselves in the `request_epoch` when allowing for clock disparity
-        // tolerance then serve this request from the cache.
+    let next_epoch = current_epoch
+        .safe_add(1)
+        .map_err(warp_utils::reject::arith_error)?;
+
+    if request_epoch == current_epoch
+        || request_epoch == tolerant_current_epoch
+        || request_epoch == next_epoch
+    {
+        // The current and next epoch are served from the cache. Entries are keyed by the
+        // dependent root, so a re-org which changes the dependent root results in a miss rather
+        // than stale duties.
         if let Some(duties) = try_proposer_duties_from_cache(request_epoch, chain)? {
+            metrics::inc_counter(&metrics::HTTP_API_BEACON_PROPOSER_CACHE_HITS_TOTAL);
             Ok(duties)
         } else {
+            metrics::inc_counter(&metrics::HTTP_API_BEACON_PROPOSER_CACHE_MISSES_TOTAL);
             debug!(
                 log,
                 "Proposer cache miss";
@@ -50,26 +61,7 @@ pub fn proposer_duties<T: BeaconChainTypes>(
             );
             compute_and_cache_proposer_duties(request_epoch, chain)
         }
-    } else if request_epoch
-        == current_epoch
-            .safe_add(1)
-            .map_err(warp_utils::reject::arith_error)?
-    {
-        let (proposers, dependent_root, execution_status, _fork) =
-            compute_proposer_duties_from_head(request_epoch, chain)
-                .map_err(warp_utils::reject::beacon_chain_error)?;
-        convert_to_api_response(
-            chain,
-            request_epoch,
-            dependent_root,
-            execution_status.is_optimistic_or_invalid(),
-            proposers,
-        )
-    } else if request_epoch
-        > current_epoch
-            .safe_add(1)
-            .map_err(warp_utils::reject::arith_error)?
-    {
+    } else if request_epoch > next_epoch {
         // Reject queries about the future epochs for which lookahead is not possible
         Err(warp_utils::reject::custom_bad_request(format!(
             "request epoch {} is ahead of the next epoch {}",
@@ -88,7 +80,7 @@ pub fn proposer_duties<T: BeaconChainTypes>(
 ///
 /// ## Notes
 ///
-/// The `current_epoch` value should equal the current epoch on the slot clock (with some
+/// The `request_epoch` value should equal the current or next epoch on the slot clock (with some
 /// tolerance), otherwise we risk washing out the proposer cache at the expense of block processing.
 fn try_proposer_duties_from_cache<T: BeaconChainTypes>(
     request_epoch: Epoch,
@@ -146,27 +138,29 @@ fn try_proposer_duties_from_cache<T: BeaconChainTypes>(
 ///
 /// ## Notes
 ///
-/// The `current_epoch` value should equal the current epoch on the slot clock, otherwise we risk
-/// washing out the proposer cache at the expense of block processing.
+/// The `request_epoch` value should equal the current or next epoch on the slot clock, otherwise
+/// we risk washing out the proposer cache at the expense of block processing.
 fn compute_and_cache_proposer_duties<T: BeaconChainTypes>(
-    current_epoch: Epoch,
+    request_epoch: Epoch,
     chain: &BeaconChain<T>,
 ) -> Result<ApiDuties, warp::reject::Rejection> {
+    let timer = metrics::start_timer(&metrics::HTTP_API_BEACON_PROPOSER_CACHE_TIMES);
     let (indices, dependent_root, execution_status, fork) =
-        compute_proposer_duties_from_head(current_epoch, chain)
+        compute_proposer_duties_from_head(request_epoch, chain)
             .map_err(warp_utils::reject::beacon_chain_error)?;
+    drop(timer);
 
     // Prime the proposer shuffling cache with the newly-learned value.
     chain
         .beacon_proposer_cache
         .lock()
-        .insert(current_epoch, dependent_root, indices.clone(), fork)
+        .insert(request_epoch, dependent_root, indices.clone(), fork)
         .map_err(BeaconChainError::from)
         .map_err(warp_utils::reject::beacon_chain_error)?;
 
     convert_to_api_response(
         chain,
-        current_epoch,
+        request_epoch,
         dependent_root,
         execution_status.is_optimistic_or_invalid(),
         indices,
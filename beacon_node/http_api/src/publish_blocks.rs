@@ -40,6 +40,7 @@ impl<T: BeaconChainTypes, B: IntoGossipVerifiedBlock<T>> ProvenancedBlock<T, B>
 }
 
 /// Handles a request from the HTTP API for full blocks.
+#[tracing::instrument(level = "debug", skip_all, fields(block_root = ?block_root))]
 pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlock<T>>(
     block_root: Option<Hash256>,
     provenanced_block: ProvenancedBlock<T, B>,
@@ -137,7 +138,7 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlock<T>>(
                 log,
                 "Valid block from HTTP API";
                 "block_delay" => ?delay,
-                "root" => format!("{}", root),
+                "block_root" => format!("{}", root),
                 "proposer_index" => beacon_block.message().proposer_index(),
                 "slot" => beacon_block.slot(),
             );
@@ -345,7 +346,7 @@ fn late_block_logging<T: BeaconChainTypes, P: AbstractExecPayload<T::EthSpec>>(
             "provenance" => provenance,
             "delay_ms" => delay.as_millis(),
             "slot" => block.slot(),
-            "root" => ?root,
+            "block_root" => ?root,
         )
     } else if delay >= delayed_threshold {
         error!(
@@ -355,7 +356,7 @@ fn late_block_logging<T: BeaconChainTypes, P: AbstractExecPayload<T::EthSpec>>(
             "provenance" => provenance,
             "delay_ms" => delay.as_millis(),
             "slot" => block.slot(),
-            "root" => ?root,
+            "block_root" => ?root,
         )
     }
 }
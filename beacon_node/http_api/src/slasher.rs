@@ -0,0 +1,30 @@
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::SlasherStats;
+use std::sync::Arc;
+
+pub fn stats<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+) -> Result<SlasherStats, warp::Rejection> {
+    let slasher = chain.slasher.as_ref().ok_or_else(|| {
+        warp_utils::reject::custom_not_found(
+            "slasher is not enabled, see the --slasher CLI flag".to_string(),
+        )
+    })?;
+    let current_epoch = chain
+        .epoch()
+        .map_err(warp_utils::reject::beacon_chain_error)?;
+    let stats = slasher.stats().map_err(|e| {
+        warp_utils::reject::custom_server_error(format!("unable to read slasher stats: {:?}", e))
+    })?;
+
+    Ok(SlasherStats {
+        database_size: stats.database_size,
+        num_attestations: stats.num_attestations as u64,
+        num_blocks: stats.num_blocks as u64,
+        current_epoch,
+        last_processed_epoch: stats.last_processed_epoch,
+        prune_backlog_epochs: stats.prune_backlog_epochs,
+        num_attester_slashings: stats.num_attester_slashings,
+        num_proposer_slashings: stats.num_proposer_slashings,
+    })
+}
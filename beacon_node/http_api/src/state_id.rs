@@ -50,19 +50,11 @@ impl StateId {
                     checkpoint_slot_and_execution_optimistic(chain, justified_checkpoint)?;
                 (slot, execution_optimistic, false)
             }
-            CoreStateId::Slot(slot) => (
-                *slot,
-                chain
-                    .is_optimistic_or_invalid_head()
-                    .map_err(warp_utils::reject::beacon_chain_error)?,
-                *slot
-                    <= chain
-                        .canonical_head
-                        .cached_head()
-                        .finalized_checkpoint()
-                        .epoch
-                        .start_slot(T::EthSpec::slots_per_epoch()),
-            ),
+            CoreStateId::Slot(slot) => {
+                let (execution_optimistic, finalized) =
+                    slot_execution_optimistic_and_finalized(chain, *slot)?;
+                (*slot, execution_optimistic, finalized)
+            }
             CoreStateId::Root(root) => {
                 if let Some(hot_summary) = chain
                     .store
@@ -263,3 +255,28 @@ pub fn checkpoint_slot_and_execution_optimistic<T: BeaconChainTypes>(
 
     Ok((slot, execution_optimistic))
 }
+
+/// Returns the `execution_optimistic` and `finalized` values for data at `slot` on the canonical
+/// chain.
+///
+/// Since the canonical chain is identified by the head, the execution status of the head is used.
+/// Both values are read from a single fork choice lock so they can't describe different heads.
+pub fn slot_execution_optimistic_and_finalized<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    slot: Slot,
+) -> Result<(ExecutionOptimistic, Finalized), warp::reject::Rejection> {
+    let fork_choice = chain.canonical_head.fork_choice_read_lock();
+    let fork_choice_view = fork_choice.cached_fork_choice_view();
+
+    let execution_optimistic = fork_choice
+        .is_optimistic_or_invalid_block(&fork_choice_view.head_block_root)
+        .map_err(BeaconChainError::ForkChoiceError)
+        .map_err(warp_utils::reject::beacon_chain_error)?;
+    let finalized = slot
+        <= fork_choice_view
+            .finalized_checkpoint
+            .epoch
+            .start_slot(T::EthSpec::slots_per_epoch());
+
+    Ok((execution_optimistic, finalized))
+}
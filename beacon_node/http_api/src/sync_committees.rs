@@ -1,12 +1,12 @@
 //! Handlers for sync committee endpoints.
 
-use crate::publish_pubsub_message;
+use crate::{publish_pubsub_message, DUTIES_CHUNK_SIZE};
 use beacon_chain::sync_committee_verification::{
     Error as SyncVerificationError, VerifiedSyncCommitteeMessage,
 };
 use beacon_chain::{
     validator_monitor::timestamp_now, BeaconChain, BeaconChainError, BeaconChainTypes,
-    StateSkipConfig, MAXIMUM_GOSSIP_CLOCK_DISPARITY,
+    BeaconSnapshot, StateSkipConfig, MAXIMUM_GOSSIP_CLOCK_DISPARITY,
 };
 use eth2::types::{self as api_types};
 use lighthouse_network::PubsubMessage;
@@ -15,20 +15,22 @@ use slog::{debug, error, warn, Logger};
 use slot_clock::SlotClock;
 use std::cmp::max;
 use std::collections::HashMap;
+use std::sync::Arc;
 use tokio::sync::mpsc::UnboundedSender;
 use types::{
     slot_data::SlotData, BeaconStateError, Epoch, EthSpec, SignedContributionAndProof,
     SyncCommitteeMessage, SyncDuty, SyncSubnetId,
 };
+use warp_utils::task::blocking_task;
 
 /// The struct that is returned to the requesting HTTP client.
 type SyncDuties = api_types::ExecutionOptimisticResponse<Vec<SyncDuty>>;
 
 /// Handles a request from the HTTP API for sync committee duties.
-pub fn sync_committee_duties<T: BeaconChainTypes>(
+pub async fn sync_committee_duties<T: BeaconChainTypes>(
     request_epoch: Epoch,
-    request_indices: &[u64],
-    chain: &BeaconChain<T>,
+    request_indices: Vec<u64>,
+    chain: Arc<BeaconChain<T>>,
 ) -> Result<SyncDuties, warp::reject::Rejection> {
     let altair_fork_epoch = if let Some(altair_fork_epoch) = chain.spec.altair_fork_epoch {
         altair_fork_epoch
@@ -37,16 +39,26 @@ pub fn sync_committee_duties<T: BeaconChainTypes>(
         return Ok(convert_to_response(vec![], false));
     };
 
+    // Pin a single head snapshot for the whole request, so that all chunks read the same sync
+    // committees even if the head changes part way through.
+    //
     // Even when computing duties from state, any block roots pulled using the request epoch are
-    // still dependent on the head. So using `is_optimistic_head` is fine for both cases.
-    let execution_optimistic = chain
-        .is_optimistic_or_invalid_head()
-        .map_err(warp_utils::reject::beacon_chain_error)?;
+    // still dependent on the head. So using the head's execution status is fine for both cases.
+    let (head, execution_optimistic) = {
+        let (cached_head, execution_status) = chain
+            .canonical_head
+            .head_and_execution_status()
+            .map_err(warp_utils::reject::beacon_chain_error)?;
+        (
+            cached_head.snapshot.clone(),
+            execution_status.is_optimistic_or_invalid(),
+        )
+    };
 
     // Try using the head's sync committees to satisfy the request. This should be sufficient for
     // the vast majority of requests. Rather than checking if we think the request will succeed in a
     // way prone to data races, we attempt the request immediately and check the error code.
-    match chain.sync_committee_duties_from_head(request_epoch, request_indices) {
+    match duties_from_head(request_epoch, &request_indices, head, &chain).await? {
         Ok(duties) => return Ok(convert_to_response(duties, execution_optimistic)),
         Err(BeaconChainError::SyncDutiesError(BeaconStateError::SyncCommitteeNotKnown {
             ..
@@ -55,20 +67,63 @@ pub fn sync_committee_duties<T: BeaconChainTypes>(
         Err(e) => return Err(warp_utils::reject::beacon_chain_error(e)),
     }
 
-    let duties = duties_from_state_load(request_epoch, request_indices, altair_fork_epoch, chain)
-        .map_err(|e| match e {
-        BeaconChainError::SyncDutiesError(BeaconStateError::SyncCommitteeNotKnown {
-            current_epoch,
-            ..
-        }) => warp_utils::reject::custom_bad_request(format!(
-            "invalid epoch: {}, current epoch: {}",
-            request_epoch, current_epoch
-        )),
-        e => warp_utils::reject::beacon_chain_error(e),
-    })?;
+    let duties =
+        blocking_task(move || {
+            duties_from_state_load(request_epoch, &request_indices, altair_fork_epoch, &chain)
+                .map_err(|e| match e {
+                    BeaconChainError::SyncDutiesError(
+                        BeaconStateError::SyncCommitteeNotKnown { current_epoch, .. },
+                    ) => warp_utils::reject::custom_bad_request(format!(
+                        "invalid epoch: {}, current epoch: {}",
+                        request_epoch, current_epoch
+                    )),
+                    e => warp_utils::reject::beacon_chain_error(e),
+                })
+        })
+        .await?;
     Ok(convert_to_response(duties, execution_optimistic))
 }
 
+/// Fast path for duties: compute them from the pinned head state.
+///
+/// Each chunk of indices is computed in its own blocking task, so that very large requests don't
+/// tie up a single worker thread. The outer `Result` carries task failures, the inner one carries
+/// duty computation errors so the caller can fall back to loading a state.
+async fn duties_from_head<T: BeaconChainTypes>(
+    request_epoch: Epoch,
+    request_indices: &[u64],
+    head: Arc<BeaconSnapshot<T::EthSpec>>,
+    chain: &Arc<BeaconChain<T>>,
+) -> Result<Result<Vec<Option<SyncDuty>>, BeaconChainError>, warp::reject::Rejection> {
+    // An empty request still needs one (empty) chunk so that an unknown epoch is detected.
+    let chunks = if request_indices.is_empty() {
+        vec![vec![]]
+    } else {
+        request_indices
+            .chunks(DUTIES_CHUNK_SIZE)
+            .map(<[u64]>::to_vec)
+            .collect()
+    };
+
+    let mut duties = Vec::with_capacity(request_indices.len());
+    for chunk in chunks {
+        let head = head.clone();
+        let chain = chain.clone();
+        let chunk_duties = blocking_task(move || {
+            Ok(head
+                .beacon_state
+                .get_sync_committee_duties(request_epoch, &chunk, &chain.spec)
+                .map_err(BeaconChainError::SyncDutiesError))
+        })
+        .await?;
+        match chunk_duties {
+            Ok(chunk_duties) => duties.extend(chunk_duties),
+            Err(e) => return Ok(Err(e)),
+        }
+    }
+    Ok(Ok(duties))
+}
+
 /// Slow path for duties: load a state and use it to compute the duties.
 fn duties_from_state_load<T: BeaconChainTypes>(
     request_epoch: Epoch,
@@ -55,6 +55,7 @@ pub struct ApiServer<E: EthSpec, SFut: Future<Output = ()>> {
     pub network_rx: NetworkReceivers<E>,
     pub local_enr: Enr,
     pub external_peer_id: PeerId,
+    pub eth1_service: eth1::Service,
 }
 
 type Initializer<E> = Box<
@@ -197,7 +198,7 @@ pub async fn create_api_server_on_port<T: BeaconChainTypes>(
         chain: Some(chain),
         network_senders: Some(network_senders),
         network_globals: Some(network_globals),
-        eth1_service: Some(eth1_service),
+        eth1_service: Some(eth1_service.clone()),
         sse_logging_components: None,
         log,
     });
@@ -216,5 +217,6 @@ pub async fn create_api_server_on_port<T: BeaconChainTypes>(
         network_rx: network_receivers,
         local_enr: enr,
         external_peer_id: peer_id,
+        eth1_service,
     }
 }
@@ -0,0 +1,143 @@
+//! Handlers for validator balance endpoints.
+
+use crate::state_id::StateId;
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::types::{self as api_types, ExecutionOptimisticFinalizedResponse, ValidatorId};
+use std::collections::HashSet;
+use std::sync::Arc;
+use warp::http::{header::CONTENT_TYPE, Response};
+use warp::hyper::Body;
+use warp_utils::reject::{custom_bad_request, custom_server_error};
+
+/// The number of balances serialized into each chunk of a streamed response.
+const BALANCES_PER_CHUNK: usize = 1024;
+
+/// Returns the balances of all validators in the state at `state_id`, optionally filtered to
+/// those matching `optional_ids`.
+///
+/// The ids are collected into sets up-front so that large requests (e.g. every validator on the
+/// network) are answered in a single pass over the state.
+pub fn get_beacon_state_validator_balances<T: BeaconChainTypes>(
+    state_id: StateId,
+    chain: Arc<BeaconChain<T>>,
+    optional_ids: Option<&[ValidatorId]>,
+) -> Result<
+    ExecutionOptimisticFinalizedResponse<Vec<api_types::ValidatorBalanceData>>,
+    warp::Rejection,
+> {
+    let id_sets = optional_ids.map(|ids| {
+        let mut indices = HashSet::new();
+        let mut pubkeys = HashSet::new();
+        for id in ids {
+            match id {
+                ValidatorId::Index(index) => {
+                    indices.insert(*index);
+                }
+                ValidatorId::PublicKey(pubkey) => {
+                    pubkeys.insert(pubkey);
+                }
+            }
+        }
+        (indices, pubkeys)
+    });
+
+    let (data, execution_optimistic, finalized) = state_id
+        .map_state_and_execution_optimistic_and_finalized(
+            &chain,
+            |state, execution_optimistic, finalized| {
+                Ok((
+                    state
+                        .validators()
+                        .iter()
+                        .zip(state.balances().iter())
+                        .enumerate()
+                        // filter by validator id(s) if provided
+                        .filter(|(index, (validator, _))| {
+                            id_sets.as_ref().map_or(true, |(indices, pubkeys)| {
+                                indices.contains(&(*index as u64))
+                                    || pubkeys.contains(&validator.pubkey)
+                            })
+                        })
+                        .map(|(index, (_, balance))| api_types::ValidatorBalanceData {
+                            index: index as u64,
+                            balance: *balance,
+                        })
+                        .collect::<Vec<_>>(),
+                    execution_optimistic,
+                    finalized,
+                ))
+            },
+        )?;
+
+    Ok(api_types::ExecutionOptimisticFinalizedResponse {
+        data,
+        execution_optimistic: Some(execution_optimistic),
+        finalized: Some(finalized),
+    })
+}
+
+/// Parses the body of a `POST beacon/states/{state_id}/validator_balances` request.
+///
+/// Per the Beacon API, a missing body or an empty list of ids selects every validator, so both
+/// are returned as `None`.
+pub fn parse_validator_ids(body: &[u8]) -> Result<Option<Vec<ValidatorId>>, warp::Rejection> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(None);
+    }
+
+    let request: api_types::ValidatorBalancesRequestBody = serde_json::from_slice(body)
+        .map_err(|e| custom_bad_request(format!("invalid request body: {:?}", e)))?;
+
+    if request.ids.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(request.ids))
+    }
+}
+
+/// Serializes `response` as JSON, streaming the balances in chunks of `BALANCES_PER_CHUNK` rather
+/// than rendering the whole body up-front.
+pub fn stream_validator_balances(
+    response: ExecutionOptimisticFinalizedResponse<Vec<api_types::ValidatorBalanceData>>,
+) -> Result<Response<Body>, warp::Rejection> {
+    let ExecutionOptimisticFinalizedResponse {
+        execution_optimistic,
+        finalized,
+        data,
+    } = response;
+
+    // Serialize the envelope with an empty `data` list and cut off the closing `]}` so the
+    // balances can be appended to it.
+    let mut head = serde_json::to_vec(&ExecutionOptimisticFinalizedResponse {
+        execution_optimistic,
+        finalized,
+        data: Vec::<api_types::ValidatorBalanceData>::new(),
+    })
+    .map_err(|e| custom_server_error(format!("unable to serialize response: {:?}", e)))?;
+    head.truncate(head.len().saturating_sub(2));
+
+    // The balances are serialized lazily, one chunk at a time, as the body is polled.
+    let chunks = (0..data.len()).step_by(BALANCES_PER_CHUNK).map(
+        move |start| -> Result<Vec<u8>, serde_json::Error> {
+            let end = std::cmp::min(start + BALANCES_PER_CHUNK, data.len());
+            let mut bytes = serde_json::to_vec(&data[start..end])?;
+            // Strip the surrounding brackets; every chunk after the first continues the list.
+            bytes.pop();
+            if start == 0 {
+                bytes.remove(0);
+            } else {
+                bytes[0] = b',';
+            }
+            Ok(bytes)
+        },
+    );
+
+    let body = std::iter::once(Ok(head))
+        .chain(chunks)
+        .chain(std::iter::once(Ok(b"]}".to_vec())));
+
+    Response::builder()
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::wrap_stream(futures::stream::iter(body)))
+        .map_err(|e| custom_server_error(format!("unable to build response: {:?}", e)))
+}
@@ -509,6 +509,16 @@ async fn bls_to_execution_changes_update_all_around_capella_fork() {
             assert!(valid_address_changes.contains(address_change));
         }
 
+        // The first half of the fork block should be the earliest-received changes, in order.
+        if i == 0 {
+            let num_earliest = max_bls_to_execution_changes / 2;
+            assert_eq!(
+                bls_to_execution_changes[..num_earliest],
+                valid_address_changes[..num_earliest],
+                "fork block should include the earliest-received changes first"
+            );
+        }
+
         // After the initial 2 blocks, add the rest of the changes using a large
         // request containing all the valid, all the conflicting and all the invalid.
         // Despite the invalid and duplicate messages, the new ones should still get picked up by
@@ -223,3 +223,48 @@ async fn node_health_el_online_and_not_synced() {
         }
     }
 }
+
+/// Check `lighthouse/health/ready` when the EL is online and synced.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn lighthouse_health_ready_el_online_and_synced() {
+    let num_blocks = E::slots_per_epoch() / 2;
+    let num_validators = E::slots_per_epoch();
+    let tester = post_merge_tester(num_blocks, num_validators).await;
+    let harness = &tester.harness;
+    let mock_el = harness.mock_execution_layer.as_ref().unwrap();
+
+    // EL synced
+    mock_el.server.set_syncing_response(Ok(false));
+    mock_el.el.upcheck().await;
+
+    let (status, response) = tester.client.get_lighthouse_health_ready().await.unwrap();
+    assert_eq!(status, StatusCode::OK);
+    assert!(response.data.is_ready);
+    assert!(!response.data.el_offline);
+    assert_eq!(
+        response.data.head_slot,
+        harness.chain.head_snapshot().beacon_block.slot()
+    );
+}
+
+/// Check `lighthouse/health/ready` when the EL is offline.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn lighthouse_health_ready_el_offline() {
+    let num_blocks = E::slots_per_epoch() / 2;
+    let num_validators = E::slots_per_epoch();
+    let tester = post_merge_tester(num_blocks, num_validators).await;
+    let harness = &tester.harness;
+    let mock_el = harness.mock_execution_layer.as_ref().unwrap();
+
+    // EL offline
+    mock_el.server.set_syncing_response(Err("offline".into()));
+    mock_el.el.upcheck().await;
+
+    let (status, response) = tester.client.get_lighthouse_health_ready().await.unwrap();
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert!(!response.data.is_ready);
+    assert!(response.data.el_offline);
+
+    // The node is still live.
+    tester.client.get_lighthouse_health_live().await.unwrap();
+}
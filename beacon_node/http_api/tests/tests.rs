@@ -8,7 +8,7 @@ use eth2::{
     mixin::{RequestAccept, ResponseForkName, ResponseOptional},
     reqwest::RequestBuilder,
     types::{BlockId as CoreBlockId, ForkChoiceNode, StateId as CoreStateId, *},
-    BeaconNodeHttpClient, Error, Timeouts,
+    BeaconNodeHttpClient, Error, StatusCode, Timeouts,
 };
 use execution_layer::test_utils::TestingBuilder;
 use execution_layer::test_utils::DEFAULT_BUILDER_THRESHOLD_WEI;
@@ -28,6 +28,7 @@ use sensitive_url::SensitiveUrl;
 use slot_clock::SlotClock;
 use state_processing::per_block_processing::get_expected_withdrawals;
 use state_processing::per_slot_processing;
+use std::collections::HashSet;
 use std::convert::TryInto;
 use std::sync::Arc;
 use tokio::sync::oneshot;
@@ -35,7 +36,7 @@ use tokio::time::Duration;
 use tree_hash::TreeHash;
 use types::application_domain::ApplicationDomain;
 use types::{
-    AggregateSignature, BitList, Domain, EthSpec, ExecutionBlockHash, Hash256, Keypair,
+    AggregateSignature, BitList, Domain, EthSpec, ExecutionBlockHash, Graffiti, Hash256, Keypair,
     MainnetEthSpec, RelativeEpoch, SelectionProof, SignedRoot, Slot,
 };
 
@@ -74,6 +75,7 @@ struct ApiTester {
     network_rx: NetworkReceivers<E>,
     local_enr: Enr,
     external_peer_id: PeerId,
+    eth1_service: eth1::Service,
     mock_builder: Option<Arc<TestingBuilder<E>>>,
 }
 
@@ -238,6 +240,7 @@ impl ApiTester {
             network_rx,
             local_enr,
             external_peer_id,
+            eth1_service,
         } = create_api_server_on_port(chain.clone(), log, port).await;
 
         harness.runtime.task_executor.spawn(server, "api_server");
@@ -270,6 +273,7 @@ impl ApiTester {
             network_rx,
             local_enr,
             external_peer_id,
+            eth1_service,
             mock_builder,
         }
     }
@@ -324,6 +328,7 @@ impl ApiTester {
             network_rx,
             local_enr,
             external_peer_id,
+            eth1_service,
         } = create_api_server(chain.clone(), log).await;
 
         harness.runtime.task_executor.spawn(server, "api_server");
@@ -353,6 +358,7 @@ impl ApiTester {
             network_rx,
             local_enr,
             external_peer_id,
+            eth1_service,
             mock_builder: None,
         }
     }
@@ -613,6 +619,35 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_beacon_headers_slot_optimistic_and_finalized(self) -> Self {
+        let finalized_slot = self
+            .chain
+            .canonical_head
+            .cached_head()
+            .finalized_checkpoint()
+            .epoch
+            .start_slot(E::slots_per_epoch());
+        let head_slot = self.chain.head_snapshot().beacon_block.slot();
+
+        for slot in [0, finalized_slot.as_u64(), head_slot.as_u64()]
+            .into_iter()
+            .chain(SKIPPED_SLOTS.iter().copied())
+            .map(Slot::new)
+        {
+            let result = match self.client.get_beacon_headers(Some(slot), None).await {
+                Ok(Some(result)) => result,
+                // Skipped slots have no header.
+                Err(e) if e.status() == Some(StatusCode::NOT_FOUND) => continue,
+                other => panic!("unexpected response for slot {}: {:?}", slot, other),
+            };
+
+            assert_eq!(result.execution_optimistic, Some(false), "{}", slot);
+            assert_eq!(result.finalized, Some(slot <= finalized_slot), "{}", slot);
+        }
+
+        self
+    }
+
     pub async fn test_beacon_blocks_finalized<T: EthSpec>(self) -> Self {
         for block_id in self.interesting_block_ids() {
             let block_root = block_id.root(&self.chain);
@@ -827,24 +862,89 @@ impl ApiTester {
                     .await
                     .unwrap()
                     .map(|res| res.data);
+                let result_post_index_ids = self
+                    .client
+                    .post_beacon_states_validator_balances(state_id.0, validator_index_ids)
+                    .await
+                    .unwrap()
+                    .map(|res| res.data);
+                let result_post_pubkey_ids = self
+                    .client
+                    .post_beacon_states_validator_balances(state_id.0, validator_pubkey_ids)
+                    .await
+                    .unwrap()
+                    .map(|res| res.data);
 
-                let expected = state_opt.map(|(state, _execution_optimistic, _finalized)| {
-                    let mut validators = Vec::with_capacity(validator_indices.len());
-
-                    for i in validator_indices {
-                        if i < state.balances().len() as u64 {
-                            validators.push(ValidatorBalanceData {
-                                index: i as u64,
-                                balance: state.balances()[i as usize],
-                            });
-                        }
-                    }
+                let expected =
+                    state_opt
+                        .as_ref()
+                        .map(|(state, _execution_optimistic, _finalized)| {
+                            let mut validators = Vec::with_capacity(validator_indices.len());
+
+                            for &i in &validator_indices {
+                                if i < state.balances().len() as u64 {
+                                    validators.push(ValidatorBalanceData {
+                                        index: i as u64,
+                                        balance: state.balances()[i as usize],
+                                    });
+                                }
+                            }
 
-                    validators
-                });
+                            validators
+                        });
+
+                // An empty list of ids in a POST body selects every validator.
+                let expected_post = if validator_indices.is_empty() {
+                    state_opt
+                        .as_ref()
+                        .map(|(state, _execution_optimistic, _finalized)| {
+                            all_validator_balances(state)
+                        })
+                } else {
+                    expected.clone()
+                };
 
                 assert_eq!(result_index_ids, expected, "{:?}", state_id);
                 assert_eq!(result_pubkey_ids, expected, "{:?}", state_id);
+                assert_eq!(result_post_index_ids, expected_post, "{:?}", state_id);
+                assert_eq!(result_post_pubkey_ids, expected_post, "{:?}", state_id);
+            }
+        }
+
+        self
+    }
+
+    pub async fn test_post_beacon_states_validator_balances_without_body(self) -> Self {
+        for state_id in self.interesting_state_ids() {
+            let url = eth2::reqwest::Url::parse(self.client.as_ref())
+                .unwrap()
+                .join(&format!(
+                    "eth/v1/beacon/states/{}/validator_balances",
+                    state_id.0
+                ))
+                .unwrap();
+            let response = eth2::reqwest::Client::new().post(url).send().await.unwrap();
+
+            let expected = state_id
+                .state(&self.chain)
+                .ok()
+                .map(|(state, _execution_optimistic, _finalized)| all_validator_balances(&state));
+
+            match expected {
+                Some(expected) => {
+                    assert_eq!(response.status(), StatusCode::OK, "{:?}", state_id);
+                    assert_eq!(
+                        response.headers().get("content-type").unwrap(),
+                        "application/json"
+                    );
+                    let result = response
+                        .json::<ExecutionOptimisticFinalizedResponse<Vec<ValidatorBalanceData>>>()
+                        .await
+                        .unwrap()
+                        .data;
+                    assert_eq!(result, expected, "{:?}", state_id);
+                }
+                None => assert!(!response.status().is_success(), "{:?}", state_id),
             }
         }
 
@@ -1795,6 +1895,39 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_peer(self) -> Self {
+        let result = self
+            .client
+            .get_lighthouse_peer(&self.external_peer_id.to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(result.peer_id, self.external_peer_id.to_string());
+
+        let score = result.score.expect("untrusted peer should have a score");
+        assert_eq!(score.score, 0.0);
+        assert_eq!(score.lighthouse_score, 0.0);
+        assert_eq!(score.gossipsub_score_contribution, 0.0);
+        assert_eq!(score.state, "Healthy");
+
+        assert!(result.peer_actions.is_empty());
+        assert!(result
+            .connection_history
+            .iter()
+            .any(|event| event.event == "connected_incoming"));
+
+        let unknown_peer = self
+            .client
+            .get_lighthouse_peer(&PeerId::random().to_string())
+            .await;
+        assert_eq!(
+            unknown_peer.unwrap_err().status(),
+            Some(StatusCode::NOT_FOUND)
+        );
+
+        self
+    }
+
     pub async fn test_get_node_peers(self) -> Self {
         let peer_states: Vec<Option<&[PeerState]>> = vec![
             Some(&[PeerState::Connected]),
@@ -2042,6 +2175,33 @@ impl ApiTester {
         interesting
     }
 
+    pub async fn test_get_validator_duties_attester_large_request(self) -> Self {
+        let epoch = self.chain.epoch().unwrap();
+        let validator_count = self.chain.head_snapshot().beacon_state.validators().len() as u64;
+
+        // Large enough to be processed in several chunks. Indices beyond the validator count
+        // have no duties, so the response should match a request for only the known validators.
+        let large_indices = (0..20_000).collect::<Vec<u64>>();
+        let known_indices = (0..validator_count).collect::<Vec<u64>>();
+
+        let large = self
+            .client
+            .post_validator_duties_attester(epoch, &large_indices)
+            .await
+            .unwrap();
+        let known = self
+            .client
+            .post_validator_duties_attester(epoch, &known_indices)
+            .await
+            .unwrap();
+
+        assert_eq!(large.dependent_root, known.dependent_root);
+        assert_eq!(large.data, known.data);
+        assert_eq!(large.data.len(), validator_count as usize);
+
+        self
+    }
+
     pub async fn test_get_validator_duties_attester(self) -> Self {
         let current_epoch = self.chain.epoch().unwrap().as_u64();
 
@@ -2175,16 +2335,16 @@ impl ApiTester {
                 .await
                 .unwrap();
 
-            // Check that current-epoch requests prime the proposer cache, whilst non-current
-            // requests don't.
-            if epoch == current_epoch {
+            // Check that current and next epoch requests prime the proposer cache, whilst
+            // historic requests don't.
+            if epoch >= current_epoch {
                 assert!(
                     self.chain
                         .beacon_proposer_cache
                         .lock()
                         .get_epoch::<E>(dependent_root, epoch)
                         .is_some(),
-                    "a current-epoch request should prime the proposer cache"
+                    "a current or next epoch request should prime the proposer cache"
                 );
             } else {
                 assert!(
@@ -2193,7 +2353,7 @@ impl ApiTester {
                         .lock()
                         .get_epoch::<E>(dependent_root, epoch)
                         .is_none(),
-                    "a non-current-epoch request should not prime the proposer cache"
+                    "a historic request should not prime the proposer cache"
                 );
             }
 
@@ -2233,8 +2393,8 @@ impl ApiTester {
 
             assert_eq!(result, expected);
 
-            // If it's the current epoch, check the function with a primed proposer cache.
-            if epoch == current_epoch {
+            // If it's the current or next epoch, check the function with a primed proposer cache.
+            if epoch >= current_epoch {
                 // This is technically a double-check, but it's defensive.
                 assert!(
                     self.chain
@@ -2291,13 +2451,15 @@ impl ApiTester {
             .await
             .expect("should get proposer duties for the next epoch outside of tolerance");
 
+        // Outside of tolerance `current_epoch` is the next epoch, the duties for which are also
+        // cached.
         assert!(
             self.chain
                 .beacon_proposer_cache
                 .lock()
                 .get_epoch::<E>(dependent_root, current_epoch)
-                .is_none(),
-            "should not prime the proposer cache outside of tolerance"
+                .is_some(),
+            "should prime the proposer cache for the next epoch"
         );
 
         assert_eq!(
@@ -2550,6 +2712,7 @@ impl ApiTester {
                     &Signature::infinity().unwrap().into(),
                     None,
                     SkipRandaoVerification::Yes,
+                    None,
                 )
                 .await
                 .unwrap()
@@ -2616,6 +2779,7 @@ impl ApiTester {
                     &bad_randao_reveal,
                     None,
                     SkipRandaoVerification::Yes,
+                    None,
                 )
                 .await
                 .unwrap_err();
@@ -3707,6 +3871,111 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_payload_chosen_by_builder_boost_factor(self) -> Self {
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+
+        let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
+
+        // (relay value, boost factor, builder payload expected)
+        let cases = [
+            (DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI + 1, Some(0), false),
+            (DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI + 1, Some(50), false),
+            (DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI - 1, Some(200), true),
+        ];
+
+        for (relay_value, builder_boost_factor, expect_builder) in cases {
+            self.mock_builder
+                .as_ref()
+                .unwrap()
+                .builder
+                .add_operation(Operation::Value(Uint256::from(relay_value)));
+
+            let payload: BlindedPayload<E> = self
+                .client
+                .get_validator_blinded_blocks_modular::<E, BlindedPayload<E>>(
+                    slot,
+                    &randao_reveal,
+                    None,
+                    SkipRandaoVerification::No,
+                    builder_boost_factor,
+                )
+                .await
+                .unwrap()
+                .data
+                .body()
+                .execution_payload()
+                .unwrap()
+                .into();
+
+            // The local payload cache is only populated when the local payload is chosen.
+            assert_eq!(
+                self.chain
+                    .execution_layer
+                    .as_ref()
+                    .unwrap()
+                    .get_payload_by_root(&payload.tree_hash_root())
+                    .is_none(),
+                expect_builder,
+                "builder_boost_factor {:?}",
+                builder_boost_factor
+            );
+        }
+        self
+    }
+
+    pub async fn test_block_production_v3_payload_source(self) -> Self {
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+
+        let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
+
+        // (relay value, builder payload expected)
+        let cases = [
+            (DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI + 1, true),
+            (DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI - 1, false),
+        ];
+
+        for (relay_value, expect_builder) in cases {
+            self.mock_builder
+                .as_ref()
+                .unwrap()
+                .builder
+                .add_operation(Operation::Value(Uint256::from(relay_value)));
+
+            let response = self
+                .client
+                .get_validator_blocks_v3::<E>(
+                    slot,
+                    &randao_reveal,
+                    None,
+                    SkipRandaoVerification::No,
+                    None,
+                )
+                .await
+                .unwrap();
+
+            match response {
+                ProducedBlockV3::Blinded(response) => {
+                    assert!(expect_builder, "local payload should be returned in full");
+                    assert!(response.execution_payload_blinded);
+                    assert_eq!(response.execution_payload_value, Uint256::from(relay_value));
+                    assert_eq!(response.data.slot(), slot);
+                }
+                ProducedBlockV3::Full(response) => {
+                    assert!(!expect_builder, "builder payload should be blinded");
+                    assert!(!response.execution_payload_blinded);
+                    assert_eq!(
+                        response.execution_payload_value,
+                        Uint256::from(DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI)
+                    );
+                    assert_eq!(response.data.slot(), slot);
+                }
+            }
+        }
+        self
+    }
+
     pub async fn test_local_payload_chosen_when_more_profitable(self) -> Self {
         // Mutate value.
         self.mock_builder
@@ -3908,6 +4177,112 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_eth1_block_cache_summary(self) -> Self {
+        let summary = self
+            .client
+            .get_lighthouse_eth1_block_cache_summary()
+            .await
+            .unwrap()
+            .data;
+
+        assert_eq!(summary.block_count, 0);
+        assert_eq!(summary.highest_block_number, None);
+        assert_eq!(summary.latest_block, None);
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_eth1_deposit_cache_summary(self) -> Self {
+        let summary = self
+            .client
+            .get_lighthouse_eth1_deposit_cache_summary()
+            .await
+            .unwrap()
+            .data;
+
+        assert_eq!(summary.deposit_count, 0);
+        assert_eq!(summary.finalized_deposit_count, 0);
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_eth1_cache_summaries_with_contents(self) -> Self {
+        let blocks = (100..103)
+            .map(|number| eth2::lighthouse::Eth1Block {
+                hash: Hash256::from_low_u64_be(number),
+                timestamp: number * 14,
+                number,
+                deposit_root: Some(Hash256::from_low_u64_be(number + 1000)),
+                deposit_count: Some(number - 100),
+            })
+            .collect::<Vec<_>>();
+        let logs = (0..2)
+            .map(|index| eth2::lighthouse::DepositLog {
+                deposit_data: DepositData {
+                    pubkey: PublicKeyBytes::empty(),
+                    withdrawal_credentials: Hash256::from_low_u64_be(index),
+                    amount: 32_000_000_000,
+                    signature: SignatureBytes::empty(),
+                },
+                block_number: 101 + index,
+                index,
+                signature_is_valid: false,
+            })
+            .collect::<Vec<_>>();
+
+        {
+            let mut block_cache = self.eth1_service.blocks().write();
+            for block in &blocks {
+                block_cache.insert_root_or_child(block.clone()).unwrap();
+            }
+        }
+        let expected_deposit_root = {
+            let mut deposits = self.eth1_service.deposits().write();
+            for log in &logs {
+                deposits.cache.insert_log(log.clone()).unwrap();
+            }
+            deposits.last_processed_block = Some(102);
+            *deposits.cache.get_root(logs.len()).unwrap()
+        };
+
+        let block_summary = self
+            .client
+            .get_lighthouse_eth1_block_cache_summary()
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(
+            block_summary,
+            eth2::lighthouse::Eth1BlockCacheSummary {
+                block_count: 3,
+                lowest_block_number: Some(100),
+                highest_block_number: Some(102),
+                earliest_block_timestamp: Some(1400),
+                latest_block_timestamp: Some(1428),
+                latest_block: blocks.last().cloned(),
+            }
+        );
+
+        let deposit_summary = self
+            .client
+            .get_lighthouse_eth1_deposit_cache_summary()
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(
+            deposit_summary,
+            eth2::lighthouse::Eth1DepositCacheSummary {
+                deposit_count: 2,
+                finalized_deposit_count: 0,
+                deposit_root: expected_deposit_root,
+                latest_deposit_block_number: 102,
+                last_processed_block: Some(102),
+            }
+        );
+
+        self
+    }
+
     pub async fn test_get_lighthouse_beacon_states_ssz(self) -> Self {
         for state_id in self.interesting_state_ids() {
             let result = self
@@ -3949,6 +4324,123 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_slasher_stats(self) -> Self {
+        let result = self.client.get_lighthouse_slasher_stats().await;
+
+        // The test harness does not run a slasher.
+        assert!(self.chain.slasher.is_none());
+        assert_eq!(result.unwrap_err().status(), Some(StatusCode::NOT_FOUND));
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_op_pool(self) -> Self {
+        let result = self.client.get_lighthouse_op_pool().await.unwrap();
+
+        let op_pool = &self.chain.op_pool;
+        let attestation_stats = op_pool.attestation_stats();
+        assert_eq!(
+            result.attestations,
+            eth2::lighthouse::AttestationPoolInfo {
+                num_attestations: attestation_stats.num_attestations as u64,
+                num_attestation_data: attestation_stats.num_attestation_data as u64,
+                max_aggregates_per_data: attestation_stats.max_aggregates_per_data as u64,
+                num_attesting_indices: attestation_stats.num_attesting_indices as u64,
+            }
+        );
+        assert!(result.attestations.num_attesting_indices >= result.attestations.num_attestations);
+        assert_eq!(
+            result.num_attester_slashings,
+            op_pool.num_attester_slashings() as u64
+        );
+        assert_eq!(
+            result.num_proposer_slashings,
+            op_pool.num_proposer_slashings() as u64
+        );
+        assert_eq!(
+            result.num_voluntary_exits,
+            op_pool.num_voluntary_exits() as u64
+        );
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_op_pool_with_known_operations(self) -> Self {
+        let before = self.client.get_lighthouse_op_pool().await.unwrap();
+
+        self.client
+            .post_beacon_pool_attestations(self.attestations.as_slice())
+            .await
+            .unwrap();
+        self.client
+            .post_beacon_pool_attester_slashings(&self.attester_slashing)
+            .await
+            .unwrap();
+        self.client
+            .post_beacon_pool_proposer_slashings(&self.proposer_slashing)
+            .await
+            .unwrap();
+        self.client
+            .post_beacon_pool_voluntary_exits(&self.voluntary_exit)
+            .await
+            .unwrap();
+
+        let after = self.client.get_lighthouse_op_pool().await.unwrap();
+
+        // The unaggregated attestations are for the current slot, which has no attestations in the
+        // pool yet. Those sharing `AttestationData` have disjoint signers and are aggregated.
+        let num_data = self
+            .attestations
+            .iter()
+            .map(|attestation| &attestation.data)
+            .collect::<HashSet<_>>()
+            .len() as u64;
+        assert_eq!(
+            after.attestations.num_attestation_data,
+            before.attestations.num_attestation_data + num_data
+        );
+        assert_eq!(
+            after.attestations.num_attestations,
+            before.attestations.num_attestations + num_data
+        );
+        assert_eq!(
+            after.attestations.num_attesting_indices,
+            before.attestations.num_attesting_indices + self.attestations.len() as u64
+        );
+
+        assert_eq!(
+            after.num_attester_slashings,
+            before.num_attester_slashings + 1
+        );
+        assert_eq!(
+            after.num_proposer_slashings,
+            before.num_proposer_slashings + 1
+        );
+        assert_eq!(after.num_voluntary_exits, before.num_voluntary_exits + 1);
+
+        self
+    }
+
+    pub async fn test_get_and_post_lighthouse_graffiti(self) -> Self {
+        let result = self.client.get_lighthouse_graffiti().await.unwrap().data;
+        assert_eq!(result.graffiti, self.chain.graffiti());
+
+        let request = eth2::lighthouse::UpdateGraffitiRequest {
+            graffiti: "runtime graffiti".parse().unwrap(),
+        };
+        self.client
+            .post_lighthouse_graffiti(&request)
+            .await
+            .unwrap();
+
+        let expected: Graffiti = request.graffiti.into();
+        let result = self.client.get_lighthouse_graffiti().await.unwrap().data;
+        assert_eq!(result.graffiti, expected);
+        assert_eq!(self.chain.graffiti(), expected);
+
+        self
+    }
+
     pub async fn test_post_lighthouse_database_reconstruct(self) -> Self {
         let response = self
             .client
@@ -4276,6 +4768,18 @@ impl ApiTester {
     }
 }
 
+fn all_validator_balances(state: &BeaconState<E>) -> Vec<ValidatorBalanceData> {
+    state
+        .balances()
+        .iter()
+        .enumerate()
+        .map(|(index, balance)| ValidatorBalanceData {
+            index: index as u64,
+            balance: *balance,
+        })
+        .collect()
+}
+
 async fn poll_events<S: Stream<Item = Result<EventKind<T>, eth2::Error>> + Unpin, T: EthSpec>(
     stream: &mut S,
     num_events: usize,
@@ -4337,6 +4841,8 @@ async fn beacon_get() {
         .await
         .test_beacon_headers_block_id_finalized()
         .await
+        .test_beacon_headers_slot_optimistic_and_finalized()
+        .await
         .test_beacon_blocks_finalized::<MainnetEthSpec>()
         .await
         .test_beacon_blinded_blocks_finalized::<MainnetEthSpec>()
@@ -4353,6 +4859,8 @@ async fn beacon_get() {
         .await
         .test_beacon_states_validator_balances()
         .await
+        .test_post_beacon_states_validator_balances_without_body()
+        .await
         .test_beacon_states_committees()
         .await
         .test_beacon_states_validator_id()
@@ -4498,6 +5006,8 @@ async fn node_get() {
         .await
         .test_get_node_peers_by_id()
         .await
+        .test_get_lighthouse_peer()
+        .await
         .test_get_node_peers()
         .await
         .test_get_node_peer_count()
@@ -4520,6 +5030,14 @@ async fn get_validator_duties_attester() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_validator_duties_attester_large_request() {
+    ApiTester::new()
+        .await
+        .test_get_validator_duties_attester_large_request()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn get_validator_duties_attester_with_skip_slots() {
     ApiTester::new()
@@ -4681,6 +5199,8 @@ async fn get_validator_aggregate_and_proofs_valid() {
     ApiTester::new()
         .await
         .test_get_validator_aggregate_and_proofs_valid()
+        .await
+        .test_get_lighthouse_op_pool()
         .await;
 }
 
@@ -4847,6 +5367,10 @@ async fn builder_payload_chosen_by_profit() {
         .test_local_payload_chosen_when_equally_profitable()
         .await
         .test_local_payload_chosen_when_more_profitable()
+        .await
+        .test_payload_chosen_by_builder_boost_factor()
+        .await
+        .test_block_production_v3_payload_source()
         .await;
 }
 
@@ -4890,12 +5414,26 @@ async fn lighthouse_endpoints() {
         .await
         .test_get_lighthouse_eth1_deposit_cache()
         .await
+        .test_get_lighthouse_eth1_block_cache_summary()
+        .await
+        .test_get_lighthouse_eth1_deposit_cache_summary()
+        .await
+        .test_get_lighthouse_eth1_cache_summaries_with_contents()
+        .await
         .test_get_lighthouse_beacon_states_ssz()
         .await
         .test_get_lighthouse_staking()
         .await
         .test_get_lighthouse_database_info()
         .await
+        .test_get_lighthouse_slasher_stats()
+        .await
+        .test_get_lighthouse_op_pool()
+        .await
+        .test_get_lighthouse_op_pool_with_known_operations()
+        .await
+        .test_get_and_post_lighthouse_graffiti()
+        .await
         .test_post_lighthouse_database_reconstruct()
         .await
         .test_post_lighthouse_liveness()
@@ -6,6 +6,7 @@ mod metrics;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use lighthouse_network::prometheus_client::registry::Registry;
 use lighthouse_version::version_with_platform;
+pub use metrics::MetricFilter;
 use serde::{Deserialize, Serialize};
 use slog::{crit, info, Logger};
 use std::future::Future;
@@ -52,6 +53,12 @@ pub struct Config {
     pub listen_port: u16,
     pub allow_origin: Option<String>,
     pub allocator_metrics_enabled: bool,
+    /// If set, only metrics with names matching this regex are served.
+    pub allowlist: Option<String>,
+    /// If set, metrics with names matching this regex are not served.
+    pub denylist: Option<String>,
+    /// Buckets to use instead of the defaults for the histograms with the given names.
+    pub histogram_buckets: Vec<(String, Vec<f64>)>,
 }
 
 impl Default for Config {
@@ -62,6 +69,9 @@ impl Default for Config {
             listen_port: 5054,
             allow_origin: None,
             allocator_metrics_enabled: true,
+            allowlist: None,
+            denylist: None,
+            histogram_buckets: vec![],
         }
     }
 }
@@ -109,29 +119,33 @@ pub fn serve<T: BeaconChainTypes>(
         ));
     }
 
+    let filter = Arc::new(metrics::MetricFilter::new(config)?);
+
     let inner_ctx = ctx.clone();
     let routes = warp::get()
         .and(warp::path("metrics"))
-        .map(move || inner_ctx.clone())
-        .and_then(|ctx: Arc<Context<T>>| async move {
-            Ok::<_, warp::Rejection>(
-                metrics::gather_prometheus_metrics(&ctx)
-                    .map(|body| {
-                        Response::builder()
-                            .status(200)
-                            .header("Content-Type", "text/plain")
-                            .body(body)
-                            .unwrap()
-                    })
-                    .unwrap_or_else(|e| {
-                        Response::builder()
-                            .status(500)
-                            .header("Content-Type", "text/plain")
-                            .body(format!("Unable to gather metrics: {:?}", e))
-                            .unwrap()
-                    }),
-            )
-        })
+        .map(move || (inner_ctx.clone(), filter.clone()))
+        .and_then(
+            |(ctx, filter): (Arc<Context<T>>, Arc<metrics::MetricFilter>)| async move {
+                Ok::<_, warp::Rejection>(
+                    metrics::gather_prometheus_metrics(&ctx, &filter)
+                        .map(|body| {
+                            Response::builder()
+                                .status(200)
+                                .header("Content-Type", "text/plain")
+                                .body(body)
+                                .unwrap()
+                        })
+                        .unwrap_or_else(|e| {
+                            Response::builder()
+                                .status(500)
+                                .header("Content-Type", "text/plain")
+                                .body(format!("Unable to gather metrics: {:?}", e))
+                                .unwrap()
+                        }),
+                )
+            },
+        )
         // Add a `Server` header.
         .map(|reply| warp::reply::with_header(reply, "Server", &version_with_platform()))
         .with(cors_builder.build());
@@ -1,13 +1,91 @@
-use crate::Context;
+use crate::{Config, Context};
 use beacon_chain::BeaconChainTypes;
 use lighthouse_metrics::{Encoder, TextEncoder};
 use lighthouse_network::prometheus_client::encoding::text::encode;
 use malloc_utils::scrape_allocator_metrics;
+use regex::Regex;
+use slog::warn;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 pub use lighthouse_metrics::*;
 
+/// Set once any unapplied histogram bucket overrides have been logged, so that the warning isn't
+/// repeated on every scrape.
+static CHECKED_HISTOGRAM_BUCKETS: AtomicBool = AtomicBool::new(false);
+
+/// Restricts the metrics served at `/metrics` to those allowed by the allowlist and denylist.
+pub struct MetricFilter {
+    allowlist: Option<Regex>,
+    denylist: Option<Regex>,
+}
+
+impl MetricFilter {
+    pub fn new(config: &Config) -> std::result::Result<Self, String> {
+        let compile = |regex: &Option<String>, name: &str| {
+            regex
+                .as_deref()
+                .map(Regex::new)
+                .transpose()
+                .map_err(|e| format!("Invalid metrics {}: {:?}", name, e))
+        };
+
+        Ok(Self {
+            allowlist: compile(&config.allowlist, "allowlist")?,
+            denylist: compile(&config.denylist, "denylist")?,
+        })
+    }
+
+    pub fn is_allowed(&self, name: &str) -> bool {
+        self.allowlist
+            .as_ref()
+            .map_or(true, |allowlist| allowlist.is_match(name))
+            && !self
+                .denylist
+                .as_ref()
+                .map_or(false, |denylist| denylist.is_match(name))
+    }
+
+    fn is_noop(&self) -> bool {
+        self.allowlist.is_none() && self.denylist.is_none()
+    }
+
+    /// Removes disallowed metric families from a text-encoded scrape.
+    ///
+    /// Like the Lighthouse metrics, families are matched by their family name (from the `# HELP`
+    /// and `# TYPE` lines) rather than by the names of their samples, which may carry suffixes
+    /// such as `_total` or `_bucket`.
+    fn filter_text(&self, text: &str) -> String {
+        let mut family_allowed = None;
+        text.lines()
+            .filter(|line| match line.strip_prefix('#') {
+                Some(comment) => {
+                    let mut words = comment.split_whitespace();
+                    match (words.next(), words.next()) {
+                        (Some("HELP" | "TYPE" | "UNIT"), Some(family)) => {
+                            let allowed = self.is_allowed(family);
+                            family_allowed = Some(allowed);
+                            allowed
+                        }
+                        // `# EOF`.
+                        _ => true,
+                    }
+                }
+                // `<name>{<labels>} <value>` or `<name> <value>`, which belongs to the family of
+                // the preceding metadata.
+                None => family_allowed.unwrap_or_else(|| {
+                    line.split(|c: char| c == '{' || c == ' ')
+                        .next()
+                        .map_or(true, |name| self.is_allowed(name))
+                }),
+            })
+            .flat_map(|line| [line, "\n"])
+            .collect()
+    }
+}
+
 pub fn gather_prometheus_metrics<T: BeaconChainTypes>(
     ctx: &Context<T>,
+    filter: &MetricFilter,
 ) -> std::result::Result<String, String> {
     let mut buffer = vec![];
     let encoder = TextEncoder::new();
@@ -49,15 +127,43 @@ pub fn gather_prometheus_metrics<T: BeaconChainTypes>(
         scrape_allocator_metrics();
     }
 
-    encoder
-        .encode(&lighthouse_metrics::gather(), &mut buffer)
-        .unwrap();
+    // By the first scrape any histogram which is used regularly will have been created, so an
+    // override which still hasn't been applied most likely has the wrong name.
+    if !CHECKED_HISTOGRAM_BUCKETS.swap(true, Ordering::Relaxed) {
+        for name in lighthouse_metrics::unapplied_histogram_buckets() {
+            warn!(
+                ctx.log,
+                "Histogram buckets not applied";
+                "msg" => "check the metric name passed to --metrics-histogram-buckets",
+                "name" => name,
+            );
+        }
+    }
+
+    let metric_families = lighthouse_metrics::gather()
+        .into_iter()
+        .filter(|family| filter.is_allowed(family.get_name()))
+        .collect::<Vec<_>>();
+    encoder.encode(&metric_families, &mut buffer).unwrap();
+
     // encode gossipsub metrics also if they exist
+    let mut gossipsub_buffer = vec![];
     if let Some(registry) = ctx.gossipsub_registry.as_ref() {
         if let Ok(registry_locked) = registry.lock() {
-            let _ = encode(&mut buffer, &registry_locked);
+            let _ = encode(&mut gossipsub_buffer, &registry_locked);
         }
     }
 
-    String::from_utf8(buffer).map_err(|e| format!("Failed to encode prometheus info: {:?}", e))
+    let mut metrics = String::from_utf8(buffer)
+        .map_err(|e| format!("Failed to encode prometheus info: {:?}", e))?;
+    let gossipsub_metrics = String::from_utf8(gossipsub_buffer)
+        .map_err(|e| format!("Failed to encode gossipsub metrics: {:?}", e))?;
+    // The gossipsub metrics are already encoded, so they must be filtered as text.
+    if filter.is_noop() {
+        metrics.push_str(&gossipsub_metrics);
+    } else {
+        metrics.push_str(&filter.filter_text(&gossipsub_metrics));
+    }
+
+    Ok(metrics)
 }
@@ -1,6 +1,7 @@
 use beacon_chain::test_utils::EphemeralHarnessType;
 use environment::null_logger;
 use http_metrics::Config;
+use lighthouse_network::prometheus_client::{metrics::counter::Counter, registry::Registry};
 use reqwest::header::HeaderValue;
 use reqwest::StatusCode;
 use std::net::{IpAddr, Ipv4Addr};
@@ -22,6 +23,9 @@ async fn returns_200_ok() {
                 listen_port: 0,
                 allow_origin: None,
                 allocator_metrics_enabled: true,
+                allowlist: None,
+                denylist: None,
+                histogram_buckets: vec![],
             },
             chain: None,
             db_path: None,
@@ -56,3 +60,166 @@ async fn returns_200_ok() {
     }
     .await
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn filters_metrics() {
+    async {
+        let log = null_logger().unwrap();
+
+        let allowed = lighthouse_metrics::try_create_int_counter(
+            "http_metrics_test_allowed_total",
+            "Counter that should be served",
+        )
+        .unwrap();
+        allowed.inc();
+        let denied = lighthouse_metrics::try_create_int_counter(
+            "http_metrics_test_denied_total",
+            "Counter that should not be served",
+        )
+        .unwrap();
+        denied.inc();
+
+        let context = Arc::new(Context {
+            config: Config {
+                enabled: true,
+                listen_addr: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+                listen_port: 0,
+                allow_origin: None,
+                allocator_metrics_enabled: true,
+                allowlist: Some("^http_metrics_test_".to_string()),
+                denylist: Some("denied".to_string()),
+                histogram_buckets: vec![],
+            },
+            chain: None,
+            db_path: None,
+            freezer_db_path: None,
+            gossipsub_registry: None,
+            log,
+        });
+
+        let ctx = context.clone();
+        let (_shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let server_shutdown = async {
+            let _ = shutdown_rx.await;
+        };
+        let (listening_socket, server) = http_metrics::serve(ctx, server_shutdown).unwrap();
+
+        tokio::spawn(server);
+
+        let url = format!(
+            "http://{}:{}/metrics",
+            listening_socket.ip(),
+            listening_socket.port()
+        );
+
+        let body = reqwest::get(&url).await.unwrap().text().await.unwrap();
+
+        assert!(body.contains("http_metrics_test_allowed_total 1"));
+        assert!(!body.contains("http_metrics_test_denied_total"));
+        assert!(!body.contains("process_"));
+    }
+    .await
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn filters_gossipsub_metrics_by_family() {
+    async {
+        let log = null_logger().unwrap();
+
+        let mut registry = Registry::default();
+        let allowed = Counter::<u64>::default();
+        allowed.inc();
+        registry.register(
+            "gossip_test",
+            "Counter that should be served",
+            Box::new(allowed),
+        );
+        let denied = Counter::<u64>::default();
+        denied.inc();
+        registry.register(
+            "gossip_test_denied",
+            "Counter that should not be served",
+            Box::new(denied),
+        );
+
+        let context = Arc::new(Context {
+            config: Config {
+                enabled: true,
+                listen_addr: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+                listen_port: 0,
+                allow_origin: None,
+                allocator_metrics_enabled: true,
+                // Matches the family name, but not the name of its `_total` sample.
+                allowlist: Some("^gossip_test$".to_string()),
+                denylist: None,
+                histogram_buckets: vec![],
+            },
+            chain: None,
+            db_path: None,
+            freezer_db_path: None,
+            gossipsub_registry: Some(std::sync::Mutex::new(registry)),
+            log,
+        });
+
+        let ctx = context.clone();
+        let (_shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let server_shutdown = async {
+            let _ = shutdown_rx.await;
+        };
+        let (listening_socket, server) = http_metrics::serve(ctx, server_shutdown).unwrap();
+
+        tokio::spawn(server);
+
+        let url = format!(
+            "http://{}:{}/metrics",
+            listening_socket.ip(),
+            listening_socket.port()
+        );
+
+        let body = reqwest::get(&url).await.unwrap().text().await.unwrap();
+
+        assert!(body.contains("# TYPE gossip_test counter"));
+        assert!(body.contains("gossip_test_total 1"));
+        assert!(!body.contains("gossip_test_denied"));
+    }
+    .await
+}
+
+#[test]
+fn histogram_bucket_overrides() {
+    let buckets = vec![0.5, 1.0];
+
+    // Overriding a histogram which has already been created has no effect, so it is an error.
+    lighthouse_metrics::try_create_histogram("http_metrics_test_created_seconds", "Created")
+        .unwrap();
+    assert!(lighthouse_metrics::set_histogram_buckets(
+        "http_metrics_test_created_seconds",
+        buckets.clone()
+    )
+    .is_err());
+
+    lighthouse_metrics::set_histogram_buckets("http_metrics_test_used_seconds", buckets.clone())
+        .unwrap();
+    lighthouse_metrics::set_histogram_buckets("http_metrics_test_unknown_seconds", buckets)
+        .unwrap();
+    let histogram =
+        lighthouse_metrics::try_create_histogram("http_metrics_test_used_seconds", "Used").unwrap();
+    histogram.observe(0.75);
+
+    let family = lighthouse_metrics::gather()
+        .into_iter()
+        .find(|family| family.get_name() == "http_metrics_test_used_seconds")
+        .unwrap();
+    let upper_bounds = family.get_metric()[0]
+        .get_histogram()
+        .get_bucket()
+        .iter()
+        .map(|bucket| bucket.get_upper_bound())
+        .collect::<Vec<_>>();
+    assert_eq!(upper_bounds, vec![0.5, 1.0]);
+
+    // Only the override which was never applied is reported.
+    let unapplied = lighthouse_metrics::unapplied_histogram_buckets();
+    assert!(unapplied.contains(&"http_metrics_test_unknown_seconds".to_string()));
+    assert!(!unapplied.contains(&"http_metrics_test_used_seconds".to_string()));
+}
@@ -38,6 +38,11 @@ const GOSSIP_MAX_SIZE_POST_MERGE: usize = 10 * 1_048_576; // 10M
 /// another 500ms for "fudge factor".
 pub const DUPLICATE_CACHE_TIME: Duration = Duration::from_secs(33 * 12 + 1);
 
+/// Number of slots before a fork when we subscribe to the new fork's gossip topics.
+pub const DEFAULT_FORK_SUBSCRIBE_SLOTS: u64 = 2;
+/// Number of epochs after a fork when we unsubscribe from the previous fork's gossip topics.
+pub const DEFAULT_FORK_UNSUBSCRIBE_EPOCHS: u64 = 2;
+
 // We treat uncompressed messages as invalid and never use the INVALID_SNAPPY_DOMAIN as in the
 // specification. We leave it here for posterity.
 // const MESSAGE_DOMAIN_INVALID_SNAPPY: [u8; 4] = [0, 0, 0, 0];
@@ -151,6 +156,12 @@ pub struct Config {
 
     /// Configuration for the inbound rate limiter (requests received by this node).
     pub inbound_rate_limiter_config: Option<InboundRateLimiterConfig>,
+
+    /// The number of slots before a fork at which we subscribe to the new fork's gossip topics.
+    pub fork_subscribe_slots: u64,
+
+    /// The number of epochs after a fork at which we unsubscribe from the old fork's gossip topics.
+    pub fork_unsubscribe_epochs: u64,
 }
 
 impl Config {
@@ -341,6 +352,8 @@ impl Default for Config {
             outbound_rate_limiter_config: None,
             invalid_block_storage: None,
             inbound_rate_limiter_config: None,
+            fork_subscribe_slots: DEFAULT_FORK_SUBSCRIBE_SLOTS,
+            fork_unsubscribe_epochs: DEFAULT_FORK_UNSUBSCRIBE_EPOCHS,
         }
     }
 }
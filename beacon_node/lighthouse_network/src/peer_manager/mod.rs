@@ -27,7 +27,8 @@ pub use libp2p::core::{identity::Keypair, Multiaddr};
 pub mod peerdb;
 
 pub use peerdb::peer_info::{
-    ConnectionDirection, PeerConnectionStatus, PeerConnectionStatus::*, PeerInfo,
+    ConnectionDirection, ConnectionEvent, PeerActionRecord, PeerConnectionStatus,
+    PeerConnectionStatus::*, PeerInfo,
 };
 use peerdb::score::{PeerAction, ReportSource};
 pub use peerdb::sync_status::{SyncInfo, SyncStatus};
@@ -440,11 +441,12 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
             let previous_listening_addresses =
                 peer_info.set_listening_addresses(info.listen_addrs.clone());
             peer_info.set_client(peerdb::client::Client::from_identify_info(info));
+            peer_info.set_protocols(info.protocols.clone());
 
             if previous_kind != peer_info.client().kind
                 || *peer_info.listening_addresses() != previous_listening_addresses
             {
-                debug!(self.log, "Identified Peer"; "peer" => %peer_id,
+                debug!(self.log, "Identified Peer"; "peer_id" => %peer_id,
                     "protocol_version" => &info.protocol_version,
                     "agent_version" => &info.agent_version,
                     "listening_addresses" => ?info.listen_addrs,
@@ -529,10 +529,12 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
         match self.peers.get_mut(peer_id) {
             Some(info) => {
                 let previous_state = info.score_state();
+                let source: &'static str = source.into();
                 info.apply_peer_action_to_score(action);
+                info.record_peer_action(action.into(), source, msg);
                 metrics::inc_counter_vec(
                     &metrics::PEER_ACTION_EVENTS_PER_CLIENT,
-                    &[info.client().kind.as_ref(), action.as_ref(), source.into()],
+                    &[info.client().kind.as_ref(), action.as_ref(), source],
                 );
                 let result =
                     Self::handle_score_transition(previous_state, peer_id, info, &self.log);
@@ -726,6 +728,8 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
             }
         }
 
+        info.record_connection_event(new_state.event_name());
+
         // Handle all the possible state changes
         match (info.connection_status().clone(), new_state) {
             /* CONNECTED
@@ -1156,6 +1160,28 @@ enum NewConnectionState {
     Unbanned,
 }
 
+impl NewConnectionState {
+    /// A short description of the transition, recorded in the peer's connection history.
+    fn event_name(&self) -> &'static str {
+        match self {
+            NewConnectionState::Connected {
+                direction: ConnectionDirection::Incoming,
+                ..
+            } => "connected_incoming",
+            NewConnectionState::Connected {
+                direction: ConnectionDirection::Outgoing,
+                ..
+            } => "connected_outgoing",
+            NewConnectionState::Disconnecting { to_ban: false } => "disconnecting",
+            NewConnectionState::Disconnecting { to_ban: true } => "disconnecting_to_ban",
+            NewConnectionState::Dialing { .. } => "dialing",
+            NewConnectionState::Disconnected => "disconnected",
+            NewConnectionState::Banned => "banned",
+            NewConnectionState::Unbanned => "unbanned",
+        }
+    }
+}
+
 /// The result of applying a score transition to a peer.
 enum ScoreTransitionResult {
     /// The peer has become disconnected.
@@ -1273,6 +1299,7 @@ impl BannedPeersCount {
 
 #[cfg(test)]
 mod tests {
+    use super::peer_info::MAX_HISTORY_LEN;
     use super::*;
     use libp2p::core::Multiaddr;
     use slog::{o, Drain};
@@ -1356,6 +1383,47 @@ mod tests {
         assert_eq!(pdb.connected_outbound_only_peers().count(), 1);
     }
 
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn test_peer_history_recorded() {
+        let mut pdb = get_db();
+        let random_peer = PeerId::random();
+
+        pdb.connect_ingoing(&random_peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+        let _ = pdb.report_peer(
+            &random_peer,
+            PeerAction::MidToleranceError,
+            ReportSource::RPC,
+            "test_msg",
+        );
+        let _ = pdb.inject_disconnect(&random_peer);
+
+        let peer_info = pdb.peer_info(&random_peer).unwrap();
+
+        let actions = peer_info.peer_actions().collect::<Vec<_>>();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].action, "mid_tolerance_error");
+        assert_eq!(actions[0].source, "rpc_error");
+        assert_eq!(actions[0].msg, "test_msg");
+        assert_eq!(actions[0].score, pdb.score(&random_peer));
+
+        let events = peer_info
+            .connection_history()
+            .map(|event| event.event.as_ref())
+            .collect::<Vec<_>>();
+        assert_eq!(events, vec!["connected_incoming", "disconnected"]);
+
+        // The history is bounded.
+        for _ in 0..100 {
+            pdb.connect_outgoing(&random_peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+        }
+        let peer_info = pdb.peer_info(&random_peer).unwrap();
+        assert_eq!(peer_info.connection_history().count(), MAX_HISTORY_LEN);
+        assert!(peer_info
+            .connection_history()
+            .all(|event| event.event == "connected_outgoing"));
+    }
+
     #[test]
     fn test_disconnected_removed_in_correct_order() {
         let mut pdb = get_db();
@@ -7,15 +7,19 @@ use crate::{rpc::MetaData, types::Subnet};
 use discv5::Enr;
 use serde::{
     ser::{SerializeStruct, Serializer},
-    Serialize,
+    Deserialize, Serialize,
 };
-use std::collections::HashSet;
+use std::borrow::Cow;
+use std::collections::{HashSet, VecDeque};
 use std::net::{IpAddr, SocketAddr};
-use std::time::Instant;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use strum::AsRefStr;
 use types::EthSpec;
 use PeerConnectionStatus::*;
 
+/// The maximum number of peer actions and connection events retained for each peer.
+pub(super) const MAX_HISTORY_LEN: usize = 32;
+
 /// Information about a given connected peer.
 #[derive(Clone, Debug, Serialize)]
 #[serde(bound = "T: EthSpec")]
@@ -51,6 +55,39 @@ pub struct PeerInfo<T: EthSpec> {
     connection_direction: Option<ConnectionDirection>,
     /// The enr of the peer, if known.
     enr: Option<Enr>,
+    /// The protocols the peer advertised via identify.
+    #[serde(skip)]
+    protocols: Vec<String>,
+    /// The most recent actions reported against this peer, oldest first.
+    #[serde(skip)]
+    peer_actions: VecDeque<PeerActionRecord>,
+    /// The most recent changes to this peer's connection state, oldest first.
+    #[serde(skip)]
+    connection_history: VecDeque<ConnectionEvent>,
+}
+
+/// A record of a `PeerAction` reported against a peer.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PeerActionRecord {
+    /// The action that was applied to the peer's score.
+    pub action: Cow<'static, str>,
+    /// The component which reported the peer.
+    pub source: Cow<'static, str>,
+    /// A short description of why the peer was reported.
+    pub msg: Cow<'static, str>,
+    /// The peer's score after the action was applied.
+    pub score: f64,
+    /// Seconds since the unix epoch at which the action was reported.
+    pub timestamp: u64,
+}
+
+/// A record of a change to a peer's connection state.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionEvent {
+    /// The connection state the peer transitioned to.
+    pub event: Cow<'static, str>,
+    /// Seconds since the unix epoch at which the transition occurred.
+    pub timestamp: u64,
 }
 
 impl<TSpec: EthSpec> Default for PeerInfo<TSpec> {
@@ -68,6 +105,9 @@ impl<TSpec: EthSpec> Default for PeerInfo<TSpec> {
             is_trusted: false,
             connection_direction: None,
             enr: None,
+            protocols: Vec::new(),
+            peer_actions: VecDeque::new(),
+            connection_history: VecDeque::new(),
         }
     }
 }
@@ -158,6 +198,21 @@ impl<T: EthSpec> PeerInfo<T> {
         0
     }
 
+    /// Returns the protocols the peer advertised via identify.
+    pub fn protocols(&self) -> &[String] {
+        &self.protocols
+    }
+
+    /// Returns the most recent actions reported against this peer, oldest first.
+    pub fn peer_actions(&self) -> impl Iterator<Item = &PeerActionRecord> {
+        self.peer_actions.iter()
+    }
+
+    /// Returns the most recent changes to this peer's connection state, oldest first.
+    pub fn connection_history(&self) -> impl Iterator<Item = &ConnectionEvent> {
+        self.connection_history.iter()
+    }
+
     /// Returns an iterator over the long-lived subnets if it has any.
     pub fn long_lived_subnets(&self) -> Vec<Subnet> {
         let mut long_lived_subnets = Vec::new();
@@ -342,6 +397,44 @@ impl<T: EthSpec> PeerInfo<T> {
         self.meta_data = Some(meta_data)
     }
 
+    /// Sets the protocols advertised by the peer.
+    // VISIBILITY: The peer manager is able to set the protocols
+    pub(in crate::peer_manager) fn set_protocols(&mut self, protocols: Vec<String>) {
+        self.protocols = protocols
+    }
+
+    /// Records an action reported against the peer, discarding the oldest record if the history
+    /// is full.
+    pub(super) fn record_peer_action(
+        &mut self,
+        action: &'static str,
+        source: &'static str,
+        msg: &'static str,
+    ) {
+        if self.peer_actions.len() >= MAX_HISTORY_LEN {
+            self.peer_actions.pop_front();
+        }
+        self.peer_actions.push_back(PeerActionRecord {
+            action: action.into(),
+            source: source.into(),
+            msg: msg.into(),
+            score: self.score.score(),
+            timestamp: unix_timestamp(),
+        });
+    }
+
+    /// Records a change to the peer's connection state, discarding the oldest event if the
+    /// history is full.
+    pub(super) fn record_connection_event(&mut self, event: &'static str) {
+        if self.connection_history.len() >= MAX_HISTORY_LEN {
+            self.connection_history.pop_front();
+        }
+        self.connection_history.push_back(ConnectionEvent {
+            event: event.into(),
+            timestamp: unix_timestamp(),
+        });
+    }
+
     /// Sets the connection status of the peer.
     pub(super) fn set_connection_status(&mut self, connection_status: PeerConnectionStatus) {
         self.connection_status = connection_status
@@ -562,3 +655,11 @@ impl Serialize for PeerConnectionStatus {
         }
     }
 }
+
+/// Returns the number of seconds since the unix epoch, or zero if the system clock is before it.
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
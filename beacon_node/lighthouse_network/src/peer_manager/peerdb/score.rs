@@ -6,9 +6,9 @@
 //!
 //! The scoring algorithms are currently experimental.
 use crate::service::gossipsub_scoring_parameters::GREYLIST_THRESHOLD as GOSSIPSUB_GREYLIST_THRESHOLD;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::time::Instant;
-use strum::AsRefStr;
+use strum::{AsRefStr, IntoStaticStr};
 use tokio::time::Duration;
 
 lazy_static! {
@@ -43,7 +43,7 @@ const GOSSIPSUB_POSITIVE_SCORE_WEIGHT: f64 = GOSSIPSUB_NEGATIVE_SCORE_WEIGHT;
 /// Each variant has an associated score change.
 // To easily assess the behaviour of scores changes the number of variants should stay low, and
 // somewhat generic.
-#[derive(Debug, Clone, Copy, AsRefStr)]
+#[derive(Debug, Clone, Copy, AsRefStr, IntoStaticStr)]
 #[strum(serialize_all = "snake_case")]
 pub enum PeerAction {
     /// We should not communicate more with this peer.
@@ -140,6 +140,32 @@ pub struct RealScore {
     last_updated: Instant,
 }
 
+/// A breakdown of the components which make up a peer's score.
+///
+/// `score` is the sum of `lighthouse_score` and `gossipsub_score_contribution`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScoreDetails {
+    /// The overall score used to decide whether to disconnect or ban the peer.
+    pub score: f64,
+    /// The component of the score derived from actions reported against the peer.
+    pub lighthouse_score: f64,
+    /// The score reported by gossipsub for the peer.
+    ///
+    /// libp2p only exposes the aggregate gossipsub score, not its per-topic parts.
+    pub gossipsub_score: f64,
+    /// The component of the score derived from `gossipsub_score`, after weighting.
+    ///
+    /// This is zero if the gossipsub score is being ignored, or if the lighthouse score alone is
+    /// low enough to ban the peer.
+    pub gossipsub_score_contribution: f64,
+    /// The weight applied to `gossipsub_score`, which may differ for positive and negative scores.
+    pub gossipsub_score_weight: f64,
+    /// Whether a negative gossipsub score is currently being ignored.
+    pub ignore_negative_gossipsub_score: bool,
+    /// The state the peer is in given its score.
+    pub state: String,
+}
+
 impl Default for RealScore {
     fn default() -> Self {
         RealScore {
@@ -318,6 +344,26 @@ impl Score {
         }
     }
 
+    /// Returns a breakdown of the score, or `None` for trusted peers which have the maximum score.
+    pub fn details(&self) -> Option<ScoreDetails> {
+        match self {
+            Self::Max => None,
+            Self::Real(score) => Some(ScoreDetails {
+                score: score.score,
+                lighthouse_score: score.lighthouse_score,
+                gossipsub_score: score.gossipsub_score,
+                gossipsub_score_contribution: score.score - score.lighthouse_score,
+                gossipsub_score_weight: if score.gossipsub_score >= 0.0 {
+                    GOSSIPSUB_POSITIVE_SCORE_WEIGHT
+                } else {
+                    GOSSIPSUB_NEGATIVE_SCORE_WEIGHT
+                },
+                ignore_negative_gossipsub_score: score.ignore_negative_gossipsub_score,
+                state: self.state().to_string(),
+            }),
+        }
+    }
+
     pub fn is_good_gossipsub_peer(&self) -> bool {
         match self {
             Self::Max => true,
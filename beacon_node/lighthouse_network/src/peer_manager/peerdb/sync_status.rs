@@ -56,6 +56,16 @@ impl SyncStatus {
         matches!(self, SyncStatus::Behind { .. })
     }
 
+    /// Returns the sync info advertised by the peer, if it is relevant and known.
+    pub fn info(&self) -> Option<&SyncInfo> {
+        match self {
+            SyncStatus::Synced { info }
+            | SyncStatus::Advanced { info }
+            | SyncStatus::Behind { info } => Some(info),
+            SyncStatus::IrrelevantPeer | SyncStatus::Unknown => None,
+        }
+    }
+
     /// Updates the peer's sync status, returning whether the status transitioned.
     ///
     /// E.g. returns `true` if the state changed from `Synced` to `Advanced`, but not if
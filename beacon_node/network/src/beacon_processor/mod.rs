@@ -51,6 +51,7 @@ use lighthouse_network::{
     Client, MessageId, NetworkGlobals, PeerId, PeerRequestId,
 };
 use logging::TimeLatch;
+use serde::{Deserialize, Serialize};
 use slog::{crit, debug, error, trace, warn, Logger};
 use std::collections::VecDeque;
 use std::future::Future;
@@ -63,6 +64,7 @@ use std::{cmp, collections::HashSet};
 use task_executor::TaskExecutor;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::error::TrySendError;
+use tokio_stream::wrappers::BroadcastStream;
 use types::{
     Attestation, AttesterSlashing, Hash256, LightClientFinalityUpdate, LightClientOptimisticUpdate,
     ProposerSlashing, SignedAggregateAndProof, SignedBeaconBlock, SignedBlsToExecutionChange,
@@ -70,7 +72,9 @@ use types::{
 };
 use work_reprocessing_queue::{
     spawn_reprocess_scheduler, QueuedAggregate, QueuedLightClientUpdate, QueuedRpcBlock,
-    QueuedUnaggregate, ReadyWork,
+    QueuedUnaggregate, ReadyWork, ADDITIONAL_QUEUED_BLOCK_DELAY, MAXIMUM_QUEUED_ATTESTATIONS,
+    MAXIMUM_QUEUED_BLOCKS, MAXIMUM_QUEUED_LIGHT_CLIENT_UPDATES, QUEUED_ATTESTATION_DELAY,
+    QUEUED_LIGHT_CLIENT_UPDATE_DELAY, QUEUED_RPC_BLOCK_DELAY,
 };
 
 use worker::{Toolbox, Worker};
@@ -82,7 +86,10 @@ mod worker;
 use crate::beacon_processor::work_reprocessing_queue::{
     QueuedBackfillBatch, QueuedGossipBlock, ReprocessQueueMessage,
 };
-pub use worker::{ChainSegmentProcessId, GossipAggregatePackage, GossipAttestationPackage};
+pub use worker::{
+    ChainSegmentProcessId, GossipAggregatePackage, GossipAttestationPackage,
+    GossipSyncSignaturePackage,
+};
 
 /// The maximum size of the channel for work events to the `BeaconProcessor`.
 ///
@@ -203,10 +210,12 @@ const WORKER_TASK_NAME: &str = "beacon_processor_worker";
 /// individually verifying each attestation signature.
 const MAX_GOSSIP_ATTESTATION_BATCH_SIZE: usize = 64;
 const MAX_GOSSIP_AGGREGATE_BATCH_SIZE: usize = 64;
+const MAX_GOSSIP_SYNC_SIGNATURE_BATCH_SIZE: usize = 64;
 
 /// Unique IDs used for metrics and testing.
 pub const WORKER_FREED: &str = "worker_freed";
 pub const NOTHING_TO_DO: &str = "nothing_to_do";
+pub const HEAD_RECOMPUTED: &str = "head_recomputed";
 pub const GOSSIP_ATTESTATION: &str = "gossip_attestation";
 pub const GOSSIP_ATTESTATION_BATCH: &str = "gossip_attestation_batch";
 pub const GOSSIP_AGGREGATE: &str = "gossip_aggregate";
@@ -217,6 +226,7 @@ pub const GOSSIP_VOLUNTARY_EXIT: &str = "gossip_voluntary_exit";
 pub const GOSSIP_PROPOSER_SLASHING: &str = "gossip_proposer_slashing";
 pub const GOSSIP_ATTESTER_SLASHING: &str = "gossip_attester_slashing";
 pub const GOSSIP_SYNC_SIGNATURE: &str = "gossip_sync_signature";
+pub const GOSSIP_SYNC_SIGNATURE_BATCH: &str = "gossip_sync_signature_batch";
 pub const GOSSIP_SYNC_CONTRIBUTION: &str = "gossip_sync_contribution";
 pub const GOSSIP_LIGHT_CLIENT_FINALITY_UPDATE: &str = "light_client_finality_update";
 pub const GOSSIP_LIGHT_CLIENT_OPTIMISTIC_UPDATE: &str = "light_client_optimistic_update";
@@ -846,6 +856,9 @@ pub enum Work<T: BeaconChainTypes> {
         subnet_id: SyncSubnetId,
         seen_timestamp: Duration,
     },
+    GossipSyncSignatureBatch {
+        packages: Vec<GossipSyncSignaturePackage>,
+    },
     GossipSyncContribution {
         message_id: MessageId,
         peer_id: PeerId,
@@ -915,6 +928,7 @@ impl<T: BeaconChainTypes> Work<T> {
             Work::GossipProposerSlashing { .. } => GOSSIP_PROPOSER_SLASHING,
             Work::GossipAttesterSlashing { .. } => GOSSIP_ATTESTER_SLASHING,
             Work::GossipSyncSignature { .. } => GOSSIP_SYNC_SIGNATURE,
+            Work::GossipSyncSignatureBatch { .. } => GOSSIP_SYNC_SIGNATURE_BATCH,
             Work::GossipSyncContribution { .. } => GOSSIP_SYNC_CONTRIBUTION,
             Work::GossipLightClientFinalityUpdate { .. } => GOSSIP_LIGHT_CLIENT_FINALITY_UPDATE,
             Work::GossipLightClientOptimisticUpdate { .. } => GOSSIP_LIGHT_CLIENT_OPTIMISTIC_UPDATE,
@@ -934,6 +948,25 @@ impl<T: BeaconChainTypes> Work<T> {
             Work::UnknownLightClientOptimisticUpdate { .. } => UNKNOWN_LIGHT_CLIENT_UPDATE,
         }
     }
+
+    /// Returns `true` if the work does not affect the head or attestations and can be deferred
+    /// whilst the head is recomputed ahead of the attestation deadline.
+    fn is_low_priority(&self) -> bool {
+        matches!(
+            self,
+            Work::BlocksByRangeRequest { .. }
+                | Work::BlocksByRootsRequest { .. }
+                | Work::LightClientBootstrapRequest { .. }
+                | Work::GossipAttesterSlashing { .. }
+                | Work::GossipProposerSlashing { .. }
+                | Work::GossipVoluntaryExit { .. }
+                | Work::GossipBlsToExecutionChange { .. }
+                | Work::ChainSegment {
+                    process_id: ChainSegmentProcessId::BackSyncBatchId { .. },
+                    ..
+                }
+        )
+    }
 }
 
 /// Unifies all the messages processed by the `BeaconProcessor`.
@@ -944,6 +977,8 @@ enum InboundEvent<T: BeaconChainTypes> {
     WorkEvent(WorkEvent<T>),
     /// A work event that was queued for re-processing has become ready.
     ReprocessingWork(WorkEvent<T>),
+    /// A head recomputation has finished whilst low-priority work was deferred.
+    HeadRecomputed,
 }
 
 /// Combines the various incoming event streams for the `BeaconProcessor` into a single stream.
@@ -957,6 +992,11 @@ struct InboundEvents<T: BeaconChainTypes> {
     event_rx: mpsc::Receiver<WorkEvent<T>>,
     /// Used internally for queuing work ready to be re-processed.
     reprocess_work_rx: mpsc::Receiver<ReadyWork<T>>,
+    /// Used by the beacon chain to signal that a head recomputation has finished.
+    recompute_head_finished_rx: BroadcastStream<()>,
+    /// Set when low-priority work has been deferred whilst the head is recomputed. Head
+    /// recomputations are ignored whilst this is `false`.
+    deferred_low_priority: bool,
 }
 
 impl<T: BeaconChainTypes> Stream for InboundEvents<T> {
@@ -975,6 +1015,16 @@ impl<T: BeaconChainTypes> Stream for InboundEvents<T> {
             Poll::Pending => {}
         }
 
+        // Head recomputations are only of interest whilst work is deferred, drain them otherwise. If
+        // the recompute which caused the deferral is still running when an earlier one is received,
+        // the work will simply be deferred again. A lagged receiver has still missed a recompute.
+        while let Poll::Ready(Some(_)) = self.recompute_head_finished_rx.poll_next_unpin(cx) {
+            if self.deferred_low_priority {
+                self.deferred_low_priority = false;
+                return Poll::Ready(Some(InboundEvent::HeadRecomputed));
+            }
+        }
+
         // Poll for delayed blocks before polling for new work. It might be the case that a delayed
         // block is required to successfully process some new work.
         match self.reprocess_work_rx.poll_recv(cx) {
@@ -1008,6 +1058,45 @@ pub enum InvalidBlockStorage {
     Disabled,
 }
 
+/// Tunable parameters for the `BeaconProcessor` and its work re-processing queue.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeaconProcessorConfig {
+    /// Delay after the start of a slot before an early block for that slot is processed. This
+    /// accounts for slight drift in the system clock.
+    pub early_block_delay: Duration,
+    /// How long to hold attestations which reference an unknown block before giving up on them.
+    pub unknown_block_attestation_delay: Duration,
+    /// How long to hold light client updates which reference an unknown parent.
+    pub unknown_parent_light_client_update_delay: Duration,
+    /// How long to hold an RPC block which is concurrently being imported via gossip.
+    pub rpc_block_delay: Duration,
+    /// Maximum number of early gossip blocks (and, separately, RPC blocks) to queue.
+    pub max_queued_blocks: usize,
+    /// Maximum number of attestations to queue.
+    pub max_queued_attestations: usize,
+    /// Maximum number of light client updates to queue.
+    pub max_queued_light_client_updates: usize,
+    /// When `true`, attestations which arrive up to one slot before they are permitted by the
+    /// gossip clock disparity are queued and processed at the start of their slot, rather than
+    /// being ignored.
+    pub queue_early_attestations: bool,
+}
+
+impl Default for BeaconProcessorConfig {
+    fn default() -> Self {
+        Self {
+            early_block_delay: ADDITIONAL_QUEUED_BLOCK_DELAY,
+            unknown_block_attestation_delay: QUEUED_ATTESTATION_DELAY,
+            unknown_parent_light_client_update_delay: QUEUED_LIGHT_CLIENT_UPDATE_DELAY,
+            rpc_block_delay: QUEUED_RPC_BLOCK_DELAY,
+            max_queued_blocks: MAXIMUM_QUEUED_BLOCKS,
+            max_queued_attestations: MAXIMUM_QUEUED_ATTESTATIONS,
+            max_queued_light_client_updates: MAXIMUM_QUEUED_LIGHT_CLIENT_UPDATES,
+            queue_early_attestations: false,
+        }
+    }
+}
+
 /// A mutli-threaded processor for messages received on the network
 /// that need to be processed by the `BeaconChain`
 ///
@@ -1022,6 +1111,7 @@ pub struct BeaconProcessor<T: BeaconChainTypes> {
     pub current_workers: usize,
     pub importing_blocks: DuplicateCache,
     pub invalid_block_storage: InvalidBlockStorage,
+    pub config: BeaconProcessorConfig,
     pub log: Logger,
 }
 
@@ -1107,6 +1197,7 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
             ready_work_tx,
             &self.executor,
             chain.slot_clock.clone(),
+            self.config.clone(),
             self.log.clone(),
         );
 
@@ -1119,16 +1210,27 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                 idle_rx,
                 event_rx,
                 reprocess_work_rx: ready_work_rx,
+                recompute_head_finished_rx: BroadcastStream::new(
+                    chain.subscribe_to_recompute_head_finished(),
+                ),
+                deferred_low_priority: false,
             };
 
             let enable_backfill_rate_limiting = chain.config.enable_backfill_rate_limiting;
 
             loop {
-                let work_event = match inbound_events.next().await {
+                let inbound_event = inbound_events.next().await;
+                let idle_event_id = match inbound_event {
+                    Some(InboundEvent::HeadRecomputed) => HEAD_RECOMPUTED,
+                    _ => WORKER_FREED,
+                };
+                let work_event = match inbound_event {
                     Some(InboundEvent::WorkerIdle) => {
                         self.current_workers = self.current_workers.saturating_sub(1);
                         None
                     }
+                    // No worker has been freed, but the deferred work may now be spawned.
+                    Some(InboundEvent::HeadRecomputed) => None,
                     Some(InboundEvent::WorkEvent(event)) if enable_backfill_rate_limiting => {
                         match QueuedBackfillBatch::try_from(event) {
                             Ok(backfill_batch) => {
@@ -1197,7 +1299,7 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                     let id = work_event
                         .as_ref()
                         .map(|event| event.work.str_id())
-                        .unwrap_or(WORKER_FREED);
+                        .unwrap_or(idle_event_id);
 
                     // We don't care if this message was successfully sent, we only use the journal
                     // during testing.
@@ -1205,6 +1307,11 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                 }
 
                 let can_spawn = self.current_workers < self.max_workers;
+                // Whilst the head is being recomputed ahead of the attestation deadline, leave
+                // the CPU to it and to the work that may change the head.
+                let defer_low_priority = self.beacon_chain.upgrade().map_or(false, |chain| {
+                    chain.is_recomputing_head_before_attestation_deadline()
+                });
                 let drop_during_sync = work_event
                     .as_ref()
                     .map_or(false, |event| event.drop_during_sync);
@@ -1340,8 +1447,58 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                         // and they don't influence fork choice.
                         } else if let Some(item) = sync_contribution_queue.pop() {
                             self.spawn_worker(item, toolbox);
-                        } else if let Some(item) = sync_message_queue.pop() {
-                            self.spawn_worker(item, toolbox);
+                        // Potentially use batching for sync committee messages, since every
+                        // member of the sync committee publishes one each slot.
+                        } else if sync_message_queue.len() > 0 {
+                            let batch_size = cmp::min(
+                                sync_message_queue.len(),
+                                MAX_GOSSIP_SYNC_SIGNATURE_BATCH_SIZE,
+                            );
+
+                            if batch_size < 2 {
+                                // One single message is in the queue, process it individually.
+                                if let Some(item) = sync_message_queue.pop() {
+                                    self.spawn_worker(item, toolbox);
+                                }
+                            } else {
+                                // Collect two or more messages into a batch, so they can take
+                                // advantage of batch signature verification.
+                                //
+                                // Note: this will convert the `Work::GossipSyncSignature` item into
+                                // a `Work::GossipSyncSignatureBatch` item.
+                                let mut packages = Vec::with_capacity(batch_size);
+                                for _ in 0..batch_size {
+                                    if let Some(item) = sync_message_queue.pop() {
+                                        match item {
+                                            Work::GossipSyncSignature {
+                                                message_id,
+                                                peer_id,
+                                                sync_signature,
+                                                subnet_id,
+                                                seen_timestamp,
+                                            } => {
+                                                packages.push(GossipSyncSignaturePackage::new(
+                                                    message_id,
+                                                    peer_id,
+                                                    sync_signature,
+                                                    subnet_id,
+                                                    seen_timestamp,
+                                                ));
+                                            }
+                                            _ => error!(
+                                                self.log,
+                                                "Invalid item in sync message queue"
+                                            ),
+                                        }
+                                    }
+                                }
+
+                                // Process all messages with a single worker.
+                                self.spawn_worker(
+                                    Work::GossipSyncSignatureBatch { packages },
+                                    toolbox,
+                                )
+                            }
                         // Aggregates and unaggregates queued for re-processing are older and we
                         // care about fresher ones, so check those first.
                         } else if let Some(item) = unknown_block_aggregate_queue.pop() {
@@ -1353,6 +1510,23 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                         // and BlocksByRoot)
                         } else if let Some(item) = status_queue.pop() {
                             self.spawn_worker(item, toolbox);
+                        // All the remaining work is low priority. Leave it queued until the head
+                        // recompute finishes, which is signalled by the beacon chain wherever the
+                        // recompute was started.
+                        } else if defer_low_priority {
+                            inbound_events.deferred_low_priority |= bbrange_queue.len() > 0
+                                || bbroots_queue.len() > 0
+                                || gossip_attester_slashing_queue.len() > 0
+                                || gossip_proposer_slashing_queue.len() > 0
+                                || gossip_voluntary_exit_queue.len() > 0
+                                || gossip_bls_to_execution_change_queue.len() > 0
+                                || backfill_chain_segment.len() > 0
+                                || lcbootstrap_queue.len() > 0;
+                            if let Some(work_journal_tx) = &work_journal_tx {
+                                // We don't care if this message was successfully sent, we only use the journal
+                                // during testing.
+                                let _ = work_journal_tx.try_send(NOTHING_TO_DO);
+                            }
                         } else if let Some(item) = bbrange_queue.pop() {
                             self.spawn_worker(item, toolbox);
                         } else if let Some(item) = bbroots_queue.pop() {
@@ -1424,8 +1598,13 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                             work_reprocessing_tx: work_reprocessing_tx.clone(),
                         };
 
+                        let defer = defer_low_priority && work.is_low_priority();
+                        if can_spawn && defer {
+                            inbound_events.deferred_low_priority = true;
+                        }
+
                         match work {
-                            _ if can_spawn => self.spawn_worker(work, toolbox),
+                            _ if can_spawn && !defer => self.spawn_worker(work, toolbox),
                             Work::GossipAttestation { .. } => attestation_queue.push(work),
                             // Attestation batches are formed internally within the
                             // `BeaconProcessor`, they are not sent from external services.
@@ -1458,6 +1637,13 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                                 gossip_attester_slashing_queue.push(work, work_id, &self.log)
                             }
                             Work::GossipSyncSignature { .. } => sync_message_queue.push(work),
+                            // Sync message batches are formed internally within the
+                            // `BeaconProcessor`, they are not sent from external services.
+                            Work::GossipSyncSignatureBatch { .. } => crit!(
+                                    self.log,
+                                    "Unsupported inbound event";
+                                    "type" => "GossipSyncSignatureBatch"
+                            ),
                             Work::GossipSyncContribution { .. } => {
                                 sync_contribution_queue.push(work)
                             }
@@ -1625,6 +1811,7 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
             chain,
             network_tx: self.network_tx.clone(),
             sync_tx: self.sync_tx.clone(),
+            queue_early_attestations: self.config.queue_early_attestations,
             log: self.log.clone(),
         };
 
@@ -1785,6 +1972,11 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                     seen_timestamp,
                 )
             }),
+            /*
+             * Batched sync committee message verification.
+             */
+            Work::GossipSyncSignatureBatch { packages } => task_spawner
+                .spawn_blocking(|| worker.process_gossip_sync_committee_signature_batch(packages)),
             /*
              * Sync contribution verification.
              */
@@ -204,6 +204,7 @@ impl TestRig {
             current_workers: 0,
             importing_blocks: duplicate_cache.clone(),
             invalid_block_storage: InvalidBlockStorage::Disabled,
+            config: BeaconProcessorConfig::default(),
             log: log.clone(),
         }
         .spawn_manager(beacon_processor_rx, Some(work_journal_tx));
@@ -859,6 +860,48 @@ async fn import_misc_gossip_ops() {
     );
 }
 
+/// Ensure that low-priority work is deferred whilst the head is recomputed before the attestation
+/// deadline, and released once the recompute finishes.
+#[tokio::test]
+#[allow(clippy::await_holding_lock)]
+async fn low_priority_work_deferred_during_head_recompute() {
+    // Exits need the long chain so validators aren't too young to exit.
+    let mut rig = TestRig::new(LONG_CHAIN).await;
+
+    let initial_voluntary_exits = rig.chain.op_pool.num_voluntary_exits();
+
+    // Stall a head recompute by holding the fork choice lock. The recompute is started outside
+    // of the `BeaconProcessor`, like those from the timer or the HTTP API.
+    let fork_choice_lock = rig.chain.canonical_head.fork_choice_write_lock();
+    let chain = rig.chain.clone();
+    let recompute_handle =
+        tokio::spawn(async move { chain.recompute_head_at_current_slot().await });
+    while !rig.chain.is_recomputing_head_before_attestation_deadline() {
+        tokio::task::yield_now().await;
+    }
+
+    rig.enqueue_gossip_voluntary_exit();
+
+    rig.assert_event_journal(&[GOSSIP_VOLUNTARY_EXIT]).await;
+    rig.assert_no_events_for(Duration::from_millis(100)).await;
+    assert_eq!(
+        rig.chain.op_pool.num_voluntary_exits(),
+        initial_voluntary_exits,
+        "exit should be deferred"
+    );
+
+    drop(fork_choice_lock);
+    recompute_handle.await.unwrap();
+
+    rig.assert_event_journal(&[HEAD_RECOMPUTED, WORKER_FREED, NOTHING_TO_DO])
+        .await;
+    assert_eq!(
+        rig.chain.op_pool.num_voluntary_exits(),
+        initial_voluntary_exits + 1,
+        "exit should be processed after the recompute"
+    );
+}
+
 /// Ensure that rpc block going to the reprocessing queue flow
 /// works when the duplicate cache handle is held by another task.
 #[tokio::test]
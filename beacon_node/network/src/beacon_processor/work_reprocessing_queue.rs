@@ -10,7 +10,7 @@
 //!
 //! Aggregated and unaggregated attestations that failed verification due to referencing an unknown
 //! block will be re-queued until their block is imported, or until they expire.
-use super::MAX_SCHEDULED_WORK_QUEUE_LEN;
+use super::{BeaconProcessorConfig, MAX_SCHEDULED_WORK_QUEUE_LEN};
 use crate::beacon_processor::{ChainSegmentProcessId, Work, WorkEvent};
 use crate::metrics;
 use crate::sync::manager::BlockProcessType;
@@ -36,7 +36,7 @@ use tokio::time::error::Error as TimeError;
 use tokio_util::time::delay_queue::{DelayQueue, Key as DelayKey};
 use types::{
     Attestation, EthSpec, Hash256, LightClientOptimisticUpdate, SignedAggregateAndProof,
-    SignedBeaconBlock, SubnetId,
+    SignedBeaconBlock, Slot, SubnetId,
 };
 
 const TASK_NAME: &str = "beacon_processor_reprocess_queue";
@@ -47,7 +47,7 @@ const LIGHT_CLIENT_UPDATES: &str = "lc_updates";
 
 /// Queue blocks for re-processing with an `ADDITIONAL_QUEUED_BLOCK_DELAY` after the slot starts.
 /// This is to account for any slight drift in the system clock.
-const ADDITIONAL_QUEUED_BLOCK_DELAY: Duration = Duration::from_millis(5);
+pub const ADDITIONAL_QUEUED_BLOCK_DELAY: Duration = Duration::from_millis(5);
 
 /// For how long to queue aggregated and unaggregated attestations for re-processing.
 pub const QUEUED_ATTESTATION_DELAY: Duration = Duration::from_secs(12);
@@ -61,13 +61,13 @@ pub const QUEUED_RPC_BLOCK_DELAY: Duration = Duration::from_secs(4);
 /// Set an arbitrary upper-bound on the number of queued blocks to avoid DoS attacks. The fact that
 /// we signature-verify blocks before putting them in the queue *should* protect against this, but
 /// it's nice to have extra protection.
-const MAXIMUM_QUEUED_BLOCKS: usize = 16;
+pub const MAXIMUM_QUEUED_BLOCKS: usize = 16;
 
 /// How many attestations we keep before new ones get dropped.
-const MAXIMUM_QUEUED_ATTESTATIONS: usize = 16_384;
+pub const MAXIMUM_QUEUED_ATTESTATIONS: usize = 16_384;
 
 /// How many light client updates we keep before new ones get dropped.
-const MAXIMUM_QUEUED_LIGHT_CLIENT_UPDATES: usize = 128;
+pub const MAXIMUM_QUEUED_LIGHT_CLIENT_UPDATES: usize = 128;
 
 // Process backfill batch 50%, 60%, 80% through each slot.
 //
@@ -100,6 +100,12 @@ pub enum ReprocessQueueMessage<T: BeaconChainTypes> {
     UnknownBlockUnaggregate(QueuedUnaggregate<T::EthSpec>),
     /// An aggregated attestation that references an unknown block.
     UnknownBlockAggregate(QueuedAggregate<T::EthSpec>),
+    /// An unaggregated attestation that arrived before its slot and should be processed at the
+    /// start of that slot.
+    EarlyUnaggregate(QueuedUnaggregate<T::EthSpec>),
+    /// An aggregated attestation that arrived before its slot and should be processed at the
+    /// start of that slot.
+    EarlyAggregate(QueuedAggregate<T::EthSpec>),
     /// A light client optimistic update that references a parent root that has not been seen as a parent.
     UnknownLightClientOptimisticUpdate(QueuedLightClientUpdate<T::EthSpec>),
     /// A new backfill batch that needs to be scheduled for processing.
@@ -244,6 +250,8 @@ struct ReprocessQueue<T: BeaconChainTypes> {
     queued_unaggregates: FnvHashMap<usize, (QueuedUnaggregate<T::EthSpec>, DelayKey)>,
     /// Attestations (aggregated and unaggregated) per root.
     awaiting_attestations_per_root: HashMap<Hash256, Vec<QueuedAttestationId>>,
+    /// Attestations (aggregated and unaggregated) which arrived before their slot.
+    queued_early_attestations: HashSet<QueuedAttestationId>,
     /// Queued Light Client Updates.
     queued_lc_updates: FnvHashMap<usize, (QueuedLightClientUpdate<T::EthSpec>, DelayKey)>,
     /// Light Client Updates per parent_root.
@@ -261,11 +269,12 @@ struct ReprocessQueue<T: BeaconChainTypes> {
     lc_update_delay_debounce: TimeLatch,
     next_backfill_batch_event: Option<Pin<Box<tokio::time::Sleep>>>,
     slot_clock: Pin<Box<T::SlotClock>>,
+    config: BeaconProcessorConfig,
 }
 
 pub type QueuedLightClientUpdateId = usize;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum QueuedAttestationId {
     Aggregate(usize),
     Unaggregate(usize),
@@ -275,12 +284,20 @@ impl<T: EthSpec> QueuedAggregate<T> {
     pub fn beacon_block_root(&self) -> &Hash256 {
         &self.attestation.message.aggregate.data.beacon_block_root
     }
+
+    pub fn slot(&self) -> Slot {
+        self.attestation.message.aggregate.data.slot
+    }
 }
 
 impl<T: EthSpec> QueuedUnaggregate<T> {
     pub fn beacon_block_root(&self) -> &Hash256 {
         &self.attestation.data.beacon_block_root
     }
+
+    pub fn slot(&self) -> Slot {
+        self.attestation.data.slot
+    }
 }
 
 impl<T: BeaconChainTypes> Stream for ReprocessQueue<T> {
@@ -379,35 +396,19 @@ pub fn spawn_reprocess_scheduler<T: BeaconChainTypes>(
     ready_work_tx: Sender<ReadyWork<T>>,
     executor: &TaskExecutor,
     slot_clock: T::SlotClock,
+    config: BeaconProcessorConfig,
     log: Logger,
 ) -> Sender<ReprocessQueueMessage<T>> {
     let (work_reprocessing_tx, work_reprocessing_rx) = mpsc::channel(MAX_SCHEDULED_WORK_QUEUE_LEN);
-    // Basic sanity check.
+    // Basic sanity check. The configured `early_block_delay` is checked when parsing the CLI.
     assert!(ADDITIONAL_QUEUED_BLOCK_DELAY < MAXIMUM_GOSSIP_CLOCK_DISPARITY);
 
-    let mut queue = ReprocessQueue {
+    let mut queue = ReprocessQueue::new(
         work_reprocessing_rx,
         ready_work_tx,
-        gossip_block_delay_queue: DelayQueue::new(),
-        rpc_block_delay_queue: DelayQueue::new(),
-        attestations_delay_queue: DelayQueue::new(),
-        lc_updates_delay_queue: DelayQueue::new(),
-        queued_gossip_block_roots: HashSet::new(),
-        queued_lc_updates: FnvHashMap::default(),
-        queued_aggregates: FnvHashMap::default(),
-        queued_unaggregates: FnvHashMap::default(),
-        awaiting_attestations_per_root: HashMap::new(),
-        awaiting_lc_updates_per_parent_root: HashMap::new(),
-        queued_backfill_batches: Vec::new(),
-        next_attestation: 0,
-        next_lc_update: 0,
-        early_block_debounce: TimeLatch::default(),
-        rpc_block_debounce: TimeLatch::default(),
-        attestation_delay_debounce: TimeLatch::default(),
-        lc_update_delay_debounce: TimeLatch::default(),
-        next_backfill_batch_event: None,
-        slot_clock: Box::pin(slot_clock.clone()),
-    };
+        slot_clock.clone(),
+        config,
+    );
 
     executor.spawn(
         async move {
@@ -428,6 +429,39 @@ pub fn spawn_reprocess_scheduler<T: BeaconChainTypes>(
 }
 
 impl<T: BeaconChainTypes> ReprocessQueue<T> {
+    fn new(
+        work_reprocessing_rx: Receiver<ReprocessQueueMessage<T>>,
+        ready_work_tx: Sender<ReadyWork<T>>,
+        slot_clock: T::SlotClock,
+        config: BeaconProcessorConfig,
+    ) -> Self {
+        ReprocessQueue {
+            work_reprocessing_rx,
+            ready_work_tx,
+            gossip_block_delay_queue: DelayQueue::new(),
+            rpc_block_delay_queue: DelayQueue::new(),
+            attestations_delay_queue: DelayQueue::new(),
+            lc_updates_delay_queue: DelayQueue::new(),
+            queued_gossip_block_roots: HashSet::new(),
+            queued_lc_updates: FnvHashMap::default(),
+            queued_aggregates: FnvHashMap::default(),
+            queued_unaggregates: FnvHashMap::default(),
+            awaiting_attestations_per_root: HashMap::new(),
+            queued_early_attestations: HashSet::new(),
+            awaiting_lc_updates_per_parent_root: HashMap::new(),
+            queued_backfill_batches: Vec::new(),
+            next_attestation: 0,
+            next_lc_update: 0,
+            early_block_debounce: TimeLatch::default(),
+            rpc_block_debounce: TimeLatch::default(),
+            attestation_delay_debounce: TimeLatch::default(),
+            lc_update_delay_debounce: TimeLatch::default(),
+            next_backfill_batch_event: None,
+            slot_clock: Box::pin(slot_clock),
+            config,
+        }
+    }
+
     fn handle_message(&mut self, msg: InboundEvent<T>, slot_clock: &T::SlotClock, log: &Logger) {
         use ReprocessQueueMessage::*;
         match msg {
@@ -444,12 +478,12 @@ impl<T: BeaconChainTypes> ReprocessQueue<T> {
 
                 if let Some(duration_till_slot) = slot_clock.duration_to_slot(block_slot) {
                     // Check to ensure this won't over-fill the queue.
-                    if self.queued_gossip_block_roots.len() >= MAXIMUM_QUEUED_BLOCKS {
+                    if self.queued_gossip_block_roots.len() >= self.config.max_queued_blocks {
                         if self.early_block_debounce.elapsed() {
                             warn!(
                                 log,
                                 "Early blocks queue is full";
-                                "queue_size" => MAXIMUM_QUEUED_BLOCKS,
+                                "queue_size" => self.config.max_queued_blocks,
                                 "msg" => "check system clock"
                             );
                         }
@@ -459,10 +493,10 @@ impl<T: BeaconChainTypes> ReprocessQueue<T> {
 
                     self.queued_gossip_block_roots.insert(block_root);
                     // Queue the block until the start of the appropriate slot, plus
-                    // `ADDITIONAL_QUEUED_BLOCK_DELAY`.
+                    // `early_block_delay`.
                     self.gossip_block_delay_queue.insert(
                         early_block,
-                        duration_till_slot + ADDITIONAL_QUEUED_BLOCK_DELAY,
+                        duration_till_slot + self.config.early_block_delay,
                     );
                 } else {
                     // If there is no duration till the next slot, check to see if the slot
@@ -491,17 +525,17 @@ impl<T: BeaconChainTypes> ReprocessQueue<T> {
                 }
             }
             // A rpc block arrived for processing at the same time when a gossip block
-            // for the same block hash is being imported. We wait for `QUEUED_RPC_BLOCK_DELAY`
+            // for the same block hash is being imported. We wait for `rpc_block_delay`
             // and then send the rpc block back for processing assuming the gossip import
             // has completed by then.
             InboundEvent::Msg(RpcBlock(mut rpc_block)) => {
                 // Check to ensure this won't over-fill the queue.
-                if self.rpc_block_delay_queue.len() >= MAXIMUM_QUEUED_BLOCKS {
+                if self.rpc_block_delay_queue.len() >= self.config.max_queued_blocks {
                     if self.rpc_block_debounce.elapsed() {
                         warn!(
                             log,
                             "RPC blocks queue is full";
-                            "queue_size" => MAXIMUM_QUEUED_BLOCKS,
+                            "queue_size" => self.config.max_queued_blocks,
                             "msg" => "check system clock"
                         );
                     }
@@ -521,9 +555,9 @@ impl<T: BeaconChainTypes> ReprocessQueue<T> {
                     return;
                 }
 
-                // Queue the block for 1/3rd of a slot
+                // Queue the block for 1/3rd of a slot (by default).
                 self.rpc_block_delay_queue
-                    .insert(rpc_block, QUEUED_RPC_BLOCK_DELAY);
+                    .insert(rpc_block, self.config.rpc_block_delay);
             }
             InboundEvent::ReadyRpcBlock(queued_rpc_block) => {
                 debug!(
@@ -543,12 +577,12 @@ impl<T: BeaconChainTypes> ReprocessQueue<T> {
                 }
             }
             InboundEvent::Msg(UnknownBlockAggregate(queued_aggregate)) => {
-                if self.attestations_delay_queue.len() >= MAXIMUM_QUEUED_ATTESTATIONS {
+                if self.attestations_delay_queue.len() >= self.config.max_queued_attestations {
                     if self.attestation_delay_debounce.elapsed() {
                         error!(
                             log,
                             "Aggregate attestation delay queue is full";
-                            "queue_size" => MAXIMUM_QUEUED_ATTESTATIONS,
+                            "queue_size" => self.config.max_queued_attestations,
                             "msg" => "check system clock"
                         );
                     }
@@ -561,7 +595,7 @@ impl<T: BeaconChainTypes> ReprocessQueue<T> {
                 // Register the delay.
                 let delay_key = self
                     .attestations_delay_queue
-                    .insert(att_id, QUEUED_ATTESTATION_DELAY);
+                    .insert(att_id, self.config.unknown_block_attestation_delay);
 
                 // Register this attestation for the corresponding root.
                 self.awaiting_attestations_per_root
@@ -576,12 +610,12 @@ impl<T: BeaconChainTypes> ReprocessQueue<T> {
                 self.next_attestation += 1;
             }
             InboundEvent::Msg(UnknownBlockUnaggregate(queued_unaggregate)) => {
-                if self.attestations_delay_queue.len() >= MAXIMUM_QUEUED_ATTESTATIONS {
+                if self.attestations_delay_queue.len() >= self.config.max_queued_attestations {
                     if self.attestation_delay_debounce.elapsed() {
                         error!(
                             log,
                             "Attestation delay queue is full";
-                            "queue_size" => MAXIMUM_QUEUED_ATTESTATIONS,
+                            "queue_size" => self.config.max_queued_attestations,
                             "msg" => "check system clock"
                         );
                     }
@@ -594,7 +628,7 @@ impl<T: BeaconChainTypes> ReprocessQueue<T> {
                 // Register the delay.
                 let delay_key = self
                     .attestations_delay_queue
-                    .insert(att_id, QUEUED_ATTESTATION_DELAY);
+                    .insert(att_id, self.config.unknown_block_attestation_delay);
 
                 // Register this attestation for the corresponding root.
                 self.awaiting_attestations_per_root
@@ -608,15 +642,40 @@ impl<T: BeaconChainTypes> ReprocessQueue<T> {
 
                 self.next_attestation += 1;
             }
+            // An attestation arrived before its slot. Queue it until the start of the slot, without
+            // registering it against its block root.
+            InboundEvent::Msg(EarlyAggregate(queued_aggregate)) => {
+                let slot = queued_aggregate.slot();
+                if let Some(delay) = self.early_attestation_delay(slot, slot_clock, log) {
+                    let att_id = QueuedAttestationId::Aggregate(self.next_attestation);
+                    let delay_key = self.attestations_delay_queue.insert(att_id, delay);
+                    self.queued_early_attestations.insert(att_id);
+                    self.queued_aggregates
+                        .insert(self.next_attestation, (queued_aggregate, delay_key));
+                    self.next_attestation += 1;
+                }
+            }
+            InboundEvent::Msg(EarlyUnaggregate(queued_unaggregate)) => {
+                let slot = queued_unaggregate.slot();
+                if let Some(delay) = self.early_attestation_delay(slot, slot_clock, log) {
+                    let att_id = QueuedAttestationId::Unaggregate(self.next_attestation);
+                    let delay_key = self.attestations_delay_queue.insert(att_id, delay);
+                    self.queued_early_attestations.insert(att_id);
+                    self.queued_unaggregates
+                        .insert(self.next_attestation, (queued_unaggregate, delay_key));
+                    self.next_attestation += 1;
+                }
+            }
             InboundEvent::Msg(UnknownLightClientOptimisticUpdate(
                 queued_light_client_optimistic_update,
             )) => {
-                if self.lc_updates_delay_queue.len() >= MAXIMUM_QUEUED_LIGHT_CLIENT_UPDATES {
+                if self.lc_updates_delay_queue.len() >= self.config.max_queued_light_client_updates
+                {
                     if self.lc_update_delay_debounce.elapsed() {
                         error!(
                             log,
                             "Light client updates delay queue is full";
-                            "queue_size" => MAXIMUM_QUEUED_LIGHT_CLIENT_UPDATES,
+                            "queue_size" => self.config.max_queued_light_client_updates,
                             "msg" => "check system clock"
                         );
                     }
@@ -629,7 +688,7 @@ impl<T: BeaconChainTypes> ReprocessQueue<T> {
                 // Register the delay.
                 let delay_key = self
                     .lc_updates_delay_queue
-                    .insert(lc_id, QUEUED_LIGHT_CLIENT_UPDATE_DELAY);
+                    .insert(lc_id, self.config.unknown_parent_light_client_update_delay);
 
                 // Register the light client update for the corresponding root.
                 self.awaiting_lc_updates_per_parent_root
@@ -799,9 +858,16 @@ impl<T: BeaconChainTypes> ReprocessQueue<T> {
                 )
             }
             InboundEvent::ReadyAttestation(queued_id) => {
-                metrics::inc_counter(
-                    &metrics::BEACON_PROCESSOR_REPROCESSING_QUEUE_EXPIRED_ATTESTATIONS,
-                );
+                // Early attestations are ready at the start of their slot, they don't expire.
+                if self.queued_early_attestations.remove(&queued_id) {
+                    metrics::inc_counter(
+                        &metrics::BEACON_PROCESSOR_REPROCESSING_QUEUE_READY_EARLY_ATTESTATIONS,
+                    );
+                } else {
+                    metrics::inc_counter(
+                        &metrics::BEACON_PROCESSOR_REPROCESSING_QUEUE_EXPIRED_ATTESTATIONS,
+                    );
+                }
 
                 if let Some((root, work)) = match queued_id {
                     QueuedAttestationId::Aggregate(id) => {
@@ -923,6 +989,38 @@ impl<T: BeaconChainTypes> ReprocessQueue<T> {
         );
     }
 
+    /// Returns the delay until the start of `slot` for an early attestation, or `None` if the
+    /// attestation should be dropped because the queue is full or the slot can't be scheduled.
+    ///
+    /// Attestations for a slot which has already started are given a zero delay so they are
+    /// processed immediately.
+    fn early_attestation_delay(
+        &mut self,
+        slot: Slot,
+        slot_clock: &T::SlotClock,
+        log: &Logger,
+    ) -> Option<Duration> {
+        if self.attestations_delay_queue.len() >= self.config.max_queued_attestations {
+            if self.attestation_delay_debounce.elapsed() {
+                error!(
+                    log,
+                    "Early attestation delay queue is full";
+                    "queue_size" => self.config.max_queued_attestations,
+                    "msg" => "check system clock"
+                );
+            }
+            return None;
+        }
+
+        match slot_clock.duration_to_slot(slot) {
+            Some(duration) => Some(duration),
+            None => slot_clock
+                .now()
+                .filter(|now| slot <= *now)
+                .map(|_| Duration::from_secs(0)),
+        }
+    }
+
     fn recompute_next_backfill_batch_event(&mut self) {
         // only recompute the `next_backfill_batch_event` if there are backfill batches in the queue
         if !self.queued_backfill_batches.is_empty() {
@@ -971,7 +1069,7 @@ mod tests {
     use slot_clock::TestingSlotClock;
     use store::MemoryStore;
     use types::MainnetEthSpec as E;
-    use types::Slot;
+    use types::{AggregateSignature, AttestationData, BitList, Slot};
 
     type TestBeaconChainType =
         Witness<TestingSlotClock, CachingEth1Backend<E>, E, MemoryStore<E>, MemoryStore<E>>;
@@ -1013,4 +1111,55 @@ mod tests {
             duration_to_next_slot + event_times[0]
         );
     }
+
+    fn unknown_block_unaggregate() -> QueuedUnaggregate<E> {
+        QueuedUnaggregate {
+            peer_id: PeerId::random(),
+            message_id: MessageId::new(&[]),
+            attestation: Box::new(Attestation {
+                aggregation_bits: BitList::with_capacity(1).unwrap(),
+                data: AttestationData::default(),
+                signature: AggregateSignature::empty(),
+            }),
+            subnet_id: SubnetId::new(0),
+            should_import: true,
+            seen_timestamp: Duration::from_secs(0),
+        }
+    }
+
+    #[tokio::test]
+    async fn max_queued_attestations_is_enforced() {
+        let log = Logger::root(slog::Discard, slog::o!());
+        let slot_clock = TestingSlotClock::new(
+            Slot::new(0),
+            Duration::from_secs(0),
+            Duration::from_secs(12),
+        );
+        let (_work_reprocessing_tx, work_reprocessing_rx) = mpsc::channel(1);
+        let (ready_work_tx, _ready_work_rx) = mpsc::channel(1);
+        let config = BeaconProcessorConfig {
+            max_queued_attestations: 2,
+            ..BeaconProcessorConfig::default()
+        };
+        let mut queue = ReprocessQueue::<TestBeaconChainType>::new(
+            work_reprocessing_rx,
+            ready_work_tx,
+            slot_clock.clone(),
+            config,
+        );
+
+        for _ in 0..3 {
+            queue.handle_message(
+                InboundEvent::Msg(ReprocessQueueMessage::UnknownBlockUnaggregate(
+                    unknown_block_unaggregate(),
+                )),
+                &slot_clock,
+                &log,
+            );
+        }
+
+        // The attestation beyond the limit is dropped.
+        assert_eq!(queue.attestations_delay_queue.len(), 2);
+        assert_eq!(queue.queued_unaggregates.len(), 2);
+    }
 }
@@ -6,7 +6,9 @@ use beacon_chain::{
     light_client_finality_update_verification::Error as LightClientFinalityUpdateError,
     light_client_optimistic_update_verification::Error as LightClientOptimisticUpdateError,
     observed_operations::ObservationOutcome,
-    sync_committee_verification::{self, Error as SyncCommitteeError},
+    sync_committee_verification::{
+        self, Error as SyncCommitteeError, VerifiedSyncCommitteeMessage,
+    },
     validator_monitor::get_block_delay_ms,
     BeaconChainError, BeaconChainTypes, BlockError, ForkChoiceError, GossipVerifiedBlock,
     NotifyExecutionLayer,
@@ -202,6 +204,34 @@ impl<E: EthSpec> GossipAggregatePackage<E> {
     }
 }
 
+/// Items required to verify a batch of gossip sync committee messages.
+#[derive(Debug)]
+pub struct GossipSyncSignaturePackage {
+    message_id: MessageId,
+    peer_id: PeerId,
+    sync_signature: Box<SyncCommitteeMessage>,
+    subnet_id: SyncSubnetId,
+    seen_timestamp: Duration,
+}
+
+impl GossipSyncSignaturePackage {
+    pub fn new(
+        message_id: MessageId,
+        peer_id: PeerId,
+        sync_signature: Box<SyncCommitteeMessage>,
+        subnet_id: SyncSubnetId,
+        seen_timestamp: Duration,
+    ) -> Self {
+        Self {
+            message_id,
+            peer_id,
+            sync_signature,
+            subnet_id,
+            seen_timestamp,
+        }
+    }
+}
+
 impl<T: BeaconChainTypes> Worker<T> {
     /* Auxiliary functions */
 
@@ -276,6 +306,7 @@ impl<T: BeaconChainTypes> Worker<T> {
         );
     }
 
+    #[tracing::instrument(level = "debug", skip_all, fields(num_attestations = packages.len()))]
     pub fn process_gossip_attestation_batch(
         self,
         packages: Vec<GossipAttestationPackage<T::EthSpec>>,
@@ -399,7 +430,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                                 self.log,
                                 "Attestation invalid for fork choice";
                                 "reason" => ?e,
-                                "peer" => %peer_id,
+                                "peer_id" => %peer_id,
                                 "beacon_block_root" => ?beacon_block_root
                             )
                         }
@@ -407,7 +438,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                             self.log,
                             "Error applying attestation to fork choice";
                             "reason" => ?e,
-                            "peer" => %peer_id,
+                            "peer_id" => %peer_id,
                             "beacon_block_root" => ?beacon_block_root
                         ),
                     }
@@ -421,7 +452,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                         self.log,
                         "Attestation invalid for agg pool";
                         "reason" => ?e,
-                        "peer" => %peer_id,
+                        "peer_id" => %peer_id,
                         "beacon_block_root" => ?beacon_block_root
                     )
                 }
@@ -489,6 +520,7 @@ impl<T: BeaconChainTypes> Worker<T> {
         );
     }
 
+    #[tracing::instrument(level = "debug", skip_all, fields(num_aggregates = packages.len()))]
     pub fn process_gossip_aggregate_batch(
         self,
         packages: Vec<GossipAggregatePackage<T::EthSpec>>,
@@ -602,7 +634,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                                 self.log,
                                 "Aggregate invalid for fork choice";
                                 "reason" => ?e,
-                                "peer" => %peer_id,
+                                "peer_id" => %peer_id,
                                 "beacon_block_root" => ?beacon_block_root
                             )
                         }
@@ -610,7 +642,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                             self.log,
                             "Error applying aggregate to fork choice";
                             "reason" => ?e,
-                            "peer" => %peer_id,
+                            "peer_id" => %peer_id,
                             "beacon_block_root" => ?beacon_block_root
                         ),
                     }
@@ -621,7 +653,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                         self.log,
                         "Attestation invalid for op pool";
                         "reason" => ?e,
-                        "peer" => %peer_id,
+                        "peer_id" => %peer_id,
                         "beacon_block_root" => ?beacon_block_root
                     )
                 }
@@ -658,6 +690,11 @@ impl<T: BeaconChainTypes> Worker<T> {
     ///
     /// Raises a log if there are errors.
     #[allow(clippy::too_many_arguments)]
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(slot = %block.slot(), peer_id = %peer_id)
+    )]
     pub async fn process_gossip_block(
         self,
         message_id: MessageId,
@@ -767,7 +804,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                     self.log,
                     "New block received";
                     "slot" => verified_block.block.slot(),
-                    "root" => ?verified_block.block_root
+                    "block_root" => ?verified_block.block_root
                 );
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Accept);
 
@@ -803,7 +840,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                 debug!(
                     self.log,
                     "Unknown parent for gossip block";
-                    "root" => ?block_root
+                    "block_root" => ?block_root
                 );
                 self.send_sync_message(SyncMessage::UnknownBlock(peer_id, block, block_root));
                 return None;
@@ -1066,7 +1103,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                     self.log,
                     "Dropping exit for already exiting validator";
                     "validator_index" => validator_index,
-                    "peer" => %peer_id
+                    "peer_id" => %peer_id
                 );
                 return;
             }
@@ -1075,7 +1112,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                     self.log,
                     "Dropping invalid exit";
                     "validator_index" => validator_index,
-                    "peer" => %peer_id,
+                    "peer_id" => %peer_id,
                     "error" => ?e
                 );
                 // These errors occur due to a fault in the beacon chain. It is not necessarily
@@ -1127,7 +1164,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                     "Dropping proposer slashing";
                     "reason" => "Already seen a proposer slashing for that validator",
                     "validator_index" => validator_index,
-                    "peer" => %peer_id
+                    "peer_id" => %peer_id
                 );
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Ignore);
                 return;
@@ -1139,7 +1176,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                     self.log,
                     "Dropping invalid proposer slashing";
                     "validator_index" => validator_index,
-                    "peer" => %peer_id,
+                    "peer_id" => %peer_id,
                     "error" => ?e
                 );
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Ignore);
@@ -1186,7 +1223,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                     self.log,
                     "Dropping attester slashing";
                     "reason" => "Slashings already known for all slashed validators",
-                    "peer" => %peer_id
+                    "peer_id" => %peer_id
                 );
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Ignore);
                 return;
@@ -1195,7 +1232,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                 debug!(
                     self.log,
                     "Dropping invalid attester slashing";
-                    "peer" => %peer_id,
+                    "peer_id" => %peer_id,
                     "error" => ?e
                 );
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Ignore);
@@ -1244,7 +1281,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                     self.log,
                     "Dropping BLS to execution change";
                     "validator_index" => validator_index,
-                    "peer" => %peer_id
+                    "peer_id" => %peer_id
                 );
                 return;
             }
@@ -1253,7 +1290,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                     self.log,
                     "Dropping invalid BLS to execution change";
                     "validator_index" => validator_index,
-                    "peer" => %peer_id,
+                    "peer_id" => %peer_id,
                     "error" => ?e
                 );
                 // We ignore pre-capella messages without penalizing peers.
@@ -1316,10 +1353,93 @@ impl<T: BeaconChainTypes> Worker<T> {
         seen_timestamp: Duration,
     ) {
         let message_slot = sync_signature.slot;
-        let sync_signature = match self
+        let result = self
             .chain
-            .verify_sync_committee_message_for_gossip(sync_signature, subnet_id)
+            .verify_sync_committee_message_for_gossip(sync_signature, subnet_id);
+
+        self.process_gossip_sync_committee_signature_result(
+            result,
+            message_id,
+            peer_id,
+            message_slot,
+            seen_timestamp,
+        );
+    }
+
+    /// Process a batch of sync committee signatures received from the gossip network, using batch
+    /// BLS verification.
+    ///
+    /// Each result is handled exactly as it would be by
+    /// `Self::process_gossip_sync_committee_signature`.
+    pub fn process_gossip_sync_committee_signature_batch(
+        self,
+        packages: Vec<GossipSyncSignaturePackage>,
+    ) {
+        // Keep the metadata of each package so the results can be attributed to the right peer.
+        let (metadata, sync_messages): (Vec<_>, Vec<_>) = packages
+            .into_iter()
+            .map(|package| {
+                (
+                    (
+                        package.message_id,
+                        package.peer_id,
+                        package.sync_signature.slot,
+                        package.seen_timestamp,
+                    ),
+                    (*package.sync_signature, package.subnet_id),
+                )
+            })
+            .unzip();
+
+        let results = match self
+            .chain
+            .batch_verify_sync_committee_messages_for_gossip(sync_messages)
         {
+            Ok(results) => results,
+            Err(e) => {
+                error!(
+                    self.log,
+                    "Batch sync message verification failed";
+                    "error" => ?e
+                );
+                return;
+            }
+        };
+
+        // Sanity check.
+        if results.len() != metadata.len() {
+            // The log is `crit` since in this scenario we might be penalizing/rewarding the wrong
+            // peer.
+            crit!(
+                self.log,
+                "Batch sync message result mismatch";
+                "results" => results.len(),
+                "packages" => metadata.len(),
+            )
+        }
+
+        for (result, (message_id, peer_id, message_slot, seen_timestamp)) in
+            results.into_iter().zip(metadata)
+        {
+            self.process_gossip_sync_committee_signature_result(
+                result,
+                message_id,
+                peer_id,
+                message_slot,
+                seen_timestamp,
+            );
+        }
+    }
+
+    fn process_gossip_sync_committee_signature_result(
+        &self,
+        result: Result<VerifiedSyncCommitteeMessage, SyncCommitteeError>,
+        message_id: MessageId,
+        peer_id: PeerId,
+        message_slot: Slot,
+        seen_timestamp: Duration,
+    ) {
+        let sync_signature = match result {
             Ok(sync_signature) => sync_signature,
             Err(e) => {
                 self.handle_sync_committee_message_failure(
@@ -1357,7 +1477,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                 self.log,
                 "Sync committee signature invalid for agg pool";
                 "reason" => ?e,
-                "peer" => %peer_id,
+                "peer_id" => %peer_id,
             )
         }
 
@@ -1419,7 +1539,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                 self.log,
                 "Sync contribution invalid for op pool";
                 "reason" => ?e,
-                "peer" => %peer_id,
+                "peer_id" => %peer_id,
             )
         }
         metrics::inc_counter(&metrics::BEACON_PROCESSOR_SYNC_CONTRIBUTION_IMPORTED_TOTAL);
@@ -1446,7 +1566,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                         debug!(
                             self.log,
                             "Light client invalid finality update";
-                            "peer" => %peer_id,
+                            "peer_id" => %peer_id,
                             "error" => ?e,
                         );
 
@@ -1460,7 +1580,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                         debug!(
                             self.log,
                             "Light client finality update too early";
-                            "peer" => %peer_id,
+                            "peer_id" => %peer_id,
                             "error" => ?e,
                         );
 
@@ -1473,7 +1593,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                     LightClientFinalityUpdateError::FinalityUpdateAlreadySeen => debug!(
                         self.log,
                         "Light client finality update already seen";
-                        "peer" => %peer_id,
+                        "peer_id" => %peer_id,
                         "error" => ?e,
                     ),
                     LightClientFinalityUpdateError::BeaconChainError(_)
@@ -1482,7 +1602,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                     | LightClientFinalityUpdateError::FailedConstructingUpdate => debug!(
                         self.log,
                         "Light client error constructing finality update";
-                        "peer" => %peer_id,
+                        "peer_id" => %peer_id,
                         "error" => ?e,
                     ),
                 }
@@ -1507,7 +1627,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                 debug!(
                     self.log,
                     "Light client successful optimistic update";
-                    "peer" => %peer_id,
+                    "peer_id" => %peer_id,
                     "parent_root" => %verified_light_client_optimistic_update.parent_root,
                 );
 
@@ -1567,7 +1687,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                         debug!(
                             self.log,
                             "Light client invalid optimistic update";
-                            "peer" => %peer_id,
+                            "peer_id" => %peer_id,
                             "error" => ?e,
                         );
 
@@ -1582,7 +1702,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                         debug!(
                             self.log,
                             "Light client optimistic update too early";
-                            "peer" => %peer_id,
+                            "peer_id" => %peer_id,
                             "error" => ?e,
                         );
 
@@ -1598,7 +1718,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                         debug!(
                             self.log,
                             "Light client optimistic update already seen";
-                            "peer" => %peer_id,
+                            "peer_id" => %peer_id,
                             "error" => ?e,
                         )
                     }
@@ -1611,7 +1731,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                         debug!(
                             self.log,
                             "Light client error constructing optimistic update";
-                            "peer" => %peer_id,
+                            "peer_id" => %peer_id,
                             "error" => ?e,
                         )
                     }
@@ -1636,6 +1756,61 @@ impl<T: BeaconChainTypes> Worker<T> {
         let attestation_type = failed_att.kind();
         metrics::register_attestation_error(&error);
         match &error {
+            AttnError::FutureSlot {
+                attestation_slot,
+                latest_permissible_slot,
+            } if self.queue_early_attestations
+                && *attestation_slot <= *latest_permissible_slot + 1
+                && reprocess_tx.is_some() =>
+            {
+                /*
+                 * The attestation is at most one slot early. Rather than ignoring it, hold on to
+                 * it until the start of its slot and process it then.
+                 */
+                trace!(
+                    self.log,
+                    "Queueing early attestation";
+                    "peer_id" => %peer_id,
+                    "block" => ?beacon_block_root,
+                    "type" => ?attestation_type,
+                    "slot" => attestation_slot,
+                );
+                let msg = match failed_att {
+                    FailedAtt::Aggregate {
+                        attestation,
+                        seen_timestamp,
+                    } => ReprocessQueueMessage::EarlyAggregate(QueuedAggregate {
+                        peer_id,
+                        message_id,
+                        attestation,
+                        seen_timestamp,
+                    }),
+                    FailedAtt::Unaggregate {
+                        attestation,
+                        subnet_id,
+                        should_import,
+                        seen_timestamp,
+                    } => ReprocessQueueMessage::EarlyUnaggregate(QueuedUnaggregate {
+                        peer_id,
+                        message_id,
+                        attestation,
+                        subnet_id,
+                        should_import,
+                        seen_timestamp,
+                    }),
+                };
+
+                if let Some(sender) = reprocess_tx {
+                    if sender.try_send(msg).is_err() {
+                        error!(
+                            self.log,
+                            "Failed to send early attestation for re-processing";
+                        )
+                    }
+                }
+
+                return;
+            }
             AttnError::FutureSlot { .. } => {
                 /*
                  * These errors can be triggered by a mismatch between our slot and the peer.
@@ -2578,7 +2753,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                         "Failed to store invalid block/error";
                         "error" => e,
                         "path" => ?path,
-                        "root" => ?block_root,
+                        "block_root" => ?block_root,
                         "slot" => block.slot(),
                     )
                 } else {
@@ -2586,7 +2761,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                         log,
                         "Stored invalid block/error ";
                         "path" => ?path,
-                        "root" => ?block_root,
+                        "block_root" => ?block_root,
                         "slot" => block.slot(),
                     )
                 }
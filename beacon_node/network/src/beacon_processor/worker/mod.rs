@@ -9,7 +9,9 @@ mod gossip_methods;
 mod rpc_methods;
 mod sync_methods;
 
-pub use gossip_methods::{GossipAggregatePackage, GossipAttestationPackage};
+pub use gossip_methods::{
+    GossipAggregatePackage, GossipAttestationPackage, GossipSyncSignaturePackage,
+};
 pub use sync_methods::ChainSegmentProcessId;
 
 pub(crate) const FUTURE_SLOT_TOLERANCE: u64 = 1;
@@ -19,6 +21,8 @@ pub struct Worker<T: BeaconChainTypes> {
     pub chain: Arc<BeaconChain<T>>,
     pub network_tx: mpsc::UnboundedSender<NetworkMessage<T::EthSpec>>,
     pub sync_tx: mpsc::UnboundedSender<SyncMessage<T::EthSpec>>,
+    /// Queue attestations from a future slot for processing at the start of that slot.
+    pub queue_early_attestations: bool,
     pub log: Logger,
 }
 
@@ -106,7 +106,7 @@ impl<T: BeaconChainTypes> Worker<T> {
     pub fn process_status(&self, peer_id: PeerId, status: StatusMessage) {
         match self.check_peer_relevance(&status) {
             Ok(Some(irrelevant_reason)) => {
-                debug!(self.log, "Handshake Failure"; "peer" => %peer_id, "reason" => irrelevant_reason);
+                debug!(self.log, "Handshake Failure"; "peer_id" => %peer_id, "reason" => irrelevant_reason);
                 self.goodbye_peer(peer_id, GoodbyeReason::IrrelevantNetwork);
             }
             Ok(None) => {
@@ -158,7 +158,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                             debug!(
                                 self.log,
                                 "Peer requested unknown block";
-                                "peer" => %peer_id,
+                                "peer_id" => %peer_id,
                                 "request_root" => ?root
                             );
                         }
@@ -183,7 +183,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                             debug!(
                                 self.log,
                                 "Error fetching block for peer";
-                                "peer" => %peer_id,
+                                "peer_id" => %peer_id,
                                 "request_root" => ?root,
                                 "error" => ?e,
                             );
@@ -193,7 +193,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                 debug!(
                     self.log,
                     "Received BlocksByRoot Request";
-                    "peer" => %peer_id,
+                    "peer_id" => %peer_id,
                     "requested" => requested_blocks,
                     "returned" => %send_block_count
                 );
@@ -446,7 +446,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                     debug!(
                         self.log,
                         "BlocksByRange outgoing response processed";
-                        "peer" => %peer_id,
+                        "peer_id" => %peer_id,
                         "msg" => "Failed to return all requested blocks",
                         "start_slot" => req.start_slot(),
                         "current_slot" => current_slot,
@@ -457,7 +457,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                     debug!(
                         self.log,
                         "BlocksByRange outgoing response processed";
-                        "peer" => %peer_id,
+                        "peer_id" => %peer_id,
                         "start_slot" => req.start_slot(),
                         "current_slot" => current_slot,
                         "requested" => req.count(),
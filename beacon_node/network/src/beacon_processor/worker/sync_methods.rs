@@ -42,6 +42,7 @@ struct ChainSegmentFailed {
 impl<T: BeaconChainTypes> Worker<T> {
     /// Attempt to process a block received from a direct RPC request.
     #[allow(clippy::too_many_arguments)]
+    #[tracing::instrument(level = "debug", skip_all, fields(block_root = ?block_root))]
     pub async fn process_rpc_block(
         self,
         block_root: Hash256,
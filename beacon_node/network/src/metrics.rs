@@ -361,6 +361,12 @@ lazy_static! {
         //decimal_buckets(-1,2)
 
     );
+    pub static ref GOSSIP_MESSAGE_SLOT_START_DELAY_TIME: Result<HistogramVec> = try_create_histogram_vec_with_buckets(
+        "gossipsub_message_slot_start_delay_time",
+        "Duration between the start of a message's slot and when it is first received, by topic.",
+        Ok(vec![0.1, 0.2, 0.3,0.4,0.5,0.75,1.0,1.25,1.5,1.75,2.0,2.5,3.0,3.5,4.0,5.0,6.0,7.0,8.0,9.0,10.0,15.0,20.0]),
+        &["topic"]
+    );
     pub static ref BEACON_BLOCK_LAST_DELAY: Result<IntGauge> = try_create_int_gauge(
         "beacon_block_last_delay",
         "Keeps track of the last block's delay from the start of the slot"
@@ -384,6 +390,10 @@ lazy_static! {
         "beacon_processor_reprocessing_queue_expired_attestations",
         "Number of queued attestations which have expired before a matching block has been found."
     );
+    pub static ref BEACON_PROCESSOR_REPROCESSING_QUEUE_READY_EARLY_ATTESTATIONS: Result<IntCounter> = try_create_int_counter(
+        "beacon_processor_reprocessing_queue_ready_early_attestations",
+        "Number of attestations which arrived before their slot and were queued until it started."
+    );
     pub static ref BEACON_PROCESSOR_REPROCESSING_QUEUE_MATCHED_ATTESTATIONS: Result<IntCounter> = try_create_int_counter(
         "beacon_processor_reprocessing_queue_matched_attestations",
         "Number of queued attestations where as matching block has been imported."
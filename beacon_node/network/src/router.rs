@@ -6,14 +6,16 @@
 #![allow(clippy::unit_arg)]
 
 use crate::beacon_processor::{
-    BeaconProcessor, BeaconProcessorSend, InvalidBlockStorage, WorkEvent as BeaconWorkEvent,
-    MAX_WORK_EVENT_QUEUE_LEN,
+    BeaconProcessor, BeaconProcessorConfig, BeaconProcessorSend, InvalidBlockStorage,
+    WorkEvent as BeaconWorkEvent, MAX_WORK_EVENT_QUEUE_LEN,
 };
 use crate::error;
+use crate::metrics;
 use crate::service::{NetworkMessage, RequestId};
 use crate::status::status_message;
 use crate::sync::manager::RequestId as SyncId;
 use crate::sync::SyncMessage;
+use beacon_chain::validator_monitor::get_slot_delay_ms;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use futures::prelude::*;
 use lighthouse_network::rpc::*;
@@ -86,6 +88,7 @@ impl<T: BeaconChainTypes> Router<T> {
         network_send: mpsc::UnboundedSender<NetworkMessage<T::EthSpec>>,
         executor: task_executor::TaskExecutor,
         invalid_block_storage: InvalidBlockStorage,
+        beacon_processor_config: BeaconProcessorConfig,
         log: slog::Logger,
     ) -> error::Result<mpsc::UnboundedSender<RouterMessage<T::EthSpec>>> {
         let message_handler_log = log.new(o!("service"=> "router"));
@@ -118,6 +121,7 @@ impl<T: BeaconChainTypes> Router<T> {
             current_workers: 0,
             importing_blocks: Default::default(),
             invalid_block_storage,
+            config: beacon_processor_config,
             log: log.clone(),
         }
         .spawn_manager(beacon_processor_receive, None);
@@ -234,6 +238,30 @@ impl<T: BeaconChainTypes> Router<T> {
         }
     }
 
+    /// Records the delay between the start of the message's slot and when it was received, for
+    /// the message types which are expected to be published at a fixed point in their slot.
+    fn observe_gossip_slot_start_delay(&self, gossip_message: &PubsubMessage<T::EthSpec>) {
+        let slot = match gossip_message {
+            PubsubMessage::BeaconBlock(block) => block.slot(),
+            PubsubMessage::AggregateAndProofAttestation(aggregate_and_proof) => {
+                aggregate_and_proof.message.aggregate.data.slot
+            }
+            PubsubMessage::Attestation(subnet_attestation) => subnet_attestation.1.data.slot,
+            PubsubMessage::SignedContributionAndProof(contribution_and_proof) => {
+                contribution_and_proof.message.contribution.slot
+            }
+            PubsubMessage::SyncCommitteeMessage(sync_committee_msg) => sync_committee_msg.1.slot,
+            _ => return,
+        };
+
+        let delay = get_slot_delay_ms(timestamp_now(), slot, &self.chain.slot_clock);
+        metrics::observe_timer_vec(
+            &metrics::GOSSIP_MESSAGE_SLOT_START_DELAY_TIME,
+            &[&gossip_message.kind().to_string()],
+            delay,
+        );
+    }
+
     /// Handle RPC messages.
     /// Note: `should_process` is currently only useful for the `Attestation` variant.
     /// if `should_process` is `false`, we only propagate the message on successful verification,
@@ -245,6 +273,8 @@ impl<T: BeaconChainTypes> Router<T> {
         gossip_message: PubsubMessage<T::EthSpec>,
         should_process: bool,
     ) {
+        self.observe_gossip_slot_start_delay(&gossip_message);
+
         match gossip_message {
             PubsubMessage::AggregateAndProofAttestation(aggregate_and_proof) => self
                 .send_beacon_processor_work(BeaconWorkEvent::aggregated_attestation(
@@ -370,7 +400,7 @@ impl<T: BeaconChainTypes> Router<T> {
 
     fn send_status(&mut self, peer_id: PeerId) {
         let status_message = status_message(&self.chain);
-        debug!(self.log, "Sending Status Request"; "peer" => %peer_id, &status_message);
+        debug!(self.log, "Sending Status Request"; "peer_id" => %peer_id, &status_message);
         self.network
             .send_processor_request(peer_id, Request::Status(status_message));
     }
@@ -439,7 +469,7 @@ impl<T: BeaconChainTypes> Router<T> {
         trace!(
             self.log,
             "Received BlocksByRange Response";
-            "peer" => %peer_id,
+            "peer_id" => %peer_id,
         );
 
         self.send_to_sync(SyncMessage::RpcBlock {
@@ -470,7 +500,7 @@ impl<T: BeaconChainTypes> Router<T> {
         trace!(
             self.log,
             "Received BlocksByRoot Response";
-            "peer" => %peer_id,
+            "peer_id" => %peer_id,
         );
         self.send_to_sync(SyncMessage::RpcBlock {
             peer_id,
@@ -1,5 +1,5 @@
 use super::sync::manager::RequestId as SyncId;
-use crate::beacon_processor::InvalidBlockStorage;
+use crate::beacon_processor::{BeaconProcessorConfig, InvalidBlockStorage};
 use crate::persisted_dht::{clear_dht, load_dht, persist_dht};
 use crate::router::{Router, RouterMessage};
 use crate::subnet_service::SyncCommitteeService;
@@ -40,10 +40,6 @@ mod tests;
 
 /// The interval (in seconds) that various network metrics will update.
 const METRIC_UPDATE_INTERVAL: u64 = 5;
-/// Number of slots before the fork when we should subscribe to the new fork topics.
-const SUBSCRIBE_DELAY_SLOTS: u64 = 2;
-/// Delay after a fork where we unsubscribe from pre-fork topics.
-const UNSUBSCRIBE_DELAY_EPOCHS: u64 = 2;
 /// Size of the queue for validator subnet subscriptions. The number is chosen so that we may be
 /// able to run tens of thousands of validators on one BN.
 const VALIDATOR_SUBSCRIPTION_MESSAGE_QUEUE_SIZE: usize = 65_536;
@@ -200,6 +196,10 @@ pub struct NetworkService<T: BeaconChainTypes> {
     next_fork_subscriptions: Pin<Box<OptionFuture<Sleep>>>,
     /// A delay that expires when we need to unsubscribe from old fork topics.
     next_unsubscribe: Pin<Box<OptionFuture<Sleep>>>,
+    /// Number of slots before a fork when we subscribe to the new fork topics.
+    fork_subscribe_slots: u64,
+    /// Number of epochs after a fork when we unsubscribe from pre-fork topics.
+    fork_unsubscribe_epochs: u64,
     /// Subscribe to all the subnets once synced.
     subscribe_all_subnets: bool,
     /// Shutdown beacon node after sync is complete.
@@ -222,9 +222,37 @@ impl<T: BeaconChainTypes> NetworkService<T> {
     pub async fn start(
         beacon_chain: Arc<BeaconChain<T>>,
         config: &NetworkConfig,
+        beacon_processor_config: BeaconProcessorConfig,
         executor: task_executor::TaskExecutor,
         gossipsub_registry: Option<&'_ mut Registry>,
     ) -> error::Result<(Arc<NetworkGlobals<T::EthSpec>>, NetworkSenders<T::EthSpec>)> {
+        let (network_service, network_globals, network_senders) = Self::build(
+            beacon_chain,
+            config,
+            beacon_processor_config,
+            executor.clone(),
+            gossipsub_registry,
+        )
+        .await?;
+
+        network_service.spawn_service(executor);
+
+        Ok((network_globals, network_senders))
+    }
+
+    /// Builds the network service without spawning it, see `Self::start`.
+    #[allow(clippy::type_complexity)]
+    async fn build(
+        beacon_chain: Arc<BeaconChain<T>>,
+        config: &NetworkConfig,
+        beacon_processor_config: BeaconProcessorConfig,
+        executor: task_executor::TaskExecutor,
+        gossipsub_registry: Option<&'_ mut Registry>,
+    ) -> error::Result<(
+        NetworkService<T>,
+        Arc<NetworkGlobals<T::EthSpec>>,
+        NetworkSenders<T::EthSpec>,
+    )> {
         let network_log = executor.log().clone();
         // build the channels for external comms
         let (network_senders, network_recievers) = NetworkSenders::new();
@@ -255,7 +283,9 @@ impl<T: BeaconChainTypes> NetworkService<T> {
 
         // keep track of when our fork_id needs to be updated
         let next_fork_update = Box::pin(next_fork_delay(&beacon_chain).into());
-        let next_fork_subscriptions = Box::pin(next_fork_subscriptions_delay(&beacon_chain).into());
+        let next_fork_subscriptions = Box::pin(
+            next_fork_subscriptions_delay(&beacon_chain, config.fork_subscribe_slots).into(),
+        );
         let next_unsubscribe = Box::pin(None.into());
 
         let current_slot = beacon_chain
@@ -311,6 +341,7 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             network_senders.network_send(),
             executor.clone(),
             invalid_block_storage,
+            beacon_processor_config,
             network_log.clone(),
         )?;
 
@@ -336,7 +367,7 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             validator_subscription_recv,
         } = network_recievers;
 
-        // create the network service and spawn the task
+        // create the network service
         let network_log = network_log.new(o!("service" => "network"));
         let network_service = NetworkService {
             beacon_chain,
@@ -353,6 +384,8 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             next_fork_update,
             next_fork_subscriptions,
             next_unsubscribe,
+            fork_subscribe_slots: config.fork_subscribe_slots,
+            fork_unsubscribe_epochs: config.fork_unsubscribe_epochs,
             subscribe_all_subnets: config.subscribe_all_subnets,
             shutdown_after_sync: config.shutdown_after_sync,
             metrics_enabled: config.metrics_enabled,
@@ -363,9 +396,7 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             enable_light_client_server: config.enable_light_client_server,
         };
 
-        network_service.spawn_service(executor);
-
-        Ok((network_globals, network_senders))
+        Ok((network_service, network_globals, network_senders))
     }
 
     /// Returns the required fork digests that gossipsub needs to subscribe to based on the current slot.
@@ -388,7 +419,7 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             })];
 
         if let Some((next_fork, fork_epoch)) = spec.next_fork_epoch::<T::EthSpec>(current_slot) {
-            if current_slot.saturating_add(Slot::new(SUBSCRIBE_DELAY_SLOTS))
+            if current_slot.saturating_add(Slot::new(self.fork_subscribe_slots))
                 >= fork_epoch.start_slot(T::EthSpec::slots_per_epoch())
             {
                 let next_fork_context_bytes =
@@ -910,13 +941,15 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             // Set the next_unsubscribe delay.
             let epoch_duration =
                 self.beacon_chain.spec.seconds_per_slot * T::EthSpec::slots_per_epoch();
-            let unsubscribe_delay = Duration::from_secs(UNSUBSCRIBE_DELAY_EPOCHS * epoch_duration);
+            let unsubscribe_delay =
+                Duration::from_secs(self.fork_unsubscribe_epochs * epoch_duration);
 
             // Update the `next_fork_subscriptions` timer if the next fork is known.
-            self.next_fork_subscriptions =
-                Box::pin(next_fork_subscriptions_delay(&self.beacon_chain).into());
+            self.next_fork_subscriptions = Box::pin(
+                next_fork_subscriptions_delay(&self.beacon_chain, self.fork_subscribe_slots).into(),
+            );
             self.next_unsubscribe = Box::pin(Some(tokio::time::sleep(unsubscribe_delay)).into());
-            info!(self.log, "Network will unsubscribe from old fork gossip topics in a few epochs"; "remaining_epochs" => UNSUBSCRIBE_DELAY_EPOCHS);
+            info!(self.log, "Network will unsubscribe from old fork gossip topics in a few epochs"; "remaining_epochs" => self.fork_unsubscribe_epochs);
         } else {
             crit!(self.log, "Unknown new enr fork id"; "new_fork_id" => ?new_enr_fork_id);
         }
@@ -943,14 +976,15 @@ fn next_fork_delay<T: BeaconChainTypes>(
         .map(|(_, until_fork)| tokio::time::sleep(until_fork))
 }
 
-/// Returns a `Sleep` that triggers `SUBSCRIBE_DELAY_SLOTS` before the next fork.
-/// Returns `None` if there are no scheduled forks or we are already past `current_slot + SUBSCRIBE_DELAY_SLOTS > fork_slot`.
+/// Returns a `Sleep` that triggers `subscribe_slots` before the next fork.
+/// Returns `None` if there are no scheduled forks or we are already past `current_slot + subscribe_slots > fork_slot`.
 fn next_fork_subscriptions_delay<T: BeaconChainTypes>(
     beacon_chain: &BeaconChain<T>,
+    subscribe_slots: u64,
 ) -> Option<tokio::time::Sleep> {
     if let Some((_, duration_to_fork)) = beacon_chain.duration_to_next_fork() {
         let duration_to_subscription = duration_to_fork.saturating_sub(Duration::from_secs(
-            beacon_chain.spec.seconds_per_slot * SUBSCRIBE_DELAY_SLOTS,
+            beacon_chain.spec.seconds_per_slot * subscribe_slots,
         ));
         if !duration_to_subscription.is_zero() {
             return Some(tokio::time::sleep(duration_to_subscription));
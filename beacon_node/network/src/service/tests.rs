@@ -2,7 +2,7 @@
 #[cfg(test)]
 mod tests {
     use crate::persisted_dht::load_dht;
-    use crate::{NetworkConfig, NetworkService};
+    use crate::{BeaconProcessorConfig, NetworkConfig, NetworkService};
     use beacon_chain::test_utils::BeaconChainHarness;
     use lighthouse_network::Enr;
     use slog::{o, Drain, Level, Logger};
@@ -10,7 +10,7 @@ mod tests {
     use std::str::FromStr;
     use std::sync::Arc;
     use tokio::runtime::Runtime;
-    use types::MinimalEthSpec;
+    use types::{Epoch, EthSpec, MinimalEthSpec, Slot};
 
     fn get_logger(actual_log: bool) -> Logger {
         if actual_log {
@@ -67,10 +67,15 @@ mod tests {
             // Create a new network service which implicitly gets dropped at the
             // end of the block.
 
-            let _network_service =
-                NetworkService::start(beacon_chain.clone(), &config, executor, None)
-                    .await
-                    .unwrap();
+            let _network_service = NetworkService::start(
+                beacon_chain.clone(),
+                &config,
+                BeaconProcessorConfig::default(),
+                executor,
+                None,
+            )
+            .await
+            .unwrap();
             drop(signal);
         });
 
@@ -88,4 +93,63 @@ mod tests {
             "should have persisted the second ENR to store"
         );
     }
+
+    #[test]
+    fn test_fork_topic_subscription_timing() {
+        let log = get_logger(false);
+
+        let mut spec = MinimalEthSpec::default_spec();
+        spec.altair_fork_epoch = Some(Epoch::new(1));
+        spec.bellatrix_fork_epoch = None;
+        spec.capella_fork_epoch = None;
+        spec.deneb_fork_epoch = None;
+        let fork_slot = Epoch::new(1).start_slot(MinimalEthSpec::slots_per_epoch());
+
+        let harness = BeaconChainHarness::builder(MinimalEthSpec)
+            .spec(spec)
+            .deterministic_keypairs(8)
+            .fresh_ephemeral_store()
+            .build();
+        let beacon_chain = harness.chain.clone();
+
+        let runtime = Arc::new(Runtime::new().unwrap());
+
+        let (signal, exit) = exit_future::signal();
+        let (shutdown_tx, _) = futures::channel::mpsc::channel(1);
+        let executor = task_executor::TaskExecutor::new(
+            Arc::downgrade(&runtime),
+            exit,
+            log.clone(),
+            shutdown_tx,
+        );
+
+        let mut config = NetworkConfig::default();
+        config.set_ipv4_listening_address(std::net::Ipv4Addr::UNSPECIFIED, 21213, 21213);
+        config.disable_discovery = true;
+        config.upnp_enabled = false;
+        runtime.block_on(async move {
+            let (mut network_service, _, _) = NetworkService::build(
+                beacon_chain,
+                &config,
+                BeaconProcessorConfig::default(),
+                executor,
+                None,
+            )
+            .await
+            .unwrap();
+
+            // By default we subscribe to the new fork's topics 2 slots before the fork.
+            harness.set_current_slot(fork_slot - 3);
+            assert_eq!(network_service.required_gossip_fork_digests().len(), 1);
+            harness.set_current_slot(fork_slot - 2);
+            assert_eq!(network_service.required_gossip_fork_digests().len(), 2);
+
+            // `--fork-topic-subscribe-epochs 1` subscribes a full epoch before the fork.
+            network_service.fork_subscribe_slots = MinimalEthSpec::slots_per_epoch();
+            harness.set_current_slot(Slot::new(0));
+            assert_eq!(network_service.required_gossip_fork_digests().len(), 2);
+
+            drop(signal);
+        });
+    }
 }
@@ -76,7 +76,13 @@ impl TestBeaconChain {
                     Duration::from_millis(SLOT_DURATION_MILLIS),
                 ))
                 .shutdown_sender(shutdown_tx)
-                .monitor_validators(true, vec![], DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD, log)
+                .monitor_validators(
+                    true,
+                    None,
+                    vec![],
+                    DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD,
+                    log,
+                )
                 .build()
                 .expect("should build"),
         );
@@ -204,6 +204,19 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
         }
     }
 
+    /// Pauses the backfill sync if the database is low on disk space. Returns `true` if the
+    /// database is low on disk space.
+    fn pause_if_low_disk_space(&mut self) -> bool {
+        if !self.beacon_chain.store.is_low_disk_space() {
+            return false;
+        }
+        if let BackFillState::Syncing = self.state() {
+            info!(self.log, "Backfill sync paused"; "reason" => "low_disk_space");
+            self.set_state(BackFillState::Paused);
+        }
+        true
+    }
+
     /// Starts or resumes syncing.
     ///
     /// If resuming is successful, reports back the current syncing metrics.
@@ -215,6 +228,10 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
         match self.state() {
             BackFillState::Syncing => {} // already syncing ignore.
             BackFillState::Paused => {
+                if self.beacon_chain.store.is_low_disk_space() {
+                    // Wait for space to be freed before resuming.
+                    return Ok(SyncStart::NotSyncing);
+                }
                 if self
                     .network_globals
                     .peers
@@ -334,7 +351,7 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
                     self.retry_batch_download(network, id)?;
                 } else {
                     debug!(self.log, "Batch not found while removing peer";
-                        "peer" => %peer_id, "batch" => id)
+                        "peer_id" => %peer_id, "batch" => id)
                 }
             }
         }
@@ -508,6 +525,11 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
             return Ok(ProcessResult::Successful);
         }
 
+        // Don't write any more blocks to disk while space is low.
+        if self.pause_if_low_disk_space() {
+            return Ok(ProcessResult::Successful);
+        }
+
         let batch = match self.batches.get_mut(&batch_id) {
             Some(batch) => batch,
             None => {
@@ -608,7 +630,7 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
         };
 
         debug!(self.log, "Backfill batch processed"; "result" => ?result, &batch,
-            "batch_epoch" => batch_id, "peer" => %peer, "client" => %network.client_type(&peer));
+            "batch_epoch" => batch_id, "peer_id" => %peer, "client" => %network.client_type(&peer));
 
         match result {
             BatchProcessResult::Success { was_non_empty } => {
@@ -1034,6 +1056,10 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
             return Ok(());
         }
 
+        if self.pause_if_low_disk_space() {
+            return Ok(());
+        }
+
         // find the next pending batch and request it from the peer
 
         // randomize the peers for load balancing
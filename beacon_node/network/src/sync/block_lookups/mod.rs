@@ -75,6 +75,19 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
     /// Searches for a single block hash. If the blocks parent is unknown, a chain of blocks is
     /// constructed.
     pub fn search_block(&mut self, hash: Hash256, peer_id: PeerId, cx: &mut SyncNetworkContext<T>) {
+        // Do not search for blocks that are part of a known failed chain, the peer advertising it
+        // is either faulty or following an invalid chain.
+        if self.failed_chains.contains(&hash) {
+            debug!(self.log, "Block is from a past failed chain. Dropping";
+                "peer_id" => %peer_id, "block" => %hash);
+            cx.report_peer(
+                peer_id,
+                PeerAction::MidToleranceError,
+                "failed_chain_lookup",
+            );
+            return;
+        }
+
         // Do not re-request a block that is already being requested
         if self
             .single_block_lookups
@@ -136,6 +149,11 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
         if self.failed_chains.contains(&parent_root) || self.failed_chains.contains(&block_root) {
             debug!(self.log, "Block is from a past failed chain. Dropping";
                 "block_root" => ?block_root, "block_slot" => block.slot());
+            cx.report_peer(
+                peer_id,
+                PeerAction::MidToleranceError,
+                "failed_chain_lookup",
+            );
             return;
         }
 
@@ -464,12 +482,12 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
                         debug!(
                             self.log,
                             "Single block lookup failed. Execution layer is offline / unsynced / misconfigured";
-                            "root" => %root,
+                            "block_root" => %root,
                             "error" => ?e
                         );
                     }
                     other => {
-                        warn!(self.log, "Peer sent invalid block in single block lookup"; "root" => %root, "error" => ?other, "peer_id" => %peer_id);
+                        warn!(self.log, "Peer sent invalid block in single block lookup"; "block_root" => %root, "error" => ?other, "peer_id" => %peer_id);
                         cx.report_peer(
                             peer_id,
                             PeerAction::MidToleranceError,
@@ -622,8 +640,8 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
         result: BatchProcessResult,
         cx: &mut SyncNetworkContext<T>,
     ) {
-        let request = match self.processing_parent_lookups.remove(&chain_hash) {
-            Some((_hashes, request)) => request,
+        let (hashes, request) = match self.processing_parent_lookups.remove(&chain_hash) {
+            Some((hashes, request)) => (hashes, request),
             None => {
                 return debug!(self.log, "Chain process response for a parent lookup request that was not found"; "chain_hash" => %chain_hash, "result" => ?result)
             }
@@ -635,10 +653,17 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
                 // nothing to do.
             }
             BatchProcessResult::FaultyFailure {
-                imported_blocks: _,
+                imported_blocks,
                 penalty,
             } => {
                 self.failed_chains.insert(chain_hash);
+                // If nothing was imported, every block in the chain descends from the faulty one.
+                // Otherwise we can't tell which blocks were valid, so only reject the tip.
+                if !imported_blocks {
+                    for hash in hashes {
+                        self.failed_chains.insert(hash);
+                    }
+                }
                 for peer_id in request.used_peers {
                     cx.report_peer(peer_id, penalty, "parent_chain_failure")
                 }
@@ -534,6 +534,97 @@ fn test_parent_lookup_too_deep() {
     assert!(bl.failed_chains.contains(&chain_hash));
 }
 
+#[test]
+fn test_parent_lookup_too_deep_does_not_blacklist_ancestors() {
+    let (mut bl, mut cx, mut rig) = TestRig::test_setup(None);
+    let mut blocks =
+        Vec::<SignedBeaconBlock<E>>::with_capacity(parent_lookup::PARENT_DEPTH_TOLERANCE);
+    while blocks.len() < parent_lookup::PARENT_DEPTH_TOLERANCE {
+        let parent = blocks
+            .last()
+            .map(|b| b.canonical_root())
+            .unwrap_or_else(Hash256::random);
+        let block = rig.block_with_parent(parent);
+        blocks.push(block);
+    }
+
+    let peer_id = PeerId::random();
+    let trigger_block = blocks.pop().unwrap();
+    let chain_hash = trigger_block.canonical_root();
+    let intermediate_root = blocks[blocks.len() / 2].canonical_root();
+    bl.search_parent(chain_hash, Arc::new(trigger_block), peer_id, &mut cx);
+
+    for block in blocks.into_iter().rev() {
+        let id = rig.expect_parent_request();
+        bl.parent_lookup_response(id, peer_id, Some(Arc::new(block.clone())), D, &mut cx);
+        bl.parent_lookup_response(id, peer_id, None, D, &mut cx);
+        rig.expect_block_process();
+        bl.parent_block_processed(
+            chain_hash,
+            BlockError::ParentUnknown(Arc::new(block)).into(),
+            &mut cx,
+        )
+    }
+
+    rig.expect_penalty();
+    assert!(bl.failed_chains.contains(&chain_hash));
+    assert!(!bl.failed_chains.contains(&intermediate_root));
+
+    // Being too far behind says nothing about the validity of the chain, so a peer mentioning one
+    // of its blocks is not penalized and the block is looked up.
+    bl.search_block(intermediate_root, PeerId::random(), &mut cx);
+    rig.expect_block_request();
+    rig.expect_empty_network();
+}
+
+#[test]
+fn test_parent_chain_failure_blacklists_whole_chain() {
+    let (mut bl, mut cx, mut rig) = TestRig::test_setup(None);
+
+    let grandparent = Arc::new(rig.rand_block());
+    let parent = Arc::new(rig.block_with_parent(grandparent.canonical_root()));
+    let parent_root = parent.canonical_root();
+    let block = rig.block_with_parent(parent_root);
+    let chain_hash = block.canonical_root();
+    let peer_id = PeerId::random();
+
+    // Trigger the request
+    bl.search_parent(chain_hash, Arc::new(block), peer_id, &mut cx);
+
+    // Download the parent, which has an unknown parent itself.
+    let id = rig.expect_parent_request();
+    bl.parent_lookup_response(id, peer_id, Some(parent.clone()), D, &mut cx);
+    rig.expect_block_process();
+    bl.parent_block_processed(
+        chain_hash,
+        BlockError::ParentUnknown(parent).into(),
+        &mut cx,
+    );
+
+    // Download the grandparent, processing succeeds and the chain is sent for processing.
+    let id = rig.expect_parent_request();
+    bl.parent_lookup_response(id, peer_id, Some(grandparent), D, &mut cx);
+    rig.expect_block_process();
+    bl.parent_block_processed(chain_hash, Ok(()).into(), &mut cx);
+    rig.expect_parent_chain_process();
+
+    // The chain fails processing without importing any block.
+    let process_result = BatchProcessResult::FaultyFailure {
+        imported_blocks: false,
+        penalty: PeerAction::LowToleranceError,
+    };
+    bl.parent_chain_processed(chain_hash, process_result, &mut cx);
+    rig.expect_penalty();
+    assert!(bl.failed_chains.contains(&chain_hash));
+    assert!(bl.failed_chains.contains(&parent_root));
+
+    // Another peer advertising a block in the failed chain is penalized and nothing is requested.
+    bl.search_block(parent_root, PeerId::random(), &mut cx);
+    rig.expect_penalty();
+    rig.expect_empty_network();
+    assert!(bl.single_block_lookups.is_empty());
+}
+
 #[test]
 fn test_parent_lookup_disconnection() {
     let (mut bl, mut cx, mut rig) = TestRig::test_setup(None);
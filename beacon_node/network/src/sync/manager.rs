@@ -41,6 +41,7 @@ use super::range_sync::{RangeSync, RangeSyncType, EPOCHS_PER_BATCH};
 use crate::beacon_processor::{BeaconProcessorSend, ChainSegmentProcessId};
 use crate::service::NetworkMessage;
 use crate::status::ToStatusMessage;
+use beacon_chain::events::{EventKind, SseSyncState};
 use beacon_chain::{BeaconChain, BeaconChainTypes, BlockError, EngineState};
 use futures::StreamExt;
 use lighthouse_network::rpc::methods::MAX_REQUEST_BLOCKS;
@@ -449,6 +450,16 @@ impl<T: BeaconChainTypes> SyncManager<T> {
         let new_state = self.network_globals.sync_state.read();
         if !new_state.eq(&old_state) {
             info!(self.log, "Sync state updated"; "old_state" => %old_state, "new_state" => %new_state);
+            if let Some(event_handler) = self.chain.event_handler.as_ref() {
+                if event_handler.has_sync_state_subscribers() {
+                    event_handler.register(EventKind::SyncState(SseSyncState {
+                        old_state: old_state.clone(),
+                        new_state: new_state.clone(),
+                        is_syncing: new_state.is_syncing(),
+                        head_slot: self.chain.best_slot(),
+                    }));
+                }
+            }
             // If we have become synced - Subscribe to all the core subnet topics
             // We don't need to subscribe if the old state is a state that would have already
             // invoked this call.
@@ -719,3 +730,61 @@ impl<T: EthSpec> From<BlockError<T>> for BlockProcessResult<T> {
         BlockProcessResult::Err(e)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beacon_chain::test_utils::BeaconChainHarness;
+    use types::MinimalEthSpec;
+
+    #[test]
+    fn sync_state_transition_emits_event() {
+        let harness = BeaconChainHarness::builder(MinimalEthSpec)
+            .default_spec()
+            .deterministic_keypairs(8)
+            .fresh_ephemeral_store()
+            .build();
+        let chain = harness.chain.clone();
+        let log = harness.logger().clone();
+
+        let network_globals = Arc::new(NetworkGlobals::new_test_globals(vec![], &log));
+        let (network_tx, _network_rx) = mpsc::unbounded_channel();
+        let (beacon_processor_tx, _beacon_processor_rx) = mpsc::channel(1);
+        let (_sync_tx, sync_rx) = mpsc::unbounded_channel();
+        let mut sync_manager = SyncManager {
+            chain: chain.clone(),
+            network_globals: network_globals.clone(),
+            input_channel: sync_rx,
+            network: SyncNetworkContext::new(
+                network_tx,
+                network_globals.clone(),
+                BeaconProcessorSend(beacon_processor_tx),
+                log.clone(),
+            ),
+            range_sync: RangeSync::new(chain.clone(), log.clone()),
+            backfill_sync: BackFillSync::new(chain.clone(), network_globals.clone(), log.clone()),
+            block_lookups: BlockLookups::new(log.clone()),
+            log,
+        };
+
+        let mut events = chain.event_handler.as_ref().unwrap().subscribe_sync_state();
+
+        // Without any peers, a node at genesis is stalled.
+        network_globals.set_sync_state(SyncState::Synced);
+        sync_manager.update_sync_state();
+        assert_eq!(*network_globals.sync_state.read(), SyncState::Stalled);
+        assert_eq!(
+            events.try_recv().unwrap(),
+            EventKind::SyncState(SseSyncState {
+                old_state: SyncState::Synced,
+                new_state: SyncState::Stalled,
+                is_syncing: false,
+                head_slot: Slot::new(0),
+            })
+        );
+
+        // No event is emitted if the state does not change.
+        sync_manager.update_sync_state();
+        assert!(events.try_recv().is_err());
+    }
+}
@@ -9,7 +9,9 @@ use crate::status::ToStatusMessage;
 use beacon_chain::{BeaconChainTypes, EngineState};
 use fnv::FnvHashMap;
 use lighthouse_network::rpc::{BlocksByRangeRequest, BlocksByRootRequest, GoodbyeReason};
-use lighthouse_network::{Client, NetworkGlobals, PeerAction, PeerId, ReportSource, Request};
+use lighthouse_network::{
+    Client, NetworkGlobals, PeerAction, PeerId, ReportSource, Request, SyncInfo,
+};
 use slog::{debug, trace, warn};
 use std::sync::Arc;
 use tokio::sync::mpsc;
@@ -72,6 +74,15 @@ impl<T: BeaconChainTypes> SyncNetworkContext<T> {
             .unwrap_or_default()
     }
 
+    /// Returns the sync info advertised by the peer in its latest STATUS message, if known.
+    pub fn peer_sync_info(&self, peer_id: &PeerId) -> Option<SyncInfo> {
+        self.network_globals
+            .peers
+            .read()
+            .peer_info(peer_id)
+            .and_then(|info| info.sync_status().info().cloned())
+    }
+
     pub fn status_peers<C: ToStatusMessage>(
         &mut self,
         chain: &C,
@@ -82,7 +93,7 @@ impl<T: BeaconChainTypes> SyncNetworkContext<T> {
             debug!(
                 self.log,
                 "Sending Status Request";
-                "peer" => %peer_id,
+                "peer_id" => %peer_id,
                 "fork_digest" => ?status_message.fork_digest,
                 "finalized_root" => ?status_message.finalized_root,
                 "finalized_epoch" => ?status_message.finalized_epoch,
@@ -113,7 +124,7 @@ impl<T: BeaconChainTypes> SyncNetworkContext<T> {
             "Sending BlocksByRange Request";
             "method" => "BlocksByRange",
             "count" => request.count(),
-            "peer" => %peer_id,
+            "peer_id" => %peer_id,
         );
         let request = Request::BlocksByRange(request);
         let id = self.next_id();
@@ -139,7 +150,7 @@ impl<T: BeaconChainTypes> SyncNetworkContext<T> {
             "Sending backfill BlocksByRange Request";
             "method" => "BlocksByRange",
             "count" => request.count(),
-            "peer" => %peer_id,
+            "peer_id" => %peer_id,
         );
         let request = Request::BlocksByRange(request);
         let id = self.next_id();
@@ -186,7 +197,7 @@ impl<T: BeaconChainTypes> SyncNetworkContext<T> {
             "Sending BlocksByRoot Request";
             "method" => "BlocksByRoot",
             "count" => request.block_roots().len(),
-            "peer" => %peer_id
+            "peer_id" => %peer_id
         );
         let request = Request::BlocksByRoot(request);
         let id = self.next_id();
@@ -210,7 +221,7 @@ impl<T: BeaconChainTypes> SyncNetworkContext<T> {
             "Sending BlocksByRoot Request";
             "method" => "BlocksByRoot",
             "count" => request.block_roots().len(),
-            "peer" => %peer_id
+            "peer_id" => %peer_id
         );
         let request = Request::BlocksByRoot(request);
         let id = self.next_id();
@@ -5,10 +5,10 @@ use crate::sync::{
 };
 use beacon_chain::BeaconChainTypes;
 use fnv::FnvHashMap;
-use lighthouse_network::{PeerAction, PeerId};
-use rand::seq::SliceRandom;
+use lighthouse_network::{PeerAction, PeerId, SyncInfo};
+use rand::{seq::SliceRandom, Rng};
 use slog::{crit, debug, o, warn};
-use std::collections::{btree_map::Entry, BTreeMap, HashSet};
+use std::collections::{btree_map::Entry, BTreeMap, HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 use types::{Epoch, EthSpec, Hash256, SignedBeaconBlock, Slot};
@@ -193,7 +193,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
                     self.retry_batch_download(network, id)?;
                 } else {
                     debug!(self.log, "Batch not found while removing peer";
-                        "peer" => %peer_id, "batch" => id)
+                        "peer_id" => %peer_id, "batch" => id)
                 }
             }
         }
@@ -973,26 +973,26 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
 
         // find the next pending batch and request it from the peer
 
-        // randomize the peers for load balancing
+        // spread requests across peers with different heads, randomizing for load balancing
         let mut rng = rand::thread_rng();
-        let mut idle_peers = self
+        let idle_peers = self
             .peers
             .iter()
             .filter_map(|(peer, requests)| {
                 if requests.is_empty() {
-                    Some(*peer)
+                    Some((*peer, network.peer_sync_info(peer)))
                 } else {
                     None
                 }
             })
             .collect::<Vec<_>>();
-        idle_peers.shuffle(&mut rng);
+        let mut idle_peers = prioritize_peers(idle_peers, &mut rng).into_iter();
 
         // check if we have the batch for our optimistic start. If not, request it first.
         // We wait for this batch before requesting any other batches.
         if let Some(epoch) = self.optimistic_start {
             if let Entry::Vacant(entry) = self.batches.entry(epoch) {
-                if let Some(peer) = idle_peers.pop() {
+                if let Some(peer) = idle_peers.next() {
                     let optimistic_batch = BatchInfo::new(&epoch, EPOCHS_PER_BATCH);
                     entry.insert(optimistic_batch);
                     self.send_batch(network, epoch, peer)?;
@@ -1001,7 +1001,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
             return Ok(KeepChain);
         }
 
-        while let Some(peer) = idle_peers.pop() {
+        for peer in idle_peers {
             if let Some(batch_id) = self.include_next_batch() {
                 // send the batch
                 self.send_batch(network, batch_id, peer)?;
@@ -1061,6 +1061,49 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
     }
 }
 
+/// Orders `peers` for batch requests, so that consecutive batches are requested from peers which
+/// advertise different heads.
+///
+/// Peers are grouped by their advertised head root and groups are ordered by how far ahead of us
+/// they are (head slot, then finalized epoch), with peers of unknown status last. Peers are then
+/// taken from each group in turn, in a random order within each group. Spreading batches across
+/// heads means a peer on a divergent chain has its batches fail early, rather than once a
+/// round-robin over all peers reaches it.
+fn prioritize_peers<R: Rng>(peers: Vec<(PeerId, Option<SyncInfo>)>, rng: &mut R) -> Vec<PeerId> {
+    let num_peers = peers.len();
+
+    let mut groups: HashMap<Option<Hash256>, (Option<(Slot, Epoch)>, Vec<PeerId>)> = HashMap::new();
+    for (peer, info) in peers {
+        let head_root = info.as_ref().map(|info| info.head_root);
+        let rank = info.map(|info| (info.head_slot, info.finalized_epoch));
+        groups
+            .entry(head_root)
+            .or_insert_with(|| (rank, vec![]))
+            .1
+            .push(peer);
+    }
+
+    // Shuffle before the stable sort so that groups with equal rank are in a random order.
+    let mut groups = groups.into_values().collect::<Vec<_>>();
+    groups.shuffle(rng);
+    // `None` sorts lowest, so peers with an unknown status come last.
+    groups.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+    let mut groups = groups
+        .into_iter()
+        .map(|(_, mut group)| {
+            group.shuffle(rng);
+            group.into_iter()
+        })
+        .collect::<Vec<_>>();
+
+    let mut prioritized = Vec::with_capacity(num_peers);
+    while prioritized.len() < num_peers {
+        prioritized.extend(groups.iter_mut().filter_map(|group| group.next()));
+    }
+    prioritized
+}
+
 impl<T: BeaconChainTypes> slog::KV for &mut SyncingChain<T> {
     fn serialize(
         &self,
@@ -1116,3 +1159,46 @@ impl RemoveChain {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sync_info(head_slot: u64, head_root: Hash256, finalized_epoch: u64) -> SyncInfo {
+        SyncInfo {
+            head_slot: Slot::new(head_slot),
+            head_root,
+            finalized_epoch: Epoch::new(finalized_epoch),
+            finalized_root: Hash256::zero(),
+        }
+    }
+
+    #[test]
+    fn prioritize_peers_spreads_across_heads() {
+        let mut rng = rand::thread_rng();
+        let (high_root, low_root) = (Hash256::repeat_byte(1), Hash256::repeat_byte(2));
+
+        let high_peers = [PeerId::random(), PeerId::random()];
+        let low_peers = [PeerId::random(), PeerId::random()];
+        let unknown_peer = PeerId::random();
+
+        let mut peers = vec![(unknown_peer, None)];
+        for peer in low_peers {
+            peers.push((peer, Some(sync_info(64, low_root, 1))));
+        }
+        for peer in high_peers {
+            peers.push((peer, Some(sync_info(96, high_root, 2))));
+        }
+
+        let prioritized = prioritize_peers(peers, &mut rng);
+
+        assert_eq!(prioritized.len(), 5);
+        // Peers alternate between heads, most advanced first, with the unknown peer in the first
+        // round.
+        assert!(high_peers.contains(&prioritized[0]));
+        assert!(low_peers.contains(&prioritized[1]));
+        assert_eq!(prioritized[2], unknown_peer);
+        assert!(high_peers.contains(&prioritized[3]));
+        assert!(low_peers.contains(&prioritized[4]));
+    }
+}
@@ -212,6 +212,7 @@ impl<T: EthSpec> AttestationMap<T> {
                 acc.num_attestation_data += new.num_attestation_data;
                 acc.max_aggregates_per_data =
                     std::cmp::max(acc.max_aggregates_per_data, new.max_aggregates_per_data);
+                acc.num_attesting_indices += new.num_attesting_indices;
                 acc
             })
     }
@@ -239,6 +240,10 @@ impl<T: EthSpec> AttestationDataMap<T> {
             stats.num_attestation_data += 1;
             stats.max_aggregates_per_data =
                 std::cmp::max(stats.max_aggregates_per_data, aggregates.len());
+            stats.num_attesting_indices += aggregates
+                .iter()
+                .map(|indexed| indexed.attesting_indices.len())
+                .sum::<usize>();
         }
         stats
     }
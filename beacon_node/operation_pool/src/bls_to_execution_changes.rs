@@ -15,10 +15,12 @@ pub enum ReceivedPreCapella {
     No,
 }
 
-/// Pool of BLS to execution changes that maintains a LIFO queue and an index by validator.
+/// Pool of BLS to execution changes that maintains a queue in order of receipt and an index by
+/// validator.
 ///
-/// Using the LIFO queue for block production disincentivises spam on P2P at the Capella fork,
-/// and is less-relevant after that.
+/// Block production takes changes from both ends of the queue: the oldest first so that changes
+/// received before the Capella fork are included in order, and the newest first (LIFO) so that
+/// spam on P2P at the Capella fork can't delay fresh changes indefinitely.
 #[derive(Debug, Default)]
 pub struct BlsToExecutionChanges<T: EthSpec> {
     /// Map from validator index to BLS to execution change.
@@ -61,7 +63,7 @@ impl<T: EthSpec> BlsToExecutionChanges<T> {
         }
     }
 
-    /// FIFO ordering, used for persistence to disk.
+    /// FIFO ordering, used for persistence to disk and block packing.
     pub fn iter_fifo(
         &self,
     ) -> impl Iterator<Item = &Arc<SigVerifiedOp<SignedBlsToExecutionChange, T>>> {
@@ -23,7 +23,7 @@ use crate::attestation_storage::{AttestationMap, CheckpointKey};
 use crate::bls_to_execution_changes::BlsToExecutionChanges;
 use crate::sync_aggregate_id::SyncAggregateId;
 use attester_slashing::AttesterSlashingMaxCover;
-use max_cover::maximum_cover;
+use max_cover::{maximum_cover, maximum_cover_with_deadline};
 use parking_lot::{RwLock, RwLockWriteGuard};
 use rand::seq::SliceRandom;
 use rand::thread_rng;
@@ -35,6 +35,8 @@ use state_processing::{SigVerifiedOp, VerifyOperation};
 use std::collections::{hash_map::Entry, HashMap, HashSet};
 use std::marker::PhantomData;
 use std::ptr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use types::{
     sync_aggregate::Error as SyncAggregateError, typenum::Unsigned, AbstractExecPayload,
     Attestation, AttestationData, AttesterSlashing, BeaconState, BeaconStateError, ChainSpec,
@@ -85,6 +87,8 @@ pub struct AttestationStats {
     pub num_attestation_data: usize,
     /// Maximum number of aggregates for a single `AttestationData`.
     pub max_aggregates_per_data: usize,
+    /// Total number of attesting indices across all attestations, counting each aggregate.
+    pub num_attesting_indices: usize,
 }
 
 impl From<SyncAggregateError> for OpPoolError {
@@ -245,11 +249,15 @@ impl<T: EthSpec> OperationPool<T> {
     /// before an approximately optimal bundle is constructed. We use it to provide access
     /// to the fork choice data from the `BeaconChain` struct that doesn't logically belong
     /// in the operation pool.
+    ///
+    /// The `packing_time_budget` bounds the time spent improving upon the greedy packing of
+    /// attestations. A zero budget yields the greedy packing.
     pub fn get_attestations(
         &self,
         state: &BeaconState<T>,
         prev_epoch_validity_filter: impl for<'a> FnMut(&AttestationRef<'a, T>) -> bool + Send,
         curr_epoch_validity_filter: impl for<'a> FnMut(&AttestationRef<'a, T>) -> bool + Send,
+        packing_time_budget: Duration,
         spec: &ChainSpec,
     ) -> Result<Vec<Attestation<T>>, OpPoolError> {
         // Attestations for the current fork, which may be from the current or previous epoch.
@@ -304,6 +312,9 @@ impl<T: EthSpec> OperationPool<T> {
             T::MaxAttestations::to_usize()
         };
 
+        // Both epochs are packed in parallel, so they share the same deadline.
+        let packing_deadline = Instant::now() + packing_time_budget;
+
         let (prev_cover, curr_cover) = rayon::join(
             move || {
                 let _timer = metrics::start_timer(&metrics::ATTESTATION_PREV_EPOCH_PACKING_TIME);
@@ -311,15 +322,21 @@ impl<T: EthSpec> OperationPool<T> {
                 if prev_epoch_key == curr_epoch_key {
                     vec![]
                 } else {
-                    maximum_cover(prev_epoch_att, prev_epoch_limit, "prev_epoch_attestations")
+                    maximum_cover_with_deadline(
+                        prev_epoch_att,
+                        prev_epoch_limit,
+                        "prev_epoch_attestations",
+                        packing_deadline,
+                    )
                 }
             },
             move || {
                 let _timer = metrics::start_timer(&metrics::ATTESTATION_CURR_EPOCH_PACKING_TIME);
-                maximum_cover(
+                maximum_cover_with_deadline(
                     curr_epoch_att,
                     T::MaxAttestations::to_usize(),
                     "curr_epoch_attestations",
+                    packing_deadline,
                 )
             },
         );
@@ -546,25 +563,46 @@ impl<T: EthSpec> OperationPool<T> {
 
     /// Get a list of execution changes for inclusion in a block.
     ///
-    /// They're in random `HashMap` order, which isn't exactly fair, but isn't unfair either.
+    /// Half of the block is reserved for the earliest-received changes, in the order they were
+    /// received. Changes received before Capella are at the front of the queue, so from the fork
+    /// slot onwards they are included oldest first. The rest of the block is filled in LIFO order,
+    /// so that a flood of changes before the fork can't crowd out recent ones (and vice versa).
     pub fn get_bls_to_execution_changes(
         &self,
         state: &BeaconState<T>,
         spec: &ChainSpec,
     ) -> Vec<SignedBlsToExecutionChange> {
-        filter_limit_operations(
-            self.bls_to_execution_changes.read().iter_lifo(),
+        let is_valid = |address_change: &Arc<SigVerifiedOp<SignedBlsToExecutionChange, T>>| {
+            address_change.signature_is_still_valid(&state.fork())
+                && state
+                    .get_validator(address_change.as_inner().message.validator_index as usize)
+                    .map_or(false, |validator| {
+                        !validator.has_eth1_withdrawal_credential(spec)
+                    })
+        };
+        let limit = T::MaxBlsToExecutionChanges::to_usize();
+        let bls_to_execution_changes = self.bls_to_execution_changes.read();
+
+        let mut changes = filter_limit_operations(
+            bls_to_execution_changes.iter_fifo(),
+            is_valid,
+            |address_change| address_change.as_inner().clone(),
+            limit / 2,
+        );
+        let earliest_indices = changes
+            .iter()
+            .map(|address_change| address_change.message.validator_index)
+            .collect::<HashSet<_>>();
+        changes.extend(filter_limit_operations(
+            bls_to_execution_changes.iter_lifo(),
             |address_change| {
-                address_change.signature_is_still_valid(&state.fork())
-                    && state
-                        .get_validator(address_change.as_inner().message.validator_index as usize)
-                        .map_or(false, |validator| {
-                            !validator.has_eth1_withdrawal_credential(spec)
-                        })
+                !earliest_indices.contains(&address_change.as_inner().message.validator_index)
+                    && is_valid(address_change)
             },
             |address_change| address_change.as_inner().clone(),
-            T::MaxBlsToExecutionChanges::to_usize(),
-        )
+            limit.saturating_sub(changes.len()),
+        ));
+        changes
     }
 
     /// Get a list of execution changes to be broadcast at the Capella fork.
@@ -636,6 +674,11 @@ impl<T: EthSpec> OperationPool<T> {
         self.voluntary_exits.read().len()
     }
 
+    /// Total number of BLS to execution changes in the pool.
+    pub fn num_bls_to_execution_changes(&self) -> usize {
+        self.bls_to_execution_changes.read().iter_fifo().count()
+    }
+
     /// Returns all known `Attestation` objects.
     ///
     /// This method may return objects that are invalid for block inclusion.
@@ -968,7 +1011,7 @@ mod release_tests {
         // Before the min attestation inclusion delay, get_attestations shouldn't return anything.
         assert_eq!(
             op_pool
-                .get_attestations(&state, |_| true, |_| true, spec)
+                .get_attestations(&state, |_| true, |_| true, Duration::ZERO, spec)
                 .expect("should have attestations")
                 .len(),
             0
@@ -978,7 +1021,7 @@ mod release_tests {
         *state.slot_mut() += spec.min_attestation_inclusion_delay;
 
         let block_attestations = op_pool
-            .get_attestations(&state, |_| true, |_| true, spec)
+            .get_attestations(&state, |_| true, |_| true, Duration::ZERO, spec)
             .expect("Should have block attestations");
         assert_eq!(block_attestations.len(), committees.len());
 
@@ -1214,7 +1257,7 @@ mod release_tests {
 
         *state.slot_mut() += spec.min_attestation_inclusion_delay;
         let best_attestations = op_pool
-            .get_attestations(&state, |_| true, |_| true, spec)
+            .get_attestations(&state, |_| true, |_| true, Duration::ZERO, spec)
             .expect("should have best attestations");
         assert_eq!(best_attestations.len(), max_attestations);
 
@@ -1311,7 +1354,7 @@ mod release_tests {
 
         *state.slot_mut() += spec.min_attestation_inclusion_delay;
         let best_attestations = op_pool
-            .get_attestations(&state, |_| true, |_| true, spec)
+            .get_attestations(&state, |_| true, |_| true, Duration::ZERO, spec)
             .expect("should have valid best attestations");
         assert_eq!(best_attestations.len(), max_attestations);
 
@@ -1,5 +1,6 @@
 use crate::metrics;
 use itertools::Itertools;
+use std::time::Instant;
 
 /// Trait for types that we can compute a maximum cover for.
 ///
@@ -33,13 +34,16 @@ pub trait MaxCover: Clone {
 /// Helper struct to track which items of the input are still available for inclusion.
 /// Saves removing elements from the work vector.
 struct MaxCoverItem<T> {
+    /// Position of the item in the input.
+    index: usize,
     item: T,
     available: bool,
 }
 
 impl<T> MaxCoverItem<T> {
-    fn new(item: T) -> Self {
+    fn new(index: usize, item: T) -> Self {
         MaxCoverItem {
+            index,
             item,
             available: true,
         }
@@ -55,31 +59,92 @@ where
     I: IntoIterator<Item = T>,
     T: MaxCover,
 {
-    // Construct an initial vec of all items, marked available.
-    let mut all_items: Vec<_> = items_iter
+    let items = non_zero_items(items_iter, label);
+    greedy_cover(items, limit)
         .into_iter()
-        .map(MaxCoverItem::new)
-        .filter(|x| x.item.score() != 0)
-        .collect();
+        .map(|(_, item)| item)
+        .collect()
+}
+
+/// Compute an approximate maximum cover using the greedy algorithm, then improve it with a local
+/// search until no improvement can be found or the `deadline` is reached.
+///
+/// The local search repeatedly swaps an item in the solution for one outside of it, whenever
+/// that increases the total score. The result is never worse than that of `maximum_cover`.
+pub fn maximum_cover_with_deadline<I, T>(
+    items_iter: I,
+    limit: usize,
+    label: &str,
+    deadline: Instant,
+) -> Vec<T>
+where
+    I: IntoIterator<Item = T>,
+    T: MaxCover,
+{
+    let items = non_zero_items(items_iter, label);
+    let greedy_solution = greedy_cover(items.clone(), limit);
+
+    let mut solution = greedy_solution
+        .iter()
+        .map(|(index, _)| *index)
+        .collect::<Vec<_>>();
+    let num_swaps = improve_cover(&items, &mut solution, limit, deadline);
+    metrics::inc_counter_vec_by(&metrics::MAX_COVER_IMPROVEMENTS, &[label], num_swaps);
+
+    if num_swaps == 0 {
+        return greedy_solution.into_iter().map(|(_, item)| item).collect();
+    }
+
+    // Re-run the greedy algorithm on the chosen items, so that the covering sets and scores of
+    // the result only reflect what each item adds, like those of a greedy solution.
+    let chosen_items = solution.into_iter().map(|index| items[index].clone());
+    greedy_cover(chosen_items.collect(), limit)
+        .into_iter()
+        .map(|(_, item)| item)
+        .collect()
+}
+
+/// Remove items which don't cover anything.
+fn non_zero_items<I, T>(items_iter: I, label: &str) -> Vec<T>
+where
+    I: IntoIterator<Item = T>,
+    T: MaxCover,
+{
+    let items = items_iter
+        .into_iter()
+        .filter(|item| item.score() != 0)
+        .collect::<Vec<_>>();
 
     metrics::set_int_gauge(
         &metrics::MAX_COVER_NON_ZERO_ITEMS,
         &[label],
-        all_items.len() as i64,
+        items.len() as i64,
     );
 
+    items
+}
+
+/// Select up to `limit` items greedily, returning them alongside their index in `items`.
+fn greedy_cover<T: MaxCover>(items: Vec<T>, limit: usize) -> Vec<(usize, T)> {
+    // Construct an initial vec of all items, marked available.
+    let mut all_items: Vec<_> = items
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| MaxCoverItem::new(index, item))
+        .collect();
+
     let mut result = vec![];
 
     for _ in 0..limit {
         // Select the item with the maximum score.
-        let best = match all_items
+        let (index, best) = match all_items
             .iter_mut()
             .filter(|x| x.available && x.item.score() != 0)
             .max_by_key(|x| x.item.score())
         {
             Some(x) => {
                 x.available = false;
-                x.item.clone()
+                (x.index, x.item.clone())
             }
             None => return result,
         };
@@ -94,12 +159,99 @@ where
                     .update_covering_set(best.intermediate(), best.covering_set())
             });
 
-        result.push(best);
+        result.push((index, best));
     }
 
     result
 }
 
+/// Improve `solution` (a list of indices into `items`) by adding items which cover something new
+/// and by swapping items in the solution for better ones, until neither is possible or the
+/// `deadline` is reached.
+///
+/// Returns the number of changes made to the solution.
+fn improve_cover<T: MaxCover>(
+    items: &[T],
+    solution: &mut Vec<usize>,
+    limit: usize,
+    deadline: Instant,
+) -> u64 {
+    let mut in_solution = vec![false; items.len()];
+    for &index in solution.iter() {
+        in_solution[index] = true;
+    }
+    let mut num_changes = 0;
+
+    loop {
+        let mut improved = false;
+
+        // Swapping items out may have uncovered elements, allowing more items to be added.
+        while solution.len() < limit {
+            let mut addition = None;
+            for candidate in (0..items.len()).filter(|&i| !in_solution[i]) {
+                if Instant::now() >= deadline {
+                    return num_changes;
+                }
+                if marginal_score(items, candidate, solution) > 0 {
+                    addition = Some(candidate);
+                    break;
+                }
+            }
+            match addition {
+                Some(candidate) => {
+                    in_solution[candidate] = true;
+                    solution.push(candidate);
+                    num_changes += 1;
+                    improved = true;
+                }
+                None => break,
+            }
+        }
+
+        for position in 0..solution.len() {
+            let current = solution[position];
+            let others = solution
+                .iter()
+                .copied()
+                .filter(|&index| index != current)
+                .collect::<Vec<_>>();
+            let current_score = marginal_score(items, current, &others);
+
+            for candidate in 0..items.len() {
+                if Instant::now() >= deadline {
+                    return num_changes;
+                }
+                // An item's marginal score can't exceed its score, so skip those which can't be
+                // an improvement without computing their marginal score.
+                if in_solution[candidate] || items[candidate].score() <= current_score {
+                    continue;
+                }
+                if marginal_score(items, candidate, &others) > current_score {
+                    in_solution[current] = false;
+                    in_solution[candidate] = true;
+                    solution[position] = candidate;
+                    num_changes += 1;
+                    improved = true;
+                    break;
+                }
+            }
+        }
+
+        if !improved {
+            return num_changes;
+        }
+    }
+}
+
+/// The score of `items[index]` excluding any elements already covered by the `solution`.
+fn marginal_score<T: MaxCover>(items: &[T], index: usize, solution: &[usize]) -> usize {
+    let mut item = items[index].clone();
+    for &other in solution.iter().filter(|&&other| other != index) {
+        item.update_covering_set(items[other].intermediate(), items[other].covering_set());
+    }
+    item.score()
+}
+
 /// Perform a greedy merge of two max cover solutions, preferring higher-score values.
 pub fn merge_solutions<I1, I2, T>(cover1: I1, cover2: I2, limit: usize) -> Vec<T::Object>
 where
@@ -223,4 +375,55 @@ mod test {
         assert_eq!(quality(&cover), 19);
         assert_eq!(cover.len(), 5);
     }
+
+    fn far_deadline() -> Instant {
+        Instant::now() + std::time::Duration::from_secs(60)
+    }
+
+    // Greedy selects the first set and then either of the others (quality 5), while the optimal
+    // solution is the last two sets (quality 6).
+    #[test]
+    fn local_search_improves_greedy() {
+        let sets = vec![
+            HashSet::from_iter(vec![1, 2, 3, 4]),
+            HashSet::from_iter(vec![1, 2, 5]),
+            HashSet::from_iter(vec![3, 4, 6]),
+        ];
+        assert_eq!(quality(&maximum_cover(sets.clone(), 2, "test")), 5);
+
+        let cover = maximum_cover_with_deadline(sets.clone(), 2, "test", far_deadline());
+        assert_eq!(quality(&cover), 6);
+        assert!(cover.contains(&sets[1]));
+        assert!(cover.contains(&sets[2]));
+    }
+
+    #[test]
+    fn local_search_never_worse_than_greedy() {
+        let sets = vec![
+            HashSet::from_iter(vec![1, 2, 3, 4, 5, 6, 7, 8]),
+            HashSet::from_iter(vec![1, 2, 3, 9, 10, 11]),
+            HashSet::from_iter(vec![4, 5, 6, 12, 13, 14]),
+            HashSet::from_iter(vec![7, 8, 15, 16, 17, 18]),
+            HashSet::from_iter(vec![1, 2, 9, 10]),
+            HashSet::from_iter(vec![1, 5, 6, 8]),
+            HashSet::from_iter(vec![1, 7, 11, 19]),
+        ];
+        for k in 0..8 {
+            let greedy = maximum_cover(sets.clone(), k, "test");
+            let cover = maximum_cover_with_deadline(sets.clone(), k, "test", far_deadline());
+            assert!(quality(&cover) >= quality(&greedy));
+            assert!(cover.len() <= k);
+        }
+    }
+
+    #[test]
+    fn expired_deadline_returns_greedy() {
+        let sets = vec![
+            HashSet::from_iter(vec![1, 2, 3, 4]),
+            HashSet::from_iter(vec![1, 2, 5]),
+            HashSet::from_iter(vec![3, 4, 6]),
+        ];
+        let cover = maximum_cover_with_deadline(sets.clone(), 2, "test", Instant::now());
+        assert_eq!(cover, maximum_cover(sets, 2, "test"));
+    }
 }
@@ -28,4 +28,9 @@ lazy_static! {
         "Number of non-trivial items considered in a max coverage optimisation",
         &["label"]
     );
+    pub static ref MAX_COVER_IMPROVEMENTS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "op_pool_max_cover_improvements_total",
+        "Number of changes made by local search to improve a greedy max coverage solution",
+        &["label"]
+    );
 }
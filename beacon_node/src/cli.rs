@@ -60,6 +60,24 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                        not be performed before shutdown.")
                 .takes_value(false),
         )
+        .arg(
+            Arg::with_name("fork-topic-subscribe-epochs")
+                .long("fork-topic-subscribe-epochs")
+                .value_name("EPOCHS")
+                .help("Subscribe to the gossip topics of an upcoming fork this many epochs \
+                       before the fork. Must be at least 1. If omitted, the node subscribes 2 \
+                       slots before the fork.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("fork-topic-unsubscribe-epochs")
+                .long("fork-topic-unsubscribe-epochs")
+                .value_name("EPOCHS")
+                .help("Unsubscribe from the gossip topics of the previous fork this many \
+                       epochs after a fork.")
+                .default_value("2")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("zero-ports")
                 .long("zero-ports")
@@ -252,6 +270,16 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .help("Attempts to download blocks all the way back to genesis when checkpoint syncing.")
                 .takes_value(false),
         )
+        .arg(
+            Arg::with_name("disable-backfill")
+                .long("disable-backfill")
+                .help("Do not download any historic blocks when checkpoint syncing. Blocks prior to \
+                       the checkpoint will be unavailable via the HTTP API and to peers. Intended for \
+                       short-lived nodes which only need to follow the head of the chain.")
+                .conflicts_with("genesis-backfill")
+                .conflicts_with("reconstruct-historic-states")
+                .takes_value(false),
+        )
         .arg(
             Arg::with_name("enable-private-discovery")
                 .long("enable-private-discovery")
@@ -415,6 +443,34 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                     address of this server (e.g., http://localhost:5054).")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("metrics-allowlist")
+                .long("metrics-allowlist")
+                .value_name("REGEX")
+                .help("If set, only metrics with names matching this regular expression are \
+                    served by the Prometheus metrics HTTP server.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("metrics-denylist")
+                .long("metrics-denylist")
+                .value_name("REGEX")
+                .help("If set, metrics with names matching this regular expression are not \
+                    served by the Prometheus metrics HTTP server. Applied after \
+                    --metrics-allowlist.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("metrics-histogram-buckets")
+                .long("metrics-histogram-buckets")
+                .value_name("NAME=BUCKETS")
+                .help("Override the buckets of a histogram metric, e.g. \
+                    `beacon_block_processing_seconds=0.1,0.25,0.5,1,2,4`. Buckets are upper \
+                    bounds in ascending order. May be supplied multiple times.")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
         .arg(
             Arg::with_name("shuffling-cache-size")
             .long("shuffling-cache-size")
@@ -423,6 +479,63 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Shufflings are dependent on validator count and setting this value to a large number can consume a large amount of memory.")
             .takes_value(true)
         )
+        .arg(
+            Arg::with_name("observed-gossip-attesters-epochs")
+                .long("observed-gossip-attesters-epochs")
+                .value_name("EPOCHS")
+                .help("The number of epochs of unaggregated attestation gossip for which the attesters are \
+                       remembered, to detect duplicate attestations from the same validator. Values lower than the default will \
+                       cause some valid messages to be rejected. [default: 3]")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("observed-gossip-attesters-capacity")
+                .long("observed-gossip-attesters-capacity")
+                .value_name("VALIDATORS")
+                .help("The number of validators to initially allocate space for when remembering \
+                       the attesters of a new epoch of unaggregated attestation gossip. This is only an allocation hint, \
+                       it is used for the first epoch only and does not bound memory usage. Later epochs are \
+                       sized from the previous ones and grow to fit every observed validator.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("observed-block-attesters-epochs")
+                .long("observed-block-attesters-epochs")
+                .value_name("EPOCHS")
+                .help("The number of epochs of attestations included in blocks for which the attesters are \
+                       remembered, to detect validators which have already been included in a block. Values lower than the default will \
+                       cause some valid messages to be rejected. [default: 3]")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("observed-block-attesters-capacity")
+                .long("observed-block-attesters-capacity")
+                .value_name("VALIDATORS")
+                .help("The number of validators to initially allocate space for when remembering \
+                       the attesters of a new epoch of attestations included in blocks. This is only an allocation hint, \
+                       it is used for the first epoch only and does not bound memory usage. Later epochs are \
+                       sized from the previous ones and grow to fit every observed validator.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("observed-aggregators-epochs")
+                .long("observed-aggregators-epochs")
+                .value_name("EPOCHS")
+                .help("The number of epochs of aggregate attestation gossip for which the aggregators are \
+                       remembered, to detect duplicate aggregates from the same aggregator. Values lower than the default will \
+                       cause some valid messages to be rejected. [default: 3]")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("observed-aggregators-capacity")
+                .long("observed-aggregators-capacity")
+                .value_name("VALIDATORS")
+                .help("The number of validators to initially allocate space for when remembering \
+                       the aggregators of a new epoch of aggregate attestation gossip. This is only an allocation hint, \
+                       it is used for the first epoch only and does not bound memory usage. Later epochs are \
+                       sized from the previous ones and grow to fit every observed validator.")
+                .takes_value(true)
+        )
 
         /*
          * Monitoring metrics
@@ -492,10 +605,13 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .long("eth1-endpoints")
                 .value_name("HTTP-ENDPOINTS")
                 .conflicts_with("eth1-endpoint")
-                .help("One http endpoint for a web3 connection to an execution node. \
-                       Note: This flag is now only useful for testing, use `--execution-endpoint` \
-                       flag to connect to an execution node on mainnet and testnets.
-                       Defaults to http://127.0.0.1:8545.")
+                .help("One or more comma-delimited http endpoints for a web3 connection to an \
+                       execution node. The first endpoint is used whilst it is healthy, the others \
+                       are used as fallbacks when it is unreachable, far behind or on the wrong \
+                       chain. When `--execution-endpoint` is set it is used as the primary \
+                       endpoint and all of these endpoints are fallbacks. Note: This flag is now \
+                       only useful for testing, use `--execution-endpoint` flag to connect to an \
+                       execution node on mainnet and testnets. Defaults to http://127.0.0.1:8545.")
                 .takes_value(true)
         )
         .arg(
@@ -668,6 +784,16 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .default_value("true")
         )
+        .arg(
+            Arg::with_name("low-disk-space-threshold")
+                .long("low-disk-space-threshold")
+                .value_name("GIGABYTES")
+                .help("When the free space on the volume holding the database (or the slasher \
+                       database, if enabled) drops below this many gigabytes, pause backfill sync and historic state reconstruction and \
+                       log a critical warning until space is freed. Set to 0 to disable.")
+                .takes_value(true)
+                .default_value("5")
+        )
 
         /*
          * Misc.
@@ -767,6 +893,18 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .requires("slasher")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("slasher-prune-batch-size")
+                .long("slasher-prune-batch-size")
+                .help(
+                    "Maximum number of blocks, and maximum number of attestations, for the slasher \
+                     to delete from its database per update. Pruning of a larger backlog is \
+                     spread across subsequent updates."
+                )
+                .value_name("COUNT")
+                .requires("slasher")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("slasher-chunk-size")
                 .long("slasher-chunk-size")
@@ -804,6 +942,16 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .possible_values(slasher::DatabaseBackend::VARIANTS)
                 .requires("slasher")
         )
+        .arg(
+            Arg::with_name("slasher-migrate-backend")
+                .long("slasher-migrate-backend")
+                .help("If an existing slasher database is found which uses a different backend to \
+                       the one selected by --slasher-backend, copy its contents into the selected \
+                       backend and delete the old database. The copy happens once at start-up \
+                       and may take a long time for large databases.")
+                .takes_value(false)
+                .requires("slasher")
+        )
         .arg(
             Arg::with_name("wss-checkpoint")
                 .long("wss-checkpoint")
@@ -842,6 +990,22 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .conflicts_with("checkpoint-state")
         )
+        .arg(
+            Arg::with_name("genesis-state-url")
+                .long("genesis-state-url")
+                .help("Download the SSZ-encoded genesis state from this URL. Only for networks \
+                       which don't include a genesis.ssz in their --testnet-dir.")
+                .value_name("URL")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("genesis-state-url-timeout")
+                .long("genesis-state-url-timeout")
+                .help("Set the timeout for downloading the genesis state from --genesis-state-url.")
+                .value_name("SECONDS")
+                .takes_value(true)
+                .default_value("180")
+        )
         .arg(
             Arg::with_name("checkpoint-sync-url-timeout")
                 .long("checkpoint-sync-url-timeout")
@@ -860,9 +1024,19 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Arg::with_name("validator-monitor-auto")
                 .long("validator-monitor-auto")
                 .help("Enables the automatic detection and monitoring of validators connected to the \
-                    HTTP API and using the subnet subscription endpoint. This generally has the \
-                    effect of providing additional logging and metrics for locally controlled \
-                    validators.")
+                    HTTP API and using the subnet subscription, proposer preparation or validator \
+                    registration endpoints. This generally has the effect of providing additional \
+                    logging and metrics for locally controlled validators.")
+        )
+        .arg(
+            Arg::with_name("validator-monitor-auto-limit")
+                .long("validator-monitor-auto-limit")
+                .help("Stop automatically enrolling validators for monitoring once this many \
+                    validators are being monitored. This bounds the memory used by the validator \
+                    monitor when a large number of validators are connected. Defaults to no limit.")
+                .value_name("INTEGER")
+                .requires("validator-monitor-auto")
+                .takes_value(true)
         )
         .arg(
             Arg::with_name("validator-monitor-pubkeys")
@@ -972,6 +1146,36 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("250")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("attestation-packing-time-budget")
+                .long("attestation-packing-time-budget")
+                .value_name("MILLISECONDS")
+                .help("Set the maximum number of milliseconds to spend improving the selection \
+                       of attestations for a block proposal, beyond the initial greedy \
+                       selection. Higher values may increase attestation rewards at the cost of \
+                       slower block production. Set to 0 to only use the greedy selection.")
+                .default_value("50")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("state-advance-offset")
+                .long("state-advance-offset")
+                .value_name("MILLISECONDS")
+                .help("Delay after the start of each slot at which to advance the head state to \
+                       the next slot ahead of time, to speed up the import of the next block. \
+                       The state advance is skipped automatically whilst syncing or when the \
+                       node is overloaded. The default is 3/4 of a slot (9 seconds on mainnet).")
+                .takes_value(true)
+                .conflicts_with("disable-state-advance")
+        )
+        .arg(
+            Arg::with_name("disable-state-advance")
+                .long("disable-state-advance")
+                .help("Do not advance the head state to the next slot ahead of time. This reduces \
+                       CPU and memory usage at the cost of slower block imports, especially at \
+                       the start of each epoch.")
+                .takes_value(false)
+        )
         .arg(
             Arg::with_name("paranoid-block-proposal")
                 .long("paranoid-block-proposal")
@@ -1131,4 +1335,68 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .possible_values(ProgressiveBalancesMode::VARIANTS)
         )
+        .arg(
+            Arg::with_name("beacon-processor-early-block-delay-ms")
+                .long("beacon-processor-early-block-delay-ms")
+                .value_name("MILLISECONDS")
+                .help("Delay after the start of a slot before processing a block which arrived \
+                        early for that slot. Must be less than the gossip clock disparity. \
+                        [default: 5]")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("beacon-processor-unknown-block-attestation-delay-ms")
+                .long("beacon-processor-unknown-block-attestation-delay-ms")
+                .value_name("MILLISECONDS")
+                .help("How long to hold attestations which reference an unknown block while \
+                        waiting for that block to be imported. [default: 12000]")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("beacon-processor-rpc-block-delay-ms")
+                .long("beacon-processor-rpc-block-delay-ms")
+                .value_name("MILLISECONDS")
+                .help("How long to hold a block received via RPC whilst the same block is being \
+                        imported from gossip. [default: 4000]")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("beacon-processor-unknown-parent-light-client-update-delay-ms")
+                .long("beacon-processor-unknown-parent-light-client-update-delay-ms")
+                .value_name("MILLISECONDS")
+                .help("How long to hold light client updates which reference an unknown parent \
+                        block while waiting for that block to be imported. [default: 12000]")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("beacon-processor-max-queued-blocks")
+                .long("beacon-processor-max-queued-blocks")
+                .value_name("INTEGER")
+                .help("Maximum number of early gossip blocks (and, separately, RPC blocks) held \
+                        for re-processing before new ones are dropped. [default: 16]")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("beacon-processor-max-queued-light-client-updates")
+                .long("beacon-processor-max-queued-light-client-updates")
+                .value_name("INTEGER")
+                .help("Maximum number of light client updates held for re-processing before new \
+                        ones are dropped. [default: 128]")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("beacon-processor-max-queued-attestations")
+                .long("beacon-processor-max-queued-attestations")
+                .value_name("INTEGER")
+                .help("Maximum number of attestations held for re-processing before new ones \
+                        are dropped. [default: 16384]")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("beacon-processor-queue-early-attestations")
+                .long("beacon-processor-queue-early-attestations")
+                .help("Rather than ignoring attestations which arrive up to one slot too early, \
+                        hold them and process them at the start of their slot.")
+                .takes_value(false)
+        )
 }
@@ -2,6 +2,7 @@ use beacon_chain::chain_config::{
     DisallowedReOrgOffsets, ReOrgThreshold, DEFAULT_PREPARE_PAYLOAD_LOOKAHEAD_FACTOR,
     DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION, DEFAULT_RE_ORG_THRESHOLD,
 };
+use beacon_chain::MAXIMUM_GOSSIP_CLOCK_DISPARITY;
 use clap::ArgMatches;
 use clap_utils::flags::DISABLE_MALLOC_TUNING_FLAG;
 use client::{ClientConfig, ClientGenesis};
@@ -152,6 +153,44 @@ pub fn get_config<E: EthSpec>(
         client_config.chain.shuffling_cache_size = cache_size;
     }
 
+    if let Some(epochs) = clap_utils::parse_optional(cli_args, "observed-gossip-attesters-epochs")?
+    {
+        if epochs == 0 {
+            return Err("--observed-gossip-attesters-epochs must be greater than zero".into());
+        }
+        client_config.chain.observed_gossip_attesters_epochs = epochs;
+    }
+
+    if let Some(capacity) =
+        clap_utils::parse_optional(cli_args, "observed-gossip-attesters-capacity")?
+    {
+        client_config.chain.observed_gossip_attesters_capacity = capacity;
+    }
+
+    if let Some(epochs) = clap_utils::parse_optional(cli_args, "observed-block-attesters-epochs")? {
+        if epochs == 0 {
+            return Err("--observed-block-attesters-epochs must be greater than zero".into());
+        }
+        client_config.chain.observed_block_attesters_epochs = epochs;
+    }
+
+    if let Some(capacity) =
+        clap_utils::parse_optional(cli_args, "observed-block-attesters-capacity")?
+    {
+        client_config.chain.observed_block_attesters_capacity = capacity;
+    }
+
+    if let Some(epochs) = clap_utils::parse_optional(cli_args, "observed-aggregators-epochs")? {
+        if epochs == 0 {
+            return Err("--observed-aggregators-epochs must be greater than zero".into());
+        }
+        client_config.chain.observed_aggregators_epochs = epochs;
+    }
+
+    if let Some(capacity) = clap_utils::parse_optional(cli_args, "observed-aggregators-capacity")? {
+        client_config.chain.observed_aggregators_capacity = capacity;
+    }
+
     /*
      * Prometheus metrics HTTP server
      */
@@ -181,6 +220,18 @@ pub fn get_config<E: EthSpec>(
         client_config.http_metrics.allow_origin = Some(allow_origin.to_string());
     }
 
+    client_config.http_metrics.allowlist =
+        clap_utils::parse_optional(cli_args, "metrics-allowlist")?;
+    client_config.http_metrics.denylist = clap_utils::parse_optional(cli_args, "metrics-denylist")?;
+    // Pre-validate the regexes to give feedback to the user on node startup.
+    http_metrics::MetricFilter::new(&client_config.http_metrics)?;
+
+    if let Some(values) = cli_args.values_of("metrics-histogram-buckets") {
+        client_config.http_metrics.histogram_buckets = values
+            .map(parse_histogram_buckets)
+            .collect::<Result<_, _>>()?;
+    }
+
     /*
      * Explorer metrics
      */
@@ -240,11 +291,19 @@ pub fn get_config<E: EthSpec>(
         let endpoint = SensitiveUrl::parse(endpoint)
             .map_err(|e| format!("eth1-endpoint was an invalid URL: {:?}", e))?;
         client_config.eth1.endpoint = Eth1Endpoint::NoAuth(endpoint);
-    } else if let Some(endpoint) = cli_args.value_of("eth1-endpoints") {
+    } else if let Some(endpoints) = cli_args.value_of("eth1-endpoints") {
         client_config.sync_eth1_chain = true;
-        let endpoint = SensitiveUrl::parse(endpoint)
-            .map_err(|e| format!("eth1-endpoints contains an invalid URL {:?}", e))?;
+        let mut endpoints = endpoints
+            .split(',')
+            .map(|endpoint| SensitiveUrl::parse(endpoint.trim()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("eth1-endpoints contains an invalid URL {:?}", e))?
+            .into_iter();
+        let endpoint = endpoints
+            .next()
+            .ok_or("eth1-endpoints must contain at least one URL")?;
         client_config.eth1.endpoint = Eth1Endpoint::NoAuth(endpoint);
+        client_config.eth1.fallback_endpoints = endpoints.collect();
     }
 
     if let Some(val) = cli_args.value_of("eth1-blocks-per-log-query") {
@@ -348,15 +407,21 @@ pub fn get_config<E: EthSpec>(
             clap_utils::parse_required(cli_args, "execution-timeout-multiplier")?;
         el_config.execution_timeout_multiplier = Some(execution_timeout_multiplier);
 
-        // If `--execution-endpoint` is provided, we should ignore any `--eth1-endpoints` values and
-        // use `--execution-endpoint` instead. Also, log a deprecation warning.
+        // If `--execution-endpoint` is provided, it becomes the primary eth1 endpoint and any
+        // `--eth1-endpoints` values are only used as fallbacks behind it. Also, log a deprecation
+        // warning.
         if cli_args.is_present("eth1-endpoints") || cli_args.is_present("eth1-endpoint") {
             warn!(
                 log,
-                "Ignoring --eth1-endpoints flag";
-                "info" => "the value for --execution-endpoint will be used instead. \
-                    --eth1-endpoints has been deprecated for post-merge configurations"
+                "Using --eth1-endpoints as fallbacks";
+                "info" => "the value for --execution-endpoint will be used as the primary eth1 \
+                    endpoint. --eth1-endpoints has been deprecated for post-merge configurations"
             );
+            let eth1_endpoint = client_config.eth1.endpoint.get_endpoint();
+            client_config
+                .eth1
+                .fallback_endpoints
+                .insert(0, eth1_endpoint);
         }
         client_config.eth1.endpoint = Eth1Endpoint::Auth {
             endpoint: execution_endpoint,
@@ -400,6 +465,13 @@ pub fn get_config<E: EthSpec>(
         client_config.store.prune_payloads = prune_payloads;
     }
 
+    if let Some(threshold_gb) =
+        clap_utils::parse_optional::<u64>(cli_args, "low-disk-space-threshold")?
+    {
+        client_config.store.low_disk_space_threshold =
+            threshold_gb.saturating_mul(1024 * 1024 * 1024);
+    }
+
     /*
      * Zero-ports
      *
@@ -452,8 +524,31 @@ pub fn get_config<E: EthSpec>(
     client_config.chain.checkpoint_sync_url_timeout =
         clap_utils::parse_required::<u64>(cli_args, "checkpoint-sync-url-timeout")?;
 
-    client_config.genesis = if let Some(genesis_state_bytes) =
-        eth2_network_config.genesis_state_bytes.clone()
+    // The genesis state itself is downloaded when the client is built, so that the request
+    // doesn't block the runtime.
+    if let Some(url) = cli_args.value_of("genesis-state-url") {
+        if eth2_network_config.beacon_state_is_known() {
+            return Err(
+                "--genesis-state-url cannot be used as the genesis state for this network is \
+                 already known"
+                    .to_string(),
+            );
+        }
+        client_config.genesis_state_url = Some(url.to_string());
+    }
+    client_config.genesis_state_url_timeout = Duration::from_secs(clap_utils::parse_required(
+        cli_args,
+        "genesis-state-url-timeout",
+    )?);
+
+    // Check user-supplied testnet directories up-front, rather than failing part way through
+    // startup or, worse, silently following the wrong chain.
+    if cli_args.is_present("testnet-dir") {
+        eth2_network_config.validate::<E>()?;
+    }
+
+    client_config.genesis = if eth2_network_config.beacon_state_is_known()
+        || client_config.genesis_state_url.is_some()
     {
         // Set up weak subjectivity sync, or start from the hardcoded genesis state.
         if let (Some(initial_state_path), Some(initial_block_path)) = (
@@ -476,7 +571,6 @@ pub fn get_config<E: EthSpec>(
             let anchor_block_bytes = read(initial_block_path)?;
 
             ClientGenesis::WeakSubjSszBytes {
-                genesis_state_bytes,
                 anchor_state_bytes,
                 anchor_block_bytes,
             }
@@ -484,17 +578,9 @@ pub fn get_config<E: EthSpec>(
             let url = SensitiveUrl::parse(remote_bn_url)
                 .map_err(|e| format!("Invalid checkpoint sync URL: {:?}", e))?;
 
-            ClientGenesis::CheckpointSyncUrl {
-                genesis_state_bytes,
-                url,
-            }
+            ClientGenesis::CheckpointSyncUrl { url }
         } else {
-            // Note: re-serializing the genesis state is not so efficient, however it avoids adding
-            // trait bounds to the `ClientGenesis` enum. This would have significant flow-on
-            // effects.
-            ClientGenesis::SszBytes {
-                genesis_state_bytes,
-            }
+            ClientGenesis::GenesisState
         }
     } else {
         if cli_args.is_present("checkpoint-state") || cli_args.is_present("checkpoint-sync-url") {
@@ -519,6 +605,7 @@ pub fn get_config<E: EthSpec>(
             ));
         }
 
+        client_config.graffiti_set_explicitly = true;
         graffiti.as_bytes()
     } else if cli_args.is_present("private") {
         b""
@@ -623,6 +710,12 @@ pub fn get_config<E: EthSpec>(
             slasher_config.attestation_root_cache_size = attestation_cache_size;
         }
 
+        if let Some(prune_batch_size) =
+            clap_utils::parse_optional(cli_args, "slasher-prune-batch-size")?
+        {
+            slasher_config.prune_batch_size = prune_batch_size;
+        }
+
         if let Some(chunk_size) = clap_utils::parse_optional(cli_args, "slasher-chunk-size")? {
             slasher_config.chunk_size = chunk_size;
         }
@@ -641,6 +734,10 @@ pub fn get_config<E: EthSpec>(
             slasher_config.backend = backend;
         }
 
+        if cli_args.is_present("slasher-migrate-backend") {
+            slasher_config.migrate_backend = true;
+        }
+
         client_config.slasher = Some(slasher_config);
     }
 
@@ -648,6 +745,9 @@ pub fn get_config<E: EthSpec>(
         client_config.validator_monitor_auto = true;
     }
 
+    client_config.validator_monitor_auto_limit =
+        clap_utils::parse_optional(cli_args, "validator-monitor-auto-limit")?;
+
     if let Some(pubkeys) = cli_args.value_of("validator-monitor-pubkeys") {
         let pubkeys = pubkeys
             .split(',')
@@ -738,6 +838,14 @@ pub fn get_config<E: EthSpec>(
         client_config.chain.fork_choice_before_proposal_timeout_ms = timeout;
     }
 
+    if let Some(budget) = clap_utils::parse_optional(cli_args, "attestation-packing-time-budget")? {
+        client_config.chain.attestation_packing_time_budget_ms = budget;
+    }
+
+    client_config.chain.state_advance_offset_millis =
+        clap_utils::parse_optional(cli_args, "state-advance-offset")?;
+    client_config.chain.disable_state_advance = cli_args.is_present("disable-state-advance");
+
     if !clap_utils::parse_required::<bool>(cli_args, "count-unrealized")? {
         warn!(
             log,
@@ -786,6 +894,10 @@ pub fn get_config<E: EthSpec>(
     if cli_args.is_present("genesis-backfill") {
         client_config.chain.genesis_backfill = true;
     }
+
+    if cli_args.is_present("disable-backfill") {
+        client_config.chain.disable_backfill = true;
+    }
     // Payload selection configs
     if cli_args.is_present("always-prefer-builder-payload") {
         client_config.always_prefer_builder_payload = true;
@@ -795,6 +907,21 @@ pub fn get_config<E: EthSpec>(
     client_config.chain.enable_backfill_rate_limiting =
         !cli_args.is_present("disable-backfill-rate-limiting");
 
+    if let Some(epochs) =
+        clap_utils::parse_optional::<u64>(cli_args, "fork-topic-subscribe-epochs")?
+    {
+        // Subscribing exactly at the fork would drop messages published around the boundary. The
+        // default of a couple of slots is used when the flag is omitted.
+        if epochs == 0 {
+            return Err("--fork-topic-subscribe-epochs must be at least 1".to_string());
+        }
+        client_config.network.fork_subscribe_slots = epochs
+            .checked_mul(E::slots_per_epoch())
+            .ok_or("--fork-topic-subscribe-epochs is too large")?;
+    }
+    client_config.network.fork_unsubscribe_epochs =
+        clap_utils::parse_required(cli_args, "fork-topic-unsubscribe-epochs")?;
+
     if let Some(path) = clap_utils::parse_optional(cli_args, "invalid-gossip-verified-blocks-path")?
     {
         client_config.network.invalid_block_storage = Some(path);
@@ -806,6 +933,60 @@ pub fn get_config<E: EthSpec>(
         client_config.chain.progressive_balances_mode = progressive_balances_mode;
     }
 
+    // Beacon processor re-processing queue.
+    if let Some(delay) =
+        clap_utils::parse_optional(cli_args, "beacon-processor-early-block-delay-ms")?
+    {
+        let delay = Duration::from_millis(delay);
+        if delay >= MAXIMUM_GOSSIP_CLOCK_DISPARITY {
+            return Err(format!(
+                "beacon-processor-early-block-delay-ms must be less than {}",
+                MAXIMUM_GOSSIP_CLOCK_DISPARITY.as_millis()
+            ));
+        }
+        client_config.beacon_processor.early_block_delay = delay;
+    }
+    if let Some(delay) = clap_utils::parse_optional(
+        cli_args,
+        "beacon-processor-unknown-block-attestation-delay-ms",
+    )? {
+        client_config
+            .beacon_processor
+            .unknown_block_attestation_delay = Duration::from_millis(delay);
+    }
+    if let Some(delay) =
+        clap_utils::parse_optional(cli_args, "beacon-processor-rpc-block-delay-ms")?
+    {
+        client_config.beacon_processor.rpc_block_delay = Duration::from_millis(delay);
+    }
+    if let Some(delay) = clap_utils::parse_optional(
+        cli_args,
+        "beacon-processor-unknown-parent-light-client-update-delay-ms",
+    )? {
+        client_config
+            .beacon_processor
+            .unknown_parent_light_client_update_delay = Duration::from_millis(delay);
+    }
+    if let Some(max_queued_blocks) =
+        clap_utils::parse_optional(cli_args, "beacon-processor-max-queued-blocks")?
+    {
+        client_config.beacon_processor.max_queued_blocks = max_queued_blocks;
+    }
+    if let Some(max_queued_attestations) =
+        clap_utils::parse_optional(cli_args, "beacon-processor-max-queued-attestations")?
+    {
+        client_config.beacon_processor.max_queued_attestations = max_queued_attestations;
+    }
+    if let Some(max_queued_light_client_updates) =
+        clap_utils::parse_optional(cli_args, "beacon-processor-max-queued-light-client-updates")?
+    {
+        client_config
+            .beacon_processor
+            .max_queued_light_client_updates = max_queued_light_client_updates;
+    }
+    client_config.beacon_processor.queue_early_attestations =
+        cli_args.is_present("beacon-processor-queue-early-attestations");
+
     Ok(client_config)
 }
 
@@ -1319,6 +1500,32 @@ pub fn get_slots_per_restore_point<E: EthSpec>(
     }
 }
 
+/// Parses a `--metrics-histogram-buckets` value of the form `NAME=BUCKET,BUCKET,...`.
+fn parse_histogram_buckets(cli_value: &str) -> Result<(String, Vec<f64>), String> {
+    let (name, buckets) = cli_value.split_once('=').ok_or_else(|| {
+        format!(
+            "Histogram buckets must be of the form NAME=BUCKETS: {}",
+            cli_value
+        )
+    })?;
+
+    let buckets = buckets
+        .split(',')
+        .map(|bucket| {
+            bucket
+                .trim()
+                .parse::<f64>()
+                .map_err(|e| format!("Invalid bucket {} for {}: {:?}", bucket, name, e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if !buckets.windows(2).all(|pair| pair[0] < pair[1]) {
+        return Err(format!("Buckets for {} must be in ascending order", name));
+    }
+
+    Ok((name.to_string(), buckets))
+}
+
 /// Parses the `cli_value` as a comma-separated string of values to be parsed with `parser`.
 ///
 /// If there is more than one value, log a warning. If there are no values, return an error.
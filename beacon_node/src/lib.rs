@@ -75,6 +75,11 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
             )
         }
 
+        // Apply before the histograms are lazily created.
+        for (name, buckets) in &client_config.http_metrics.histogram_buckets {
+            lighthouse_metrics::set_histogram_buckets(name, buckets.clone())?;
+        }
+
         if !client_config.chain.enable_lock_timeouts {
             info!(log, "Disabling lock timeouts globally");
             TimeoutRwLock::disable_timeouts()
@@ -87,6 +92,10 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
             .disk_store(&db_path, &freezer_db_path, store_config, log.clone())?;
 
         let builder = if let Some(mut slasher_config) = client_config.slasher.clone() {
+            if slasher_config.migrate_backend {
+                slasher::migrate_backend(&slasher_config, &log)
+                    .map_err(|e| format!("Slasher backend migration error: {:?}", e))?;
+            }
             match slasher_config.override_backend() {
                 DatabaseBackendOverride::Success(old_backend) => {
                     info!(
@@ -161,9 +170,13 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
 
         builder
             .build_beacon_chain()?
-            .network(&client_config.network)
+            .network(
+                &client_config.network,
+                client_config.beacon_processor.clone(),
+            )
             .await?
             .notifier()?
+            .disk_monitor()?
             .http_metrics_config(client_config.http_metrics.clone())
             .build()
             .map(Self)
@@ -8,6 +8,7 @@ pub const PREV_DEFAULT_SLOTS_PER_RESTORE_POINT: u64 = 2048;
 pub const DEFAULT_SLOTS_PER_RESTORE_POINT: u64 = 8192;
 pub const DEFAULT_BLOCK_CACHE_SIZE: usize = 5;
 pub const DEFAULT_HISTORIC_STATE_CACHE_SIZE: usize = 1;
+pub const DEFAULT_LOW_DISK_SPACE_THRESHOLD: u64 = 5 * 1024 * 1024 * 1024;
 
 /// Database configuration parameters.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -26,6 +27,9 @@ pub struct StoreConfig {
     pub compact_on_prune: bool,
     /// Whether to prune payloads on initialization and finalization.
     pub prune_payloads: bool,
+    /// Free space (in bytes) below which the database enters low disk space mode. Zero disables
+    /// the check.
+    pub low_disk_space_threshold: u64,
 }
 
 /// Variant of `StoreConfig` that gets written to disk. Contains immutable configuration params.
@@ -50,6 +54,7 @@ impl Default for StoreConfig {
             compact_on_init: false,
             compact_on_prune: true,
             prune_payloads: true,
+            low_disk_space_threshold: DEFAULT_LOW_DISK_SPACE_THRESHOLD,
         }
     }
 }
@@ -40,6 +40,10 @@ pub enum Error {
         expected: Hash256,
         computed: Hash256,
     },
+    /// State reconstruction was paused because the database is in low disk space mode.
+    StateReconstructionPaused {
+        slot: Slot,
+    },
     BlockReplayError(BlockReplayError),
     AddPayloadLogicError,
     SlotClockUnavailableForMigration,
@@ -36,6 +36,7 @@ use std::cmp::min;
 use std::convert::TryInto;
 use std::marker::PhantomData;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use types::*;
@@ -66,6 +67,9 @@ pub struct HotColdDB<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> {
     state_cache: Mutex<LruCache<Slot, BeaconState<E>>>,
     /// Chain spec.
     pub(crate) spec: ChainSpec,
+    /// Set while free disk space is below `config.low_disk_space_threshold`, during which
+    /// non-essential writes (backfill, state reconstruction) are paused.
+    low_disk_space: AtomicBool,
     /// Logger.
     pub(crate) log: Logger,
     /// Mere vessel for E.
@@ -134,6 +138,7 @@ impl<E: EthSpec> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
             state_cache: Mutex::new(LruCache::new(config.historic_state_cache_size)),
             config,
             spec,
+            low_disk_space: AtomicBool::new(false),
             log,
             _phantom: PhantomData,
         };
@@ -168,6 +173,7 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
             state_cache: Mutex::new(LruCache::new(config.historic_state_cache_size)),
             config,
             spec,
+            low_disk_space: AtomicBool::new(false),
             log,
             _phantom: PhantomData,
         };
@@ -1351,6 +1357,16 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         &self.config
     }
 
+    /// Returns `true` if the database is in low disk space mode.
+    pub fn is_low_disk_space(&self) -> bool {
+        self.low_disk_space.load(Ordering::Relaxed)
+    }
+
+    /// Enter or leave low disk space mode, returning the previous value.
+    pub fn set_low_disk_space(&self, low_disk_space: bool) -> bool {
+        self.low_disk_space.swap(low_disk_space, Ordering::Relaxed)
+    }
+
     /// Load previously-stored config from disk.
     fn load_config(&self) -> Result<Option<OnDiskStoreConfig>, Error> {
         self.hot_db.get(&CONFIG_KEY)
@@ -31,6 +31,12 @@ where
             });
         }
 
+        if self.is_low_disk_space() {
+            return Err(Error::StateReconstructionPaused {
+                slot: anchor.state_lower_limit,
+            });
+        }
+
         info!(
             self.log,
             "Beginning historic state reconstruction";
@@ -150,6 +156,12 @@ where
                             old_anchor,
                             Some(anchor.clone()),
                         )?;
+
+                        // Progress up to this slot is persisted, so it's safe to stop here and
+                        // resume once space has been freed.
+                        if self.is_low_disk_space() {
+                            return Err(Error::StateReconstructionPaused { slot });
+                        }
                     }
                 }
             }
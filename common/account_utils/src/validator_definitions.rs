@@ -3,7 +3,10 @@
 //! Serves as the source-of-truth of which validators this validator client should attempt (or not
 //! attempt) to load into the `crate::intialized_validators::InitializedValidators` struct.
 
-use crate::{default_keystore_password_path, write_file_via_temporary, ZeroizeString};
+use crate::{
+    default_keystore_password_path, read_password, strip_off_newlines, write_file_via_temporary,
+    PlainText, ZeroizeString,
+};
 use directory::ensure_dir_exists;
 use eth2_keystore::Keystore;
 use regex::Regex;
@@ -25,6 +28,14 @@ pub const CONFIG_FILENAME: &str = "validator_definitions.yml";
 /// See: https://github.com/sigp/lighthouse/issues/2159
 pub const CONFIG_TEMP_FILENAME: &str = ".validator_definitions.yml.tmp";
 
+/// A `voting_keystore_password_path` with this prefix names an environment variable containing
+/// the password, e.g. `env:VALIDATOR_PASSWORD`.
+pub const PASSWORD_ENV_VAR_PREFIX: &str = "env:";
+
+/// The extension of password files in a directory of passwords, which are named by the public key
+/// of their keystore.
+pub const PASSWORD_FILE_EXTENSION: &str = "pass";
+
 #[derive(Debug)]
 pub enum Error {
     /// The config file could not be opened.
@@ -66,6 +77,13 @@ pub struct Web3SignerDefinition {
     /// An empty password will be used if this is omitted.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub client_identity_password: Option<String>,
+
+    /// Hex-encoded SHA-256 fingerprint of the DER-encoded certificate presented by the server.
+    ///
+    /// If provided, responses from a server presenting any other certificate will be rejected.
+    /// Cannot be combined with `root_certificate_path` or `client_identity_path`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub certificate_fingerprint: Option<String>,
 }
 
 /// Defines how the validator client should attempt to sign messages for this validator.
@@ -73,6 +91,8 @@ pub struct Web3SignerDefinition {
 #[serde(tag = "type")]
 pub enum SigningDefinition {
     /// A validator that is defined by an EIP-2335 keystore on the local filesystem.
+    ///
+    /// See `read_voting_keystore_password` for the values of `voting_keystore_password_path`.
     #[serde(rename = "local_keystore")]
     LocalKeystore {
         voting_keystore_path: PathBuf,
@@ -115,6 +135,9 @@ pub struct ValidatorDefinition {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub builder_proposals: Option<bool>,
     #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub builder_boost_factor: Option<u64>,
+    #[serde(default)]
     pub description: String,
     #[serde(flatten)]
     pub signing_definition: SigningDefinition,
@@ -148,6 +171,7 @@ impl ValidatorDefinition {
             suggested_fee_recipient,
             gas_limit,
             builder_proposals,
+            builder_boost_factor: None,
             signing_definition: SigningDefinition::LocalKeystore {
                 voting_keystore_path,
                 voting_keystore_password_path: None,
@@ -296,6 +320,7 @@ impl ValidatorDefinitions {
                     suggested_fee_recipient: None,
                     gas_limit: None,
                     builder_proposals: None,
+                    builder_boost_factor: None,
                     signing_definition: SigningDefinition::LocalKeystore {
                         voting_keystore_path,
                         voting_keystore_password_path,
@@ -348,6 +373,41 @@ impl ValidatorDefinitions {
     }
 }
 
+/// Reads the password for the keystore of `voting_public_key` from `password_path`, which is one
+/// of:
+///
+/// - `env:VAR_NAME`: the password is the value of the environment variable `VAR_NAME`.
+/// - A directory: the password is read from the `<pubkey>.pass` file in the directory, where
+///   `<pubkey>` is the 0x-prefixed hex public key.
+/// - A file: the password is read from the file.
+///
+/// New-lines are removed from the password in all cases.
+pub fn read_voting_keystore_password(
+    password_path: &Path,
+    voting_public_key: &PublicKey,
+) -> Result<PlainText, io::Error> {
+    if let Some(var_name) = password_path
+        .to_str()
+        .and_then(|path| path.strip_prefix(PASSWORD_ENV_VAR_PREFIX))
+    {
+        let password = std::env::var(var_name).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Unable to read environment variable {}: {}", var_name, e),
+            )
+        })?;
+        Ok(strip_off_newlines(password.into_bytes()).into())
+    } else if password_path.is_dir() {
+        read_password(password_path.join(format!(
+            "{}.{}",
+            voting_public_key.as_hex_string(),
+            PASSWORD_FILE_EXTENSION
+        )))
+    } else {
+        read_password(password_path)
+    }
+}
+
 /// Perform an exhaustive tree search of `dir`, adding any discovered voting keystore paths to
 /// `matches`.
 ///
@@ -458,6 +518,40 @@ mod tests {
         assert!(!is_voting_keystore("keystore-cats.json"));
     }
 
+    #[test]
+    fn voting_keystore_password_from_env() {
+        let pubkey = types::Keypair::random().pk;
+        let path = PathBuf::from("env:LIGHTHOUSE_TEST_KEYSTORE_PASSWORD");
+
+        assert!(read_voting_keystore_password(&path, &pubkey).is_err());
+
+        std::env::set_var("LIGHTHOUSE_TEST_KEYSTORE_PASSWORD", "hunter2\n");
+        assert_eq!(
+            read_voting_keystore_password(&path, &pubkey)
+                .unwrap()
+                .as_bytes(),
+            b"hunter2"
+        );
+    }
+
+    #[test]
+    fn voting_keystore_password_from_directory() {
+        let pubkey = types::Keypair::random().pk;
+        let other_pubkey = types::Keypair::random().pk;
+        let dir = tempfile::tempdir().unwrap();
+
+        let password_file = dir.path().join(format!("{}.pass", pubkey.as_hex_string()));
+        fs::write(password_file, "hunter2\n").unwrap();
+
+        assert_eq!(
+            read_voting_keystore_password(dir.path(), &pubkey)
+                .unwrap()
+                .as_bytes(),
+            b"hunter2"
+        );
+        assert!(read_voting_keystore_password(dir.path(), &other_pubkey).is_err());
+    }
+
     #[test]
     fn graffiti_checks() {
         let no_graffiti = r#"---
@@ -618,4 +712,41 @@ mod tests {
         let def: ValidatorDefinition = serde_yaml::from_str(valid_builder_proposals).unwrap();
         assert_eq!(def.builder_proposals, Some(true));
     }
+
+    #[test]
+    fn builder_boost_factor_checks() {
+        let no_builder_boost_factor = r#"---
+        description: ""
+        enabled: true
+        type: local_keystore
+        voting_keystore_path: ""
+        voting_public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+        "#;
+        let def: ValidatorDefinition = serde_yaml::from_str(no_builder_boost_factor).unwrap();
+        assert!(def.builder_boost_factor.is_none());
+
+        let invalid_builder_boost_factor = r#"---
+        description: ""
+        enabled: true
+        type: local_keystore
+        builder_boost_factor: -1
+        voting_keystore_path: ""
+        voting_public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+        "#;
+        let def: Result<ValidatorDefinition, _> =
+            serde_yaml::from_str(invalid_builder_boost_factor);
+        assert!(def.is_err());
+
+        let valid_builder_boost_factor = r#"---
+        description: ""
+        enabled: true
+        type: local_keystore
+        builder_proposals: true
+        builder_boost_factor: 150
+        voting_keystore_path: ""
+        voting_public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+        "#;
+        let def: ValidatorDefinition = serde_yaml::from_str(valid_builder_boost_factor).unwrap();
+        assert_eq!(def.builder_boost_factor, Some(150));
+    }
 }
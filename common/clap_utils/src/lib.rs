@@ -6,7 +6,7 @@ use ethereum_types::U256 as Uint256;
 use ssz::Decode;
 use std::path::PathBuf;
 use std::str::FromStr;
-use types::{ChainSpec, Config, EthSpec};
+use types::{ChainSpec, Config, Epoch, EthSpec, ForkName};
 
 pub mod flags;
 
@@ -59,9 +59,77 @@ pub fn get_eth2_network_config(cli_args: &ArgMatches) -> Result<Eth2NetworkConfi
             .safe_slots_to_import_optimistically = slots;
     }
 
+    let fork_epochs = parse_fork_epoch_overrides(cli_args)?;
+    for (fork_name, epoch) in &fork_epochs {
+        eth2_network_config
+            .config
+            .set_fork_epoch(*fork_name, *epoch)
+            .map_err(|e| format!("Invalid fork epoch override: {}", e))?;
+    }
+    if !fork_epochs.is_empty() {
+        check_fork_epoch_order(&eth2_network_config.config)?;
+    }
+
     Ok(eth2_network_config)
 }
 
+/// Parses the `--fork-epoch NAME=EPOCH` values followed by the per-fork `--<fork>-fork-epoch`
+/// flags, so that the latter take precedence when both are supplied.
+fn parse_fork_epoch_overrides(cli_args: &ArgMatches) -> Result<Vec<(ForkName, Epoch)>, String> {
+    let mut fork_epochs = vec![];
+
+    for value in cli_args.values_of("fork-epoch").into_iter().flatten() {
+        let (fork_name, epoch) = value
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid --fork-epoch {}, expected NAME=EPOCH", value))?;
+        let fork_name = ForkName::from_str(fork_name)?;
+        let epoch = epoch
+            .parse::<u64>()
+            .map_err(|e| format!("Invalid epoch in --fork-epoch {}: {:?}", value, e))?;
+        fork_epochs.push((fork_name, Epoch::new(epoch)));
+    }
+
+    for (flag, fork_name) in [
+        ("altair-fork-epoch", ForkName::Altair),
+        ("bellatrix-fork-epoch", ForkName::Merge),
+        ("capella-fork-epoch", ForkName::Capella),
+    ] {
+        if let Some(epoch) = parse_optional::<u64>(cli_args, flag)? {
+            fork_epochs.push((fork_name, Epoch::new(epoch)));
+        }
+    }
+
+    Ok(fork_epochs)
+}
+
+/// Returns an error if a scheduled fork activates before an earlier one.
+fn check_fork_epoch_order(config: &Config) -> Result<(), String> {
+    let scheduled = [
+        (ForkName::Altair, config.altair_fork_epoch.as_ref()),
+        (ForkName::Merge, config.bellatrix_fork_epoch.as_ref()),
+        (ForkName::Capella, config.capella_fork_epoch.as_ref()),
+    ];
+
+    let mut previous: Option<(ForkName, Epoch)> = None;
+    for (fork_name, epoch) in scheduled {
+        let epoch = match epoch {
+            Some(epoch) => epoch.value,
+            None => continue,
+        };
+        if let Some((previous_fork, previous_epoch)) = previous {
+            if epoch < previous_epoch {
+                return Err(format!(
+                    "The {} fork epoch ({}) is before the {} fork epoch ({})",
+                    fork_name, epoch, previous_fork, previous_epoch
+                ));
+            }
+        }
+        previous = Some((fork_name, epoch));
+    }
+
+    Ok(())
+}
+
 /// Attempts to load the testnet dir at the path if `name` is in `matches`, returning an error if
 /// the path cannot be found or the testnet dir is invalid.
 pub fn parse_testnet_dir(
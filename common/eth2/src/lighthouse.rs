@@ -10,8 +10,8 @@ mod sync_committee_rewards;
 use crate::{
     ok_or_error,
     types::{
-        BeaconState, ChainSpec, DepositTreeSnapshot, Epoch, EthSpec, FinalizedExecutionBlock,
-        GenericResponse, ValidatorId,
+        graffiti::GraffitiString, BeaconState, ChainSpec, DepositTreeSnapshot, Epoch, EthSpec,
+        FinalizedExecutionBlock, GenericResponse, Graffiti, ValidatorId,
     },
     BeaconNodeHttpClient, DepositData, Error, Eth1Data, Hash256, Slot, StateId, StatusCode,
 };
@@ -30,7 +30,10 @@ pub use block_packing_efficiency::{
     BlockPackingEfficiency, BlockPackingEfficiencyQuery, ProposerInfo, UniqueAttestation,
 };
 pub use block_rewards::{AttestationRewards, BlockReward, BlockRewardMeta, BlockRewardsQuery};
-pub use lighthouse_network::{types::SyncState, PeerInfo};
+pub use lighthouse_network::{
+    types::{Subnet, SyncState},
+    ConnectionEvent, PeerActionRecord, PeerInfo, ScoreDetails,
+};
 pub use standard_block_rewards::StandardBlockReward;
 pub use sync_committee_rewards::SyncCommitteeReward;
 
@@ -50,6 +53,28 @@ pub struct Peer<T: EthSpec> {
     pub peer_info: PeerInfo<T>,
 }
 
+/// Information returned by `peers/{peer_id}`, used to diagnose why a peer was scored, disconnected
+/// or banned.
+///
+/// The peer's `PeerInfo` is available from `lighthouse/peers`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeerDetails {
+    /// The Peer's ID
+    pub peer_id: String,
+    /// The components of the peer's score, or `None` if the peer is trusted.
+    pub score: Option<ScoreDetails>,
+    /// The subnets the peer is subscribed to on gossipsub.
+    pub gossipsub_subnets: Vec<Subnet>,
+    /// The long-lived subnets advertised in the peer's metadata.
+    pub long_lived_subnets: Vec<Subnet>,
+    /// The protocols negotiated with the peer via identify.
+    pub protocols: Vec<String>,
+    /// The most recent actions reported against the peer, oldest first.
+    pub peer_actions: Vec<PeerActionRecord>,
+    /// The most recent changes to the peer's connection state, oldest first.
+    pub connection_history: Vec<ConnectionEvent>,
+}
+
 /// The results of validators voting during an epoch.
 ///
 /// Provides information about the current and previous epochs.
@@ -70,6 +95,21 @@ pub struct GlobalValidatorInclusionData {
     pub previous_epoch_head_attesting_gwei: u64,
 }
 
+/// The readiness of a beacon node to serve traffic, as returned by `lighthouse/health/ready`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Readiness {
+    /// True if the node is synced, its head is not optimistic and its execution layer is online.
+    pub is_ready: bool,
+    /// True if sync has reached the head of the chain.
+    pub is_synced: bool,
+    /// True if the head block has not been fully verified by the execution layer.
+    pub is_optimistic: bool,
+    /// True if the execution layer is offline, erroring or not configured.
+    pub el_offline: bool,
+    pub head_slot: Slot,
+    pub current_slot: Option<Slot>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ValidatorInclusionData {
     /// True if the validator has been slashed, ever.
@@ -306,6 +346,27 @@ pub struct Eth1SyncStatusData {
     pub lighthouse_is_cached_and_ready: bool,
 }
 
+/// A summary of the blocks in the Eth1 block cache.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Eth1BlockCacheSummary {
+    pub block_count: u64,
+    pub lowest_block_number: Option<u64>,
+    pub highest_block_number: Option<u64>,
+    pub earliest_block_timestamp: Option<u64>,
+    pub latest_block_timestamp: Option<u64>,
+    pub latest_block: Option<Eth1Block>,
+}
+
+/// A summary of the logs in the Eth1 deposit cache.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Eth1DepositCacheSummary {
+    pub deposit_count: u64,
+    pub finalized_deposit_count: u64,
+    pub deposit_root: Hash256,
+    pub latest_deposit_block_number: u64,
+    pub last_processed_block: Option<u64>,
+}
+
 /// A fully parsed eth1 deposit contract log.
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode)]
 pub struct DepositLog {
@@ -353,6 +414,18 @@ impl From<Eth1Block> for FinalizedExecutionBlock {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraffitiData {
+    pub graffiti: Graffiti,
+}
+
+/// Body of a `POST lighthouse/graffiti` request. The graffiti is a UTF-8 string of at most 32
+/// bytes, as accepted by `--graffiti`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpdateGraffitiRequest {
+    pub graffiti: GraffitiString,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DatabaseInfo {
     pub schema_version: u64,
@@ -361,6 +434,47 @@ pub struct DatabaseInfo {
     pub anchor: Option<AnchorInfo>,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlasherStats {
+    /// Total size of the slasher database files, in bytes.
+    pub database_size: u64,
+    pub num_attestations: u64,
+    pub num_blocks: u64,
+    pub current_epoch: Epoch,
+    /// Epoch at which the slasher last processed attestations and blocks successfully.
+    pub last_processed_epoch: Option<Epoch>,
+    /// Number of epochs of expired data which the slasher has yet to prune.
+    pub prune_backlog_epochs: u64,
+    /// Number of attester slashings found since the beacon node was started.
+    pub num_attester_slashings: u64,
+    /// Number of proposer slashings found since the beacon node was started.
+    pub num_proposer_slashings: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperationPoolInfo {
+    pub attestations: AttestationPoolInfo,
+    pub num_sync_contributions: u64,
+    pub num_attester_slashings: u64,
+    pub num_proposer_slashings: u64,
+    pub num_voluntary_exits: u64,
+    pub num_bls_to_execution_changes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttestationPoolInfo {
+    /// Total number of aggregates in the pool.
+    pub num_attestations: u64,
+    /// Number of distinct `AttestationData` the aggregates attest to.
+    pub num_attestation_data: u64,
+    /// Maximum number of aggregates for a single `AttestationData`.
+    pub max_aggregates_per_data: u64,
+    /// Total number of attesting indices across all aggregates.
+    ///
+    /// Dividing by `num_attestations` gives the mean aggregation degree.
+    pub num_attesting_indices: u64,
+}
+
 impl BeaconNodeHttpClient {
     /// Perform a HTTP GET request, returning `None` on a 404 error.
     async fn get_bytes_opt<U: IntoUrl>(&self, url: U) -> Result<Option<Vec<u8>>, Error> {
@@ -395,6 +509,39 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/health/ready`
+    ///
+    /// Returns the status code along with the readiness, since a node which is not ready responds
+    /// with `503 Service Unavailable`.
+    pub async fn get_lighthouse_health_ready(
+        &self,
+    ) -> Result<(StatusCode, GenericResponse<Readiness>), Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("health")
+            .push("ready");
+
+        let response = self.client.get(path).send().await?;
+        let status = response.status();
+        Ok((status, response.json().await?))
+    }
+
+    /// `GET lighthouse/health/live`
+    pub async fn get_lighthouse_health_live(&self) -> Result<(), Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("health")
+            .push("live");
+
+        self.get_response(path, |b| b).await.map(|_| ())
+    }
+
     /// `GET lighthouse/syncing`
     pub async fn get_lighthouse_syncing(&self) -> Result<GenericResponse<SyncState>, Error> {
         let mut path = self.server.full.clone();
@@ -415,6 +562,19 @@ impl BeaconNodeHttpClient {
      * fairly simply achieved, if desired.
      */
 
+    /// `GET lighthouse/peers/{peer_id}`
+    pub async fn get_lighthouse_peer(&self, peer_id: &str) -> Result<PeerDetails, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("peers")
+            .push(peer_id);
+
+        self.get(path).await
+    }
+
     /// `GET lighthouse/proto_array`
     pub async fn get_lighthouse_proto_array(&self) -> Result<GenericResponse<ProtoArray>, Error> {
         let mut path = self.server.full.clone();
@@ -492,6 +652,22 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/eth1/block_cache/summary`
+    pub async fn get_lighthouse_eth1_block_cache_summary(
+        &self,
+    ) -> Result<GenericResponse<Eth1BlockCacheSummary>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("eth1")
+            .push("block_cache")
+            .push("summary");
+
+        self.get(path).await
+    }
+
     /// `GET lighthouse/eth1/deposit_cache`
     pub async fn get_lighthouse_eth1_deposit_cache(
         &self,
@@ -507,6 +683,22 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/eth1/deposit_cache/summary`
+    pub async fn get_lighthouse_eth1_deposit_cache_summary(
+        &self,
+    ) -> Result<GenericResponse<Eth1DepositCacheSummary>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("eth1")
+            .push("deposit_cache")
+            .push("summary");
+
+        self.get(path).await
+    }
+
     /// `GET lighthouse/beacon/states/{state_id}/ssz`
     pub async fn get_lighthouse_beacon_states_ssz<E: EthSpec>(
         &self,
@@ -541,6 +733,33 @@ impl BeaconNodeHttpClient {
         self.get_opt::<(), _>(path).await.map(|opt| opt.is_some())
     }
 
+    /// `GET lighthouse/graffiti`
+    pub async fn get_lighthouse_graffiti(&self) -> Result<GenericResponse<GraffitiData>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("graffiti");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/graffiti`
+    pub async fn post_lighthouse_graffiti(
+        &self,
+        request: &UpdateGraffitiRequest,
+    ) -> Result<(), Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("graffiti");
+
+        self.post(path, request).await
+    }
+
     /// `GET lighthouse/database/info`
     pub async fn get_lighthouse_database_info(&self) -> Result<DatabaseInfo, Error> {
         let mut path = self.server.full.clone();
@@ -554,6 +773,31 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/slasher/stats`
+    pub async fn get_lighthouse_slasher_stats(&self) -> Result<SlasherStats, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("slasher")
+            .push("stats");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/op_pool`
+    pub async fn get_lighthouse_op_pool(&self) -> Result<OperationPoolInfo, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("op_pool");
+
+        self.get(path).await
+    }
+
     /// `POST lighthouse/database/reconstruct`
     pub async fn post_lighthouse_database_reconstruct(&self) -> Result<String, Error> {
         let mut path = self.server.full.clone();
@@ -461,6 +461,53 @@ impl ValidatorClientHttpClient {
         self.post(path, &request).await
     }
 
+    /// `GET lighthouse/ui/fallback_health`
+    pub async fn get_lighthouse_ui_fallback_health(
+        &self,
+    ) -> Result<GenericResponse<Vec<BeaconNodeFallbackHealth>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("ui")
+            .push("fallback_health");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/validators/doppelganger`
+    pub async fn get_lighthouse_validators_doppelganger(
+        &self,
+    ) -> Result<GenericResponse<Vec<ValidatorDoppelgangerData>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validators")
+            .push("doppelganger");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/validators/doppelganger/skip`
+    pub async fn post_lighthouse_validators_doppelganger_skip(
+        &self,
+        request: &SkipDoppelgangerRequest,
+    ) -> Result<(), Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validators")
+            .push("doppelganger")
+            .push("skip");
+
+        self.post(path, &request).await
+    }
+
     /// `PATCH lighthouse/validators/{validator_pubkey}`
     pub async fn patch_lighthouse_validators(
         &self,
@@ -534,6 +581,18 @@ impl ValidatorClientHttpClient {
         Ok(url)
     }
 
+    fn make_graffiti_url(&self, pubkey: &PublicKeyBytes) -> Result<Url, Error> {
+        let mut url = self.server.full.clone();
+        url.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("eth")
+            .push("v1")
+            .push("validator")
+            .push(&pubkey.to_string())
+            .push("graffiti");
+        Ok(url)
+    }
+
     /// `GET lighthouse/auth`
     pub async fn get_auth(&self) -> Result<AuthResponse, Error> {
         let mut url = self.server.full.clone();
@@ -646,11 +705,39 @@ impl ValidatorClientHttpClient {
         self.delete_with_raw_response(url, &()).await
     }
 
+    /// `GET /eth/v1/validator/{pubkey}/graffiti`
+    pub async fn get_graffiti(
+        &self,
+        pubkey: &PublicKeyBytes,
+    ) -> Result<GetGraffitiResponse, Error> {
+        let url = self.make_graffiti_url(pubkey)?;
+        self.get(url)
+            .await
+            .map(|generic: GenericResponse<GetGraffitiResponse>| generic.data)
+    }
+
+    /// `POST /eth/v1/validator/{pubkey}/graffiti`
+    pub async fn post_graffiti(
+        &self,
+        pubkey: &PublicKeyBytes,
+        req: &UpdateGraffitiRequest,
+    ) -> Result<Response, Error> {
+        let url = self.make_graffiti_url(pubkey)?;
+        self.post_with_raw_response(url, req).await
+    }
+
+    /// `DELETE /eth/v1/validator/{pubkey}/graffiti`
+    pub async fn delete_graffiti(&self, pubkey: &PublicKeyBytes) -> Result<Response, Error> {
+        let url = self.make_graffiti_url(pubkey)?;
+        self.delete_with_raw_response(url, &()).await
+    }
+
     /// `POST /eth/v1/validator/{pubkey}/voluntary_exit`
     pub async fn post_validator_voluntary_exit(
         &self,
         pubkey: &PublicKeyBytes,
         epoch: Option<Epoch>,
+        broadcast: bool,
     ) -> Result<SignedVoluntaryExit, Error> {
         let mut path = self.server.full.clone();
 
@@ -667,6 +754,10 @@ impl ValidatorClientHttpClient {
                 .append_pair("epoch", &epoch.to_string());
         }
 
+        if broadcast {
+            path.query_pairs_mut().append_pair("broadcast", "true");
+        }
+
         self.post(path, &()).await
     }
 }
@@ -2,7 +2,7 @@ use account_utils::ZeroizeString;
 use eth2_keystore::Keystore;
 use serde::{Deserialize, Serialize};
 use slashing_protection::interchange::Interchange;
-use types::{Address, PublicKeyBytes};
+use types::{Address, Graffiti, PublicKeyBytes};
 
 #[derive(Debug, Deserialize, Serialize, PartialEq)]
 pub struct GetFeeRecipientResponse {
@@ -17,6 +17,13 @@ pub struct GetGasLimitResponse {
     pub gas_limit: u64,
 }
 
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct GetGraffitiResponse {
+    pub pubkey: PublicKeyBytes,
+    /// The raw graffiti bytes, which may not be valid UTF-8, serialized as hex.
+    pub graffiti: Graffiti,
+}
+
 #[derive(Debug, Deserialize, Serialize, PartialEq)]
 pub struct AuthResponse {
     pub token_path: String,
@@ -135,6 +135,8 @@ pub struct Web3SignerValidatorRequest {
     pub client_identity_path: Option<PathBuf>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub client_identity_password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certificate_fingerprint: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -148,7 +150,56 @@ pub struct UpdateGasLimitRequest {
     pub gas_limit: u64,
 }
 
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct UpdateGraffitiRequest {
+    pub graffiti: GraffitiString,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorDoppelgangerData {
+    pub pubkey: PublicKeyBytes,
+    /// `true` if doppelganger protection permits this validator to sign slashable messages.
+    pub signing_enabled: bool,
+    /// The number of epochs which must still be checked before signing is permitted, or `None` if
+    /// the validator is unknown to doppelganger protection.
+    pub remaining_epochs: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkipDoppelgangerRequest {
+    pub pubkeys: Vec<PublicKeyBytes>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BeaconNodeStatus {
+    Synced,
+    NotSynced,
+    Offline,
+    Incompatible,
+    Uninitialized,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeaconNodeFallbackHealth {
+    /// The position of the beacon node in `--beacon-nodes`.
+    pub index: usize,
+    pub endpoint: String,
+    pub status: BeaconNodeStatus,
+    /// The position in which the beacon node is currently tried, after latency is considered.
+    pub fallback_position: usize,
+    pub last_latency_ms: Option<u64>,
+    pub average_latency_ms: Option<u64>,
+    pub min_latency_ms: Option<u64>,
+    pub max_latency_ms: Option<u64>,
+    pub successful_measurements: u64,
+    pub failed_measurements: u64,
+}
+
 #[derive(Deserialize)]
 pub struct VoluntaryExitQuery {
     pub epoch: Option<Epoch>,
+    /// Publish the signed exit to the beacon node(s) in addition to returning it.
+    #[serde(default)]
+    pub broadcast: bool,
 }
@@ -2,7 +2,9 @@
 //! required for the HTTP API.
 
 use crate::Error as ServerError;
-use lighthouse_network::{ConnectionDirection, Enr, Multiaddr, PeerConnectionStatus};
+use lighthouse_network::{
+    types::SyncState, ConnectionDirection, Enr, Multiaddr, PeerConnectionStatus,
+};
 use mediatype::{names, MediaType, MediaTypeList};
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
@@ -647,6 +649,12 @@ pub struct ValidatorBalancesQuery {
     pub id: Option<Vec<ValidatorId>>,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ValidatorBalancesRequestBody {
+    pub ids: Vec<ValidatorId>,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct ValidatorIndexData(#[serde(with = "serde_utils::quoted_u64_vec")] pub Vec<u64>);
@@ -674,6 +682,16 @@ pub struct AttesterData {
     pub slot: Slot,
 }
 
+/// A validator's selection proof for an attestation duty, as exchanged with distributed validator
+/// middleware via `POST validator/beacon_committee_selections`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeaconCommitteeSelection {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    pub slot: Slot,
+    pub selection_proof: Signature,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProposerData {
     pub pubkey: PublicKeyBytes,
@@ -687,6 +705,7 @@ pub struct ValidatorBlocksQuery {
     pub randao_reveal: SignatureBytes,
     pub graffiti: Option<Graffiti>,
     pub skip_randao_verification: SkipRandaoVerification,
+    pub builder_boost_factor: Option<u64>,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
@@ -712,6 +731,78 @@ impl TryFrom<Option<String>> for SkipRandaoVerification {
     }
 }
 
+/// The body of a `GET v3/validator/blocks/{slot}` response.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProduceBlockV3Response<B> {
+    pub version: ForkName,
+    pub execution_payload_blinded: bool,
+    #[serde(with = "serde_utils::quoted_u256")]
+    pub execution_payload_value: Uint256,
+    pub data: B,
+}
+
+pub type FullBlockV3Response<T> = ProduceBlockV3Response<BeaconBlock<T, FullPayload<T>>>;
+pub type BlindedBlockV3Response<T> = ProduceBlockV3Response<BeaconBlock<T, BlindedPayload<T>>>;
+
+/// A block returned by `GET v3/validator/blocks/{slot}`.
+///
+/// The beacon node decides whether to return a full block (local payload) or a blinded block
+/// (builder payload), which is indicated by the `execution_payload_blinded` field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProducedBlockV3<T: EthSpec> {
+    Full(FullBlockV3Response<T>),
+    Blinded(BlindedBlockV3Response<T>),
+}
+
+impl<T: EthSpec> ProducedBlockV3<T> {
+    pub fn version(&self) -> ForkName {
+        match self {
+            Self::Full(response) => response.version,
+            Self::Blinded(response) => response.version,
+        }
+    }
+
+    pub fn execution_payload_value(&self) -> Uint256 {
+        match self {
+            Self::Full(response) => response.execution_payload_value,
+            Self::Blinded(response) => response.execution_payload_value,
+        }
+    }
+}
+
+impl<'de, T: EthSpec> Deserialize<'de> for ProducedBlockV3<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Helper {
+            version: ForkName,
+            execution_payload_blinded: bool,
+            #[serde(with = "serde_utils::quoted_u256")]
+            execution_payload_value: Uint256,
+            data: serde_json::Value,
+        }
+
+        let helper = Helper::deserialize(deserializer)?;
+        if helper.execution_payload_blinded {
+            Ok(Self::Blinded(ProduceBlockV3Response {
+                version: helper.version,
+                execution_payload_blinded: true,
+                execution_payload_value: helper.execution_payload_value,
+                data: BeaconBlock::deserialize_by_fork::<D>(helper.data, helper.version)?,
+            }))
+        } else {
+            Ok(Self::Full(ProduceBlockV3Response {
+                version: helper.version,
+                execution_payload_blinded: false,
+                execution_payload_value: helper.execution_payload_value,
+                data: BeaconBlock::deserialize_by_fork::<D>(helper.data, helper.version)?,
+            }))
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct ValidatorAttestationDataQuery {
     pub slot: Slot,
@@ -917,6 +1008,32 @@ pub struct SseLateHead {
     pub execution_optimistic: bool,
 }
 
+/// Emitted whenever the sync manager transitions between sync states.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct SseSyncState {
+    pub old_state: SyncState,
+    pub new_state: SyncState,
+    pub is_syncing: bool,
+    pub head_slot: Slot,
+}
+
+/// Emitted when a validator enrolled in the validator monitor fails to propose a block.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct SseMissedBlock {
+    pub slot: Slot,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+}
+
+/// Emitted when a validator enrolled in the validator monitor has no attestation for `epoch`
+/// included on chain within the inclusion window.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct SseMissedAttestation {
+    pub epoch: Epoch,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+}
+
 #[superstruct(
     variants(V1, V2),
     variant_attributes(derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize))
@@ -1004,6 +1121,13 @@ pub enum EventKind<T: EthSpec> {
     #[cfg(feature = "lighthouse")]
     BlockReward(BlockReward),
     PayloadAttributes(VersionedSsePayloadAttributes),
+    SyncState(SseSyncState),
+    MissedBlock(SseMissedBlock),
+    MissedAttestation(SseMissedAttestation),
+    #[cfg(feature = "lighthouse")]
+    SlasherAttestation(Box<IndexedAttestation<T>>),
+    #[cfg(feature = "lighthouse")]
+    SlasherBlockHeader(SignedBeaconBlockHeader),
 }
 
 impl<T: EthSpec> EventKind<T> {
@@ -1018,8 +1142,15 @@ impl<T: EthSpec> EventKind<T> {
             EventKind::ContributionAndProof(_) => "contribution_and_proof",
             EventKind::PayloadAttributes(_) => "payload_attributes",
             EventKind::LateHead(_) => "late_head",
+            EventKind::SyncState(_) => "sync_state",
+            EventKind::MissedBlock(_) => "missed_block",
+            EventKind::MissedAttestation(_) => "missed_attestation",
             #[cfg(feature = "lighthouse")]
             EventKind::BlockReward(_) => "block_reward",
+            #[cfg(feature = "lighthouse")]
+            EventKind::SlasherAttestation(_) => "slasher_attestation",
+            #[cfg(feature = "lighthouse")]
+            EventKind::SlasherBlockHeader(_) => "slasher_block_header",
         }
     }
 
@@ -1077,10 +1208,33 @@ impl<T: EthSpec> EventKind<T> {
                     ServerError::InvalidServerSentEvent(format!("Payload Attributes: {:?}", e))
                 })?,
             )),
+            "sync_state" => Ok(EventKind::SyncState(serde_json::from_str(data).map_err(
+                |e| ServerError::InvalidServerSentEvent(format!("Sync State: {:?}", e)),
+            )?)),
+            "missed_block" => Ok(EventKind::MissedBlock(serde_json::from_str(data).map_err(
+                |e| ServerError::InvalidServerSentEvent(format!("Missed Block: {:?}", e)),
+            )?)),
+            "missed_attestation" => Ok(EventKind::MissedAttestation(
+                serde_json::from_str(data).map_err(|e| {
+                    ServerError::InvalidServerSentEvent(format!("Missed Attestation: {:?}", e))
+                })?,
+            )),
             #[cfg(feature = "lighthouse")]
             "block_reward" => Ok(EventKind::BlockReward(serde_json::from_str(data).map_err(
                 |e| ServerError::InvalidServerSentEvent(format!("Block Reward: {:?}", e)),
             )?)),
+            #[cfg(feature = "lighthouse")]
+            "slasher_attestation" => Ok(EventKind::SlasherAttestation(Box::new(
+                serde_json::from_str(data).map_err(|e| {
+                    ServerError::InvalidServerSentEvent(format!("Slasher Attestation: {:?}", e))
+                })?,
+            ))),
+            #[cfg(feature = "lighthouse")]
+            "slasher_block_header" => Ok(EventKind::SlasherBlockHeader(
+                serde_json::from_str(data).map_err(|e| {
+                    ServerError::InvalidServerSentEvent(format!("Slasher Block Header: {:?}", e))
+                })?,
+            )),
             _ => Err(ServerError::InvalidServerSentEvent(
                 "Could not parse event tag".to_string(),
             )),
@@ -1107,8 +1261,15 @@ pub enum EventTopic {
     ContributionAndProof,
     LateHead,
     PayloadAttributes,
+    SyncState,
+    MissedBlock,
+    MissedAttestation,
     #[cfg(feature = "lighthouse")]
     BlockReward,
+    #[cfg(feature = "lighthouse")]
+    SlasherAttestation,
+    #[cfg(feature = "lighthouse")]
+    SlasherBlockHeader,
 }
 
 impl FromStr for EventTopic {
@@ -1125,8 +1286,15 @@ impl FromStr for EventTopic {
             "contribution_and_proof" => Ok(EventTopic::ContributionAndProof),
             "payload_attributes" => Ok(EventTopic::PayloadAttributes),
             "late_head" => Ok(EventTopic::LateHead),
+            "sync_state" => Ok(EventTopic::SyncState),
+            "missed_block" => Ok(EventTopic::MissedBlock),
+            "missed_attestation" => Ok(EventTopic::MissedAttestation),
             #[cfg(feature = "lighthouse")]
             "block_reward" => Ok(EventTopic::BlockReward),
+            #[cfg(feature = "lighthouse")]
+            "slasher_attestation" => Ok(EventTopic::SlasherAttestation),
+            #[cfg(feature = "lighthouse")]
+            "slasher_block_header" => Ok(EventTopic::SlasherBlockHeader),
             _ => Err("event topic cannot be parsed.".to_string()),
         }
     }
@@ -1144,8 +1312,15 @@ impl fmt::Display for EventTopic {
             EventTopic::ContributionAndProof => write!(f, "contribution_and_proof"),
             EventTopic::PayloadAttributes => write!(f, "payload_attributes"),
             EventTopic::LateHead => write!(f, "late_head"),
+            EventTopic::SyncState => write!(f, "sync_state"),
+            EventTopic::MissedBlock => write!(f, "missed_block"),
+            EventTopic::MissedAttestation => write!(f, "missed_attestation"),
             #[cfg(feature = "lighthouse")]
             EventTopic::BlockReward => write!(f, "block_reward"),
+            #[cfg(feature = "lighthouse")]
+            EventTopic::SlasherAttestation => write!(f, "slasher_attestation"),
+            #[cfg(feature = "lighthouse")]
+            EventTopic::SlasherBlockHeader => write!(f, "slasher_block_header"),
         }
     }
 }
@@ -16,7 +16,8 @@ use eth2_config::{instantiate_hardcoded_nets, HardcodedNet};
 use std::fs::{create_dir_all, File};
 use std::io::{Read, Write};
 use std::path::PathBuf;
-use types::{BeaconState, ChainSpec, Config, EthSpec, EthSpecId};
+use std::time::Duration;
+use types::{BeaconState, ChainSpec, Config, Epoch, EthSpec, EthSpecId};
 
 pub const DEPLOY_BLOCK_FILE: &str = "deploy_block.txt";
 pub const BOOT_ENR_FILE: &str = "boot_enr.yaml";
@@ -77,7 +78,7 @@ impl Eth2NetworkConfig {
     pub fn eth_spec_id(&self) -> Result<EthSpecId, String> {
         self.config
             .eth_spec_id()
-            .ok_or_else(|| "Config does not match any known preset".to_string())
+            .ok_or_else(|| unknown_preset_message(&self.config))
     }
 
     /// Returns `true` if this configuration contains a `BeaconState`.
@@ -89,7 +90,9 @@ impl Eth2NetworkConfig {
     pub fn chain_spec<E: EthSpec>(&self) -> Result<ChainSpec, String> {
         ChainSpec::from_config::<E>(&self.config).ok_or_else(|| {
             format!(
-                "YAML configuration incompatible with spec constants for {}",
+                "{} has PRESET_BASE {:?}, which is incompatible with the {} preset",
+                BASE_CONFIG_FILE,
+                self.config.preset_base,
                 E::spec_name()
             )
         })
@@ -103,8 +106,70 @@ impl Eth2NetworkConfig {
             .as_ref()
             .ok_or("Genesis state is unknown")?;
 
-        BeaconState::from_ssz_bytes(genesis_state_bytes, &spec)
-            .map_err(|e| format!("Genesis state SSZ bytes are invalid: {:?}", e))
+        self.decode_genesis_state(genesis_state_bytes, &spec)
+    }
+
+    /// Returns the genesis state, downloading it from `genesis_state_url` if it isn't included
+    /// in the network config.
+    ///
+    /// Returns `Ok(None)` if the genesis state is unknown and no URL is supplied.
+    pub async fn genesis_state<E: EthSpec>(
+        &self,
+        genesis_state_url: Option<&str>,
+        timeout: Duration,
+    ) -> Result<Option<BeaconState<E>>, String> {
+        if self.genesis_state_bytes.is_some() {
+            return self.beacon_state().map(Some);
+        }
+
+        if let Some(url) = genesis_state_url {
+            let spec = self.chain_spec::<E>()?;
+            let genesis_state_bytes = download_genesis_state(url, timeout).await?;
+            self.decode_genesis_state(&genesis_state_bytes, &spec)
+                .map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Decodes `genesis_state_bytes` and checks that the state matches the config.
+    fn decode_genesis_state<E: EthSpec>(
+        &self,
+        genesis_state_bytes: &[u8],
+        spec: &ChainSpec,
+    ) -> Result<BeaconState<E>, String> {
+        let genesis_state = BeaconState::from_ssz_bytes(genesis_state_bytes, spec)
+            .map_err(|e| format!("Genesis state SSZ bytes are invalid: {:?}", e))?;
+
+        // A genesis state built for another network would otherwise only be detected when no
+        // peers are found, since the fork digest would differ.
+        let genesis_fork = spec.fork_name_at_epoch(Epoch::new(0));
+        let expected_fork_version = spec.fork_version_for_name(genesis_fork);
+        let fork_version = genesis_state.fork().current_version;
+        if fork_version != expected_fork_version {
+            return Err(format!(
+                "Genesis state has fork version 0x{} but {} expects 0x{} for the {} fork at \
+                 genesis",
+                hex::encode(fork_version),
+                BASE_CONFIG_FILE,
+                hex::encode(expected_fork_version),
+                genesis_fork
+            ));
+        }
+
+        Ok(genesis_state)
+    }
+
+    /// Checks that the config is compatible with the `E` preset and, if known, that the genesis
+    /// state matches the config.
+    ///
+    /// Intended for user-supplied testnet directories, the hardcoded networks are checked by tests.
+    pub fn validate<E: EthSpec>(&self) -> Result<(), String> {
+        self.chain_spec::<E>()?;
+        if self.beacon_state_is_known() {
+            self.beacon_state::<E>()?;
+        }
+        Ok(())
     }
 
     /// Write the files to the directory.
@@ -170,11 +235,17 @@ impl Eth2NetworkConfig {
         Ok(())
     }
 
+    /// Loads a testnet directory, which must contain `config.yaml` and `deploy_block.txt` and may
+    /// contain `boot_enr.yaml` and `genesis.ssz`.
     pub fn load(base_dir: PathBuf) -> Result<Self, String> {
+        if !base_dir.is_dir() {
+            return Err(format!("{:?} is not a directory", base_dir));
+        }
+
         macro_rules! load_from_file {
             ($file: ident) => {
                 File::open(base_dir.join($file))
-                    .map_err(|e| format!("Unable to open {}: {:?}", $file, e))
+                    .map_err(|e| format!("Unable to open {} in {:?}: {:?}", $file, base_dir, e))
                     .and_then(|file| {
                         serde_yaml::from_reader(file)
                             .map_err(|e| format!("Unable to parse {}: {:?}", $file, e))
@@ -194,7 +265,11 @@ impl Eth2NetworkConfig {
 
         let deposit_contract_deploy_block = load_from_file!(DEPLOY_BLOCK_FILE);
         let boot_enr = optional_load_from_file!(BOOT_ENR_FILE);
-        let config = load_from_file!(BASE_CONFIG_FILE);
+        let config: Config = load_from_file!(BASE_CONFIG_FILE);
+
+        if config.eth_spec_id().is_none() {
+            return Err(unknown_preset_message(&config));
+        }
 
         // The genesis state is a special case because it uses SSZ, not YAML.
         let genesis_file_path = base_dir.join(GENESIS_STATE_FILE);
@@ -221,6 +296,37 @@ impl Eth2NetworkConfig {
     }
 }
 
+/// Downloads an SSZ-encoded genesis state from `url`, for networks which don't distribute a
+/// `genesis.ssz` in their testnet directory.
+async fn download_genesis_state(url: &str, timeout: Duration) -> Result<Vec<u8>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| format!("Unable to build HTTP client: {:?}", e))?;
+    let bytes = client
+        .get(url)
+        .header("Accept", "application/octet-stream")
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Unable to download genesis state from {}: {:?}", url, e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("Unable to download genesis state from {}: {:?}", url, e))?;
+
+    if bytes.is_empty() {
+        return Err(format!("Genesis state downloaded from {} is empty", url));
+    }
+    Ok(bytes.to_vec())
+}
+
+fn unknown_preset_message(config: &Config) -> String {
+    format!(
+        "Unknown PRESET_BASE {:?} in {}, expected one of mainnet, minimal or gnosis",
+        config.preset_base, BASE_CONFIG_FILE
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -339,4 +445,138 @@ mod tests {
 
         assert_eq!(testnet, decoded, "should decode as encoded");
     }
+
+    #[test]
+    fn load_rejects_unknown_preset() {
+        let temp_dir = TempBuilder::new()
+            .prefix("eth2_testnet_test")
+            .tempdir()
+            .expect("should create temp dir");
+        let base_dir = temp_dir.path().join("my_testnet");
+
+        let mut config = Config::from_chain_spec::<E>(&E::default_spec());
+        config.preset_base = "unknown".to_string();
+        let testnet = Eth2NetworkConfig {
+            deposit_contract_deploy_block: 42,
+            boot_enr: None,
+            genesis_state_bytes: None,
+            config,
+        };
+        testnet
+            .write_to_file(base_dir.clone(), false)
+            .expect("should write to file");
+
+        let error = Eth2NetworkConfig::load(base_dir).unwrap_err();
+        assert!(error.contains("PRESET_BASE"), "{}", error);
+    }
+
+    #[test]
+    fn genesis_state_fork_version_mismatch() {
+        let spec = E::default_spec();
+        let eth1_data = Eth1Data {
+            deposit_root: Hash256::zero(),
+            deposit_count: 0,
+            block_hash: Hash256::zero(),
+        };
+        let genesis_state = BeaconState::<E>::new(42, eth1_data, &spec);
+
+        let mut config = Config::from_chain_spec::<E>(&spec);
+        config.genesis_fork_version = [0x42; 4];
+        let testnet = Eth2NetworkConfig {
+            deposit_contract_deploy_block: 42,
+            boot_enr: None,
+            genesis_state_bytes: Some(genesis_state.as_ssz_bytes()),
+            config,
+        };
+
+        let error = testnet.validate::<E>().unwrap_err();
+        assert!(error.contains("fork version"), "{}", error);
+    }
+
+    /// Serves `body` in response to a single HTTP request, returning the URL to request.
+    fn serve_once(body: Vec<u8>) -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("should bind");
+        let addr = listener.local_addr().expect("should have local addr");
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("should accept");
+
+            // Read the request headers before responding.
+            let mut request = vec![];
+            let mut buf = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = stream.read(&mut buf).expect("should read request");
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            )
+            .expect("should write headers");
+            stream.write_all(&body).expect("should write body");
+        });
+        format!("http://{}/genesis.ssz", addr)
+    }
+
+    fn genesis_state_for(spec: &ChainSpec) -> BeaconState<E> {
+        let eth1_data = Eth1Data {
+            deposit_root: Hash256::zero(),
+            deposit_count: 0,
+            block_hash: Hash256::zero(),
+        };
+        BeaconState::<E>::new(42, eth1_data, spec)
+    }
+
+    #[tokio::test]
+    async fn genesis_state_from_url() {
+        let spec = E::default_spec();
+        let genesis_state = genesis_state_for(&spec);
+        let testnet = Eth2NetworkConfig {
+            deposit_contract_deploy_block: 42,
+            boot_enr: None,
+            genesis_state_bytes: None,
+            config: Config::from_chain_spec::<E>(&spec),
+        };
+        let timeout = Duration::from_secs(10);
+
+        assert!(testnet
+            .genesis_state::<E>(None, timeout)
+            .await
+            .expect("should not error without a url")
+            .is_none());
+
+        let url = serve_once(genesis_state.as_ssz_bytes());
+        let downloaded = testnet
+            .genesis_state::<E>(Some(&url), timeout)
+            .await
+            .expect("should download genesis state")
+            .expect("genesis state should be known");
+        assert_eq!(downloaded.as_ssz_bytes(), genesis_state.as_ssz_bytes());
+    }
+
+    #[tokio::test]
+    async fn genesis_state_from_url_fork_version_mismatch() {
+        let spec = E::default_spec();
+        let genesis_state = genesis_state_for(&spec);
+        let mut config = Config::from_chain_spec::<E>(&spec);
+        config.genesis_fork_version = [0x42; 4];
+        let testnet = Eth2NetworkConfig {
+            deposit_contract_deploy_block: 42,
+            boot_enr: None,
+            genesis_state_bytes: None,
+            config,
+        };
+
+        let url = serve_once(genesis_state.as_ssz_bytes());
+        let error = testnet
+            .genesis_state::<E>(Some(&url), Duration::from_secs(10))
+            .await
+            .unwrap_err();
+        assert!(error.contains("fork version"), "{}", error);
+    }
 }
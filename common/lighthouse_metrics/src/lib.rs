@@ -54,7 +54,10 @@
 //! }
 //! ```
 
+use lazy_static::lazy_static;
 use prometheus::{Error, HistogramOpts, Opts};
+use std::collections::HashMap;
+use std::sync::RwLock;
 use std::time::Duration;
 
 use prometheus::core::{Atomic, GenericGauge, GenericGaugeVec};
@@ -65,6 +68,77 @@ pub use prometheus::{
     IntGauge, IntGaugeVec, Result, TextEncoder, DEFAULT_BUCKETS,
 };
 
+/// Buckets which replace those supplied when a histogram is created.
+struct HistogramBucketOverride {
+    buckets: Vec<f64>,
+    /// Set once a histogram named after this override has been created.
+    applied: bool,
+}
+
+lazy_static! {
+    /// Histogram bucket overrides, keyed by metric name.
+    static ref HISTOGRAM_BUCKET_OVERRIDES: RwLock<HashMap<String, HistogramBucketOverride>> =
+        RwLock::new(HashMap::new());
+}
+
+/// Overrides the buckets of the histogram (or histogram vec) named `name`.
+///
+/// Histograms are created lazily, so this has no effect on a histogram which has already been
+/// used. It should be called during start-up, before any metrics are recorded. An error is
+/// returned if a metric named `name` has already been created.
+///
+/// Since histograms are created lazily it's not possible to check that `name` is a real
+/// histogram up-front, see `unapplied_histogram_buckets`.
+pub fn set_histogram_buckets(name: &str, buckets: Vec<f64>) -> std::result::Result<(), String> {
+    if gather().iter().any(|family| family.get_name() == name) {
+        return Err(format!(
+            "Unable to override the buckets of {}, it has already been created",
+            name
+        ));
+    }
+
+    HISTOGRAM_BUCKET_OVERRIDES
+        .write()
+        .map_err(|_| "Histogram bucket overrides lock poisoned".to_string())?
+        .insert(
+            name.to_string(),
+            HistogramBucketOverride {
+                buckets,
+                applied: false,
+            },
+        );
+    Ok(())
+}
+
+/// Returns the names of the overrides from `set_histogram_buckets` which have not (yet) been
+/// applied to a histogram, which likely means the name is wrong.
+pub fn unapplied_histogram_buckets() -> Vec<String> {
+    HISTOGRAM_BUCKET_OVERRIDES
+        .read()
+        .map(|overrides| {
+            overrides
+                .iter()
+                .filter(|(_, bucket_override)| !bucket_override.applied)
+                .map(|(name, _)| name.clone())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns `buckets`, unless they have been overridden for `name` with `set_histogram_buckets`.
+fn histogram_buckets(name: &str, buckets: Result<Vec<f64>>) -> Result<Vec<f64>> {
+    HISTOGRAM_BUCKET_OVERRIDES
+        .write()
+        .ok()
+        .and_then(|mut overrides| {
+            overrides.get_mut(name).map(|bucket_override| {
+                bucket_override.applied = true;
+                bucket_override.buckets.clone()
+            })
+        })
+        .map_or(buckets, Ok)
+}
+
 /// Collect all the metrics for reporting.
 pub fn gather() -> Vec<prometheus::proto::MetricFamily> {
     prometheus::gather()
@@ -110,7 +184,7 @@ pub fn try_create_histogram_with_buckets(
     help: &str,
     buckets: Result<Vec<f64>>,
 ) -> Result<Histogram> {
-    let opts = HistogramOpts::new(name, help).buckets(buckets?);
+    let opts = HistogramOpts::new(name, help).buckets(histogram_buckets(name, buckets)?);
     let histogram = Histogram::with_opts(opts)?;
     prometheus::register(Box::new(histogram.clone()))?;
     Ok(histogram)
@@ -134,7 +208,7 @@ pub fn try_create_histogram_vec_with_buckets(
     buckets: Result<Vec<f64>>,
     label_names: &[&str],
 ) -> Result<HistogramVec> {
-    let opts = HistogramOpts::new(name, help).buckets(buckets?);
+    let opts = HistogramOpts::new(name, help).buckets(histogram_buckets(name, buckets)?);
     let histogram_vec = HistogramVec::new(opts, label_names)?;
     prometheus::register(Box::new(histogram_vec.clone()))?;
     Ok(histogram_vec)
@@ -13,7 +13,9 @@ pub const MAX_MESSAGE_WIDTH: usize = 40;
 
 pub mod async_record;
 mod sse_logging_components;
+mod time_rotation;
 pub use sse_logging_components::SSELoggingComponents;
+pub use time_rotation::{TimeRotatingDrain, TimeRotationConfig};
 
 /// The minimum interval between log messages indicating that a queue is full.
 const LOG_DEBOUNCE_INTERVAL: Duration = Duration::from_secs(30);
@@ -0,0 +1,284 @@
+//! Provides a drain which rotates a log file at a fixed interval, in addition to the size-based
+//! rotation performed by the `sloggers` file logger it wraps.
+//!
+//! Rotated files are renamed to `<path>.<timestamp>` (and compressed to `<path>.<timestamp>.gz`
+//! if compression is enabled). Only the newest `keep` of these files are retained.
+
+use flate2::{write::GzEncoder, Compression};
+use slog::{o, Discard, Drain, Logger, Never, OwnedKVList, Record};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The format of the timestamp appended to rotated log files. Sorts chronologically.
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+const COMPRESSED_EXTENSION: &str = "gz";
+
+/// Configuration for a `TimeRotatingDrain`.
+#[derive(Debug, Clone)]
+pub struct TimeRotationConfig {
+    /// Path of the active log file.
+    pub path: PathBuf,
+    /// The interval between rotations.
+    pub interval: Duration,
+    /// The maximum number of rotated files to retain.
+    pub keep: usize,
+    /// If `true`, compress rotated files with gzip.
+    pub compress: bool,
+    /// If `true`, compressed files are only readable by the current user.
+    pub restrict_permissions: bool,
+}
+
+struct State {
+    logger: Logger,
+    next_rotation: Instant,
+}
+
+/// A drain which forwards all records to a file logger, rebuilding it with `build_logger` each
+/// time the log file is rotated.
+pub struct TimeRotatingDrain<F> {
+    config: TimeRotationConfig,
+    build_logger: F,
+    state: Mutex<State>,
+}
+
+impl<F> TimeRotatingDrain<F>
+where
+    F: Fn() -> Result<Logger, String>,
+{
+    pub fn new(config: TimeRotationConfig, build_logger: F) -> Result<Self, String> {
+        if config.interval.is_zero() {
+            return Err("Log rotation interval must be greater than zero".to_string());
+        }
+
+        let state = State {
+            logger: build_logger()?,
+            next_rotation: Instant::now() + config.interval,
+        };
+
+        Ok(Self {
+            config,
+            build_logger,
+            state: Mutex::new(state),
+        })
+    }
+
+    /// Moves the active log file aside and starts a new one.
+    fn rotate(&self, state: &mut State) -> Result<(), String> {
+        // Drop the current logger first, so that its background thread flushes any pending records
+        // to the file before it is renamed.
+        state.logger = Logger::root(Discard, o!());
+
+        let timestamp = chrono::Local::now().format(TIMESTAMP_FORMAT);
+        let rotated_path = append_extension(&self.config.path, &timestamp.to_string());
+        let rename_result = fs::rename(&self.config.path, &rotated_path);
+
+        // Always try to resume logging, even if the file could not be moved aside.
+        state.logger = (self.build_logger)()?;
+        rename_result.map_err(|e| {
+            format!(
+                "Unable to rename {:?} to {:?}: {:?}",
+                self.config.path, rotated_path, e
+            )
+        })?;
+
+        // Compression can take a while for large files, so don't block the thread that is
+        // logging.
+        let config = self.config.clone();
+        std::thread::Builder::new()
+            .name("log_rotation".to_string())
+            .spawn(move || {
+                if config.compress {
+                    if let Err(e) = compress(&rotated_path, config.restrict_permissions) {
+                        eprintln!("Unable to compress {:?}: {:?}", rotated_path, e);
+                    }
+                }
+                if let Err(e) = prune(&config.path, config.keep) {
+                    eprintln!("Unable to prune rotated log files: {:?}", e);
+                }
+            })
+            .map_err(|e| format!("Unable to spawn log rotation thread: {:?}", e))?;
+
+        Ok(())
+    }
+}
+
+impl<F> Drain for TimeRotatingDrain<F>
+where
+    F: Fn() -> Result<Logger, String>,
+{
+    type Ok = ();
+    type Err = Never;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> Result<(), Never> {
+        // A panic while holding the lock cannot leave the state inconsistent, so ignore poisoning.
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let now = Instant::now();
+        if now >= state.next_rotation {
+            state.next_rotation = now + self.config.interval;
+            if let Err(e) = self.rotate(&mut state) {
+                eprintln!("Unable to rotate log file: {}", e);
+            }
+        }
+
+        state.logger.log(record, values)
+    }
+}
+
+fn append_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(extension);
+    path.into()
+}
+
+/// Replaces the file at `path` with a gzip-compressed copy.
+fn compress(path: &Path, restrict_permissions: bool) -> io::Result<()> {
+    let compressed_path = append_extension(path, COMPRESSED_EXTENSION);
+
+    let mut input = File::open(path)?;
+    let output = File::create(&compressed_path)?;
+    if restrict_permissions {
+        filesystem::restrict_file_permissions(&compressed_path)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("{:?}", e)))?;
+    }
+
+    let mut encoder = GzEncoder::new(output, Compression::default());
+    io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?;
+
+    fs::remove_file(path)
+}
+
+/// Deletes all but the newest `keep` files which were rotated from `path`.
+fn prune(path: &Path, keep: usize) -> io::Result<()> {
+    let (dir, file_name) = match (path.parent(), path.file_name().and_then(|n| n.to_str())) {
+        (Some(dir), Some(file_name)) => (dir, file_name),
+        _ => return Ok(()),
+    };
+    let prefix = format!("{}.", file_name);
+
+    let mut rotated = vec![];
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let timestamp = name
+            .to_str()
+            .and_then(|name| name.strip_prefix(&prefix))
+            .map(|suffix| {
+                suffix
+                    .strip_suffix(&format!(".{}", COMPRESSED_EXTENSION))
+                    .unwrap_or(suffix)
+            })
+            .filter(|timestamp| {
+                chrono::NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).is_ok()
+            })
+            .map(String::from);
+
+        if let Some(timestamp) = timestamp {
+            rotated.push((timestamp, entry.path()));
+        }
+    }
+
+    // Newest first.
+    rotated.sort_by(|a, b| b.0.cmp(&a.0));
+    for (_, path) in rotated.into_iter().skip(keep) {
+        fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slog::info;
+    use std::io::Read;
+
+    fn write_file(path: &Path) {
+        fs::write(path, b"log").unwrap();
+    }
+
+    #[test]
+    fn prune_keeps_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("beacon.log");
+
+        write_file(&path);
+        write_file(&dir.path().join("beacon.log.1"));
+        write_file(&dir.path().join("beacon.log.20230101-000000.gz"));
+        write_file(&dir.path().join("beacon.log.20230102-000000"));
+        write_file(&dir.path().join("beacon.log.20230103-000000.gz"));
+
+        prune(&path, 2).unwrap();
+
+        let mut remaining = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        remaining.sort();
+        assert_eq!(
+            remaining,
+            vec![
+                "beacon.log",
+                "beacon.log.1",
+                "beacon.log.20230102-000000",
+                "beacon.log.20230103-000000.gz",
+            ]
+        );
+    }
+
+    #[test]
+    fn compress_replaces_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("beacon.log.20230101-000000");
+        write_file(&path);
+
+        compress(&path, true).unwrap();
+
+        assert!(!path.exists());
+        let mut decoder = flate2::read::GzDecoder::new(
+            File::open(append_extension(&path, COMPRESSED_EXTENSION)).unwrap(),
+        );
+        let mut contents = String::new();
+        decoder.read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, "log");
+    }
+
+    #[test]
+    fn rotates_after_interval() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("beacon.log");
+        let config = TimeRotationConfig {
+            path: path.clone(),
+            interval: Duration::from_millis(1),
+            keep: 5,
+            compress: false,
+            restrict_permissions: true,
+        };
+        let build_path = path.clone();
+        let drain = TimeRotatingDrain::new(config, move || {
+            File::create(&build_path).map_err(|e| format!("{:?}", e))?;
+            Ok(Logger::root(Discard, o!()))
+        })
+        .unwrap();
+        let log = Logger::root(drain.fuse(), o!());
+
+        std::thread::sleep(Duration::from_millis(5));
+        info!(log, "hi");
+
+        let num_rotated = fs::read_dir(dir.path())
+            .unwrap()
+            .filter(|entry| entry.as_ref().unwrap().path() != path)
+            .count();
+        assert_eq!(num_rotated, 1);
+        assert!(path.exists());
+    }
+}
@@ -11,7 +11,8 @@ use state_processing::per_epoch_processing::{
     weigh_justification_and_finalization, JustificationAndFinalizationState,
 };
 use state_processing::{
-    per_block_processing::errors::AttesterSlashingValidationError, per_epoch_processing,
+    per_block_processing::errors::AttesterSlashingValidationError,
+    per_block_processing::is_merge_transition_complete, per_epoch_processing,
 };
 use std::cmp::Ordering;
 use std::collections::BTreeSet;
@@ -378,8 +379,19 @@ where
             |_| ExecutionStatus::irrelevant(),
             |execution_payload| {
                 if execution_payload.is_default_with_empty_roots() {
-                    // A default payload does not have execution enabled.
-                    ExecutionStatus::irrelevant()
+                    // A genesis block always has a default payload, even when the genesis state
+                    // contains a non-default execution payload header (i.e. the merge happened at
+                    // genesis). In that case, trust the header in the same way as the anchor.
+                    match anchor_state.latest_execution_payload_header() {
+                        Ok(header)
+                            if anchor_block.slot() == spec.genesis_slot
+                                && is_merge_transition_complete(anchor_state) =>
+                        {
+                            ExecutionStatus::Valid(header.block_hash())
+                        }
+                        // A default payload does not have execution enabled.
+                        _ => ExecutionStatus::irrelevant(),
+                    }
                 } else {
                     // Assume that this payload is valid, since the anchor should be a trusted block and
                     // state.
@@ -13,6 +13,7 @@ use serde_derive::{Deserialize, Serialize};
 use ssz::{ssz_encode, Decode, DecodeError, Encode};
 use ssz_derive::{Decode, Encode};
 use ssz_types::{typenum::Unsigned, BitVector, FixedVector};
+use std::collections::HashMap;
 use std::convert::TryInto;
 use std::hash::Hash;
 use std::{fmt, mem, sync::Arc};
@@ -929,16 +930,24 @@ impl<T: EthSpec> BeaconState<T> {
     ) -> Result<Vec<Option<SyncDuty>>, Error> {
         let sync_committee = self.get_built_sync_committee(epoch, spec)?;
 
+        // Index the committee by pubkey once, rather than scanning it for every requested
+        // validator. This keeps requests for very large numbers of validators cheap.
+        let mut committee_positions: HashMap<&PublicKeyBytes, Vec<u64>> = HashMap::new();
+        for (position, pubkey) in sync_committee.pubkeys.iter().enumerate() {
+            committee_positions
+                .entry(pubkey)
+                .or_default()
+                .push(position as u64);
+        }
+
         validator_indices
             .iter()
             .map(|&validator_index| {
                 let pubkey = self.get_validator(validator_index as usize)?.pubkey;
 
-                Ok(SyncDuty::from_sync_committee(
-                    validator_index,
-                    pubkey,
-                    sync_committee,
-                ))
+                Ok(committee_positions.get(&pubkey).and_then(|positions| {
+                    SyncDuty::new(validator_index, pubkey, positions.clone())
+                }))
             })
             .collect()
     }
@@ -1042,6 +1042,22 @@ impl Config {
         }
     }
 
+    /// Sets the epoch at which `fork_name` activates.
+    ///
+    /// Returns an error for the genesis fork, which always activates at epoch 0.
+    pub fn set_fork_epoch(&mut self, fork_name: ForkName, epoch: Epoch) -> Result<(), String> {
+        let fork_epoch = match fork_name {
+            ForkName::Base => {
+                return Err(format!("the {} fork epoch cannot be changed", fork_name))
+            }
+            ForkName::Altair => &mut self.altair_fork_epoch,
+            ForkName::Merge => &mut self.bellatrix_fork_epoch,
+            ForkName::Capella => &mut self.capella_fork_epoch,
+        };
+        *fork_epoch = Some(MaybeQuoted { value: epoch });
+        Ok(())
+    }
+
     pub fn from_chain_spec<T: EthSpec>(spec: &ChainSpec) -> Self {
         Self {
             config_name: spec.config_name.clone(),
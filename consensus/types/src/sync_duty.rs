@@ -58,7 +58,7 @@ impl SyncDuty {
     }
 
     /// Create a duty if the `validator_sync_committee_indices` is non-empty.
-    fn new(
+    pub fn new(
         validator_index: u64,
         pubkey: PublicKeyBytes,
         validator_sync_committee_indices: Vec<u64>,
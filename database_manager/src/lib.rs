@@ -5,9 +5,10 @@ use beacon_chain::{
 use beacon_node::{get_data_dir, get_slots_per_restore_point, ClientConfig};
 use clap::{App, Arg, ArgMatches};
 use environment::{Environment, RuntimeContext};
+use slasher::{DatabaseBackend, Slasher};
 use slog::{info, Logger};
-use std::fs;
-use std::io::Write;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Write};
 use std::path::PathBuf;
 use store::{
     errors::Error,
@@ -75,6 +76,83 @@ pub fn prune_payloads_app<'a, 'b>() -> App<'a, 'b> {
         .about("Prune finalized execution payloads")
 }
 
+pub fn slasher_cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new("slasher")
+        .setting(clap::AppSettings::ColoredHelp)
+        .about(
+            "Export or import the slasher database in a portable format, e.g. to move it between \
+            database backends or machines",
+        )
+        .arg(
+            Arg::with_name("slasher-dir")
+                .long("slasher-dir")
+                .value_name("PATH")
+                .help(
+                    "Set the slasher's database directory. Defaults to `slasher_db` inside the \
+                    beacon node's data directory.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slasher-backend")
+                .long("slasher-backend")
+                .value_name("DATABASE")
+                .help("Set the database backend used by the slasher")
+                .takes_value(true)
+                .possible_values(DatabaseBackend::VARIANTS),
+        )
+        .arg(
+            Arg::with_name("slasher-history-length")
+                .long("slasher-history-length")
+                .value_name("EPOCHS")
+                .help("The slasher's history length. Must match the value used by the beacon node.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slasher-chunk-size")
+                .long("slasher-chunk-size")
+                .value_name("EPOCHS")
+                .help("The slasher's chunk size. Must match the value used by the beacon node.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slasher-validator-chunk-size")
+                .long("slasher-validator-chunk-size")
+                .value_name("NUM_VALIDATORS")
+                .help(
+                    "The slasher's validator chunk size. Must match the value used by the beacon \
+                    node.",
+                )
+                .takes_value(true),
+        )
+        .subcommand(
+            App::new("export")
+                .setting(clap::AppSettings::ColoredHelp)
+                .about("Export the slasher database to a file")
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .value_name("FILE")
+                        .help("Path of the file to write the export to")
+                        .takes_value(true)
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            App::new("import")
+                .setting(clap::AppSettings::ColoredHelp)
+                .about("Import a slasher database export into an empty slasher database")
+                .arg(
+                    Arg::with_name("input")
+                        .long("input")
+                        .value_name("FILE")
+                        .help("Path of a file created by `lighthouse db slasher export`")
+                        .takes_value(true)
+                        .required(true),
+                ),
+        )
+}
+
 pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
     App::new(CMD)
         .visible_aliases(&["db"])
@@ -102,6 +180,7 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         .subcommand(version_cli_app())
         .subcommand(inspect_cli_app())
         .subcommand(prune_payloads_app())
+        .subcommand(slasher_cli_app())
 }
 
 fn parse_client_config<E: EthSpec>(
@@ -334,6 +413,98 @@ pub fn prune_payloads<E: EthSpec>(
     db.try_prune_execution_payloads(force)
 }
 
+fn parse_slasher_config(
+    cli_args: &ArgMatches,
+    client_config: &ClientConfig,
+) -> Result<slasher::Config, String> {
+    let database_path = clap_utils::parse_optional(cli_args, "slasher-dir")?
+        .unwrap_or_else(|| client_config.data_dir().join("slasher_db"));
+    let mut slasher_config = slasher::Config::new(database_path);
+
+    if let Some(backend) = clap_utils::parse_optional(cli_args, "slasher-backend")? {
+        slasher_config.backend = backend;
+    }
+
+    if let Some(history_length) = clap_utils::parse_optional(cli_args, "slasher-history-length")? {
+        slasher_config.history_length = history_length;
+    }
+
+    if let Some(chunk_size) = clap_utils::parse_optional(cli_args, "slasher-chunk-size")? {
+        slasher_config.chunk_size = chunk_size;
+    }
+
+    if let Some(validator_chunk_size) =
+        clap_utils::parse_optional(cli_args, "slasher-validator-chunk-size")?
+    {
+        slasher_config.validator_chunk_size = validator_chunk_size;
+    }
+
+    Ok(slasher_config)
+}
+
+pub fn export_slasher_db<E: EthSpec>(
+    mut slasher_config: slasher::Config,
+    output: PathBuf,
+    log: Logger,
+) -> Result<(), String> {
+    if !slasher_config.database_path.exists() {
+        return Err(format!(
+            "No slasher database found at {}",
+            slasher_config.database_path.display()
+        ));
+    }
+
+    // Use the backend of an existing database, as the beacon node would.
+    slasher_config.override_backend();
+
+    let slasher = Slasher::<E>::open(slasher_config, log.clone())
+        .map_err(|e| format!("Unable to open slasher database: {:?}", e))?;
+
+    let file = File::create(&output)
+        .map_err(|e| format!("Unable to create {}: {:?}", output.display(), e))?;
+    let mut writer = BufWriter::new(file);
+    let num_records = slasher
+        .export_database(&mut writer)
+        .map_err(|e| format!("Unable to export slasher database: {:?}", e))?;
+    writer
+        .flush()
+        .map_err(|e| format!("Unable to write {}: {:?}", output.display(), e))?;
+
+    info!(
+        log,
+        "Exported slasher database";
+        "records" => num_records,
+        "path" => output.display(),
+    );
+
+    Ok(())
+}
+
+pub fn import_slasher_db<E: EthSpec>(
+    slasher_config: slasher::Config,
+    input: PathBuf,
+    log: Logger,
+) -> Result<(), String> {
+    let file =
+        File::open(&input).map_err(|e| format!("Unable to open {}: {:?}", input.display(), e))?;
+
+    let slasher = Slasher::<E>::open(slasher_config, log.clone())
+        .map_err(|e| format!("Unable to open slasher database: {:?}", e))?;
+
+    let num_records = slasher
+        .import_database(BufReader::new(file))
+        .map_err(|e| format!("Unable to import slasher database: {:?}", e))?;
+
+    info!(
+        log,
+        "Imported slasher database";
+        "records" => num_records,
+        "backend" => %slasher.config().backend,
+    );
+
+    Ok(())
+}
+
 /// Run the database manager, returning an error string if the operation did not succeed.
 pub fn run<T: EthSpec>(cli_args: &ArgMatches<'_>, env: Environment<T>) -> Result<(), String> {
     let client_config = parse_client_config(cli_args, &env)?;
@@ -356,6 +527,23 @@ pub fn run<T: EthSpec>(cli_args: &ArgMatches<'_>, env: Environment<T>) -> Result
         ("prune_payloads", Some(_)) => {
             prune_payloads(client_config, &context, log).map_err(format_err)
         }
+        ("slasher", Some(cli_args)) => {
+            let slasher_config = parse_slasher_config(cli_args, &client_config)?;
+            match cli_args.subcommand() {
+                ("export", Some(cli_args)) => {
+                    let output = clap_utils::parse_required(cli_args, "output")?;
+                    export_slasher_db::<T>(slasher_config, output, log)
+                }
+                ("import", Some(cli_args)) => {
+                    let input = clap_utils::parse_required(cli_args, "input")?;
+                    import_slasher_db::<T>(slasher_config, input, log)
+                }
+                _ => Err(
+                    "Unknown subcommand, for help `lighthouse database_manager slasher --help`"
+                        .into(),
+                ),
+            }
+        }
         _ => Err("Unknown subcommand, for help `lighthouse database_manager --help`".into()),
     }
 }
@@ -3,7 +3,8 @@
 //! Use this tool to compute the canonical root of a `SignedBeaconBlock`. This is most likely only
 //! useful for benchmarking with tools like `flamegraph`.
 //!
-//! It can load a block from a SSZ file or download it from a beaconAPI.
+//! It can load a block from a SSZ file or download it from a beaconAPI. After the runs, summary
+//! statistics of the run times are printed.
 //!
 //! Logging output is controlled via the `RUST_LOG` environment variable. For example, `export
 //! RUST_LOG=debug`.
@@ -26,6 +27,7 @@
 //!     --block-path /tmp/block.ssz \
 //!     --runs 1
 //! ```
+use crate::stats::DurationStats;
 use crate::transition_blocks::load_from_ssz_with;
 use clap::ArgMatches;
 use clap_utils::{parse_optional, parse_required};
@@ -86,6 +88,7 @@ pub fn run<T: EthSpec>(env: Environment<T>, matches: &ArgMatches) -> Result<(),
      */
 
     let mut block_root = None;
+    let mut durations = Vec::with_capacity(runs);
     for i in 0..runs {
         let start = Instant::now();
 
@@ -93,11 +96,15 @@ pub fn run<T: EthSpec>(env: Environment<T>, matches: &ArgMatches) -> Result<(),
 
         let duration = Instant::now().duration_since(start);
         info!("Run {}: {:?}", i, duration);
+        durations.push(duration);
     }
 
     if let Some(block_root) = block_root {
         info!("Block root is {:?}", block_root);
     }
+    if let Some(stats) = DurationStats::new(&durations) {
+        info!("Total: {}", stats);
+    }
 
     Ok(())
 }
@@ -0,0 +1,292 @@
+//! # ETL
+//!
+//! Use this tool to export chain data from a beaconAPI into CSV files, for analysis with tools
+//! like pandas, DuckDB or a spreadsheet.
+//!
+//! Every block in the slot range is downloaded and flattened into the following files in the
+//! output directory:
+//!
+//! - `blocks.csv`: one row per block.
+//! - `attestations.csv`: one row per attestation included in a block.
+//! - `deposits.csv`: one row per deposit included in a block.
+//! - `balances.csv`: one row per validator at each balance snapshot. Only written if
+//!   `--balances-interval` is supplied, since each snapshot contains every validator.
+//!
+//! Historic blocks and states are only available from a node which has them in its database,
+//! e.g. one which has completed backfill sync and is running with `--reconstruct-historic-states`.
+//!
+//! ## Examples
+//!
+//! ### Export the blocks of an epoch
+//!
+//! ```ignore
+//! lcli etl \
+//!     --beacon-url http://localhost:5052 \
+//!     --start-slot 6627328 \
+//!     --end-slot 6627360 \
+//!     --output-dir /tmp/etl
+//! ```
+//!
+//! ### Export blocks and daily balance snapshots
+//!
+//! ```ignore
+//! lcli etl \
+//!     --beacon-url http://localhost:5052 \
+//!     --start-slot 6400000 \
+//!     --end-slot 6700000 \
+//!     --balances-interval 225 \
+//!     --output-dir /tmp/etl
+//! ```
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use environment::Environment;
+use eth2::{
+    types::{BlockId, StateId},
+    BeaconNodeHttpClient, SensitiveUrl, Timeouts,
+};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use types::{
+    EthSpec, ExecPayload, ExecutionBlockHash, Hash256, PublicKeyBytes, SignedBeaconBlock, Slot,
+};
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Serialize)]
+struct BlockRow {
+    slot: Slot,
+    block_root: Hash256,
+    parent_root: Hash256,
+    state_root: Hash256,
+    proposer_index: u64,
+    graffiti: String,
+    num_attestations: usize,
+    num_deposits: usize,
+    num_voluntary_exits: usize,
+    num_proposer_slashings: usize,
+    num_attester_slashings: usize,
+    execution_block_number: Option<u64>,
+    execution_block_hash: Option<ExecutionBlockHash>,
+}
+
+#[derive(Serialize)]
+struct AttestationRow {
+    block_slot: Slot,
+    slot: Slot,
+    committee_index: u64,
+    beacon_block_root: Hash256,
+    source_epoch: u64,
+    target_epoch: u64,
+    target_root: Hash256,
+    num_attesters: usize,
+}
+
+#[derive(Serialize)]
+struct DepositRow {
+    block_slot: Slot,
+    pubkey: PublicKeyBytes,
+    withdrawal_credentials: Hash256,
+    amount: u64,
+}
+
+#[derive(Serialize)]
+struct BalanceRow {
+    slot: Slot,
+    validator_index: u64,
+    balance: u64,
+}
+
+pub fn run<T: EthSpec>(env: Environment<T>, matches: &ArgMatches) -> Result<(), String> {
+    let beacon_url: SensitiveUrl = parse_required(matches, "beacon-url")?;
+    let start_slot: Slot = parse_required(matches, "start-slot")?;
+    let end_slot: Slot = parse_required(matches, "end-slot")?;
+    let output_dir: PathBuf = parse_required(matches, "output-dir")?;
+    let balances_interval: Option<u64> = parse_optional(matches, "balances-interval")?;
+
+    if end_slot <= start_slot {
+        return Err("--end-slot must be greater than --start-slot".into());
+    }
+    if balances_interval == Some(0) {
+        return Err("--balances-interval must be greater than zero".into());
+    }
+
+    fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Unable to create {:?}: {:?}", output_dir, e))?;
+
+    info!("Using {} spec", T::spec_name());
+    info!("Exporting slots {} to {}", start_slot, end_slot);
+
+    let client = BeaconNodeHttpClient::new(beacon_url, Timeouts::set_all(HTTP_TIMEOUT));
+
+    env.core_context()
+        .executor
+        .handle()
+        .ok_or("shutdown in progress")?
+        .block_on(export::<T>(
+            &client,
+            start_slot,
+            end_slot,
+            balances_interval,
+            &output_dir,
+        ))?;
+
+    info!("Wrote CSV files to {:?}", output_dir);
+
+    Ok(())
+}
+
+async fn export<T: EthSpec>(
+    client: &BeaconNodeHttpClient,
+    start_slot: Slot,
+    end_slot: Slot,
+    balances_interval: Option<u64>,
+    output_dir: &Path,
+) -> Result<(), String> {
+    let mut blocks = csv_writer(&output_dir.join("blocks.csv"))?;
+    let mut attestations = csv_writer(&output_dir.join("attestations.csv"))?;
+    let mut deposits = csv_writer(&output_dir.join("deposits.csv"))?;
+    let mut balances = balances_interval
+        .map(|_| csv_writer(&output_dir.join("balances.csv")))
+        .transpose()?;
+
+    let mut num_blocks = 0;
+    for slot in start_slot.as_u64()..end_slot.as_u64() {
+        let slot = Slot::new(slot);
+
+        let block = client
+            .get_beacon_blocks::<T>(BlockId::Slot(slot))
+            .await
+            .map_err(|e| format!("Failed to download block at slot {}: {:?}", slot, e))?
+            .map(|response| response.data);
+
+        // Skipped slots have no block.
+        if let Some(block) = block {
+            write_block(&block, &mut blocks, &mut attestations, &mut deposits)?;
+            num_blocks += 1;
+        }
+
+        if let (Some(interval), Some(balances)) = (balances_interval, balances.as_mut()) {
+            if slot % (interval * T::slots_per_epoch()) == 0 {
+                write_balances(client, slot, balances).await?;
+            }
+        }
+
+        if slot % T::slots_per_epoch() == 0 {
+            info!("Exported slot {} ({} blocks so far)", slot, num_blocks);
+        }
+    }
+
+    blocks
+        .flush()
+        .and_then(|()| attestations.flush())
+        .and_then(|()| deposits.flush())
+        .and_then(|()| {
+            balances
+                .as_mut()
+                .map_or(Ok(()), |balances| balances.flush())
+        })
+        .map_err(|e| format!("Unable to flush CSV files: {:?}", e))?;
+
+    info!("Exported {} blocks", num_blocks);
+
+    Ok(())
+}
+
+fn write_block<T: EthSpec>(
+    block: &SignedBeaconBlock<T>,
+    blocks: &mut csv::Writer<fs::File>,
+    attestations: &mut csv::Writer<fs::File>,
+    deposits: &mut csv::Writer<fs::File>,
+) -> Result<(), String> {
+    let message = block.message();
+    let body = message.body();
+    let payload = body.execution_payload().ok();
+
+    write_row(
+        blocks,
+        BlockRow {
+            slot: message.slot(),
+            block_root: block.canonical_root(),
+            parent_root: message.parent_root(),
+            state_root: message.state_root(),
+            proposer_index: message.proposer_index(),
+            graffiti: body.graffiti().as_utf8_lossy(),
+            num_attestations: body.attestations().len(),
+            num_deposits: body.deposits().len(),
+            num_voluntary_exits: body.voluntary_exits().len(),
+            num_proposer_slashings: body.proposer_slashings().len(),
+            num_attester_slashings: body.attester_slashings().len(),
+            execution_block_number: payload.as_ref().map(|payload| payload.block_number()),
+            execution_block_hash: payload.as_ref().map(|payload| payload.block_hash()),
+        },
+    )?;
+
+    for attestation in body.attestations() {
+        let data = &attestation.data;
+        write_row(
+            attestations,
+            AttestationRow {
+                block_slot: message.slot(),
+                slot: data.slot,
+                committee_index: data.index,
+                beacon_block_root: data.beacon_block_root,
+                source_epoch: data.source.epoch.as_u64(),
+                target_epoch: data.target.epoch.as_u64(),
+                target_root: data.target.root,
+                num_attesters: attestation.aggregation_bits.num_set_bits(),
+            },
+        )?;
+    }
+
+    for deposit in body.deposits() {
+        write_row(
+            deposits,
+            DepositRow {
+                block_slot: message.slot(),
+                pubkey: deposit.data.pubkey,
+                withdrawal_credentials: deposit.data.withdrawal_credentials,
+                amount: deposit.data.amount,
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+async fn write_balances(
+    client: &BeaconNodeHttpClient,
+    slot: Slot,
+    balances: &mut csv::Writer<fs::File>,
+) -> Result<(), String> {
+    let snapshot = client
+        .get_beacon_states_validator_balances(StateId::Slot(slot), None)
+        .await
+        .map_err(|e| format!("Failed to download balances at slot {}: {:?}", slot, e))?
+        .ok_or_else(|| format!("Unable to locate state at slot {}", slot))?
+        .data;
+
+    for balance in snapshot {
+        write_row(
+            balances,
+            BalanceRow {
+                slot,
+                validator_index: balance.index,
+                balance: balance.balance,
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+fn csv_writer(path: &Path) -> Result<csv::Writer<fs::File>, String> {
+    csv::Writer::from_path(path).map_err(|e| format!("Unable to create {:?}: {:?}", path, e))
+}
+
+fn write_row(writer: &mut csv::Writer<fs::File>, row: impl Serialize) -> Result<(), String> {
+    writer
+        .serialize(row)
+        .map_err(|e| format!("Unable to write CSV row: {:?}", e))
+}
@@ -0,0 +1,328 @@
+//! # Generate SSZ
+//!
+//! Use this tool to generate random, serialized examples of the consensus types for each fork,
+//! for use in the test suites of SSZ libraries.
+//!
+//! The examples use the layout of the `ssz_static` consensus spec tests, so that test runners
+//! written for the spec tests can also run them:
+//!
+//! ```ignore
+//! <output-dir>/<preset>/<fork>/ssz_static/<type>/ssz_random/case_<n>/
+//!     serialized.ssz_snappy
+//!     value.yaml
+//!     roots.yaml
+//! ```
+//!
+//! Types which do not implement `TreeHash` (e.g. the light client types) are written without a
+//! `roots.yaml`. The preset is selected with the global `--spec` flag.
+//!
+//! The light client types are only generated for Altair and Bellatrix, since the Capella light
+//! client types are not yet implemented.
+//!
+//! ## Examples
+//!
+//! ### Generate 10 examples of every type, for every fork
+//!
+//! ```ignore
+//! lcli --spec minimal generate-ssz \
+//!     --output-dir /tmp/ssz-vectors \
+//!     --count 10
+//! ```
+//!
+//! ### Generate examples for a single fork, with a different seed
+//!
+//! ```ignore
+//! lcli generate-ssz \
+//!     --output-dir /tmp/ssz-vectors \
+//!     --fork capella \
+//!     --seed 7
+//! ```
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use serde::Serialize;
+use snap::raw::Encoder;
+use ssz::Encode;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tree_hash::TreeHash;
+use types::light_client_bootstrap::LightClientBootstrap;
+use types::light_client_update::LightClientUpdate;
+use types::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+use types::{historical_summary::HistoricalSummary, *};
+
+pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
+    let output_dir: PathBuf = parse_required(matches, "output-dir")?;
+    let fork: Option<ForkName> = parse_optional(matches, "fork")?;
+    let count: usize = parse_required(matches, "count")?;
+    let seed: u64 = parse_required(matches, "seed")?;
+
+    let forks = fork.map_or_else(ForkName::list_all, |fork| vec![fork]);
+    let preset_dir = output_dir.join(T::spec_name().to_string());
+
+    for fork in forks {
+        let mut generator = Generator {
+            dir: preset_dir.join(fork.to_string()).join("ssz_static"),
+            count,
+            rng: XorShiftRng::seed_from_u64(seed),
+            num_types: 0,
+        };
+        generator.generate_fork::<T>(fork)?;
+        info!("Generated {} types for {}", generator.num_types, fork);
+    }
+
+    info!("Wrote examples to {:?}", preset_dir);
+
+    Ok(())
+}
+
+struct Generator {
+    /// The `ssz_static` directory of the fork.
+    dir: PathBuf,
+    /// Number of examples to generate for each type.
+    count: usize,
+    rng: XorShiftRng,
+    num_types: usize,
+}
+
+impl Generator {
+    fn generate_fork<T: EthSpec>(&mut self, fork: ForkName) -> Result<(), String> {
+        // Types which are present in every fork.
+        self.generate::<AggregateAndProof<T>>("AggregateAndProof")?;
+        self.generate::<Attestation<T>>("Attestation")?;
+        self.generate::<AttestationData>("AttestationData")?;
+        self.generate::<AttesterSlashing<T>>("AttesterSlashing")?;
+        self.generate::<BeaconBlockHeader>("BeaconBlockHeader")?;
+        self.generate::<Checkpoint>("Checkpoint")?;
+        self.generate::<Deposit>("Deposit")?;
+        self.generate::<DepositData>("DepositData")?;
+        self.generate::<DepositMessage>("DepositMessage")?;
+        self.generate::<Eth1Data>("Eth1Data")?;
+        self.generate::<Fork>("Fork")?;
+        self.generate::<ForkData>("ForkData")?;
+        self.generate::<HistoricalBatch<T>>("HistoricalBatch")?;
+        self.generate::<IndexedAttestation<T>>("IndexedAttestation")?;
+        self.generate::<PendingAttestation<T>>("PendingAttestation")?;
+        self.generate::<ProposerSlashing>("ProposerSlashing")?;
+        self.generate::<SignedAggregateAndProof<T>>("SignedAggregateAndProof")?;
+        self.generate::<SignedBeaconBlockHeader>("SignedBeaconBlockHeader")?;
+        self.generate::<SignedVoluntaryExit>("SignedVoluntaryExit")?;
+        self.generate::<SigningData>("SigningData")?;
+        self.generate::<Validator>("Validator")?;
+        self.generate::<VoluntaryExit>("VoluntaryExit")?;
+
+        // Types which changed at each fork.
+        match fork {
+            ForkName::Base => {
+                self.generate::<BeaconBlockBase<T>>("BeaconBlock")?;
+                self.generate::<BeaconBlockBodyBase<T>>("BeaconBlockBody")?;
+                self.generate::<BeaconStateBase<T>>("BeaconState")?;
+                self.generate_with("SignedBeaconBlock", |rng| SignedBeaconBlockBase::<T> {
+                    message: BeaconBlockBase::random_for_test(rng),
+                    signature: Signature::random_for_test(rng),
+                })?;
+            }
+            ForkName::Altair => {
+                self.generate::<BeaconBlockAltair<T>>("BeaconBlock")?;
+                self.generate::<BeaconBlockBodyAltair<T>>("BeaconBlockBody")?;
+                self.generate::<BeaconStateAltair<T>>("BeaconState")?;
+                self.generate_with("SignedBeaconBlock", |rng| SignedBeaconBlockAltair::<T> {
+                    message: BeaconBlockAltair::random_for_test(rng),
+                    signature: Signature::random_for_test(rng),
+                })?;
+            }
+            ForkName::Merge => {
+                self.generate::<BeaconBlockMerge<T>>("BeaconBlock")?;
+                self.generate::<BeaconBlockBodyMerge<T>>("BeaconBlockBody")?;
+                self.generate::<BeaconStateMerge<T>>("BeaconState")?;
+                self.generate_with("SignedBeaconBlock", |rng| SignedBeaconBlockMerge::<T> {
+                    message: BeaconBlockMerge::random_for_test(rng),
+                    signature: Signature::random_for_test(rng),
+                })?;
+                self.generate::<ExecutionPayloadMerge<T>>("ExecutionPayload")?;
+                self.generate::<ExecutionPayloadHeaderMerge<T>>("ExecutionPayloadHeader")?;
+            }
+            ForkName::Capella => {
+                self.generate::<BeaconBlockCapella<T>>("BeaconBlock")?;
+                self.generate::<BeaconBlockBodyCapella<T>>("BeaconBlockBody")?;
+                self.generate::<BeaconStateCapella<T>>("BeaconState")?;
+                self.generate_with("SignedBeaconBlock", |rng| SignedBeaconBlockCapella::<T> {
+                    message: BeaconBlockCapella::random_for_test(rng),
+                    signature: Signature::random_for_test(rng),
+                })?;
+                self.generate::<ExecutionPayloadCapella<T>>("ExecutionPayload")?;
+                self.generate::<ExecutionPayloadHeaderCapella<T>>("ExecutionPayloadHeader")?;
+            }
+        }
+
+        // Types which were introduced after genesis.
+        if fork != ForkName::Base {
+            self.generate::<ContributionAndProof<T>>("ContributionAndProof")?;
+            self.generate::<SignedContributionAndProof<T>>("SignedContributionAndProof")?;
+            self.generate::<SyncAggregate<T>>("SyncAggregate")?;
+            self.generate::<SyncAggregatorSelectionData>("SyncAggregatorSelectionData")?;
+            self.generate::<SyncCommittee<T>>("SyncCommittee")?;
+            self.generate::<SyncCommitteeContribution<T>>("SyncCommitteeContribution")?;
+            self.generate::<SyncCommitteeMessage>("SyncCommitteeMessage")?;
+        }
+        // The light client types changed at Capella, and only the Altair layout is implemented.
+        if matches!(fork, ForkName::Altair | ForkName::Merge) {
+            self.generate_without_root::<LightClientBootstrap<T>>("LightClientBootstrap")?;
+            self.generate_without_root::<LightClientUpdate<T>>("LightClientUpdate")?;
+            self.generate_without_root::<LightClientFinalityUpdate<T>>(
+                "LightClientFinalityUpdate",
+            )?;
+            self.generate_without_root::<LightClientOptimisticUpdate<T>>(
+                "LightClientOptimisticUpdate",
+            )?;
+        }
+        if fork == ForkName::Capella {
+            self.generate::<BlsToExecutionChange>("BLSToExecutionChange")?;
+            self.generate::<HistoricalSummary>("HistoricalSummary")?;
+            self.generate::<SignedBlsToExecutionChange>("SignedBLSToExecutionChange")?;
+            self.generate::<Withdrawal>("Withdrawal")?;
+        }
+
+        Ok(())
+    }
+
+    fn generate<V>(&mut self, type_name: &str) -> Result<(), String>
+    where
+        V: TestRandom + Encode + TreeHash + Serialize,
+    {
+        self.generate_with(type_name, |rng| V::random_for_test(rng))
+    }
+
+    fn generate_with<V, F>(&mut self, type_name: &str, random: F) -> Result<(), String>
+    where
+        V: Encode + TreeHash + Serialize,
+        F: Fn(&mut XorShiftRng) -> V,
+    {
+        for i in 0..self.count {
+            let value = random(&mut self.rng);
+            let root = value.tree_hash_root();
+            write_case(&self.case_dir(type_name, i), &value, Some(root))?;
+        }
+        self.num_types += 1;
+        Ok(())
+    }
+
+    fn generate_without_root<V>(&mut self, type_name: &str) -> Result<(), String>
+    where
+        V: TestRandom + Encode + Serialize,
+    {
+        for i in 0..self.count {
+            let value = V::random_for_test(&mut self.rng);
+            write_case(&self.case_dir(type_name, i), &value, None)?;
+        }
+        self.num_types += 1;
+        Ok(())
+    }
+
+    fn case_dir(&self, type_name: &str, case: usize) -> PathBuf {
+        self.dir
+            .join(type_name)
+            .join("ssz_random")
+            .join(format!("case_{}", case))
+    }
+}
+
+#[derive(Serialize)]
+struct Roots {
+    root: Hash256,
+}
+
+fn write_case<V: Encode + Serialize>(
+    dir: &Path,
+    value: &V,
+    root: Option<Hash256>,
+) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Unable to create {:?}: {:?}", dir, e))?;
+
+    let serialized = Encoder::new()
+        .compress_vec(&value.as_ssz_bytes())
+        .map_err(|e| format!("Unable to compress SSZ: {:?}", e))?;
+    write_file(&dir.join("serialized.ssz_snappy"), serialized)?;
+
+    let yaml =
+        serde_yaml::to_string(value).map_err(|e| format!("Unable to serialize value: {:?}", e))?;
+    write_file(&dir.join("value.yaml"), yaml)?;
+
+    if let Some(root) = root {
+        let yaml = serde_yaml::to_string(&Roots { root })
+            .map_err(|e| format!("Unable to serialize roots: {:?}", e))?;
+        write_file(&dir.join("roots.yaml"), yaml)?;
+    }
+
+    Ok(())
+}
+
+fn write_file(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), String> {
+    fs::write(path, contents).map_err(|e| format!("Unable to write {:?}: {:?}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::DeserializeOwned;
+    use snap::raw::Decoder;
+    use ssz::Decode;
+    use std::fmt::Debug;
+    use tempfile::tempdir;
+
+    type E = MinimalEthSpec;
+
+    #[derive(serde::Deserialize)]
+    struct ReadRoots {
+        root: Hash256,
+    }
+
+    /// Check that the SSZ, YAML and root of a generated case all describe the same value.
+    fn check_case<V>(case_dir: &Path)
+    where
+        V: Decode + DeserializeOwned + TreeHash + PartialEq + Debug,
+    {
+        let compressed = fs::read(case_dir.join("serialized.ssz_snappy")).unwrap();
+        let ssz_bytes = Decoder::new().decompress_vec(&compressed).unwrap();
+        let from_ssz = V::from_ssz_bytes(&ssz_bytes).unwrap();
+
+        let yaml = fs::read_to_string(case_dir.join("value.yaml")).unwrap();
+        let from_yaml: V = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(from_ssz, from_yaml);
+
+        let roots = fs::read_to_string(case_dir.join("roots.yaml")).unwrap();
+        let roots: ReadRoots = serde_yaml::from_str(&roots).unwrap();
+        assert_eq!(from_ssz.tree_hash_root(), roots.root);
+    }
+
+    fn generate(dir: &Path, fork: ForkName) -> Generator {
+        let mut generator = Generator {
+            dir: dir.join(fork.to_string()).join("ssz_static"),
+            count: 2,
+            rng: XorShiftRng::seed_from_u64(42),
+            num_types: 0,
+        };
+        generator.generate_fork::<E>(fork).unwrap();
+        generator
+    }
+
+    #[test]
+    fn round_trip_generated_vectors() {
+        let dir = tempdir().unwrap();
+        let generator = generate(dir.path(), ForkName::Altair);
+
+        for case in 0..generator.count {
+            check_case::<Checkpoint>(&generator.case_dir("Checkpoint", case));
+            check_case::<BeaconBlockAltair<E>>(&generator.case_dir("BeaconBlock", case));
+            check_case::<SyncCommittee<E>>(&generator.case_dir("SyncCommittee", case));
+        }
+    }
+
+    #[test]
+    fn no_light_client_types_for_capella() {
+        let dir = tempdir().unwrap();
+        let generator = generate(dir.path(), ForkName::Capella);
+
+        assert!(generator.dir.join("BeaconBlock").exists());
+        assert!(!generator.dir.join("LightClientUpdate").exists());
+    }
+}
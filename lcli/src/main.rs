@@ -6,15 +6,21 @@ mod check_deposit_data;
 mod create_payload_header;
 mod deploy_deposit_contract;
 mod eth1_genesis;
+mod etl;
 mod generate_bootnode_enr;
+mod generate_ssz;
 mod indexed_attestations;
 mod insecure_validators;
 mod interop_genesis;
 mod mnemonic_validators;
+mod mock_el;
 mod new_testnet;
 mod parse_ssz;
 mod replace_state_pubkeys;
 mod skip_slots;
+mod state_diff;
+mod state_root;
+mod stats;
 mod transition_blocks;
 
 use clap::{App, Arg, ArgMatches, SubCommand};
@@ -93,7 +99,8 @@ fn main() {
                         .value_name("INTEGER")
                         .takes_value(true)
                         .default_value("1")
-                        .help("Number of repeat runs, useful for benchmarking."),
+                        .help("Number of repeat runs, useful for benchmarking. Statistics of the run \
+                            times are reported once all runs are complete."),
                 )
                 .arg(
                     Arg::with_name("state-root")
@@ -202,6 +209,216 @@ fn main() {
                             the block."),
                 )
         )
+        .subcommand(
+            SubCommand::with_name("state-diff")
+                .about("Prints the differences between two BeaconStates, field-by-field")
+                .arg(
+                    Arg::with_name("state-a-path")
+                        .long("state-a-path")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .conflicts_with("beacon-url-a")
+                        .help("Path to a SSZ file of the first state."),
+                )
+                .arg(
+                    Arg::with_name("state-b-path")
+                        .long("state-b-path")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .conflicts_with("beacon-url-b")
+                        .help("Path to a SSZ file of the second state."),
+                )
+                .arg(
+                    Arg::with_name("beacon-url-a")
+                        .long("beacon-url-a")
+                        .value_name("URL")
+                        .takes_value(true)
+                        .help("URL to a beacon-API provider to download the first state from."),
+                )
+                .arg(
+                    Arg::with_name("beacon-url-b")
+                        .long("beacon-url-b")
+                        .value_name("URL")
+                        .takes_value(true)
+                        .help("URL to a beacon-API provider to download the second state from."),
+                )
+                .arg(
+                    Arg::with_name("state-id")
+                        .long("state-id")
+                        .value_name("STATE_ID")
+                        .takes_value(true)
+                        .help("Identifier for the states to download as per beacon-API standards \
+                            (slot, root, etc.)"),
+                )
+                .arg(
+                    Arg::with_name("max-differences")
+                        .long("max-differences")
+                        .value_name("INTEGER")
+                        .takes_value(true)
+                        .default_value("100")
+                        .help("Maximum number of individual differences to print."),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("etl")
+                .about("Exports blocks, attestations, deposits and validator balances from a \
+                    beacon-API provider to CSV files")
+                .arg(
+                    Arg::with_name("beacon-url")
+                        .long("beacon-url")
+                        .value_name("URL")
+                        .takes_value(true)
+                        .required(true)
+                        .help("URL to a beacon-API provider."),
+                )
+                .arg(
+                    Arg::with_name("start-slot")
+                        .long("start-slot")
+                        .value_name("SLOT")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The first slot to export."),
+                )
+                .arg(
+                    Arg::with_name("end-slot")
+                        .long("end-slot")
+                        .value_name("SLOT")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The slot to stop exporting at (exclusive)."),
+                )
+                .arg(
+                    Arg::with_name("output-dir")
+                        .long("output-dir")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Directory to write the CSV files to."),
+                )
+                .arg(
+                    Arg::with_name("balances-interval")
+                        .long("balances-interval")
+                        .value_name("EPOCHS")
+                        .takes_value(true)
+                        .help("If present, export the balances of all validators every \
+                            EPOCHS epochs. Requires historic states for past slots."),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("mock-el")
+                .about("Runs a mock execution layer, for testing beacon nodes without a real \
+                    execution client")
+                .arg(
+                    Arg::with_name("jwt-output-path")
+                        .long("jwt-output-path")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Path to write the JWT secret used by the mock EL."),
+                )
+                .arg(
+                    Arg::with_name("listen-address")
+                        .long("listen-address")
+                        .value_name("IP_ADDRESS")
+                        .takes_value(true)
+                        .default_value("127.0.0.1")
+                        .help("The IPv4 address to serve the engine API on."),
+                )
+                .arg(
+                    Arg::with_name("listen-port")
+                        .long("listen-port")
+                        .value_name("PORT")
+                        .takes_value(true)
+                        .default_value("8551")
+                        .help("The port to serve the engine API on."),
+                )
+                .arg(
+                    Arg::with_name("terminal-total-difficulty")
+                        .long("terminal-total-difficulty")
+                        .value_name("INTEGER")
+                        .takes_value(true)
+                        .default_value("6400")
+                        .help("The total difficulty of the terminal PoW block."),
+                )
+                .arg(
+                    Arg::with_name("terminal-block-number")
+                        .long("terminal-block-number")
+                        .value_name("INTEGER")
+                        .takes_value(true)
+                        .help("The number of the terminal PoW block. Defaults to 64."),
+                )
+                .arg(
+                    Arg::with_name("terminal-block-hash")
+                        .long("terminal-block-hash")
+                        .value_name("HASH")
+                        .takes_value(true)
+                        .help("The terminal block hash override, as used by the beacon node."),
+                )
+                .arg(
+                    Arg::with_name("shanghai-time")
+                        .long("shanghai-time")
+                        .value_name("UNIX_TIMESTAMP")
+                        .takes_value(true)
+                        .help("The timestamp at which withdrawals are enabled. If not \
+                            supplied, Shanghai is never activated."),
+                )
+                .arg(
+                    Arg::with_name("payload-status")
+                        .long("payload-status")
+                        .value_name("STATUS")
+                        .takes_value(true)
+                        .possible_values(&["valid", "syncing", "invalid", "verify"])
+                        .default_value("valid")
+                        .help("The status returned for every newPayload and forkchoiceUpdated \
+                            call. \"verify\" checks each payload against the mock EL's own \
+                            chain instead."),
+                )
+                .arg(
+                    Arg::with_name("latency-ms")
+                        .long("latency-ms")
+                        .value_name("MILLISECONDS")
+                        .takes_value(true)
+                        .help("Delay every engine API response by this many milliseconds."),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("generate-ssz")
+                .about("Generates random, serialized examples of the consensus types for use in \
+                    SSZ library test suites")
+                .arg(
+                    Arg::with_name("output-dir")
+                        .long("output-dir")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Directory to write the examples to."),
+                )
+                .arg(
+                    Arg::with_name("fork")
+                        .long("fork")
+                        .value_name("FORK")
+                        .takes_value(true)
+                        .possible_values(&["phase0", "altair", "bellatrix", "capella"])
+                        .help("Only generate examples for this fork. Defaults to all forks."),
+                )
+                .arg(
+                    Arg::with_name("count")
+                        .long("count")
+                        .value_name("INTEGER")
+                        .takes_value(true)
+                        .default_value("1")
+                        .help("Number of examples to generate for each type."),
+                )
+                .arg(
+                    Arg::with_name("seed")
+                        .long("seed")
+                        .value_name("INTEGER")
+                        .takes_value(true)
+                        .default_value("42")
+                        .help("Seed for the random number generator, so that the examples can \
+                            be reproduced."),
+                )
+        )
         .subcommand(
             SubCommand::with_name("pretty-ssz")
                 .about("Parses SSZ-encoded data from a file")
@@ -648,6 +865,15 @@ fn main() {
                         .takes_value(true)
                         .help("The proposer score boost to apply as a percentage, e.g. 70 = 70%"),
                 )
+                .arg(
+                    Arg::with_name("eth1-withdrawal-address")
+                        .long("eth1-withdrawal-address")
+                        .value_name("ETH1_ADDRESS")
+                        .takes_value(true)
+                        .help("If present, the validators in the genesis state are given 0x01 \
+                            withdrawal credentials for this address, instead of BLS withdrawal \
+                            credentials."),
+                )
 
         )
         .subcommand(
@@ -841,6 +1067,52 @@ fn main() {
                         .help("Number of repeat runs, useful for benchmarking."),
                 )
         )
+        .subcommand(
+            SubCommand::with_name("state-root")
+                .about("Computes the state root of some state")
+                .arg(
+                    Arg::with_name("state-path")
+                        .long("state-path")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .conflicts_with("beacon-url")
+                        .help("Path to load a BeaconState from file as SSZ."),
+                )
+                .arg(
+                    Arg::with_name("beacon-url")
+                        .long("beacon-url")
+                        .value_name("URL")
+                        .takes_value(true)
+                        .help("URL to a beacon-API provider."),
+                )
+                .arg(
+                    Arg::with_name("state-id")
+                        .long("state-id")
+                        .value_name("STATE_ID")
+                        .takes_value(true)
+                        .requires("beacon-url")
+                        .help("Identifier for a state as per beacon-API standards (slot, root, etc.)"),
+                )
+                .arg(
+                    Arg::with_name("mode")
+                        .long("mode")
+                        .value_name("MODE")
+                        .takes_value(true)
+                        .possible_values(&["uncached", "build", "update"])
+                        .default_value("uncached")
+                        .help("How to compute the root. \"uncached\" doesn't use the tree hash \
+                            cache, \"build\" builds the cache from scratch on each run and \
+                            \"update\" builds the cache once then updates it on each run."),
+                )
+                .arg(
+                    Arg::with_name("runs")
+                        .long("runs")
+                        .value_name("INTEGER")
+                        .takes_value(true)
+                        .default_value("1")
+                        .help("Number of repeat runs, useful for benchmarking."),
+                )
+        )
         .get_matches();
 
     let result = matches
@@ -899,6 +1171,17 @@ fn run<T: EthSpec>(
         ("skip-slots", Some(matches)) => {
             skip_slots::run::<T>(env, matches).map_err(|e| format!("Failed to skip slots: {}", e))
         }
+        ("state-diff", Some(matches)) => {
+            state_diff::run::<T>(env, matches).map_err(|e| format!("Failed to diff states: {}", e))
+        }
+        ("etl", Some(matches)) => {
+            etl::run::<T>(env, matches).map_err(|e| format!("Failed to export data: {}", e))
+        }
+        ("mock-el", Some(matches)) => {
+            mock_el::run::<T>(env, matches).map_err(|e| format!("Failed to run mock EL: {}", e))
+        }
+        ("generate-ssz", Some(matches)) => generate_ssz::run::<T>(matches)
+            .map_err(|e| format!("Failed to generate SSZ examples: {}", e)),
         ("pretty-ssz", Some(matches)) => {
             run_parse_ssz::<T>(matches).map_err(|e| format!("Failed to pretty print hex: {}", e))
         }
@@ -934,6 +1217,8 @@ fn run<T: EthSpec>(
             .map_err(|e| format!("Failed to run indexed-attestations command: {}", e)),
         ("block-root", Some(matches)) => block_root::run::<T>(env, matches)
             .map_err(|e| format!("Failed to run block-root command: {}", e)),
+        ("state-root", Some(matches)) => state_root::run::<T>(env, matches)
+            .map_err(|e| format!("Failed to run state-root command: {}", e)),
         (other, _) => Err(format!("Unknown subcommand {}. See --help.", other)),
     }
 }
@@ -0,0 +1,101 @@
+//! # Mock EL
+//!
+//! Use this tool to run a mock execution layer, so that beacon nodes can be tested on a devnet
+//! without running a real execution client.
+//!
+//! The mock EL serves the engine API on the given address, authenticated using a random JWT secret
+//! which is written to `--jwt-output-path`. It produces a chain of PoW blocks up to the terminal
+//! block, then builds payloads on request from the beacon node. The response to every `newPayload`
+//! and `forkchoiceUpdated` call can be fixed with `--payload-status`, and every response can be
+//! delayed with `--latency-ms`.
+//!
+//! ## Examples
+//!
+//! ### Serve a mock EL on the default engine API port
+//!
+//! ```ignore
+//! lcli mock-el \
+//!     --jwt-output-path ~/.lighthouse/mainnet/beacon/execution/jwtsecret \
+//!     --listen-port 8551
+//! ```
+//!
+//! ### Return `SYNCING` for every payload, with a 500ms delay
+//!
+//! ```ignore
+//! lcli mock-el \
+//!     --jwt-output-path /tmp/jwtsecret \
+//!     --payload-status syncing \
+//!     --latency-ms 500
+//! ```
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use environment::Environment;
+use execution_layer::{
+    auth::JwtKey,
+    test_utils::{
+        Config as MockServerConfig, MockExecutionConfig, MockServer, DEFAULT_TERMINAL_BLOCK,
+    },
+};
+use std::net::Ipv4Addr;
+use std::path::PathBuf;
+use std::time::Duration;
+use types::{EthSpec, ExecutionBlockHash};
+
+pub fn run<T: EthSpec>(mut env: Environment<T>, matches: &ArgMatches) -> Result<(), String> {
+    let jwt_path: PathBuf = parse_required(matches, "jwt-output-path")?;
+    let listen_addr: Ipv4Addr = parse_required(matches, "listen-address")?;
+    let listen_port: u16 = parse_required(matches, "listen-port")?;
+    let terminal_difficulty: u64 = parse_required(matches, "terminal-total-difficulty")?;
+    let terminal_block: u64 =
+        parse_optional(matches, "terminal-block-number")?.unwrap_or(DEFAULT_TERMINAL_BLOCK);
+    let terminal_block_hash: ExecutionBlockHash =
+        parse_optional(matches, "terminal-block-hash")?.unwrap_or_else(ExecutionBlockHash::zero);
+    let shanghai_time: Option<u64> = parse_optional(matches, "shanghai-time")?;
+    let payload_status: String = parse_required(matches, "payload-status")?;
+    let latency_ms: Option<u64> = parse_optional(matches, "latency-ms")?;
+
+    let handle = env
+        .core_context()
+        .executor
+        .handle()
+        .ok_or("shutdown in progress")?;
+
+    let jwt_key = JwtKey::random();
+    std::fs::write(&jwt_path, jwt_key.hex_string())
+        .map_err(|e| format!("Failed to write JWT secret to {:?}: {:?}", jwt_path, e))?;
+
+    let config = MockExecutionConfig {
+        server_config: MockServerConfig {
+            listen_addr,
+            listen_port,
+            response_latency: latency_ms.map(Duration::from_millis),
+        },
+        jwt_key,
+        terminal_difficulty: terminal_difficulty.into(),
+        terminal_block,
+        terminal_block_hash,
+        shanghai_time,
+    };
+    let server: MockServer<T> = MockServer::new_with_config(&handle, config);
+
+    match payload_status.as_str() {
+        "valid" => server.all_payloads_valid(),
+        "syncing" => server.all_payloads_syncing(false),
+        "invalid" => server.all_payloads_invalid(ExecutionBlockHash::zero()),
+        "verify" => server.full_payload_verification(),
+        other => return Err(format!("Unknown payload status: {}", other)),
+    }
+
+    info!("Mock EL listening on {}", server.url());
+    info!("JWT secret written to {:?}", jwt_path);
+    info!("Payload status: {}", payload_status);
+    if let Some(latency_ms) = latency_ms {
+        info!("Response latency: {}ms", latency_ms);
+    }
+
+    env.block_until_shutdown_requested()?;
+    info!("Shutting down mock EL");
+    drop(server);
+
+    Ok(())
+}
@@ -9,7 +9,7 @@ use ethereum_hashing::hash;
 use ssz::Decode;
 use ssz::Encode;
 use state_processing::process_activations;
-use state_processing::upgrade::{upgrade_to_altair, upgrade_to_bellatrix};
+use state_processing::upgrade::{upgrade_to_altair, upgrade_to_bellatrix, upgrade_to_capella};
 use std::fs::File;
 use std::io::Read;
 use std::path::PathBuf;
@@ -89,6 +89,26 @@ pub fn run<T: EthSpec>(testnet_dir_path: PathBuf, matches: &ArgMatches) -> Resul
         spec.terminal_total_difficulty = ttd;
     }
 
+    // Starting from a later fork at genesis requires all prior forks to also be at genesis.
+    let genesis_forks = [
+        ("altair", spec.altair_fork_epoch),
+        ("bellatrix", spec.bellatrix_fork_epoch),
+        ("capella", spec.capella_fork_epoch),
+    ];
+    for window in genesis_forks.windows(2) {
+        let (prior_fork, prior_epoch) = window[0];
+        let (fork, epoch) = window[1];
+        if epoch == Some(T::genesis_epoch()) && prior_epoch != Some(T::genesis_epoch()) {
+            return Err(format!(
+                "{} is enabled at genesis, so {} must be too. Set --{}-fork-epoch 0",
+                fork, prior_fork, prior_fork
+            ));
+        }
+    }
+
+    let eth1_withdrawal_address: Option<Address> =
+        parse_optional(matches, "eth1-withdrawal-address")?;
+
     let validator_count = parse_required(matches, "validator-count")?;
     let execution_payload_header: Option<ExecutionPayloadHeader<T>> =
         parse_optional(matches, "execution-payload-header")?
@@ -144,6 +164,7 @@ pub fn run<T: EthSpec>(testnet_dir_path: PathBuf, matches: &ArgMatches) -> Resul
             genesis_time,
             eth1_block_hash.into_root(),
             execution_payload_header,
+            eth1_withdrawal_address,
             &spec,
         )?;
 
@@ -180,6 +201,7 @@ pub fn run<T: EthSpec>(testnet_dir_path: PathBuf, matches: &ArgMatches) -> Resul
             genesis_time,
             eth1_block_hash.into_root(),
             execution_payload_header,
+            eth1_withdrawal_address,
             &spec,
         )?;
         Some(genesis_state.as_ssz_bytes())
@@ -201,8 +223,12 @@ pub fn run<T: EthSpec>(testnet_dir_path: PathBuf, matches: &ArgMatches) -> Resul
 /// genesis state. This allows us to start testnets without having to deposit validators
 /// manually.
 ///
-/// The optional `execution_payload_header` allows us to start a network from the bellatrix
-/// fork without the need to transition to altair and bellatrix.
+/// The optional `execution_payload_header` allows us to start a network from the bellatrix or
+/// capella fork without the need to transition through the prior forks.
+///
+/// If `eth1_withdrawal_address` is set, every validator is given `0x01` withdrawal credentials for
+/// that address instead of BLS withdrawal credentials, so that withdrawals can be tested without
+/// first submitting BLS to execution changes.
 ///
 /// We need to ensure that `eth1_block_hash` is equal to the genesis block hash that is
 /// generated from the execution side `genesis.json`.
@@ -211,15 +237,24 @@ fn initialize_state_with_validators<T: EthSpec>(
     genesis_time: u64,
     eth1_block_hash: Hash256,
     execution_payload_header: Option<ExecutionPayloadHeader<T>>,
+    eth1_withdrawal_address: Option<Address>,
     spec: &ChainSpec,
 ) -> Result<BeaconState<T>, String> {
-    // If no header is provided, then start from a Bellatrix state by default
+    // If no header is provided, then start from a default header for the genesis fork.
     let default_header: ExecutionPayloadHeader<T> =
-        ExecutionPayloadHeader::Merge(ExecutionPayloadHeaderMerge {
-            block_hash: ExecutionBlockHash::from_root(eth1_block_hash),
-            parent_hash: ExecutionBlockHash::zero(),
-            ..ExecutionPayloadHeaderMerge::default()
-        });
+        if spec.fork_name_at_epoch(T::genesis_epoch()) == ForkName::Capella {
+            ExecutionPayloadHeader::Capella(ExecutionPayloadHeaderCapella {
+                block_hash: ExecutionBlockHash::from_root(eth1_block_hash),
+                parent_hash: ExecutionBlockHash::zero(),
+                ..ExecutionPayloadHeaderCapella::default()
+            })
+        } else {
+            ExecutionPayloadHeader::Merge(ExecutionPayloadHeaderMerge {
+                block_hash: ExecutionBlockHash::from_root(eth1_block_hash),
+                parent_hash: ExecutionBlockHash::zero(),
+                ..ExecutionPayloadHeaderMerge::default()
+            })
+        };
     let execution_payload_header = execution_payload_header.unwrap_or(default_header);
     // Empty eth1 data
     let eth1_data = Eth1Data {
@@ -243,7 +278,7 @@ fn initialize_state_with_validators<T: EthSpec>(
         };
         let amount = spec.max_effective_balance;
         // Create a new validator.
-        let validator = Validator {
+        let mut validator = Validator {
             pubkey: keypair.0.pk.clone().into(),
             withdrawal_credentials: withdrawal_credentials(&keypair.1.pk),
             activation_eligibility_epoch: spec.far_future_epoch,
@@ -256,6 +291,9 @@ fn initialize_state_with_validators<T: EthSpec>(
             ),
             slashed: false,
         };
+        if let Some(address) = &eth1_withdrawal_address {
+            validator.change_withdrawal_credentials(address, spec);
+        }
         state.validators_mut().push(validator).unwrap();
         state.balances_mut().push(amount).unwrap();
     }
@@ -281,23 +319,42 @@ fn initialize_state_with_validators<T: EthSpec>(
         // Remove intermediate Altair fork from `state.fork`.
         state.fork_mut().previous_version = spec.bellatrix_fork_version;
 
+        // Similarly, perform an upgrade to capella if configured from genesis.
+        if spec
+            .capella_fork_epoch
+            .map_or(false, |fork_epoch| fork_epoch == T::genesis_epoch())
+        {
+            upgrade_to_capella(&mut state, spec).unwrap();
+
+            // Remove intermediate Bellatrix fork from `state.fork`.
+            state.fork_mut().previous_version = spec.capella_fork_version;
+        }
+
         // Override latest execution payload header.
         // See https://github.com/ethereum/consensus-specs/blob/v1.1.0/specs/merge/beacon-chain.md#testing
-
-        // Currently, we only support starting from a bellatrix state
-        match state
-            .latest_execution_payload_header_mut()
-            .map_err(|e| format!("Failed to get execution payload header: {:?}", e))?
-        {
-            ExecutionPayloadHeaderRefMut::Merge(header_mut) => {
-                if let ExecutionPayloadHeader::Merge(eph) = execution_payload_header {
-                    *header_mut = eph;
-                } else {
-                    return Err("Execution payload header must be a bellatrix header".to_string());
-                }
+        match (
+            state
+                .latest_execution_payload_header_mut()
+                .map_err(|e| format!("Failed to get execution payload header: {:?}", e))?,
+            execution_payload_header,
+        ) {
+            (
+                ExecutionPayloadHeaderRefMut::Merge(header_mut),
+                ExecutionPayloadHeader::Merge(eph),
+            ) => {
+                *header_mut = eph;
+            }
+            (
+                ExecutionPayloadHeaderRefMut::Capella(header_mut),
+                ExecutionPayloadHeader::Capella(eph),
+            ) => {
+                *header_mut = eph;
+            }
+            (ExecutionPayloadHeaderRefMut::Merge(_), _) => {
+                return Err("Execution payload header must be a bellatrix header".to_string());
             }
-            ExecutionPayloadHeaderRefMut::Capella(_) => {
-                return Err("Cannot start genesis from a capella state".to_string())
+            (ExecutionPayloadHeaderRefMut::Capella(_), _) => {
+                return Err("Execution payload header must be a capella header".to_string());
             }
         }
     }
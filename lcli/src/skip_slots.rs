@@ -6,6 +6,11 @@
 //! It can load states from file or pull them from a beaconAPI. States pulled from a beaconAPI can
 //! be saved to disk to reduce future calls to that server.
 //!
+//! Each run advances a fresh copy of the initial state. Once all runs are complete, statistics of
+//! the run times are reported, along with those of the slots which required epoch processing
+//! (unless `--partial-state-advance` is used). This makes it possible to measure epoch processing
+//! against real states, e.g. from mainnet.
+//!
 //! ## Examples
 //!
 //! ### Example 1.
@@ -44,13 +49,14 @@
 //!     --slots 32 \
 //!     --runs 2
 //! ```
+use crate::stats::DurationStats;
 use crate::transition_blocks::load_from_ssz_with;
 use clap::ArgMatches;
 use clap_utils::{parse_optional, parse_required};
 use environment::Environment;
 use eth2::{types::StateId, BeaconNodeHttpClient, SensitiveUrl, Timeouts};
 use ssz::Encode;
-use state_processing::state_advance::{complete_state_advance, partial_state_advance};
+use state_processing::{per_slot_processing, state_advance::partial_state_advance};
 use std::fs::File;
 use std::io::prelude::*;
 use std::path::PathBuf;
@@ -120,6 +126,8 @@ pub fn run<T: EthSpec>(env: Environment<T>, matches: &ArgMatches) -> Result<(),
             .map_err(|e| format!("Unable to build THC: {:?}", e))?
     };
 
+    let mut run_durations = Vec::with_capacity(runs);
+    let mut epoch_slot_durations = vec![];
     for i in 0..runs {
         let mut state = state.clone_with(CloneConfig::all());
 
@@ -129,12 +137,28 @@ pub fn run<T: EthSpec>(env: Environment<T>, matches: &ArgMatches) -> Result<(),
             partial_state_advance(&mut state, Some(state_root), target_slot, spec)
                 .map_err(|e| format!("Unable to perform partial advance: {:?}", e))?;
         } else {
-            complete_state_advance(&mut state, Some(state_root), target_slot, spec)
-                .map_err(|e| format!("Unable to perform complete advance: {:?}", e))?;
+            // Equivalent to `complete_state_advance`, but timing each slot.
+            let mut state_root_opt = Some(state_root);
+            while state.slot() < target_slot {
+                let slot_start = Instant::now();
+                let summary = per_slot_processing(&mut state, state_root_opt.take(), spec)
+                    .map_err(|e| format!("Unable to perform complete advance: {:?}", e))?;
+                if summary.is_some() {
+                    epoch_slot_durations.push(slot_start.elapsed());
+                }
+            }
         }
 
         let duration = Instant::now().duration_since(start);
         info!("Run {}: {:?}", i, duration);
+        run_durations.push(duration);
+    }
+
+    if let Some(stats) = DurationStats::new(&run_durations) {
+        info!("Total: {}", stats);
+    }
+    if let Some(stats) = DurationStats::new(&epoch_slot_durations) {
+        info!("Slots with epoch processing: {}", stats);
     }
 
     if let Some(output_path) = output_path {
@@ -0,0 +1,293 @@
+//! # State Diff
+//!
+//! Use this tool to compare two `BeaconState`s field-by-field. Useful for troubleshooting
+//! consensus failures, e.g. finding the cause of a split between two clients which computed
+//! different post-states for the same block.
+//!
+//! Each state can be loaded from file or pulled from a beaconAPI. Differences in lists indexed by
+//! validator index (e.g. `validators` and `balances`) are grouped by validator.
+//!
+//! ## Examples
+//!
+//! ### Compare the states of two nodes at the same slot
+//!
+//! ```ignore
+//! lcli state-diff \
+//!     --beacon-url-a http://localhost:5052 \
+//!     --beacon-url-b http://localhost:5062 \
+//!     --state-id 6627360
+//! ```
+//!
+//! ### Compare a state from file with the state of a node
+//!
+//! ```ignore
+//! lcli state-diff \
+//!     --state-a-path /tmp/post-state-0x6c69.ssz \
+//!     --beacon-url-b http://localhost:5052 \
+//!     --state-id 0x3cdc33cd02713d8d6cc33a6dbe2d3a5bf9af1d357de0d175a403496486ff845e
+//! ```
+use crate::transition_blocks::load_from_ssz_with;
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use environment::Environment;
+use eth2::{types::StateId, BeaconNodeHttpClient, SensitiveUrl, Timeouts};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use types::{BeaconState, ChainSpec, EthSpec};
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Fields of the `BeaconState` which are lists indexed by validator index.
+const VALIDATOR_FIELDS: &[&str] = &[
+    "validators",
+    "balances",
+    "previous_epoch_participation",
+    "current_epoch_participation",
+    "inactivity_scores",
+];
+
+/// Maximum length of a value in the output, beyond which it is truncated.
+const MAX_VALUE_LEN: usize = 128;
+
+#[derive(Debug)]
+struct Difference {
+    path: String,
+    a: Option<Value>,
+    b: Option<Value>,
+}
+
+pub fn run<T: EthSpec>(env: Environment<T>, matches: &ArgMatches) -> Result<(), String> {
+    let spec = &T::default_spec();
+    let max_differences: usize = parse_required(matches, "max-differences")?;
+
+    info!("Using {} spec", T::spec_name());
+
+    let state_a = load_state(matches, "a", &env, spec)?;
+    let state_b = load_state(matches, "b", &env, spec)?;
+
+    let root_a = state_a.canonical_root();
+    let root_b = state_b.canonical_root();
+    println!("State A: slot {}, root {:?}", state_a.slot(), root_a);
+    println!("State B: slot {}, root {:?}", state_b.slot(), root_b);
+
+    if root_a == root_b {
+        println!("States are identical");
+        return Ok(());
+    }
+
+    let value_a = to_object(&state_a)?;
+    let value_b = to_object(&state_b)?;
+
+    // Number of differences in each top-level field. `serde_json` sorts object keys, so these are
+    // in alphabetical order, followed by any fields which are only present in state B.
+    let mut field_counts: Vec<(&String, usize)> = vec![];
+    // Differences for each validator, in fields indexed by validator index.
+    let mut validator_differences: BTreeMap<usize, Vec<Difference>> = BTreeMap::new();
+    // Differences in all other fields.
+    let mut other_differences = vec![];
+
+    let fields = value_a
+        .keys()
+        .chain(value_b.keys().filter(|field| !value_a.contains_key(*field)));
+    for field in fields {
+        let (a, b) = (value_a.get(field), value_b.get(field));
+
+        let count = match (a, b) {
+            (Some(Value::Array(a)), Some(Value::Array(b)))
+                if VALIDATOR_FIELDS.contains(&field.as_str()) =>
+            {
+                let mut count = 0;
+                for i in 0..a.len().max(b.len()) {
+                    let mut differences = vec![];
+                    diff_values(
+                        format!("{}[{}]", field, i),
+                        a.get(i),
+                        b.get(i),
+                        &mut differences,
+                    );
+                    if !differences.is_empty() {
+                        count += differences.len();
+                        validator_differences
+                            .entry(i)
+                            .or_default()
+                            .extend(differences);
+                    }
+                }
+                count
+            }
+            _ => {
+                let initial_len = other_differences.len();
+                diff_values(field.clone(), a, b, &mut other_differences);
+                other_differences.len() - initial_len
+            }
+        };
+
+        if count > 0 {
+            field_counts.push((field, count));
+        }
+    }
+
+    println!();
+    println!("Fields with differences:");
+    for (field, count) in field_counts {
+        println!("  {}: {} differences", field, count);
+    }
+
+    // Number of differences visited so far, only the first `max_differences` of which are printed.
+    let mut num_differences = 0;
+
+    if !validator_differences.is_empty() {
+        println!();
+        println!(
+            "Validator differences ({} validators):",
+            validator_differences.len()
+        );
+        for (validator_index, differences) in &validator_differences {
+            if num_differences < max_differences {
+                println!("  Validator {}:", validator_index);
+            }
+            for diff in differences {
+                print_difference("    ", diff, &mut num_differences, max_differences);
+            }
+        }
+    }
+
+    if !other_differences.is_empty() {
+        println!();
+        println!("Other differences:");
+        for diff in &other_differences {
+            print_difference("  ", diff, &mut num_differences, max_differences);
+        }
+    }
+
+    if num_differences > max_differences {
+        println!();
+        println!(
+            "... and {} more differences, see --max-differences",
+            num_differences - max_differences
+        );
+    }
+
+    Ok(())
+}
+
+/// Load state `"a"` or `"b"`, from file or from a beaconAPI.
+fn load_state<T: EthSpec>(
+    matches: &ArgMatches,
+    name: &str,
+    env: &Environment<T>,
+    spec: &ChainSpec,
+) -> Result<BeaconState<T>, String> {
+    let state_path: Option<PathBuf> = parse_optional(matches, &format!("state-{}-path", name))?;
+    let beacon_url: Option<SensitiveUrl> =
+        parse_optional(matches, &format!("beacon-url-{}", name))?;
+
+    match (state_path, beacon_url) {
+        (Some(state_path), None) => {
+            info!("State {} path: {:?}", name, state_path);
+            load_from_ssz_with(&state_path, spec, BeaconState::from_ssz_bytes)
+        }
+        (None, Some(beacon_url)) => {
+            let state_id: StateId = parse_required(matches, "state-id")?;
+            info!("State {} URL: {}", name, beacon_url);
+            let client = BeaconNodeHttpClient::new(beacon_url, Timeouts::set_all(HTTP_TIMEOUT));
+            Ok(env
+                .core_context()
+                .executor
+                .handle()
+                .ok_or("shutdown in progress")?
+                .block_on(async move {
+                    client
+                        .get_debug_beacon_states::<T>(state_id)
+                        .await
+                        .map_err(|e| format!("Failed to download state: {:?}", e))
+                })
+                .map_err(|e| format!("Failed to complete task: {:?}", e))?
+                .ok_or_else(|| format!("Unable to locate state at {:?}", state_id))?
+                .data)
+        }
+        _ => Err(format!(
+            "must supply either --state-{}-path or --beacon-url-{}",
+            name, name
+        )),
+    }
+}
+
+fn to_object<T: EthSpec>(state: &BeaconState<T>) -> Result<serde_json::Map<String, Value>, String> {
+    match serde_json::to_value(state) {
+        Ok(Value::Object(object)) => Ok(object),
+        Ok(_) => Err("State is not a JSON object".into()),
+        Err(e) => Err(format!("Unable to convert state to JSON: {:?}", e)),
+    }
+}
+
+/// Append the differences between `a` and `b` to `differences`, recursing into objects and
+/// arrays so that each difference is as specific as possible.
+fn diff_values(
+    path: String,
+    a: Option<&Value>,
+    b: Option<&Value>,
+    differences: &mut Vec<Difference>,
+) {
+    match (a, b) {
+        (Some(Value::Object(a)), Some(Value::Object(b))) => {
+            let keys = a.keys().chain(b.keys().filter(|key| !a.contains_key(*key)));
+            for key in keys {
+                diff_values(
+                    format!("{}.{}", path, key),
+                    a.get(key),
+                    b.get(key),
+                    differences,
+                );
+            }
+        }
+        (Some(Value::Array(a)), Some(Value::Array(b))) => {
+            for i in 0..a.len().max(b.len()) {
+                diff_values(format!("{}[{}]", path, i), a.get(i), b.get(i), differences);
+            }
+        }
+        (a, b) if a != b => differences.push(Difference {
+            path,
+            a: a.cloned(),
+            b: b.cloned(),
+        }),
+        _ => (),
+    }
+}
+
+fn print_difference(
+    indent: &str,
+    diff: &Difference,
+    num_differences: &mut usize,
+    max_differences: usize,
+) {
+    if *num_differences < max_differences {
+        println!(
+            "{}{}: {} -> {}",
+            indent,
+            diff.path,
+            display_value(diff.a.as_ref()),
+            display_value(diff.b.as_ref())
+        );
+    }
+    *num_differences += 1;
+}
+
+fn display_value(value: Option<&Value>) -> String {
+    let string = match value {
+        None => return "<missing>".into(),
+        Some(Value::String(string)) => string.clone(),
+        Some(value) => value.to_string(),
+    };
+
+    if string.chars().count() > MAX_VALUE_LEN {
+        format!(
+            "{}...",
+            string.chars().take(MAX_VALUE_LEN).collect::<String>()
+        )
+    } else {
+        string
+    }
+}
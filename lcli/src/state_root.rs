@@ -0,0 +1,135 @@
+//! # State Root
+//!
+//! Use this tool to compute the tree hash root of a `BeaconState`. This is most likely only useful
+//! for benchmarking changes to tree hashing and the tree hash cache with tools like `flamegraph`.
+//!
+//! It can load a state from a SSZ file or download it from a beaconAPI.
+//!
+//! The root can be computed in one of three modes:
+//!
+//! - `uncached`: compute the root without the tree hash cache.
+//! - `build`: build the tree hash cache from scratch on each run.
+//! - `update`: build the tree hash cache once before the runs, then update it on each run.
+//!
+//! Logging output is controlled via the `RUST_LOG` environment variable. For example, `export
+//! RUST_LOG=debug`.
+//!
+//! ## Examples
+//!
+//! Download a state and build the tree hash cache 100 times.
+//!
+//! ```ignore
+//! lcli state-root \
+//!     --beacon-url http://localhost:5052 \
+//!     --state-id head \
+//!     --mode build \
+//!     --runs 100
+//! ```
+//!
+//! Load a state from SSZ and compute the root once without the tree hash cache.
+//!
+//! ```ignore
+//! lcli state-root \
+//!     --state-path /tmp/state.ssz \
+//!     --runs 1
+//! ```
+use crate::stats::DurationStats;
+use crate::transition_blocks::load_from_ssz_with;
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use environment::Environment;
+use eth2::{types::StateId, BeaconNodeHttpClient, SensitiveUrl, Timeouts};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use types::{BeaconState, EthSpec};
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub fn run<T: EthSpec>(env: Environment<T>, matches: &ArgMatches) -> Result<(), String> {
+    let spec = &T::default_spec();
+    let executor = env.core_context().executor;
+
+    /*
+     * Parse (most) CLI arguments.
+     */
+
+    let state_path: Option<PathBuf> = parse_optional(matches, "state-path")?;
+    let beacon_url: Option<SensitiveUrl> = parse_optional(matches, "beacon-url")?;
+    let mode: String = parse_required(matches, "mode")?;
+    let runs: usize = parse_required(matches, "runs")?;
+
+    info!("Using {} spec", T::spec_name());
+    info!("Doing {} runs in {} mode", runs, mode);
+
+    /*
+     * Load the state from disk or beaconAPI URL.
+     */
+
+    let mut state: BeaconState<T> = match (state_path, beacon_url) {
+        (Some(state_path), None) => {
+            info!("State path: {:?}", state_path);
+            load_from_ssz_with(&state_path, spec, BeaconState::from_ssz_bytes)?
+        }
+        (None, Some(beacon_url)) => {
+            let state_id: StateId = parse_required(matches, "state-id")?;
+            let client = BeaconNodeHttpClient::new(beacon_url, Timeouts::set_all(HTTP_TIMEOUT));
+            executor
+                .handle()
+                .ok_or("shutdown in progress")?
+                .block_on(async move {
+                    let state = client
+                        .get_debug_beacon_states::<T>(state_id)
+                        .await
+                        .map_err(|e| format!("Failed to download state: {:?}", e))?
+                        .ok_or_else(|| format!("Unable to locate state at {:?}", state_id))?
+                        .data;
+                    Ok::<_, String>(state)
+                })
+                .map_err(|e| format!("Failed to complete task: {:?}", e))?
+        }
+        _ => return Err("must supply --state-path *or* --beacon-url".into()),
+    };
+
+    if mode == "update" {
+        let start = Instant::now();
+        state
+            .update_tree_hash_cache()
+            .map_err(|e| format!("Unable to build tree hash cache: {:?}", e))?;
+        info!("Built tree hash cache in {:?}", start.elapsed());
+    }
+
+    /*
+     * Perform the core "runs".
+     */
+
+    let mut state_root = None;
+    let mut durations = Vec::with_capacity(runs);
+    for i in 0..runs {
+        if mode == "build" {
+            state.drop_tree_hash_cache();
+        }
+
+        let start = Instant::now();
+
+        state_root = Some(match mode.as_str() {
+            "uncached" => state.canonical_root(),
+            "build" | "update" => state
+                .update_tree_hash_cache()
+                .map_err(|e| format!("Unable to update tree hash cache: {:?}", e))?,
+            other => return Err(format!("Unknown mode: {}", other)),
+        });
+
+        let duration = Instant::now().duration_since(start);
+        info!("Run {}: {:?}", i, duration);
+        durations.push(duration);
+    }
+
+    if let Some(state_root) = state_root {
+        info!("State root is {:?}", state_root);
+    }
+    if let Some(stats) = DurationStats::new(&durations) {
+        info!("Total: {}", stats);
+    }
+
+    Ok(())
+}
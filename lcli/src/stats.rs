@@ -0,0 +1,54 @@
+//! Summary statistics for the timings reported by benchmarking commands.
+use std::fmt;
+use std::time::Duration;
+
+/// Summary of a set of durations, with percentiles computed using the nearest-rank method.
+#[derive(Debug, Clone, Copy)]
+pub struct DurationStats {
+    /// The number of durations, e.g. runs or slots depending on what was timed.
+    pub count: usize,
+    pub mean: Duration,
+    pub min: Duration,
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+impl DurationStats {
+    /// Compute the statistics of `durations`, returning `None` if it is empty.
+    pub fn new(durations: &[Duration]) -> Option<Self> {
+        if durations.is_empty() {
+            return None;
+        }
+
+        let mut sorted = durations.to_vec();
+        sorted.sort();
+
+        let count = sorted.len();
+        let percentile = |p: usize| {
+            let rank = (p * count + 99) / 100;
+            sorted[rank.saturating_sub(1)]
+        };
+
+        Some(Self {
+            count,
+            mean: sorted.iter().sum::<Duration>() / count as u32,
+            min: sorted[0],
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: sorted[count - 1],
+        })
+    }
+}
+
+impl fmt::Display for DurationStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "mean {:?}, min {:?}, p50 {:?}, p90 {:?}, p99 {:?}, max {:?} ({} samples)",
+            self.mean, self.min, self.p50, self.p90, self.p99, self.max, self.count
+        )
+    }
+}
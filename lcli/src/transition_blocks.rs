@@ -4,7 +4,13 @@
 //! troubleshooting consensus failures.
 //!
 //! It can load states and blocks from file or pull them from a beaconAPI. Objects pulled from a
-//! beaconAPI can be saved to disk to reduce future calls to that server.
+//! beaconAPI can be saved to disk to reduce future calls to that server. A block can be selected
+//! from a beaconAPI by root or by slot (or any other block identifier), and its pre-state is the
+//! post-state of its parent block.
+//!
+//! The time taken by each stage of the transition (cache builds, tree hashing, slot processing,
+//! signature verification and block processing) is recorded for every run, and summarised once
+//! all runs are complete.
 //!
 //! Logging output is controlled via the `RUST_LOG` environment variable. For example, `export
 //! RUST_LOG=debug`.
@@ -61,6 +67,7 @@
 //!     --exclude-cache-builds \
 //!     --exclude-post-block-thc
 //! ```
+use crate::stats::DurationStats;
 use beacon_chain::{
     test_utils::EphemeralHarnessType, validator_pubkey_cache::ValidatorPubkeyCache,
 };
@@ -87,6 +94,37 @@ use types::{BeaconState, ChainSpec, CloneConfig, EthSpec, Hash256, SignedBeaconB
 
 const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
 
+/// Time taken by each stage of a single run, in order of execution.
+#[derive(Debug, Default)]
+pub struct StageTimings {
+    stages: Vec<(&'static str, Duration)>,
+}
+
+impl StageTimings {
+    /// Record that `stage` took `duration`.
+    pub fn record(&mut self, stage: &'static str, duration: Duration) {
+        debug!("{}: {:?}", stage, duration);
+        self.stages.push((stage, duration));
+    }
+
+    /// Log statistics of the time taken by each stage across all `runs`.
+    pub fn summarise(runs: &[StageTimings]) {
+        let mut stages: Vec<(&'static str, Vec<Duration>)> = vec![];
+        for (stage, duration) in runs.iter().flat_map(|timings| &timings.stages) {
+            match stages.iter_mut().find(|(name, _)| *name == *stage) {
+                Some((_, durations)) => durations.push(*duration),
+                None => stages.push((*stage, vec![*duration])),
+            }
+        }
+
+        for (stage, durations) in stages {
+            if let Some(stats) = DurationStats::new(&durations) {
+                info!("{}: {}", stage, stats);
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 struct Config {
     no_signature_verification: bool,
@@ -226,9 +264,11 @@ pub fn run<T: EthSpec>(env: Environment<T>, matches: &ArgMatches) -> Result<(),
      */
 
     let mut output_post_state = None;
+    let mut run_timings = Vec::with_capacity(runs);
     for i in 0..runs {
         let pre_state = pre_state.clone_with(CloneConfig::all());
         let block = block.clone();
+        let mut timings = StageTimings::default();
 
         let start = Instant::now();
 
@@ -239,17 +279,25 @@ pub fn run<T: EthSpec>(env: Environment<T>, matches: &ArgMatches) -> Result<(),
             state_root_opt,
             &config,
             &validator_pubkey_cache,
+            &mut timings,
             spec,
         )?;
 
         let duration = Instant::now().duration_since(start);
         info!("Run {}: {:?}", i, duration);
+        timings.stages.push(("Total", duration));
+        run_timings.push(timings);
 
         if output_post_state.is_none() {
             output_post_state = Some(post_state)
         }
     }
 
+    if !run_timings.is_empty() {
+        info!("Block {:?} at slot {}", block_root, block.slot());
+        StageTimings::summarise(&run_timings);
+    }
+
     /*
      * Write artifacts to disk, if required.
      */
@@ -298,6 +346,7 @@ fn do_transition<T: EthSpec>(
     mut state_root_opt: Option<Hash256>,
     config: &Config,
     validator_pubkey_cache: &ValidatorPubkeyCache<EphemeralHarnessType<T>>,
+    timings: &mut StageTimings,
     spec: &ChainSpec,
 ) -> Result<BeaconState<T>, String> {
     if !config.exclude_cache_builds {
@@ -305,13 +354,13 @@ fn do_transition<T: EthSpec>(
         pre_state
             .build_caches(spec)
             .map_err(|e| format!("Unable to build caches: {:?}", e))?;
-        debug!("Build caches: {:?}", t.elapsed());
+        timings.record("Build caches", t.elapsed());
 
         let t = Instant::now();
         let state_root = pre_state
             .update_tree_hash_cache()
             .map_err(|e| format!("Unable to build tree hash cache: {:?}", e))?;
-        debug!("Initial tree hash: {:?}", t.elapsed());
+        timings.record("Initial tree hash", t.elapsed());
 
         if state_root_opt.map_or(false, |expected| expected != state_root) {
             return Err(format!(
@@ -331,13 +380,13 @@ fn do_transition<T: EthSpec>(
         per_slot_processing(&mut pre_state, Some(state_root), spec)
             .map_err(|e| format!("Failed to advance slot on iteration {}: {:?}", i, e))?;
     }
-    debug!("Slot processing: {:?}", t.elapsed());
+    timings.record("Slot processing", t.elapsed());
 
     let t = Instant::now();
     pre_state
         .build_caches(spec)
         .map_err(|e| format!("Unable to build caches: {:?}", e))?;
-    debug!("Build all caches (again): {:?}", t.elapsed());
+    timings.record("Build all caches (again)", t.elapsed());
 
     let mut ctxt = ConsensusContext::new(pre_state.slot())
         .set_current_block_root(block_root)
@@ -367,7 +416,7 @@ fn do_transition<T: EthSpec>(
             spec,
         )
         .map_err(|e| format!("Invalid block signature: {:?}", e))?;
-        debug!("Batch verify block signatures: {:?}", t.elapsed());
+        timings.record("Batch verify block signatures", t.elapsed());
 
         // Signature verification should prime the indexed attestation cache.
         assert_eq!(
@@ -387,14 +436,14 @@ fn do_transition<T: EthSpec>(
         spec,
     )
     .map_err(|e| format!("State transition failed: {:?}", e))?;
-    debug!("Process block: {:?}", t.elapsed());
+    timings.record("Process block", t.elapsed());
 
     if !config.exclude_post_block_thc {
         let t = Instant::now();
         pre_state
             .update_tree_hash_cache()
             .map_err(|e| format!("Unable to build tree hash cache: {:?}", e))?;
-        debug!("Post-block tree hash: {:?}", t.elapsed());
+        timings.record("Post-block tree hash", t.elapsed());
     }
 
     Ok(pre_state)
@@ -20,6 +20,7 @@ use std::fs::create_dir_all;
 use std::io::{Result as IOResult, Write};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use task_executor::{ShutdownReason, TaskExecutor};
 use tokio::runtime::{Builder as RuntimeBuilder, Runtime};
 use types::{EthSpec, GnosisEthSpec, MainnetEthSpec, MinimalEthSpec};
@@ -46,6 +47,9 @@ const MAXIMUM_SHUTDOWN_TIME: u64 = 15;
 /// - `path` == None,
 /// - `max_log_size` == 0,
 /// - `max_log_number` == 0,
+///
+/// If `rotation_interval` is set the log file is also rotated at that interval, regardless of its
+/// size.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoggerConfig {
     pub path: Option<PathBuf>,
@@ -58,6 +62,7 @@ pub struct LoggerConfig {
     pub max_log_size: u64,
     pub max_log_number: usize,
     pub compression: bool,
+    pub rotation_interval: Option<Duration>,
     pub is_restricted: bool,
     pub sse_logging: bool,
 }
@@ -74,6 +79,7 @@ impl Default for LoggerConfig {
             max_log_size: 200,
             max_log_number: 5,
             compression: false,
+            rotation_interval: None,
             is_restricted: true,
             sse_logging: false,
         }
@@ -297,21 +303,44 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
             unknown => return Err(format!("Unknown loglevel-debug-level: {}", unknown)),
         };
 
-        let file_logger = FileLoggerBuilder::new(&path)
-            .level(logfile_level)
-            .channel_size(LOG_CHANNEL_SIZE)
-            .format(match config.logfile_format.as_deref() {
-                Some("JSON") => Format::Json,
-                _ => Format::default(),
-            })
-            .rotate_size(config.max_log_size)
-            .rotate_keep(config.max_log_number)
-            .rotate_compress(config.compression)
-            .restrict_permissions(config.is_restricted)
-            .build()
-            .map_err(|e| format!("Unable to build file logger: {}", e))?;
+        let logfile_format = match config.logfile_format.as_deref().map(str::to_uppercase) {
+            Some(format) if format == "JSON" => Format::Json,
+            Some(format) if format != "DEFAULT" => {
+                return Err(format!("Unknown logfile-format: {}", format))
+            }
+            _ => Format::default(),
+        };
 
-        let mut log = Logger::root(Duplicate::new(stdout_logger, file_logger).fuse(), o!());
+        let file_logger_path = path.clone();
+        let build_file_logger = move || {
+            FileLoggerBuilder::new(&file_logger_path)
+                .level(logfile_level)
+                .channel_size(LOG_CHANNEL_SIZE)
+                .format(logfile_format)
+                .rotate_size(config.max_log_size)
+                .rotate_keep(config.max_log_number)
+                .rotate_compress(config.compression)
+                .restrict_permissions(config.is_restricted)
+                .build()
+                .map_err(|e| format!("Unable to build file logger: {}", e))
+        };
+
+        let mut log = if let Some(interval) = config.rotation_interval {
+            let file_drain = logging::TimeRotatingDrain::new(
+                logging::TimeRotationConfig {
+                    path: path.clone(),
+                    interval,
+                    keep: config.max_log_number,
+                    compress: config.compression,
+                    restrict_permissions: config.is_restricted,
+                },
+                build_file_logger,
+            )?;
+            Logger::root(Duplicate::new(stdout_logger, file_drain).fuse(), o!())
+        } else {
+            let file_logger = build_file_logger()?;
+            Logger::root(Duplicate::new(stdout_logger, file_logger).fuse(), o!())
+        };
 
         info!(
             log,
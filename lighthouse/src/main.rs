@@ -1,9 +1,12 @@
 mod metrics;
+mod tokio_console;
 
 use beacon_node::ProductionBeaconNode;
 use clap::{App, Arg, ArgMatches};
 use clap_utils::{flags::DISABLE_MALLOC_TUNING_FLAG, get_eth2_network_config};
-use directory::{parse_path_or_default, DEFAULT_BEACON_NODE_DIR, DEFAULT_VALIDATOR_DIR};
+use directory::{
+    parse_path_or_default, DEFAULT_BEACON_NODE_DIR, DEFAULT_SLASHER_DIR, DEFAULT_VALIDATOR_DIR,
+};
 use env_logger::{Builder, Env};
 use environment::{EnvironmentBuilder, LoggerConfig};
 use eth2_network_config::{Eth2NetworkConfig, DEFAULT_HARDCODED_NETWORK, HARDCODED_NET_NAMES};
@@ -13,9 +16,11 @@ use malloc_utils::configure_memory_allocator;
 use slog::{crit, info, warn};
 use std::path::PathBuf;
 use std::process::exit;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use task_executor::ShutdownReason;
 use types::{EthSpec, EthSpecId};
-use validator_client::ProductionValidatorClient;
+use validator_client::{GracefulShutdown, ProductionValidatorClient};
 
 fn bls_library_name() -> &'static str {
     if cfg!(feature = "portable") {
@@ -70,13 +75,12 @@ fn main() {
                  SHA256 hardware acceleration: {}\n\
                  Allocator: {}\n\
                  Profile: {}\n\
-                 Specs: mainnet (true), minimal ({}), gnosis ({})",
+                 Specs: mainnet (true), minimal (true), gnosis ({})",
                  VERSION.replace("Lighthouse/", ""),
                  bls_library_name(),
                  have_sha_extensions(),
                  allocator_name(),
                  build_profile_name(),
-                 cfg!(feature = "spec-minimal"),
                  cfg!(feature = "gnosis"),
             ).as_str()
         )
@@ -124,8 +128,11 @@ fn main() {
             Arg::with_name("logfile-format")
                 .long("logfile-format")
                 .value_name("FORMAT")
-                .help("Specifies the log format used when emitting logs to the logfile.")
+                .help("Specifies the log format used when emitting logs to the logfile. The JSON \
+                    format writes one object per line with the message, level, timestamp and \
+                    structured fields (e.g. `slot`, `block_root`, `peer_id`) as keys.")
                 .possible_values(&["DEFAULT", "JSON"])
+                .case_insensitive(true)
                 .takes_value(true)
                 .global(true)
         )
@@ -151,6 +158,17 @@ fn main() {
                 .default_value("5")
                 .global(true),
         )
+        .arg(
+            Arg::with_name("logfile-rotation-interval")
+                .long("logfile-rotation-interval")
+                .value_name("HOURS")
+                .help(
+                    "If set, the log file is also rotated every HOURS hours, regardless of its \
+                    size. Rotated files are suffixed with a timestamp and at most \
+                    `--logfile-max-number` of them are kept.")
+                .takes_value(true)
+                .global(true),
+        )
         .arg(
             Arg::with_name("logfile-compress")
                 .long("logfile-compress")
@@ -175,9 +193,22 @@ fn main() {
                 .value_name("FORMAT")
                 .help("Specifies the log format used when emitting logs to the terminal.")
                 .possible_values(&["JSON"])
+                .case_insensitive(true)
                 .takes_value(true)
                 .global(true),
         )
+        .arg(
+            Arg::with_name("tokio-console")
+                .long("tokio-console")
+                .help(
+                    "Serve async task diagnostics to `tokio-console` on port 6669. If the \
+                    LIGHTHOUSE_TRACING environment variable is set (e.g. \
+                    `LIGHTHOUSE_TRACING=beacon_chain=debug`), the duration of matching `tracing` \
+                    spans is also printed to stderr. Requires Lighthouse to be built with the \
+                    `tokio-console` feature.")
+                .takes_value(false)
+                .global(true),
+        )
         .arg(
             Arg::with_name("log-color")
                 .long("log-color")
@@ -324,11 +355,53 @@ fn main() {
                 .takes_value(true)
                 .global(true)
         )
+        .arg(
+            Arg::with_name("fork-epoch")
+                .long("fork-epoch")
+                .value_name("NAME=EPOCH")
+                .help("Overrides the epoch at which the named fork activates in the loaded \
+                       network config, e.g. `capella=100`. May be supplied multiple times. \
+                       Intended for devnets and shadow forks, incorrect use of this flag will \
+                       cause your node to experience a consensus failure.")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .global(true)
+        )
+        .arg(
+            Arg::with_name("altair-fork-epoch")
+                .long("altair-fork-epoch")
+                .value_name("EPOCH")
+                .help("Overrides the ALTAIR_FORK_EPOCH of the loaded network config. Takes \
+                       precedence over --fork-epoch. Intended for devnets and shadow forks.")
+                .takes_value(true)
+                .global(true)
+        )
+        .arg(
+            Arg::with_name("bellatrix-fork-epoch")
+                .long("bellatrix-fork-epoch")
+                .value_name("EPOCH")
+                .help("Overrides the BELLATRIX_FORK_EPOCH of the loaded network config. Takes \
+                       precedence over --fork-epoch. Intended for devnets and shadow forks.")
+                .takes_value(true)
+                .global(true)
+        )
+        .arg(
+            Arg::with_name("capella-fork-epoch")
+                .long("capella-fork-epoch")
+                .value_name("EPOCH")
+                .help("Overrides the CAPELLA_FORK_EPOCH of the loaded network config. Takes \
+                       precedence over --fork-epoch. Intended for devnets and shadow forks.")
+                .takes_value(true)
+                .global(true)
+        )
         .subcommand(beacon_node::cli_app())
         .subcommand(boot_node::cli_app())
         .subcommand(validator_client::cli_app())
         .subcommand(account_manager::cli_app())
         .subcommand(database_manager::cli_app())
+        .subcommand(validator_manager::cli_app())
+        .subcommand(standalone_slasher::cli_app())
         .get_matches();
 
     // Configure the allocator early in the process, before it has the chance to use the default values for
@@ -353,6 +426,14 @@ fn main() {
         Builder::from_env(Env::default()).init();
     }
 
+    // Diagnostics for async tasks and `tracing` spans.
+    if matches.is_present("tokio-console") {
+        if let Err(e) = tokio_console::init() {
+            eprintln!("Unable to start tokio-console server: {}", e);
+            exit(1)
+        }
+    }
+
     let result = get_eth2_network_config(&matches).and_then(|eth2_network_config| {
         let eth_spec_id = eth2_network_config.eth_spec_id()?;
 
@@ -379,9 +460,8 @@ fn main() {
             EthSpecId::Mainnet => run(EnvironmentBuilder::mainnet(), &matches, eth2_network_config),
             #[cfg(feature = "gnosis")]
             EthSpecId::Gnosis => run(EnvironmentBuilder::gnosis(), &matches, eth2_network_config),
-            #[cfg(feature = "spec-minimal")]
             EthSpecId::Minimal => run(EnvironmentBuilder::minimal(), &matches, eth2_network_config),
-            #[cfg(not(all(feature = "spec-minimal", feature = "gnosis")))]
+            #[cfg(not(feature = "gnosis"))]
             other => {
                 eprintln!(
                     "Eth spec `{}` is not supported by this build of Lighthouse",
@@ -450,6 +530,12 @@ fn run<E: EthSpec>(
         .parse()
         .map_err(|e| format!("Failed to parse `logfile-max-number`: {:?}", e))?;
 
+    let logfile_rotation_interval: Option<u64> =
+        clap_utils::parse_optional(matches, "logfile-rotation-interval")?;
+    if logfile_rotation_interval == Some(0) {
+        return Err("--logfile-rotation-interval must be greater than zero".to_string());
+    }
+
     let logfile_compress = matches.is_present("logfile-compress");
 
     let logfile_restricted = !matches.is_present("logfile-no-restricted-perms");
@@ -479,6 +565,13 @@ fn run<E: EthSpec>(
                         .with_extension("log"),
                 )
             }
+            (standalone_slasher::CMD, _) => Some(
+                parse_path_or_default(matches, "datadir")?
+                    .join(DEFAULT_SLASHER_DIR)
+                    .join("logs")
+                    .join("slasher")
+                    .with_extension("log"),
+            ),
             _ => None,
         };
     }
@@ -504,6 +597,8 @@ fn run<E: EthSpec>(
         max_log_size: logfile_max_size * 1_024 * 1_024,
         max_log_number: logfile_max_number,
         compression: logfile_compress,
+        rotation_interval: logfile_rotation_interval
+            .map(|hours| Duration::from_secs(hours * 60 * 60)),
         is_restricted: logfile_restricted,
         sse_logging,
     };
@@ -576,6 +671,15 @@ fn run<E: EthSpec>(
         return Ok(());
     }
 
+    if let Some(sub_matches) = matches.subcommand_matches(validator_manager::CMD) {
+        eprintln!("Running validator manager for {} network", network_name);
+        // Pass the entire `environment` to the validator manager so it can run blocking operations.
+        validator_manager::run(sub_matches, environment)?;
+
+        // Exit as soon as validator manager returns control.
+        return Ok(());
+    }
+
     info!(log, "Lighthouse started"; "version" => VERSION);
     info!(
         log,
@@ -583,6 +687,10 @@ fn run<E: EthSpec>(
         "name" => &network_name
     );
 
+    // Populated by the validator client once it has started, so that in-flight duties can be
+    // drained before the rest of the process is shut down.
+    let graceful_shutdown: Arc<Mutex<Option<GracefulShutdown>>> = <_>::default();
+
     match matches.subcommand() {
         ("beacon_node", Some(matches)) => {
             let context = environment.core_context();
@@ -621,11 +729,19 @@ fn run<E: EthSpec>(
             // Dump configs if `dump-config` or `dump-chain-config` flags are set
             clap_utils::check_dump_configs::<_, E>(matches, &config, &context.eth2_config.spec)?;
             if !shutdown_flag {
+                let graceful_shutdown = graceful_shutdown.clone();
                 executor.clone().spawn(
                     async move {
                         if let Err(e) = ProductionValidatorClient::new(context, config)
                             .await
-                            .and_then(|mut vc| vc.start_service())
+                            .and_then(|mut vc| {
+                                vc.start_service()?;
+                                *graceful_shutdown
+                                    .lock()
+                                    .map_err(|_| "Graceful shutdown lock poisoned")? =
+                                    Some(vc.graceful_shutdown());
+                                Ok(())
+                            })
                         {
                             crit!(log, "Failed to start validator client"; "reason" => e);
                             // Ignore the error since it always occurs during normal operation when
@@ -643,6 +759,35 @@ fn run<E: EthSpec>(
                 ));
             }
         }
+        (standalone_slasher::CMD, Some(matches)) => {
+            let context = environment.core_context();
+            let log = context.log().clone();
+            let executor = context.executor.clone();
+            let config = standalone_slasher::Config::from_cli(matches)
+                .map_err(|e| format!("Unable to initialize slasher config: {}", e))?;
+            let shutdown_flag = matches.is_present("immediate-shutdown");
+            // Dump configs if `dump-config` or `dump-chain-config` flags are set
+            clap_utils::check_dump_configs::<_, E>(matches, &config, &context.eth2_config.spec)?;
+            if !shutdown_flag {
+                executor.clone().spawn(
+                    async move {
+                        if let Err(e) = standalone_slasher::start(context, config).await {
+                            crit!(log, "Failed to start slasher"; "reason" => e);
+                            // Ignore the error since it always occurs during normal operation when
+                            // shutting down.
+                            let _ = executor
+                                .shutdown_sender()
+                                .try_send(ShutdownReason::Failure("Failed to start slasher"));
+                        }
+                    },
+                    "standalone_slasher",
+                );
+            } else {
+                let _ = executor.shutdown_sender().try_send(ShutdownReason::Success(
+                    "Slasher immediate shutdown triggered.",
+                ));
+            }
+        }
         _ => {
             crit!(log, "No subcommand supplied. See --help .");
             return Err("No subcommand supplied.".into());
@@ -653,6 +798,16 @@ fn run<E: EthSpec>(
     let shutdown_reason = environment.block_until_shutdown_requested()?;
     info!(log, "Shutting down.."; "reason" => ?shutdown_reason);
 
+    // Give the validator client a chance to finish any duties it has already started before
+    // signalling the remaining tasks to exit.
+    let graceful_shutdown = graceful_shutdown
+        .lock()
+        .ok()
+        .and_then(|mut graceful_shutdown| graceful_shutdown.take());
+    if let Some(graceful_shutdown) = graceful_shutdown {
+        environment.runtime().block_on(graceful_shutdown.run());
+    }
+
     environment.fire_signal();
 
     // Shutdown the environment once all tasks have completed.
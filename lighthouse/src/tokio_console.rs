@@ -0,0 +1,41 @@
+//! Serves async task diagnostics to `tokio-console` and optionally prints the duration of
+//! `tracing` spans, for diagnosing stalled tasks and lock contention.
+//!
+//! Only available when compiled with the `tokio-console` feature and `--cfg tokio_unstable`.
+
+/// Environment variable containing a `tracing` filter for spans which should be printed to stderr.
+#[cfg(feature = "tokio-console")]
+const TRACING_FILTER_ENV: &str = "LIGHTHOUSE_TRACING";
+
+#[cfg(feature = "tokio-console")]
+pub fn init() -> Result<(), String> {
+    use tracing_subscriber::{fmt::format::FmtSpan, prelude::*, EnvFilter};
+
+    let span_durations = match std::env::var(TRACING_FILTER_ENV) {
+        Ok(filter) => {
+            let filter = EnvFilter::try_new(&filter)
+                .map_err(|e| format!("Invalid {}: {:?}", TRACING_FILTER_ENV, e))?;
+            Some(
+                tracing_subscriber::fmt::layer()
+                    .with_writer(std::io::stderr)
+                    .with_span_events(FmtSpan::CLOSE)
+                    .with_filter(filter),
+            )
+        }
+        Err(_) => None,
+    };
+
+    let subscriber = tracing_subscriber::registry()
+        .with(console_subscriber::spawn())
+        .with(span_durations);
+
+    // Avoid `SubscriberInitExt::init`, which would conflict with the `log` logger installed by
+    // `--env_log`.
+    tracing::subscriber::set_global_default(subscriber)
+        .map_err(|e| format!("Unable to set tracing subscriber: {:?}", e))
+}
+
+#[cfg(not(feature = "tokio-console"))]
+pub fn init() -> Result<(), String> {
+    Err("Lighthouse was not built with the `tokio-console` feature".to_string())
+}
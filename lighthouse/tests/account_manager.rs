@@ -3,6 +3,7 @@ use account_manager::{
         create::*,
         import::{self, CMD as IMPORT_CMD},
         modify::{ALL, CMD as MODIFY_CMD, DISABLE, ENABLE, PUBKEY_FLAG},
+        recover::{self, CMD as RECOVER_CMD, FIRST_INDEX_FLAG},
         CMD as VALIDATOR_CMD,
     },
     wallet::{
@@ -399,6 +400,105 @@ fn validator_create() {
     assert_eq!(dir_validator_count(validator_dir.path()), 6);
 }
 
+#[test]
+fn validator_create_offline() {
+    let base_dir = tempdir().unwrap();
+    let validator_dir = tempdir().unwrap();
+    let secrets_dir = tempdir().unwrap();
+    let deposit_dir = tempdir().unwrap();
+
+    let wallet = TestWallet::new(base_dir.path(), "wally");
+    wallet.create_expect_success();
+
+    let create_cmd = || {
+        let mut cmd = validator_cmd();
+        cmd.arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(validator_dir.path().as_os_str())
+            .arg(CREATE_CMD)
+            .arg(format!("--{}", OFFLINE_FLAG))
+            .arg(format!("--{}", WALLETS_DIR_FLAG))
+            .arg(wallet.base_dir().into_os_string())
+            .arg(format!("--{}", WALLET_NAME_FLAG))
+            .arg(&wallet.name)
+            .arg(format!("--{}", WALLET_PASSWORD_FLAG))
+            .arg(wallet.password_path().into_os_string())
+            .arg(format!("--{}", SECRETS_DIR_FLAG))
+            .arg(secrets_dir.path().as_os_str())
+            .arg(format!("--{}", COUNT_FLAG))
+            .arg("2");
+        cmd
+    };
+
+    // Creating validators offline without somewhere to write the deposits should fail.
+    output_result(&mut create_cmd()).unwrap_err();
+    assert_eq!(dir_validator_count(validator_dir.path()), 0);
+
+    output_result(
+        create_cmd()
+            .arg(format!("--{}", DEPOSIT_OUTPUT_DIR_FLAG))
+            .arg(deposit_dir.path().as_os_str()),
+    )
+    .unwrap();
+    assert_eq!(dir_validator_count(validator_dir.path()), 2);
+
+    // Both deposit files should contain an entry for each validator.
+    let mut file_names = fs::read_dir(deposit_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    file_names.sort();
+    assert_eq!(file_names.len(), 2);
+    assert!(file_names[0].starts_with("deposit_data-"));
+    assert!(file_names[1].starts_with("deposit_transactions-"));
+    for file_name in file_names {
+        let file = File::open(deposit_dir.path().join(&file_name)).unwrap();
+        let deposits: Vec<serde_json::Value> = serde_json::from_reader(file).unwrap();
+        assert_eq!(deposits.len(), 2);
+
+        // The launchpad deposit data should report the version of Lighthouse that created it.
+        if file_name.starts_with("deposit_data-") {
+            for deposit in deposits {
+                assert_eq!(
+                    deposit["deposit_cli_version"],
+                    lighthouse_version::VERSION.trim_start_matches("Lighthouse/v")
+                );
+            }
+        }
+    }
+
+    // Commands which aren't known to be safe offline should be refused.
+    let exit_dir = deposit_dir.path().join("exits");
+    let network_cmds: [&[&str]; 3] = [
+        &["exit", "--all"],
+        &[
+            "exit-export",
+            "--epoch",
+            "0",
+            "--output-dir",
+            exit_dir.to_str().unwrap(),
+        ],
+        &[
+            "bls-change",
+            "--validator-index",
+            "0",
+            "--execution-address",
+            "0x0000000000000000000000000000000000000000",
+        ],
+    ];
+    for args in network_cmds {
+        let cmd = args[0];
+        let err = output_result(
+            validator_cmd()
+                .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+                .arg(validator_dir.path().as_os_str())
+                .arg(format!("--{}", OFFLINE_FLAG))
+                .args(args),
+        )
+        .unwrap_err();
+        assert!(err.contains("requires network access"), "{}: {}", cmd, err);
+    }
+}
+
 #[test]
 fn validator_import_launchpad() {
     const PASSWORD: &str = "cats";
@@ -492,6 +592,7 @@ fn validator_import_launchpad() {
         suggested_fee_recipient: None,
         gas_limit: None,
         builder_proposals: None,
+        builder_boost_factor: None,
         voting_public_key: keystore.public_key().unwrap(),
         signing_definition: SigningDefinition::LocalKeystore {
             voting_keystore_path,
@@ -614,6 +715,7 @@ fn validator_import_launchpad_no_password_then_add_password() {
         suggested_fee_recipient: None,
         gas_limit: None,
         builder_proposals: None,
+        builder_boost_factor: None,
         voting_public_key: keystore.public_key().unwrap(),
         signing_definition: SigningDefinition::LocalKeystore {
             voting_keystore_path,
@@ -640,6 +742,7 @@ fn validator_import_launchpad_no_password_then_add_password() {
         suggested_fee_recipient: None,
         gas_limit: None,
         builder_proposals: None,
+        builder_boost_factor: None,
         voting_public_key: keystore.public_key().unwrap(),
         signing_definition: SigningDefinition::LocalKeystore {
             voting_keystore_path: dst_keystore_dir.join(KEYSTORE_NAME),
@@ -742,6 +845,7 @@ fn validator_import_launchpad_password_file() {
         suggested_fee_recipient: None,
         gas_limit: None,
         builder_proposals: None,
+        builder_boost_factor: None,
         signing_definition: SigningDefinition::LocalKeystore {
             voting_keystore_path,
             voting_keystore_password_path: None,
@@ -755,6 +859,194 @@ fn validator_import_launchpad_password_file() {
     );
 }
 
+#[test]
+fn validator_import_launchpad_password_file_parallel_resume() {
+    const PASSWORD: &str = "cats";
+    const PASSWORD_FILE_NAME: &str = "pw_is_cats.txt";
+    const NUM_KEYSTORES: usize = 3;
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystores = (0..NUM_KEYSTORES)
+        .map(|i| {
+            let keypair = Keypair::random();
+            let keystore = KeystoreBuilder::new(&keypair, PASSWORD.as_bytes(), "".into())
+                .unwrap()
+                .build()
+                .unwrap();
+            let keystore_name = format!("keystore-m_12381_3600_{}_0_0-1595406747.json", i);
+
+            File::create(src_dir.path().join(&keystore_name))
+                .map(|mut file| keystore.to_json_writer(&mut file).unwrap())
+                .unwrap();
+
+            (keystore_name, keystore)
+        })
+        .collect::<Vec<_>>();
+
+    // Simulate an interrupted import by copying the first keystore into the validator directory
+    // without adding it to the validator definitions.
+    let (interrupted_name, interrupted_keystore) = &keystores[0];
+    let interrupted_dir = dst_dir
+        .path()
+        .join(format!("0x{}", interrupted_keystore.pubkey()));
+    fs::create_dir_all(&interrupted_dir).unwrap();
+    fs::copy(
+        src_dir.path().join(interrupted_name),
+        interrupted_dir.join(interrupted_name),
+    )
+    .unwrap();
+
+    File::create(src_dir.path().join(PASSWORD_FILE_NAME))
+        .map(|mut file| file.write(PASSWORD.as_ref()))
+        .unwrap()
+        .unwrap();
+
+    output_result(
+        validator_cmd()
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(IMPORT_CMD)
+            .arg(format!("--{}", import::DIR_FLAG))
+            .arg(src_dir.path().as_os_str())
+            .arg(format!("--{}", import::REUSE_PASSWORD_FLAG))
+            .arg(format!("--{}", PASSWORD_FLAG))
+            .arg(src_dir.path().join(PASSWORD_FILE_NAME).as_os_str())
+            .arg(format!("--{}", import::WORKERS_FLAG))
+            .arg("2"),
+    )
+    .unwrap();
+
+    // All validators should be registered with slashing protection.
+    check_slashing_protection(
+        &dst_dir,
+        keystores
+            .iter()
+            .map(|(_, keystore)| keystore.public_key().unwrap()),
+    );
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    assert_eq!(defs.as_slice().len(), NUM_KEYSTORES);
+
+    for (keystore_name, keystore) in &keystores {
+        let def = defs
+            .as_slice()
+            .iter()
+            .find(|def| def.voting_public_key == keystore.public_key().unwrap())
+            .expect("validator should be imported");
+        assert!(
+            def.signing_definition
+                == SigningDefinition::LocalKeystore {
+                    voting_keystore_path: dst_dir
+                        .path()
+                        .join(format!("0x{}", keystore.pubkey()))
+                        .join(keystore_name),
+                    voting_keystore_password_path: None,
+                    voting_keystore_password: Some(ZeroizeString::from(PASSWORD.to_string())),
+                },
+            "validator defs file should be accurate"
+        );
+    }
+}
+
+#[test]
+fn validator_recover_index_range() {
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon \
+                            abandon abandon abandon about";
+
+    let mnemonic_dir = tempdir().unwrap();
+    let validator_dir = tempdir().unwrap();
+    let secrets_dir = tempdir().unwrap();
+
+    let mnemonic_path = mnemonic_dir.path().join("mnemonic.txt");
+    fs::write(&mnemonic_path, MNEMONIC).unwrap();
+
+    // Recover the given index range, returning the index and pubkey of each recovered validator.
+    let recover = |first_index: u32, count: u32| -> Vec<(u32, String)> {
+        let output = output_result(
+            validator_cmd()
+                .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+                .arg(validator_dir.path().as_os_str())
+                .arg(RECOVER_CMD)
+                .arg(format!("--{}", recover::MNEMONIC_FLAG))
+                .arg(mnemonic_path.as_os_str())
+                .arg(format!("--{}", SECRETS_DIR_FLAG))
+                .arg(secrets_dir.path().as_os_str())
+                .arg(format!("--{}", FIRST_INDEX_FLAG))
+                .arg(first_index.to_string())
+                .arg(format!("--{}", COUNT_FLAG))
+                .arg(count.to_string()),
+        )
+        .unwrap();
+
+        from_utf8(&output.stdout)
+            .expect("stdout is not utf8")
+            .lines()
+            .map(|line| {
+                let fields = line.split('\t').collect::<Vec<_>>();
+                let index = fields[1].trim_start_matches("Index: ").parse().unwrap();
+                (index, fields[2].to_string())
+            })
+            .collect()
+    };
+
+    let recovered = recover(2, 3);
+    assert_eq!(
+        recovered
+            .iter()
+            .map(|(index, _)| *index)
+            .collect::<Vec<_>>(),
+        vec![2, 3, 4]
+    );
+
+    // Recovering an overlapping range should only recover the new validators.
+    let recovered_overlap = recover(3, 3);
+    assert_eq!(
+        recovered_overlap
+            .iter()
+            .map(|(index, _)| *index)
+            .collect::<Vec<_>>(),
+        vec![5]
+    );
+
+    let pubkeys = recovered
+        .iter()
+        .chain(&recovered_overlap)
+        .map(|(_, pubkey)| pubkey.clone())
+        .collect::<Vec<_>>();
+
+    let defs = ValidatorDefinitions::open(&validator_dir).unwrap();
+    assert_eq!(defs.as_slice().len(), pubkeys.len());
+
+    for pubkey in &pubkeys {
+        let def = defs
+            .as_slice()
+            .iter()
+            .find(|def| def.voting_public_key.as_hex_string() == *pubkey)
+            .expect("validator should be registered");
+        assert!(def.enabled);
+        assert!(
+            def.signing_definition
+                == SigningDefinition::LocalKeystore {
+                    voting_keystore_path: ValidatorDir::open(validator_dir.path().join(pubkey))
+                        .unwrap()
+                        .voting_keystore_path(),
+                    voting_keystore_password_path: Some(secrets_dir.path().join(pubkey)),
+                    voting_keystore_password: None,
+                },
+            "validator defs file should be accurate"
+        );
+    }
+
+    check_slashing_protection(
+        &validator_dir,
+        defs.as_slice()
+            .iter()
+            .map(|def| def.voting_public_key.clone()),
+    );
+}
+
 /// Check that all of the given pubkeys have been registered with slashing protection.
 fn check_slashing_protection(validator_dir: &TempDir, pubkeys: impl Iterator<Item = PublicKey>) {
     let slashing_db_path = validator_dir.path().join(SLASHING_PROTECTION_FILENAME);
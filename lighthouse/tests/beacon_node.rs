@@ -17,7 +17,7 @@ use std::string::ToString;
 use std::time::Duration;
 use tempfile::TempDir;
 use types::{
-    Address, Checkpoint, Epoch, ExecutionBlockHash, ForkName, Hash256, MainnetEthSpec,
+    Address, Checkpoint, Epoch, EthSpec, ExecutionBlockHash, ForkName, Hash256, MainnetEthSpec,
     ProgressiveBalancesMode,
 };
 use unused_port::{unused_tcp4_port, unused_tcp6_port, unused_udp4_port, unused_udp6_port};
@@ -142,6 +142,48 @@ fn shuffling_cache_set() {
         .with_config(|config| assert_eq!(config.chain.shuffling_cache_size, 500));
 }
 
+#[test]
+fn observed_attesters_caches_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.chain.observed_gossip_attesters_epochs, 3);
+            assert_eq!(config.chain.observed_gossip_attesters_capacity, 16_384);
+            assert_eq!(config.chain.observed_block_attesters_epochs, 3);
+            assert_eq!(config.chain.observed_block_attesters_capacity, 16_384);
+            assert_eq!(config.chain.observed_aggregators_epochs, 3);
+            assert_eq!(config.chain.observed_aggregators_capacity, 1024);
+        });
+}
+
+#[test]
+fn observed_attesters_caches_set() {
+    CommandLineTest::new()
+        .flag("observed-gossip-attesters-epochs", Some("4"))
+        .flag("observed-gossip-attesters-capacity", Some("1000000"))
+        .flag("observed-block-attesters-epochs", Some("2"))
+        .flag("observed-block-attesters-capacity", Some("64"))
+        .flag("observed-aggregators-epochs", Some("5"))
+        .flag("observed-aggregators-capacity", Some("32"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.chain.observed_gossip_attesters_epochs, 4);
+            assert_eq!(config.chain.observed_gossip_attesters_capacity, 1_000_000);
+            assert_eq!(config.chain.observed_block_attesters_epochs, 2);
+            assert_eq!(config.chain.observed_block_attesters_capacity, 64);
+            assert_eq!(config.chain.observed_aggregators_epochs, 5);
+            assert_eq!(config.chain.observed_aggregators_capacity, 32);
+        });
+}
+
+#[test]
+#[should_panic]
+fn observed_attesters_epochs_zero() {
+    CommandLineTest::new()
+        .flag("observed-gossip-attesters-epochs", Some("0"))
+        .run_with_zero_port();
+}
+
 #[test]
 fn fork_choice_before_proposal_timeout_default() {
     CommandLineTest::new()
@@ -162,6 +204,26 @@ fn fork_choice_before_proposal_timeout_zero() {
         .with_config(|config| assert_eq!(config.chain.fork_choice_before_proposal_timeout_ms, 0));
 }
 
+#[test]
+fn attestation_packing_time_budget_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.attestation_packing_time_budget_ms,
+                beacon_node::beacon_chain::chain_config::DEFAULT_ATTESTATION_PACKING_TIME_BUDGET
+            )
+        });
+}
+
+#[test]
+fn attestation_packing_time_budget_flag() {
+    CommandLineTest::new()
+        .flag("attestation-packing-time-budget", Some("200"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.attestation_packing_time_budget_ms, 200));
+}
+
 #[test]
 fn checkpoint_sync_url_timeout_flag() {
     CommandLineTest::new()
@@ -181,6 +243,28 @@ fn checkpoint_sync_url_timeout_default() {
         });
 }
 
+#[test]
+fn genesis_state_url_timeout_flag() {
+    CommandLineTest::new()
+        .flag("genesis-state-url-timeout", Some("42"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.genesis_state_url, None);
+            assert_eq!(config.genesis_state_url_timeout, Duration::from_secs(42));
+        });
+}
+
+#[test]
+#[should_panic]
+fn genesis_state_url_with_known_genesis() {
+    CommandLineTest::new()
+        .flag(
+            "genesis-state-url",
+            Some("http://localhost:4242/genesis.ssz"),
+        )
+        .run_with_zero_port();
+}
+
 #[test]
 fn prepare_payload_lookahead_default() {
     CommandLineTest::new()
@@ -324,8 +408,15 @@ fn graffiti_flag() {
                 config.graffiti.to_string(),
                 "0x6e6963652d677261666669746900000000000000000000000000000000000000"
             );
+            assert!(config.graffiti_set_explicitly);
         });
 }
+#[test]
+fn graffiti_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.graffiti_set_explicitly));
+}
 
 #[test]
 fn trusted_peers_flag() {
@@ -365,6 +456,30 @@ fn genesis_backfill_with_historic_flag() {
         .with_config(|config| assert_eq!(config.chain.genesis_backfill, true));
 }
 
+#[test]
+fn disable_backfill_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.disable_backfill, false));
+}
+
+#[test]
+fn disable_backfill_flag() {
+    CommandLineTest::new()
+        .flag("disable-backfill", None)
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.disable_backfill, true));
+}
+
+#[test]
+#[should_panic]
+fn disable_backfill_with_genesis_backfill_flag() {
+    CommandLineTest::new()
+        .flag("disable-backfill", None)
+        .flag("genesis-backfill", None)
+        .run_with_zero_port();
+}
+
 #[test]
 fn always_prefer_builder_payload_flag() {
     CommandLineTest::new()
@@ -409,6 +524,32 @@ fn eth1_endpoints_flag() {
                 config.eth1.endpoint.get_endpoint().to_string(),
                 "http://localhost:9545/"
             );
+            assert!(config.eth1.fallback_endpoints.is_empty());
+            assert!(config.sync_eth1_chain);
+        });
+}
+#[test]
+fn eth1_endpoints_flag_multiple() {
+    use sensitive_url::SensitiveUrl;
+
+    CommandLineTest::new()
+        .flag(
+            "eth1-endpoints",
+            Some("http://localhost:9545,http://localhost:9546, http://localhost:9547"),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.eth1.endpoint,
+                Eth1Endpoint::NoAuth(SensitiveUrl::parse("http://localhost:9545").unwrap())
+            );
+            assert_eq!(
+                config.eth1.fallback_endpoints,
+                vec![
+                    SensitiveUrl::parse("http://localhost:9546").unwrap(),
+                    SensitiveUrl::parse("http://localhost:9547").unwrap(),
+                ]
+            );
             assert!(config.sync_eth1_chain);
         });
 }
@@ -557,8 +698,8 @@ fn run_execution_endpoints_overrides_eth1_endpoints_test(eth1_flag: &str, execut
                 vec![SensitiveUrl::parse(execution_endpoint).unwrap()]
             );
 
-            // The eth1 endpoint should have been set to the --execution-endpoint value in defiance
-            // of --eth1-endpoints.
+            // The eth1 endpoint should have been set to the --execution-endpoint value, with the
+            // --eth1-endpoints value kept as a fallback.
             assert_eq!(
                 config.eth1.endpoint,
                 Eth1Endpoint::Auth {
@@ -568,6 +709,10 @@ fn run_execution_endpoints_overrides_eth1_endpoints_test(eth1_flag: &str, execut
                     jwt_version: None,
                 }
             );
+            assert_eq!(
+                config.eth1.fallback_endpoints,
+                vec![SensitiveUrl::parse(eth1_endpoint).unwrap()]
+            );
         });
 }
 #[test]
@@ -818,6 +963,43 @@ fn terminal_total_difficulty_override_flag() {
         });
 }
 #[test]
+fn fork_epoch_override_flags() {
+    CommandLineTest::new()
+        .flag("fork-epoch", Some("altair=10"))
+        .flag("bellatrix-fork-epoch", Some("20"))
+        .flag("capella-fork-epoch", Some("30"))
+        .run_with_zero_port()
+        .with_spec::<MainnetEthSpec, _>(|spec| {
+            assert_eq!(spec.altair_fork_epoch, Some(Epoch::new(10)));
+            assert_eq!(spec.bellatrix_fork_epoch, Some(Epoch::new(20)));
+            assert_eq!(spec.capella_fork_epoch, Some(Epoch::new(30)));
+        });
+}
+#[test]
+fn fork_epoch_flag_overridden_by_named_flag() {
+    CommandLineTest::new()
+        .flag("fork-epoch", Some("capella=300000"))
+        .flag("capella-fork-epoch", Some("400000"))
+        .run_with_zero_port()
+        .with_spec::<MainnetEthSpec, _>(|spec| {
+            assert_eq!(spec.capella_fork_epoch, Some(Epoch::new(400000)));
+        });
+}
+#[test]
+#[should_panic]
+fn fork_epoch_override_out_of_order() {
+    CommandLineTest::new()
+        .flag("capella-fork-epoch", Some("1"))
+        .run_with_zero_port();
+}
+#[test]
+#[should_panic]
+fn fork_epoch_override_genesis() {
+    CommandLineTest::new()
+        .flag("fork-epoch", Some("phase0=1"))
+        .run_with_zero_port();
+}
+#[test]
 fn terminal_block_hash_and_activation_epoch_override_flags() {
     CommandLineTest::new()
         .flag("terminal-block-hash-epoch-override", Some("1337"))
@@ -910,6 +1092,36 @@ fn network_shutdown_after_sync_disabled_flag() {
         .with_config(|config| assert!(!config.network.shutdown_after_sync));
 }
 #[test]
+fn network_fork_topic_epochs_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.fork_subscribe_slots, 2);
+            assert_eq!(config.network.fork_unsubscribe_epochs, 2);
+        });
+}
+#[test]
+fn network_fork_topic_epochs_flags() {
+    CommandLineTest::new()
+        .flag("fork-topic-subscribe-epochs", Some("3"))
+        .flag("fork-topic-unsubscribe-epochs", Some("4"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.fork_subscribe_slots,
+                3 * MainnetEthSpec::slots_per_epoch()
+            );
+            assert_eq!(config.network.fork_unsubscribe_epochs, 4);
+        });
+}
+#[test]
+#[should_panic]
+fn network_fork_topic_subscribe_epochs_zero() {
+    CommandLineTest::new()
+        .flag("fork-topic-subscribe-epochs", Some("0"))
+        .run_with_zero_port();
+}
+#[test]
 fn network_listen_address_flag_v4() {
     let addr = "127.0.0.2".parse::<Ipv4Addr>().unwrap();
     CommandLineTest::new()
@@ -1563,6 +1775,66 @@ fn metrics_allow_origin_all_flag() {
         .run_with_zero_port()
         .with_config(|config| assert_eq!(config.http_metrics.allow_origin, Some("*".to_string())));
 }
+#[test]
+fn metrics_allowlist_and_denylist_flags() {
+    CommandLineTest::new()
+        .flag("metrics", None)
+        .flag("metrics-allowlist", Some("^beacon_"))
+        .flag("metrics-denylist", Some("_bucket$"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.http_metrics.allowlist, Some("^beacon_".to_string()));
+            assert_eq!(config.http_metrics.denylist, Some("_bucket$".to_string()));
+        });
+}
+#[test]
+#[should_panic]
+fn metrics_allowlist_invalid_regex() {
+    CommandLineTest::new()
+        .flag("metrics", None)
+        .flag("metrics-allowlist", Some("(beacon_"))
+        .run_with_zero_port();
+}
+#[test]
+fn metrics_histogram_buckets_flag() {
+    CommandLineTest::new()
+        .flag("metrics", None)
+        .flag(
+            "metrics-histogram-buckets",
+            Some("beacon_block_processing_seconds=0.1,0.5,1"),
+        )
+        .flag(
+            "metrics-histogram-buckets",
+            Some("beacon_unaggregated_attestation_gossip_verification_seconds=0.01,0.1"),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.http_metrics.histogram_buckets,
+                vec![
+                    (
+                        "beacon_block_processing_seconds".to_string(),
+                        vec![0.1, 0.5, 1.0]
+                    ),
+                    (
+                        "beacon_unaggregated_attestation_gossip_verification_seconds".to_string(),
+                        vec![0.01, 0.1]
+                    ),
+                ]
+            )
+        });
+}
+#[test]
+#[should_panic]
+fn metrics_histogram_buckets_unsorted() {
+    CommandLineTest::new()
+        .flag("metrics", None)
+        .flag(
+            "metrics-histogram-buckets",
+            Some("beacon_block_processing_seconds=1,0.5"),
+        )
+        .run_with_zero_port();
+}
 
 // Tests for Validator Monitor flags.
 #[test]
@@ -1573,6 +1845,21 @@ fn validator_monitor_auto_flag() {
         .with_config(|config| assert!(config.validator_monitor_auto));
 }
 #[test]
+fn validator_monitor_auto_limit_default() {
+    CommandLineTest::new()
+        .flag("validator-monitor-auto", None)
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.validator_monitor_auto_limit, None));
+}
+#[test]
+fn validator_monitor_auto_limit_flag() {
+    CommandLineTest::new()
+        .flag("validator-monitor-auto", None)
+        .flag("validator-monitor-auto-limit", Some("128"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.validator_monitor_auto_limit, Some(128)));
+}
+#[test]
 fn validator_monitor_pubkeys_flag() {
     CommandLineTest::new()
         .flag("validator-monitor-pubkeys", Some("0xdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef,\
@@ -1721,6 +2008,36 @@ fn prune_payloads_on_startup_false() {
         .with_config(|config| assert!(!config.store.prune_payloads));
 }
 #[test]
+fn low_disk_space_threshold_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.store.low_disk_space_threshold,
+                5 * 1024 * 1024 * 1024
+            )
+        });
+}
+#[test]
+fn low_disk_space_threshold_flag() {
+    CommandLineTest::new()
+        .flag("low-disk-space-threshold", Some("20"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.store.low_disk_space_threshold,
+                20 * 1024 * 1024 * 1024
+            )
+        });
+}
+#[test]
+fn low_disk_space_threshold_disabled() {
+    CommandLineTest::new()
+        .flag("low-disk-space-threshold", Some("0"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.low_disk_space_threshold, 0));
+}
+#[test]
 fn reconstruct_historic_states_flag() {
     CommandLineTest::new()
         .flag("reconstruct-historic-states", None)
@@ -1850,6 +2167,21 @@ fn slasher_attestation_cache_size_flag() {
         });
 }
 #[test]
+fn slasher_prune_batch_size_flag() {
+    CommandLineTest::new()
+        .flag("slasher", None)
+        .flag("slasher-max-db-size", Some("1"))
+        .flag("slasher-prune-batch-size", Some("1000"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let slasher_config = config
+                .slasher
+                .as_ref()
+                .expect("Unable to parse Slasher config");
+            assert_eq!(slasher_config.prune_batch_size, 1000);
+        });
+}
+#[test]
 fn slasher_chunk_size_flag() {
     CommandLineTest::new()
         .flag("slasher", None)
@@ -1953,6 +2285,32 @@ fn slasher_backend_override_to_default() {
         });
 }
 
+#[test]
+fn slasher_backend_memory_flag() {
+    CommandLineTest::new()
+        .flag("slasher", None)
+        .flag("slasher-backend", Some("memory"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let slasher_config = config.slasher.as_ref().unwrap();
+            assert_eq!(slasher_config.backend, slasher::DatabaseBackend::Memory);
+            assert!(!slasher_config.migrate_backend);
+        });
+}
+
+#[test]
+fn slasher_migrate_backend_flag() {
+    CommandLineTest::new()
+        .flag("slasher", None)
+        .flag("slasher-max-db-size", Some("1"))
+        .flag("slasher-migrate-backend", None)
+        .run_with_zero_port()
+        .with_config(|config| {
+            let slasher_config = config.slasher.as_ref().unwrap();
+            assert!(slasher_config.migrate_backend);
+        });
+}
+
 #[test]
 fn malloc_tuning_flag() {
     CommandLineTest::new()
@@ -2037,6 +2395,41 @@ fn proposer_re_org_cutoff() {
         });
 }
 
+#[test]
+fn state_advance_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(!config.chain.disable_state_advance);
+            assert_eq!(config.chain.state_advance_offset_millis, None);
+            assert_eq!(
+                config.chain.state_advance_offset(12),
+                Duration::from_secs(9)
+            );
+        });
+}
+
+#[test]
+fn state_advance_offset() {
+    CommandLineTest::new()
+        .flag("state-advance-offset", Some("10000"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.state_advance_offset(12),
+                Duration::from_millis(10000)
+            )
+        });
+}
+
+#[test]
+fn disable_state_advance() {
+    CommandLineTest::new()
+        .flag("disable-state-advance", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.chain.disable_state_advance));
+}
+
 #[test]
 fn proposer_re_org_disallowed_offsets_default() {
     CommandLineTest::new()
@@ -2154,6 +2547,36 @@ fn logfile_format_flag() {
         });
 }
 #[test]
+fn logfile_format_flag_lowercase() {
+    CommandLineTest::new()
+        .flag("logfile-format", Some("json"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.logger_config.logfile_format,
+                Some("json".to_string())
+            )
+        });
+}
+#[test]
+fn logfile_rotation_interval_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.logger_config.rotation_interval, None));
+}
+#[test]
+fn logfile_rotation_interval_flag() {
+    CommandLineTest::new()
+        .flag("logfile-rotation-interval", Some("24"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.logger_config.rotation_interval,
+                Some(Duration::from_secs(24 * 60 * 60))
+            )
+        });
+}
+#[test]
 fn sync_eth1_chain_default() {
     CommandLineTest::new()
         .run_with_zero_port()
@@ -2277,3 +2700,86 @@ fn progressive_balances_fast() {
             )
         });
 }
+
+#[test]
+fn beacon_processor_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.beacon_processor.early_block_delay,
+                Duration::from_millis(5)
+            );
+            assert_eq!(
+                config
+                    .beacon_processor
+                    .unknown_parent_light_client_update_delay,
+                Duration::from_secs(12)
+            );
+            assert_eq!(config.beacon_processor.max_queued_blocks, 16);
+            assert_eq!(config.beacon_processor.max_queued_attestations, 16_384);
+            assert_eq!(config.beacon_processor.max_queued_light_client_updates, 128);
+            assert!(!config.beacon_processor.queue_early_attestations);
+        });
+}
+
+#[test]
+fn beacon_processor_reprocess_delays() {
+    CommandLineTest::new()
+        .flag("beacon-processor-early-block-delay-ms", Some("10"))
+        .flag(
+            "beacon-processor-unknown-block-attestation-delay-ms",
+            Some("6000"),
+        )
+        .flag("beacon-processor-rpc-block-delay-ms", Some("2000"))
+        .flag(
+            "beacon-processor-unknown-parent-light-client-update-delay-ms",
+            Some("3000"),
+        )
+        .flag("beacon-processor-max-queued-blocks", Some("32"))
+        .flag("beacon-processor-max-queued-attestations", Some("1024"))
+        .flag(
+            "beacon-processor-max-queued-light-client-updates",
+            Some("64"),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.beacon_processor.early_block_delay,
+                Duration::from_millis(10)
+            );
+            assert_eq!(
+                config.beacon_processor.unknown_block_attestation_delay,
+                Duration::from_millis(6000)
+            );
+            assert_eq!(
+                config.beacon_processor.rpc_block_delay,
+                Duration::from_millis(2000)
+            );
+            assert_eq!(
+                config
+                    .beacon_processor
+                    .unknown_parent_light_client_update_delay,
+                Duration::from_millis(3000)
+            );
+            assert_eq!(config.beacon_processor.max_queued_blocks, 32);
+            assert_eq!(config.beacon_processor.max_queued_attestations, 1024);
+            assert_eq!(config.beacon_processor.max_queued_light_client_updates, 64);
+        });
+}
+
+#[test]
+#[should_panic]
+fn beacon_processor_early_block_delay_too_large() {
+    CommandLineTest::new()
+        .flag("beacon-processor-early-block-delay-ms", Some("1000"))
+        .run_with_zero_port();
+}
+
+#[test]
+fn beacon_processor_queue_early_attestations() {
+    CommandLineTest::new()
+        .flag("beacon-processor-queue-early-attestations", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.beacon_processor.queue_early_attestations));
+}
@@ -4,4 +4,5 @@ mod account_manager;
 mod beacon_node;
 mod boot_node;
 mod exec;
+mod slasher;
 mod validator_client;
@@ -0,0 +1,159 @@
+use standalone_slasher::Config;
+
+use crate::exec::CommandLineTestExec;
+use slasher::DatabaseBackend;
+use std::path::PathBuf;
+use std::process::Command;
+use tempfile::TempDir;
+
+/// Returns the `lighthouse slasher` command.
+fn base_cmd() -> Command {
+    let lighthouse_bin = env!("CARGO_BIN_EXE_lighthouse");
+    let path = lighthouse_bin
+        .parse::<PathBuf>()
+        .expect("should parse CARGO_TARGET_DIR");
+
+    let mut cmd = Command::new(path);
+    cmd.arg(standalone_slasher::CMD);
+    cmd
+}
+
+// Wrapper around `Command` for easier Command Line Testing.
+struct CommandLineTest {
+    cmd: Command,
+}
+impl CommandLineTest {
+    fn new() -> CommandLineTest {
+        let base_cmd = base_cmd();
+        CommandLineTest { cmd: base_cmd }
+    }
+}
+
+impl CommandLineTestExec for CommandLineTest {
+    type Config = Config;
+
+    fn cmd_mut(&mut self) -> &mut Command {
+        &mut self.cmd
+    }
+}
+
+#[test]
+fn beacon_node_flag_default() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(
+            config.beacon_node.full.to_string(),
+            standalone_slasher::DEFAULT_BEACON_NODE
+        );
+    });
+}
+
+#[test]
+fn beacon_node_flag() {
+    CommandLineTest::new()
+        .flag("beacon-node", Some("http://localhost:1001"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.beacon_node.full.to_string(),
+                "http://localhost:1001/"
+            );
+        });
+}
+
+#[test]
+#[should_panic]
+fn beacon_node_flag_invalid() {
+    CommandLineTest::new()
+        .flag("beacon-node", Some("not a url"))
+        .run();
+}
+
+#[test]
+fn slasher_dir_default() {
+    CommandLineTest::new()
+        .run()
+        .with_config_and_dir(|config, dir| {
+            assert_eq!(config.slasher.database_path, dir.path().join("slasher"));
+        });
+}
+
+#[test]
+fn slasher_dir_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag("slasher-dir", dir.path().as_os_str().to_str())
+        .run()
+        .with_config(|config| assert_eq!(config.slasher.database_path, dir.path()));
+}
+
+#[test]
+fn slasher_backend_flag() {
+    CommandLineTest::new()
+        .flag("slasher-backend", Some("memory"))
+        .run()
+        .with_config(|config| assert_eq!(config.slasher.backend, DatabaseBackend::Memory));
+}
+
+#[test]
+#[should_panic]
+fn slasher_backend_flag_invalid() {
+    CommandLineTest::new()
+        .flag("slasher-backend", Some("unknown"))
+        .run();
+}
+
+#[test]
+fn slasher_update_period_flag() {
+    CommandLineTest::new()
+        .flag("slasher-update-period", Some("100"))
+        .run()
+        .with_config(|config| assert_eq!(config.slasher.update_period, 100));
+}
+
+#[test]
+fn slasher_slot_offset_flag() {
+    CommandLineTest::new()
+        .flag("slasher-slot-offset", Some("11.25"))
+        .run()
+        .with_config(|config| assert_eq!(config.slasher.slot_offset, 11.25));
+}
+
+#[test]
+#[should_panic]
+fn slasher_slot_offset_nan_flag() {
+    CommandLineTest::new()
+        .flag("slasher-slot-offset", Some("NaN"))
+        .run();
+}
+
+#[test]
+fn slasher_history_length_flag() {
+    CommandLineTest::new()
+        .flag("slasher-history-length", Some("2048"))
+        .run()
+        .with_config(|config| assert_eq!(config.slasher.history_length, 2048));
+}
+
+#[test]
+fn slasher_chunk_size_flag() {
+    CommandLineTest::new()
+        .flag("slasher-chunk-size", Some("32"))
+        .run()
+        .with_config(|config| assert_eq!(config.slasher.chunk_size, 32));
+}
+
+#[test]
+fn slasher_validator_chunk_size_flag() {
+    CommandLineTest::new()
+        .flag("slasher-validator-chunk-size", Some("512"))
+        .run()
+        .with_config(|config| assert_eq!(config.slasher.validator_chunk_size, 512));
+}
+
+#[test]
+fn slasher_max_db_size_flag() {
+    CommandLineTest::new()
+        .flag("slasher-max-db-size", Some("2"))
+        .run()
+        .with_config(|config| assert_eq!(config.slasher.max_db_size_mbs, 2048));
+}
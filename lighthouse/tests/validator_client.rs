@@ -1,4 +1,4 @@
-use validator_client::Config;
+use validator_client::{ApiTopic, Config};
 
 use crate::exec::CommandLineTestExec;
 use bls::{Keypair, PublicKeyBytes};
@@ -9,6 +9,7 @@ use std::path::PathBuf;
 use std::process::Command;
 use std::str::FromStr;
 use std::string::ToString;
+use std::time::Duration;
 use tempfile::TempDir;
 use types::Address;
 
@@ -131,6 +132,84 @@ fn use_long_timeouts_flag() {
         .with_config(|config| assert!(config.use_long_timeouts));
 }
 
+#[test]
+fn distributed_flag() {
+    CommandLineTest::new()
+        .flag("distributed", None)
+        .run()
+        .with_config(|config| assert!(config.distributed));
+}
+
+#[test]
+fn no_distributed_flag() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert!(!config.distributed));
+}
+
+#[test]
+fn strict_fee_recipient_flag() {
+    CommandLineTest::new()
+        .flag("strict-fee-recipient", None)
+        .run()
+        .with_config(|config| assert!(config.strict_fee_recipient));
+}
+
+#[test]
+fn no_strict_fee_recipient_flag() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert!(!config.strict_fee_recipient));
+}
+
+#[test]
+fn web3_signer_keep_alive_timeout_default() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(
+            config.web3_signer_keep_alive_timeout,
+            Some(Duration::from_secs(20))
+        )
+    });
+}
+
+#[test]
+fn web3_signer_keep_alive_timeout_flag() {
+    CommandLineTest::new()
+        .flag("web3-signer-keep-alive-timeout", Some("1000"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.web3_signer_keep_alive_timeout,
+                Some(Duration::from_millis(1000))
+            )
+        });
+}
+
+#[test]
+fn web3_signer_keep_alive_timeout_zero_disables() {
+    CommandLineTest::new()
+        .flag("web3-signer-keep-alive-timeout", Some("0"))
+        .run()
+        .with_config(|config| assert!(config.web3_signer_keep_alive_timeout.is_none()));
+}
+
+#[test]
+fn web3_signer_max_idle_connections_flag() {
+    CommandLineTest::new()
+        .flag("web3-signer-max-idle-connections", Some("64"))
+        .run()
+        .with_config(|config| assert_eq!(config.web3_signer_max_idle_connections, Some(64)));
+}
+
+#[test]
+#[should_panic]
+fn distributed_and_use_long_timeouts_flags() {
+    CommandLineTest::new()
+        .flag("distributed", None)
+        .flag("use-long-timeouts", None)
+        .run();
+}
+
 #[test]
 fn beacon_nodes_tls_certs_flag() {
     let dir = TempDir::new().expect("Unable to create temporary directory");
@@ -309,6 +388,64 @@ fn http_allow_origin_all_flag() {
         .run()
         .with_config(|config| assert_eq!(config.http_api.allow_origin, Some("*".to_string())));
 }
+#[test]
+fn http_tls_flags() {
+    CommandLineTest::new()
+        .flag("http-enable-tls", None)
+        .flag("http-tls-cert", Some("tls.crt"))
+        .flag("http-tls-key", Some("tls.key"))
+        .run()
+        .with_config(|config| {
+            let tls_config = config
+                .http_api
+                .tls_config
+                .as_ref()
+                .expect("tls_config was empty.");
+            assert_eq!(tls_config.cert, PathBuf::from("tls.crt"));
+            assert_eq!(tls_config.key, PathBuf::from("tls.key"));
+        });
+}
+#[test]
+fn http_address_with_tls_flag() {
+    let addr = "127.0.0.99".parse::<IpAddr>().unwrap();
+    CommandLineTest::new()
+        .flag("http-address", Some("127.0.0.99"))
+        .flag("http-enable-tls", None)
+        .flag("http-tls-cert", Some("tls.crt"))
+        .flag("http-tls-key", Some("tls.key"))
+        .run()
+        .with_config(|config| assert_eq!(config.http_api.listen_addr, addr));
+}
+#[test]
+#[should_panic]
+fn http_enable_tls_without_cert_flag() {
+    CommandLineTest::new()
+        .flag("http-enable-tls", None)
+        .flag("http-tls-key", Some("tls.key"))
+        .run()
+        .with_config(|config| assert!(config.http_api.tls_config.is_some()));
+}
+#[test]
+fn http_token_flags_default() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert!(!config.http_api.rotate_api_token);
+        assert!(!config.http_api.generate_read_only_token);
+    });
+}
+#[test]
+fn http_rotate_token_flag() {
+    CommandLineTest::new()
+        .flag("http-rotate-token", None)
+        .run()
+        .with_config(|config| assert!(config.http_api.rotate_api_token));
+}
+#[test]
+fn http_generate_read_only_token_flag() {
+    CommandLineTest::new()
+        .flag("http-generate-read-only-token", None)
+        .run()
+        .with_config(|config| assert!(config.http_api.generate_read_only_token));
+}
 
 // Tests for Metrics flags.
 #[test]
@@ -387,6 +524,28 @@ fn no_doppelganger_protection_flag() {
         .with_config(|config| assert!(!config.enable_doppelganger_protection));
 }
 #[test]
+fn doppelganger_detection_epochs_default() {
+    CommandLineTest::new()
+        .flag("enable-doppelganger-protection", None)
+        .run()
+        .with_config(|config| assert_eq!(config.doppelganger_detection_epochs, 1));
+}
+#[test]
+fn doppelganger_detection_epochs_flag() {
+    CommandLineTest::new()
+        .flag("enable-doppelganger-protection", None)
+        .flag("doppelganger-detection-epochs", Some("3"))
+        .run()
+        .with_config(|config| assert_eq!(config.doppelganger_detection_epochs, 3));
+}
+#[test]
+#[should_panic]
+fn doppelganger_detection_epochs_requires_protection() {
+    CommandLineTest::new()
+        .flag("doppelganger-detection-epochs", Some("3"))
+        .run();
+}
+#[test]
 fn block_delay_ms() {
     CommandLineTest::new()
         .flag("block-delay-ms", Some("2000"))
@@ -399,6 +558,37 @@ fn block_delay_ms() {
         });
 }
 #[test]
+fn attestation_production_offset_ms() {
+    CommandLineTest::new()
+        .flag("attestation-production-offset-ms", Some("3000"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.attestation_production_offset,
+                Some(Duration::from_millis(3000))
+            )
+        });
+}
+#[test]
+fn aggregate_production_offset_ms() {
+    CommandLineTest::new()
+        .flag("aggregate-production-offset-ms", Some("7000"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.aggregate_production_offset,
+                Some(Duration::from_millis(7000))
+            )
+        });
+}
+#[test]
+fn no_production_offsets() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(config.attestation_production_offset, None);
+        assert_eq!(config.aggregate_production_offset, None);
+    });
+}
+#[test]
 fn no_block_delay_ms() {
     CommandLineTest::new()
         .run()
@@ -432,6 +622,19 @@ fn builder_proposals_flag() {
         .with_config(|config| assert!(config.builder_proposals));
 }
 #[test]
+fn no_builder_boost_factor_flag() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert!(config.builder_boost_factor.is_none()));
+}
+#[test]
+fn builder_boost_factor_flag() {
+    CommandLineTest::new()
+        .flag("builder-boost-factor", Some("150"))
+        .run()
+        .with_config(|config| assert_eq!(config.builder_boost_factor, Some(150)));
+}
+#[test]
 fn no_builder_registration_timestamp_override_flag() {
     CommandLineTest::new()
         .run()
@@ -459,9 +662,12 @@ fn monitoring_endpoint() {
         });
 }
 #[test]
-fn disable_run_on_all_default() {
+fn broadcast_default() {
     CommandLineTest::new().run().with_config(|config| {
-        assert!(!config.disable_run_on_all);
+        assert_eq!(
+            config.broadcast_topics,
+            vec![ApiTopic::Subscriptions, ApiTopic::Preparations]
+        );
     });
 }
 
@@ -471,10 +677,41 @@ fn disable_run_on_all() {
         .flag("disable-run-on-all", None)
         .run()
         .with_config(|config| {
-            assert!(config.disable_run_on_all);
+            assert!(config.broadcast_topics.is_empty());
+        });
+}
+
+#[test]
+fn broadcast_none() {
+    CommandLineTest::new()
+        .flag("broadcast", Some("none"))
+        .run()
+        .with_config(|config| {
+            assert!(config.broadcast_topics.is_empty());
+        });
+}
+
+#[test]
+fn broadcast_registrations() {
+    CommandLineTest::new()
+        .flag("broadcast", Some("subscriptions,registrations"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.broadcast_topics,
+                vec![ApiTopic::Subscriptions, ApiTopic::Registrations]
+            );
         });
 }
 
+#[test]
+#[should_panic]
+fn broadcast_invalid() {
+    CommandLineTest::new()
+        .flag("broadcast", Some("blocks"))
+        .run();
+}
+
 #[test]
 fn latency_measurement_service() {
     CommandLineTest::new().run().with_config(|config| {
@@ -520,3 +757,64 @@ fn validator_registration_batch_size_zero_value() {
         .flag("validator-registration-batch-size", Some("0"))
         .run();
 }
+
+#[test]
+fn validator_registration_interval_epochs() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(config.validator_registration_interval_epochs, 1);
+    });
+    CommandLineTest::new()
+        .flag("validator-registration-interval-epochs", Some("8"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(config.validator_registration_interval_epochs, 8);
+        });
+}
+
+#[test]
+#[should_panic]
+fn validator_registration_interval_epochs_zero_value() {
+    CommandLineTest::new()
+        .flag("validator-registration-interval-epochs", Some("0"))
+        .run();
+}
+
+#[test]
+fn sync_committee_subscription_lookahead_epochs() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(config.sync_committee_subscription_lookahead_epochs, 4);
+    });
+    CommandLineTest::new()
+        .flag("sync-committee-subscription-lookahead-epochs", Some("8"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(config.sync_committee_subscription_lookahead_epochs, 8);
+        });
+}
+
+#[test]
+#[should_panic]
+fn sync_committee_subscription_lookahead_epochs_zero_value() {
+    CommandLineTest::new()
+        .flag("sync-committee-subscription-lookahead-epochs", Some("0"))
+        .run();
+}
+
+#[test]
+fn graceful_shutdown_timeout() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(config.graceful_shutdown_timeout, Duration::from_secs(12));
+    });
+    CommandLineTest::new()
+        .flag("graceful-shutdown-timeout", Some("30"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(config.graceful_shutdown_timeout, Duration::from_secs(30));
+        });
+    CommandLineTest::new()
+        .flag("graceful-shutdown-timeout", Some("0"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(config.graceful_shutdown_timeout, Duration::ZERO);
+        });
+}
@@ -0,0 +1,265 @@
+//! Rate-limited broadcasting of the slashings found by the slasher.
+//!
+//! A mass equivocation can produce thousands of slashings at once, far more than can be included
+//! on chain in the short term. Rather than publishing them all at once, slashings are queued and
+//! published at most `MAX_BROADCASTS_PER_UPDATE` at a time. Slashings which have not been included
+//! on chain after `RETRY_EPOCHS` are published again, up to `MAX_BROADCAST_ATTEMPTS` times.
+use std::collections::{HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use types::{AttesterSlashing, Epoch, EthSpec, ProposerSlashing};
+
+/// Maximum number of slashings to publish each time the slasher runs.
+pub const MAX_BROADCASTS_PER_UPDATE: usize = 16;
+
+/// Number of epochs to wait for a published slashing to be included before publishing it again.
+pub const RETRY_EPOCHS: u64 = 4;
+
+/// Number of times to publish a slashing before giving up on it.
+pub const MAX_BROADCAST_ATTEMPTS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlashingMessage<E: EthSpec> {
+    Attester(AttesterSlashing<E>),
+    Proposer(ProposerSlashing),
+}
+
+// Implemented manually because deriving `Hash` would require `E: Hash`.
+impl<E: EthSpec> Hash for SlashingMessage<E> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Self::Attester(slashing) => slashing.hash(state),
+            Self::Proposer(slashing) => slashing.hash(state),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PendingBroadcast<E: EthSpec> {
+    slashing: SlashingMessage<E>,
+    last_broadcast: Option<Epoch>,
+    attempts: usize,
+}
+
+impl<E: EthSpec> PendingBroadcast<E> {
+    fn is_due(&self, current_epoch: Epoch) -> bool {
+        self.last_broadcast.map_or(true, |last_broadcast| {
+            current_epoch >= last_broadcast.saturating_add(RETRY_EPOCHS)
+        })
+    }
+}
+
+/// Outcome of a call to `BroadcastQueue::next_batch`.
+#[derive(Debug)]
+pub struct BroadcastBatch<E: EthSpec> {
+    /// Slashings to publish, with `true` for those which have been published before.
+    pub slashings: Vec<(SlashingMessage<E>, bool)>,
+    /// Number of slashings removed from the queue because they were included on chain.
+    pub num_included: usize,
+    /// Number of slashings removed from the queue after exhausting their broadcast attempts.
+    pub num_abandoned: usize,
+}
+
+/// Queue of slashings waiting to be published, or waiting to be included after publication.
+#[derive(Debug)]
+pub struct BroadcastQueue<E: EthSpec> {
+    pending: VecDeque<PendingBroadcast<E>>,
+    /// Every slashing in `pending`, to avoid queueing the same slashing twice.
+    queued: HashSet<SlashingMessage<E>>,
+}
+
+impl<E: EthSpec> Default for BroadcastQueue<E> {
+    fn default() -> Self {
+        Self {
+            pending: VecDeque::new(),
+            queued: HashSet::new(),
+        }
+    }
+}
+
+impl<E: EthSpec> BroadcastQueue<E> {
+    /// Queue a slashing for broadcast, returning `false` if it is already queued.
+    pub fn queue(&mut self, slashing: SlashingMessage<E>) -> bool {
+        if !self.queued.insert(slashing.clone()) {
+            return false;
+        }
+        self.pending.push_back(PendingBroadcast {
+            slashing,
+            last_broadcast: None,
+            attempts: 0,
+        });
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Remove slashings for which `is_included` returns `true`, and return the slashings which
+    /// should be published at `current_epoch`.
+    ///
+    /// Slashings which have never been published take priority over retries.
+    pub fn next_batch(
+        &mut self,
+        current_epoch: Epoch,
+        is_included: impl Fn(&SlashingMessage<E>) -> bool,
+    ) -> BroadcastBatch<E> {
+        let mut num_included = 0;
+        let mut num_abandoned = 0;
+        let queued = &mut self.queued;
+
+        self.pending.retain(|pending| {
+            let keep = if is_included(&pending.slashing) {
+                num_included += 1;
+                false
+            } else if pending.attempts >= MAX_BROADCAST_ATTEMPTS && pending.is_due(current_epoch) {
+                num_abandoned += 1;
+                false
+            } else {
+                true
+            };
+
+            if !keep {
+                queued.remove(&pending.slashing);
+            }
+            keep
+        });
+
+        let mut due = self
+            .pending
+            .iter_mut()
+            .filter(|pending| pending.is_due(current_epoch))
+            .collect::<Vec<_>>();
+        due.sort_by_key(|pending| pending.attempts > 0);
+
+        let slashings = due
+            .into_iter()
+            .take(MAX_BROADCASTS_PER_UPDATE)
+            .map(|pending| {
+                let is_retry = pending.attempts > 0;
+                pending.last_broadcast = Some(current_epoch);
+                pending.attempts += 1;
+                (pending.slashing.clone(), is_retry)
+            })
+            .collect();
+
+        BroadcastBatch {
+            slashings,
+            num_included,
+            num_abandoned,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{BeaconBlockHeader, MainnetEthSpec, Signature, SignedBeaconBlockHeader, Slot};
+
+    type E = MainnetEthSpec;
+
+    fn proposer_slashing(proposer_index: u64) -> SlashingMessage<E> {
+        let header = |body_root| SignedBeaconBlockHeader {
+            message: BeaconBlockHeader {
+                slot: Slot::new(0),
+                proposer_index,
+                parent_root: Default::default(),
+                state_root: Default::default(),
+                body_root,
+            },
+            signature: Signature::empty(),
+        };
+        SlashingMessage::Proposer(ProposerSlashing {
+            signed_header_1: header(types::Hash256::from_low_u64_be(1)),
+            signed_header_2: header(types::Hash256::from_low_u64_be(2)),
+        })
+    }
+
+    fn proposer_indices(batch: &BroadcastBatch<E>) -> Vec<u64> {
+        batch
+            .slashings
+            .iter()
+            .map(|(slashing, _)| match slashing {
+                SlashingMessage::Proposer(slashing) => {
+                    slashing.signed_header_1.message.proposer_index
+                }
+                SlashingMessage::Attester(_) => panic!("unexpected attester slashing"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn deduplicates() {
+        let mut queue = BroadcastQueue::default();
+        assert!(queue.queue(proposer_slashing(0)));
+        assert!(!queue.queue(proposer_slashing(0)));
+        assert_eq!(queue.len(), 1);
+    }
+
+    #[test]
+    fn rate_limited() {
+        let num_slashings = 2 * MAX_BROADCASTS_PER_UPDATE as u64;
+        let mut queue = BroadcastQueue::default();
+        for i in 0..num_slashings {
+            queue.queue(proposer_slashing(i));
+        }
+
+        let epoch = Epoch::new(0);
+        let batch = queue.next_batch(epoch, |_| false);
+        assert_eq!(
+            proposer_indices(&batch),
+            (0..MAX_BROADCASTS_PER_UPDATE as u64).collect::<Vec<_>>()
+        );
+
+        let batch = queue.next_batch(epoch, |_| false);
+        assert_eq!(
+            proposer_indices(&batch),
+            (MAX_BROADCASTS_PER_UPDATE as u64..num_slashings).collect::<Vec<_>>()
+        );
+        assert!(batch.slashings.iter().all(|(_, is_retry)| !is_retry));
+
+        assert!(queue.next_batch(epoch, |_| false).slashings.is_empty());
+        assert_eq!(queue.len(), num_slashings as usize);
+    }
+
+    #[test]
+    fn retries_until_included() {
+        let mut queue = BroadcastQueue::default();
+        queue.queue(proposer_slashing(0));
+        queue.queue(proposer_slashing(1));
+
+        let epoch = Epoch::new(0);
+        assert_eq!(queue.next_batch(epoch, |_| false).slashings.len(), 2);
+
+        let epoch = epoch.saturating_add(RETRY_EPOCHS);
+        let included = proposer_slashing(0);
+        let batch = queue.next_batch(epoch, |slashing| *slashing == included);
+        assert_eq!(batch.num_included, 1);
+        assert_eq!(proposer_indices(&batch), vec![1]);
+        assert!(batch.slashings[0].1);
+
+        // The included slashing may be queued again.
+        assert!(queue.queue(proposer_slashing(0)));
+    }
+
+    #[test]
+    fn abandons_after_max_attempts() {
+        let mut queue = BroadcastQueue::default();
+        queue.queue(proposer_slashing(0));
+
+        let mut epoch = Epoch::new(0);
+        for _ in 0..MAX_BROADCAST_ATTEMPTS {
+            assert_eq!(queue.next_batch(epoch, |_| false).slashings.len(), 1);
+            epoch = epoch.saturating_add(RETRY_EPOCHS);
+        }
+
+        let batch = queue.next_batch(epoch, |_| false);
+        assert!(batch.slashings.is_empty());
+        assert_eq!(batch.num_abandoned, 1);
+        assert!(queue.is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+mod broadcast;
 mod service;
 
 pub use service::SlasherService;
@@ -1,3 +1,4 @@
+use crate::broadcast::{BroadcastQueue, SlashingMessage};
 use beacon_chain::{
     observed_operations::ObservationOutcome, BeaconChain, BeaconChainError, BeaconChainTypes,
 };
@@ -5,7 +6,7 @@ use directory::size_of_dir;
 use lighthouse_network::PubsubMessage;
 use network::NetworkMessage;
 use slasher::{
-    metrics::{self, SLASHER_DATABASE_SIZE, SLASHER_RUN_TIME},
+    metrics::{self, SLASHER_BROADCAST_QUEUE_SIZE, SLASHER_DATABASE_SIZE, SLASHER_RUN_TIME},
     Slasher,
 };
 use slog::{debug, error, info, trace, warn, Logger};
@@ -16,12 +17,13 @@ use state_processing::{
     },
     VerifyOperation,
 };
+use std::collections::HashSet;
 use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
 use std::sync::Arc;
 use task_executor::TaskExecutor;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::time::{interval_at, Duration, Instant};
-use types::{AttesterSlashing, Epoch, EthSpec, ProposerSlashing};
+use types::{AttesterSlashing, BeaconState, Epoch, EthSpec, ProposerSlashing};
 
 pub struct SlasherService<T: BeaconChainTypes> {
     beacon_chain: Arc<BeaconChain<T>>,
@@ -117,6 +119,7 @@ impl<T: BeaconChainTypes> SlasherService<T> {
         network_sender: UnboundedSender<NetworkMessage<T::EthSpec>>,
     ) {
         let log = slasher.log();
+        let mut broadcast_queue = BroadcastQueue::default();
         while let Ok(current_epoch) = notif_receiver.recv() {
             let t = Instant::now();
 
@@ -137,18 +140,37 @@ impl<T: BeaconChainTypes> SlasherService<T> {
 
             // Prune the database, even in the case where batch processing failed.
             // If the database is full then pruning could help to free it up.
-            if let Err(e) = slasher.prune_database(current_epoch) {
-                error!(
-                    log,
-                    "Error during slasher database pruning";
-                    "epoch" => current_epoch,
-                    "error" => ?e,
-                );
-                continue;
-            };
+            match slasher.prune_database(current_epoch) {
+                Ok(prune_stats) if prune_stats.backlog_epochs > 0 => {
+                    debug!(
+                        log,
+                        "Slasher database pruning incomplete";
+                        "epoch" => current_epoch,
+                        "backlog_epochs" => prune_stats.backlog_epochs,
+                        "num_blocks_pruned" => prune_stats.num_proposers_pruned,
+                        "num_attestations_pruned" => prune_stats.num_indexed_attestations_pruned,
+                    );
+                }
+                Ok(_) => (),
+                Err(e) => {
+                    error!(
+                        log,
+                        "Error during slasher database pruning";
+                        "epoch" => current_epoch,
+                        "error" => ?e,
+                    );
+                    continue;
+                }
+            }
 
             // Provide slashings to the beacon chain, and optionally publish them.
-            Self::process_slashings(&beacon_chain, &slasher, &network_sender);
+            Self::process_slashings(
+                &beacon_chain,
+                &slasher,
+                &mut broadcast_queue,
+                &network_sender,
+                current_epoch,
+            );
 
             let database_size = size_of_dir(&slasher.config().database_path);
             metrics::set_gauge(&SLASHER_DATABASE_SIZE, database_size as i64);
@@ -170,16 +192,25 @@ impl<T: BeaconChainTypes> SlasherService<T> {
     fn process_slashings(
         beacon_chain: &BeaconChain<T>,
         slasher: &Slasher<T::EthSpec>,
+        broadcast_queue: &mut BroadcastQueue<T::EthSpec>,
         network_sender: &UnboundedSender<NetworkMessage<T::EthSpec>>,
+        current_epoch: Epoch,
     ) {
-        Self::process_attester_slashings(beacon_chain, slasher, network_sender);
-        Self::process_proposer_slashings(beacon_chain, slasher, network_sender);
+        Self::process_attester_slashings(beacon_chain, slasher, broadcast_queue);
+        Self::process_proposer_slashings(beacon_chain, slasher, broadcast_queue);
+        Self::broadcast_slashings(
+            beacon_chain,
+            slasher,
+            broadcast_queue,
+            network_sender,
+            current_epoch,
+        );
     }
 
     fn process_attester_slashings(
         beacon_chain: &BeaconChain<T>,
         slasher: &Slasher<T::EthSpec>,
-        network_sender: &UnboundedSender<NetworkMessage<T::EthSpec>>,
+        broadcast_queue: &mut BroadcastQueue<T::EthSpec>,
     ) {
         let log = slasher.log();
         let attester_slashings = slasher.get_attester_slashings();
@@ -218,17 +249,9 @@ impl<T: BeaconChainTypes> SlasherService<T> {
             // Add to local op pool.
             beacon_chain.import_attester_slashing(verified_slashing);
 
-            // Publish to the network if broadcast is enabled.
+            // Queue for publication to the network if broadcast is enabled.
             if slasher.config().broadcast {
-                if let Err(e) =
-                    Self::publish_attester_slashing(beacon_chain, network_sender, slashing)
-                {
-                    debug!(
-                        log,
-                        "Unable to publish attester slashing";
-                        "error" => e,
-                    );
-                }
+                broadcast_queue.queue(SlashingMessage::Attester(slashing));
             }
         }
     }
@@ -236,7 +259,7 @@ impl<T: BeaconChainTypes> SlasherService<T> {
     fn process_proposer_slashings(
         beacon_chain: &BeaconChain<T>,
         slasher: &Slasher<T::EthSpec>,
-        network_sender: &UnboundedSender<NetworkMessage<T::EthSpec>>,
+        broadcast_queue: &mut BroadcastQueue<T::EthSpec>,
     ) {
         let log = slasher.log();
         let proposer_slashings = slasher.get_proposer_slashings();
@@ -273,15 +296,114 @@ impl<T: BeaconChainTypes> SlasherService<T> {
             beacon_chain.import_proposer_slashing(verified_slashing);
 
             if slasher.config().broadcast {
-                if let Err(e) =
+                broadcast_queue.queue(SlashingMessage::Proposer(slashing));
+            }
+        }
+    }
+
+    /// Publish the next batch of queued slashings, dropping those which have been included on
+    /// chain.
+    ///
+    /// Slashings are published at a bounded rate, and republished if they are not included on
+    /// chain after a few epochs. See the `broadcast` module.
+    fn broadcast_slashings(
+        beacon_chain: &BeaconChain<T>,
+        slasher: &Slasher<T::EthSpec>,
+        broadcast_queue: &mut BroadcastQueue<T::EthSpec>,
+        network_sender: &UnboundedSender<NetworkMessage<T::EthSpec>>,
+        current_epoch: Epoch,
+    ) {
+        let log = slasher.log();
+        if broadcast_queue.is_empty() {
+            return;
+        }
+
+        let batch = match beacon_chain.with_head(|head| {
+            Ok::<_, BeaconChainError>(broadcast_queue.next_batch(current_epoch, |slashing| {
+                Self::is_included(&head.beacon_state, slashing)
+            }))
+        }) {
+            Ok(batch) => batch,
+            Err(e) => {
+                error!(
+                    log,
+                    "Unable to read head for slashing broadcast";
+                    "error" => ?e,
+                );
+                return;
+            }
+        };
+
+        let num_published = batch.slashings.len();
+        for (slashing, is_retry) in batch.slashings {
+            let result = match (slashing, is_retry) {
+                // Slashings are checked against the gossip cache on their first publication so
+                // that slashings already received from the network are not published again.
+                (SlashingMessage::Attester(slashing), false) => {
+                    Self::publish_attester_slashing(beacon_chain, network_sender, slashing)
+                }
+                (SlashingMessage::Proposer(slashing), false) => {
                     Self::publish_proposer_slashing(beacon_chain, network_sender, slashing)
-                {
-                    debug!(
-                        log,
-                        "Unable to publish proposer slashing";
-                        "error" => e,
-                    );
                 }
+                (SlashingMessage::Attester(slashing), true) => Self::publish(
+                    network_sender,
+                    PubsubMessage::AttesterSlashing(Box::new(slashing)),
+                ),
+                (SlashingMessage::Proposer(slashing), true) => Self::publish(
+                    network_sender,
+                    PubsubMessage::ProposerSlashing(Box::new(slashing)),
+                ),
+            };
+            if let Err(e) = result {
+                debug!(
+                    log,
+                    "Unable to publish slashing";
+                    "error" => e,
+                    "retry" => is_retry,
+                );
+            }
+        }
+
+        debug!(
+            log,
+            "Broadcast slashings";
+            "num_published" => num_published,
+            "num_included" => batch.num_included,
+            "num_abandoned" => batch.num_abandoned,
+            "num_queued" => broadcast_queue.len(),
+        );
+        metrics::set_gauge(&SLASHER_BROADCAST_QUEUE_SIZE, broadcast_queue.len() as i64);
+    }
+
+    /// Return `true` if every validator slashable by `slashing` has already been slashed in
+    /// `state`, most likely because the slashing has been included on chain.
+    fn is_included(
+        state: &BeaconState<T::EthSpec>,
+        slashing: &SlashingMessage<T::EthSpec>,
+    ) -> bool {
+        let is_slashed = |validator_index: u64| {
+            state
+                .validators()
+                .get(validator_index as usize)
+                .map_or(true, |validator| validator.slashed)
+        };
+
+        match slashing {
+            SlashingMessage::Attester(slashing) => {
+                let attesters_1 = slashing
+                    .attestation_1
+                    .attesting_indices
+                    .iter()
+                    .collect::<HashSet<_>>();
+                slashing
+                    .attestation_2
+                    .attesting_indices
+                    .iter()
+                    .filter(|validator_index| attesters_1.contains(validator_index))
+                    .all(|&validator_index| is_slashed(validator_index))
+            }
+            SlashingMessage::Proposer(slashing) => {
+                is_slashed(slashing.signed_header_1.message.proposer_index)
             }
         }
     }
@@ -296,13 +418,10 @@ impl<T: BeaconChainTypes> SlasherService<T> {
             .map_err(|e| format!("gossip verification error: {:?}", e))?;
 
         if let ObservationOutcome::New(slashing) = outcome {
-            network_sender
-                .send(NetworkMessage::Publish {
-                    messages: vec![PubsubMessage::AttesterSlashing(Box::new(
-                        slashing.into_inner(),
-                    ))],
-                })
-                .map_err(|e| format!("network error: {:?}", e))?;
+            Self::publish(
+                network_sender,
+                PubsubMessage::AttesterSlashing(Box::new(slashing.into_inner())),
+            )?;
         }
         Ok(())
     }
@@ -317,14 +436,22 @@ impl<T: BeaconChainTypes> SlasherService<T> {
             .map_err(|e| format!("gossip verification error: {:?}", e))?;
 
         if let ObservationOutcome::New(slashing) = outcome {
-            network_sender
-                .send(NetworkMessage::Publish {
-                    messages: vec![PubsubMessage::ProposerSlashing(Box::new(
-                        slashing.into_inner(),
-                    ))],
-                })
-                .map_err(|e| format!("network error: {:?}", e))?;
+            Self::publish(
+                network_sender,
+                PubsubMessage::ProposerSlashing(Box::new(slashing.into_inner())),
+            )?;
         }
         Ok(())
     }
+
+    fn publish(
+        network_sender: &UnboundedSender<NetworkMessage<T::EthSpec>>,
+        message: PubsubMessage<T::EthSpec>,
+    ) -> Result<(), String> {
+        network_sender
+            .send(NetworkMessage::Publish {
+                messages: vec![message],
+            })
+            .map_err(|e| format!("network error: {:?}", e))
+    }
 }
@@ -0,0 +1,178 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use types::{Epoch, Hash256};
+
+/// Number of epochs prior to the current epoch for which processed attesters are remembered.
+pub const ATTESTATION_DEDUP_CACHE_EPOCHS: u64 = 2;
+
+/// Maximum number of attesters remembered by the cache.
+///
+/// This is enough for all attesters in `ATTESTATION_DEDUP_CACHE_EPOCHS + 1` epochs on a network of
+/// over 1M validators, and uses around 100 MB of memory when full.
+pub const ATTESTATION_DEDUP_CACHE_MAX_ATTESTERS: usize = 4 * 1024 * 1024;
+
+/// Cache of the attesters whose attestations have already been applied to the database, keyed by
+/// attestation data root.
+///
+/// Once a validator's vote for some attestation data has been processed, further attestations
+/// from that validator for the same data can't produce new slashings, so they can be skipped
+/// without touching the database. This is common when many aggregates carry the same votes.
+///
+/// The cache is bounded both by epoch, via `prune`, and by `max_attesters`. When full, the
+/// attesters for the oldest target epoch are evicted.
+#[derive(Debug)]
+pub struct AttestationDedupCache {
+    /// Map from target epoch to attestation data root to the validators which voted for it.
+    epochs: BTreeMap<Epoch, HashMap<Hash256, HashSet<u64>>>,
+    /// Attesters for target epochs prior to this epoch are not cached.
+    min_epoch: Epoch,
+    /// Number of attesters in `epochs`.
+    len: usize,
+    max_attesters: usize,
+}
+
+impl Default for AttestationDedupCache {
+    fn default() -> Self {
+        Self::new(ATTESTATION_DEDUP_CACHE_MAX_ATTESTERS)
+    }
+}
+
+impl AttestationDedupCache {
+    pub fn new(max_attesters: usize) -> Self {
+        Self {
+            epochs: BTreeMap::new(),
+            min_epoch: Epoch::new(0),
+            len: 0,
+            max_attesters,
+        }
+    }
+
+    /// Return `true` if `validator_index`'s vote for `attestation_data_root` has been processed.
+    pub fn contains(
+        &self,
+        target_epoch: Epoch,
+        attestation_data_root: Hash256,
+        validator_index: u64,
+    ) -> bool {
+        self.epochs
+            .get(&target_epoch)
+            .and_then(|roots| roots.get(&attestation_data_root))
+            .map_or(false, |validators| validators.contains(&validator_index))
+    }
+
+    /// Remember that `validator_index`'s vote for `attestation_data_root` has been processed.
+    ///
+    /// Votes for target epochs prior to the last pruned epoch are ignored.
+    pub fn insert(
+        &mut self,
+        target_epoch: Epoch,
+        attestation_data_root: Hash256,
+        validator_index: u64,
+    ) {
+        if target_epoch < self.min_epoch
+            || self.contains(target_epoch, attestation_data_root, validator_index)
+        {
+            return;
+        }
+
+        while self.len >= self.max_attesters {
+            if let Some((_, roots)) = self.epochs.pop_first() {
+                self.len -= num_attesters(&roots);
+            } else {
+                return;
+            }
+        }
+
+        self.epochs
+            .entry(target_epoch)
+            .or_default()
+            .entry(attestation_data_root)
+            .or_default()
+            .insert(validator_index);
+        self.len += 1;
+    }
+
+    /// Remove the attesters for target epochs prior to `min_epoch`, and stop caching them.
+    pub fn prune(&mut self, min_epoch: Epoch) {
+        self.min_epoch = std::cmp::max(self.min_epoch, min_epoch);
+        self.epochs = self.epochs.split_off(&self.min_epoch);
+        self.len = self.epochs.values().map(num_attesters).sum();
+    }
+
+    pub fn clear(&mut self) {
+        self.epochs.clear();
+        self.len = 0;
+    }
+
+    /// Return the number of attesters in the cache.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+fn num_attesters(roots: &HashMap<Hash256, HashSet<u64>>) -> usize {
+    roots.values().map(HashSet::len).sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn insert_and_prune() {
+        let root_1 = Hash256::from_low_u64_be(1);
+        let root_2 = Hash256::from_low_u64_be(2);
+
+        let mut cache = AttestationDedupCache::default();
+        cache.insert(Epoch::new(1), root_1, 10);
+        cache.insert(Epoch::new(1), root_1, 11);
+        cache.insert(Epoch::new(1), root_1, 11);
+        cache.insert(Epoch::new(2), root_2, 10);
+        assert_eq!(cache.len(), 3);
+
+        assert!(cache.contains(Epoch::new(1), root_1, 10));
+        assert!(!cache.contains(Epoch::new(1), root_2, 10));
+        assert!(!cache.contains(Epoch::new(2), root_1, 10));
+
+        cache.prune(Epoch::new(2));
+        assert_eq!(cache.len(), 1);
+        assert!(!cache.contains(Epoch::new(1), root_1, 10));
+        assert!(cache.contains(Epoch::new(2), root_2, 10));
+
+        // Attesters for pruned epochs are not cached again.
+        cache.insert(Epoch::new(1), root_1, 10);
+        assert!(!cache.contains(Epoch::new(1), root_1, 10));
+        assert_eq!(cache.len(), 1);
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn evicts_oldest_epoch_when_full() {
+        let root = Hash256::from_low_u64_be(1);
+
+        let mut cache = AttestationDedupCache::new(3);
+        cache.insert(Epoch::new(1), root, 10);
+        cache.insert(Epoch::new(1), root, 11);
+        cache.insert(Epoch::new(2), root, 10);
+        assert_eq!(cache.len(), 3);
+
+        cache.insert(Epoch::new(2), root, 11);
+        assert_eq!(cache.len(), 2);
+        assert!(!cache.contains(Epoch::new(1), root, 10));
+        assert!(!cache.contains(Epoch::new(1), root, 11));
+        assert!(cache.contains(Epoch::new(2), root, 10));
+        assert!(cache.contains(Epoch::new(2), root, 11));
+
+        // A full cache with a single epoch is emptied rather than exceeding the limit.
+        cache.insert(Epoch::new(2), root, 12);
+        assert_eq!(cache.len(), 3);
+        cache.insert(Epoch::new(2), root, 13);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.contains(Epoch::new(2), root, 13));
+    }
+}
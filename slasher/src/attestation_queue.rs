@@ -1,6 +1,6 @@
 use crate::{AttesterRecord, Config, IndexedAttesterRecord};
 use parking_lot::Mutex;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::sync::{Arc, Weak};
 use types::{EthSpec, Hash256, IndexedAttestation};
 
@@ -44,10 +44,32 @@ pub struct GroupedAttestations<E: EthSpec> {
 impl<E: EthSpec> AttestationBatch<E> {
     /// Add an attestation to the queue.
     pub fn queue(&mut self, indexed_record: Arc<IndexedAttesterRecord<E>>) {
+        self.queue_filtered(indexed_record, |_| true);
+    }
+
+    /// Add an attestation to the queue for those of its attesters which satisfy `filter`.
+    ///
+    /// The attestation is not queued at all if none of its attesters satisfy `filter`. Return the
+    /// number of attesters which were filtered out.
+    pub fn queue_filtered(
+        &mut self,
+        indexed_record: Arc<IndexedAttesterRecord<E>>,
+        mut filter: impl FnMut(u64) -> bool,
+    ) -> usize {
+        let (attesters, filtered): (Vec<u64>, Vec<u64>) = indexed_record
+            .indexed
+            .attesting_indices
+            .iter()
+            .partition(|&&validator_index| filter(validator_index));
+
+        if attesters.is_empty() {
+            return filtered.len();
+        }
+
         self.attestations.push(Arc::downgrade(&indexed_record));
 
         let attestation_data_hash = indexed_record.record.attestation_data_hash;
-        for &validator_index in &indexed_record.indexed.attesting_indices {
+        for validator_index in attesters {
             self.attesters
                 .entry((validator_index, attestation_data_hash))
                 .and_modify(|existing_entry| {
@@ -64,15 +86,27 @@ impl<E: EthSpec> AttestationBatch<E> {
                 })
                 .or_insert_with(|| indexed_record.clone());
         }
+
+        filtered.len()
     }
 
     /// Group the attestations by validator chunk index.
+    ///
+    /// Each attestation appears at most once per group, and each group is sorted by target epoch
+    /// so that attestations for the same epoch are applied to the database together.
     pub fn group_by_validator_chunk_index(self, config: &Config) -> GroupedAttestations<E> {
         let mut grouped_attestations = GroupedAttestations { subqueues: vec![] };
+        let mut grouped = HashSet::new();
 
         for ((validator_index, _), indexed_record) in self.attesters {
             let subqueue_id = config.validator_chunk_index(validator_index);
 
+            // Attestations from multiple validators in the same chunk only need to be processed
+            // once, as processing covers all of their validators in the chunk.
+            if !grouped.insert((subqueue_id, Arc::as_ptr(&indexed_record))) {
+                continue;
+            }
+
             if subqueue_id >= grouped_attestations.subqueues.len() {
                 grouped_attestations
                     .subqueues
@@ -82,6 +116,10 @@ impl<E: EthSpec> AttestationBatch<E> {
             grouped_attestations.subqueues[subqueue_id].push(indexed_record);
         }
 
+        for subqueue in &mut grouped_attestations.subqueues {
+            subqueue.sort_by_key(|indexed_record| indexed_record.indexed.data.target.epoch);
+        }
+
         grouped_attestations
     }
 }
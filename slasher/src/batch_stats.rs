@@ -1,3 +1,5 @@
+use types::Epoch;
+
 #[derive(Debug)]
 pub struct BatchStats {
     pub block_stats: BlockStats,
@@ -7,10 +9,39 @@ pub struct BatchStats {
 #[derive(Debug)]
 pub struct BlockStats {
     pub num_processed: usize,
+    /// Number of blocks which weren't already in the database.
+    pub num_new: usize,
     pub num_slashings: usize,
 }
 
 #[derive(Debug)]
 pub struct AttestationStats {
     pub num_processed: usize,
+    /// Number of indexed attestations which weren't already in the database.
+    pub num_new: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct PruneStats {
+    pub num_proposers_pruned: usize,
+    pub num_indexed_attestations_pruned: usize,
+    /// Number of epochs of expired data left in the database after pruning.
+    pub backlog_epochs: u64,
+}
+
+/// Statistics about the contents of the slasher's database and its progress.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlasherStats {
+    /// Total size of the database files, in bytes.
+    pub database_size: u64,
+    pub num_attestations: usize,
+    pub num_blocks: usize,
+    /// Epoch at which attestations and blocks were last processed successfully.
+    pub last_processed_epoch: Option<Epoch>,
+    /// Number of epochs of expired data left in the database after the last pruning.
+    pub prune_backlog_epochs: u64,
+    /// Number of attester slashings found since the slasher was started.
+    pub num_attester_slashings: u64,
+    /// Number of proposer slashings found since the slasher was started.
+    pub num_proposer_slashings: u64,
 }
@@ -1,7 +1,7 @@
 use crate::Error;
 use serde_derive::{Deserialize, Serialize};
 use std::path::PathBuf;
-use strum::{Display, EnumString, EnumVariantNames};
+use strum::{Display, EnumIter, EnumString, EnumVariantNames, IntoEnumIterator};
 use types::{Epoch, EthSpec, IndexedAttestation};
 
 pub const DEFAULT_CHUNK_SIZE: usize = 16;
@@ -12,6 +12,7 @@ pub const DEFAULT_SLOT_OFFSET: f64 = 10.5;
 pub const DEFAULT_MAX_DB_SIZE: usize = 256 * 1024; // 256 GiB
 pub const DEFAULT_ATTESTATION_ROOT_CACHE_SIZE: usize = 100_000;
 pub const DEFAULT_BROADCAST: bool = false;
+pub const DEFAULT_PRUNE_BATCH_SIZE: usize = 100_000;
 
 #[cfg(all(feature = "mdbx", not(feature = "lmdb")))]
 pub const DEFAULT_BACKEND: DatabaseBackend = DatabaseBackend::Mdbx;
@@ -23,6 +24,7 @@ pub const DEFAULT_BACKEND: DatabaseBackend = DatabaseBackend::Disabled;
 pub const MAX_HISTORY_LENGTH: usize = 1 << 16;
 pub const MEGABYTE: usize = 1 << 20;
 pub const MDBX_DATA_FILENAME: &str = "mdbx.dat";
+pub const LMDB_DATA_FILENAME: &str = "data.mdb";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -41,8 +43,12 @@ pub struct Config {
     pub attestation_root_cache_size: usize,
     /// Whether to broadcast slashings found to the network.
     pub broadcast: bool,
+    /// Maximum number of blocks, and maximum number of attestations, to delete per pruning run.
+    pub prune_batch_size: usize,
     /// Database backend to use.
     pub backend: DatabaseBackend,
+    /// Whether to migrate an existing database from another backend into `backend`.
+    pub migrate_backend: bool,
 }
 
 /// Immutable configuration parameters which are stored on disk and checked for consistency.
@@ -54,7 +60,16 @@ pub struct DiskConfig {
 }
 
 #[derive(
-    Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Display, EnumString, EnumVariantNames,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    EnumVariantNames,
+    EnumIter,
 )]
 #[strum(serialize_all = "lowercase")]
 pub enum DatabaseBackend {
@@ -62,9 +77,24 @@ pub enum DatabaseBackend {
     Mdbx,
     #[cfg(feature = "lmdb")]
     Lmdb,
+    /// Ephemeral database which is lost when the beacon node is shut down.
+    Memory,
     Disabled,
 }
 
+impl DatabaseBackend {
+    /// The name of the file which holds the data for this backend, if it persists data to disk.
+    pub fn data_filename(self) -> Option<&'static str> {
+        match self {
+            #[cfg(feature = "mdbx")]
+            DatabaseBackend::Mdbx => Some(MDBX_DATA_FILENAME),
+            #[cfg(feature = "lmdb")]
+            DatabaseBackend::Lmdb => Some(LMDB_DATA_FILENAME),
+            DatabaseBackend::Memory | DatabaseBackend::Disabled => None,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum DatabaseBackendOverride {
     Success(DatabaseBackend),
@@ -84,7 +114,9 @@ impl Config {
             max_db_size_mbs: DEFAULT_MAX_DB_SIZE,
             attestation_root_cache_size: DEFAULT_ATTESTATION_ROOT_CACHE_SIZE,
             broadcast: DEFAULT_BROADCAST,
+            prune_batch_size: DEFAULT_PRUNE_BATCH_SIZE,
             backend: DEFAULT_BACKEND,
+            migrate_backend: false,
         }
     }
 
@@ -93,6 +125,7 @@ impl Config {
             || self.validator_chunk_size == 0
             || self.history_length == 0
             || self.max_db_size_mbs == 0
+            || self.prune_batch_size == 0
         {
             Err(Error::ConfigInvalidZeroParameter {
                 config: self.clone(),
@@ -170,6 +203,16 @@ impl Config {
             .copied()
     }
 
+    /// Return a backend other than the configured one which has a database in `database_path`.
+    pub fn existing_other_backend(&self) -> Option<DatabaseBackend> {
+        DatabaseBackend::iter().find(|backend| {
+            *backend != self.backend
+                && backend
+                    .data_filename()
+                    .map_or(false, |filename| self.database_path.join(filename).exists())
+        })
+    }
+
     pub fn override_backend(&mut self) -> DatabaseBackendOverride {
         let mdbx_path = self.database_path.join(MDBX_DATA_FILENAME);
 
@@ -1,13 +1,14 @@
 pub mod interface;
 mod lmdb_impl;
 mod mdbx_impl;
+mod memory_impl;
 
 use crate::{
-    metrics, AttesterRecord, AttesterSlashingStatus, CompactAttesterRecord, Config, Error,
-    ProposerSlashingStatus,
+    batch_stats::PruneStats, config::DiskConfig, metrics, AttesterRecord, AttesterSlashingStatus,
+    CompactAttesterRecord, Config, Error, ProposerSlashingStatus,
 };
 use byteorder::{BigEndian, ByteOrder};
-use interface::{Environment, OpenDatabases, RwTransaction};
+use interface::{Database, Environment, OpenDatabases, RwTransaction};
 use lru::LruCache;
 use parking_lot::Mutex;
 use serde::de::DeserializeOwned;
@@ -251,7 +252,6 @@ impl<E: EthSpec> SlasherDB<E> {
         std::fs::create_dir_all(&config.database_path)?;
 
         let env = Box::leak(Box::new(Environment::new(&config)?));
-        let databases = env.create_databases()?;
 
         #[cfg(windows)]
         {
@@ -261,6 +261,16 @@ impl<E: EthSpec> SlasherDB<E> {
             }
         }
 
+        Self::open_in_environment(env, config, log)
+    }
+
+    /// Open the databases in `env`, migrating them to the current schema and config if necessary.
+    pub(crate) fn open_in_environment(
+        env: &'static Environment,
+        config: Arc<Config>,
+        log: Logger,
+    ) -> Result<Self, Error> {
+        let databases = env.create_databases()?;
         let attestation_root_cache = Mutex::new(LruCache::new(config.attestation_root_cache_size));
 
         let mut db = Self {
@@ -274,15 +284,18 @@ impl<E: EthSpec> SlasherDB<E> {
         db = db.migrate()?;
 
         let mut txn = db.begin_rw_txn()?;
-        if let Some(on_disk_config) = db.load_config(&mut txn)? {
-            let current_disk_config = db.config.disk_config();
-            if current_disk_config != on_disk_config {
-                return Err(Error::ConfigIncompatible {
-                    on_disk_config,
-                    config: current_disk_config,
-                });
+        let on_disk_config = db.load_config::<DiskConfig>(&mut txn)?;
+        drop(txn);
+
+        let current_disk_config = db.config.disk_config();
+        if let Some(on_disk_config) = on_disk_config {
+            if on_disk_config != current_disk_config {
+                db.migrate_config(&on_disk_config, &log)?;
             }
         }
+
+        let mut txn = db.begin_rw_txn()?;
+        db.store_config(&current_disk_config, &mut txn)?;
         txn.commit()?;
 
         Ok(db)
@@ -320,7 +333,11 @@ impl<E: EthSpec> SlasherDB<E> {
             .transpose()
     }
 
-    pub fn store_config(&self, config: &Config, txn: &mut RwTransaction<'_>) -> Result<(), Error> {
+    pub fn store_config(
+        &self,
+        config: &DiskConfig,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<(), Error> {
         txn.put(
             &self.databases.metadata_db,
             METADATA_CONFIG_KEY,
@@ -430,12 +447,13 @@ impl<E: EthSpec> SlasherDB<E> {
     /// Store an indexed attestation and return its ID.
     ///
     /// If the attestation is already stored then the existing ID will be returned without a write.
+    /// Store an indexed attestation, returning its ID and whether it was newly stored.
     pub fn store_indexed_attestation(
         &self,
         txn: &mut RwTransaction<'_>,
         indexed_attestation_hash: Hash256,
         indexed_attestation: &IndexedAttestation<E>,
-    ) -> Result<u64, Error> {
+    ) -> Result<(u64, bool), Error> {
         // Look-up ID by hash.
         let id_key = IndexedAttestationIdKey::new(
             indexed_attestation.data.target.epoch,
@@ -443,7 +461,7 @@ impl<E: EthSpec> SlasherDB<E> {
         );
 
         if let Some(indexed_att_id) = self.get_indexed_attestation_id(txn, &id_key)? {
-            return Ok(indexed_att_id);
+            return Ok((indexed_att_id, false));
         }
 
         // Store the new indexed attestation at the end of the current table.
@@ -465,7 +483,7 @@ impl<E: EthSpec> SlasherDB<E> {
         // Update the (epoch, hash) to ID mapping.
         self.put_indexed_attestation_id(txn, &id_key, attestation_key)?;
 
-        Ok(indexed_att_id)
+        Ok((indexed_att_id, true))
     }
 
     pub fn get_indexed_attestation(
@@ -659,16 +677,71 @@ impl<E: EthSpec> SlasherDB<E> {
                 &ProposerKey::new(proposer_index, slot),
                 &block_header.as_ssz_bytes(),
             )?;
-            Ok(ProposerSlashingStatus::NotSlashable)
+            Ok(ProposerSlashingStatus::Stored)
+        }
+    }
+
+    /// Return the total size of the database files, in bytes.
+    pub fn database_size(&self) -> u64 {
+        self.env
+            .filenames(&self.config)
+            .iter()
+            .filter_map(|path| std::fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum()
+    }
+
+    /// Return the number of indexed attestations stored in the database.
+    pub fn num_indexed_attestations(&self, txn: &mut RwTransaction<'_>) -> Result<usize, Error> {
+        Self::num_records(txn, &self.databases.indexed_attestation_db)
+    }
+
+    /// Return the number of block proposals stored in the database.
+    pub fn num_block_proposals(&self, txn: &mut RwTransaction<'_>) -> Result<usize, Error> {
+        Self::num_records(txn, &self.databases.proposers_db)
+    }
+
+    fn num_records(txn: &mut RwTransaction<'_>, db: &Database) -> Result<usize, Error> {
+        let mut cursor = txn.cursor(db)?;
+        if cursor.first_key()?.is_none() {
+            return Ok(0);
+        }
+
+        let mut num_records = 1;
+        while cursor.next_key()?.is_some() {
+            num_records += 1;
         }
+        Ok(num_records)
     }
 
     /// Attempt to prune the database, deleting old blocks and attestations.
-    pub fn prune(&self, current_epoch: Epoch) -> Result<(), Error> {
+    ///
+    /// At most `prune_batch_size` blocks and `prune_batch_size` attestations are deleted per call,
+    /// so that a large backlog of expired data is pruned incrementally over several calls rather
+    /// than in a single long-running transaction.
+    pub fn prune(&self, current_epoch: Epoch) -> Result<PruneStats, Error> {
+        let _timer = metrics::start_timer(&metrics::SLASHER_PRUNE_TIME);
+
         let mut txn = self.begin_rw_txn()?;
-        self.try_prune(current_epoch, &mut txn)?;
+        let stats = self.try_prune(current_epoch, &mut txn)?;
         txn.commit()?;
-        Ok(())
+
+        metrics::inc_counter_vec_by(
+            &metrics::SLASHER_NUM_RECORDS_PRUNED,
+            &["proposers"],
+            stats.num_proposers_pruned as u64,
+        );
+        metrics::inc_counter_vec_by(
+            &metrics::SLASHER_NUM_RECORDS_PRUNED,
+            &["indexed_attestations"],
+            stats.num_indexed_attestations_pruned as u64,
+        );
+        metrics::set_gauge(
+            &metrics::SLASHER_PRUNE_BACKLOG_EPOCHS,
+            stats.backlog_epochs as i64,
+        );
+
+        Ok(stats)
     }
 
     /// Try to prune the database.
@@ -676,35 +749,57 @@ impl<E: EthSpec> SlasherDB<E> {
         &self,
         current_epoch: Epoch,
         txn: &mut RwTransaction<'_>,
-    ) -> Result<(), Error> {
-        self.prune_proposers(current_epoch, txn)?;
-        self.prune_indexed_attestations(current_epoch, txn)?;
-        Ok(())
+    ) -> Result<PruneStats, Error> {
+        let min_epoch = current_epoch
+            .saturating_add(1u64)
+            .saturating_sub(self.config.history_length as u64);
+
+        let (num_proposers_pruned, proposers_backlog) = self.prune_proposers(min_epoch, txn)?;
+        let (num_indexed_attestations_pruned, attestations_backlog) =
+            self.prune_indexed_attestations(min_epoch, txn)?;
+
+        Ok(PruneStats {
+            num_proposers_pruned,
+            num_indexed_attestations_pruned,
+            backlog_epochs: std::cmp::max(proposers_backlog, attestations_backlog),
+        })
     }
 
+    /// Delete blocks from before `min_epoch`, up to the configured batch size.
+    ///
+    /// Return the number of blocks deleted and the number of epochs of expired blocks remaining.
     fn prune_proposers(
         &self,
-        current_epoch: Epoch,
+        min_epoch: Epoch,
         txn: &mut RwTransaction<'_>,
-    ) -> Result<(), Error> {
-        let min_slot = current_epoch
-            .saturating_add(1u64)
-            .saturating_sub(self.config.history_length)
-            .start_slot(E::slots_per_epoch());
+    ) -> Result<(usize, u64), Error> {
+        let min_slot = min_epoch.start_slot(E::slots_per_epoch());
 
         let mut cursor = txn.cursor(&self.databases.proposers_db)?;
 
         // Position cursor at first key, bailing out if the database is empty.
         if cursor.first_key()?.is_none() {
-            return Ok(());
+            return Ok((0, 0));
         }
 
+        let mut num_pruned = 0;
+        let mut backlog_epochs = 0;
+
         loop {
             let (key_bytes, _) = cursor.get_current()?.ok_or(Error::MissingProposerKey)?;
 
             let (slot, _) = ProposerKey::parse(key_bytes)?;
             if slot < min_slot {
+                // Leave the remaining blocks for the next run once the batch is full.
+                if num_pruned >= self.config.prune_batch_size {
+                    backlog_epochs = min_epoch
+                        .saturating_sub(slot.epoch(E::slots_per_epoch()))
+                        .as_u64();
+                    break;
+                }
+
                 cursor.delete_current()?;
+                num_pruned += 1;
 
                 // End the loop if there is no next entry.
                 if cursor.next_key()?.is_none() {
@@ -715,26 +810,27 @@ impl<E: EthSpec> SlasherDB<E> {
             }
         }
 
-        Ok(())
+        Ok((num_pruned, backlog_epochs))
     }
 
+    /// Delete attestations targeting epochs before `min_epoch`, up to the configured batch size.
+    ///
+    /// Return the number of attestations deleted and the number of epochs of expired attestations
+    /// remaining.
     fn prune_indexed_attestations(
         &self,
-        current_epoch: Epoch,
+        min_epoch: Epoch,
         txn: &mut RwTransaction<'_>,
-    ) -> Result<(), Error> {
-        let min_epoch = current_epoch
-            .saturating_add(1u64)
-            .saturating_sub(self.config.history_length as u64);
-
+    ) -> Result<(usize, u64), Error> {
         // Collect indexed attestation IDs to delete.
         let mut indexed_attestation_ids = vec![];
+        let mut backlog_epochs = 0;
 
         let mut cursor = txn.cursor(&self.databases.indexed_attestation_id_db)?;
 
         // Position cursor at first key, bailing out if the database is empty.
         if cursor.first_key()?.is_none() {
-            return Ok(());
+            return Ok((0, 0));
         }
 
         loop {
@@ -745,6 +841,12 @@ impl<E: EthSpec> SlasherDB<E> {
             let (target_epoch, _) = IndexedAttestationIdKey::parse(key_bytes)?;
 
             if target_epoch < min_epoch {
+                // Leave the remaining attestations for the next run once the batch is full.
+                if indexed_attestation_ids.len() >= self.config.prune_batch_size {
+                    backlog_epochs = min_epoch.saturating_sub(target_epoch).as_u64();
+                    break;
+                }
+
                 indexed_attestation_ids.push(IndexedAttestationId::new(
                     IndexedAttestationId::parse(value)?,
                 ));
@@ -759,6 +861,7 @@ impl<E: EthSpec> SlasherDB<E> {
             }
         }
         drop(cursor);
+        let num_pruned = indexed_attestation_ids.len();
 
         // Delete the indexed attestations.
         // Optimisation potential: use a cursor here.
@@ -768,6 +871,6 @@ impl<E: EthSpec> SlasherDB<E> {
         }
         self.delete_attestation_data_roots(indexed_attestation_ids);
 
-        Ok(())
+        Ok((num_pruned, backlog_epochs))
     }
 }
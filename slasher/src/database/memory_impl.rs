@@ -0,0 +1,226 @@
+//! An in-memory database backend.
+//!
+//! All data is lost when the slasher is shut down, so this backend is only suitable for testing or
+//! for running an ephemeral slasher.
+use crate::{
+    database::{
+        interface::{Key, OpenDatabases, Value},
+        *,
+    },
+    Error,
+};
+use parking_lot::{Mutex, MutexGuard};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+use std::ops::Bound;
+use std::path::PathBuf;
+
+type Table = BTreeMap<Vec<u8>, Vec<u8>>;
+type Tables = [Table; MAX_NUM_DBS];
+
+/// Log of `(table_index, key, previous_value)` for every write made by a transaction.
+type UndoLog = Vec<(usize, Vec<u8>, Option<Vec<u8>>)>;
+
+#[derive(Debug, Default)]
+pub struct Environment {
+    tables: Mutex<Tables>,
+}
+
+/// A transaction holding exclusive access to the environment.
+///
+/// Writes are applied immediately and rolled back using the undo log if the transaction is dropped
+/// without being committed.
+#[derive(Debug)]
+pub struct RwTransaction<'env> {
+    tables: MutexGuard<'env, Tables>,
+    undo_log: UndoLog,
+}
+
+#[derive(Debug)]
+pub struct Database<'env> {
+    index: usize,
+    _phantom: PhantomData<&'env ()>,
+}
+
+#[derive(Debug)]
+pub struct Cursor<'env> {
+    tables: &'env mut Tables,
+    undo_log: &'env mut UndoLog,
+    index: usize,
+    current: Option<Vec<u8>>,
+}
+
+impl Environment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn create_databases(&self) -> Result<OpenDatabases, Error> {
+        let mut next_index = 0;
+        let mut next_db = || {
+            let db = crate::Database::Memory(Database {
+                index: next_index,
+                _phantom: PhantomData,
+            });
+            next_index += 1;
+            db
+        };
+
+        Ok(OpenDatabases {
+            indexed_attestation_db: next_db(),
+            indexed_attestation_id_db: next_db(),
+            attesters_db: next_db(),
+            attesters_max_targets_db: next_db(),
+            min_targets_db: next_db(),
+            max_targets_db: next_db(),
+            current_epochs_db: next_db(),
+            proposers_db: next_db(),
+            metadata_db: next_db(),
+        })
+    }
+
+    pub fn begin_rw_txn(&self) -> Result<RwTransaction, Error> {
+        Ok(RwTransaction {
+            tables: self.tables.lock(),
+            undo_log: vec![],
+        })
+    }
+
+    pub fn filenames(&self) -> Vec<PathBuf> {
+        vec![]
+    }
+}
+
+impl<'env> RwTransaction<'env> {
+    pub fn get<K: AsRef<[u8]> + ?Sized>(
+        &'env self,
+        db: &Database<'env>,
+        key: &K,
+    ) -> Result<Option<Cow<'env, [u8]>>, Error> {
+        Ok(self.tables[db.index]
+            .get(key.as_ref())
+            .map(|value| Cow::Borrowed(value.as_slice())))
+    }
+
+    pub fn put<K: AsRef<[u8]>, V: AsRef<[u8]>>(
+        &mut self,
+        db: &Database,
+        key: K,
+        value: V,
+    ) -> Result<(), Error> {
+        put(
+            &mut *self.tables,
+            &mut self.undo_log,
+            db.index,
+            key.as_ref(),
+            value.as_ref(),
+        );
+        Ok(())
+    }
+
+    pub fn del<K: AsRef<[u8]>>(&mut self, db: &Database, key: K) -> Result<(), Error> {
+        del(
+            &mut *self.tables,
+            &mut self.undo_log,
+            db.index,
+            key.as_ref(),
+        );
+        Ok(())
+    }
+
+    pub fn cursor<'a>(&'a mut self, db: &Database) -> Result<Cursor<'a>, Error> {
+        Ok(Cursor {
+            tables: &mut *self.tables,
+            undo_log: &mut self.undo_log,
+            index: db.index,
+            current: None,
+        })
+    }
+
+    pub fn commit(mut self) -> Result<(), Error> {
+        self.undo_log.clear();
+        Ok(())
+    }
+}
+
+impl<'env> Drop for RwTransaction<'env> {
+    fn drop(&mut self) {
+        // Roll back any uncommitted writes, most recent first.
+        while let Some((index, key, previous_value)) = self.undo_log.pop() {
+            match previous_value {
+                Some(value) => self.tables[index].insert(key, value),
+                None => self.tables[index].remove(&key),
+            };
+        }
+    }
+}
+
+impl<'env> Cursor<'env> {
+    pub fn first_key(&mut self) -> Result<Option<Key>, Error> {
+        self.current = self.table().keys().next().cloned();
+        Ok(self.current.clone().map(Cow::Owned))
+    }
+
+    pub fn last_key(&mut self) -> Result<Option<Key>, Error> {
+        self.current = self.table().keys().next_back().cloned();
+        Ok(self.current.clone().map(Cow::Owned))
+    }
+
+    pub fn next_key(&mut self) -> Result<Option<Key>, Error> {
+        let next = match &self.current {
+            Some(current) => self
+                .table()
+                .range::<[u8], _>((Bound::Excluded(current.as_slice()), Bound::Unbounded))
+                .next()
+                .map(|(key, _)| key.clone()),
+            None => self.table().keys().next().cloned(),
+        };
+        if next.is_some() {
+            self.current = next.clone();
+        }
+        Ok(next.map(Cow::Owned))
+    }
+
+    pub fn get_current(&mut self) -> Result<Option<(Key, Value)>, Error> {
+        Ok(self.current.as_ref().and_then(|key| {
+            self.table()
+                .get(key)
+                .map(|value| (Cow::Owned(key.clone()), Cow::Owned(value.clone())))
+        }))
+    }
+
+    pub fn delete_current(&mut self) -> Result<(), Error> {
+        if let Some(key) = &self.current {
+            del(self.tables, self.undo_log, self.index, key);
+        }
+        Ok(())
+    }
+
+    pub fn put<K: AsRef<[u8]>, V: AsRef<[u8]>>(&mut self, key: K, value: V) -> Result<(), Error> {
+        put(
+            self.tables,
+            self.undo_log,
+            self.index,
+            key.as_ref(),
+            value.as_ref(),
+        );
+        self.current = Some(key.as_ref().to_vec());
+        Ok(())
+    }
+
+    fn table(&self) -> &Table {
+        &self.tables[self.index]
+    }
+}
+
+fn put(tables: &mut Tables, undo_log: &mut UndoLog, index: usize, key: &[u8], value: &[u8]) {
+    let previous_value = tables[index].insert(key.to_vec(), value.to_vec());
+    undo_log.push((index, key.to_vec(), previous_value));
+}
+
+fn del(tables: &mut Tables, undo_log: &mut UndoLog, index: usize, key: &[u8]) {
+    if let Some(previous_value) = tables[index].remove(key) {
+        undo_log.push((index, key.to_vec(), Some(previous_value)));
+    }
+}
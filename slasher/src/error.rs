@@ -1,4 +1,4 @@
-use crate::config::{Config, DiskConfig};
+use crate::config::{Config, DatabaseBackend, DiskConfig};
 use std::io;
 use types::Epoch;
 
@@ -9,6 +9,8 @@ pub enum Error {
     #[cfg(feature = "lmdb")]
     DatabaseLmdbError(lmdb::Error),
     SlasherDatabaseBackendDisabled,
+    /// Existing databases cannot be migrated into a backend which does not persist to disk.
+    BackendMigrationUnsupported(DatabaseBackend),
     MismatchedDatabaseVariant,
     DatabaseIOError(io::Error),
     DatabasePermissionsError(filesystem::Error),
@@ -65,6 +67,10 @@ pub enum Error {
     MissingProposerKey,
     MissingIndexedAttestationId,
     MissingIndexedAttestationIdKey,
+    MissingDatabaseRecord,
+    /// The file being imported is not a slasher database export, or is corrupt.
+    ExportFormatInvalid,
+    ImportDatabaseNotEmpty,
     InconsistentAttestationDataRoot,
 }
 
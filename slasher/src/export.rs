@@ -0,0 +1,162 @@
+//! Export and import of the slasher database in a portable, backend-independent format.
+//!
+//! The format is a zlib-compressed stream containing a header followed by every record from every
+//! database:
+//!
+//! - Header: magic bytes, format version (`u64`), schema version (`u64`) and the bincode-encoded
+//!   `DiskConfig` (prefixed by its length as a `u32`).
+//! - Records: database index (`u8`), key length (`u32`), key, value length (`u32`), value.
+//! - Terminator: the byte `END_OF_RECORDS`.
+//!
+//! All integers are big-endian.
+use crate::{
+    config::DiskConfig, database::CURRENT_SCHEMA_VERSION, Error, RwTransaction, SlasherDB,
+};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
+use std::io::{Read, Write};
+use types::EthSpec;
+
+const MAGIC: &[u8; 8] = b"LHSLASHR";
+const FORMAT_VERSION: u64 = 1;
+const END_OF_RECORDS: u8 = 0xff;
+
+/// Number of records to import before committing the transaction.
+const IMPORT_BATCH_SIZE: usize = 100_000;
+
+impl<E: EthSpec> SlasherDB<E> {
+    /// Write every record in the database to `writer`, returning the number of records written.
+    pub fn export<W: Write>(&self, writer: W) -> Result<usize, Error> {
+        let mut encoder = ZlibEncoder::new(writer, Compression::default());
+
+        encoder.write_all(MAGIC)?;
+        encoder.write_u64::<BigEndian>(FORMAT_VERSION)?;
+        encoder.write_u64::<BigEndian>(CURRENT_SCHEMA_VERSION)?;
+        write_bytes(
+            &mut encoder,
+            &bincode::serialize(&self.config.disk_config())?,
+        )?;
+
+        let mut num_records = 0;
+        let mut txn = self.begin_rw_txn()?;
+
+        for (index, db) in self.databases.all().into_iter().enumerate() {
+            let mut cursor = txn.cursor(db)?;
+
+            // Skip the database if it is empty.
+            if cursor.first_key()?.is_none() {
+                continue;
+            }
+
+            loop {
+                let (key, value) = cursor.get_current()?.ok_or(Error::MissingDatabaseRecord)?;
+                encoder.write_u8(index as u8)?;
+                write_bytes(&mut encoder, &key)?;
+                write_bytes(&mut encoder, &value)?;
+                num_records += 1;
+
+                if cursor.next_key()?.is_none() {
+                    break;
+                }
+            }
+        }
+        drop(txn);
+
+        encoder.write_u8(END_OF_RECORDS)?;
+        encoder.finish()?;
+
+        Ok(num_records)
+    }
+
+    /// Read the records written by `export` from `reader` into the database, returning the number
+    /// of records imported.
+    ///
+    /// The database must be empty, and must be configured with the same chunk sizes and history
+    /// length as the exported database. Records are committed in batches, so an import which fails
+    /// part-way through may leave some records in the database.
+    pub fn import<R: Read>(&self, reader: R) -> Result<usize, Error> {
+        let mut decoder = ZlibDecoder::new(reader);
+
+        let mut magic = [0; 8];
+        decoder.read_exact(&mut magic)?;
+        let format_version = decoder.read_u64::<BigEndian>()?;
+        if &magic != MAGIC || format_version != FORMAT_VERSION {
+            return Err(Error::ExportFormatInvalid);
+        }
+
+        let schema_version = decoder.read_u64::<BigEndian>()?;
+        if schema_version != CURRENT_SCHEMA_VERSION {
+            return Err(Error::IncompatibleSchemaVersion {
+                database_schema_version: schema_version,
+                software_schema_version: CURRENT_SCHEMA_VERSION,
+            });
+        }
+
+        let exported_config: DiskConfig = bincode::deserialize(&read_bytes(&mut decoder)?)?;
+        let config = self.config.disk_config();
+        if exported_config != config {
+            return Err(Error::ConfigIncompatible {
+                on_disk_config: exported_config,
+                config,
+            });
+        }
+
+        let databases = self.databases.all();
+        let mut txn = self.begin_rw_txn()?;
+        if !self.is_empty(&mut txn)? {
+            return Err(Error::ImportDatabaseNotEmpty);
+        }
+
+        let mut num_records = 0;
+        loop {
+            let index = decoder.read_u8()?;
+            if index == END_OF_RECORDS {
+                break;
+            }
+
+            let db = databases
+                .get(index as usize)
+                .ok_or(Error::ExportFormatInvalid)?;
+            let key = read_bytes(&mut decoder)?;
+            let value = read_bytes(&mut decoder)?;
+            txn.put(db, key, value)?;
+            num_records += 1;
+
+            if num_records % IMPORT_BATCH_SIZE == 0 {
+                txn.commit()?;
+                txn = self.begin_rw_txn()?;
+            }
+        }
+        txn.commit()?;
+
+        Ok(num_records)
+    }
+
+    /// Return `true` if none of the databases contain any records.
+    ///
+    /// The metadata written when the database is opened is ignored, and overwritten by the import.
+    fn is_empty(&self, txn: &mut RwTransaction<'_>) -> Result<bool, Error> {
+        for db in self.databases.all() {
+            if std::ptr::eq(db, &self.databases.metadata_db) {
+                continue;
+            }
+            if txn.cursor(db)?.first_key()?.is_some() {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+fn write_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> Result<(), Error> {
+    writer.write_u32::<BigEndian>(bytes.len() as u32)?;
+    writer.write_all(bytes)?;
+    Ok(())
+}
+
+fn read_bytes<R: Read>(reader: &mut R) -> Result<Vec<u8>, Error> {
+    let len = reader.read_u32::<BigEndian>()? as usize;
+    let mut bytes = vec![0; len];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
@@ -5,6 +5,7 @@
 )]
 
 mod array;
+mod attestation_dedup_cache;
 mod attestation_queue;
 mod attester_record;
 mod batch_stats;
@@ -12,6 +13,7 @@ mod block_queue;
 pub mod config;
 mod database;
 mod error;
+mod export;
 pub mod metrics;
 mod migrate;
 mod slasher;
@@ -20,6 +22,7 @@ pub mod test_utils;
 pub use crate::slasher::Slasher;
 pub use attestation_queue::{AttestationBatch, AttestationQueue, SimpleBatch};
 pub use attester_record::{AttesterRecord, CompactAttesterRecord, IndexedAttesterRecord};
+pub use batch_stats::SlasherStats;
 pub use block_queue::BlockQueue;
 pub use config::{Config, DatabaseBackend, DatabaseBackendOverride};
 pub use database::{
@@ -27,6 +30,7 @@ pub use database::{
     IndexedAttestationId, SlasherDB,
 };
 pub use error::Error;
+pub use migrate::migrate_backend;
 
 use types::{AttesterSlashing, EthSpec, IndexedAttestation, ProposerSlashing};
 
@@ -45,6 +49,8 @@ pub enum AttesterSlashingStatus<E: EthSpec> {
 #[derive(Debug, PartialEq)]
 pub enum ProposerSlashingStatus {
     NotSlashable,
+    /// The block is not slashable and was stored for the first time.
+    Stored,
     DoubleVote(Box<ProposerSlashing>),
 }
 
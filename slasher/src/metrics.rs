@@ -10,6 +10,10 @@ lazy_static! {
         "slasher_process_batch_time",
         "Time taken to process a batch of blocks and attestations"
     );
+    pub static ref SLASHER_BROADCAST_QUEUE_SIZE: Result<IntGauge> = try_create_int_gauge(
+        "slasher_broadcast_queue_size",
+        "Number of slashings waiting to be broadcast or included on chain"
+    );
     pub static ref SLASHER_NUM_ATTESTATIONS_DROPPED: Result<IntGauge> = try_create_int_gauge(
         "slasher_num_attestations_dropped",
         "Number of attestations dropped per batch"
@@ -18,6 +22,10 @@ lazy_static! {
         "slasher_num_attestations_deferred",
         "Number of attestations deferred per batch"
     );
+    pub static ref SLASHER_NUM_ATTESTERS_DEDUPLICATED: Result<IntGauge> = try_create_int_gauge(
+        "slasher_num_attesters_deduplicated",
+        "Number of attesters skipped per batch because their vote had already been processed"
+    );
     pub static ref SLASHER_NUM_ATTESTATIONS_VALID: Result<IntGauge> = try_create_int_gauge(
         "slasher_num_attestations_valid",
         "Number of valid attestations per batch"
@@ -53,4 +61,17 @@ lazy_static! {
         "slasher_attestation_root_cache_size",
         "Number of attestation data roots cached in memory"
     );
+    pub static ref SLASHER_PRUNE_TIME: Result<Histogram> = try_create_histogram(
+        "slasher_prune_time",
+        "Time taken to prune old blocks and attestations from the database"
+    );
+    pub static ref SLASHER_NUM_RECORDS_PRUNED: Result<IntCounterVec> = try_create_int_counter_vec(
+        "slasher_num_records_pruned",
+        "Number of blocks or attestations deleted from the database by pruning",
+        &["type"],
+    );
+    pub static ref SLASHER_PRUNE_BACKLOG_EPOCHS: Result<IntGauge> = try_create_int_gauge(
+        "slasher_prune_backlog_epochs",
+        "Number of epochs of expired data remaining to be pruned from the database"
+    );
 }
@@ -1,5 +1,17 @@
-use crate::{database::CURRENT_SCHEMA_VERSION, Error, SlasherDB};
-use types::EthSpec;
+use crate::{
+    array,
+    config::DiskConfig,
+    database::{IndexedAttestationIdKey, CURRENT_SCHEMA_VERSION},
+    AttestationBatch, AttesterRecord, Config, Database, Environment, Error, IndexedAttestationId,
+    IndexedAttesterRecord, RwTransaction, SlasherDB,
+};
+use slog::{debug, info, Logger};
+use ssz::Decode;
+use std::sync::Arc;
+use types::{Epoch, EthSpec, IndexedAttestation};
+
+/// Number of indexed attestations to replay per transaction when migrating to a new config.
+const CONFIG_MIGRATION_BATCH_SIZE: u64 = 16_384;
 
 impl<E: EthSpec> SlasherDB<E> {
     /// If the database exists, and has a schema, attempt to migrate it to the current version.
@@ -26,4 +38,329 @@ impl<E: EthSpec> SlasherDB<E> {
             Ok(self)
         }
     }
+
+    /// Rebuild the parts of the database which depend on the chunk size, validator chunk size and
+    /// history length, by replaying every stored attestation under the current config.
+    ///
+    /// Attestations which fall outside of a reduced history length are not replayed, and are
+    /// deleted by subsequent pruning. If the migration is interrupted it is restarted from scratch
+    /// the next time the database is opened, as `on_disk_config` is only replaced once it
+    /// completes.
+    pub(crate) fn migrate_config(
+        &self,
+        on_disk_config: &DiskConfig,
+        log: &Logger,
+    ) -> Result<(), Error> {
+        info!(
+            log,
+            "Migrating slasher database to new config";
+            "from_config" => ?on_disk_config,
+            "to_config" => ?self.config.disk_config(),
+        );
+
+        let mut txn = self.begin_rw_txn()?;
+        for db in [
+            &self.databases.attesters_db,
+            &self.databases.attesters_max_targets_db,
+            &self.databases.min_targets_db,
+            &self.databases.max_targets_db,
+            &self.databases.current_epochs_db,
+        ] {
+            clear_database(&mut txn, db)?;
+        }
+
+        // Replay the attestations as if they were all received in the epoch of the latest one.
+        let current_epoch = match txn
+            .cursor(&self.databases.indexed_attestation_id_db)?
+            .last_key()?
+            .map(IndexedAttestationIdKey::parse)
+            .transpose()?
+        {
+            Some((target_epoch, _)) => target_epoch,
+            None => Epoch::new(0),
+        };
+        let min_epoch = current_epoch
+            .saturating_add(1u64)
+            .saturating_sub(self.config.history_length as u64);
+
+        // Attestation IDs are allocated sequentially, so replaying them in order of ID replays them
+        // in the order in which they were originally processed.
+        let mut cursor = txn.cursor(&self.databases.indexed_attestation_db)?;
+        let first_id = cursor
+            .first_key()?
+            .map(IndexedAttestationId::parse)
+            .transpose()?
+            .unwrap_or(1);
+        let last_id = cursor
+            .last_key()?
+            .map(IndexedAttestationId::parse)
+            .transpose()?
+            .unwrap_or(0);
+        drop(cursor);
+
+        let mut num_replayed = 0;
+        let mut num_truncated = 0;
+        let mut batch_start = first_id;
+
+        while batch_start <= last_id {
+            let batch_end = std::cmp::min(
+                batch_start.saturating_add(CONFIG_MIGRATION_BATCH_SIZE - 1),
+                last_id,
+            );
+
+            let mut batch = vec![];
+            for id in batch_start..=batch_end {
+                let indexed_attestation = match txn.get(
+                    &self.databases.indexed_attestation_db,
+                    IndexedAttestationId::new(id).as_ref(),
+                )? {
+                    Some(bytes) => IndexedAttestation::<E>::from_ssz_bytes(&bytes)?,
+                    // Deleted by pruning.
+                    None => continue,
+                };
+
+                if indexed_attestation.data.target.epoch < min_epoch {
+                    num_truncated += 1;
+                    continue;
+                }
+
+                let record = AttesterRecord::from(indexed_attestation.clone());
+                let indexed_record = IndexedAttesterRecord::new(indexed_attestation, record);
+                indexed_record.set_id(id);
+                batch.push(indexed_record);
+            }
+
+            num_replayed += batch.len();
+            self.replay_attestations(&mut txn, batch, current_epoch)?;
+            txn.commit()?;
+            txn = self.begin_rw_txn()?;
+
+            debug!(
+                log,
+                "Slasher config migration in progress";
+                "num_replayed" => num_replayed,
+                "last_id" => batch_end,
+                "max_id" => last_id,
+            );
+
+            batch_start = batch_end + 1;
+        }
+        txn.commit()?;
+
+        info!(
+            log,
+            "Slasher config migration complete";
+            "num_replayed" => num_replayed,
+            "num_truncated" => num_truncated,
+        );
+
+        Ok(())
+    }
+
+    /// Apply a batch of stored attestations to the attester records and min-max arrays.
+    ///
+    /// Any slashings found are discarded, as they were already found when the attestations were
+    /// first processed.
+    fn replay_attestations(
+        &self,
+        txn: &mut RwTransaction<'_>,
+        attestations: Vec<Arc<IndexedAttesterRecord<E>>>,
+        current_epoch: Epoch,
+    ) -> Result<(), Error> {
+        let mut batch = AttestationBatch::default();
+        for indexed_record in &attestations {
+            batch.queue(indexed_record.clone());
+        }
+
+        let grouped_attestations = batch.group_by_validator_chunk_index(&self.config);
+        for (subqueue_id, subqueue) in grouped_attestations.subqueues.into_iter().enumerate() {
+            for attestation in &subqueue {
+                for validator_index in self
+                    .config
+                    .attesting_validators_in_chunk(&attestation.indexed, subqueue_id)
+                {
+                    self.check_and_update_attester_record(
+                        txn,
+                        validator_index,
+                        &attestation.indexed,
+                        &attestation.record,
+                        IndexedAttestationId::new(attestation.get_id()),
+                    )?;
+                }
+            }
+
+            array::update(
+                self,
+                txn,
+                subqueue_id,
+                subqueue,
+                current_epoch,
+                &self.config,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Delete every record from `db`.
+fn clear_database(txn: &mut RwTransaction<'_>, db: &Database<'_>) -> Result<(), Error> {
+    let mut cursor = txn.cursor(db)?;
+
+    // Position cursor at first key, bailing out if the database is empty.
+    if cursor.first_key()?.is_none() {
+        return Ok(());
+    }
+
+    loop {
+        cursor.delete_current()?;
+
+        if cursor.next_key()?.is_none() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Copy an existing database which uses a different backend into the backend set in `config`, and
+/// then delete the old database.
+///
+/// Does nothing if there is no database for another backend in the database directory.
+pub fn migrate_backend(config: &Config, log: &Logger) -> Result<(), Error> {
+    let from_backend = match config.existing_other_backend() {
+        Some(backend) => backend,
+        None => return Ok(()),
+    };
+
+    if config.backend.data_filename().is_none() {
+        return Err(Error::BackendMigrationUnsupported(config.backend));
+    }
+
+    info!(
+        log,
+        "Migrating slasher database";
+        "from_backend" => %from_backend,
+        "to_backend" => %config.backend,
+    );
+
+    let from_config = Config {
+        backend: from_backend,
+        ..config.clone()
+    };
+    let from_env = Environment::new(&from_config)?;
+    let to_env = Environment::new(config)?;
+
+    let num_records = copy_databases(&from_env, &to_env)?;
+
+    // Close both databases before deleting the old one.
+    let old_files = from_env.filenames(&from_config);
+    drop(from_env);
+    drop(to_env);
+
+    for file in old_files {
+        if file.exists() {
+            std::fs::remove_file(file)?;
+        }
+    }
+
+    info!(
+        log,
+        "Slasher database migrated";
+        "records" => num_records,
+        "backend" => %config.backend,
+    );
+
+    Ok(())
+}
+
+/// Copy every record from the databases in `from_env` to the databases in `to_env`.
+///
+/// Each database is copied in its own transaction. Return the total number of records copied.
+fn copy_databases(from_env: &Environment, to_env: &Environment) -> Result<usize, Error> {
+    let from_dbs = from_env.create_databases()?;
+    let to_dbs = to_env.create_databases()?;
+    let mut num_records = 0;
+
+    for (from_db, to_db) in from_dbs.all().into_iter().zip(to_dbs.all()) {
+        let mut from_txn = from_env.begin_rw_txn()?;
+        let mut to_txn = to_env.begin_rw_txn()?;
+        let mut cursor = from_txn.cursor(from_db)?;
+
+        // Skip the database if it is empty.
+        if cursor.first_key()?.is_some() {
+            loop {
+                let (key, value) = cursor.get_current()?.ok_or(Error::MissingDatabaseRecord)?;
+                to_txn.put(to_db, key, value)?;
+                num_records += 1;
+
+                if cursor.next_key()?.is_none() {
+                    break;
+                }
+            }
+        }
+
+        drop(cursor);
+        to_txn.commit()?;
+    }
+
+    Ok(num_records)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        test_utils::{att_slashing, indexed_att, E},
+        DatabaseBackend, Slasher,
+    };
+    use logging::test_logger;
+    use maplit::hashset;
+    use std::path::PathBuf;
+
+    fn open_slasher(env: &'static Environment, config: Config) -> Slasher<E> {
+        let config = Arc::new(config);
+        let db = SlasherDB::open_in_environment(env, config.clone(), test_logger()).unwrap();
+        Slasher::from_db(db, config, test_logger())
+    }
+
+    #[test]
+    fn migrate_config_replays_attestations() {
+        let mut config = Config::new(PathBuf::new());
+        config.backend = DatabaseBackend::Memory;
+        config.chunk_size = 4;
+        config.validator_chunk_size = 4;
+        config.history_length = 16;
+        let env = Box::leak(Box::new(Environment::new(&config).unwrap()));
+
+        let slasher = open_slasher(env, config.clone());
+        let att1 = indexed_att([1, 6], 2, 3, 0);
+        slasher.accept_attestation(att1.clone());
+        slasher.process_queued(Epoch::new(4)).unwrap();
+        drop(slasher);
+
+        let mut new_config = config;
+        new_config.chunk_size = 8;
+        new_config.validator_chunk_size = 2;
+        new_config.history_length = 32;
+        let slasher = open_slasher(env, new_config);
+
+        // Double votes are detected using the rebuilt attester records.
+        let att2 = indexed_att([1], 2, 3, 1);
+        slasher.accept_attestation(att2.clone());
+        slasher.process_queued(Epoch::new(4)).unwrap();
+        assert_eq!(
+            slasher.get_attester_slashings(),
+            hashset![att_slashing(&att1, &att2)]
+        );
+
+        // Surround votes are detected using the rebuilt min-max arrays.
+        let att3 = indexed_att([6], 1, 4, 0);
+        slasher.accept_attestation(att3.clone());
+        slasher.process_queued(Epoch::new(4)).unwrap();
+        assert_eq!(
+            slasher.get_attester_slashings(),
+            hashset![att_slashing(&att3, &att1)]
+        );
+    }
 }
@@ -1,8 +1,9 @@
-use crate::batch_stats::{AttestationStats, BatchStats, BlockStats};
+use crate::attestation_dedup_cache::{AttestationDedupCache, ATTESTATION_DEDUP_CACHE_EPOCHS};
+use crate::batch_stats::{AttestationStats, BatchStats, BlockStats, PruneStats, SlasherStats};
 use crate::metrics::{
     self, SLASHER_NUM_ATTESTATIONS_DEFERRED, SLASHER_NUM_ATTESTATIONS_DROPPED,
     SLASHER_NUM_ATTESTATIONS_STORED_PER_BATCH, SLASHER_NUM_ATTESTATIONS_VALID,
-    SLASHER_NUM_BLOCKS_PROCESSED,
+    SLASHER_NUM_ATTESTERS_DEDUPLICATED, SLASHER_NUM_BLOCKS_PROCESSED,
 };
 use crate::{
     array, AttestationBatch, AttestationQueue, AttesterRecord, BlockQueue, Config, Error,
@@ -11,6 +12,7 @@ use crate::{
 use parking_lot::Mutex;
 use slog::{debug, error, info, Logger};
 use std::collections::HashSet;
+use std::io::{Read, Write};
 use std::sync::Arc;
 use types::{
     AttesterSlashing, Epoch, EthSpec, IndexedAttestation, ProposerSlashing, SignedBeaconBlockHeader,
@@ -21,29 +23,85 @@ pub struct Slasher<E: EthSpec> {
     db: SlasherDB<E>,
     attestation_queue: AttestationQueue<E>,
     block_queue: BlockQueue,
+    attestation_dedup_cache: Mutex<AttestationDedupCache>,
     attester_slashings: Mutex<HashSet<AttesterSlashing<E>>>,
     proposer_slashings: Mutex<HashSet<ProposerSlashing>>,
+    progress: Mutex<Progress>,
     config: Arc<Config>,
     log: Logger,
 }
 
+/// Record of the slasher's progress since it was started, reported by `Slasher::stats`.
+#[derive(Debug, Default)]
+struct Progress {
+    last_processed_epoch: Option<Epoch>,
+    prune_backlog_epochs: u64,
+    num_attester_slashings: u64,
+    num_proposer_slashings: u64,
+    /// Number of indexed attestations in the database, updated as they are stored and pruned.
+    num_attestations: usize,
+    /// Number of blocks in the database, updated as they are stored and pruned.
+    num_blocks: usize,
+}
+
 impl<E: EthSpec> Slasher<E> {
     pub fn open(config: Config, log: Logger) -> Result<Self, Error> {
         config.validate()?;
         let config = Arc::new(config);
         let db = SlasherDB::open(config.clone(), log.clone())?;
+        let slasher = Self::from_db(db, config, log);
+        slasher.count_records()?;
+        Ok(slasher)
+    }
+
+    /// Create a slasher using a database which has already been opened.
+    pub(crate) fn from_db(db: SlasherDB<E>, config: Arc<Config>, log: Logger) -> Self {
         let attester_slashings = Mutex::new(HashSet::new());
         let proposer_slashings = Mutex::new(HashSet::new());
         let attestation_queue = AttestationQueue::default();
         let block_queue = BlockQueue::default();
-        Ok(Self {
+        let attestation_dedup_cache = Mutex::new(AttestationDedupCache::default());
+        let progress = Mutex::new(Progress::default());
+        Self {
             db,
             attestation_queue,
             block_queue,
+            attestation_dedup_cache,
             attester_slashings,
             proposer_slashings,
+            progress,
             config,
             log,
+        }
+    }
+
+    /// Count the attestations and blocks in the database.
+    ///
+    /// This requires a scan of the database, so it is only done when the database is opened or
+    /// imported. The counts are then kept up to date as records are stored and pruned.
+    fn count_records(&self) -> Result<(), Error> {
+        let mut txn = self.db.begin_rw_txn()?;
+        let num_attestations = self.db.num_indexed_attestations(&mut txn)?;
+        let num_blocks = self.db.num_block_proposals(&mut txn)?;
+        drop(txn);
+
+        let mut progress = self.progress.lock();
+        progress.num_attestations = num_attestations;
+        progress.num_blocks = num_blocks;
+        Ok(())
+    }
+
+    /// Return statistics about the database and the slasher's progress.
+    pub fn stats(&self) -> Result<SlasherStats, Error> {
+        let progress = self.progress.lock();
+        Ok(SlasherStats {
+            database_size: self.db.database_size(),
+            num_attestations: progress.num_attestations,
+            num_blocks: progress.num_blocks,
+            last_processed_epoch: progress.last_processed_epoch,
+            prune_backlog_epochs: progress.prune_backlog_epochs,
+            num_attester_slashings: progress.num_attester_slashings,
+            num_proposer_slashings: progress.num_proposer_slashings,
         })
     }
 
@@ -65,6 +123,23 @@ impl<E: EthSpec> Slasher<E> {
         &self.log
     }
 
+    /// Write the contents of the database to `writer` in a portable format.
+    ///
+    /// See `SlasherDB::export`.
+    pub fn export_database<W: Write>(&self, writer: W) -> Result<usize, Error> {
+        self.db.export(writer)
+    }
+
+    /// Import the contents of a database written by `export_database` into this slasher's
+    /// database, which must be empty.
+    ///
+    /// See `SlasherDB::import`.
+    pub fn import_database<R: Read>(&self, reader: R) -> Result<usize, Error> {
+        let num_records = self.db.import(reader)?;
+        self.count_records()?;
+        Ok(num_records)
+    }
+
     /// Accept an attestation from the network and queue it for processing.
     pub fn accept_attestation(&self, attestation: IndexedAttestation<E>) {
         self.attestation_queue.queue(attestation);
@@ -77,10 +152,26 @@ impl<E: EthSpec> Slasher<E> {
 
     /// Apply queued blocks and attestations to the on-disk database, and detect slashings!
     pub fn process_queued(&self, current_epoch: Epoch) -> Result<BatchStats, Error> {
+        self.try_process_queued(current_epoch).map_err(|e| {
+            // The attesters processed by the failed batch were never committed to the database, so
+            // they must not be skipped when they are seen again.
+            self.attestation_dedup_cache.lock().clear();
+            e
+        })
+    }
+
+    fn try_process_queued(&self, current_epoch: Epoch) -> Result<BatchStats, Error> {
         let mut txn = self.db.begin_rw_txn()?;
         let block_stats = self.process_blocks(&mut txn)?;
         let attestation_stats = self.process_attestations(current_epoch, &mut txn)?;
         txn.commit()?;
+
+        let mut progress = self.progress.lock();
+        progress.last_processed_epoch = Some(current_epoch);
+        progress.num_blocks += block_stats.num_new;
+        progress.num_attestations += attestation_stats.num_new;
+        drop(progress);
+
         Ok(BatchStats {
             block_stats,
             attestation_stats,
@@ -93,15 +184,16 @@ impl<E: EthSpec> Slasher<E> {
     pub fn process_blocks(&self, txn: &mut RwTransaction<'_>) -> Result<BlockStats, Error> {
         let blocks = self.block_queue.dequeue();
         let num_processed = blocks.len();
+        let mut num_new = 0;
         let mut slashings = vec![];
 
         metrics::set_gauge(&SLASHER_NUM_BLOCKS_PROCESSED, blocks.len() as i64);
 
         for block in blocks {
-            if let ProposerSlashingStatus::DoubleVote(slashing) =
-                self.db.check_or_insert_block_proposal(txn, block)?
-            {
-                slashings.push(*slashing);
+            match self.db.check_or_insert_block_proposal(txn, block)? {
+                ProposerSlashingStatus::NotSlashable => (),
+                ProposerSlashingStatus::Stored => num_new += 1,
+                ProposerSlashingStatus::DoubleVote(slashing) => slashings.push(*slashing),
             }
         }
 
@@ -112,11 +204,12 @@ impl<E: EthSpec> Slasher<E> {
                 "Found {} new proposer slashings!",
                 slashings.len(),
             );
-            self.proposer_slashings.lock().extend(slashings);
+            self.add_proposer_slashings(slashings);
         }
 
         Ok(BlockStats {
             num_processed,
+            num_new,
             num_slashings,
         })
     }
@@ -147,18 +240,43 @@ impl<E: EthSpec> Slasher<E> {
         metrics::set_gauge(&SLASHER_NUM_ATTESTATIONS_DEFERRED, num_deferred as i64);
         metrics::set_gauge(&SLASHER_NUM_ATTESTATIONS_DROPPED, num_dropped as i64);
 
-        // De-duplicate attestations and sort by validator index.
+        // De-duplicate attestations and sort by validator index, skipping the attesters whose
+        // votes for the same attestation data have already been processed.
         let mut batch = AttestationBatch::default();
+        let mut dedup_cache = self.attestation_dedup_cache.lock();
+        dedup_cache.prune(current_epoch.saturating_sub(ATTESTATION_DEDUP_CACHE_EPOCHS));
 
+        let mut num_deduplicated = 0;
         for indexed_record in snapshot {
-            batch.queue(indexed_record);
+            let target_epoch = indexed_record.indexed.data.target.epoch;
+            let attestation_data_hash = indexed_record.record.attestation_data_hash;
+            num_deduplicated += batch.queue_filtered(indexed_record, |validator_index| {
+                !dedup_cache.contains(target_epoch, attestation_data_hash, validator_index)
+            });
+        }
+
+        for ((validator_index, attestation_data_hash), indexed_record) in &batch.attesters {
+            dedup_cache.insert(
+                indexed_record.indexed.data.target.epoch,
+                *attestation_data_hash,
+                *validator_index,
+            );
         }
+        drop(dedup_cache);
+
+        debug!(
+            self.log,
+            "Deduplicated attesters for slasher";
+            "num_deduplicated" => num_deduplicated,
+        );
+        metrics::set_gauge(&SLASHER_NUM_ATTESTERS_DEDUPLICATED, num_deduplicated as i64);
 
         // Insert relevant attestations into database.
         let mut num_stored = 0;
+        let mut num_new = 0;
         for weak_record in &batch.attestations {
             if let Some(indexed_record) = weak_record.upgrade() {
-                let indexed_attestation_id = self.db.store_indexed_attestation(
+                let (indexed_attestation_id, is_new) = self.db.store_indexed_attestation(
                     txn,
                     indexed_record.record.indexed_attestation_hash,
                     &indexed_record.indexed,
@@ -172,6 +290,9 @@ impl<E: EthSpec> Slasher<E> {
                 );
 
                 num_stored += 1;
+                if is_new {
+                    num_new += 1;
+                }
             }
         }
 
@@ -197,7 +318,10 @@ impl<E: EthSpec> Slasher<E> {
             self.db.attestation_root_cache_size() as i64,
         );
 
-        Ok(AttestationStats { num_processed })
+        Ok(AttestationStats {
+            num_processed,
+            num_new,
+        })
     }
 
     /// Process a batch of attestations for a range of validator indices.
@@ -226,7 +350,7 @@ impl<E: EthSpec> Slasher<E> {
                             slashings.len()
                         );
                     }
-                    self.attester_slashings.lock().extend(slashings);
+                    self.add_attester_slashings(slashings);
                 }
                 Err(e) => {
                     error!(
@@ -256,7 +380,7 @@ impl<E: EthSpec> Slasher<E> {
                         slashings.len()
                     );
                 }
-                self.attester_slashings.lock().extend(slashings);
+                self.add_attester_slashings(slashings);
             }
             Err(e) => {
                 error!(
@@ -349,7 +473,41 @@ impl<E: EthSpec> Slasher<E> {
     }
 
     /// Prune unnecessary attestations and blocks from the on-disk database.
-    pub fn prune_database(&self, current_epoch: Epoch) -> Result<(), Error> {
-        self.db.prune(current_epoch)
+    pub fn prune_database(&self, current_epoch: Epoch) -> Result<PruneStats, Error> {
+        let stats = self.db.prune(current_epoch)?;
+
+        let mut progress = self.progress.lock();
+        progress.prune_backlog_epochs = stats.backlog_epochs;
+        progress.num_blocks = progress
+            .num_blocks
+            .saturating_sub(stats.num_proposers_pruned);
+        progress.num_attestations = progress
+            .num_attestations
+            .saturating_sub(stats.num_indexed_attestations_pruned);
+        drop(progress);
+
+        Ok(stats)
+    }
+
+    fn add_attester_slashings(&self, slashings: impl IntoIterator<Item = AttesterSlashing<E>>) {
+        let mut attester_slashings = self.attester_slashings.lock();
+        let mut num_new = 0;
+        for slashing in slashings {
+            if attester_slashings.insert(slashing) {
+                num_new += 1;
+            }
+        }
+        self.progress.lock().num_attester_slashings += num_new;
+    }
+
+    fn add_proposer_slashings(&self, slashings: impl IntoIterator<Item = ProposerSlashing>) {
+        let mut proposer_slashings = self.proposer_slashings.lock();
+        let mut num_new = 0;
+        for slashing in slashings {
+            if proposer_slashings.insert(slashing) {
+                num_new += 1;
+            }
+        }
+        self.progress.lock().num_proposer_slashings += num_new;
     }
 }
@@ -0,0 +1,89 @@
+use clap::{App, Arg};
+use slasher::DatabaseBackend;
+use strum::VariantNames;
+
+pub const CMD: &str = "slasher";
+
+pub const DEFAULT_BEACON_NODE: &str = "http://localhost:5052/";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .setting(clap::AppSettings::ColoredHelp)
+        .about(
+            "Runs a slasher in a separate process from the beacon node. Blocks and attestations are \
+            received from the beacon node's HTTP event stream, and slashings are submitted to the \
+            beacon node's operation pool.",
+        )
+        .arg(
+            Arg::with_name("beacon-node")
+                .long("beacon-node")
+                .value_name("NETWORK_ADDRESS")
+                .help("Address of the beacon node HTTP API to receive events from and submit slashings to")
+                .default_value(DEFAULT_BEACON_NODE)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slasher-dir")
+                .long("slasher-dir")
+                .value_name("PATH")
+                .help(
+                    "Set the slasher's database directory. Defaults to `slasher` inside the data \
+                    directory.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slasher-backend")
+                .long("slasher-backend")
+                .value_name("DATABASE")
+                .help("Set the database backend used by the slasher")
+                .takes_value(true)
+                .possible_values(DatabaseBackend::VARIANTS),
+        )
+        .arg(
+            Arg::with_name("slasher-update-period")
+                .long("slasher-update-period")
+                .value_name("SECONDS")
+                .help("Configure how often the slasher runs batch processing")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slasher-slot-offset")
+                .long("slasher-slot-offset")
+                .value_name("SECONDS")
+                .help(
+                    "Set the delay from the start of the slot at which the slasher should ingest \
+                    attestations. Only effective if the slasher-update-period is a multiple of the \
+                    slot duration.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slasher-history-length")
+                .long("slasher-history-length")
+                .value_name("EPOCHS")
+                .help("Configure how many epochs of history the slasher keeps")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slasher-chunk-size")
+                .long("slasher-chunk-size")
+                .value_name("EPOCHS")
+                .help("Number of epochs per validator per chunk stored on disk")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slasher-validator-chunk-size")
+                .long("slasher-validator-chunk-size")
+                .value_name("NUM_VALIDATORS")
+                .help("Number of validators per chunk stored on disk")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slasher-max-db-size")
+                .long("slasher-max-db-size")
+                .value_name("GIGABYTES")
+                .help("Maximum size of the database used by the slasher")
+                .takes_value(true),
+        )
+}
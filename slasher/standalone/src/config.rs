@@ -0,0 +1,75 @@
+use clap::ArgMatches;
+use directory::{parse_path_or_default, DEFAULT_SLASHER_DIR};
+use sensitive_url::SensitiveUrl;
+use serde_derive::{Deserialize, Serialize};
+
+/// Configuration for a slasher running separately from the beacon node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    /// The beacon node to receive blocks and attestations from, and to submit slashings to.
+    pub beacon_node: SensitiveUrl,
+    pub slasher: slasher::Config,
+}
+
+impl Config {
+    pub fn from_cli(cli_args: &ArgMatches) -> Result<Self, String> {
+        let beacon_node: String = clap_utils::parse_required(cli_args, "beacon-node")?;
+        let beacon_node = SensitiveUrl::parse(&beacon_node)
+            .map_err(|e| format!("Unable to parse beacon node URL: {:?}", e))?;
+
+        let slasher_dir = match clap_utils::parse_optional(cli_args, "slasher-dir")? {
+            Some(slasher_dir) => slasher_dir,
+            None => parse_path_or_default(cli_args, "datadir")?.join(DEFAULT_SLASHER_DIR),
+        };
+        let mut slasher = slasher::Config::new(slasher_dir);
+
+        if let Some(backend) = clap_utils::parse_optional(cli_args, "slasher-backend")? {
+            slasher.backend = backend;
+        }
+
+        if let Some(update_period) = clap_utils::parse_optional(cli_args, "slasher-update-period")?
+        {
+            slasher.update_period = update_period;
+        }
+
+        if let Some(slot_offset) =
+            clap_utils::parse_optional::<f64>(cli_args, "slasher-slot-offset")?
+        {
+            if slot_offset.is_finite() {
+                slasher.slot_offset = slot_offset;
+            } else {
+                return Err(format!(
+                    "invalid float for slasher-slot-offset: {}",
+                    slot_offset
+                ));
+            }
+        }
+
+        if let Some(history_length) =
+            clap_utils::parse_optional(cli_args, "slasher-history-length")?
+        {
+            slasher.history_length = history_length;
+        }
+
+        if let Some(chunk_size) = clap_utils::parse_optional(cli_args, "slasher-chunk-size")? {
+            slasher.chunk_size = chunk_size;
+        }
+
+        if let Some(validator_chunk_size) =
+            clap_utils::parse_optional(cli_args, "slasher-validator-chunk-size")?
+        {
+            slasher.validator_chunk_size = validator_chunk_size;
+        }
+
+        if let Some(max_db_size_gbs) =
+            clap_utils::parse_optional::<usize>(cli_args, "slasher-max-db-size")?
+        {
+            slasher.max_db_size_mbs = max_db_size_gbs * 1024;
+        }
+
+        Ok(Self {
+            beacon_node,
+            slasher,
+        })
+    }
+}
@@ -0,0 +1,12 @@
+//! A slasher which runs in a separate process from the beacon node.
+//!
+//! Block headers and attestations are received from a Lighthouse beacon node's slasher event
+//! streams, and any slashings found are submitted to the beacon node's operation pool using the
+//! standard HTTP API. This allows the slasher to run on separate hardware from the beacon node.
+mod cli;
+mod config;
+mod service;
+
+pub use cli::{cli_app, CMD, DEFAULT_BEACON_NODE};
+pub use config::Config;
+pub use service::start;
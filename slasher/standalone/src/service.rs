@@ -0,0 +1,292 @@
+use crate::Config;
+use environment::RuntimeContext;
+use eth2::{
+    types::{EventKind, EventTopic},
+    BeaconNodeHttpClient, Timeouts,
+};
+use futures::stream::StreamExt;
+use slasher::{DatabaseBackendOverride, Slasher};
+use slog::{debug, error, info, trace, warn, Logger};
+use slot_clock::{SlotClock, SystemTimeSlotClock};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::time::{interval_at, sleep, Duration, Instant};
+use types::{AttesterSlashing, Epoch, EthSpec, ProposerSlashing};
+
+/// Time to wait before re-subscribing to the beacon node's event stream after it ends.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// A slashing found by the slasher, waiting to be submitted to the beacon node.
+enum Slashing<E: EthSpec> {
+    Attester(AttesterSlashing<E>),
+    Proposer(ProposerSlashing),
+}
+
+/// Open the slasher database and start the tasks which feed the slasher from the beacon node and
+/// submit the slashings it finds.
+pub async fn start<E: EthSpec>(context: RuntimeContext<E>, config: Config) -> Result<(), String> {
+    let log = context.log().clone();
+    let executor = context.executor.clone();
+    let spec = &context.eth2_config.spec;
+
+    let client = BeaconNodeHttpClient::new(
+        config.beacon_node.clone(),
+        Timeouts::set_all(Duration::from_secs(spec.seconds_per_slot)),
+    );
+
+    let genesis = client
+        .get_beacon_genesis()
+        .await
+        .map_err(|e| format!("Unable to get genesis from beacon node: {}", e))?
+        .data;
+    let slot_clock = SystemTimeSlotClock::new(
+        spec.genesis_slot,
+        Duration::from_secs(genesis.genesis_time),
+        Duration::from_secs(spec.seconds_per_slot),
+    );
+
+    let mut slasher_config = config.slasher;
+    match slasher_config.override_backend() {
+        DatabaseBackendOverride::Success(old_backend) => {
+            info!(
+                log,
+                "Slasher backend overriden";
+                "reason" => "database exists",
+                "configured_backend" => %old_backend,
+                "override_backend" => %slasher_config.backend,
+            );
+        }
+        DatabaseBackendOverride::Failure(path) => {
+            warn!(
+                log,
+                "Slasher backend override failed";
+                "advice" => "delete old MDBX database or enable MDBX backend",
+                "path" => path.display()
+            );
+        }
+        _ => {}
+    }
+    let slasher = Arc::new(
+        Slasher::open(slasher_config, log.new(slog::o!("service" => "slasher")))
+            .map_err(|e| format!("Slasher open error: {:?}", e))?,
+    );
+
+    info!(
+        log,
+        "Starting standalone slasher";
+        "beacon_node" => %config.beacon_node,
+    );
+
+    // Buffer just a single message in the channel. If the receiver is still processing, we
+    // don't need to burden them with more work (we can wait).
+    let (notif_sender, notif_receiver) = sync_channel(1);
+    let (slashing_sender, slashing_receiver) = unbounded_channel();
+    let update_period = slasher.config().update_period;
+    let slot_offset = slasher.config().slot_offset;
+
+    executor.spawn(
+        run_event_listener(client.clone(), slasher.clone(), log.clone()),
+        "slasher_event_listener",
+    );
+    executor.spawn(
+        run_notifier::<E>(
+            slot_clock,
+            update_period,
+            slot_offset,
+            notif_sender,
+            log.clone(),
+        ),
+        "slasher_notifier",
+    );
+    executor.spawn_blocking(
+        || run_processor(slasher, notif_receiver, slashing_sender),
+        "slasher_processor",
+    );
+    executor.spawn(
+        run_publisher(client, slashing_receiver, log),
+        "slasher_publisher",
+    );
+
+    Ok(())
+}
+
+/// Feed the attestations and block headers from the beacon node's slasher event streams to the
+/// slasher, re-subscribing whenever the streams end.
+///
+/// The beacon node publishes these before gossip validation rejects them, so that conflicting
+/// messages reach the slasher even though the beacon node refuses to import or propagate them.
+async fn run_event_listener<E: EthSpec>(
+    client: BeaconNodeHttpClient,
+    slasher: Arc<Slasher<E>>,
+    log: Logger,
+) {
+    loop {
+        match client
+            .get_events::<E>(&[
+                EventTopic::SlasherAttestation,
+                EventTopic::SlasherBlockHeader,
+            ])
+            .await
+        {
+            Ok(events) => {
+                info!(log, "Subscribed to beacon node events");
+                let mut events = Box::pin(events);
+
+                while let Some(event) = events.next().await {
+                    match event {
+                        Ok(EventKind::SlasherAttestation(attestation)) => {
+                            slasher.accept_attestation(*attestation)
+                        }
+                        Ok(EventKind::SlasherBlockHeader(block_header)) => {
+                            slasher.accept_block_header(block_header)
+                        }
+                        Ok(_) => (),
+                        Err(e) => debug!(
+                            log,
+                            "Unable to parse beacon node event";
+                            "error" => %e,
+                        ),
+                    }
+                }
+
+                warn!(log, "Beacon node event stream ended");
+            }
+            Err(e) => warn!(
+                log,
+                "Unable to subscribe to beacon node events";
+                "error" => %e,
+            ),
+        }
+
+        sleep(RECONNECT_DELAY).await;
+    }
+}
+
+/// Run the async notifier which periodically prompts the processor to run.
+async fn run_notifier<E: EthSpec>(
+    slot_clock: SystemTimeSlotClock,
+    update_period: u64,
+    slot_offset: f64,
+    notif_sender: SyncSender<Epoch>,
+    log: Logger,
+) {
+    let slot_offset = Duration::from_secs_f64(slot_offset);
+    let start_instant = if let Some(duration_to_next_slot) = slot_clock.duration_to_next_slot() {
+        Instant::now() + duration_to_next_slot + slot_offset
+    } else {
+        error!(log, "Error aligning slasher to slot clock");
+        Instant::now()
+    };
+    let mut interval = interval_at(start_instant, Duration::from_secs(update_period));
+
+    loop {
+        interval.tick().await;
+        if let Some(current_slot) = slot_clock.now() {
+            let current_epoch = current_slot.epoch(E::slots_per_epoch());
+            if let Err(TrySendError::Disconnected(_)) = notif_sender.try_send(current_epoch) {
+                break;
+            }
+        } else {
+            trace!(log, "Slasher has nothing to do: we are pre-genesis");
+        }
+    }
+}
+
+/// Run the blocking task that performs work, passing any slashings found to the publisher.
+fn run_processor<E: EthSpec>(
+    slasher: Arc<Slasher<E>>,
+    notif_receiver: Receiver<Epoch>,
+    slashing_sender: UnboundedSender<Slashing<E>>,
+) {
+    let log = slasher.log();
+    while let Ok(current_epoch) = notif_receiver.recv() {
+        let t = Instant::now();
+
+        let stats = match slasher.process_queued(current_epoch) {
+            Ok(stats) => Some(stats),
+            Err(e) => {
+                error!(
+                    log,
+                    "Error during scheduled slasher processing";
+                    "epoch" => current_epoch,
+                    "error" => ?e,
+                );
+                None
+            }
+        };
+
+        // Prune the database, even in the case where batch processing failed.
+        // If the database is full then pruning could help to free it up.
+        if let Err(e) = slasher.prune_database(current_epoch) {
+            error!(
+                log,
+                "Error during slasher database pruning";
+                "epoch" => current_epoch,
+                "error" => ?e,
+            );
+            continue;
+        }
+
+        let slashings = slasher
+            .get_attester_slashings()
+            .into_iter()
+            .map(Slashing::Attester)
+            .chain(
+                slasher
+                    .get_proposer_slashings()
+                    .into_iter()
+                    .map(Slashing::Proposer),
+            );
+        for slashing in slashings {
+            if slashing_sender.send(slashing).is_err() {
+                return;
+            }
+        }
+
+        if let Some(stats) = stats {
+            debug!(
+                log,
+                "Completed slasher update";
+                "epoch" => current_epoch,
+                "time_taken" => format!("{}ms", t.elapsed().as_millis()),
+                "num_attestations" => stats.attestation_stats.num_processed,
+                "num_blocks" => stats.block_stats.num_processed,
+            );
+        }
+    }
+}
+
+/// Submit slashings to the beacon node's operation pool as they are found.
+async fn run_publisher<E: EthSpec>(
+    client: BeaconNodeHttpClient,
+    mut slashing_receiver: UnboundedReceiver<Slashing<E>>,
+    log: Logger,
+) {
+    while let Some(slashing) = slashing_receiver.recv().await {
+        let (slashing_type, result) = match slashing {
+            Slashing::Attester(slashing) => (
+                "attester",
+                client.post_beacon_pool_attester_slashings(&slashing).await,
+            ),
+            Slashing::Proposer(slashing) => (
+                "proposer",
+                client.post_beacon_pool_proposer_slashings(&slashing).await,
+            ),
+        };
+
+        match result {
+            Ok(()) => info!(
+                log,
+                "Submitted slashing to beacon node";
+                "type" => slashing_type,
+            ),
+            Err(e) => error!(
+                log,
+                "Unable to submit slashing to beacon node";
+                "type" => slashing_type,
+                "error" => %e,
+            ),
+        }
+    }
+}
@@ -97,6 +97,18 @@ fn no_double_vote_repeated() {
     parallel_slasher_test(&attestations, hashset! {}, 1);
 }
 
+// A larger aggregate with the same data arrives after some of its attesters have been processed.
+#[test]
+fn double_vote_after_deduplicated_aggregate() {
+    let att1 = indexed_att([1, 2], 0, 1, 0);
+    let att2 = indexed_att([1, 2, 3], 0, 1, 0);
+    let att3 = indexed_att([1, 3], 0, 1, 1);
+    let slashings = hashset![att_slashing(&att1, &att3), att_slashing(&att2, &att3)];
+    let attestations = vec![att1, att2, att3];
+    slasher_test_indiv(&attestations, &slashings, 1);
+    slasher_test_indiv(&attestations, &slashings, 1000);
+}
+
 #[test]
 fn surrounds_existing_single_val_single_chunk() {
     let v = vec![0];
@@ -148,6 +160,40 @@ fn surrounded_by_single_val_multi_chunk() {
     slasher_test_indiv(&attestations, &slashings, 4 * chunk_size);
 }
 
+#[test]
+fn stats() {
+    let tempdir = tempdir().unwrap();
+    let config = Config::new(tempdir.path().into());
+    let slasher = Slasher::<E>::open(config, test_logger()).unwrap();
+
+    let stats = slasher.stats().unwrap();
+    assert_eq!(stats.num_attestations, 0);
+    assert_eq!(stats.last_processed_epoch, None);
+    assert_eq!(stats.num_attester_slashings, 0);
+
+    let v = vec![0, 1];
+    let att1 = indexed_att(&v, 0, 1, 0);
+    let att2 = indexed_att(&v, 0, 1, 1);
+    let current_epoch = Epoch::new(1);
+    slasher.accept_attestation(att1);
+    slasher.accept_attestation(att2);
+    slasher.process_queued(current_epoch).unwrap();
+    slasher.prune_database(current_epoch).unwrap();
+
+    let stats = slasher.stats().unwrap();
+    assert_eq!(stats.num_attestations, 2);
+    assert_eq!(stats.num_blocks, 0);
+    assert_eq!(stats.last_processed_epoch, Some(current_epoch));
+    assert_eq!(stats.prune_backlog_epochs, 0);
+    assert_eq!(stats.num_attester_slashings, 1);
+    assert_eq!(stats.num_proposer_slashings, 0);
+    assert!(stats.database_size > 0);
+
+    // Harvesting the slashings does not reset the count.
+    assert_eq!(slasher.get_attester_slashings().len(), 1);
+    assert_eq!(slasher.stats().unwrap().num_attester_slashings, 1);
+}
+
 // Process each attestation individually, and confirm that the slashings produced are as expected.
 fn slasher_test_indiv(
     attestations: &[IndexedAttestation<E>],
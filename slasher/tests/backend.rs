@@ -1,8 +1,15 @@
 #![cfg(all(feature = "lmdb"))]
 
-use slasher::{config::MDBX_DATA_FILENAME, Config, DatabaseBackend, DatabaseBackendOverride};
+use logging::test_logger;
+use slasher::{
+    config::{LMDB_DATA_FILENAME, MDBX_DATA_FILENAME},
+    migrate_backend,
+    test_utils::{block as test_block, E},
+    Config, DatabaseBackend, DatabaseBackendOverride, Error, Slasher,
+};
 use std::fs::File;
 use tempfile::tempdir;
+use types::Epoch;
 
 #[test]
 #[cfg(all(feature = "mdbx", feature = "lmdb"))]
@@ -55,3 +62,80 @@ fn failed_override_with_existing_mdbx_db() {
     );
     assert_eq!(config.backend, DatabaseBackend::Lmdb);
 }
+
+#[test]
+fn memory_backend_detects_slashings() {
+    let tempdir = tempdir().unwrap();
+    let mut config = Config::new(tempdir.path().into());
+    config.backend = DatabaseBackend::Memory;
+
+    let slasher = Slasher::<E>::open(config.clone(), test_logger()).unwrap();
+    slasher.accept_block_header(test_block(1, 0, 0));
+    slasher.accept_block_header(test_block(1, 0, 1));
+    slasher.process_queued(Epoch::new(0)).unwrap();
+    assert_eq!(slasher.get_proposer_slashings().len(), 1);
+
+    // Nothing should be persisted to disk.
+    assert_eq!(config.existing_other_backend(), None);
+    assert!(!config.database_path.join(LMDB_DATA_FILENAME).exists());
+}
+
+#[test]
+fn migrate_without_existing_db() {
+    let tempdir = tempdir().unwrap();
+    let config = Config::new(tempdir.path().into());
+    migrate_backend(&config, &test_logger()).unwrap();
+    assert_eq!(config.existing_other_backend(), None);
+}
+
+#[test]
+fn migrate_to_memory_unsupported() {
+    let tempdir = tempdir().unwrap();
+    let mut config = Config::new(tempdir.path().into());
+    config.backend = DatabaseBackend::Memory;
+
+    File::create(config.database_path.join(LMDB_DATA_FILENAME)).unwrap();
+
+    assert_eq!(config.existing_other_backend(), Some(DatabaseBackend::Lmdb));
+    assert!(matches!(
+        migrate_backend(&config, &test_logger()),
+        Err(Error::BackendMigrationUnsupported(DatabaseBackend::Memory))
+    ));
+}
+
+#[test]
+#[cfg(all(feature = "mdbx", feature = "lmdb"))]
+fn migrate_mdbx_to_lmdb() {
+    use slasher::Environment;
+
+    let tempdir = tempdir().unwrap();
+    let mut config = Config::new(tempdir.path().into());
+    config.backend = DatabaseBackend::Mdbx;
+    config.max_db_size_mbs = 1;
+
+    {
+        let env = Environment::new(&config).unwrap();
+        let databases = env.create_databases().unwrap();
+        let mut txn = env.begin_rw_txn().unwrap();
+        txn.put(&databases.proposers_db, [1u8], [2u8]).unwrap();
+        txn.put(&databases.metadata_db, [3u8], [4u8]).unwrap();
+        txn.commit().unwrap();
+    }
+
+    config.backend = DatabaseBackend::Lmdb;
+    migrate_backend(&config, &test_logger()).unwrap();
+    assert!(!config.database_path.join(MDBX_DATA_FILENAME).exists());
+    assert_eq!(config.existing_other_backend(), None);
+
+    let env = Environment::new(&config).unwrap();
+    let databases = env.create_databases().unwrap();
+    let txn = env.begin_rw_txn().unwrap();
+    assert_eq!(
+        txn.get(&databases.proposers_db, &[1u8]).unwrap().as_deref(),
+        Some(&[2u8][..])
+    );
+    assert_eq!(
+        txn.get(&databases.metadata_db, &[3u8]).unwrap().as_deref(),
+        Some(&[4u8][..])
+    );
+}
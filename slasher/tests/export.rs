@@ -0,0 +1,83 @@
+use logging::test_logger;
+use slasher::{
+    test_utils::{block as test_block, E},
+    Config, DatabaseBackend, Error, Slasher,
+};
+use tempfile::tempdir;
+use types::Epoch;
+
+fn memory_slasher(config: Config) -> Slasher<E> {
+    let mut config = config;
+    config.backend = DatabaseBackend::Memory;
+    Slasher::open(config, test_logger()).unwrap()
+}
+
+#[test]
+fn export_import_round_trip() {
+    let tempdir = tempdir().unwrap();
+    let config = Config::new(tempdir.path().into());
+
+    let slasher = memory_slasher(config.clone());
+    slasher.accept_block_header(test_block(1, 0, 0));
+    slasher.accept_block_header(test_block(2, 1, 0));
+    slasher.process_queued(Epoch::new(0)).unwrap();
+
+    let mut export = vec![];
+    let num_exported = slasher.export_database(&mut export).unwrap();
+    assert!(num_exported >= 2);
+
+    let imported = memory_slasher(config);
+    assert_eq!(imported.import_database(&export[..]).unwrap(), num_exported);
+
+    // Blocks from the exported database should be used to detect slashings.
+    imported.accept_block_header(test_block(1, 0, 1));
+    imported.process_queued(Epoch::new(0)).unwrap();
+    assert_eq!(imported.get_proposer_slashings().len(), 1);
+
+    // Re-exporting should include the imported records as well as the new block.
+    let mut re_export = vec![];
+    assert!(imported.export_database(&mut re_export).unwrap() > num_exported);
+}
+
+#[test]
+fn import_into_non_empty_database() {
+    let tempdir = tempdir().unwrap();
+    let config = Config::new(tempdir.path().into());
+
+    let slasher = memory_slasher(config);
+    slasher.accept_block_header(test_block(1, 0, 0));
+    slasher.process_queued(Epoch::new(0)).unwrap();
+
+    let mut export = vec![];
+    slasher.export_database(&mut export).unwrap();
+
+    assert!(matches!(
+        slasher.import_database(&export[..]),
+        Err(Error::ImportDatabaseNotEmpty)
+    ));
+}
+
+#[test]
+fn import_with_incompatible_config() {
+    let tempdir = tempdir().unwrap();
+    let config = Config::new(tempdir.path().into());
+
+    let slasher = memory_slasher(config.clone());
+    let mut export = vec![];
+    slasher.export_database(&mut export).unwrap();
+
+    let mut other_config = config;
+    other_config.history_length /= 2;
+    let imported = memory_slasher(other_config);
+    assert!(matches!(
+        imported.import_database(&export[..]),
+        Err(Error::ConfigIncompatible { .. })
+    ));
+}
+
+#[test]
+fn import_invalid_file() {
+    let tempdir = tempdir().unwrap();
+    let slasher = memory_slasher(Config::new(tempdir.path().into()));
+    assert!(slasher.import_database(&[0u8; 64][..]).is_err());
+}
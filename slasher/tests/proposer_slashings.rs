@@ -62,3 +62,93 @@ fn block_pruning() {
         .epoch(slots_per_epoch)
         > current_epoch - config.history_length as u64));
 }
+
+#[test]
+fn incremental_block_pruning() {
+    let slots_per_epoch = E::slots_per_epoch();
+
+    let tempdir = tempdir().unwrap();
+    let mut config = Config::new(tempdir.path().into());
+    config.chunk_size = 2;
+    config.history_length = 2;
+    config.prune_batch_size = slots_per_epoch as usize;
+
+    let slasher = Slasher::<E>::open(config.clone(), test_logger()).unwrap();
+    let current_epoch = Epoch::from(2 * config.history_length);
+
+    // Add a block for every slot, leaving 3 epochs of blocks outside the history length.
+    let proposer_index = 100_000;
+    for slot in 0..current_epoch.as_u64() * slots_per_epoch {
+        slasher.accept_block_header(test_block(slot, proposer_index, 0));
+    }
+    slasher.process_queued(current_epoch).unwrap();
+
+    // Each run prunes a single epoch of blocks.
+    for backlog_epochs in (0..3).rev() {
+        let stats = slasher.prune_database(current_epoch).unwrap();
+        assert_eq!(stats.num_proposers_pruned, slots_per_epoch as usize);
+        assert_eq!(stats.backlog_epochs, backlog_epochs);
+    }
+
+    // Pruning the fully pruned database is a no-op.
+    let stats = slasher.prune_database(current_epoch).unwrap();
+    assert_eq!(stats.num_proposers_pruned, 0);
+    assert_eq!(stats.backlog_epochs, 0);
+
+    // Only blocks within the history length remain.
+    for slot in 0..current_epoch.as_u64() * slots_per_epoch {
+        slasher.accept_block_header(test_block(slot, proposer_index, 1));
+    }
+    slasher.process_queued(current_epoch).unwrap();
+    assert_eq!(
+        slasher.get_proposer_slashings().len(),
+        (config.history_length - 1) * slots_per_epoch as usize
+    );
+}
+
+#[test]
+fn stats() {
+    let slots_per_epoch = E::slots_per_epoch();
+
+    let tempdir = tempdir().unwrap();
+    let mut config = Config::new(tempdir.path().into());
+    config.chunk_size = 2;
+    config.history_length = 2;
+
+    let slasher = Slasher::<E>::open(config.clone(), test_logger()).unwrap();
+    let current_epoch = Epoch::new(0);
+
+    // A repeated block is only stored once, and a conflicting block is never stored.
+    slasher.accept_block_header(test_block(1, 0, 0));
+    slasher.accept_block_header(test_block(1, 0, 0));
+    slasher.accept_block_header(test_block(1, 0, 1));
+    slasher.accept_block_header(test_block(2, 1, 0));
+    let batch_stats = slasher.process_queued(current_epoch).unwrap();
+    assert_eq!(batch_stats.block_stats.num_processed, 4);
+    assert_eq!(batch_stats.block_stats.num_new, 2);
+
+    let stats = slasher.stats().unwrap();
+    assert_eq!(stats.num_blocks, 2);
+    assert_eq!(stats.num_attestations, 0);
+    assert_eq!(stats.num_proposer_slashings, 1);
+
+    // Finding the same slashing again is not counted twice.
+    slasher.accept_block_header(test_block(1, 0, 1));
+    slasher.process_queued(current_epoch).unwrap();
+    let stats = slasher.stats().unwrap();
+    assert_eq!(stats.num_blocks, 2);
+    assert_eq!(stats.num_proposer_slashings, 1);
+    drop(slasher);
+
+    // The stored blocks are counted when the database is reopened.
+    let slasher = Slasher::<E>::open(config.clone(), test_logger()).unwrap();
+    assert_eq!(slasher.stats().unwrap().num_blocks, 2);
+
+    // Pruned blocks are no longer counted.
+    let current_epoch = Epoch::new(2 * config.history_length as u64);
+    slasher.accept_block_header(test_block(current_epoch.as_u64() * slots_per_epoch, 0, 0));
+    slasher.process_queued(current_epoch).unwrap();
+    assert_eq!(slasher.stats().unwrap().num_blocks, 3);
+    slasher.prune_database(current_epoch).unwrap();
+    assert_eq!(slasher.stats().unwrap().num_blocks, 1);
+}
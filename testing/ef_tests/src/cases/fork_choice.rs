@@ -37,7 +37,7 @@ pub struct Head {
     root: Hash256,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Checks {
     head: Option<Head>,
@@ -666,3 +666,300 @@ impl<'a, T: BeaconChainTypes> VerifiedAttestation<T> for ManuallyVerifiedAttesta
         &self.indexed_attestation
     }
 }
+
+/// Runs cases built from the blocks and attestations of a `BeaconChainHarness` through the same
+/// `Tester` as the consensus-spec vectors, so the steps are exercised without downloading them.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{ExecPayload, MinimalEthSpec};
+
+    type E = MinimalEthSpec;
+    type TestStep = Step<SignedBeaconBlock<E>, Attestation<E>, AttesterSlashing<E>, PowBlock>;
+
+    const VALIDATOR_COUNT: usize = 32;
+    const FORK_NAME: ForkName = ForkName::Merge;
+
+    /// Builds a `ForkChoiceTest` from blocks produced by a separate harness, which starts at the
+    /// terminal PoW block so that every block after genesis has an execution payload.
+    struct CaseBuilder {
+        harness: BeaconChainHarness<EphemeralHarnessType<E>>,
+        anchor_state: BeaconState<E>,
+        anchor_block: BeaconBlock<E>,
+        steps: Vec<TestStep>,
+    }
+
+    impl CaseBuilder {
+        fn new() -> Self {
+            let harness = BeaconChainHarness::builder(E::default())
+                .spec(testing_spec::<E>(FORK_NAME))
+                .deterministic_keypairs(VALIDATOR_COUNT)
+                .fresh_ephemeral_store()
+                .mock_execution_layer()
+                .build();
+
+            let mut anchor_state = harness.get_current_state();
+            let mut anchor_block = BeaconBlock::empty(&harness.spec);
+            *anchor_block.state_root_mut() = anchor_state.update_tree_hash_cache().unwrap();
+
+            let (terminal_parent, terminal_block) = {
+                let mut generator = harness.execution_block_generator();
+                generator.move_to_terminal_block().unwrap();
+                let terminal_block = generator.latest_execution_block().unwrap();
+                let terminal_parent = generator
+                    .execution_block_by_hash(terminal_block.parent_hash)
+                    .unwrap();
+                (terminal_parent, terminal_block)
+            };
+
+            let mut builder = Self {
+                harness,
+                anchor_state,
+                anchor_block,
+                steps: vec![],
+            };
+            builder.tick(Slot::new(0));
+            for block in [terminal_parent, terminal_block] {
+                builder.steps.push(Step::PowBlock {
+                    pow_block: PowBlock {
+                        block_hash: block.block_hash,
+                        parent_hash: block.parent_hash,
+                        total_difficulty: block.total_difficulty,
+                    },
+                });
+            }
+            builder
+        }
+
+        fn block_on<F: Future>(&self, future: F) -> F::Output {
+            self.harness
+                .chain
+                .task_executor
+                .clone()
+                .block_on_dangerous(future, "fork_choice_case_builder")
+                .expect("runtime should not be shut down")
+        }
+
+        fn tick(&mut self, slot: Slot) {
+            let tick = self.anchor_state.genesis_time()
+                + slot.as_u64() * self.harness.spec.seconds_per_slot;
+            self.steps.push(Step::Tick { tick });
+        }
+
+        fn checks(&mut self, checks: Checks) {
+            self.steps.push(Step::Checks {
+                checks: Box::new(checks),
+            });
+        }
+
+        fn check_head(&mut self, block: &SignedBeaconBlock<E>) {
+            self.checks(Checks {
+                head: Some(Head {
+                    slot: block.slot(),
+                    root: block.canonical_root(),
+                }),
+                ..Checks::default()
+            });
+        }
+
+        fn payload_status(
+            &mut self,
+            block: &SignedBeaconBlock<E>,
+            status: JsonPayloadStatusV1Status,
+            latest_valid_hash: Option<ExecutionBlockHash>,
+        ) {
+            self.steps.push(Step::OnPayloadInfo {
+                block_hash: payload_block_hash(block),
+                payload_status: PayloadStatus {
+                    status,
+                    latest_valid_hash,
+                    validation_error: None,
+                },
+            });
+        }
+
+        /// Produces a block at `slot` on the head of the harness, without importing it.
+        fn produce_block(&self, slot: Slot) -> (SignedBeaconBlock<E>, BeaconState<E>) {
+            self.harness.set_current_slot(slot);
+            self.block_on(
+                self.harness
+                    .make_block(self.harness.get_current_state(), slot),
+            )
+        }
+
+        fn import_block(&self, block: &SignedBeaconBlock<E>) {
+            self.block_on(self.harness.process_block(
+                block.slot(),
+                block.canonical_root(),
+                block.clone(),
+            ))
+            .unwrap();
+        }
+
+        /// Extends the harness by `num_blocks` blocks which all validators attest to, adding each
+        /// block to the case at the start of its slot.
+        fn add_attested_blocks(&mut self, num_blocks: u64) -> SignedBeaconBlock<E> {
+            let validators = self.harness.get_all_validators();
+            let mut head = None;
+            for _ in 0..num_blocks {
+                let slot = self.harness.get_current_slot() + 1;
+                let (block, state) = self.produce_block(slot);
+                self.import_block(&block);
+                self.harness.attest_block(
+                    &state,
+                    state.canonical_root(),
+                    block.canonical_root().into(),
+                    &block,
+                    &validators,
+                );
+
+                self.tick(slot);
+                self.steps.push(Step::ValidBlock {
+                    block: block.clone(),
+                });
+                head = Some(block);
+            }
+            head.expect("num_blocks should be non-zero")
+        }
+
+        /// Adds one aggregate per committee, in which all validators of the committee attest to
+        /// `block`.
+        fn add_attestations(&mut self, block: &SignedBeaconBlock<E>, state: &BeaconState<E>) {
+            let attestations = self.harness.make_unaggregated_attestations(
+                &self.harness.get_all_validators(),
+                state,
+                state.canonical_root(),
+                block.canonical_root().into(),
+                block.slot(),
+            );
+            for committee in attestations {
+                let mut committee = committee.into_iter().map(|(attestation, _)| attestation);
+                if let Some(mut aggregate) = committee.next() {
+                    for attestation in committee {
+                        aggregate.aggregate(&attestation);
+                    }
+                    self.steps.push(Step::Attestation {
+                        attestation: aggregate,
+                    });
+                }
+            }
+        }
+
+        fn run(self) -> Result<(), Error> {
+            ForkChoiceTest {
+                description: "harness".into(),
+                anchor_state: self.anchor_state,
+                anchor_block: self.anchor_block,
+                steps: self.steps,
+            }
+            .result(0, FORK_NAME)
+        }
+    }
+
+    fn payload_block_hash(block: &SignedBeaconBlock<E>) -> ExecutionBlockHash {
+        block
+            .message()
+            .body()
+            .execution_payload()
+            .unwrap()
+            .block_hash()
+    }
+
+    #[test]
+    fn justified_and_finalized_chain() {
+        let mut case = CaseBuilder::new();
+        let head = case.add_attested_blocks(E::slots_per_epoch() * 4);
+
+        let fork_choice = case.harness.chain.canonical_head.fork_choice_read_lock();
+        let checks = Checks {
+            head: Some(Head {
+                slot: head.slot(),
+                root: head.canonical_root(),
+            }),
+            time: Some(
+                case.anchor_state.genesis_time()
+                    + head.slot().as_u64() * case.harness.spec.seconds_per_slot,
+            ),
+            genesis_time: Some(case.anchor_state.genesis_time()),
+            justified_checkpoint: Some(fork_choice.justified_checkpoint()),
+            finalized_checkpoint: Some(fork_choice.finalized_checkpoint()),
+            u_justified_checkpoint: Some(fork_choice.unrealized_justified_checkpoint()),
+            u_finalized_checkpoint: Some(fork_choice.unrealized_finalized_checkpoint()),
+            // The head was imported at the start of its slot.
+            proposer_boost_root: Some(head.canonical_root()),
+            ..Checks::default()
+        };
+        assert!(fork_choice.finalized_checkpoint().epoch > 0);
+        drop(fork_choice);
+
+        case.checks(checks);
+        case.run().unwrap();
+    }
+
+    #[test]
+    fn attestations_decide_head() {
+        let mut case = CaseBuilder::new();
+        case.add_attested_blocks(E::slots_per_epoch());
+
+        let slot = case.harness.get_current_slot() + 1;
+        let (block_a, state_a) = case.produce_block(slot);
+        let (block_b, state_b) = case.produce_block(slot);
+
+        // Without attestations the tie is broken in favour of the greater root, so attest to the
+        // other block.
+        let (winner, loser, winner_state) = if block_a.canonical_root() < block_b.canonical_root() {
+            (block_a, block_b, state_a)
+        } else {
+            (block_b, block_a, state_b)
+        };
+
+        // Neither block is timely, so neither receives a proposer boost.
+        case.tick(slot + 1);
+        case.steps.push(Step::ValidBlock {
+            block: winner.clone(),
+        });
+        case.steps.push(Step::ValidBlock {
+            block: loser.clone(),
+        });
+        case.check_head(&loser);
+
+        case.add_attestations(&winner, &winner_state);
+        case.check_head(&winner);
+
+        case.run().unwrap();
+    }
+
+    #[test]
+    fn invalid_payload_after_optimistic_import() {
+        let mut case = CaseBuilder::new();
+        let valid_head = case.add_attested_blocks(E::slots_per_epoch());
+
+        let slot = case.harness.get_current_slot() + 1;
+        let (syncing_block, _) = case.produce_block(slot);
+        case.import_block(&syncing_block);
+        let (invalid_block, _) = case.produce_block(slot + 1);
+
+        case.tick(slot);
+        case.payload_status(&syncing_block, JsonPayloadStatusV1Status::Syncing, None);
+        case.steps.push(Step::ValidBlock {
+            block: syncing_block.clone(),
+        });
+        case.check_head(&syncing_block);
+
+        // The invalid payload's latest valid ancestor is the payload of `valid_head`, which also
+        // invalidates the optimistically imported block.
+        case.tick(slot + 1);
+        case.payload_status(
+            &invalid_block,
+            JsonPayloadStatusV1Status::Invalid,
+            Some(payload_block_hash(&valid_head)),
+        );
+        case.steps.push(Step::MaybeValidBlock {
+            block: invalid_block,
+            valid: false,
+        });
+        case.check_head(&valid_head);
+
+        case.run().unwrap();
+    }
+}
@@ -24,6 +24,10 @@ use types::{Hash256, PublicKeyBytes};
 /// The filename within the `validators` directory that contains the slashing protection DB.
 pub const SLASHING_PROTECTION_FILENAME: &str = "slashing_protection.sqlite";
 
+/// The prefix of the files within the `validators` directory which hold an interchange backup of
+/// the slashing protection data of deleted validators.
+pub const SLASHING_PROTECTION_BACKUP_PREFIX: &str = "slashing_protection_backup-";
+
 /// The attestation or block is not safe to sign.
 ///
 /// This could be because it's slashable, or because an error occurred.
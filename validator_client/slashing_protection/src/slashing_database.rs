@@ -174,6 +174,17 @@ impl SlashingDatabase {
         Ok(())
     }
 
+    /// Wait for any in-progress transaction to complete, then flush SQLite's page cache to disk.
+    ///
+    /// Used during shutdown, once no further signatures will be recorded.
+    pub fn flush(&self) -> Result<(), NotSafe> {
+        let mut conn = self.conn_pool.get()?;
+        let txn = Transaction::new(&mut conn, TransactionBehavior::Exclusive)?;
+        txn.cache_flush()?;
+        txn.commit()?;
+        Ok(())
+    }
+
     /// Execute a database transaction as a closure, committing if `f` returns `Ok`.
     pub fn with_transaction<T, E, F>(&self, f: F) -> Result<T, E>
     where
@@ -1200,6 +1211,20 @@ mod tests {
         SlashingDatabase::open(&file).unwrap_err();
     }
 
+    // Flushing should not disturb the records already in the database.
+    #[test]
+    fn flush_retains_records() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+        let db = SlashingDatabase::create(&file).unwrap();
+        let pubkey = PublicKeyBytes::empty();
+        db.register_validator(pubkey).unwrap();
+
+        db.flush().unwrap();
+
+        assert_eq!(db.num_validator_rows().unwrap(), 1);
+    }
+
     // Attempting to create the same database twice should error.
     #[test]
     fn double_create_error() {
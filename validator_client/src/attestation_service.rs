@@ -7,7 +7,7 @@ use crate::{
 };
 use environment::RuntimeContext;
 use futures::future::join_all;
-use slog::{crit, error, info, trace};
+use slog::{crit, debug, error, info, trace};
 use slot_clock::SlotClock;
 use std::collections::HashMap;
 use std::ops::Deref;
@@ -26,6 +26,8 @@ pub struct AttestationServiceBuilder<T: SlotClock + 'static, E: EthSpec> {
     slot_clock: Option<T>,
     beacon_nodes: Option<Arc<BeaconNodeFallback<T, E>>>,
     context: Option<RuntimeContext<E>>,
+    attestation_production_offset: Option<Duration>,
+    aggregate_production_offset: Option<Duration>,
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
@@ -36,6 +38,8 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
             slot_clock: None,
             beacon_nodes: None,
             context: None,
+            attestation_production_offset: None,
+            aggregate_production_offset: None,
         }
     }
 
@@ -64,6 +68,16 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
         self
     }
 
+    pub fn attestation_production_offset(mut self, offset: Option<Duration>) -> Self {
+        self.attestation_production_offset = offset;
+        self
+    }
+
+    pub fn aggregate_production_offset(mut self, offset: Option<Duration>) -> Self {
+        self.aggregate_production_offset = offset;
+        self
+    }
+
     pub fn build(self) -> Result<AttestationService<T, E>, String> {
         Ok(AttestationService {
             inner: Arc::new(Inner {
@@ -82,6 +96,8 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
                 context: self
                     .context
                     .ok_or("Cannot build AttestationService without runtime_context")?,
+                attestation_production_offset: self.attestation_production_offset,
+                aggregate_production_offset: self.aggregate_production_offset,
             }),
         })
     }
@@ -94,9 +110,14 @@ pub struct Inner<T, E: EthSpec> {
     slot_clock: T,
     beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
     context: RuntimeContext<E>,
+    /// Overrides the default attestation production time of 1/3 of the way through the slot.
+    attestation_production_offset: Option<Duration>,
+    /// Overrides the default aggregate production time of 2/3 of the way through the slot.
+    aggregate_production_offset: Option<Duration>,
 }
 
-/// Attempts to produce attestations for all known validators 1/3rd of the way through each slot.
+/// Attempts to produce attestations for all known validators 1/3rd of the way through each slot
+/// (or at the configured attestation production offset).
 ///
 /// If any validators are on the same committee, a single attestation will be downloaded and
 /// returned to the beacon node. This attestation will have a signature from each of the
@@ -122,6 +143,18 @@ impl<T, E: EthSpec> Deref for AttestationService<T, E> {
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
+    /// Returns the time after the start of each slot at which attestations are produced.
+    fn attestation_production_offset(&self, slot_duration: Duration) -> Duration {
+        self.attestation_production_offset
+            .unwrap_or(slot_duration / 3)
+    }
+
+    /// Returns the time after the start of each slot at which aggregates are produced.
+    fn aggregate_production_offset(&self, slot_duration: Duration) -> Duration {
+        self.aggregate_production_offset
+            .unwrap_or(slot_duration * 2 / 3)
+    }
+
     /// Starts the service which periodically produces attestations.
     pub fn start_update_service(self, spec: &ChainSpec) -> Result<(), String> {
         let log = self.context.log().clone();
@@ -143,7 +176,10 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         let interval_fut = async move {
             loop {
                 if let Some(duration_to_next_slot) = self.slot_clock.duration_to_next_slot() {
-                    sleep(duration_to_next_slot + slot_duration / 3).await;
+                    sleep(
+                        duration_to_next_slot + self.attestation_production_offset(slot_duration),
+                    )
+                    .await;
                     let log = self.context.log();
 
                     if let Err(e) = self.spawn_attestation_tasks(slot_duration) {
@@ -181,10 +217,12 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             .ok_or("Unable to determine duration to next slot")?;
 
         // If a validator needs to publish an aggregate attestation, they must do so at 2/3
-        // through the slot. This delay triggers at this time
+        // through the slot (by default). This delay triggers at this time
         let aggregate_production_instant = Instant::now()
             + duration_to_next_slot
-                .checked_sub(slot_duration / 3)
+                .checked_sub(
+                    slot_duration.saturating_sub(self.aggregate_production_offset(slot_duration)),
+                )
                 .unwrap_or_else(|| Duration::from_secs(0));
 
         let duties_by_committee_index: HashMap<CommitteeIndex, Vec<DutyAndProof>> = self
@@ -205,14 +243,32 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         duties_by_committee_index
             .into_iter()
             .for_each(|(committee_index, validator_duties)| {
+                let duty_guard = match self.validator_store.in_flight_duties().start() {
+                    Some(guard) => guard,
+                    None => {
+                        debug!(
+                            self.context.log(),
+                            "Not producing attestations during shutdown";
+                            "slot" => slot.as_u64(),
+                            "committee_index" => committee_index,
+                        );
+                        return;
+                    }
+                };
                 // Spawn a separate task for each attestation.
+                let service = self.clone();
                 self.inner.context.executor.spawn_ignoring_error(
-                    self.clone().publish_attestations_and_aggregates(
-                        slot,
-                        committee_index,
-                        validator_duties,
-                        aggregate_production_instant,
-                    ),
+                    async move {
+                        let _duty_guard = duty_guard;
+                        service
+                            .publish_attestations_and_aggregates(
+                                slot,
+                                committee_index,
+                                validator_duties,
+                                aggregate_production_instant,
+                            )
+                            .await
+                    },
                     "attestation publish",
                 );
             });
@@ -354,7 +410,14 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                 },
             )
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| {
+                metrics::inc_counter_vec_by(
+                    &metrics::DUTY_FAILURES_TOTAL,
+                    &[metrics::ATTESTATIONS, metrics::BEACON_NODE_ERROR],
+                    validator_duties.len() as u64,
+                );
+                e.to_string()
+            })?;
 
         // Create futures to produce signed `Attestation` objects.
         let attestation_data_ref = &attestation_data;
@@ -396,6 +459,10 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             {
                 Ok(()) => Some((attestation, duty.validator_index)),
                 Err(e) => {
+                    metrics::inc_counter_vec(
+                        &metrics::DUTY_FAILURES_TOTAL,
+                        &[metrics::ATTESTATIONS, e.metrics_cause()],
+                    );
                     crit!(
                         log,
                         "Failed to sign attestation";
@@ -410,14 +477,19 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         });
 
         // Execute all the futures in parallel, collecting any successful results.
+        let signing_timer =
+            metrics::start_timer_vec(&metrics::DUTY_SIGNING_TIMES, &[metrics::ATTESTATIONS]);
         let (ref attestations, ref validator_indices): (Vec<_>, Vec<_>) = join_all(signing_futures)
             .await
             .into_iter()
             .flatten()
             .unzip();
+        drop(signing_timer);
 
         // Post the attestations to the BN.
-        match self
+        let publish_timer =
+            metrics::start_timer_vec(&metrics::DUTY_PUBLISH_TIMES, &[metrics::ATTESTATIONS]);
+        let publish_result = self
             .beacon_nodes
             .first_success(
                 RequireSynced::No,
@@ -432,26 +504,45 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                         .await
                 },
             )
-            .await
-        {
-            Ok(()) => info!(
-                log,
-                "Successfully published attestations";
-                "count" => attestations.len(),
-                "validator_indices" => ?validator_indices,
-                "head_block" => ?attestation_data.beacon_block_root,
-                "committee_index" => attestation_data.index,
-                "slot" => attestation_data.slot.as_u64(),
-                "type" => "unaggregated",
-            ),
-            Err(e) => error!(
-                log,
-                "Unable to publish attestations";
-                "error" => %e,
-                "committee_index" => attestation_data.index,
-                "slot" => slot.as_u64(),
-                "type" => "unaggregated",
-            ),
+            .await;
+        drop(publish_timer);
+
+        match publish_result {
+            Ok(()) => {
+                // Attestations are due a third of the way through the slot, not at the
+                // (possibly overridden) aggregation offset.
+                metrics::observe_duty_latency(
+                    &self.slot_clock,
+                    metrics::ATTESTATIONS,
+                    slot,
+                    self.slot_clock.unagg_attestation_production_delay(),
+                );
+                info!(
+                    log,
+                    "Successfully published attestations";
+                    "count" => attestations.len(),
+                    "validator_indices" => ?validator_indices,
+                    "head_block" => ?attestation_data.beacon_block_root,
+                    "committee_index" => attestation_data.index,
+                    "slot" => attestation_data.slot.as_u64(),
+                    "type" => "unaggregated",
+                )
+            }
+            Err(e) => {
+                metrics::inc_counter_vec_by(
+                    &metrics::DUTY_FAILURES_TOTAL,
+                    &[metrics::ATTESTATIONS, metrics::BEACON_NODE_ERROR],
+                    attestations.len() as u64,
+                );
+                error!(
+                    log,
+                    "Unable to publish attestations";
+                    "error" => %e,
+                    "committee_index" => attestation_data.index,
+                    "slot" => slot.as_u64(),
+                    "type" => "unaggregated",
+                )
+            }
         }
 
         Ok(Some(attestation_data))
@@ -501,7 +592,18 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                 },
             )
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| {
+                let aggregators = validator_duties
+                    .iter()
+                    .filter(|duty_and_proof| duty_and_proof.selection_proof.is_some())
+                    .count();
+                metrics::inc_counter_vec_by(
+                    &metrics::DUTY_FAILURES_TOTAL,
+                    &[metrics::AGGREGATES, metrics::BEACON_NODE_ERROR],
+                    aggregators as u64,
+                );
+                e.to_string()
+            })?;
 
         // Create futures to produce the signed aggregated attestations.
         let signing_futures = validator_duties.iter().map(|duty_and_proof| async move {
@@ -528,6 +630,10 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             {
                 Ok(aggregate) => Some(aggregate),
                 Err(e) => {
+                    metrics::inc_counter_vec(
+                        &metrics::DUTY_FAILURES_TOTAL,
+                        &[metrics::AGGREGATES, e.metrics_cause()],
+                    );
                     crit!(
                         log,
                         "Failed to sign attestation";
@@ -540,15 +646,20 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         });
 
         // Execute all the futures in parallel, collecting any successful results.
+        let signing_timer =
+            metrics::start_timer_vec(&metrics::DUTY_SIGNING_TIMES, &[metrics::AGGREGATES]);
         let signed_aggregate_and_proofs = join_all(signing_futures)
             .await
             .into_iter()
             .flatten()
             .collect::<Vec<_>>();
+        drop(signing_timer);
 
         if !signed_aggregate_and_proofs.is_empty() {
             let signed_aggregate_and_proofs_slice = signed_aggregate_and_proofs.as_slice();
-            match self
+            let publish_timer =
+                metrics::start_timer_vec(&metrics::DUTY_PUBLISH_TIMES, &[metrics::AGGREGATES]);
+            let publish_result = self
                 .beacon_nodes
                 .first_success(
                     RequireSynced::No,
@@ -563,9 +674,17 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                             .await
                     },
                 )
-                .await
-            {
+                .await;
+            drop(publish_timer);
+
+            match publish_result {
                 Ok(()) => {
+                    metrics::observe_duty_latency(
+                        &self.slot_clock,
+                        metrics::AGGREGATES,
+                        attestation_data.slot,
+                        self.slot_clock.slot_duration(),
+                    );
                     for signed_aggregate_and_proof in signed_aggregate_and_proofs {
                         let attestation = &signed_aggregate_and_proof.message.aggregate;
                         info!(
@@ -581,6 +700,11 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                     }
                 }
                 Err(e) => {
+                    metrics::inc_counter_vec_by(
+                        &metrics::DUTY_FAILURES_TOTAL,
+                        &[metrics::AGGREGATES, metrics::BEACON_NODE_ERROR],
+                        signed_aggregate_and_proofs.len() as u64,
+                    );
                     for signed_aggregate_and_proof in signed_aggregate_and_proofs {
                         let attestation = &signed_aggregate_and_proof.message.aggregate;
                         crit!(
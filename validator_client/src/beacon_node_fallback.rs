@@ -5,14 +5,17 @@
 use crate::check_synced::check_synced;
 use crate::http_metrics::metrics::{inc_counter_vec, ENDPOINT_ERRORS, ENDPOINT_REQUESTS};
 use environment::RuntimeContext;
+use eth2::lighthouse_vc::types::{BeaconNodeFallbackHealth, BeaconNodeStatus};
 use eth2::BeaconNodeHttpClient;
 use futures::future;
+use serde_derive::{Deserialize, Serialize};
 use slog::{debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use std::fmt;
 use std::fmt::Debug;
 use std::future::Future;
 use std::marker::PhantomData;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::{sync::RwLock, time::sleep};
@@ -30,6 +33,13 @@ const UPDATE_REQUIRED_LOG_HINT: &str = "this VC or the remote BN may need updati
 /// having the correct nodes up and running prior to the start of the slot.
 const SLOT_LOOKAHEAD: Duration = Duration::from_secs(2);
 
+/// A candidate whose average latency exceeds `1 / SLOW_LATENCY_DENOMINATOR` of a slot is tried
+/// after faster candidates. On mainnet this is one second.
+const SLOW_LATENCY_DENOMINATOR: u32 = 12;
+
+/// The weight of the existing average when a new latency measurement is folded into it.
+const LATENCY_AVERAGE_WEIGHT: u32 = 4;
+
 /// Indicates a measurement of latency between the VC and a BN.
 pub struct LatencyMeasurement {
     /// An identifier for the beacon node (e.g. the URL).
@@ -88,6 +98,39 @@ pub enum OfflineOnFailure {
     No,
 }
 
+/// A class of messages which may be broadcast to all beacon nodes, rather than only being sent to
+/// the first available one.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApiTopic {
+    /// Attestation and sync committee subnet subscriptions.
+    Subscriptions,
+    /// Proposer preparation (fee recipient) messages.
+    Preparations,
+    /// Validator registrations for the builder network.
+    Registrations,
+}
+
+impl ApiTopic {
+    /// The topics which are broadcast to all beacon nodes by default.
+    pub fn default_broadcast() -> Vec<ApiTopic> {
+        vec![ApiTopic::Subscriptions, ApiTopic::Preparations]
+    }
+}
+
+impl FromStr for ApiTopic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "subscriptions" => Ok(ApiTopic::Subscriptions),
+            "preparations" => Ok(ApiTopic::Preparations),
+            "registrations" => Ok(ApiTopic::Registrations),
+            other => Err(format!("Unknown API topic: {}", other)),
+        }
+    }
+}
+
 impl PartialEq<bool> for RequireSynced {
     fn eq(&self, other: &bool) -> bool {
         if *other {
@@ -132,6 +175,63 @@ impl<E: Debug> fmt::Display for Errors<E> {
     }
 }
 
+/// Statistics built from the latency measurements of a single candidate.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct LatencyStats {
+    /// The most recent successful measurement.
+    pub last: Option<Duration>,
+    /// An exponentially-weighted moving average of successful measurements.
+    pub average: Option<Duration>,
+    pub min: Option<Duration>,
+    pub max: Option<Duration>,
+    pub successes: u64,
+    pub failures: u64,
+    /// `true` if the candidate did not respond to the most recent measurement.
+    pub last_failed: bool,
+}
+
+impl LatencyStats {
+    /// Record a measurement, where `None` indicates that the candidate did not respond.
+    pub fn observe(&mut self, latency: Option<Duration>) {
+        let latency = match latency {
+            Some(latency) => latency,
+            None => {
+                self.failures += 1;
+                self.last_failed = true;
+                return;
+            }
+        };
+
+        self.successes += 1;
+        self.last_failed = false;
+        self.last = Some(latency);
+        self.average = Some(match self.average {
+            Some(average) => {
+                (average * LATENCY_AVERAGE_WEIGHT + latency) / (LATENCY_AVERAGE_WEIGHT + 1)
+            }
+            None => latency,
+        });
+        self.min = Some(self.min.map_or(latency, |min| min.min(latency)));
+        self.max = Some(self.max.map_or(latency, |max| max.max(latency)));
+    }
+
+    /// The tier of the candidate when ordering candidates, where lower tiers are tried first.
+    ///
+    /// Candidates which have not yet been measured are treated as healthy.
+    fn tier(&self, slow_threshold: Duration) -> u8 {
+        if self.last_failed {
+            2
+        } else if self
+            .average
+            .map_or(false, |average| average > slow_threshold)
+        {
+            1
+        } else {
+            0
+        }
+    }
+}
+
 /// Reasons why a candidate might not be ready.
 #[derive(Debug, Clone, Copy)]
 pub enum CandidateError {
@@ -146,6 +246,7 @@ pub enum CandidateError {
 pub struct CandidateBeaconNode<E> {
     beacon_node: BeaconNodeHttpClient,
     status: RwLock<Result<(), CandidateError>>,
+    latency: RwLock<LatencyStats>,
     _phantom: PhantomData<E>,
 }
 
@@ -155,6 +256,7 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
         Self {
             beacon_node,
             status: RwLock::new(Err(CandidateError::Uninitialized)),
+            latency: RwLock::new(LatencyStats::default()),
             _phantom: PhantomData,
         }
     }
@@ -169,6 +271,11 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
         }
     }
 
+    /// Returns the latency statistics of `self`.
+    pub async fn latency(&self) -> LatencyStats {
+        *self.latency.read().await
+    }
+
     /// Indicate that `self` is offline.
     pub async fn set_offline(&self) {
         *self.status.write().await = Err(CandidateError::Offline)
@@ -322,7 +429,7 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
 pub struct BeaconNodeFallback<T, E> {
     candidates: Vec<CandidateBeaconNode<E>>,
     slot_clock: Option<T>,
-    disable_run_on_all: bool,
+    broadcast_topics: Vec<ApiTopic>,
     spec: ChainSpec,
     log: Logger,
 }
@@ -330,14 +437,14 @@ pub struct BeaconNodeFallback<T, E> {
 impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
     pub fn new(
         candidates: Vec<CandidateBeaconNode<E>>,
-        disable_run_on_all: bool,
+        broadcast_topics: Vec<ApiTopic>,
         spec: ChainSpec,
         log: Logger,
     ) -> Self {
         Self {
             candidates,
             slot_clock: None,
-            disable_run_on_all,
+            broadcast_topics,
             spec,
             log,
         }
@@ -408,9 +515,67 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         let _ = future::join_all(futures).await;
     }
 
+    /// Returns the candidates in the order in which requests should be attempted.
+    ///
+    /// The user-supplied order is preserved, except that candidates which did not respond to the
+    /// most recent latency measurement, or whose average latency is slow, are tried last.
+    async fn ordered_candidates(&self) -> Vec<&CandidateBeaconNode<E>> {
+        let slow_threshold =
+            Duration::from_secs(self.spec.seconds_per_slot) / SLOW_LATENCY_DENOMINATOR;
+
+        let mut candidates = Vec::with_capacity(self.candidates.len());
+        for candidate in &self.candidates {
+            candidates.push((candidate.latency().await.tier(slow_threshold), candidate));
+        }
+        // The sort is stable, so candidates within a tier keep their user-supplied order.
+        candidates.sort_by_key(|(tier, _)| *tier);
+
+        candidates
+            .into_iter()
+            .map(|(_, candidate)| candidate)
+            .collect()
+    }
+
+    /// Returns the status and latency statistics of each candidate, in user-supplied order.
+    pub async fn health(&self) -> Vec<BeaconNodeFallbackHealth> {
+        let ordered = self.ordered_candidates().await;
+
+        let mut health = Vec::with_capacity(self.candidates.len());
+        for (index, candidate) in self.candidates.iter().enumerate() {
+            let status = match candidate.status(RequireSynced::Yes).await {
+                Ok(()) => BeaconNodeStatus::Synced,
+                Err(CandidateError::NotSynced) => BeaconNodeStatus::NotSynced,
+                Err(CandidateError::Offline) => BeaconNodeStatus::Offline,
+                Err(CandidateError::Incompatible) => BeaconNodeStatus::Incompatible,
+                Err(CandidateError::Uninitialized) => BeaconNodeStatus::Uninitialized,
+            };
+            let latency = candidate.latency().await;
+            let as_millis = |d: Option<Duration>| d.map(|d| d.as_millis() as u64);
+
+            health.push(BeaconNodeFallbackHealth {
+                index,
+                endpoint: candidate.beacon_node.to_string(),
+                status,
+                fallback_position: ordered
+                    .iter()
+                    .position(|c| std::ptr::eq(*c, candidate))
+                    .unwrap_or(index),
+                last_latency_ms: as_millis(latency.last),
+                average_latency_ms: as_millis(latency.average),
+                min_latency_ms: as_millis(latency.min),
+                max_latency_ms: as_millis(latency.max),
+                successful_measurements: latency.successes,
+                failed_measurements: latency.failures,
+            });
+        }
+        health
+    }
+
     /// Concurrently send a request to all candidates (regardless of
     /// offline/online) status and attempt to collect a rough reading on the
     /// latency between the VC and candidate.
+    ///
+    /// Each measurement is also recorded in the `LatencyStats` of its candidate.
     pub async fn measure_latency(&self) -> Vec<LatencyMeasurement> {
         let futures: Vec<_> = self
             .candidates
@@ -438,7 +603,7 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         // Send the request to all BNs at the same time. This might involve some
         // queueing on the sending host, however I hope it will avoid bias
         // caused by sending requests at different times.
-        future::join_all(futures)
+        let measurements: Vec<_> = future::join_all(futures)
             .await
             .into_iter()
             .map(|(beacon_node_id, response_instant)| LatencyMeasurement {
@@ -446,7 +611,13 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
                 latency: response_instant
                     .and_then(|response| response.checked_duration_since(request_instant)),
             })
-            .collect()
+            .collect();
+
+        for (candidate, measurement) in self.candidates.iter().zip(&measurements) {
+            candidate.latency.write().await.observe(measurement.latency);
+        }
+
+        measurements
     }
 
     /// Run `func` against each candidate in `self`, returning immediately if a result is found.
@@ -508,7 +679,7 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         // First pass: try `func` on all synced and ready candidates.
         //
         // This ensures that we always choose a synced node if it is available.
-        for candidate in &self.candidates {
+        for candidate in self.ordered_candidates().await {
             match candidate.status(RequireSynced::Yes).await {
                 Err(e @ CandidateError::NotSynced) if require_synced == false => {
                     // This client is unsynced we will try it after trying all synced clients
@@ -678,12 +849,13 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         }
     }
 
-    /// Call `func` on first beacon node that returns success or on all beacon nodes
-    /// depending on the value of `disable_run_on_all`.
+    /// Call `func` on all beacon nodes if `topic` is configured to be broadcast, otherwise call it
+    /// on the first beacon node that returns success.
     pub async fn run<'a, F, Err, R>(
         &'a self,
         require_synced: RequireSynced,
         offline_on_failure: OfflineOnFailure,
+        topic: ApiTopic,
         func: F,
     ) -> Result<(), Errors<Err>>
     where
@@ -691,13 +863,126 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         R: Future<Output = Result<(), Err>>,
         Err: Debug,
     {
-        if self.disable_run_on_all {
+        if self.broadcast_topics.contains(&topic) {
+            self.run_on_all(require_synced, offline_on_failure, func)
+                .await
+        } else {
             self.first_success(require_synced, offline_on_failure, func)
                 .await?;
             Ok(())
-        } else {
-            self.run_on_all(require_synced, offline_on_failure, func)
-                .await
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use environment::null_logger;
+    use eth2::Timeouts;
+    use sensitive_url::SensitiveUrl;
+    use slot_clock::TestingSlotClock;
+    use types::MainnetEthSpec;
+
+    const SLOW: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn latency_stats_observe() {
+        let mut stats = LatencyStats::default();
+        assert_eq!(stats.tier(SLOW), 0);
+
+        stats.observe(Some(Duration::from_millis(100)));
+        stats.observe(Some(Duration::from_millis(600)));
+        assert_eq!(stats.last, Some(Duration::from_millis(600)));
+        assert_eq!(stats.average, Some(Duration::from_millis(200)));
+        assert_eq!(stats.min, Some(Duration::from_millis(100)));
+        assert_eq!(stats.max, Some(Duration::from_millis(600)));
+        assert_eq!(stats.successes, 2);
+        assert_eq!(stats.tier(SLOW), 0);
+
+        stats.observe(None);
+        assert_eq!(stats.failures, 1);
+        assert_eq!(stats.last, Some(Duration::from_millis(600)));
+        assert_eq!(stats.tier(SLOW), 2);
+
+        stats.observe(Some(Duration::from_millis(100)));
+        assert_eq!(stats.tier(SLOW), 0);
+    }
+
+    #[test]
+    fn latency_stats_slow_tier() {
+        let mut stats = LatencyStats::default();
+        stats.observe(Some(Duration::from_secs(2)));
+        assert_eq!(stats.tier(SLOW), 1);
+    }
+
+    #[tokio::test]
+    async fn first_success_demotes_slow_and_unresponsive_candidates() {
+        let urls = (1..=3)
+            .map(|port| SensitiveUrl::parse(&format!("http://127.0.0.1:{}", port)).unwrap())
+            .collect::<Vec<_>>();
+        let candidates = urls
+            .iter()
+            .map(|url| {
+                CandidateBeaconNode::<MainnetEthSpec>::new(BeaconNodeHttpClient::new(
+                    url.clone(),
+                    Timeouts::set_all(Duration::from_secs(1)),
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        // The first candidate is unresponsive, the second is slow and the third is fast.
+        let latencies = [
+            None,
+            Some(Duration::from_secs(2)),
+            Some(Duration::from_millis(50)),
+        ];
+        for (candidate, latency) in candidates.iter().zip(latencies) {
+            *candidate.status.write().await = Ok(());
+            candidate.latency.write().await.observe(latency);
+        }
+
+        let fallback = BeaconNodeFallback::<TestingSlotClock, MainnetEthSpec>::new(
+            candidates,
+            vec![],
+            MainnetEthSpec::default_spec(),
+            null_logger().unwrap(),
+        );
+
+        let fallback_positions = fallback
+            .health()
+            .await
+            .iter()
+            .map(|health| health.fallback_position)
+            .collect::<Vec<_>>();
+        assert_eq!(fallback_positions, vec![2, 1, 0]);
+
+        // The fast candidate is tried first, despite being supplied last.
+        let first = fallback
+            .first_success(
+                RequireSynced::Yes,
+                OfflineOnFailure::No,
+                |beacon_node| async move { Ok::<_, ()>(beacon_node.to_string()) },
+            )
+            .await
+            .unwrap();
+        assert_eq!(first, urls[2].to_string());
+
+        // The slow candidate is tried before the unresponsive one.
+        let fast_url = urls[2].to_string();
+        let second = fallback
+            .first_success(RequireSynced::Yes, OfflineOnFailure::No, |beacon_node| {
+                let url = beacon_node.to_string();
+                let fast_url = fast_url.clone();
+                async move {
+                    if url == fast_url {
+                        Err(())
+                    } else {
+                        Ok(url)
+                    }
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(second, urls[1].to_string());
+    }
+}
@@ -7,8 +7,11 @@ use crate::{
 };
 use crate::{http_metrics::metrics, validator_store::ValidatorStore};
 use environment::RuntimeContext;
-use eth2::BeaconNodeHttpClient;
-use slog::{crit, debug, error, info, trace, warn};
+use eth2::{
+    types::{ProducedBlockV3, SkipRandaoVerification},
+    BeaconNodeHttpClient, StatusCode,
+};
+use slog::{crit, debug, error, info, trace, warn, Logger};
 use slot_clock::SlotClock;
 use std::fmt::Debug;
 use std::future::Future;
@@ -18,8 +21,8 @@ use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio::time::sleep;
 use types::{
-    AbstractExecPayload, BlindedPayload, BlockType, EthSpec, FullPayload, Graffiti, PublicKeyBytes,
-    Slot,
+    AbstractExecPayload, Address, BeaconBlock, BlindedPayload, BlockType, EthSpec, ExecPayload,
+    ExecutionBlockHash, FullPayload, Graffiti, PublicKeyBytes, SignatureBytes, Slot,
 };
 
 #[derive(Debug)]
@@ -53,6 +56,8 @@ pub struct BlockServiceBuilder<T, E: EthSpec> {
     graffiti: Option<Graffiti>,
     graffiti_file: Option<GraffitiFile>,
     block_delay: Option<Duration>,
+    distributed: bool,
+    strict_fee_recipient: bool,
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
@@ -66,6 +71,8 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
             graffiti: None,
             graffiti_file: None,
             block_delay: None,
+            distributed: false,
+            strict_fee_recipient: false,
         }
     }
 
@@ -109,6 +116,16 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
         self
     }
 
+    pub fn distributed(mut self, distributed: bool) -> Self {
+        self.distributed = distributed;
+        self
+    }
+
+    pub fn strict_fee_recipient(mut self, strict_fee_recipient: bool) -> Self {
+        self.strict_fee_recipient = strict_fee_recipient;
+        self
+    }
+
     pub fn build(self) -> Result<BlockService<T, E>, String> {
         Ok(BlockService {
             inner: Arc::new(Inner {
@@ -128,6 +145,8 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
                 graffiti: self.graffiti,
                 graffiti_file: self.graffiti_file,
                 block_delay: self.block_delay,
+                distributed: self.distributed,
+                strict_fee_recipient: self.strict_fee_recipient,
             }),
         })
     }
@@ -212,6 +231,9 @@ pub struct Inner<T, E: EthSpec> {
     graffiti: Option<Graffiti>,
     graffiti_file: Option<GraffitiFile>,
     block_delay: Option<Duration>,
+    distributed: bool,
+    /// Refuse to sign full blocks whose fee recipient differs from the configured one.
+    strict_fee_recipient: bool,
 }
 
 /// Attempts to produce attestations for any block producer(s) at the start of the epoch.
@@ -257,7 +279,9 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                 while let Some(notif) = notification_rx.recv().await {
                     let service = self.clone();
 
-                    if let Some(delay) = service.block_delay {
+                    if let Some(block_delay) = service.block_delay {
+                        let delay =
+                            block_delay_remaining(&service.slot_clock, notif.slot, block_delay);
                         debug!(
                             service.context.log(),
                             "Delaying block production by {}ms",
@@ -331,70 +355,49 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         }
 
         for validator_pubkey in proposers {
-            let builder_proposals = self
-                .validator_store
-                .get_builder_proposals(&validator_pubkey);
+            let duty_guard = match self.validator_store.in_flight_duties().start() {
+                Some(guard) => guard,
+                None => {
+                    warn!(
+                        log,
+                        "Not producing block during shutdown";
+                        "slot" => slot.as_u64(),
+                    );
+                    continue;
+                }
+            };
             let service = self.clone();
             let log = log.clone();
             self.inner.context.executor.spawn(
                 async move {
-                    if builder_proposals {
-                        let result = service
-                            .clone()
-                            .publish_block::<BlindedPayload<E>>(slot, validator_pubkey)
-                            .await;
-                        match result {
-                            Err(BlockError::Recoverable(e)) => {
-                                error!(
-                                    log,
-                                    "Error whilst producing block";
-                                    "error" => ?e,
-                                    "block_slot" => ?slot,
-                                    "info" => "blinded proposal failed, attempting full block"
-                                );
-                                if let Err(e) = service
-                                    .publish_block::<FullPayload<E>>(slot, validator_pubkey)
-                                    .await
-                                {
-                                    // Log a `crit` since a full block
-                                    // (non-builder) proposal failed.
-                                    crit!(
-                                        log,
-                                        "Error whilst producing block";
-                                        "error" => ?e,
-                                        "block_slot" => ?slot,
-                                        "info" => "full block attempted after a blinded failure",
-                                    );
-                                }
-                            }
-                            Err(BlockError::Irrecoverable(e)) => {
-                                // Only log an `error` since it's common for
-                                // builders to timeout on their response, only
-                                // to publish the block successfully themselves.
-                                error!(
-                                    log,
-                                    "Error whilst producing block";
-                                    "error" => ?e,
-                                    "block_slot" => ?slot,
-                                    "info" => "this error may or may not result in a missed block",
-                                )
-                            }
-                            Ok(_) => {}
-                        };
-                    } else if let Err(e) = service
-                        .publish_block::<FullPayload<E>>(slot, validator_pubkey)
-                        .await
-                    {
-                        // Log a `crit` since a full block (non-builder)
-                        // proposal failed.
-                        crit!(
-                            log,
-                            "Error whilst producing block";
-                            "message" => ?e,
-                            "block_slot" => ?slot,
-                            "info" => "proposal did not use a builder",
-                        );
-                    }
+                    let _duty_guard = duty_guard;
+                    let result = service.publish_block(slot, validator_pubkey).await;
+                    match result {
+                        Err(BlockError::Recoverable(e)) => {
+                            // Log a `crit` since the beacon node failed to produce a block, even
+                            // though it will fall back to a local payload if a builder fails.
+                            crit!(
+                                log,
+                                "Error whilst producing block";
+                                "error" => ?e,
+                                "block_slot" => ?slot,
+                                "info" => "block v3 proposal failed",
+                            );
+                        }
+                        Err(BlockError::Irrecoverable(e)) => {
+                            // Only log an `error` since it's common for
+                            // builders to timeout on their response, only
+                            // to publish the block successfully themselves.
+                            error!(
+                                log,
+                                "Error whilst producing block";
+                                "error" => ?e,
+                                "block_slot" => ?slot,
+                                "info" => "this error may or may not result in a missed block",
+                            )
+                        }
+                        Ok(_) => {}
+                    };
                 },
                 "block service",
             );
@@ -403,8 +406,13 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         Ok(())
     }
 
-    /// Produce a block at the given slot for validator_pubkey
-    async fn publish_block<Payload: AbstractExecPayload<E>>(
+    /// Produce a block at the given slot for validator_pubkey.
+    ///
+    /// The beacon node decides whether the block uses a local payload (a full block) or a builder
+    /// payload (a blinded block), weighing the two according to the validator's builder boost
+    /// factor. Beacon nodes which don't support the v3 endpoint are asked for a block via the v2
+    /// endpoints instead.
+    async fn publish_block(
         self,
         slot: Slot,
         validator_pubkey: PublicKeyBytes,
@@ -422,6 +430,10 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
             .randao_reveal(validator_pubkey, slot.epoch(E::slots_per_epoch()))
             .await
             .map_err(|e| {
+                metrics::inc_counter_vec(
+                    &metrics::DUTY_FAILURES_TOTAL,
+                    &[metrics::BEACON_BLOCK, e.metrics_cause()],
+                );
                 BlockError::Recoverable(format!(
                     "Unable to produce randao reveal signature: {:?}",
                     e
@@ -438,9 +450,21 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         );
 
         let randao_reveal_ref = &randao_reveal;
-        let self_ref = &self;
         let proposer_index = self.validator_store.validator_index(&validator_pubkey);
-        let validator_pubkey_ref = &validator_pubkey;
+        // Payloads built by a builder may pay the proposer via a transaction rather than the fee
+        // recipient, so only check payloads built by the beacon node's execution engine.
+        let expected_fee_recipient = if self.strict_fee_recipient {
+            self.validator_store.get_fee_recipient(&validator_pubkey)
+        } else {
+            None
+        };
+        let builder_proposals = self
+            .validator_store
+            .get_builder_proposals(&validator_pubkey);
+        let builder_boost_factor = self
+            .validator_store
+            .determine_builder_boost_factor(&validator_pubkey);
+        let distributed = self.distributed;
         let proposer_fallback = ProposerFallback {
             beacon_nodes: self.beacon_nodes.clone(),
             proposer_nodes: self.proposer_nodes.clone(),
@@ -451,6 +475,12 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
             "Requesting unsigned block";
             "slot" => slot.as_u64(),
         );
+        debug!(
+            log,
+            "Using builder boost factor";
+            "slot" => slot.as_u64(),
+            "builder_boost_factor" => ?builder_boost_factor,
+        );
 
         // Request block from first responsive beacon node.
         //
@@ -461,74 +491,99 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
                 |beacon_node| async move {
-                    let block = match Payload::block_type() {
-                        BlockType::Full => {
-                            let _get_timer = metrics::start_timer_vec(
-                                &metrics::BLOCK_SERVICE_TIMES,
-                                &[metrics::BEACON_BLOCK_HTTP_GET],
-                            );
-                            beacon_node
-                                .get_validator_blocks::<E, Payload>(
-                                    slot,
-                                    randao_reveal_ref,
-                                    graffiti.as_ref(),
-                                )
-                                .await
-                                .map_err(|e| {
-                                    BlockError::Recoverable(format!(
-                                        "Error from beacon node when producing block: {:?}",
-                                        e
-                                    ))
-                                })?
-                                .data
-                        }
-                        BlockType::Blinded => {
-                            let _get_timer = metrics::start_timer_vec(
-                                &metrics::BLOCK_SERVICE_TIMES,
-                                &[metrics::BLINDED_BEACON_BLOCK_HTTP_GET],
-                            );
-                            beacon_node
-                                .get_validator_blinded_blocks::<E, Payload>(
-                                    slot,
-                                    randao_reveal_ref,
-                                    graffiti.as_ref(),
-                                )
-                                .await
-                                .map_err(|e| {
-                                    BlockError::Recoverable(format!(
-                                        "Error from beacon node when producing block: {:?}",
-                                        e
-                                    ))
-                                })?
-                                .data
-                        }
-                    };
-
-                    info!(
-                        log,
-                        "Received unsigned block";
-                        "slot" => slot.as_u64(),
+                    let _get_timer = metrics::start_timer_vec(
+                        &metrics::BLOCK_SERVICE_TIMES,
+                        &[metrics::BEACON_BLOCK_HTTP_GET],
                     );
-                    if proposer_index != Some(block.proposer_index()) {
-                        return Err(BlockError::Recoverable(
-                            "Proposer index does not match block proposer. Beacon chain re-orged"
-                                .to_string(),
-                        ));
+                    let block = get_unsigned_block(
+                        beacon_node,
+                        slot,
+                        randao_reveal_ref,
+                        graffiti.as_ref(),
+                        builder_proposals,
+                        builder_boost_factor,
+                        distributed,
+                        log,
+                    )
+                    .await?;
+
+                    match &block {
+                        UnsignedBlock::Full(block) => {
+                            check_block(block, proposer_index, expected_fee_recipient)?
+                        }
+                        UnsignedBlock::Blinded(block) => check_block(block, proposer_index, None)?,
                     }
 
                     Ok::<_, BlockError>(block)
                 },
             )
-            .await?;
+            .await
+            .map_err(|e| {
+                metrics::inc_counter_vec(
+                    &metrics::DUTY_FAILURES_TOTAL,
+                    &[metrics::BEACON_BLOCK, metrics::BEACON_NODE_ERROR],
+                );
+                e
+            })?;
+
+        match block {
+            UnsignedBlock::Full(block) => {
+                self.sign_and_publish_block::<FullPayload<E>>(
+                    proposer_fallback,
+                    slot,
+                    validator_pubkey,
+                    graffiti,
+                    block,
+                    current_slot,
+                )
+                .await
+            }
+            UnsignedBlock::Blinded(block) => {
+                self.sign_and_publish_block::<BlindedPayload<E>>(
+                    proposer_fallback,
+                    slot,
+                    validator_pubkey,
+                    graffiti,
+                    block,
+                    current_slot,
+                )
+                .await
+            }
+        }
+    }
+
+    /// Sign a block produced for `validator_pubkey` and publish it to the beacon node(s).
+    async fn sign_and_publish_block<Payload: AbstractExecPayload<E>>(
+        &self,
+        proposer_fallback: ProposerFallback<T, E>,
+        slot: Slot,
+        validator_pubkey: PublicKeyBytes,
+        graffiti: Option<Graffiti>,
+        block: BeaconBlock<E, Payload>,
+        current_slot: Slot,
+    ) -> Result<(), BlockError> {
+        let log = self.context.log();
 
         let signing_timer = metrics::start_timer(&metrics::BLOCK_SIGNING_TIMES);
-        let signed_block = self_ref
+        let signed_block = self
             .validator_store
-            .sign_block::<Payload>(*validator_pubkey_ref, block, current_slot)
+            .sign_block::<Payload>(validator_pubkey, block, current_slot)
             .await
-            .map_err(|e| BlockError::Recoverable(format!("Unable to sign block: {:?}", e)))?;
-        let signing_time_ms =
-            Duration::from_secs_f64(signing_timer.map_or(0.0, |t| t.stop_and_record())).as_millis();
+            .map_err(|e| {
+                metrics::inc_counter_vec(
+                    &metrics::DUTY_FAILURES_TOTAL,
+                    &[metrics::BEACON_BLOCK, e.metrics_cause()],
+                );
+                BlockError::Recoverable(format!("Unable to sign block: {:?}", e))
+            })?;
+        let signing_time =
+            Duration::from_secs_f64(signing_timer.map_or(0.0, |t| t.stop_and_record()));
+        metrics::observe_timer_vec(
+            &metrics::DUTY_SIGNING_TIMES,
+            &[metrics::BEACON_BLOCK],
+            signing_time,
+        );
+        let signing_time_ms = signing_time.as_millis();
 
         info!(
             log,
@@ -542,6 +597,8 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         // Try the proposer nodes first, since we've likely gone to efforts to
         // protect them from DoS attacks and they're most likely to successfully
         // publish a block.
+        let publish_timer =
+            metrics::start_timer_vec(&metrics::DUTY_PUBLISH_TIMES, &[metrics::BEACON_BLOCK]);
         proposer_fallback
             .first_success_try_proposers_first(
                 RequireSynced::No,
@@ -582,7 +639,38 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                     Ok::<_, BlockError>(())
                 },
             )
-            .await?;
+            .await
+            .map_err(|e| {
+                metrics::inc_counter_vec(
+                    &metrics::DUTY_FAILURES_TOTAL,
+                    &[metrics::BEACON_BLOCK, metrics::BEACON_NODE_ERROR],
+                );
+                e
+            })?;
+        drop(publish_timer);
+
+        // Record how far into the slot the block was published, warning if it was published too
+        // late to be attested to.
+        let attestation_deadline = self.slot_clock.slot_duration() / 3;
+        if let Some(publish_delay) = metrics::observe_duty_latency(
+            &self.slot_clock,
+            metrics::BEACON_BLOCK,
+            slot,
+            attestation_deadline,
+        ) {
+            metrics::observe_duration(&metrics::BLOCK_PUBLISH_DELAY, publish_delay);
+
+            if publish_delay >= attestation_deadline {
+                metrics::inc_counter(&metrics::BLOCK_PUBLISHED_LATE_TOTAL);
+                warn!(
+                    log,
+                    "Block published late";
+                    "msg" => "the block may not be attested to, consider reducing --block-delay-ms",
+                    "publish_delay_ms" => publish_delay.as_millis(),
+                    "slot" => slot.as_u64(),
+                );
+            }
+        }
 
         info!(
             log,
@@ -597,3 +685,265 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         Ok(())
     }
 }
+
+/// An unsigned block produced by the beacon node, with either a local or a builder payload.
+pub(crate) enum UnsignedBlock<E: EthSpec> {
+    Full(BeaconBlock<E, FullPayload<E>>),
+    Blinded(BeaconBlock<E, BlindedPayload<E>>),
+}
+
+/// Request a block from a beacon node, falling back to the v2 endpoints if it doesn't support
+/// `GET v3/validator/blocks/{slot}`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn get_unsigned_block<E: EthSpec>(
+    beacon_node: &BeaconNodeHttpClient,
+    slot: Slot,
+    randao_reveal: &SignatureBytes,
+    graffiti: Option<&Graffiti>,
+    builder_proposals: bool,
+    builder_boost_factor: Option<u64>,
+    distributed: bool,
+    log: &Logger,
+) -> Result<UnsignedBlock<E>, BlockError> {
+    match beacon_node
+        .get_validator_blocks_v3::<E>(
+            slot,
+            randao_reveal,
+            graffiti,
+            SkipRandaoVerification::No,
+            builder_boost_factor,
+        )
+        .await
+    {
+        Ok(block_response) => {
+            info!(
+                log,
+                "Received unsigned block";
+                "slot" => slot.as_u64(),
+                "execution_payload_blinded" =>
+                    matches!(block_response, ProducedBlockV3::Blinded(_)),
+                "execution_payload_value" =>
+                    %block_response.execution_payload_value(),
+            );
+            Ok(match block_response {
+                ProducedBlockV3::Full(response) => UnsignedBlock::Full(response.data),
+                ProducedBlockV3::Blinded(response) => UnsignedBlock::Blinded(response.data),
+            })
+        }
+        Err(e)
+            if matches!(
+                e.status(),
+                Some(StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED)
+            ) =>
+        {
+            debug!(
+                log,
+                "Beacon node does not support block v3";
+                "info" => "requesting a block via the v2 endpoints",
+                "slot" => slot.as_u64(),
+            );
+            let block = get_block_v2(
+                beacon_node,
+                slot,
+                randao_reveal,
+                graffiti,
+                builder_proposals,
+                builder_boost_factor,
+                distributed,
+                log,
+            )
+            .await?;
+            info!(
+                log,
+                "Received unsigned block";
+                "slot" => slot.as_u64(),
+                "execution_payload_blinded" =>
+                    matches!(block, UnsignedBlock::Blinded(_)),
+            );
+            Ok(block)
+        }
+        Err(e) => Err(BlockError::Recoverable(format!(
+            "Error from beacon node when producing block: {:?}",
+            e
+        ))),
+    }
+}
+
+/// Request a block from a beacon node which doesn't support `GET v3/validator/blocks/{slot}`.
+///
+/// Validators using a builder request a blinded block, falling back to a full block if that
+/// fails. In distributed mode the full block is never requested, since other members of the
+/// cluster may have already signed the blinded block.
+#[allow(clippy::too_many_arguments)]
+async fn get_block_v2<E: EthSpec>(
+    beacon_node: &BeaconNodeHttpClient,
+    slot: Slot,
+    randao_reveal: &SignatureBytes,
+    graffiti: Option<&Graffiti>,
+    builder_proposals: bool,
+    builder_boost_factor: Option<u64>,
+    distributed: bool,
+    log: &Logger,
+) -> Result<UnsignedBlock<E>, BlockError> {
+    if builder_proposals {
+        match beacon_node
+            .get_validator_blinded_blocks_modular::<E, BlindedPayload<E>>(
+                slot,
+                randao_reveal,
+                graffiti,
+                SkipRandaoVerification::No,
+                builder_boost_factor,
+            )
+            .await
+        {
+            Ok(response) => return Ok(UnsignedBlock::Blinded(response.data)),
+            Err(e) if distributed => {
+                return Err(BlockError::Recoverable(format!(
+                    "Error from beacon node when producing blinded block: {:?}",
+                    e
+                )))
+            }
+            Err(e) => warn!(
+                log,
+                "Error from beacon node when producing blinded block";
+                "error" => ?e,
+                "info" => "attempting full block",
+                "slot" => slot.as_u64(),
+            ),
+        }
+    }
+
+    beacon_node
+        .get_validator_blocks::<E, FullPayload<E>>(slot, randao_reveal, graffiti)
+        .await
+        .map(|response| UnsignedBlock::Full(response.data))
+        .map_err(|e| {
+            BlockError::Recoverable(format!(
+                "Error from beacon node when producing block: {:?}",
+                e
+            ))
+        })
+}
+
+/// Check that a block received from a beacon node is safe to sign.
+fn check_block<E: EthSpec, Payload: AbstractExecPayload<E>>(
+    block: &BeaconBlock<E, Payload>,
+    proposer_index: Option<u64>,
+    expected_fee_recipient: Option<Address>,
+) -> Result<(), BlockError> {
+    if proposer_index != Some(block.proposer_index()) {
+        return Err(BlockError::Recoverable(
+            "Proposer index does not match block proposer. Beacon chain re-orged".to_string(),
+        ));
+    }
+
+    // Blocks produced before the merge has completed carry a default payload, with a zero fee
+    // recipient, so only check payloads built on top of an execution block.
+    if let (Some(expected), Ok(payload)) =
+        (expected_fee_recipient, block.body().execution_payload())
+    {
+        if payload.block_hash() == ExecutionBlockHash::zero() {
+            return Ok(());
+        }
+
+        let fee_recipient = payload.fee_recipient();
+        if fee_recipient != expected {
+            return Err(BlockError::Recoverable(format!(
+                "Refusing to sign block with incorrect fee recipient \
+                (expected {:?}, got {:?})",
+                expected, fee_recipient
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns how long to wait so that block production for `slot` starts `block_delay` after the
+/// start of the slot, accounting for time already spent since the slot started.
+fn block_delay_remaining<T: SlotClock>(
+    slot_clock: &T,
+    slot: Slot,
+    block_delay: Duration,
+) -> Duration {
+    slot_clock
+        .start_of(slot)
+        .zip(slot_clock.now_duration())
+        .map(|(slot_start, now)| (slot_start + block_delay).saturating_sub(now))
+        .unwrap_or(block_delay)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slot_clock::ManualSlotClock;
+    use types::{BeaconBlockMerge, EmptyBlock, Hash256, MainnetEthSpec};
+
+    type E = MainnetEthSpec;
+
+    fn merge_block(
+        fee_recipient: Address,
+        block_hash: ExecutionBlockHash,
+    ) -> BeaconBlock<E, FullPayload<E>> {
+        let mut block = BeaconBlockMerge::<E, FullPayload<E>>::empty(&E::default_spec());
+        block.proposer_index = 1;
+        let payload = &mut block.body.execution_payload.execution_payload;
+        payload.fee_recipient = fee_recipient;
+        payload.block_hash = block_hash;
+        BeaconBlock::Merge(block)
+    }
+
+    #[test]
+    fn strict_fee_recipient_ignores_pre_merge_blocks() {
+        let expected = Some(Address::repeat_byte(0x42));
+        let block = merge_block(Address::zero(), ExecutionBlockHash::zero());
+        assert!(check_block(&block, Some(1), expected).is_ok());
+    }
+
+    #[test]
+    fn strict_fee_recipient_checks_post_merge_blocks() {
+        let expected = Address::repeat_byte(0x42);
+        let block_hash = ExecutionBlockHash::from_root(Hash256::repeat_byte(1));
+
+        let block = merge_block(expected, block_hash);
+        assert!(check_block(&block, Some(1), Some(expected)).is_ok());
+
+        let block = merge_block(Address::repeat_byte(0x43), block_hash);
+        assert!(matches!(
+            check_block(&block, Some(1), Some(expected)),
+            Err(BlockError::Recoverable(_))
+        ));
+        assert!(check_block(&block, Some(1), None).is_ok());
+    }
+
+    #[test]
+    fn block_delay_measured_from_slot_start() {
+        let slot_clock = ManualSlotClock::new(
+            Slot::new(0),
+            Duration::from_secs(0),
+            Duration::from_secs(12),
+        );
+        let slot = Slot::new(1);
+        let slot_start = slot_clock.start_of(slot).unwrap();
+        let block_delay = Duration::from_millis(500);
+
+        slot_clock.set_current_time(slot_start);
+        assert_eq!(
+            block_delay_remaining(&slot_clock, slot, block_delay),
+            block_delay
+        );
+
+        slot_clock.set_current_time(slot_start + Duration::from_millis(200));
+        assert_eq!(
+            block_delay_remaining(&slot_clock, slot, block_delay),
+            Duration::from_millis(300)
+        );
+
+        // The notification arrived after the delay had already elapsed.
+        slot_clock.set_current_time(slot_start + Duration::from_secs(1));
+        assert_eq!(
+            block_delay_remaining(&slot_clock, slot, block_delay),
+            Duration::ZERO
+        );
+    }
+}
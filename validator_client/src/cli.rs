@@ -39,11 +39,24 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Arg::with_name("disable-run-on-all")
                 .long("disable-run-on-all")
                 .value_name("DISABLE_RUN_ON_ALL")
-                .help("By default, Lighthouse publishes attestation, sync committee subscriptions \
+                .help("DEPRECATED. Use --broadcast. \
+                       By default, Lighthouse publishes attestation, sync committee subscriptions \
                        and proposer preparation messages to all beacon nodes provided in the \
                        `--beacon-nodes flag`. This option changes that behaviour such that these \
                        api calls only go out to the first available and synced beacon node")
                 .takes_value(false)
+                .conflicts_with("broadcast")
+        )
+        .arg(
+            Arg::with_name("broadcast")
+                .long("broadcast")
+                .value_name("API_TOPICS")
+                .help("Comma-separated list of the classes of messages which are sent to all \
+                       beacon nodes provided in `--beacon-nodes`, rather than only the first \
+                       available one. This keeps fallback nodes ready to serve duties. \
+                       Possible values are: none, subscriptions, preparations, registrations. \
+                       [default: subscriptions,preparations]")
+                .takes_value(true)
         )
         // This argument is deprecated, use `--beacon-nodes` instead.
         .arg(
@@ -118,6 +131,36 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                         made to the beacon node. This flag is generally not recommended, \
                         longer timeouts can cause missed duties when fallbacks are used.")
         )
+        .arg(
+            Arg::with_name("distributed")
+                .long("distributed")
+                .help("Enables functionality required for running the validator in a distributed \
+                        validator cluster, behind middleware such as Obol's Charon. Requests to the \
+                        beacon node time out after a third of a slot, attestation selection proofs \
+                        are computed an epoch in advance and combined by the middleware before \
+                        aggregation duties are decided, and block proposals are never retried with \
+                        a different block.")
+                .conflicts_with("use-long-timeouts")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("web3-signer-keep-alive-timeout")
+                .long("web3-signer-keep-alive-timeout")
+                .value_name("MILLIS")
+                .default_value("20000")
+                .help("Keep-alive timeout for each web3signer connection. Idle connections are \
+                        pooled and re-used for subsequent signing requests. Set to '0' to \
+                        disable keep-alive.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("web3-signer-max-idle-connections")
+                .long("web3-signer-max-idle-connections")
+                .value_name("COUNT")
+                .help("Maximum number of idle connections to keep open to each web3signer. \
+                        Unlimited if not set.")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("beacon-nodes-tls-certs")
                 .long("beacon-nodes-tls-certs")
@@ -171,13 +214,14 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
              Arg::with_name("http-address")
                  .long("http-address")
                  .value_name("ADDRESS")
-                 .help("Set the address for the HTTP address. The HTTP server is not encrypted \
-                        and therefore it is unsafe to publish on a public network. When this \
-                        flag is used, it additionally requires the explicit use of the \
+                 .help("Set the address for the HTTP address. Unless `--http-enable-tls` is \
+                        used, the HTTP server is not encrypted and therefore it is unsafe to \
+                        publish on a public network. When this flag is used without TLS, it \
+                        additionally requires the explicit use of the \
                         `--unencrypted-http-transport` flag to ensure the user is aware of the \
                         risks involved. For access via the Internet, users should apply \
-                        transport-layer security like a HTTPS reverse-proxy or SSH tunnelling.")
-                .requires("unencrypted-http-transport"),
+                        transport-layer security using `--http-enable-tls`, a HTTPS \
+                        reverse-proxy or SSH tunnelling."),
          )
          .arg(
              Arg::with_name("unencrypted-http-transport")
@@ -204,6 +248,45 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                     address of this server (e.g., http://localhost:5062).")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("http-enable-tls")
+                .long("http-enable-tls")
+                .help("Serves the RESTful HTTP API server over TLS.")
+                .takes_value(false)
+                .requires("http-tls-cert")
+                .requires("http-tls-key")
+        )
+        .arg(
+            Arg::with_name("http-tls-cert")
+                .long("http-tls-cert")
+                .value_name("PATH")
+                .help("The path of the certificate to be used when serving the HTTP API server \
+                    over TLS.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("http-tls-key")
+                .long("http-tls-key")
+                .value_name("PATH")
+                .help("The path of the private key to be used when serving the HTTP API server \
+                    over TLS. Must not be password-protected.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("http-rotate-token")
+                .long("http-rotate-token")
+                .help("Generate a new HTTP API token on startup. The existing API token and all \
+                    read-only tokens will be revoked.")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("http-generate-read-only-token")
+                .long("http-generate-read-only-token")
+                .help("Generate a new read-only HTTP API token on startup and append it to the \
+                    api-token-readonly.txt file in the validators directory. Read-only tokens \
+                    can only be used with GET endpoints.")
+                .takes_value(false),
+        )
         /* Prometheus metrics HTTP server related arguments */
         .arg(
             Arg::with_name("metrics")
@@ -285,6 +368,18 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                     immediately.")
                 .takes_value(false),
         )
+        .arg(
+            Arg::with_name("doppelganger-detection-epochs")
+                .long("doppelganger-detection-epochs")
+                .value_name("EPOCHS")
+                .help("The number of epochs which must pass without any of the validators managed \
+                    by this client being seen on the network before they are permitted to sign \
+                    slashable messages. Increasing this value reduces the chance of failing to \
+                    detect a doppelganger at the cost of more missed attestations on startup. \
+                    Default: 1")
+                .requires("enable-doppelganger-protection")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("builder-proposals")
                 .long("builder-proposals")
@@ -296,14 +391,12 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         ).arg(
             Arg::with_name("strict-fee-recipient")
                 .long("strict-fee-recipient")
-                .help("[DEPRECATED] If this flag is set, Lighthouse will refuse to sign any block whose \
-                        `fee_recipient` does not match the `suggested_fee_recipient` sent by this validator. \
-                         This applies to both the normal block proposal flow, as well as block proposals \
-                         through the builder API. Proposals through the builder API are more likely to have a \
-                         discrepancy in `fee_recipient` so you should be aware of how your connected relay \
-                         sends proposer payments before using this flag. If this flag is used, a fee recipient \
-                         mismatch in the builder API flow will result in a fallback to the local execution engine \
-                         for payload construction, where a strict fee recipient check will still be applied.")
+                .help("If this flag is set, Lighthouse will refuse to sign any locally-built (i.e. \
+                        non-blinded) block whose `fee_recipient` does not match the fee recipient \
+                        configured for the proposing validator. A beacon node which returns such a \
+                        block is treated as having failed and the next beacon node will be tried. \
+                        Blocks built through the builder API are not checked, since relays may pay \
+                        the proposer using a transaction instead of the fee recipient.")
                 .takes_value(false),
         )
         .arg(
@@ -324,6 +417,17 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                     set here moves too far from the previous block's gas limit. [default: 30,000,000]")
                 .requires("builder-proposals"),
         )
+        .arg(
+            Arg::with_name("builder-boost-factor")
+                .long("builder-boost-factor")
+                .value_name("PERCENTAGE")
+                .takes_value(true)
+                .help("Percentage multiplier applied by the beacon node to the builder's bid before \
+                    comparing it to the local payload's value. A value of 0 will always use the \
+                    local payload, and 100 compares the values as-is. Can be overridden per validator \
+                    with `builder_boost_factor` in the validator definitions. Only applies to \
+                    validators using the builder API."),
+        )
         .arg(
             Arg::with_name("latency-measurement-service")
                 .long("latency-measurement-service")
@@ -343,6 +447,39 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("500")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("validator-registration-interval-epochs")
+                .long("validator-registration-interval-epochs")
+                .value_name("EPOCHS")
+                .help("Defines the number of epochs between re-submissions of all validator \
+                    registrations to the BN. New or changed registrations are always submitted \
+                    in the next slot. This value can be increased to reduce the load on builders \
+                    with strict rate limits.")
+                .default_value("1")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("sync-committee-subscription-lookahead-epochs")
+                .long("sync-committee-subscription-lookahead-epochs")
+                .value_name("EPOCHS")
+                .help("The number of epochs before the start of a sync committee period at which \
+                    the validator client subscribes the beacon node to the sync committee subnets \
+                    for that period. Selection proofs for the period are computed at least one \
+                    epoch earlier. Increasing this gives the beacon node more time to find peers \
+                    on the subnets.")
+                .default_value("4")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("graceful-shutdown-timeout")
+                .long("graceful-shutdown-timeout")
+                .value_name("SECONDS")
+                .help("The maximum number of seconds to wait on shutdown for blocks, attestations \
+                    and sync committee messages which are already being signed or published. No \
+                    new duties are started once shutdown begins. Set to 0 to exit immediately.")
+                .default_value("12")
+                .takes_value(true),
+        )
         /*
          * Experimental/development options.
          */
@@ -350,9 +487,28 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Arg::with_name("block-delay-ms")
                 .long("block-delay-ms")
                 .value_name("MILLIS")
-                .hidden(true)
-                .help("Time to delay block production from the start of the slot. Should only be \
-                       used for testing.")
+                .help("Time to delay block production from the start of the slot. A small delay \
+                       allows the beacon node to include more attestations and transactions in \
+                       the block, at the risk of the block being published too late to be \
+                       attested to. Must be less than 1/3 of the slot duration.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("attestation-production-offset-ms")
+                .long("attestation-production-offset-ms")
+                .value_name("MILLIS")
+                .help("Time after the start of the slot at which to produce attestations. \
+                       Defaults to 1/3 of the slot duration. Producing attestations earlier \
+                       increases the risk of attesting to the wrong head block.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("aggregate-production-offset-ms")
+                .long("aggregate-production-offset-ms")
+                .value_name("MILLIS")
+                .help("Time after the start of the slot at which to produce aggregate \
+                       attestations. Defaults to 2/3 of the slot duration. Must be later than the \
+                       attestation production time.")
                 .takes_value(true),
         )
 }
@@ -1,4 +1,9 @@
+use crate::beacon_node_fallback::ApiTopic;
+use crate::doppelganger_service::DEFAULT_REMAINING_DETECTION_EPOCHS;
 use crate::graffiti_file::GraffitiFile;
+use crate::initialized_validators::DEFAULT_WEB3SIGNER_IDLE_TIMEOUT;
+use crate::preparation_service::DEFAULT_EPOCHS_PER_VALIDATOR_REGISTRATION_SUBMISSION;
+use crate::sync_committee_service::SUBSCRIPTION_LOOKAHEAD_EPOCHS;
 use crate::{http_api, http_metrics};
 use clap::ArgMatches;
 use clap_utils::{flags::DISABLE_MALLOC_TUNING_FLAG, parse_optional, parse_required};
@@ -40,6 +45,14 @@ pub struct Config {
     pub init_slashing_protection: bool,
     /// If true, use longer timeouts for requests made to the beacon node.
     pub use_long_timeouts: bool,
+    /// If true, run in a mode compatible with distributed validator middleware.
+    pub distributed: bool,
+    /// If true, refuse to sign locally-built blocks with an unexpected fee recipient.
+    pub strict_fee_recipient: bool,
+    /// How long idle connections to a remote signer are kept alive. `None` disables keep-alive.
+    pub web3_signer_keep_alive_timeout: Option<Duration>,
+    /// The maximum number of idle connections to keep open to each remote signer.
+    pub web3_signer_max_idle_connections: Option<usize>,
     /// Graffiti to be inserted everytime we create a block.
     pub graffiti: Option<Graffiti>,
     /// Graffiti file to load per validator graffitis.
@@ -55,6 +68,8 @@ pub struct Config {
     /// If true, enable functionality that monitors the network for attestations or proposals from
     /// any of the validators managed by this client before starting up.
     pub enable_doppelganger_protection: bool,
+    /// The number of epochs for which doppelganger protection checks each validator.
+    pub doppelganger_detection_epochs: u64,
     /// If true, then we publish validator specific metrics (e.g next attestation duty slot)
     /// for all our managed validators.
     /// Note: We publish validator specific metrics for low validator counts without this flag
@@ -66,19 +81,34 @@ pub struct Config {
     pub builder_registration_timestamp_override: Option<u64>,
     /// Fallback gas limit.
     pub gas_limit: Option<u64>,
+    /// Fallback percentage multiplier applied to builder bids by the beacon node.
+    pub builder_boost_factor: Option<u64>,
     /// A list of custom certificates that the validator client will additionally use when
     /// connecting to a beacon node over SSL/TLS.
     pub beacon_nodes_tls_certs: Option<Vec<PathBuf>>,
-    /// Delay from the start of the slot to wait before publishing a block.
+    /// Delay from the start of the slot to wait before producing a block.
     ///
-    /// This is *not* recommended in prod and should only be used for testing.
+    /// Must be less than a third of a slot. A small delay gives the beacon node time to include
+    /// more attestations and transactions, at the risk of the block being attested to late.
     pub block_delay: Option<Duration>,
-    /// Disables publishing http api requests to all beacon nodes for select api calls.
-    pub disable_run_on_all: bool,
+    /// Time after the start of the slot at which to produce attestations (default 1/3 slot).
+    pub attestation_production_offset: Option<Duration>,
+    /// Time after the start of the slot at which to produce aggregates (default 2/3 slot).
+    pub aggregate_production_offset: Option<Duration>,
+    /// The classes of messages which are published to all beacon nodes, rather than only the first
+    /// available one.
+    pub broadcast_topics: Vec<ApiTopic>,
     /// Enables a service which attempts to measure latency between the VC and BNs.
     pub enable_latency_measurement_service: bool,
     /// Defines the number of validators per `validator/register_validator` request sent to the BN.
     pub validator_registration_batch_size: usize,
+    /// Defines the number of epochs between re-submissions of all validator registrations.
+    pub validator_registration_interval_epochs: u64,
+    /// The number of epochs before the start of a sync committee period at which subscriptions for
+    /// that period are sent to the beacon node.
+    pub sync_committee_subscription_lookahead_epochs: u64,
+    /// The maximum time to wait on shutdown for in-flight duties to be signed and published.
+    pub graceful_shutdown_timeout: Duration,
 }
 
 impl Default for Config {
@@ -104,6 +134,10 @@ impl Default for Config {
             disable_auto_discover: false,
             init_slashing_protection: false,
             use_long_timeouts: false,
+            distributed: false,
+            strict_fee_recipient: false,
+            web3_signer_keep_alive_timeout: Some(DEFAULT_WEB3SIGNER_IDLE_TIMEOUT),
+            web3_signer_max_idle_connections: None,
             graffiti: None,
             graffiti_file: None,
             fee_recipient: None,
@@ -111,15 +145,23 @@ impl Default for Config {
             http_metrics: <_>::default(),
             monitoring_api: None,
             enable_doppelganger_protection: false,
+            doppelganger_detection_epochs: DEFAULT_REMAINING_DETECTION_EPOCHS,
             enable_high_validator_count_metrics: false,
             beacon_nodes_tls_certs: None,
             block_delay: None,
+            attestation_production_offset: None,
+            aggregate_production_offset: None,
             builder_proposals: false,
             builder_registration_timestamp_override: None,
             gas_limit: None,
-            disable_run_on_all: false,
+            builder_boost_factor: None,
+            broadcast_topics: ApiTopic::default_broadcast(),
             enable_latency_measurement_service: true,
             validator_registration_batch_size: 500,
+            validator_registration_interval_epochs:
+                DEFAULT_EPOCHS_PER_VALIDATOR_REGISTRATION_SUBMISSION,
+            sync_committee_subscription_lookahead_epochs: SUBSCRIPTION_LOOKAHEAD_EPOCHS,
+            graceful_shutdown_timeout: Duration::from_secs(12),
         }
     }
 }
@@ -215,10 +257,40 @@ impl Config {
                 "msg" => "it no longer has any effect",
             );
         }
-        config.disable_run_on_all = cli_args.is_present("disable-run-on-all");
+
+        if cli_args.is_present("disable-run-on-all") {
+            warn!(
+                log,
+                "The --disable-run-on-all flag is deprecated";
+                "msg" => "please use --broadcast none instead"
+            );
+            config.broadcast_topics = vec![];
+        }
+        if let Some(broadcast_topics) = cli_args.value_of("broadcast") {
+            config.broadcast_topics = broadcast_topics
+                .split(',')
+                .map(str::trim)
+                .filter(|t| *t != "none")
+                .map(str::parse::<ApiTopic>)
+                .collect::<Result<_, _>>()?;
+        }
+
         config.disable_auto_discover = cli_args.is_present("disable-auto-discover");
         config.init_slashing_protection = cli_args.is_present("init-slashing-protection");
         config.use_long_timeouts = cli_args.is_present("use-long-timeouts");
+        config.distributed = cli_args.is_present("distributed");
+
+        if let Some(keep_alive_ms) =
+            parse_optional::<u64>(cli_args, "web3-signer-keep-alive-timeout")?
+        {
+            config.web3_signer_keep_alive_timeout = if keep_alive_ms == 0 {
+                None
+            } else {
+                Some(Duration::from_millis(keep_alive_ms))
+            };
+        }
+        config.web3_signer_max_idle_connections =
+            parse_optional(cli_args, "web3-signer-max-idle-connections")?;
 
         if let Some(graffiti_file_path) = cli_args.value_of("graffiti-file") {
             let mut graffiti_file = GraffitiFile::new(graffiti_file_path.into());
@@ -266,14 +338,32 @@ impl Config {
             config.http_api.enabled = true;
         }
 
+        if cli_args.is_present("http-enable-tls") {
+            config.http_api.tls_config = Some(http_api::TlsConfig {
+                cert: cli_args
+                    .value_of("http-tls-cert")
+                    .ok_or("--http-tls-cert was not provided.")?
+                    .parse::<PathBuf>()
+                    .map_err(|_| "http-tls-cert is not a valid path name.")?,
+                key: cli_args
+                    .value_of("http-tls-key")
+                    .ok_or("--http-tls-key was not provided.")?
+                    .parse::<PathBuf>()
+                    .map_err(|_| "http-tls-key is not a valid path name.")?,
+            });
+        }
+
         if let Some(address) = cli_args.value_of("http-address") {
-            if cli_args.is_present("unencrypted-http-transport") {
+            if cli_args.is_present("unencrypted-http-transport")
+                || config.http_api.tls_config.is_some()
+            {
                 config.http_api.listen_addr = address
                     .parse::<IpAddr>()
                     .map_err(|_| "http-address is not a valid IP address.")?;
             } else {
                 return Err(
-                    "While using `--http-address`, you must also use `--unencrypted-http-transport`."
+                    "While using `--http-address` without `--http-enable-tls`, you must also use \
+                    `--unencrypted-http-transport`."
                         .to_string(),
                 );
             }
@@ -294,6 +384,10 @@ impl Config {
             config.http_api.allow_origin = Some(allow_origin.to_string());
         }
 
+        config.http_api.rotate_api_token = cli_args.is_present("http-rotate-token");
+        config.http_api.generate_read_only_token =
+            cli_args.is_present("http-generate-read-only-token");
+
         /*
          * Prometheus metrics HTTP server
          */
@@ -349,6 +443,10 @@ impl Config {
             config.enable_doppelganger_protection = true;
         }
 
+        if let Some(epochs) = parse_optional(cli_args, "doppelganger-detection-epochs")? {
+            config.doppelganger_detection_epochs = epochs;
+        }
+
         if cli_args.is_present("builder-proposals") {
             config.builder_proposals = true;
         }
@@ -362,6 +460,15 @@ impl Config {
             })
             .transpose()?;
 
+        config.builder_boost_factor = cli_args
+            .value_of("builder-boost-factor")
+            .map(|factor| {
+                factor
+                    .parse::<u64>()
+                    .map_err(|_| "builder-boost-factor is not a valid u64.")
+            })
+            .transpose()?;
+
         if let Some(registration_timestamp_override) =
             cli_args.value_of("builder-registration-timestamp-override")
         {
@@ -372,13 +479,7 @@ impl Config {
             );
         }
 
-        if cli_args.is_present("strict-fee-recipient") {
-            warn!(
-                log,
-                "The flag `--strict-fee-recipient` has been deprecated due to a bug causing \
-                missed proposals. The flag will be ignored."
-            );
-        }
+        config.strict_fee_recipient = cli_args.is_present("strict-fee-recipient");
 
         config.enable_latency_measurement_service =
             parse_optional(cli_args, "latency-measurement-service")?.unwrap_or(true);
@@ -389,6 +490,21 @@ impl Config {
             return Err("validator-registration-batch-size cannot be 0".to_string());
         }
 
+        config.validator_registration_interval_epochs =
+            parse_required(cli_args, "validator-registration-interval-epochs")?;
+        if config.validator_registration_interval_epochs == 0 {
+            return Err("validator-registration-interval-epochs cannot be 0".to_string());
+        }
+
+        config.sync_committee_subscription_lookahead_epochs =
+            parse_required(cli_args, "sync-committee-subscription-lookahead-epochs")?;
+        if config.sync_committee_subscription_lookahead_epochs == 0 {
+            return Err("sync-committee-subscription-lookahead-epochs cannot be 0".to_string());
+        }
+
+        config.graceful_shutdown_timeout =
+            Duration::from_secs(parse_required(cli_args, "graceful-shutdown-timeout")?);
+
         /*
          * Experimental
          */
@@ -396,6 +512,17 @@ impl Config {
             config.block_delay = Some(Duration::from_millis(delay_ms));
         }
 
+        if let Some(offset_ms) =
+            parse_optional::<u64>(cli_args, "attestation-production-offset-ms")?
+        {
+            config.attestation_production_offset = Some(Duration::from_millis(offset_ms));
+        }
+
+        if let Some(offset_ms) = parse_optional::<u64>(cli_args, "aggregate-production-offset-ms")?
+        {
+            config.aggregate_production_offset = Some(Duration::from_millis(offset_ms));
+        }
+
         Ok(config)
     }
 }
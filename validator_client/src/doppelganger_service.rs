@@ -252,13 +252,16 @@ async fn beacon_node_liveness<'a, T: 'static + SlotClock, E: EthSpec>(
 
 pub struct DoppelgangerService {
     doppelganger_states: RwLock<HashMap<PublicKeyBytes, DoppelgangerState>>,
+    /// The number of epochs that newly registered validators must be checked for.
+    detection_epochs: u64,
     log: Logger,
 }
 
 impl DoppelgangerService {
-    pub fn new(log: Logger) -> Self {
+    pub fn new(detection_epochs: u64, log: Logger) -> Self {
         Self {
             doppelganger_states: <_>::default(),
+            detection_epochs,
             log,
         }
     }
@@ -370,6 +373,35 @@ impl DoppelgangerService {
             })
     }
 
+    /// Returns the number of epochs of detection remaining for `validator`, or `None` if the
+    /// validator is unknown to the doppelganger service.
+    pub fn remaining_epochs(&self, validator: &PublicKeyBytes) -> Option<u64> {
+        self.doppelganger_states
+            .read()
+            .get(validator)
+            .map(|state| state.remaining_epochs)
+    }
+
+    /// Immediately permit `validator` to sign, skipping any remaining doppelganger checks.
+    ///
+    /// This should only be used for validators which are known to have never been active on the
+    /// network (e.g., a freshly deposited validator), since no doppelganger can exist for them.
+    pub fn skip_detection(&self, validator: &PublicKeyBytes) -> Result<(), String> {
+        let mut states = self.doppelganger_states.write();
+        let state = states
+            .get_mut(validator)
+            .ok_or_else(|| format!("{:?} is unknown to the doppelganger service", validator))?;
+        state.remaining_epochs = 0;
+
+        info!(
+            self.log,
+            "Skipped doppelganger detection";
+            "validator" => ?validator,
+        );
+
+        Ok(())
+    }
+
     /// Register a new validator with the doppelganger service.
     ///
     /// Validators added during the genesis epoch will not have doppelganger protection applied to
@@ -389,15 +421,14 @@ impl DoppelgangerService {
         let remaining_epochs = if current_epoch <= genesis_epoch {
             // Disable doppelganger protection when the validator was initialized before genesis.
             //
-            // Without this, all validators would simply miss the first
-            // `DEFAULT_REMAINING_DETECTION_EPOCHS` epochs and then all start at the same time. This
-            // would be pointless.
+            // Without this, all validators would simply miss the first `self.detection_epochs`
+            // epochs and then all start at the same time. This would be pointless.
             //
             // The downside of this is that no validators have doppelganger protection at genesis.
             // It's an unfortunate trade-off.
             0
         } else {
-            DEFAULT_REMAINING_DETECTION_EPOCHS
+            self.detection_epochs
         };
 
         let state = DoppelgangerState {
@@ -723,7 +754,7 @@ mod test {
                 validators: (0..self.validator_count)
                     .map(|_| PublicKeyBytes::random_for_test(&mut rng))
                     .collect(),
-                doppelganger: DoppelgangerService::new(log),
+                doppelganger: DoppelgangerService::new(DEFAULT_REMAINING_DETECTION_EPOCHS, log),
                 slot_clock,
             }
         }
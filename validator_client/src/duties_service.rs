@@ -8,7 +8,7 @@
 
 mod sync;
 
-use crate::beacon_node_fallback::{BeaconNodeFallback, OfflineOnFailure, RequireSynced};
+use crate::beacon_node_fallback::{ApiTopic, BeaconNodeFallback, OfflineOnFailure, RequireSynced};
 use crate::http_metrics::metrics::{get_int_gauge, set_int_gauge, ATTESTATION_DUTY};
 use crate::{
     block_service::BlockServiceNotification,
@@ -17,7 +17,8 @@ use crate::{
 };
 use environment::RuntimeContext;
 use eth2::types::{
-    AttesterData, BeaconCommitteeSubscription, DutiesResponse, ProposerData, StateId, ValidatorId,
+    AttesterData, BeaconCommitteeSelection, BeaconCommitteeSubscription, DutiesResponse,
+    ProposerData, StateId, ValidatorId,
 };
 use futures::{stream, StreamExt};
 use parking_lot::RwLock;
@@ -47,11 +48,20 @@ const HISTORICAL_DUTIES_EPOCHS: u64 = 2;
 /// Compute attestation selection proofs this many slots before they are required.
 ///
 /// At start-up selection proofs will be computed with less lookahead out of necessity.
+///
+/// When running with `--distributed` a full epoch of lookahead is used instead, to allow time for
+/// the middleware to combine the proofs of all cluster members.
 const SELECTION_PROOF_SLOT_LOOKAHEAD: u64 = 8;
 
 /// Fraction of a slot at which selection proof signing should happen (2 means half way).
 const SELECTION_PROOF_SCHEDULE_DENOM: u32 = 2;
 
+/// The number of selection proofs to sign concurrently when using a remote signer.
+///
+/// Remote signing is bound by network latency rather than CPU, so signing serially can fail to
+/// keep up with large numbers of validators.
+const SELECTION_PROOF_REMOTE_SIGNING_CONCURRENCY: usize = 32;
+
 /// Minimum number of validators for which we auto-enable per-validator metrics.
 /// For validators greater than this value, we need to manually set the `enable-per-validator-metrics`
 /// flag in the cli to enable collection of per validator metrics.
@@ -67,6 +77,7 @@ pub enum Error {
     UnableToReadSlotClock,
     FailedToDownloadAttesters(String),
     FailedToProduceSelectionProof(ValidatorStoreError),
+    FailedToCombineSelectionProofs(String),
     InvalidModulo(ArithError),
     Arith(ArithError),
     SyncDutiesNotFound(u64),
@@ -98,6 +109,15 @@ impl DutyAndProof {
             .await
             .map_err(Error::FailedToProduceSelectionProof)?;
 
+        Self::from_selection_proof(duty, selection_proof, spec)
+    }
+
+    /// Instantiate `Self` from an already-computed selection proof.
+    pub fn from_selection_proof(
+        duty: AttesterData,
+        selection_proof: SelectionProof,
+        spec: &ChainSpec,
+    ) -> Result<Self, Error> {
         let selection_proof = selection_proof
             .is_aggregator(duty.committee_length as usize, spec)
             .map_err(Error::InvalidModulo)
@@ -148,6 +168,11 @@ pub struct DutiesService<T, E: EthSpec> {
     /// Provides HTTP access to remote beacon nodes.
     pub beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
     pub enable_high_validator_count_metrics: bool,
+    /// Whether the validator client is running behind distributed validator middleware.
+    pub distributed: bool,
+    /// The number of epochs before the start of a sync committee period at which subscriptions for
+    /// that period are sent to the beacon node.
+    pub sync_committee_subscription_lookahead_epochs: u64,
     pub context: RuntimeContext<E>,
     pub spec: ChainSpec,
 }
@@ -615,6 +640,7 @@ async fn poll_beacon_attesters<T: SlotClock + 'static, E: EthSpec>(
             .run(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                ApiTopic::Subscriptions,
                 |beacon_node| async move {
                     let _timer = metrics::start_timer_vec(
                         &metrics::DUTIES_SERVICE_TIMES,
@@ -898,7 +924,12 @@ async fn fill_in_selection_proofs<T: SlotClock + 'static, E: EthSpec>(
                 continue;
             };
 
-            let lookahead_slot = current_slot + SELECTION_PROOF_SLOT_LOOKAHEAD;
+            let lookahead = if duties_service.distributed {
+                E::slots_per_epoch()
+            } else {
+                SELECTION_PROOF_SLOT_LOOKAHEAD
+            };
+            let lookahead_slot = current_slot + lookahead;
 
             let mut relevant_duties = duties_by_slot.split_off(&lookahead_slot);
             std::mem::swap(&mut relevant_duties, &mut duties_by_slot);
@@ -914,18 +945,32 @@ async fn fill_in_selection_proofs<T: SlotClock + 'static, E: EthSpec>(
                 &[metrics::ATTESTATION_SELECTION_PROOFS],
             );
 
-            // Sign selection proofs (serially).
-            let duty_and_proof_results = stream::iter(relevant_duties.into_values().flatten())
-                .then(|duty| async {
-                    DutyAndProof::new_with_selection_proof(
-                        duty,
-                        &duties_service.validator_store,
-                        &duties_service.spec,
-                    )
+            // Sign selection proofs (serially, unless using a remote signer).
+            let duty_and_proof_results = if duties_service.distributed {
+                combine_distributed_selection_proofs(
+                    &duties_service,
+                    relevant_duties.into_values().flatten().collect(),
+                )
+                .await
+            } else {
+                let concurrency = if duties_service.validator_store.has_web3signer_validators() {
+                    SELECTION_PROOF_REMOTE_SIGNING_CONCURRENCY
+                } else {
+                    1
+                };
+                stream::iter(relevant_duties.into_values().flatten())
+                    .map(|duty| async {
+                        DutyAndProof::new_with_selection_proof(
+                            duty,
+                            &duties_service.validator_store,
+                            &duties_service.spec,
+                        )
+                        .await
+                    })
+                    .buffered(concurrency)
+                    .collect::<Vec<_>>()
                     .await
-                })
-                .collect::<Vec<_>>()
-                .await;
+            };
 
             // Add to attesters store.
             let mut attesters = duties_service.attesters.write();
@@ -993,6 +1038,107 @@ async fn fill_in_selection_proofs<T: SlotClock + 'static, E: EthSpec>(
     }
 }
 
+/// Sign (partial) selection proofs for `duties` and submit them to the distributed validator
+/// middleware, which returns the combined selection proofs of the cluster.
+///
+/// Aggregation duties are only decided using the combined proofs, since a partial selection proof
+/// says nothing about whether the distributed validator is an aggregator.
+async fn combine_distributed_selection_proofs<T: SlotClock + 'static, E: EthSpec>(
+    duties_service: &DutiesService<T, E>,
+    duties: Vec<AttesterData>,
+) -> Vec<Result<DutyAndProof, Error>> {
+    let mut results = Vec::with_capacity(duties.len());
+    let mut partial_proofs = Vec::with_capacity(duties.len());
+
+    for duty in duties {
+        match duties_service
+            .validator_store
+            .produce_selection_proof(duty.pubkey, duty.slot)
+            .await
+        {
+            Ok(selection_proof) => partial_proofs.push((duty, selection_proof)),
+            Err(e) => results.push(Err(Error::FailedToProduceSelectionProof(e))),
+        }
+    }
+
+    if partial_proofs.is_empty() {
+        return results;
+    }
+
+    let selections = partial_proofs
+        .iter()
+        .map(|(duty, selection_proof)| BeaconCommitteeSelection {
+            validator_index: duty.validator_index,
+            slot: duty.slot,
+            selection_proof: selection_proof.clone().into(),
+        })
+        .collect::<Vec<_>>();
+    let selections_ref = &selections;
+
+    let combined_selections = match duties_service
+        .beacon_nodes
+        .first_success(
+            RequireSynced::No,
+            OfflineOnFailure::Yes,
+            |beacon_node| async move {
+                beacon_node
+                    .post_validator_beacon_committee_selections(selections_ref)
+                    .await
+            },
+        )
+        .await
+    {
+        Ok(response) => response.data,
+        Err(e) => {
+            results.push(Err(Error::FailedToCombineSelectionProofs(e.to_string())));
+            return results;
+        }
+    };
+
+    results.extend(duties_with_combined_proofs(
+        partial_proofs,
+        combined_selections,
+        &duties_service.spec,
+    ));
+    results
+}
+
+/// Pair each duty with the selection proof that the middleware combined from the partial proofs of
+/// the cluster.
+///
+/// The middleware only returns a combined proof once a threshold of the cluster has submitted
+/// partial proofs, so a duty without one is an error rather than falling back to the partial proof.
+fn duties_with_combined_proofs(
+    partial_proofs: Vec<(AttesterData, SelectionProof)>,
+    combined_selections: Vec<BeaconCommitteeSelection>,
+    spec: &ChainSpec,
+) -> Vec<Result<DutyAndProof, Error>> {
+    let mut combined_proofs = combined_selections
+        .into_iter()
+        .map(|selection| {
+            (
+                (selection.validator_index, selection.slot),
+                selection.selection_proof,
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    partial_proofs
+        .into_iter()
+        .map(
+            |(duty, _)| match combined_proofs.remove(&(duty.validator_index, duty.slot)) {
+                Some(selection_proof) => {
+                    DutyAndProof::from_selection_proof(duty, selection_proof.into(), spec)
+                }
+                None => Err(Error::FailedToCombineSelectionProofs(format!(
+                    "no combined proof for validator {} at slot {}",
+                    duty.validator_index, duty.slot
+                ))),
+            },
+        )
+        .collect()
+}
+
 /// Download the proposer duties for the current epoch and store them in `duties_service.proposers`.
 /// If there are any proposer for this slot, send out a notification to the block proposers.
 ///
@@ -1186,3 +1332,129 @@ async fn notify_block_production_service<T: SlotClock + 'static, E: EthSpec>(
         };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{test_utils::generate_deterministic_keypair, Fork, MainnetEthSpec};
+
+    type E = MainnetEthSpec;
+
+    /// Large enough that only some selection proofs elect an aggregator.
+    const COMMITTEE_LENGTH: u64 = 512;
+
+    fn duty(validator_index: u64, slot: Slot) -> AttesterData {
+        AttesterData {
+            pubkey: generate_deterministic_keypair(validator_index as usize)
+                .pk
+                .compress(),
+            validator_index,
+            committees_at_slot: 1,
+            committee_index: 0,
+            committee_length: COMMITTEE_LENGTH,
+            validator_committee_index: validator_index,
+            slot,
+        }
+    }
+
+    /// Returns the first selection proof for `slot`, signed by a deterministic key, which does or
+    /// doesn't elect an aggregator.
+    fn selection_proof(slot: Slot, is_aggregator: bool, spec: &ChainSpec) -> SelectionProof {
+        (0..)
+            .map(|i| {
+                SelectionProof::new::<E>(
+                    slot,
+                    &generate_deterministic_keypair(i).sk,
+                    &Fork::default(),
+                    Hash256::zero(),
+                    spec,
+                )
+            })
+            .find(|proof| {
+                proof
+                    .is_aggregator(COMMITTEE_LENGTH as usize, spec)
+                    .unwrap()
+                    == is_aggregator
+            })
+            .unwrap()
+    }
+
+    fn combined_selection(duty: &AttesterData, proof: &SelectionProof) -> BeaconCommitteeSelection {
+        BeaconCommitteeSelection {
+            validator_index: duty.validator_index,
+            slot: duty.slot,
+            selection_proof: proof.clone().into(),
+        }
+    }
+
+    #[test]
+    fn combined_proofs_decide_aggregation() {
+        let spec = E::default_spec();
+        let slot = Slot::new(1);
+        let aggregator = selection_proof(slot, true, &spec);
+        let non_aggregator = selection_proof(slot, false, &spec);
+
+        // The partial proofs elect the opposite of the combined proofs, which must be used.
+        let duties = vec![duty(0, slot), duty(1, slot)];
+        let partial_proofs = vec![
+            (duties[0].clone(), non_aggregator.clone()),
+            (duties[1].clone(), aggregator.clone()),
+        ];
+        let combined_selections = vec![
+            combined_selection(&duties[1], &non_aggregator),
+            combined_selection(&duties[0], &aggregator),
+        ];
+
+        let results = duties_with_combined_proofs(partial_proofs, combined_selections, &spec);
+        assert_eq!(results.len(), 2);
+
+        let first = results[0].as_ref().unwrap();
+        assert_eq!(first.duty, duties[0]);
+        assert_eq!(first.selection_proof, Some(aggregator));
+
+        let second = results[1].as_ref().unwrap();
+        assert_eq!(second.duty, duties[1]);
+        assert_eq!(second.selection_proof, None);
+    }
+
+    #[test]
+    fn missing_combined_proof_fails() {
+        let spec = E::default_spec();
+        let slot = Slot::new(1);
+        let proof = selection_proof(slot, true, &spec);
+
+        // The middleware omits duties for which the cluster lacked a threshold of partial proofs.
+        let duties = vec![duty(0, slot), duty(1, slot), duty(0, slot + 1)];
+        let partial_proofs = duties
+            .iter()
+            .map(|duty| (duty.clone(), proof.clone()))
+            .collect();
+        let combined_selections = vec![combined_selection(&duties[1], &proof)];
+
+        let results = duties_with_combined_proofs(partial_proofs, combined_selections, &spec);
+        assert_eq!(results.len(), 3);
+        assert!(matches!(
+            results[0],
+            Err(Error::FailedToCombineSelectionProofs(_))
+        ));
+        assert_eq!(results[1].as_ref().unwrap().selection_proof, Some(proof));
+        assert!(matches!(
+            results[2],
+            Err(Error::FailedToCombineSelectionProofs(_))
+        ));
+    }
+
+    #[test]
+    fn no_combined_proofs() {
+        let spec = E::default_spec();
+        let slot = Slot::new(1);
+        let partial_proofs = vec![(duty(0, slot), selection_proof(slot, true, &spec))];
+
+        let results = duties_with_combined_proofs(partial_proofs, vec![], &spec);
+        assert_eq!(results.len(), 1);
+        assert!(matches!(
+            results[0],
+            Err(Error::FailedToCombineSelectionProofs(_))
+        ));
+    }
+}
@@ -14,7 +14,7 @@ use types::{
     ChainSpec, Epoch, EthSpec, PublicKeyBytes, Slot, SyncDuty, SyncSelectionProof, SyncSubnetId,
 };
 
-/// Number of epochs in advance to compute selection proofs.
+/// Minimum number of epochs in advance to compute selection proofs.
 pub const AGGREGATION_PRE_COMPUTE_EPOCHS: u64 = 2;
 
 /// Top-level data-structure containing sync duty information.
@@ -110,12 +110,13 @@ impl SyncDutiesMap {
         &self,
         committee_period: u64,
         current_epoch: Epoch,
+        pre_compute_epochs: u64,
         spec: &ChainSpec,
     ) -> (Epoch, Vec<(Epoch, SyncDuty)>) {
         let default_start_epoch =
             std::cmp::max(current_epoch, first_epoch_of_period(committee_period, spec));
         let pre_compute_epoch = std::cmp::min(
-            current_epoch + AGGREGATION_PRE_COMPUTE_EPOCHS,
+            current_epoch + pre_compute_epochs,
             last_epoch_of_period(committee_period, spec),
         );
 
@@ -170,12 +171,16 @@ impl SyncDutiesMap {
         )
     }
 
-    /// Get duties for all validators for the given `wall_clock_slot`.
+    /// Get duties for the validators in `local_pubkeys` for the given `wall_clock_slot`.
+    ///
+    /// Validators which are not in `local_pubkeys` (e.g. because they have been disabled since
+    /// their duties were fetched) are omitted.
     ///
     /// This is the entry-point for the sync committee service.
     pub fn get_duties_for_slot<E: EthSpec>(
         &self,
         wall_clock_slot: Slot,
+        local_pubkeys: &HashSet<PublicKeyBytes>,
         spec: &ChainSpec,
     ) -> Option<SlotDuties> {
         // Sync duties lag their assigned slot by 1
@@ -196,9 +201,11 @@ impl SyncDutiesMap {
             .validators
             .read()
             .values()
-            // Filter out non-members & failed subnet IDs.
+            // Filter out non-members, non-local validators & failed subnet IDs.
             .filter_map(|opt_duties| {
-                let duty = opt_duties.as_ref()?;
+                let duty = opt_duties
+                    .as_ref()
+                    .filter(|duty| local_pubkeys.contains(&duty.duty.pubkey))?;
                 let subnet_ids = duty.duty.subnet_ids::<E>().ok()?;
                 Some((duty, subnet_ids))
             })
@@ -270,6 +277,18 @@ fn last_epoch_of_period(sync_committee_period: u64, spec: &ChainSpec) -> Epoch {
     first_epoch_of_period(sync_committee_period + 1, spec) - 1
 }
 
+/// Number of epochs in advance to compute selection proofs.
+///
+/// Proofs for the next period are computed at least one epoch before subscriptions for that period
+/// are sent to the beacon node, so that aggregation duties are ready once the BN has joined the
+/// subnets.
+fn aggregation_pre_compute_epochs(subscription_lookahead_epochs: u64) -> u64 {
+    std::cmp::max(
+        AGGREGATION_PRE_COMPUTE_EPOCHS,
+        subscription_lookahead_epochs.saturating_add(1),
+    )
+}
+
 pub async fn poll_sync_committee_duties<T: SlotClock + 'static, E: EthSpec>(
     duties_service: &Arc<DutiesService<T, E>>,
 ) -> Result<(), Error> {
@@ -291,6 +310,11 @@ pub async fn poll_sync_committee_duties<T: SlotClock + 'static, E: EthSpec>(
 
     let current_sync_committee_period = current_epoch.sync_committee_period(spec)?;
     let next_sync_committee_period = current_sync_committee_period + 1;
+    let pre_compute_epochs =
+        aggregation_pre_compute_epochs(duties_service.sync_committee_subscription_lookahead_epochs);
+    let next_period_pre_compute_due = (current_epoch + pre_compute_epochs)
+        .sync_committee_period(spec)?
+        == next_sync_committee_period;
 
     // Collect *all* pubkeys, even those undergoing doppelganger protection.
     //
@@ -328,7 +352,12 @@ pub async fn poll_sync_committee_duties<T: SlotClock + 'static, E: EthSpec>(
 
     // Pre-compute aggregator selection proofs for the current period.
     let (current_pre_compute_epoch, new_pre_compute_duties) = sync_duties
-        .prepare_for_aggregator_pre_compute(current_sync_committee_period, current_epoch, spec);
+        .prepare_for_aggregator_pre_compute(
+            current_sync_committee_period,
+            current_epoch,
+            pre_compute_epochs,
+            spec,
+        );
 
     if !new_pre_compute_duties.is_empty() {
         let sub_duties_service = duties_service.clone();
@@ -348,8 +377,10 @@ pub async fn poll_sync_committee_duties<T: SlotClock + 'static, E: EthSpec>(
     }
 
     // If we're past the point in the current period where we should determine duties for the next
-    // period and they are not yet known, then poll.
-    if current_epoch.as_u64() % spec.epochs_per_sync_committee_period.as_u64() >= epoch_offset(spec)
+    // period (or need them to pre-compute selection proofs) and they are not yet known, then poll.
+    if (current_epoch.as_u64() % spec.epochs_per_sync_committee_period.as_u64()
+        >= epoch_offset(spec)
+        || next_period_pre_compute_due)
         && !sync_duties.all_duties_known(next_sync_committee_period, &local_indices)
     {
         poll_sync_committee_duties_for_period(
@@ -365,11 +396,14 @@ pub async fn poll_sync_committee_duties<T: SlotClock + 'static, E: EthSpec>(
     }
 
     // Pre-compute aggregator selection proofs for the next period.
-    if (current_epoch + AGGREGATION_PRE_COMPUTE_EPOCHS).sync_committee_period(spec)?
-        == next_sync_committee_period
-    {
+    if next_period_pre_compute_due {
         let (pre_compute_epoch, new_pre_compute_duties) = sync_duties
-            .prepare_for_aggregator_pre_compute(next_sync_committee_period, current_epoch, spec);
+            .prepare_for_aggregator_pre_compute(
+                next_sync_committee_period,
+                current_epoch,
+                pre_compute_epochs,
+                spec,
+            );
 
         if !new_pre_compute_duties.is_empty() {
             let sub_duties_service = duties_service.clone();
@@ -0,0 +1,161 @@
+//! Allows the validator client to finish signing and publishing duties which are already underway
+//! when a shutdown is requested, rather than abandoning them part-way through.
+use parking_lot::Mutex;
+use slashing_protection::SlashingDatabase;
+use slog::{error, info, warn, Logger};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::{timeout_at, Instant};
+
+#[derive(Default)]
+struct State {
+    stopping: bool,
+    in_flight: usize,
+}
+
+#[derive(Default)]
+struct Inner {
+    state: Mutex<State>,
+    notify: Notify,
+}
+
+/// Tracks the duties which are currently being signed or published.
+#[derive(Clone, Default)]
+pub struct InFlightDuties {
+    inner: Arc<Inner>,
+}
+
+impl InFlightDuties {
+    /// Register the start of a duty, returning a guard which marks it as complete when dropped.
+    ///
+    /// Returns `None` once shutdown has begun, in which case the duty must not be started.
+    pub fn start(&self) -> Option<DutyGuard> {
+        let mut state = self.inner.state.lock();
+        if state.stopping {
+            return None;
+        }
+        state.in_flight += 1;
+        Some(DutyGuard {
+            inner: self.inner.clone(),
+        })
+    }
+
+    /// The number of duties which are currently in flight.
+    pub fn count(&self) -> usize {
+        self.inner.state.lock().in_flight
+    }
+
+    /// Stop accepting new duties and wait at most `timeout` for the in-flight duties to complete.
+    ///
+    /// Returns the number of duties which were still in flight when the timeout was reached.
+    pub async fn stop_and_wait(&self, timeout: Duration) -> usize {
+        self.inner.state.lock().stopping = true;
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            // Create the future before checking the count so that a notification sent in between
+            // is not missed.
+            let notified = self.inner.notify.notified();
+            let in_flight = self.count();
+            if in_flight == 0 {
+                return 0;
+            }
+            if timeout_at(deadline, notified).await.is_err() {
+                return self.count();
+            }
+        }
+    }
+}
+
+/// Marks a duty as in flight for as long as it is held.
+pub struct DutyGuard {
+    inner: Arc<Inner>,
+}
+
+impl Drop for DutyGuard {
+    fn drop(&mut self) {
+        self.inner.state.lock().in_flight -= 1;
+        self.inner.notify.notify_waiters();
+    }
+}
+
+/// Performs the validator client's shutdown, once a shutdown has been requested but before the
+/// runtime is stopped.
+#[derive(Clone)]
+pub struct GracefulShutdown {
+    pub(crate) in_flight_duties: InFlightDuties,
+    pub(crate) slashing_protection: SlashingDatabase,
+    pub(crate) timeout: Duration,
+    pub(crate) log: Logger,
+}
+
+impl GracefulShutdown {
+    /// Stop accepting new duties, wait (bounded by the configured timeout) for in-flight duties
+    /// to be signed and published, then flush the slashing protection database.
+    pub async fn run(&self) {
+        let in_flight = self.in_flight_duties.count();
+        if in_flight > 0 {
+            info!(
+                self.log,
+                "Waiting for in-flight duties to complete";
+                "count" => in_flight,
+                "timeout_secs" => self.timeout.as_secs(),
+            );
+        }
+
+        let abandoned = self.in_flight_duties.stop_and_wait(self.timeout).await;
+        if abandoned > 0 {
+            warn!(
+                self.log,
+                "Shutting down with duties still in flight";
+                "count" => abandoned,
+                "msg" => "increase --graceful-shutdown-timeout to wait longer",
+            );
+        }
+
+        if let Err(e) = self.slashing_protection.flush() {
+            error!(
+                self.log,
+                "Failed to flush slashing protection database";
+                "error" => ?e,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn stop_and_wait_without_duties() {
+        let duties = InFlightDuties::default();
+        assert_eq!(duties.stop_and_wait(Duration::from_secs(1)).await, 0);
+        assert!(duties.start().is_none());
+    }
+
+    #[tokio::test]
+    async fn stop_and_wait_for_duty() {
+        let duties = InFlightDuties::default();
+        let guard = duties.start().unwrap();
+        assert_eq!(duties.count(), 1);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            drop(guard);
+        });
+
+        assert_eq!(duties.stop_and_wait(Duration::from_secs(5)).await, 0);
+        assert_eq!(duties.count(), 0);
+    }
+
+    #[tokio::test]
+    async fn stop_and_wait_times_out() {
+        let duties = InFlightDuties::default();
+        let _guard = duties.start().unwrap();
+
+        assert_eq!(duties.stop_and_wait(Duration::from_millis(50)).await, 1);
+        assert!(duties.start().is_none());
+    }
+}
@@ -1,9 +1,10 @@
 use eth2::lighthouse_vc::{PK_LEN, SECRET_PREFIX as PK_PREFIX};
 use filesystem::create_with_600_perms;
 use libsecp256k1::{Message, PublicKey, SecretKey};
-use rand::thread_rng;
+use rand::{thread_rng, Rng};
 use ring::digest::{digest, SHA256};
-use std::fs;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use warp::Filter;
 
@@ -23,6 +24,21 @@ pub const SK_LEN: usize = 32;
 /// value in a public forum.
 pub const PK_FILENAME: &str = "api-token.txt";
 
+/// The name of the file which stores the read-only API tokens, one per line.
+///
+/// Read-only tokens may only be used with `GET` endpoints, they cannot be used to modify the
+/// validator client (e.g., import or delete keys).
+pub const READ_ONLY_TOKENS_FILENAME: &str = "api-token-readonly.txt";
+
+/// The prefix for generated read-only tokens.
+///
+/// This must not start with `SECRET_PREFIX`, otherwise clients will attempt to parse read-only
+/// tokens as a public key.
+pub const READ_ONLY_TOKEN_PREFIX: &str = "readonly-token-";
+
+/// Length of a generated read-only token, in bytes.
+pub const READ_ONLY_TOKEN_LEN: usize = 32;
+
 /// Contains a `secp256k1` keypair that is saved-to/loaded-from disk on instantiation. The keypair
 /// is used for authorization/authentication for requests/responses on the HTTP API.
 ///
@@ -35,10 +51,15 @@ pub const PK_FILENAME: &str = "api-token.txt";
 ///  The aforementioned scheme was first defined here:
 ///
 ///  https://github.com/sigp/lighthouse/issues/1269#issuecomment-649879855
+///
+/// Additionally, any number of read-only tokens may be stored in `READ_ONLY_TOKENS_FILENAME`.
+/// Requests authorized with a read-only token are restricted to `GET` endpoints.
 pub struct ApiSecret {
     pk: PublicKey,
     sk: SecretKey,
     pk_path: PathBuf,
+    read_only_tokens: Vec<String>,
+    read_only_tokens_path: PathBuf,
 }
 
 impl ApiSecret {
@@ -148,7 +169,70 @@ impl ApiSecret {
             ));
         }
 
-        Ok(Self { pk, sk, pk_path })
+        let read_only_tokens_path = dir.as_ref().join(READ_ONLY_TOKENS_FILENAME);
+        let read_only_tokens = if read_only_tokens_path.exists() {
+            fs::read_to_string(&read_only_tokens_path)
+                .map_err(|e| format!("cannot read {}: {}", READ_ONLY_TOKENS_FILENAME, e))?
+                .lines()
+                .map(str::trim)
+                .filter(|token| !token.is_empty())
+                .map(String::from)
+                .collect()
+        } else {
+            vec![]
+        };
+
+        Ok(Self {
+            pk,
+            sk,
+            pk_path,
+            read_only_tokens,
+            read_only_tokens_path,
+        })
+    }
+
+    /// Delete the keypair and all read-only tokens in `dir` and generate a new keypair.
+    ///
+    /// All previously issued API tokens will be rejected after rotation.
+    pub fn rotate<P: AsRef<Path>>(dir: P) -> Result<Self, String> {
+        for filename in [SK_FILENAME, PK_FILENAME, READ_ONLY_TOKENS_FILENAME] {
+            let path = dir.as_ref().join(filename);
+            if path.exists() {
+                fs::remove_file(&path)
+                    .map_err(|e| format!("unable to remove {}: {}", filename, e))?;
+            }
+        }
+
+        Self::create_or_open(dir)
+    }
+
+    /// Generate a new random read-only token, persisting it to `READ_ONLY_TOKENS_FILENAME`.
+    pub fn generate_read_only_token(&mut self) -> Result<String, String> {
+        let bytes: [u8; READ_ONLY_TOKEN_LEN] = thread_rng().gen();
+        let token = format!(
+            "{}{}",
+            READ_ONLY_TOKEN_PREFIX,
+            serde_utils::hex::encode(bytes)
+        );
+
+        if !self.read_only_tokens_path.exists() {
+            create_with_600_perms(&self.read_only_tokens_path, &[]).map_err(|e| {
+                format!(
+                    "Unable to create file with permissions for {:?}: {:?}",
+                    self.read_only_tokens_path, e
+                )
+            })?;
+        }
+
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(&self.read_only_tokens_path)
+            .map_err(|e| format!("cannot open {}: {}", READ_ONLY_TOKENS_FILENAME, e))?;
+        writeln!(file, "{}", token)
+            .map_err(|e| format!("cannot write {}: {}", READ_ONLY_TOKENS_FILENAME, e))?;
+
+        self.read_only_tokens.push(token.clone());
+        Ok(token)
     }
 
     /// Returns the public key of `self` as a 0x-prefixed hex string.
@@ -166,6 +250,11 @@ impl ApiSecret {
         self.pk_path.clone()
     }
 
+    /// Returns the path for the read-only API tokens file.
+    pub fn read_only_tokens_path(&self) -> PathBuf {
+        self.read_only_tokens_path.clone()
+    }
+
     /// Returns the values of the `Authorization` header which indicate a valid incoming HTTP
     /// request.
     ///
@@ -179,10 +268,33 @@ impl ApiSecret {
         ]
     }
 
+    /// Returns the values of the `Authorization` header which indicate a valid incoming HTTP
+    /// request to a read-only endpoint.
+    ///
+    /// This includes the full-access API token as well as all read-only tokens.
+    fn read_only_auth_header_values(&self) -> Vec<String> {
+        let mut values = self.auth_header_values();
+        values.extend(
+            self.read_only_tokens
+                .iter()
+                .map(|token| format!("Bearer {}", token)),
+        );
+        values
+    }
+
     /// Returns a `warp` header which filters out request that have a missing or inaccurate
     /// `Authorization` header.
     pub fn authorization_header_filter(&self) -> warp::filters::BoxedFilter<()> {
-        let expected = self.auth_header_values();
+        Self::header_filter(self.auth_header_values())
+    }
+
+    /// Returns a `warp` header which filters out requests that have a missing `Authorization`
+    /// header or one which contains neither the API token nor a read-only token.
+    pub fn read_only_authorization_header_filter(&self) -> warp::filters::BoxedFilter<()> {
+        Self::header_filter(self.read_only_auth_header_values())
+    }
+
+    fn header_filter(expected: Vec<String>) -> warp::filters::BoxedFilter<()> {
         warp::any()
             .map(move || expected.clone())
             .and(warp::filters::header::header("Authorization"))
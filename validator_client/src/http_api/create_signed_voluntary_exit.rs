@@ -1,3 +1,4 @@
+use crate::beacon_node_fallback::{BeaconNodeFallback, OfflineOnFailure, RequireSynced};
 use crate::validator_store::ValidatorStore;
 use bls::{PublicKey, PublicKeyBytes};
 use slog::{info, Logger};
@@ -63,6 +64,41 @@ pub async fn create_signed_voluntary_exit<T: 'static + SlotClock + Clone, E: Eth
     Ok(signed_voluntary_exit)
 }
 
+/// Publishes `signed_voluntary_exit` to the beacon node's operation pool, from where it will be
+/// gossiped to the network.
+pub async fn publish_signed_voluntary_exit<T: 'static + SlotClock + Clone, E: EthSpec>(
+    signed_voluntary_exit: &SignedVoluntaryExit,
+    beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
+    log: Logger,
+) -> Result<(), warp::Rejection> {
+    beacon_nodes
+        .first_success(
+            RequireSynced::Yes,
+            OfflineOnFailure::No,
+            |beacon_node| async move {
+                beacon_node
+                    .post_beacon_pool_voluntary_exits(signed_voluntary_exit)
+                    .await
+            },
+        )
+        .await
+        .map_err(|e| {
+            warp_utils::reject::custom_server_error(format!(
+                "Failed to publish voluntary exit: {}",
+                e
+            ))
+        })?;
+
+    info!(
+        log,
+        "Published voluntary exit";
+        "validator_index" => signed_voluntary_exit.message.validator_index,
+        "epoch" => signed_voluntary_exit.message.epoch
+    );
+
+    Ok(())
+}
+
 /// Calculates the current epoch from the genesis time and current time.
 fn get_current_epoch<T: 'static + SlotClock + Clone, E: EthSpec>(slot_clock: T) -> Option<Epoch> {
     slot_clock.now().map(|s| s.epoch(E::slots_per_epoch()))
@@ -0,0 +1,105 @@
+//! Endpoints for inspecting and overriding doppelganger protection.
+use crate::beacon_node_fallback::{BeaconNodeFallback, OfflineOnFailure, RequireSynced};
+use crate::doppelganger_service::DoppelgangerStatus;
+use crate::ValidatorStore;
+use eth2::lighthouse_vc::types::ValidatorDoppelgangerData;
+use eth2::types::{StateId, ValidatorId, ValidatorStatus};
+use slot_clock::SlotClock;
+use std::sync::Arc;
+use types::{EthSpec, PublicKeyBytes};
+use warp::Rejection;
+use warp_utils::reject::{custom_bad_request, custom_server_error};
+
+pub fn list<T: SlotClock + 'static, E: EthSpec>(
+    validator_store: Arc<ValidatorStore<T, E>>,
+) -> Result<Vec<ValidatorDoppelgangerData>, Rejection> {
+    if !validator_store.doppelganger_protection_enabled() {
+        return Err(custom_bad_request(
+            "Doppelganger protection is not enabled".to_string(),
+        ));
+    }
+
+    Ok(validator_store
+        .doppelganger_statuses()
+        .into_iter()
+        .map(|status| {
+            let (pubkey, signing_enabled) = match status {
+                DoppelgangerStatus::SigningEnabled(pubkey) => (pubkey, true),
+                DoppelgangerStatus::SigningDisabled(pubkey)
+                | DoppelgangerStatus::UnknownToDoppelganger(pubkey) => (pubkey, false),
+            };
+            ValidatorDoppelgangerData {
+                pubkey,
+                signing_enabled,
+                remaining_epochs: validator_store.doppelganger_remaining_epochs(&pubkey),
+            }
+        })
+        .collect())
+}
+
+/// Skip doppelganger detection for each of `pubkeys`.
+///
+/// Detection is only skipped if the beacon node confirms that the validator has never been active,
+/// i.e. it is either absent from the head state or still pending activation. Such a validator
+/// cannot have attested, so there is nothing for doppelganger protection to detect.
+pub async fn skip<T: SlotClock + 'static, E: EthSpec>(
+    pubkeys: Vec<PublicKeyBytes>,
+    validator_store: Arc<ValidatorStore<T, E>>,
+    beacon_nodes: Option<Arc<BeaconNodeFallback<T, E>>>,
+) -> Result<(), Rejection> {
+    if !validator_store.doppelganger_protection_enabled() {
+        return Err(custom_bad_request(
+            "Doppelganger protection is not enabled".to_string(),
+        ));
+    }
+
+    let beacon_nodes = beacon_nodes.ok_or_else(|| {
+        custom_server_error("No beacon node is available to check validator status".to_string())
+    })?;
+
+    for pubkey in &pubkeys {
+        if !validator_store.has_validator(pubkey) {
+            return Err(custom_bad_request(format!(
+                "{} is disabled or not managed by this validator client",
+                pubkey.as_hex_string()
+            )));
+        }
+
+        let validator_id = &ValidatorId::PublicKey(*pubkey);
+        let validator = beacon_nodes
+            .first_success(
+                RequireSynced::Yes,
+                OfflineOnFailure::No,
+                |beacon_node| async move {
+                    beacon_node
+                        .get_beacon_states_validator_id(StateId::Head, validator_id)
+                        .await
+                },
+            )
+            .await
+            .map_err(|e| custom_server_error(format!("Failed to get validator status: {}", e)))?;
+
+        if let Some(validator) = validator {
+            let status = validator.data.status;
+            if !matches!(
+                status,
+                ValidatorStatus::PendingInitialized | ValidatorStatus::PendingQueued
+            ) {
+                return Err(custom_bad_request(format!(
+                    "{} may have been active on the network (status: {:?}), doppelganger \
+                    detection cannot be skipped",
+                    pubkey.as_hex_string(),
+                    status
+                )));
+            }
+        }
+    }
+
+    for pubkey in &pubkeys {
+        validator_store
+            .skip_doppelganger_detection(pubkey)
+            .map_err(custom_server_error)?;
+    }
+
+    Ok(())
+}
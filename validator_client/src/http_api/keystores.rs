@@ -10,10 +10,13 @@ use eth2::lighthouse_vc::std_types::{
     ListKeystoresResponse, SingleKeystoreResponse, Status,
 };
 use eth2_keystore::Keystore;
+use filesystem::create_with_600_perms;
+use slashing_protection::{interchange::Interchange, SLASHING_PROTECTION_BACKUP_PREFIX};
 use slog::{info, warn, Logger};
 use slot_clock::SlotClock;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 use task_executor::TaskExecutor;
 use tokio::runtime::Handle;
 use types::{EthSpec, PublicKeyBytes};
@@ -223,6 +226,19 @@ pub fn delete<T: SlotClock + 'static, E: EthSpec>(
     let initialized_validators_rwlock = validator_store.initialized_validators();
     let mut initialized_validators = initialized_validators_rwlock.write();
 
+    // Export the slashing protection data, disabling the keys so that they can't sign anything
+    // which would be missing from the export. The data is written to disk before any keys are
+    // disabled or deleted, so that it can't be lost (e.g. if the response is never received) and
+    // the keys keep signing if it can't be written.
+    let validators_dir = initialized_validators.validators_dir().to_path_buf();
+    let slashing_protection = validator_store
+        .export_slashing_protection_for_keys(&request.pubkeys, |slashing_protection| {
+            backup_slashing_protection(&validators_dir, slashing_protection, &log)
+        })
+        .map_err(|e| {
+            custom_server_error(format!("error exporting slashing protection: {:?}", e))
+        })?;
+
     let mut statuses = request
         .pubkeys
         .iter()
@@ -255,13 +271,6 @@ pub fn delete<T: SlotClock + 'static, E: EthSpec>(
             .map_err(|e| custom_server_error(format!("unable to update key cache: {:?}", e)))?;
     }
 
-    // Export the slashing protection data.
-    let slashing_protection = validator_store
-        .export_slashing_protection_for_keys(&request.pubkeys)
-        .map_err(|e| {
-            custom_server_error(format!("error exporting slashing protection: {:?}", e))
-        })?;
-
     // Update stasuses based on availability of slashing protection data.
     for (pubkey, status) in request.pubkeys.iter().zip(statuses.iter_mut()) {
         if status.status == DeleteKeystoreStatus::NotFound
@@ -280,6 +289,45 @@ pub fn delete<T: SlotClock + 'static, E: EthSpec>(
     })
 }
 
+/// Write `slashing_protection` to a new timestamped file in the `validators_dir`, so that the
+/// slashing protection history of deleted keys is never destroyed.
+///
+/// Nothing is written if `slashing_protection` contains no data.
+pub fn backup_slashing_protection(
+    validators_dir: &Path,
+    slashing_protection: &Interchange,
+    log: &Logger,
+) -> Result<(), String> {
+    if slashing_protection.is_empty() {
+        return Ok(());
+    }
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| format!("unable to read system time: {:?}", e))?
+        .as_millis();
+    let path = validators_dir.join(format!(
+        "{}{}.json",
+        SLASHING_PROTECTION_BACKUP_PREFIX, timestamp
+    ));
+
+    let mut json = vec![];
+    slashing_protection
+        .write_to(&mut json)
+        .map_err(|e| format!("unable to serialize slashing protection: {:?}", e))?;
+    create_with_600_perms(&path, &json)
+        .map_err(|e| format!("unable to write slashing protection backup: {:?}", e))?;
+
+    info!(
+        log,
+        "Backed up slashing protection data";
+        "path" => ?path,
+        "count" => slashing_protection.len(),
+    );
+
+    Ok(())
+}
+
 fn delete_single_keystore(
     pubkey_bytes: &PublicKeyBytes,
     initialized_validators: &mut InitializedValidators,
@@ -1,11 +1,17 @@
 mod api_secret;
 mod create_signed_voluntary_exit;
 mod create_validator;
+mod doppelganger;
 mod keystores;
 mod remotekeys;
 mod tests;
 
-use crate::http_api::create_signed_voluntary_exit::create_signed_voluntary_exit;
+pub mod test_utils;
+
+use crate::beacon_node_fallback::BeaconNodeFallback;
+use crate::http_api::create_signed_voluntary_exit::{
+    create_signed_voluntary_exit, publish_signed_voluntary_exit,
+};
 use crate::{determine_graffiti, GraffitiFile, ValidatorStore};
 use account_utils::{
     mnemonic_from_phrase,
@@ -14,7 +20,7 @@ use account_utils::{
 pub use api_secret::ApiSecret;
 use create_validator::{create_validators_mnemonic, create_validators_web3signer};
 use eth2::lighthouse_vc::{
-    std_types::{AuthResponse, GetFeeRecipientResponse, GetGasLimitResponse},
+    std_types::{AuthResponse, GetFeeRecipientResponse, GetGasLimitResponse, GetGraffitiResponse},
     types::{self as api_types, GenericResponse, Graffiti, PublicKey, PublicKeyBytes},
 };
 use lighthouse_version::version_with_platform;
@@ -28,6 +34,7 @@ use std::future::Future;
 use std::marker::PhantomData;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
+use std::pin::Pin;
 use std::sync::Arc;
 use sysinfo::{System, SystemExt};
 use system_health::observe_system_health_vc;
@@ -78,9 +85,20 @@ pub struct Context<T: SlotClock, E: EthSpec> {
     pub log: Logger,
     pub sse_logging_components: Option<SSELoggingComponents>,
     pub slot_clock: T,
+    pub beacon_nodes: Option<Arc<BeaconNodeFallback<T, E>>>,
     pub _phantom: PhantomData<E>,
 }
 
+/// A custom type which allows for both unsecured and TLS-enabled HTTP servers.
+type HttpServer = (SocketAddr, Pin<Box<dyn Future<Output = ()> + Send>>);
+
+/// Configuration used when serving the HTTP server over TLS.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct TlsConfig {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
 /// Configuration for the HTTP server.
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -88,6 +106,11 @@ pub struct Config {
     pub listen_addr: IpAddr,
     pub listen_port: u16,
     pub allow_origin: Option<String>,
+    pub tls_config: Option<TlsConfig>,
+    /// Generate a new API token on startup, revoking all existing tokens.
+    pub rotate_api_token: bool,
+    /// Generate an additional read-only API token on startup.
+    pub generate_read_only_token: bool,
 }
 
 impl Default for Config {
@@ -97,6 +120,9 @@ impl Default for Config {
             listen_addr: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
             listen_port: 5062,
             allow_origin: None,
+            tls_config: None,
+            rotate_api_token: false,
+            generate_read_only_token: false,
         }
     }
 }
@@ -119,7 +145,7 @@ impl Default for Config {
 pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
     ctx: Arc<Context<T, E>>,
     shutdown: impl Future<Output = ()> + Send + Sync + 'static,
-) -> Result<(SocketAddr, impl Future<Output = ()>), Error> {
+) -> Result<HttpServer, Error> {
     let config = &ctx.config;
     let log = ctx.log.clone();
 
@@ -145,6 +171,8 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
     }
 
     let authorization_header_filter = ctx.api_secret.authorization_header_filter();
+    let read_only_authorization_header_filter =
+        ctx.api_secret.read_only_authorization_header_filter();
     let mut api_token_path = ctx.api_secret.api_token_path();
 
     // Attempt to convert the path to an absolute path, but don't error if it fails.
@@ -199,6 +227,9 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
     let inner_slot_clock = ctx.slot_clock.clone();
     let slot_clock_filter = warp::any().map(move || inner_slot_clock.clone());
 
+    let inner_beacon_nodes = ctx.beacon_nodes.clone();
+    let beacon_nodes_filter = warp::any().map(move || inner_beacon_nodes.clone());
+
     let inner_spec = Arc::new(ctx.spec.clone());
     let spec_filter = warp::any().map(move || inner_spec.clone());
 
@@ -301,6 +332,19 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             })
         });
 
+    // GET lighthouse/validators/doppelganger
+    let get_lighthouse_validators_doppelganger = warp::path("lighthouse")
+        .and(warp::path("validators"))
+        .and(warp::path("doppelganger"))
+        .and(warp::path::end())
+        .and(validator_store_filter.clone())
+        .and(signer.clone())
+        .and_then(|validator_store: Arc<ValidatorStore<T, E>>, signer| {
+            blocking_signed_json_task(signer, move || {
+                doppelganger::list(validator_store).map(api_types::GenericResponse::from)
+            })
+        });
+
     // GET lighthouse/validators/{validator_pubkey}
     let get_lighthouse_validators_pubkey = warp::path("lighthouse")
         .and(warp::path("validators"))
@@ -352,13 +396,43 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             })
         });
 
+    // GET lighthouse/ui/fallback_health
+    let get_lighthouse_ui_fallback_health = warp::path("lighthouse")
+        .and(warp::path("ui"))
+        .and(warp::path("fallback_health"))
+        .and(warp::path::end())
+        .and(beacon_nodes_filter.clone())
+        .and(signer.clone())
+        .and(task_executor_filter.clone())
+        .and_then(
+            |beacon_nodes: Option<Arc<BeaconNodeFallback<T, E>>>,
+             signer,
+             task_executor: TaskExecutor| {
+                blocking_signed_json_task(signer, move || {
+                    let beacon_nodes = match beacon_nodes {
+                        Some(beacon_nodes) => beacon_nodes,
+                        None => return Ok(api_types::GenericResponse::from(vec![])),
+                    };
+                    if let Some(handle) = task_executor.handle() {
+                        Ok(api_types::GenericResponse::from(
+                            handle.block_on(beacon_nodes.health()),
+                        ))
+                    } else {
+                        Err(warp_utils::reject::custom_server_error(
+                            "Lighthouse shutting down".into(),
+                        ))
+                    }
+                })
+            },
+        );
+
     let get_lighthouse_ui_graffiti = warp::path("lighthouse")
         .and(warp::path("ui"))
         .and(warp::path("graffiti"))
         .and(warp::path::end())
         .and(validator_store_filter.clone())
         .and(graffiti_file_filter.clone())
-        .and(graffiti_flag_filter)
+        .and(graffiti_flag_filter.clone())
         .and(signer.clone())
         .and(log_filter.clone())
         .and_then(
@@ -583,6 +657,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                                 suggested_fee_recipient: web3signer.suggested_fee_recipient,
                                 gas_limit: web3signer.gas_limit,
                                 builder_proposals: web3signer.builder_proposals,
+                                builder_boost_factor: None,
                                 description: web3signer.description,
                                 signing_definition: SigningDefinition::Web3Signer(
                                     Web3SignerDefinition {
@@ -592,6 +667,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                                         client_identity_path: web3signer.client_identity_path,
                                         client_identity_password: web3signer
                                             .client_identity_password,
+                                        certificate_fingerprint: web3signer.certificate_fingerprint,
                                     },
                                 ),
                             })
@@ -610,6 +686,39 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             },
         );
 
+    // POST lighthouse/validators/doppelganger/skip
+    let post_lighthouse_validators_doppelganger_skip = warp::path("lighthouse")
+        .and(warp::path("validators"))
+        .and(warp::path("doppelganger"))
+        .and(warp::path("skip"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(validator_store_filter.clone())
+        .and(beacon_nodes_filter)
+        .and(signer.clone())
+        .and(task_executor_filter.clone())
+        .and_then(
+            |body: api_types::SkipDoppelgangerRequest,
+             validator_store: Arc<ValidatorStore<T, E>>,
+             beacon_nodes: Option<Arc<BeaconNodeFallback<T, E>>>,
+             signer,
+             task_executor: TaskExecutor| {
+                blocking_signed_json_task(signer, move || {
+                    if let Some(handle) = task_executor.handle() {
+                        handle.block_on(doppelganger::skip(
+                            body.pubkeys,
+                            validator_store,
+                            beacon_nodes,
+                        ))
+                    } else {
+                        Err(warp_utils::reject::custom_server_error(
+                            "Lighthouse shutting down".into(),
+                        ))
+                    }
+                })
+            },
+        );
+
     // PATCH lighthouse/validators/{validator_pubkey}
     let patch_validators = warp::path("lighthouse")
         .and(warp::path("validators"))
@@ -617,7 +726,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
         .and(warp::path::end())
         .and(warp::body::json())
         .and(validator_store_filter.clone())
-        .and(graffiti_file_filter)
+        .and(graffiti_file_filter.clone())
         .and(signer.clone())
         .and(task_executor_filter.clone())
         .and_then(
@@ -655,7 +764,15 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                         {
                             Ok(())
                         }
-                        (Some(_), _) => {
+                        (Some(is_enabled), _) => {
+                            // Prepare a disabled validator to resume duties before it is
+                            // enabled, so that it never signs without protection.
+                            if !is_enabled && body.enabled == Some(true) {
+                                validator_store
+                                    .register_validator_for_enable(validator_pubkey.compress())
+                                    .map_err(warp_utils::reject::custom_server_error)?;
+                            }
+
                             if let Some(handle) = task_executor.handle() {
                                 handle
                                     .block_on(
@@ -929,6 +1046,150 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
         )
         .map(|reply| warp::reply::with_status(reply, warp::http::StatusCode::NO_CONTENT));
 
+    // GET /eth/v1/validator/{pubkey}/graffiti
+    let get_graffiti = eth_v1
+        .and(warp::path("validator"))
+        .and(warp::path::param::<PublicKey>())
+        .and(warp::path("graffiti"))
+        .and(warp::path::end())
+        .and(validator_store_filter.clone())
+        .and(graffiti_file_filter.clone())
+        .and(graffiti_flag_filter)
+        .and(signer.clone())
+        .and(log_filter.clone())
+        .and_then(
+            |validator_pubkey: PublicKey,
+             validator_store: Arc<ValidatorStore<T, E>>,
+             graffiti_file: Option<GraffitiFile>,
+             graffiti_flag: Option<Graffiti>,
+             signer,
+             log| {
+                blocking_signed_json_task(signer, move || {
+                    let pubkey = PublicKeyBytes::from(&validator_pubkey);
+                    let initialized_validators_rw_lock = validator_store.initialized_validators();
+                    let initialized_validators = initialized_validators_rw_lock.read();
+                    if initialized_validators
+                        .is_enabled(&validator_pubkey)
+                        .is_none()
+                    {
+                        return Err(warp_utils::reject::custom_not_found(format!(
+                            "no validator found with pubkey {:?}",
+                            validator_pubkey
+                        )));
+                    }
+                    // Blocks are proposed with empty graffiti when none is configured.
+                    let graffiti = determine_graffiti(
+                        &pubkey,
+                        &log,
+                        graffiti_file,
+                        initialized_validators.graffiti(&pubkey),
+                        graffiti_flag,
+                    )
+                    .unwrap_or_default();
+                    Ok(GenericResponse::from(GetGraffitiResponse {
+                        pubkey,
+                        graffiti,
+                    }))
+                })
+            },
+        );
+
+    // POST /eth/v1/validator/{pubkey}/graffiti
+    let post_graffiti = eth_v1
+        .and(warp::path("validator"))
+        .and(warp::path::param::<PublicKey>())
+        .and(warp::path("graffiti"))
+        .and(warp::body::json())
+        .and(warp::path::end())
+        .and(validator_store_filter.clone())
+        .and(graffiti_file_filter.clone())
+        .and(signer.clone())
+        .and_then(
+            |validator_pubkey: PublicKey,
+             request: api_types::UpdateGraffitiRequest,
+             validator_store: Arc<ValidatorStore<T, E>>,
+             graffiti_file: Option<GraffitiFile>,
+             signer| {
+                blocking_signed_json_task(signer, move || {
+                    if graffiti_file.is_some() {
+                        return Err(warp_utils::reject::custom_bad_request(
+                            "Unable to update graffiti as the \"--graffiti-file\" flag is set"
+                                .to_string(),
+                        ));
+                    }
+                    if validator_store
+                        .initialized_validators()
+                        .read()
+                        .is_enabled(&validator_pubkey)
+                        .is_none()
+                    {
+                        return Err(warp_utils::reject::custom_not_found(format!(
+                            "no validator found with pubkey {:?}",
+                            validator_pubkey
+                        )));
+                    }
+                    validator_store
+                        .initialized_validators()
+                        .write()
+                        .set_validator_graffiti(&validator_pubkey, request.graffiti)
+                        .map_err(|e| {
+                            warp_utils::reject::custom_server_error(format!(
+                                "Error persisting graffiti: {:?}",
+                                e
+                            ))
+                        })
+                })
+            },
+        )
+        .map(|reply| warp::reply::with_status(reply, warp::http::StatusCode::ACCEPTED));
+
+    // DELETE /eth/v1/validator/{pubkey}/graffiti
+    let delete_graffiti = eth_v1
+        .and(warp::path("validator"))
+        .and(warp::path::param::<PublicKey>())
+        .and(warp::path("graffiti"))
+        .and(warp::path::end())
+        .and(validator_store_filter.clone())
+        .and(graffiti_file_filter)
+        .and(signer.clone())
+        .and_then(
+            |validator_pubkey: PublicKey,
+             validator_store: Arc<ValidatorStore<T, E>>,
+             graffiti_file: Option<GraffitiFile>,
+             signer| {
+                blocking_signed_json_task(signer, move || {
+                    if graffiti_file.is_some() {
+                        return Err(warp_utils::reject::custom_bad_request(
+                            "Unable to delete graffiti as the \"--graffiti-file\" flag is set"
+                                .to_string(),
+                        ));
+                    }
+                    if validator_store
+                        .initialized_validators()
+                        .read()
+                        .is_enabled(&validator_pubkey)
+                        .is_none()
+                    {
+                        return Err(warp_utils::reject::custom_not_found(format!(
+                            "no validator found with pubkey {:?}",
+                            validator_pubkey
+                        )));
+                    }
+                    validator_store
+                        .initialized_validators()
+                        .write()
+                        .delete_validator_graffiti(&validator_pubkey)
+                        .map_err(|e| {
+                            warp_utils::reject::custom_server_error(format!(
+                                "Error persisting graffiti removal: {:?}",
+                                e
+                            ))
+                        })
+                })
+            },
+        )
+        .map(|reply| warp::reply::with_status(reply, warp::http::StatusCode::NO_CONTENT));
+
     // POST /eth/v1/validator/{pubkey}/voluntary_exit
     let post_validators_voluntary_exits = eth_v1
         .and(warp::path("validator"))
@@ -938,6 +1199,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
         .and(warp::path::end())
         .and(validator_store_filter.clone())
         .and(slot_clock_filter)
+        .and(beacon_nodes_filter.clone())
         .and(log_filter.clone())
         .and(signer.clone())
         .and(task_executor_filter.clone())
@@ -946,7 +1208,8 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
              query: api_types::VoluntaryExitQuery,
              validator_store: Arc<ValidatorStore<T, E>>,
              slot_clock: T,
-             log,
+             beacon_nodes: Option<Arc<BeaconNodeFallback<T, E>>>,
+             log: Logger,
              signer,
              task_executor: TaskExecutor| {
                 blocking_signed_json_task(signer, move || {
@@ -957,8 +1220,20 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                                 query.epoch,
                                 validator_store,
                                 slot_clock,
+                                log.clone(),
+                            ))?;
+                        if query.broadcast {
+                            let beacon_nodes = beacon_nodes.ok_or_else(|| {
+                                warp_utils::reject::custom_server_error(
+                                    "No beacon node is available to broadcast the exit".to_string(),
+                                )
+                            })?;
+                            handle.block_on(publish_signed_voluntary_exit(
+                                &signed_voluntary_exit,
+                                beacon_nodes,
                                 log,
                             ))?;
+                        }
                         Ok(signed_voluntary_exit)
                     } else {
                         Err(warp_utils::reject::custom_server_error(
@@ -1084,43 +1359,53 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
         });
 
     let routes = warp::any()
-        .and(authorization_header_filter)
-        // Note: it is critical that the `authorization_header_filter` is applied to all routes.
-        // Keeping all the routes inside the following `and` is a reliable way to achieve this.
+        // Note: it is critical that an authorization filter is applied to all routes. `GET`
+        // routes accept read-only tokens, all other routes require the full API token. Keeping
+        // all the routes inside the following `and` is a reliable way to achieve this.
         //
         // When adding a route, don't forget to add it to the `routes_with_invalid_auth` tests!
         .and(
             warp::get()
+                .and(read_only_authorization_header_filter)
                 .and(
                     get_node_version
                         .or(get_lighthouse_health)
                         .or(get_lighthouse_spec)
                         .or(get_lighthouse_validators)
+                        .or(get_lighthouse_validators_doppelganger)
                         .or(get_lighthouse_validators_pubkey)
                         .or(get_lighthouse_ui_health)
+                        .or(get_lighthouse_ui_fallback_health)
                         .or(get_lighthouse_ui_graffiti)
                         .or(get_fee_recipient)
                         .or(get_gas_limit)
+                        .or(get_graffiti)
                         .or(get_std_keystores)
                         .or(get_std_remotekeys),
                 )
-                .or(warp::post().and(
-                    post_validators
-                        .or(post_validators_keystore)
-                        .or(post_validators_mnemonic)
-                        .or(post_validators_web3signer)
-                        .or(post_validators_voluntary_exits)
-                        .or(post_fee_recipient)
-                        .or(post_gas_limit)
-                        .or(post_std_keystores)
-                        .or(post_std_remotekeys),
-                ))
-                .or(warp::patch().and(patch_validators))
-                .or(warp::delete().and(
-                    delete_fee_recipient
-                        .or(delete_gas_limit)
-                        .or(delete_std_keystores)
-                        .or(delete_std_remotekeys),
+                .or(authorization_header_filter.and(
+                    warp::post()
+                        .and(
+                            post_validators
+                                .or(post_validators_keystore)
+                                .or(post_validators_mnemonic)
+                                .or(post_validators_web3signer)
+                                .or(post_lighthouse_validators_doppelganger_skip)
+                                .or(post_validators_voluntary_exits)
+                                .or(post_fee_recipient)
+                                .or(post_gas_limit)
+                                .or(post_graffiti)
+                                .or(post_std_keystores)
+                                .or(post_std_remotekeys),
+                        )
+                        .or(warp::patch().and(patch_validators))
+                        .or(warp::delete().and(
+                            delete_fee_recipient
+                                .or(delete_gas_limit)
+                                .or(delete_graffiti)
+                                .or(delete_std_keystores)
+                                .or(delete_std_remotekeys),
+                        )),
                 )),
         )
         // The auth route and logs  are the only routes that are allowed to be accessed without the API token.
@@ -1131,21 +1416,38 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
         .map(|reply| warp::reply::with_header(reply, "Server", &version_with_platform()))
         .with(cors_builder.build());
 
-    let (listening_socket, server) = warp::serve(routes).try_bind_with_graceful_shutdown(
-        SocketAddr::new(config.listen_addr, config.listen_port),
-        async {
-            shutdown.await;
-        },
-    )?;
+    let http_socket = SocketAddr::new(config.listen_addr, config.listen_port);
+    let http_server: HttpServer = match &config.tls_config {
+        Some(tls_config) => {
+            let (socket, server) = warp::serve(routes)
+                .tls()
+                .cert_path(&tls_config.cert)
+                .key_path(&tls_config.key)
+                .try_bind_with_graceful_shutdown(http_socket, async {
+                    shutdown.await;
+                })?;
+
+            info!(log, "HTTP API is being served over TLS";);
+
+            (socket, Box::pin(server))
+        }
+        None => {
+            let (socket, server) =
+                warp::serve(routes).try_bind_with_graceful_shutdown(http_socket, async {
+                    shutdown.await;
+                })?;
+            (socket, Box::pin(server))
+        }
+    };
 
     info!(
         log,
         "HTTP API started";
-        "listen_address" => listening_socket.to_string(),
+        "listen_address" => http_server.0.to_string(),
         "api_token_file" => ?api_token_path,
     );
 
-    Ok((listening_socket, server))
+    Ok(http_server)
 }
 
 /// Executes `func` in blocking tokio task (i.e., where long-running tasks are permitted).
@@ -1,4 +1,5 @@
 //! Implementation of the standard remotekey management API.
+use super::keystores::backup_slashing_protection;
 use crate::{initialized_validators::Error, InitializedValidators, ValidatorStore};
 use account_utils::validator_definitions::{
     SigningDefinition, ValidatorDefinition, Web3SignerDefinition,
@@ -125,6 +126,7 @@ fn import_single_remotekey<T: SlotClock + 'static, E: EthSpec>(
         suggested_fee_recipient: None,
         gas_limit: None,
         builder_proposals: None,
+        builder_boost_factor: None,
         description: String::from("Added by remotekey API"),
         signing_definition: SigningDefinition::Web3Signer(Web3SignerDefinition {
             url,
@@ -132,6 +134,7 @@ fn import_single_remotekey<T: SlotClock + 'static, E: EthSpec>(
             request_timeout_ms: None,
             client_identity_path: None,
             client_identity_password: None,
+            certificate_fingerprint: None,
         }),
     };
     handle
@@ -156,6 +159,20 @@ pub fn delete<T: SlotClock + 'static, E: EthSpec>(
     let initialized_validators_rwlock = validator_store.initialized_validators();
     let mut initialized_validators = initialized_validators_rwlock.write();
 
+    // Back up the slashing protection data before any keys are deleted. Unlike keystores, remote
+    // keys are left enabled in the slashing protection database.
+    let slashing_protection = validator_store
+        .export_slashing_protection_for_known_keys(&request.pubkeys)
+        .map_err(|e| {
+            custom_server_error(format!("error exporting slashing protection: {:?}", e))
+        })?;
+    backup_slashing_protection(
+        initialized_validators.validators_dir(),
+        &slashing_protection,
+        &log,
+    )
+    .map_err(custom_server_error)?;
+
     let statuses = request
         .pubkeys
         .iter()
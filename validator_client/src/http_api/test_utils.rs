@@ -0,0 +1,150 @@
+//! Provides a validator client HTTP API server backed by a temporary validator directory, for
+//! testing tools which drive the validator client via its HTTP API.
+use crate::doppelganger_service::{DoppelgangerService, DEFAULT_REMAINING_DETECTION_EPOCHS};
+use crate::{
+    http_api::{ApiSecret, Config as HttpConfig, Context},
+    initialized_validators::InitializedValidators,
+    Config, ValidatorDefinitions, ValidatorStore,
+};
+use eth2::lighthouse_vc::http_client::ValidatorClientHttpClient;
+use logging::test_logger;
+use sensitive_url::SensitiveUrl;
+use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
+use slot_clock::{SlotClock, TestingSlotClock};
+use std::marker::PhantomData;
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::PathBuf;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use task_executor::TaskExecutor;
+use tempfile::{tempdir, TempDir};
+use tokio::runtime::Runtime;
+use tokio::sync::oneshot;
+use types::{EthSpec, Graffiti, Hash256, MainnetEthSpec, Slot};
+
+type E = MainnetEthSpec;
+
+/// A running validator client HTTP API server with no validators.
+///
+/// The server is shut down when the `ApiTester` is dropped.
+pub struct ApiTester {
+    pub client: ValidatorClientHttpClient,
+    pub validator_store: Arc<ValidatorStore<TestingSlotClock, E>>,
+    pub url: SensitiveUrl,
+    pub api_token_path: PathBuf,
+    pub validator_dir: TempDir,
+    _secrets_dir: TempDir,
+    _server_shutdown: oneshot::Sender<()>,
+    _runtime_shutdown: exit_future::Signal,
+}
+
+impl ApiTester {
+    pub async fn new(runtime: Weak<Runtime>) -> Self {
+        let log = test_logger();
+
+        let validator_dir = tempdir().unwrap();
+        let secrets_dir = tempdir().unwrap();
+
+        let validator_defs = ValidatorDefinitions::open_or_create(validator_dir.path()).unwrap();
+
+        let initialized_validators = InitializedValidators::from_definitions(
+            validator_defs,
+            validator_dir.path().into(),
+            Default::default(),
+            log.clone(),
+        )
+        .await
+        .unwrap();
+
+        let api_secret = ApiSecret::create_or_open(validator_dir.path()).unwrap();
+        let api_pubkey = api_secret.api_token();
+        let api_token_path = api_secret.api_token_path();
+
+        let mut config = Config::default();
+        config.validator_dir = validator_dir.path().into();
+        config.secrets_dir = secrets_dir.path().into();
+
+        let spec = E::default_spec();
+
+        let slashing_db_path = config.validator_dir.join(SLASHING_PROTECTION_FILENAME);
+        let slashing_protection = SlashingDatabase::open_or_create(&slashing_db_path).unwrap();
+
+        let slot_clock =
+            TestingSlotClock::new(Slot::new(0), Duration::from_secs(0), Duration::from_secs(1));
+
+        let (runtime_shutdown, exit) = exit_future::signal();
+        let (shutdown_tx, _) = futures::channel::mpsc::channel(1);
+        let executor = TaskExecutor::new(runtime, exit, log.clone(), shutdown_tx);
+
+        let validator_store = Arc::new(ValidatorStore::<_, E>::new(
+            initialized_validators,
+            slashing_protection,
+            Hash256::repeat_byte(42),
+            spec.clone(),
+            Some(Arc::new(DoppelgangerService::new(
+                DEFAULT_REMAINING_DETECTION_EPOCHS,
+                log.clone(),
+            ))),
+            slot_clock.clone(),
+            &config,
+            executor.clone(),
+            log.clone(),
+        ));
+
+        validator_store
+            .register_all_in_doppelganger_protection_if_enabled()
+            .expect("Should attach doppelganger service");
+
+        let context = Arc::new(Context {
+            task_executor: executor,
+            api_secret,
+            validator_dir: Some(validator_dir.path().into()),
+            validator_store: Some(validator_store.clone()),
+            graffiti_file: None,
+            graffiti_flag: Some(Graffiti::default()),
+            spec,
+            config: HttpConfig {
+                enabled: true,
+                listen_addr: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+                listen_port: 0,
+                allow_origin: None,
+                tls_config: None,
+                rotate_api_token: false,
+                generate_read_only_token: false,
+            },
+            sse_logging_components: None,
+            log,
+            slot_clock,
+            beacon_nodes: None,
+            _phantom: PhantomData,
+        });
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let server_shutdown = async {
+            // It's not really interesting why this triggered, just that it happened.
+            let _ = shutdown_rx.await;
+        };
+        let (listening_socket, server) = super::serve(context, server_shutdown).unwrap();
+
+        tokio::spawn(async { server.await });
+
+        let url = SensitiveUrl::parse(&format!(
+            "http://{}:{}",
+            listening_socket.ip(),
+            listening_socket.port()
+        ))
+        .unwrap();
+
+        let client = ValidatorClientHttpClient::new(url.clone(), api_pubkey).unwrap();
+
+        Self {
+            client,
+            validator_store,
+            url,
+            api_token_path,
+            validator_dir,
+            _secrets_dir: secrets_dir,
+            _server_shutdown: shutdown_tx,
+            _runtime_shutdown: runtime_shutdown,
+        }
+    }
+}
@@ -3,7 +3,11 @@
 
 mod keystores;
 
-use crate::doppelganger_service::DoppelgangerService;
+use crate::beacon_node_fallback::{BeaconNodeFallback, CandidateBeaconNode};
+use crate::block_service::{get_unsigned_block, UnsignedBlock};
+use crate::doppelganger_service::{
+    DoppelgangerService, DoppelgangerStatus, DEFAULT_REMAINING_DETECTION_EPOCHS,
+};
 use crate::{
     http_api::{ApiSecret, Config as HttpConfig, Context},
     initialized_validators::InitializedValidators,
@@ -17,17 +21,18 @@ use deposit_contract::decode_eth1_tx_data;
 use eth2::{
     lighthouse_vc::{http_client::ValidatorClientHttpClient, types::*},
     types::ErrorMessage as ApiErrorMessage,
-    Error as ApiError,
+    BeaconNodeHttpClient, Error as ApiError, Timeouts,
 };
 use eth2_keystore::KeystoreBuilder;
 use logging::test_logger;
 use parking_lot::RwLock;
 use sensitive_url::SensitiveUrl;
 use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
+use slog::Logger;
 use slot_clock::{SlotClock, TestingSlotClock};
 use std::future::Future;
 use std::marker::PhantomData;
-use std::net::{IpAddr, Ipv4Addr};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
@@ -36,6 +41,7 @@ use tempfile::{tempdir, TempDir};
 use tokio::runtime::Runtime;
 use tokio::sync::oneshot;
 use types::graffiti::GraffitiString;
+use warp::Filter;
 
 const PASSWORD_BYTES: &[u8] = &[42, 50, 37];
 pub const TEST_DEFAULT_FEE_RECIPIENT: Address = Address::repeat_byte(42);
@@ -47,12 +53,112 @@ struct ApiTester {
     initialized_validators: Arc<RwLock<InitializedValidators>>,
     validator_store: Arc<ValidatorStore<TestingSlotClock, E>>,
     url: SensitiveUrl,
+    read_only_token: String,
     slot_clock: TestingSlotClock,
+    beacon_node: Option<MockBeaconNode>,
     _server_shutdown: oneshot::Sender<()>,
     _validator_dir: TempDir,
     _runtime_shutdown: exit_future::Signal,
 }
 
+/// A beacon node which serves just enough of the API to become a candidate of the validator
+/// client, recording the voluntary exits published to it. Every validator is absent from its head
+/// state, and blocks are only produced via `GET v2/validator/blocks/{slot}`.
+struct MockBeaconNode {
+    url: SensitiveUrl,
+    voluntary_exits: Arc<RwLock<Vec<SignedVoluntaryExit>>>,
+    _server_shutdown: oneshot::Sender<()>,
+}
+
+impl MockBeaconNode {
+    fn new(spec: &ChainSpec) -> Self {
+        let version = warp::path!("eth" / "v1" / "node" / "version")
+            .and(warp::get())
+            .map(|| {
+                warp::reply::json(&GenericResponse::from(VersionData {
+                    version: "mock_beacon_node".to_string(),
+                }))
+            });
+
+        let config = types::Config::from_chain_spec::<E>(spec);
+        let config_spec = warp::path!("eth" / "v1" / "config" / "spec")
+            .and(warp::get())
+            .map(move || warp::reply::json(&GenericResponse::from(config.clone())));
+
+        let voluntary_exits = Arc::new(RwLock::new(vec![]));
+        let inner_voluntary_exits = voluntary_exits.clone();
+        let post_voluntary_exits =
+            warp::path!("eth" / "v1" / "beacon" / "pool" / "voluntary_exits")
+                .and(warp::post())
+                .and(warp::body::json())
+                .map(move |exit: SignedVoluntaryExit| {
+                    inner_voluntary_exits.write().push(exit);
+                    warp::reply()
+                });
+
+        let get_validator =
+            warp::path!("eth" / "v1" / "beacon" / "states" / String / "validators" / String)
+                .and(warp::get())
+                .map(|_state_id: String, _validator_id: String| {
+                    warp::reply::with_status(warp::reply(), warp::http::StatusCode::NOT_FOUND)
+                });
+
+        let block_spec = spec.clone();
+        let get_block_v2 = warp::path!("eth" / "v2" / "validator" / "blocks" / u64)
+            .and(warp::get())
+            .map(move |slot: u64| {
+                let mut block = BeaconBlock::<E, FullPayload<E>>::empty(&block_spec);
+                *block.slot_mut() = Slot::new(slot);
+                warp::reply::json(&ForkVersionedResponse {
+                    version: Some(block.fork_name(&block_spec).unwrap()),
+                    data: block,
+                })
+            });
+
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let (listening_socket, server) = warp::serve(
+            version
+                .or(config_spec)
+                .or(post_voluntary_exits)
+                .or(get_validator)
+                .or(get_block_v2),
+        )
+        .bind_with_graceful_shutdown(
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0),
+            async {
+                let _ = shutdown_rx.await;
+            },
+        );
+        tokio::spawn(server);
+
+        let url = SensitiveUrl::parse(&format!(
+            "http://{}:{}",
+            listening_socket.ip(),
+            listening_socket.port()
+        ))
+        .unwrap();
+
+        Self {
+            url,
+            voluntary_exits,
+            _server_shutdown: shutdown_tx,
+        }
+    }
+
+    fn client(&self) -> BeaconNodeHttpClient {
+        BeaconNodeHttpClient::new(self.url.clone(), Timeouts::set_all(Duration::from_secs(1)))
+    }
+
+    fn fallback(&self, spec: &ChainSpec, log: Logger) -> BeaconNodeFallback<TestingSlotClock, E> {
+        BeaconNodeFallback::new(
+            vec![CandidateBeaconNode::new(self.client())],
+            vec![],
+            spec.clone(),
+            log,
+        )
+    }
+}
+
 // Builds a runtime to be used in the testing configuration.
 fn build_runtime() -> Arc<Runtime> {
     Arc::new(
@@ -65,6 +171,25 @@ fn build_runtime() -> Arc<Runtime> {
 
 impl ApiTester {
     pub async fn new(runtime: std::sync::Weak<Runtime>) -> Self {
+        Self::new_with_graffiti_flag(runtime, Some(Graffiti::default())).await
+    }
+
+    pub async fn new_with_graffiti_flag(
+        runtime: std::sync::Weak<Runtime>,
+        graffiti_flag: Option<Graffiti>,
+    ) -> Self {
+        Self::new_with_options(runtime, graffiti_flag, false).await
+    }
+
+    pub async fn new_with_mock_beacon_node(runtime: std::sync::Weak<Runtime>) -> Self {
+        Self::new_with_options(runtime, Some(Graffiti::default()), true).await
+    }
+
+    async fn new_with_options(
+        runtime: std::sync::Weak<Runtime>,
+        graffiti_flag: Option<Graffiti>,
+        mock_beacon_node: bool,
+    ) -> Self {
         let log = test_logger();
 
         let validator_dir = tempdir().unwrap();
@@ -75,13 +200,15 @@ impl ApiTester {
         let initialized_validators = InitializedValidators::from_definitions(
             validator_defs,
             validator_dir.path().into(),
+            Default::default(),
             log.clone(),
         )
         .await
         .unwrap();
 
-        let api_secret = ApiSecret::create_or_open(validator_dir.path()).unwrap();
+        let mut api_secret = ApiSecret::create_or_open(validator_dir.path()).unwrap();
         let api_pubkey = api_secret.api_token();
+        let read_only_token = api_secret.generate_read_only_token().unwrap();
 
         let mut config = Config::default();
         config.validator_dir = validator_dir.path().into();
@@ -109,7 +236,10 @@ impl ApiTester {
             slashing_protection,
             Hash256::repeat_byte(42),
             spec.clone(),
-            Some(Arc::new(DoppelgangerService::new(log.clone()))),
+            Some(Arc::new(DoppelgangerService::new(
+                DEFAULT_REMAINING_DETECTION_EPOCHS,
+                log.clone(),
+            ))),
             slot_clock.clone(),
             &config,
             executor.clone(),
@@ -122,23 +252,32 @@ impl ApiTester {
 
         let initialized_validators = validator_store.initialized_validators();
 
+        let beacon_node = mock_beacon_node.then(|| MockBeaconNode::new(&spec));
+        let beacon_nodes = beacon_node
+            .as_ref()
+            .map(|beacon_node| Arc::new(beacon_node.fallback(&spec, log.clone())));
+
         let context = Arc::new(Context {
             task_executor: executor,
             api_secret,
             validator_dir: Some(validator_dir.path().into()),
             validator_store: Some(validator_store.clone()),
             graffiti_file: None,
-            graffiti_flag: Some(Graffiti::default()),
+            graffiti_flag,
             spec: E::default_spec(),
             config: HttpConfig {
                 enabled: true,
                 listen_addr: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
                 listen_port: 0,
                 allow_origin: None,
+                tls_config: None,
+                rotate_api_token: false,
+                generate_read_only_token: false,
             },
             sse_logging_components: None,
             log,
             slot_clock: slot_clock.clone(),
+            beacon_nodes,
             _phantom: PhantomData,
         });
         let ctx = context.clone();
@@ -165,7 +304,9 @@ impl ApiTester {
             initialized_validators,
             validator_store,
             url,
+            read_only_token,
             slot_clock,
+            beacon_node,
             _server_shutdown: shutdown_tx,
             _validator_dir: validator_dir,
             _runtime_shutdown: runtime_shutdown,
@@ -209,6 +350,39 @@ impl ApiTester {
         self
     }
 
+    pub fn read_only_client(&self) -> ValidatorClientHttpClient {
+        ValidatorClientHttpClient::new(self.url.clone(), self.read_only_token.clone()).unwrap()
+    }
+
+    pub async fn test_read_only_token(self) -> Self {
+        let client = self.read_only_client();
+
+        // Read-only tokens may be used with `GET` endpoints.
+        client.get_lighthouse_version().await.unwrap();
+        client.get_lighthouse_validators().await.unwrap();
+        client.get_keystores().await.unwrap();
+
+        // Read-only tokens may not be used to modify the validator client.
+        match client
+            .patch_lighthouse_validators(&PublicKeyBytes::empty(), Some(false), None, None, None)
+            .await
+        {
+            Err(ApiError::ServerMessage(ApiErrorMessage { code: 403, .. })) => (),
+            other => panic!("expected authorized error, got {:?}", other),
+        }
+        match client
+            .delete_keystores(&DeleteKeystoresRequest {
+                pubkeys: vec![Keypair::random().pk.compress()],
+            })
+            .await
+        {
+            Err(ApiError::ServerMessage(ApiErrorMessage { code: 403, .. })) => (),
+            other => panic!("expected authorized error, got {:?}", other),
+        }
+
+        self
+    }
+
     pub fn invalidate_api_token(mut self) -> Self {
         self.client = self.invalid_token_client();
         self
@@ -485,6 +659,7 @@ impl ApiTester {
                     request_timeout_ms: None,
                     client_identity_path: None,
                     client_identity_password: None,
+                    certificate_fingerprint: None,
                 }
             })
             .collect();
@@ -515,7 +690,7 @@ impl ApiTester {
 
         let resp = self
             .client
-            .post_validator_voluntary_exit(&validator.voting_pubkey, maybe_epoch)
+            .post_validator_voluntary_exit(&validator.voting_pubkey, maybe_epoch, false)
             .await;
 
         assert!(resp.is_ok());
@@ -524,6 +699,195 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_doppelganger_statuses(self) -> Self {
+        let statuses = self
+            .client
+            .get_lighthouse_validators_doppelganger()
+            .await
+            .unwrap()
+            .data;
+
+        assert_eq!(statuses.len(), self.vals_enabled());
+        for status in statuses {
+            assert_eq!(status.signing_enabled, status.remaining_epochs == Some(0));
+        }
+
+        self
+    }
+
+    pub async fn test_skip_doppelganger(self, index: usize) -> Self {
+        let pubkey =
+            self.client.get_lighthouse_validators().await.unwrap().data[index].voting_pubkey;
+        let doppelganger_status = |statuses: Vec<ValidatorDoppelgangerData>| {
+            statuses
+                .into_iter()
+                .find(|status| status.pubkey == pubkey)
+                .unwrap()
+        };
+
+        let status = doppelganger_status(
+            self.client
+                .get_lighthouse_validators_doppelganger()
+                .await
+                .unwrap()
+                .data,
+        );
+        assert!(!status.signing_enabled);
+        assert_eq!(
+            status.remaining_epochs,
+            Some(DEFAULT_REMAINING_DETECTION_EPOCHS)
+        );
+        assert!(!self
+            .validator_store
+            .doppelganger_protection_allows_signing(pubkey));
+
+        // The mock beacon node doesn't know the validator, so it can't have been active.
+        self.client
+            .post_lighthouse_validators_doppelganger_skip(&SkipDoppelgangerRequest {
+                pubkeys: vec![pubkey],
+            })
+            .await
+            .unwrap();
+
+        let status = doppelganger_status(
+            self.client
+                .get_lighthouse_validators_doppelganger()
+                .await
+                .unwrap()
+                .data,
+        );
+        assert!(status.signing_enabled);
+        assert_eq!(status.remaining_epochs, Some(0));
+        assert!(self
+            .validator_store
+            .doppelganger_protection_allows_signing(pubkey));
+
+        self
+    }
+
+    pub async fn test_skip_doppelganger_without_beacon_node(self, index: usize) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+
+        // The tester has no beacon node with which to check the validator status.
+        let resp = self
+            .client
+            .post_lighthouse_validators_doppelganger_skip(&SkipDoppelgangerRequest {
+                pubkeys: vec![validator.voting_pubkey],
+            })
+            .await;
+
+        assert!(resp.is_err());
+
+        self
+    }
+
+    pub async fn test_broadcast_voluntary_exit_without_beacon_node(self, index: usize) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+        self.initialized_validators
+            .write()
+            .set_index(&validator.voting_pubkey, 0);
+
+        // The tester has no beacon node to publish the exit to.
+        let resp = self
+            .client
+            .post_validator_voluntary_exit(&validator.voting_pubkey, None, true)
+            .await;
+
+        assert!(resp.is_err());
+
+        self
+    }
+
+    pub async fn test_broadcast_voluntary_exit(self, index: usize, broadcast: bool) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+        self.initialized_validators
+            .write()
+            .set_index(&validator.voting_pubkey, 0);
+
+        let beacon_node = self
+            .beacon_node
+            .as_ref()
+            .expect("tester should have a mock beacon node");
+        let published_before = beacon_node.voluntary_exits.read().len();
+
+        let exit = self
+            .client
+            .post_validator_voluntary_exit(&validator.voting_pubkey, None, broadcast)
+            .await
+            .unwrap();
+
+        let published = beacon_node.voluntary_exits.read()[published_before..].to_vec();
+        if broadcast {
+            assert_eq!(published, vec![exit]);
+        } else {
+            assert!(published.is_empty());
+        }
+
+        self
+    }
+
+    pub async fn test_get_unsigned_block_v2_fallback(self) -> Self {
+        let beacon_node = self
+            .beacon_node
+            .as_ref()
+            .expect("tester should have a mock beacon node")
+            .client();
+        let slot = Slot::new(1);
+        let log = test_logger();
+
+        // The mock beacon node doesn't support block v3 or blinded blocks, so a full block should
+        // be produced via the v2 endpoint whether or not a builder is used.
+        for builder_proposals in [false, true] {
+            let block = get_unsigned_block::<E>(
+                &beacon_node,
+                slot,
+                &SignatureBytes::empty(),
+                None,
+                builder_proposals,
+                None,
+                false,
+                &log,
+            )
+            .await
+            .unwrap();
+            match block {
+                UnsignedBlock::Full(block) => assert_eq!(block.slot(), slot),
+                UnsignedBlock::Blinded(_) => panic!("expected a full block"),
+            }
+        }
+
+        // In distributed mode a full block must never be requested after the blinded block
+        // request fails.
+        let result = get_unsigned_block::<E>(
+            &beacon_node,
+            slot,
+            &SignatureBytes::empty(),
+            None,
+            true,
+            None,
+            true,
+            &log,
+        )
+        .await;
+        assert!(result.is_err());
+
+        self
+    }
+
+    pub async fn test_get_fallback_health_without_beacon_node(self) -> Self {
+        // The tester has no beacon nodes, so there is no health to report.
+        let health = self
+            .client
+            .get_lighthouse_ui_fallback_health()
+            .await
+            .unwrap()
+            .data;
+
+        assert!(health.is_empty());
+
+        self
+    }
+
     fn get_current_epoch(&self) -> Epoch {
         self.slot_clock
             .now()
@@ -573,6 +937,21 @@ impl ApiTester {
         self
     }
 
+    pub async fn assert_signing_enabled(self, index: usize, signing_enabled: bool) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+
+        let signing_pubkeys: Vec<_> = self
+            .validator_store
+            .voting_pubkeys(DoppelgangerStatus::only_safe);
+
+        assert_eq!(
+            signing_pubkeys.contains(&validator.voting_pubkey),
+            signing_enabled
+        );
+
+        self
+    }
+
     pub async fn set_gas_limit(self, index: usize, gas_limit: u64) -> Self {
         let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
 
@@ -714,6 +1093,18 @@ fn routes_with_invalid_auth() {
                     .await
             })
             .await
+            .test_with_invalid_auth(|client| async move {
+                client.get_lighthouse_validators_doppelganger().await
+            })
+            .await
+            .test_with_invalid_auth(|client| async move {
+                client
+                    .post_lighthouse_validators_doppelganger_skip(&SkipDoppelgangerRequest {
+                        pubkeys: vec![],
+                    })
+                    .await
+            })
+            .await
             .test_with_invalid_auth(|client| async move {
                 client
                     .post_lighthouse_validators(vec![ValidatorRequest {
@@ -801,6 +1192,18 @@ fn routes_with_invalid_auth() {
     });
 }
 
+#[test]
+fn read_only_token() {
+    let runtime = build_runtime();
+    let weak_runtime = Arc::downgrade(&runtime);
+    runtime.block_on(async {
+        ApiTester::new(weak_runtime)
+            .await
+            .test_read_only_token()
+            .await;
+    });
+}
+
 #[test]
 fn simple_getters() {
     let runtime = build_runtime();
@@ -875,6 +1278,101 @@ fn validator_exit() {
             .test_sign_voluntary_exits(0, None)
             .await
             .test_sign_voluntary_exits(0, Some(Epoch::new(256)))
+            .await
+            .test_broadcast_voluntary_exit_without_beacon_node(0)
+            .await;
+    });
+}
+
+#[test]
+fn validator_exit_broadcast() {
+    let runtime = build_runtime();
+    let weak_runtime = Arc::downgrade(&runtime);
+    runtime.block_on(async {
+        ApiTester::new_with_mock_beacon_node(weak_runtime)
+            .await
+            .create_hd_validators(HdValidatorScenario {
+                count: 2,
+                specify_mnemonic: false,
+                key_derivation_path_offset: 0,
+                disabled: vec![],
+            })
+            .await
+            .test_broadcast_voluntary_exit(0, false)
+            .await
+            .test_broadcast_voluntary_exit(0, true)
+            .await;
+    });
+}
+
+#[test]
+fn doppelganger_statuses() {
+    let runtime = build_runtime();
+    let weak_runtime = Arc::downgrade(&runtime);
+    runtime.block_on(async {
+        ApiTester::new(weak_runtime)
+            .await
+            .create_hd_validators(HdValidatorScenario {
+                count: 2,
+                specify_mnemonic: false,
+                key_derivation_path_offset: 0,
+                disabled: vec![],
+            })
+            .await
+            .assert_enabled_validators_count(2)
+            .test_get_doppelganger_statuses()
+            .await
+            .test_skip_doppelganger_without_beacon_node(0)
+            .await;
+    });
+}
+
+#[test]
+fn doppelganger_skip() {
+    let runtime = build_runtime();
+    let weak_runtime = Arc::downgrade(&runtime);
+    runtime.block_on(async {
+        let tester = ApiTester::new_with_mock_beacon_node(weak_runtime).await;
+        // Validators added during the genesis epoch aren't subject to doppelganger protection.
+        tester.slot_clock.set_slot(E::slots_per_epoch());
+
+        tester
+            .create_hd_validators(HdValidatorScenario {
+                count: 2,
+                specify_mnemonic: false,
+                key_derivation_path_offset: 0,
+                disabled: vec![],
+            })
+            .await
+            .test_get_doppelganger_statuses()
+            .await
+            .test_skip_doppelganger(0)
+            .await
+            .test_get_doppelganger_statuses()
+            .await;
+    });
+}
+
+#[test]
+fn unsigned_block_v2_fallback() {
+    let runtime = build_runtime();
+    let weak_runtime = Arc::downgrade(&runtime);
+    runtime.block_on(async {
+        ApiTester::new_with_mock_beacon_node(weak_runtime)
+            .await
+            .test_get_unsigned_block_v2_fallback()
+            .await;
+    });
+}
+
+#[test]
+fn fallback_health() {
+    let runtime = build_runtime();
+    let weak_runtime = Arc::downgrade(&runtime);
+    runtime.block_on(async {
+        ApiTester::new(weak_runtime)
+            .await
+            .test_get_fallback_health_without_beacon_node()
             .await;
     });
 }
@@ -906,6 +1404,37 @@ fn validator_enabling() {
     });
 }
 
+#[test]
+fn validator_enabling_updates_signing_pubkeys() {
+    let runtime = build_runtime();
+    let weak_runtime = Arc::downgrade(&runtime);
+    runtime.block_on(async {
+        ApiTester::new(weak_runtime)
+            .await
+            .create_hd_validators(HdValidatorScenario {
+                count: 2,
+                specify_mnemonic: false,
+                key_derivation_path_offset: 0,
+                disabled: vec![1],
+            })
+            .await
+            .assert_signing_enabled(0, true)
+            .await
+            .assert_signing_enabled(1, false)
+            .await
+            .set_validator_enabled(1, true)
+            .await
+            .assert_signing_enabled(1, true)
+            .await
+            .set_validator_enabled(0, false)
+            .await
+            .assert_signing_enabled(0, false)
+            .await
+            .assert_signing_enabled(1, true)
+            .await;
+    });
+}
+
 #[test]
 fn validator_gas_limit() {
     let runtime = build_runtime();
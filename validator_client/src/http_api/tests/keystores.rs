@@ -1,4 +1,4 @@
-use super::super::super::validator_store::DEFAULT_GAS_LIMIT;
+use super::super::super::validator_store::{ExportError, DEFAULT_GAS_LIMIT};
 use super::*;
 use account_utils::random_password_string;
 use bls::PublicKeyBytes;
@@ -10,9 +10,12 @@ use eth2::lighthouse_vc::{
 };
 use itertools::Itertools;
 use rand::{rngs::SmallRng, Rng, SeedableRng};
-use slashing_protection::interchange::{Interchange, InterchangeMetadata};
+use slashing_protection::{
+    interchange::{Interchange, InterchangeMetadata},
+    SLASHING_PROTECTION_BACKUP_PREFIX,
+};
 use std::{collections::HashMap, path::Path};
-use types::Address;
+use types::{Address, GRAFFITI_BYTES_LEN};
 
 fn new_keystore(password: ZeroizeString) -> Keystore {
     let keypair = Keypair::random();
@@ -48,6 +51,7 @@ fn web3signer_validator_with_pubkey(pubkey: PublicKey) -> Web3SignerValidatorReq
         request_timeout_ms: None,
         client_identity_path: None,
         client_identity_password: None,
+        certificate_fingerprint: None,
     }
 }
 
@@ -945,6 +949,184 @@ fn check_get_set_gas_limit() {
     })
 }
 
+#[test]
+fn check_get_set_graffiti() {
+    run_test(|tester: ApiTester| async move {
+        let _ = &tester;
+        let password = random_password_string();
+        let keystores = (0..3)
+            .map(|_| new_keystore(password.clone()))
+            .collect::<Vec<_>>();
+        let all_pubkeys = keystores.iter().map(keystore_pubkey).collect::<Vec<_>>();
+
+        let import_res = tester
+            .client
+            .post_keystores(&ImportKeystoresRequest {
+                keystores: keystores.clone(),
+                passwords: vec![password.clone(); keystores.len()],
+                slashing_protection: None,
+            })
+            .await
+            .unwrap();
+
+        // All keystores should be imported.
+        check_keystore_import_response(&import_res, all_imported(keystores.len()));
+
+        // Before setting anything, every graffiti should fall back to the (empty) process default.
+        for pubkey in &all_pubkeys {
+            let get_res = tester
+                .client
+                .get_graffiti(pubkey)
+                .await
+                .expect("should get graffiti");
+            assert_eq!(
+                get_res,
+                GetGraffitiResponse {
+                    pubkey: pubkey.clone(),
+                    graffiti: Graffiti::default(),
+                }
+            );
+        }
+
+        let graffiti_public_key_1 = GraffitiString::from_str("first").unwrap();
+        let graffiti_override = GraffitiString::from_str("override").unwrap();
+
+        // Set the graffiti for pubkey[1] using the API.
+        tester
+            .client
+            .post_graffiti(
+                &all_pubkeys[1],
+                &UpdateGraffitiRequest {
+                    graffiti: graffiti_public_key_1.clone(),
+                },
+            )
+            .await
+            .expect("should update graffiti");
+        for (i, pubkey) in all_pubkeys.iter().enumerate() {
+            let get_res = tester
+                .client
+                .get_graffiti(pubkey)
+                .await
+                .expect("should get graffiti");
+            let expected = if i == 1 {
+                graffiti_public_key_1.clone().into()
+            } else {
+                Graffiti::default()
+            };
+            assert_eq!(
+                get_res,
+                GetGraffitiResponse {
+                    pubkey: pubkey.clone(),
+                    graffiti: expected,
+                }
+            );
+        }
+
+        // Should be able to override the previous graffiti.
+        tester
+            .client
+            .post_graffiti(
+                &all_pubkeys[1],
+                &UpdateGraffitiRequest {
+                    graffiti: graffiti_override.clone(),
+                },
+            )
+            .await
+            .expect("should update graffiti");
+        let get_res = tester
+            .client
+            .get_graffiti(&all_pubkeys[1])
+            .await
+            .expect("should get graffiti");
+        assert_eq!(get_res.graffiti, graffiti_override.into());
+
+        // Delete the graffiti for pubkey[1] using the API.
+        tester
+            .client
+            .delete_graffiti(&all_pubkeys[1])
+            .await
+            .expect("should delete graffiti");
+        // Now everything should be back to the process default.
+        for pubkey in &all_pubkeys {
+            let get_res = tester
+                .client
+                .get_graffiti(pubkey)
+                .await
+                .expect("should get graffiti");
+            assert_eq!(get_res.graffiti, Graffiti::default());
+        }
+    })
+}
+
+#[test]
+fn get_graffiti_without_any_configured() {
+    let runtime = build_runtime();
+    let weak_runtime = Arc::downgrade(&runtime);
+    runtime.block_on(async {
+        let tester = ApiTester::new_with_graffiti_flag(weak_runtime, None).await;
+        let password = random_password_string();
+        let keystore = new_keystore(password.clone());
+        let pubkey = keystore_pubkey(&keystore);
+
+        let import_res = tester
+            .client
+            .post_keystores(&ImportKeystoresRequest {
+                keystores: vec![keystore],
+                passwords: vec![password],
+                slashing_protection: None,
+            })
+            .await
+            .unwrap();
+        check_keystore_import_response(&import_res, all_imported(1));
+
+        // With no graffiti flag, file or validator graffiti, the graffiti is empty.
+        let get_res = tester
+            .client
+            .get_graffiti(&pubkey)
+            .await
+            .expect("should get graffiti");
+        assert_eq!(
+            get_res,
+            GetGraffitiResponse {
+                pubkey,
+                graffiti: Graffiti::default(),
+            }
+        );
+    })
+}
+
+#[test]
+fn get_graffiti_not_utf8() {
+    let runtime = build_runtime();
+    let weak_runtime = Arc::downgrade(&runtime);
+    runtime.block_on(async {
+        // Graffiti set via the flag is arbitrary bytes, which need not be valid UTF-8.
+        let graffiti = Graffiti::from([0xff; GRAFFITI_BYTES_LEN]);
+        let tester = ApiTester::new_with_graffiti_flag(weak_runtime, Some(graffiti)).await;
+        let password = random_password_string();
+        let keystore = new_keystore(password.clone());
+        let pubkey = keystore_pubkey(&keystore);
+
+        let import_res = tester
+            .client
+            .post_keystores(&ImportKeystoresRequest {
+                keystores: vec![keystore],
+                passwords: vec![password],
+                slashing_protection: None,
+            })
+            .await
+            .unwrap();
+        check_keystore_import_response(&import_res, all_imported(1));
+
+        let get_res = tester
+            .client
+            .get_graffiti(&pubkey)
+            .await
+            .expect("should get graffiti");
+        assert_eq!(get_res, GetGraffitiResponse { pubkey, graffiti });
+    })
+}
+
 fn all_indices(count: usize) -> Vec<usize> {
     (0..count).collect()
 }
@@ -1398,6 +1580,111 @@ fn delete_then_reimport() {
     })
 }
 
+#[test]
+fn delete_keystores_backs_up_slashing_protection() {
+    run_test(|tester| async move {
+        let _ = &tester;
+        let password = random_password_string();
+        let keystores = (0..2)
+            .map(|_| new_keystore(password.clone()))
+            .collect::<Vec<_>>();
+
+        let import_res = tester
+            .client
+            .post_keystores(&ImportKeystoresRequest {
+                keystores: keystores.clone(),
+                passwords: vec![password.clone(); keystores.len()],
+                slashing_protection: None,
+            })
+            .await
+            .unwrap();
+        check_keystore_import_response(&import_res, all_imported(keystores.len()));
+
+        let delete_res = tester
+            .client
+            .delete_keystores(&DeleteKeystoresRequest {
+                pubkeys: keystores.iter().map(keystore_pubkey).collect(),
+            })
+            .await
+            .unwrap();
+        check_keystore_delete_response(&delete_res, all_deleted(keystores.len()));
+
+        // The slashing protection data returned should also have been written to the validators
+        // directory.
+        let validators_dir = tester
+            .initialized_validators
+            .read()
+            .validators_dir()
+            .to_path_buf();
+        let backups = std::fs::read_dir(validators_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| {
+                path.file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .starts_with(SLASHING_PROTECTION_BACKUP_PREFIX)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(backups.len(), 1);
+
+        let backup =
+            Interchange::from_json_reader(std::fs::File::open(&backups[0]).unwrap()).unwrap();
+        assert!(backup.equiv(&delete_res.slashing_protection));
+    })
+}
+
+#[test]
+fn delete_keystores_backup_failure_leaves_keys_enabled() {
+    run_test(|tester| async move {
+        let _ = &tester;
+        let password = random_password_string();
+        let keystore = new_keystore(password.clone());
+        let pubkey = keystore_pubkey(&keystore);
+
+        let import_res = tester
+            .client
+            .post_keystores(&ImportKeystoresRequest {
+                keystores: vec![keystore],
+                passwords: vec![password],
+                slashing_protection: None,
+            })
+            .await
+            .unwrap();
+        check_keystore_import_response(&import_res, all_imported(1));
+
+        // A failure to write the backup should abort the export without disabling the key.
+        let export_res = tester
+            .validator_store
+            .export_slashing_protection_for_keys(&[pubkey], |_| Err("backup failed".into()));
+        assert!(matches!(export_res, Err(ExportError::Backup(_))));
+
+        let mut attestation = make_attestation(0, 1);
+        tester
+            .validator_store
+            .sign_attestation(pubkey, 0, &mut attestation, Epoch::new(1))
+            .await
+            .unwrap();
+
+        // The key can still be deleted normally afterwards, and its export contains the
+        // attestation signed above.
+        let delete_res = tester
+            .client
+            .delete_keystores(&DeleteKeystoresRequest {
+                pubkeys: vec![pubkey],
+            })
+            .await
+            .unwrap();
+        check_keystore_delete_response(&delete_res, all_deleted(1));
+        assert_eq!(
+            delete_res.slashing_protection.data[0]
+                .signed_attestations
+                .len(),
+            1
+        );
+    })
+}
+
 #[test]
 fn get_empty_remotekeys() {
     run_test(|tester| async move {
@@ -1895,6 +2182,70 @@ fn delete_remotekeys_twice() {
     })
 }
 
+#[test]
+fn delete_remotekeys_backs_up_slashing_protection() {
+    run_test(|tester| async move {
+        let _ = &tester;
+
+        let remotekeys = (0..2)
+            .map(|_| new_remotekey_validator().1)
+            .collect::<Vec<_>>();
+
+        let import_res = tester
+            .client
+            .post_remotekeys(&ImportRemotekeysRequest {
+                remote_keys: remotekeys.clone(),
+            })
+            .await
+            .unwrap();
+        check_remotekey_import_response(
+            &import_res,
+            all_with_status(remotekeys.len(), ImportRemotekeyStatus::Imported),
+        );
+
+        let delete_res = tester
+            .client
+            .delete_remotekeys(&DeleteRemotekeysRequest {
+                pubkeys: remotekeys.iter().map(|k| k.pubkey).collect(),
+            })
+            .await
+            .unwrap();
+        check_remotekey_delete_response(
+            &delete_res,
+            all_with_status(remotekeys.len(), DeleteRemotekeyStatus::Deleted),
+        );
+
+        // The slashing protection data for the deleted keys should have been written to the
+        // validators directory.
+        let validators_dir = tester
+            .initialized_validators
+            .read()
+            .validators_dir()
+            .to_path_buf();
+        let backups = std::fs::read_dir(validators_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| {
+                path.file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .starts_with(SLASHING_PROTECTION_BACKUP_PREFIX)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(backups.len(), 1);
+
+        let backup =
+            Interchange::from_json_reader(std::fs::File::open(&backups[0]).unwrap()).unwrap();
+        assert_eq!(backup.len(), remotekeys.len());
+        for remotekey in &remotekeys {
+            assert!(backup
+                .data
+                .iter()
+                .any(|data| data.pubkey == remotekey.pubkey));
+        }
+    })
+}
+
 #[test]
 fn delete_nonexistent_remotekey() {
     run_test(|tester| async move {
@@ -1,8 +1,8 @@
 use super::Context;
 use malloc_utils::scrape_allocator_metrics;
 use slot_clock::SlotClock;
-use std::time::{SystemTime, UNIX_EPOCH};
-use types::EthSpec;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use types::{EthSpec, Slot};
 
 pub const SUCCESS: &str = "success";
 pub const SLASHABLE: &str = "slashable";
@@ -11,7 +11,6 @@ pub const UNREGISTERED: &str = "unregistered";
 pub const FULL_UPDATE: &str = "full_update";
 pub const BEACON_BLOCK: &str = "beacon_block";
 pub const BEACON_BLOCK_HTTP_GET: &str = "beacon_block_http_get";
-pub const BLINDED_BEACON_BLOCK_HTTP_GET: &str = "blinded_beacon_block_http_get";
 pub const BEACON_BLOCK_HTTP_POST: &str = "beacon_block_http_post";
 pub const BLINDED_BEACON_BLOCK_HTTP_POST: &str = "blinded_beacon_block_http_post";
 pub const ATTESTATIONS: &str = "attestations";
@@ -36,6 +35,14 @@ pub const ATTESTATION_SELECTION_PROOFS: &str = "attestation_selection_proofs";
 pub const SUBSCRIPTIONS: &str = "subscriptions";
 pub const LOCAL_KEYSTORE: &str = "local_keystore";
 pub const WEB3SIGNER: &str = "web3signer";
+pub const SYNC_COMMITTEE_MESSAGES: &str = "sync_committee_messages";
+pub const SYNC_COMMITTEE_CONTRIBUTIONS: &str = "sync_committee_contributions";
+pub const BEACON_NODE_ERROR: &str = "beacon_node_error";
+pub const SIGNER_TIMEOUT: &str = "signer_timeout";
+pub const SIGNER_ERROR: &str = "signer_error";
+pub const SLASHING_PROTECTION: &str = "slashing_protection";
+pub const DOPPELGANGER: &str = "doppelganger";
+pub const LATE: &str = "late";
 
 pub use lighthouse_metrics::*;
 
@@ -117,6 +124,14 @@ lazy_static::lazy_static! {
         "Duration to perform beacon block service tasks",
         &["task"]
     );
+    pub static ref BLOCK_PUBLISH_DELAY: Result<Histogram> = try_create_histogram(
+        "vc_beacon_block_publish_delay_seconds",
+        "Duration from the start of the slot until the block was published",
+    );
+    pub static ref BLOCK_PUBLISHED_LATE_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "vc_beacon_block_published_late_total",
+        "Count of blocks published after the attestation deadline",
+    );
     pub static ref PROPOSER_COUNT: Result<IntGaugeVec> = try_create_int_gauge_vec(
         "vc_beacon_block_proposer_count",
         "Number of beacon block proposers on this host",
@@ -183,6 +198,30 @@ lazy_static::lazy_static! {
         "Duration to obtain a signature for a block",
     );
 
+    /*
+     * Duty timing metrics
+     */
+    pub static ref DUTY_SIGNING_TIMES: Result<HistogramVec> = try_create_histogram_vec(
+        "vc_duty_signing_times_seconds",
+        "Duration to sign all messages for a duty",
+        &["duty"]
+    );
+    pub static ref DUTY_PUBLISH_TIMES: Result<HistogramVec> = try_create_histogram_vec(
+        "vc_duty_publish_times_seconds",
+        "Duration to publish all signed messages for a duty to the beacon node",
+        &["duty"]
+    );
+    pub static ref DUTY_LATENCY: Result<HistogramVec> = try_create_histogram_vec(
+        "vc_duty_latency_seconds",
+        "Duration from the start of the duty's slot until the duty was published",
+        &["duty"]
+    );
+    pub static ref DUTY_FAILURES_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "vc_duty_failures_total",
+        "Count of duties which were missed or published late, by cause",
+        &["duty", "cause"]
+    );
+
     pub static ref ATTESTATION_DUTY: Result<IntGaugeVec> = try_create_int_gauge_vec(
         "vc_attestation_duty_slot",
         "Attestation duty slot for all managed validators",
@@ -200,6 +239,32 @@ lazy_static::lazy_static! {
         "vc_beacon_node_latency_primary_endpoint",
         "Round-trip latency for the primary BN endpoint",
     );
+    pub static ref VC_BEACON_NODE_LATENCY_FAILURES: Result<IntCounterVec> = try_create_int_counter_vec(
+        "vc_beacon_node_latency_failures_total",
+        "Count of latency measurements to which a BN did not respond",
+        &["endpoint"]
+    );
+}
+
+/// Record the time from the start of `slot` until now as the end-to-end latency of `duty`.
+///
+/// The duty is counted as late in `DUTY_FAILURES_TOTAL` if the latency reached `deadline`.
+pub fn observe_duty_latency<T: SlotClock>(
+    slot_clock: &T,
+    duty: &str,
+    slot: Slot,
+    deadline: Duration,
+) -> Option<Duration> {
+    let latency = slot_clock
+        .now_duration()?
+        .checked_sub(slot_clock.start_of(slot)?)?;
+
+    observe_timer_vec(&DUTY_LATENCY, &[duty], latency);
+    if latency >= deadline {
+        inc_counter_vec(&DUTY_FAILURES_TOTAL, &[duty, LATE]);
+    }
+
+    Some(latency)
 }
 
 pub fn gather_prometheus_metrics<T: EthSpec>(
@@ -256,3 +321,57 @@ pub fn gather_prometheus_metrics<T: EthSpec>(
 
     String::from_utf8(buffer).map_err(|e| format!("Failed to encode prometheus info: {:?}", e))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slot_clock::ManualSlotClock;
+
+    const SLOT_DURATION: Duration = Duration::from_secs(12);
+
+    fn late_count(duty: &str) -> u64 {
+        get_int_counter(&DUTY_FAILURES_TOTAL, &[duty, LATE]).map_or(0, |counter| counter.get())
+    }
+
+    fn slot_clock_at(slot: Slot, into_slot: Duration) -> ManualSlotClock {
+        let slot_clock = ManualSlotClock::new(Slot::new(0), Duration::from_secs(0), SLOT_DURATION);
+        slot_clock.set_current_time(slot_clock.start_of(slot).unwrap() + into_slot);
+        slot_clock
+    }
+
+    #[test]
+    fn attestation_late_after_one_third_of_slot() {
+        let slot = Slot::new(4);
+        let deadline = slot_clock_at(slot, Duration::ZERO).unagg_attestation_production_delay();
+        assert_eq!(deadline, SLOT_DURATION / 3);
+
+        // Use a dedicated label so other tests can't interfere with the count.
+        let duty = "test_attestations";
+
+        let slot_clock = slot_clock_at(slot, Duration::from_secs(3));
+        assert_eq!(
+            observe_duty_latency(&slot_clock, duty, slot, deadline),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(late_count(duty), 0);
+
+        let slot_clock = slot_clock_at(slot, Duration::from_secs(5));
+        assert_eq!(
+            observe_duty_latency(&slot_clock, duty, slot, deadline),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(late_count(duty), 1);
+    }
+
+    #[test]
+    fn duty_latency_before_slot_start() {
+        let slot_clock = slot_clock_at(Slot::new(4), Duration::ZERO);
+        let duty = "test_early_duty";
+
+        assert_eq!(
+            observe_duty_latency(&slot_clock, duty, Slot::new(5), SLOT_DURATION),
+            None
+        );
+        assert_eq!(late_count(duty), 0);
+    }
+}
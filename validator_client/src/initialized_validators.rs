@@ -8,10 +8,10 @@
 
 use crate::signing_method::SigningMethod;
 use account_utils::{
-    read_password, read_password_from_user,
+    read_password_from_user,
     validator_definitions::{
-        self, SigningDefinition, ValidatorDefinition, ValidatorDefinitions, Web3SignerDefinition,
-        CONFIG_FILENAME,
+        self, read_voting_keystore_password, SigningDefinition, ValidatorDefinition,
+        ValidatorDefinitions, Web3SignerDefinition, CONFIG_FILENAME,
     },
     ZeroizeString,
 };
@@ -20,15 +20,18 @@ use lighthouse_metrics::set_gauge;
 use lockfile::{Lockfile, LockfileError};
 use parking_lot::{MappedMutexGuard, Mutex, MutexGuard};
 use reqwest::{Certificate, Client, Error as ReqwestError, Identity};
+use ring::digest::{digest, SHA256};
+use rustls::client::{ServerCertVerified, ServerCertVerifier};
+use rustls::ServerName;
 use slog::{debug, error, info, warn, Logger};
 use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
 use std::io::{self, Read};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 use types::graffiti::GraffitiString;
-use types::{Address, Graffiti, Keypair, PublicKey, PublicKeyBytes};
+use types::{Address, Graffiti, Hash256, Keypair, PublicKey, PublicKeyBytes};
 use url::{ParseError, Url};
 use validator_dir::Builder as ValidatorDirBuilder;
 
@@ -41,6 +44,9 @@ use crate::key_cache::KeyCache;
 /// that time is outside the synchronous assumptions of Eth2.
 const DEFAULT_REMOTE_SIGNER_REQUEST_TIMEOUT: Duration = Duration::from_secs(12);
 
+/// Default time for which an idle connection to a remote signer is kept open for re-use.
+pub const DEFAULT_WEB3SIGNER_IDLE_TIMEOUT: Duration = Duration::from_secs(20);
+
 // Use TTY instead of stdin to capture passwords from users.
 const USE_STDIN: bool = false;
 
@@ -96,6 +102,12 @@ pub enum Error {
     InvalidWeb3SignerClientIdentityCertificateFile(io::Error),
     InvalidWeb3SignerClientIdentityCertificate(ReqwestError),
     UnableToBuildWeb3SignerClient(ReqwestError),
+    InvalidWeb3SignerCertificateFingerprint(String),
+    /// A client certificate cannot be used with a pinned server certificate.
+    Web3SignerClientIdentityWithCertificateFingerprint,
+    /// A root certificate cannot be used with a pinned server certificate, since the pinned
+    /// certificate replaces chain verification entirely.
+    Web3SignerRootCertificateWithCertificateFingerprint,
     /// Unable to apply an action to a validator.
     InvalidActionOnValidator,
 }
@@ -113,6 +125,7 @@ pub struct InitializedValidator {
     suggested_fee_recipient: Option<Address>,
     gas_limit: Option<u64>,
     builder_proposals: Option<bool>,
+    builder_boost_factor: Option<u64>,
     /// The validators index in `state.validators`, to be updated by an external service.
     index: Option<u64>,
 }
@@ -145,6 +158,10 @@ impl InitializedValidator {
         self.builder_proposals
     }
 
+    pub fn get_builder_boost_factor(&self) -> Option<u64> {
+        self.builder_boost_factor
+    }
+
     pub fn get_index(&self) -> Option<u64> {
         self.index
     }
@@ -180,6 +197,7 @@ impl InitializedValidator {
         key_cache: &mut KeyCache,
         key_stores: &mut HashMap<PathBuf, Keystore>,
         web3_signer_client_map: &mut Option<HashMap<Web3SignerDefinition, Client>>,
+        web3_signer_pool: Web3SignerPoolConfig,
     ) -> Result<Self, Error> {
         if !def.enabled {
             return Err(Error::UnableToInitializeDisabledValidator);
@@ -204,6 +222,7 @@ impl InitializedValidator {
                 } else {
                     let keystore = voting_keystore.clone();
                     let keystore_path = voting_keystore_path.clone();
+                    let voting_public_key = def.voting_public_key.clone();
                     // Decoding a local keystore can take several seconds, therefore it's best
                     // to keep if off the core executor. This also has the fortunate effect of
                     // interrupting the potentially long-running task during shut down.
@@ -220,8 +239,9 @@ impl InitializedValidator {
                                 ),
                                 // If only the path is supplied, use the path.
                                 (Some(path), None) => {
-                                    let password = read_password(path)
-                                        .map_err(Error::UnableToReadVotingKeystorePassword)?;
+                                    let password =
+                                        read_voting_keystore_password(&path, &voting_public_key)
+                                            .map_err(Error::UnableToReadVotingKeystorePassword)?;
                                     let keypair = keystore
                                         .decrypt_keypair(password.as_bytes())
                                         .map_err(Error::UnableToDecryptKeystore)?;
@@ -273,6 +293,12 @@ impl InitializedValidator {
                     .map(Duration::from_millis)
                     .unwrap_or(DEFAULT_REMOTE_SIGNER_REQUEST_TIMEOUT);
 
+                let certificate_fingerprint = web3_signer
+                    .certificate_fingerprint
+                    .as_deref()
+                    .map(parse_certificate_fingerprint)
+                    .transpose()?;
+
                 // Check if a client has already been initialized for this remote signer url.
                 let http_client = if let Some(client_map) = web3_signer_client_map {
                     match client_map.get(&web3_signer) {
@@ -282,7 +308,9 @@ impl InitializedValidator {
                                 web3_signer.root_certificate_path.clone(),
                                 web3_signer.client_identity_path.clone(),
                                 web3_signer.client_identity_password.clone(),
+                                certificate_fingerprint,
                                 request_timeout,
+                                web3_signer_pool,
                             )?;
                             client_map.insert(web3_signer, client.clone());
                             client
@@ -296,7 +324,9 @@ impl InitializedValidator {
                         web3_signer.root_certificate_path.clone(),
                         web3_signer.client_identity_path.clone(),
                         web3_signer.client_identity_password.clone(),
+                        certificate_fingerprint,
                         request_timeout,
+                        web3_signer_pool,
                     )?;
                     new_web3_signer_client_map.insert(web3_signer, client.clone());
                     *web3_signer_client_map = Some(new_web3_signer_client_map);
@@ -317,6 +347,7 @@ impl InitializedValidator {
             suggested_fee_recipient: def.suggested_fee_recipient,
             gas_limit: def.gas_limit,
             builder_proposals: def.builder_proposals,
+            builder_boost_factor: def.builder_boost_factor,
             index: None,
         })
     }
@@ -354,6 +385,55 @@ pub fn load_pkcs12_identity<P: AsRef<Path>>(
         .map_err(Error::InvalidWeb3SignerClientIdentityCertificate)
 }
 
+/// Parse a hex-encoded SHA-256 certificate fingerprint.
+///
+/// An optional `0x` prefix and `:` separators (as output by `openssl x509 -fingerprint`) are
+/// permitted.
+fn parse_certificate_fingerprint(fingerprint: &str) -> Result<Hash256, Error> {
+    let hex_string = fingerprint.trim().trim_start_matches("0x").replace(':', "");
+    let bytes = hex::decode(hex_string)
+        .map_err(|e| Error::InvalidWeb3SignerCertificateFingerprint(format!("{:?}", e)))?;
+    if bytes.len() != Hash256::len_bytes() {
+        return Err(Error::InvalidWeb3SignerCertificateFingerprint(format!(
+            "expected {} bytes, got {}",
+            Hash256::len_bytes(),
+            bytes.len()
+        )));
+    }
+    Ok(Hash256::from_slice(&bytes))
+}
+
+/// Verifies that the server presents the certificate with the pinned SHA-256 fingerprint, in
+/// place of checking it against root certificates.
+///
+/// This runs during the TLS handshake, so nothing is sent to a server presenting any other
+/// certificate.
+struct PinnedCertificateVerifier {
+    fingerprint: Hash256,
+}
+
+impl ServerCertVerifier for PinnedCertificateVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let fingerprint = Hash256::from_slice(digest(&SHA256, &end_entity.0).as_ref());
+        if fingerprint == self.fingerprint {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General(format!(
+                "certificate fingerprint {:?} does not match pinned fingerprint {:?}",
+                fingerprint, self.fingerprint
+            )))
+        }
+    }
+}
+
 fn build_web3_signer_url(base_url: &str, voting_public_key: &PublicKey) -> Result<Url, ParseError> {
     Url::parse(base_url)?.join(&format!("api/v1/eth2/sign/{}", voting_public_key))
 }
@@ -362,10 +442,34 @@ fn build_web3_signer_client(
     root_certificate_path: Option<PathBuf>,
     client_identity_path: Option<PathBuf>,
     client_identity_password: Option<String>,
+    certificate_fingerprint: Option<Hash256>,
     request_timeout: Duration,
+    pool: Web3SignerPoolConfig,
 ) -> Result<Client, Error> {
+    // A pinned certificate is checked with a custom TLS config, which would silently discard any
+    // root certificate or client identity, so reject those combinations outright.
+    if certificate_fingerprint.is_some() {
+        if root_certificate_path.is_some() {
+            return Err(Error::Web3SignerRootCertificateWithCertificateFingerprint);
+        }
+        if client_identity_path.is_some() {
+            return Err(Error::Web3SignerClientIdentityWithCertificateFingerprint);
+        }
+    }
+
+    // Connections are pooled and re-used across requests (and validators sharing the same remote
+    // signer), so keep them alive rather than re-establishing them for each signature.
     let builder = Client::builder().timeout(request_timeout);
 
+    let builder = match (pool.idle_timeout, pool.max_idle_connections) {
+        // Pooling is disabled, close each connection once its request completes.
+        (None, _) => builder.pool_max_idle_per_host(0),
+        (Some(idle_timeout), None) => builder.pool_idle_timeout(idle_timeout),
+        (Some(idle_timeout), Some(max_idle_connections)) => builder
+            .pool_idle_timeout(idle_timeout)
+            .pool_max_idle_per_host(max_idle_connections),
+    };
+
     let builder = if let Some(path) = root_certificate_path {
         let certificate = load_pem_certificate(path)?;
         builder.add_root_certificate(certificate)
@@ -373,6 +477,16 @@ fn build_web3_signer_client(
         builder
     };
 
+    let builder = if let Some(fingerprint) = certificate_fingerprint {
+        let tls_config = rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(Arc::new(PinnedCertificateVerifier { fingerprint }))
+            .with_no_client_auth();
+        builder.use_preconfigured_tls(tls_config)
+    } else {
+        builder
+    };
+
     let builder = if let Some(path) = client_identity_path {
         let identity = load_pkcs12_identity(
             path,
@@ -433,6 +547,24 @@ fn unlock_keystore_via_stdin_password(
 /// A set of `InitializedValidator` objects which is initialized from a list of
 /// `ValidatorDefinition`. The `ValidatorDefinition` file is maintained as `self` is modified.
 ///
+/// Connection pooling settings for the HTTP clients used to communicate with remote signers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Web3SignerPoolConfig {
+    /// How long an idle connection is kept in the pool. `None` disables pooling.
+    pub idle_timeout: Option<Duration>,
+    /// The maximum number of idle connections to keep open to each remote signer.
+    pub max_idle_connections: Option<usize>,
+}
+
+impl Default for Web3SignerPoolConfig {
+    fn default() -> Self {
+        Self {
+            idle_timeout: Some(DEFAULT_WEB3SIGNER_IDLE_TIMEOUT),
+            max_idle_connections: None,
+        }
+    }
+}
+
 /// Forms the fundamental list of validators that are managed by this validator client instance.
 pub struct InitializedValidators {
     /// A list of validator definitions which can be stored on-disk.
@@ -443,15 +575,23 @@ pub struct InitializedValidators {
     validators: HashMap<PublicKeyBytes, InitializedValidator>,
     /// The clients used for communications with a remote signer.
     web3_signer_client_map: Option<HashMap<Web3SignerDefinition, Client>>,
+    /// Connection pooling settings for the remote signer clients.
+    web3_signer_pool: Web3SignerPoolConfig,
     /// For logging via `slog`.
     log: Logger,
 }
 
 impl InitializedValidators {
+    /// The directory in which the validator definitions are stored.
+    pub fn validators_dir(&self) -> &Path {
+        &self.validators_dir
+    }
+
     /// Instantiates `Self`, initializing all validators in `definitions`.
     pub async fn from_definitions(
         definitions: ValidatorDefinitions,
         validators_dir: PathBuf,
+        web3_signer_pool: Web3SignerPoolConfig,
         log: Logger,
     ) -> Result<Self, Error> {
         let mut this = Self {
@@ -459,6 +599,7 @@ impl InitializedValidators {
             definitions,
             validators: HashMap::default(),
             web3_signer_client_map: None,
+            web3_signer_pool,
             log,
         };
         this.update_validators().await?;
@@ -470,6 +611,16 @@ impl InitializedValidators {
         self.validators.len()
     }
 
+    /// Returns `true` if any enabled validator in `self` signs via a remote signer.
+    pub fn has_web3signer_validators(&self) -> bool {
+        self.validators.values().any(|validator| {
+            matches!(
+                validator.signing_method.as_ref(),
+                SigningMethod::Web3Signer { .. }
+            )
+        })
+    }
+
     /// The total count of enabled and disabled validators contained in `self`.
     pub fn num_total(&self) -> usize {
         self.definitions.as_slice().len()
@@ -670,6 +821,14 @@ impl InitializedValidators {
             .and_then(|v| v.builder_proposals)
     }
 
+    /// Returns the `builder_boost_factor` for a given public key specified in the
+    /// `ValidatorDefinitions`.
+    pub fn builder_boost_factor(&self, public_key: &PublicKeyBytes) -> Option<u64> {
+        self.validators
+            .get(public_key)
+            .and_then(|v| v.builder_boost_factor)
+    }
+
     /// Returns an `Option` of a reference to an `InitializedValidator` for a given public key specified in the
     /// `ValidatorDefinitions`.
     pub fn validator(&self, public_key: &PublicKeyBytes) -> Option<&InitializedValidator> {
@@ -888,6 +1047,78 @@ impl InitializedValidators {
         Ok(())
     }
 
+    /// Sets the `InitializedValidator` and `ValidatorDefinition` `graffiti` values.
+    ///
+    /// ## Notes
+    ///
+    /// Setting a validator `graffiti` will cause `self.definitions` to be updated and saved to
+    /// disk.
+    ///
+    /// Saves the `ValidatorDefinitions` to file, even if no definitions were changed.
+    pub fn set_validator_graffiti(
+        &mut self,
+        voting_public_key: &PublicKey,
+        graffiti: GraffitiString,
+    ) -> Result<(), Error> {
+        if let Some(def) = self
+            .definitions
+            .as_mut_slice()
+            .iter_mut()
+            .find(|def| def.voting_public_key == *voting_public_key)
+        {
+            def.graffiti = Some(graffiti.clone());
+        }
+
+        if let Some(val) = self
+            .validators
+            .get_mut(&PublicKeyBytes::from(voting_public_key))
+        {
+            val.graffiti = Some(graffiti.into());
+        }
+
+        self.definitions
+            .save(&self.validators_dir)
+            .map_err(Error::UnableToSaveDefinitions)?;
+
+        Ok(())
+    }
+
+    /// Removes the `InitializedValidator` and `ValidatorDefinition` `graffiti` values.
+    ///
+    /// ## Notes
+    ///
+    /// Removing a validator `graffiti` will cause `self.definitions` to be updated and saved to
+    /// disk. The graffiti for the validator will then fall back to the `--graffiti-file` or
+    /// `--graffiti` flag if either is set.
+    ///
+    /// Saves the `ValidatorDefinitions` to file, even if no definitions were changed.
+    pub fn delete_validator_graffiti(
+        &mut self,
+        voting_public_key: &PublicKey,
+    ) -> Result<(), Error> {
+        if let Some(def) = self
+            .definitions
+            .as_mut_slice()
+            .iter_mut()
+            .find(|def| def.voting_public_key == *voting_public_key)
+        {
+            def.graffiti = None;
+        }
+
+        if let Some(val) = self
+            .validators
+            .get_mut(&PublicKeyBytes::from(voting_public_key))
+        {
+            val.graffiti = None;
+        }
+
+        self.definitions
+            .save(&self.validators_dir)
+            .map_err(Error::UnableToSaveDefinitions)?;
+
+        Ok(())
+    }
+
     /// Tries to decrypt the key cache.
     ///
     /// Returns the decrypted cache if decryption was successful, or an error if a required password
@@ -952,7 +1183,8 @@ impl InitializedValidators {
                     let pw = if let Some(p) = voting_keystore_password {
                         p.as_ref().to_vec().into()
                     } else if let Some(path) = voting_keystore_password_path {
-                        read_password(path).map_err(Error::UnableToReadVotingKeystorePassword)?
+                        read_voting_keystore_password(path, &def.voting_public_key)
+                            .map_err(Error::UnableToReadVotingKeystorePassword)?
                     } else {
                         let keystore = open_keystore(voting_keystore_path)?;
                         unlock_keystore_via_stdin_password(&keystore, voting_keystore_path)?
@@ -1042,6 +1274,7 @@ impl InitializedValidators {
                             &mut key_cache,
                             &mut key_stores,
                             &mut None,
+                            self.web3_signer_pool,
                         )
                         .await
                         {
@@ -1092,6 +1325,7 @@ impl InitializedValidators {
                             &mut key_cache,
                             &mut key_stores,
                             &mut self.web3_signer_client_map,
+                            self.web3_signer_pool,
                         )
                         .await
                         {
@@ -1192,3 +1426,111 @@ impl InitializedValidators {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use warp::Filter;
+
+    /// The SHA-256 fingerprint of the Web3Signer test certificate.
+    const CERTIFICATE_FINGERPRINT: &str =
+        "50:38:44:85:99:1E:55:1D:43:27:3F:0D:17:2D:5C:21:C3:68:8F:90:8A:3C:29:69:79:EA:80:A8:88:6B:B2:64";
+
+    fn tls_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("testing")
+            .join("web3signer_tests")
+            .join("tls")
+    }
+
+    /// Serve HTTPS using the Web3Signer test certificate, counting the requests received.
+    fn serve_tls(requests: Arc<AtomicUsize>) -> SocketAddr {
+        let routes = warp::any().map(move || {
+            requests.fetch_add(1, Ordering::SeqCst);
+            "{}"
+        });
+        let (listening_socket, server) = warp::serve(routes)
+            .tls()
+            .cert_path(tls_dir().join("web3signer").join("cert.pem"))
+            .key_path(tls_dir().join("web3signer").join("key.key"))
+            .bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        listening_socket
+    }
+
+    fn pinned_client(fingerprint: &str) -> Client {
+        build_web3_signer_client(
+            None,
+            None,
+            None,
+            Some(parse_certificate_fingerprint(fingerprint).unwrap()),
+            DEFAULT_REMOTE_SIGNER_REQUEST_TIMEOUT,
+            Web3SignerPoolConfig::default(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn pinned_certificate_accepted() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let listening_socket = serve_tls(requests.clone());
+
+        pinned_client(CERTIFICATE_FINGERPRINT)
+            .post(format!("https://{}/", listening_socket))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn mismatched_certificate_rejected_before_request() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let listening_socket = serve_tls(requests.clone());
+
+        pinned_client(&"00".repeat(32))
+            .post(format!("https://{}/", listening_socket))
+            .send()
+            .await
+            .unwrap_err();
+
+        // The handshake failed, so the server never received the request.
+        assert_eq!(requests.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn pinned_certificate_with_client_identity() {
+        let result = build_web3_signer_client(
+            None,
+            Some(tls_dir().join("lighthouse").join("key.p12")),
+            Some("password".into()),
+            Some(parse_certificate_fingerprint(CERTIFICATE_FINGERPRINT).unwrap()),
+            DEFAULT_REMOTE_SIGNER_REQUEST_TIMEOUT,
+            Web3SignerPoolConfig::default(),
+        );
+        assert!(matches!(
+            result,
+            Err(Error::Web3SignerClientIdentityWithCertificateFingerprint)
+        ));
+    }
+
+    #[test]
+    fn pinned_certificate_with_root_certificate() {
+        let result = build_web3_signer_client(
+            Some(tls_dir().join("web3signer").join("cert.pem")),
+            None,
+            None,
+            Some(parse_certificate_fingerprint(CERTIFICATE_FINGERPRINT).unwrap()),
+            DEFAULT_REMOTE_SIGNER_REQUEST_TIMEOUT,
+            Web3SignerPoolConfig::default(),
+        );
+        assert!(matches!(
+            result,
+            Err(Error::Web3SignerRootCertificateWithCertificateFingerprint)
+        ));
+    }
+}
@@ -37,23 +37,36 @@ pub fn start_latency_service<T: SlotClock + 'static, E: EthSpec>(
             sleep(sleep_time).await;
 
             for (i, measurement) in beacon_nodes.measure_latency().await.iter().enumerate() {
-                if let Some(latency) = measurement.latency {
-                    debug!(
-                        log,
-                        "Measured BN latency";
-                        "node" => &measurement.beacon_node_id,
-                        "latency" => latency.as_millis(),
-                    );
-                    metrics::observe_timer_vec(
-                        &metrics::VC_BEACON_NODE_LATENCY,
-                        &[&measurement.beacon_node_id],
-                        latency,
-                    );
-                    if i == 0 {
-                        metrics::observe_duration(
-                            &metrics::VC_BEACON_NODE_LATENCY_PRIMARY_ENDPOINT,
+                match measurement.latency {
+                    Some(latency) => {
+                        debug!(
+                            log,
+                            "Measured BN latency";
+                            "node" => &measurement.beacon_node_id,
+                            "latency" => latency.as_millis(),
+                        );
+                        metrics::observe_timer_vec(
+                            &metrics::VC_BEACON_NODE_LATENCY,
+                            &[&measurement.beacon_node_id],
                             latency,
                         );
+                        if i == 0 {
+                            metrics::observe_duration(
+                                &metrics::VC_BEACON_NODE_LATENCY_PRIMARY_ENDPOINT,
+                                latency,
+                            );
+                        }
+                    }
+                    None => {
+                        debug!(
+                            log,
+                            "BN did not respond to latency measurement";
+                            "node" => &measurement.beacon_node_id,
+                        );
+                        metrics::inc_counter_vec(
+                            &metrics::VC_BEACON_NODE_LATENCY_FAILURES,
+                            &[&measurement.beacon_node_id],
+                        );
                     }
                 }
             }
@@ -5,6 +5,7 @@ mod check_synced;
 mod cli;
 mod config;
 mod duties_service;
+mod graceful_shutdown;
 mod graffiti_file;
 mod http_metrics;
 mod key_cache;
@@ -19,9 +20,11 @@ pub mod http_api;
 pub mod initialized_validators;
 pub mod validator_store;
 
+pub use beacon_node_fallback::ApiTopic;
 pub use cli::cli_app;
 pub use config::Config;
-use initialized_validators::InitializedValidators;
+pub use graceful_shutdown::{GracefulShutdown, InFlightDuties};
+use initialized_validators::{InitializedValidators, Web3SignerPoolConfig};
 use lighthouse_metrics::set_gauge;
 use monitoring_api::{MonitoringHttpClient, ProcessType};
 use sensitive_url::SensitiveUrl;
@@ -83,6 +86,18 @@ const HTTP_GET_BEACON_BLOCK_SSZ_TIMEOUT_QUOTIENT: u32 = 4;
 const HTTP_GET_DEBUG_BEACON_STATE_QUOTIENT: u32 = 4;
 const HTTP_GET_DEPOSIT_SNAPSHOT_QUOTIENT: u32 = 4;
 
+/// Timeout quotient for all duty-related requests when running with `--distributed`.
+///
+/// Distributed validator middleware needs time to reach consensus amongst the cluster before it
+/// responds, so each request is allowed up to a third of a slot (the interval between duties).
+const HTTP_DISTRIBUTED_DUTY_TIMEOUT_QUOTIENT: u32 = 3;
+
+/// Block production may be delayed by at most `slot_duration / MAX_BLOCK_DELAY_QUOTIENT`.
+///
+/// Blocks published after the attestation deadline are unlikely to be attested to or to receive
+/// proposer boost, so delaying beyond this point would risk orphaning the block.
+const MAX_BLOCK_DELAY_QUOTIENT: u32 = 3;
+
 const DOPPELGANGER_SERVICE_NAME: &str = "doppelganger";
 
 #[derive(Clone)]
@@ -95,6 +110,7 @@ pub struct ProductionValidatorClient<T: EthSpec> {
     doppelganger_service: Option<Arc<DoppelgangerService>>,
     preparation_service: PreparationService<SystemTimeSlotClock, T>,
     validator_store: Arc<ValidatorStore<SystemTimeSlotClock, T>>,
+    slashing_protection: SlashingDatabase,
     slot_clock: SystemTimeSlotClock,
     http_api_listen_addr: Option<SocketAddr>,
     config: Config,
@@ -124,6 +140,36 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             "validator_dir" => format!("{:?}", config.validator_dir),
         );
 
+        // Check that the configured proposal and attestation timings fit within the slot.
+        let slot_duration = Duration::from_secs(context.eth2_config.spec.seconds_per_slot);
+
+        if let Some(block_delay) = config.block_delay {
+            let max_block_delay = slot_duration / MAX_BLOCK_DELAY_QUOTIENT;
+            if block_delay >= max_block_delay {
+                return Err(format!(
+                    "block-delay-ms must be less than {}ms (1/{} of a slot)",
+                    max_block_delay.as_millis(),
+                    MAX_BLOCK_DELAY_QUOTIENT
+                ));
+            }
+        }
+
+        let attestation_offset = config
+            .attestation_production_offset
+            .unwrap_or(slot_duration / 3);
+        let aggregate_offset = config
+            .aggregate_production_offset
+            .unwrap_or(slot_duration * 2 / 3);
+        if attestation_offset >= aggregate_offset || aggregate_offset >= slot_duration {
+            return Err(format!(
+                "Attestation production ({}ms) must occur before aggregate production ({}ms), \
+                which must occur within the slot ({}ms)",
+                attestation_offset.as_millis(),
+                aggregate_offset.as_millis(),
+                slot_duration.as_millis()
+            ));
+        }
+
         // Optionally start the metrics server.
         let http_metrics_ctx = if config.http_metrics.enabled {
             let shared = http_metrics::Shared {
@@ -185,6 +231,10 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
         let validators = InitializedValidators::from_definitions(
             validator_defs,
             config.validator_dir.clone(),
+            Web3SignerPoolConfig {
+                idle_timeout: config.web3_signer_keep_alive_timeout,
+                max_idle_connections: config.web3_signer_max_idle_connections,
+            },
             log.clone(),
         )
         .await
@@ -285,8 +335,21 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
                 .build()
                 .map_err(|e| format!("Unable to build HTTP client: {:?}", e))?;
 
-            // Use quicker timeouts if a fallback beacon node exists.
-            let timeouts = if i < last_beacon_node_index && !config.use_long_timeouts {
+            // Align duty timeouts with slot thirds when behind distributed validator middleware,
+            // otherwise use quicker timeouts if a fallback beacon node exists.
+            let timeouts = if config.distributed {
+                let duty_timeout = slot_duration / HTTP_DISTRIBUTED_DUTY_TIMEOUT_QUOTIENT;
+                Timeouts {
+                    attestation: duty_timeout,
+                    attester_duties: duty_timeout,
+                    liveness: duty_timeout,
+                    proposal: duty_timeout,
+                    proposer_duties: duty_timeout,
+                    sync_committee_contribution: duty_timeout,
+                    sync_duties: duty_timeout,
+                    ..Timeouts::set_all(slot_duration)
+                }
+            } else if i < last_beacon_node_index && !config.use_long_timeouts {
                 info!(
                     log,
                     "Fallback endpoints are available, using optimized timeouts.";
@@ -361,14 +424,14 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
 
         let mut beacon_nodes: BeaconNodeFallback<_, T> = BeaconNodeFallback::new(
             candidates,
-            config.disable_run_on_all,
+            config.broadcast_topics.clone(),
             context.eth2_config.spec.clone(),
             log.clone(),
         );
 
         let mut proposer_nodes: BeaconNodeFallback<_, T> = BeaconNodeFallback::new(
             proposer_candidates,
-            config.disable_run_on_all,
+            config.broadcast_topics.clone(),
             context.eth2_config.spec.clone(),
             log.clone(),
         );
@@ -401,6 +464,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
 
         let doppelganger_service = if config.enable_doppelganger_protection {
             Some(Arc::new(DoppelgangerService::new(
+                config.doppelganger_detection_epochs,
                 context
                     .service_context(DOPPELGANGER_SERVICE_NAME.into())
                     .log()
@@ -412,7 +476,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
 
         let validator_store = Arc::new(ValidatorStore::new(
             validators,
-            slashing_protection,
+            slashing_protection.clone(),
             genesis_validators_root,
             context.eth2_config.spec.clone(),
             doppelganger_service.clone(),
@@ -449,6 +513,9 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             spec: context.eth2_config.spec.clone(),
             context: duties_context,
             enable_high_validator_count_metrics: config.enable_high_validator_count_metrics,
+            distributed: config.distributed,
+            sync_committee_subscription_lookahead_epochs: config
+                .sync_committee_subscription_lookahead_epochs,
         });
 
         // Update the metrics server.
@@ -464,7 +531,9 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             .runtime_context(context.service_context("block".into()))
             .graffiti(config.graffiti)
             .graffiti_file(config.graffiti_file.clone())
-            .block_delay(config.block_delay);
+            .block_delay(config.block_delay)
+            .distributed(config.distributed)
+            .strict_fee_recipient(config.strict_fee_recipient);
 
         // If we have proposer nodes, add them to the block service builder.
         if proposer_nodes_num > 0 {
@@ -479,6 +548,8 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             .validator_store(validator_store.clone())
             .beacon_nodes(beacon_nodes.clone())
             .runtime_context(context.service_context("attestation".into()))
+            .attestation_production_offset(config.attestation_production_offset)
+            .aggregate_production_offset(config.aggregate_production_offset)
             .build()?;
 
         let preparation_service = PreparationServiceBuilder::new()
@@ -488,6 +559,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             .runtime_context(context.service_context("preparation".into()))
             .builder_registration_timestamp_override(config.builder_registration_timestamp_override)
             .validator_registration_batch_size(config.validator_registration_batch_size)
+            .validator_registration_interval_epochs(config.validator_registration_interval_epochs)
             .build()?;
 
         let sync_committee_service = SyncCommitteeService::new(
@@ -513,6 +585,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             doppelganger_service,
             preparation_service,
             validator_store,
+            slashing_protection,
             config,
             slot_clock,
             http_api_listen_addr: None,
@@ -565,7 +638,26 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
 
         spawn_notifier(self).map_err(|e| format!("Failed to start notifier: {}", e))?;
 
-        let api_secret = ApiSecret::create_or_open(&self.config.validator_dir)?;
+        let mut api_secret = if self.config.http_api.rotate_api_token {
+            let api_secret = ApiSecret::rotate(&self.config.validator_dir)?;
+            info!(
+                log,
+                "Rotated HTTP API token";
+                "api_token_file" => ?api_secret.api_token_path(),
+            );
+            api_secret
+        } else {
+            ApiSecret::create_or_open(&self.config.validator_dir)?
+        };
+
+        if self.config.http_api.generate_read_only_token {
+            api_secret.generate_read_only_token()?;
+            info!(
+                log,
+                "Generated read-only HTTP API token";
+                "read_only_tokens_file" => ?api_secret.read_only_tokens_path(),
+            );
+        }
 
         self.http_api_listen_addr = if self.config.http_api.enabled {
             let ctx = Arc::new(http_api::Context {
@@ -579,6 +671,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
                 config: self.config.http_api.clone(),
                 sse_logging_components: self.context.sse_logging_components.clone(),
                 slot_clock: self.slot_clock.clone(),
+                beacon_nodes: Some(self.duties_service.beacon_nodes.clone()),
                 log: log.clone(),
                 _phantom: PhantomData,
             });
@@ -609,6 +702,17 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
 
         Ok(())
     }
+
+    /// Returns the handle used to drain in-flight duties and flush the slashing protection
+    /// database once a shutdown has been requested.
+    pub fn graceful_shutdown(&self) -> GracefulShutdown {
+        GracefulShutdown {
+            in_flight_duties: self.validator_store.in_flight_duties().clone(),
+            slashing_protection: self.slashing_protection.clone(),
+            timeout: self.config.graceful_shutdown_timeout,
+            log: self.context.log().clone(),
+        }
+    }
 }
 
 async fn init_from_beacon_node<E: EthSpec>(
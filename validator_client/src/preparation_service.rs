@@ -1,4 +1,4 @@
-use crate::beacon_node_fallback::{BeaconNodeFallback, RequireSynced};
+use crate::beacon_node_fallback::{ApiTopic, BeaconNodeFallback, RequireSynced};
 use crate::validator_store::{DoppelgangerStatus, ValidatorStore};
 use crate::OfflineOnFailure;
 use bls::PublicKeyBytes;
@@ -20,8 +20,8 @@ use types::{
 /// Number of epochs before the Bellatrix hard fork to begin posting proposer preparations.
 const PROPOSER_PREPARATION_LOOKAHEAD_EPOCHS: u64 = 2;
 
-/// Number of epochs to wait before re-submitting validator registration.
-const EPOCHS_PER_VALIDATOR_REGISTRATION_SUBMISSION: u64 = 1;
+/// Default number of epochs to wait before re-submitting validator registration.
+pub const DEFAULT_EPOCHS_PER_VALIDATOR_REGISTRATION_SUBMISSION: u64 = 1;
 
 /// Builds an `PreparationService`.
 pub struct PreparationServiceBuilder<T: SlotClock + 'static, E: EthSpec> {
@@ -31,6 +31,7 @@ pub struct PreparationServiceBuilder<T: SlotClock + 'static, E: EthSpec> {
     context: Option<RuntimeContext<E>>,
     builder_registration_timestamp_override: Option<u64>,
     validator_registration_batch_size: Option<usize>,
+    validator_registration_interval_epochs: Option<u64>,
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> PreparationServiceBuilder<T, E> {
@@ -42,6 +43,7 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationServiceBuilder<T, E> {
             context: None,
             builder_registration_timestamp_override: None,
             validator_registration_batch_size: None,
+            validator_registration_interval_epochs: None,
         }
     }
 
@@ -81,6 +83,14 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationServiceBuilder<T, E> {
         self
     }
 
+    pub fn validator_registration_interval_epochs(
+        mut self,
+        validator_registration_interval_epochs: u64,
+    ) -> Self {
+        self.validator_registration_interval_epochs = Some(validator_registration_interval_epochs);
+        self
+    }
+
     pub fn build(self) -> Result<PreparationService<T, E>, String> {
         Ok(PreparationService {
             inner: Arc::new(Inner {
@@ -101,6 +111,9 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationServiceBuilder<T, E> {
                 validator_registration_batch_size: self.validator_registration_batch_size.ok_or(
                     "Cannot build PreparationService without validator_registration_batch_size",
                 )?,
+                validator_registration_interval_epochs: self
+                    .validator_registration_interval_epochs
+                    .unwrap_or(DEFAULT_EPOCHS_PER_VALIDATOR_REGISTRATION_SUBMISSION),
                 validator_registration_cache: RwLock::new(HashMap::new()),
             }),
         })
@@ -118,6 +131,8 @@ pub struct Inner<T, E: EthSpec> {
     validator_registration_cache:
         RwLock<HashMap<ValidatorRegistrationKey, SignedValidatorRegistrationData>>,
     validator_registration_batch_size: usize,
+    /// Number of epochs between re-submissions of all validator registrations.
+    validator_registration_interval_epochs: u64,
 }
 
 #[derive(Hash, Eq, PartialEq, Debug, Clone)]
@@ -345,6 +360,7 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationService<T, E> {
             .run(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                ApiTopic::Preparations,
                 |beacon_node| async move {
                     beacon_node
                         .post_validator_prepare_beacon_proposer(preparation_entries)
@@ -385,9 +401,9 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationService<T, E> {
             drop(guard);
         }
 
-        // Check if any have changed or it's been `EPOCHS_PER_VALIDATOR_REGISTRATION_SUBMISSION`.
+        // Check if any have changed or it's been `validator_registration_interval_epochs`.
         if let Some(slot) = self.slot_clock.now() {
-            if slot % (E::slots_per_epoch() * EPOCHS_PER_VALIDATOR_REGISTRATION_SUBMISSION) == 0 {
+            if slot % (E::slots_per_epoch() * self.validator_registration_interval_epochs) == 0 {
                 self.publish_validator_registration_data(registration_keys)
                     .await?;
             } else if !changed_keys.is_empty() {
@@ -461,9 +477,10 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationService<T, E> {
             for batch in signed.chunks(self.validator_registration_batch_size) {
                 match self
                     .beacon_nodes
-                    .first_success(
+                    .run(
                         RequireSynced::No,
                         OfflineOnFailure::No,
+                        ApiTopic::Registrations,
                         |beacon_node| async move {
                             beacon_node.post_validator_register_validator(batch).await
                         },
@@ -475,6 +492,20 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationService<T, E> {
                         "Published validator registrations to the builder network";
                         "count" => batch.len(),
                     ),
+                    // When broadcasting, an error is returned if *any* beacon node failed. The
+                    // registrations still reached the builder network if at least one succeeded.
+                    Err(e) if e.0.len() < self.beacon_nodes.num_total() => {
+                        info!(
+                            log,
+                            "Published validator registrations to the builder network";
+                            "count" => batch.len(),
+                        );
+                        debug!(
+                            log,
+                            "Unable to publish validator registrations to some beacon nodes";
+                            "error" => %e,
+                        );
+                    }
                     Err(e) => warn!(
                         log,
                         "Unable to publish validator registrations to the builder network";
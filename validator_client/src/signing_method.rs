@@ -26,6 +26,7 @@ pub enum Error {
         domain: Domain,
     },
     Web3SignerRequestFailed(String),
+    Web3SignerRequestTimedOut(String),
     Web3SignerJsonParsingFailed(String),
     ShuttingDown,
     TokioJoin(String),
@@ -232,7 +233,13 @@ impl SigningMethod {
                     .json(&request)
                     .send()
                     .await
-                    .map_err(|e| Error::Web3SignerRequestFailed(e.to_string()))?
+                    .map_err(|e| {
+                        if e.is_timeout() {
+                            Error::Web3SignerRequestTimedOut(e.to_string())
+                        } else {
+                            Error::Web3SignerRequestFailed(e.to_string())
+                        }
+                    })?
                     .error_for_status()
                     .map_err(|e| Error::Web3SignerRequestFailed(e.to_string()))?
                     .json()
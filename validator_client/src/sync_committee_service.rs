@@ -1,12 +1,15 @@
-use crate::beacon_node_fallback::{BeaconNodeFallback, RequireSynced};
-use crate::{duties_service::DutiesService, validator_store::ValidatorStore, OfflineOnFailure};
+use crate::beacon_node_fallback::{ApiTopic, BeaconNodeFallback, RequireSynced};
+use crate::{
+    doppelganger_service::DoppelgangerStatus, duties_service::DutiesService, http_metrics::metrics,
+    validator_store::ValidatorStore, OfflineOnFailure,
+};
 use environment::RuntimeContext;
 use eth2::types::BlockId;
 use futures::future::join_all;
 use futures::future::FutureExt;
 use slog::{crit, debug, error, info, trace, warn};
 use slot_clock::SlotClock;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ops::Deref;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -16,6 +19,8 @@ use types::{
     SyncContributionData, SyncDuty, SyncSelectionProof, SyncSubnetId,
 };
 
+/// The default number of epochs before the start of a sync committee period at which
+/// subscriptions for that period are sent to the beacon node.
 pub const SUBSCRIPTION_LOOKAHEAD_EPOCHS: u64 = 4;
 
 pub struct SyncCommitteeService<T: SlotClock + 'static, E: EthSpec> {
@@ -154,10 +159,16 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                 .checked_sub(slot_duration / 3)
                 .unwrap_or_else(|| Duration::from_secs(0));
 
+        // Only sign with validators which are enabled and considered safe in terms of
+        // doppelganger protection.
+        let signing_pubkeys: HashSet<_> = self
+            .validator_store
+            .voting_pubkeys(DoppelgangerStatus::only_safe);
+
         let slot_duties = if let Some(duties) = self
             .duties_service
             .sync_duties
-            .get_duties_for_slot::<E>(slot, &self.duties_service.spec)
+            .get_duties_for_slot::<E>(slot, &signing_pubkeys, &self.duties_service.spec)
         {
             duties
         } else {
@@ -209,11 +220,24 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             }
         };
 
+        let duty_guard = match self.validator_store.in_flight_duties().start() {
+            Some(guard) => guard,
+            None => {
+                debug!(
+                    log,
+                    "Not producing sync committee messages during shutdown";
+                    "slot" => slot,
+                );
+                return Ok(());
+            }
+        };
+
         // Spawn one task to publish all of the sync committee signatures.
         let validator_duties = slot_duties.duties;
         let service = self.clone();
         self.inner.context.executor.spawn(
             async move {
+                let _duty_guard = duty_guard;
                 service
                     .publish_sync_committee_signatures(slot, block_root, validator_duties)
                     .map(|_| ())
@@ -222,10 +246,23 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             "sync_committee_signature_publish",
         );
 
+        let duty_guard = match self.validator_store.in_flight_duties().start() {
+            Some(guard) => guard,
+            None => {
+                debug!(
+                    log,
+                    "Not producing sync committee aggregates during shutdown";
+                    "slot" => slot,
+                );
+                return Ok(());
+            }
+        };
+
         let aggregators = slot_duties.aggregators;
         let service = self.clone();
         self.inner.context.executor.spawn(
             async move {
+                let _duty_guard = duty_guard;
                 service
                     .publish_sync_committee_aggregates(
                         slot,
@@ -265,6 +302,10 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             {
                 Ok(signature) => Some(signature),
                 Err(e) => {
+                    metrics::inc_counter_vec(
+                        &metrics::DUTY_FAILURES_TOTAL,
+                        &[metrics::SYNC_COMMITTEE_MESSAGES, e.metrics_cause()],
+                    );
                     crit!(
                         log,
                         "Failed to sign sync committee signature";
@@ -278,12 +319,21 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
         });
 
         // Execute all the futures in parallel, collecting any successful results.
+        let signing_timer = metrics::start_timer_vec(
+            &metrics::DUTY_SIGNING_TIMES,
+            &[metrics::SYNC_COMMITTEE_MESSAGES],
+        );
         let committee_signatures = &join_all(signature_futures)
             .await
             .into_iter()
             .flatten()
             .collect::<Vec<_>>();
+        drop(signing_timer);
 
+        let publish_timer = metrics::start_timer_vec(
+            &metrics::DUTY_PUBLISH_TIMES,
+            &[metrics::SYNC_COMMITTEE_MESSAGES],
+        );
         self.beacon_nodes
             .first_success(
                 RequireSynced::No,
@@ -296,6 +346,11 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             )
             .await
             .map_err(|e| {
+                metrics::inc_counter_vec_by(
+                    &metrics::DUTY_FAILURES_TOTAL,
+                    &[metrics::SYNC_COMMITTEE_MESSAGES, metrics::BEACON_NODE_ERROR],
+                    committee_signatures.len() as u64,
+                );
                 error!(
                     log,
                     "Unable to publish sync committee messages";
@@ -303,6 +358,14 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                     "error" => %e,
                 );
             })?;
+        drop(publish_timer);
+
+        metrics::observe_duty_latency(
+            &self.slot_clock,
+            metrics::SYNC_COMMITTEE_MESSAGES,
+            slot,
+            self.slot_clock.slot_duration() * 2 / 3,
+        );
 
         info!(
             log,
@@ -373,6 +436,14 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             )
             .await
             .map_err(|e| {
+                metrics::inc_counter_vec_by(
+                    &metrics::DUTY_FAILURES_TOTAL,
+                    &[
+                        metrics::SYNC_COMMITTEE_CONTRIBUTIONS,
+                        metrics::BEACON_NODE_ERROR,
+                    ],
+                    subnet_aggregators.len() as u64,
+                );
                 crit!(
                     log,
                     "Failed to produce sync contribution";
@@ -382,6 +453,14 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                 )
             })?
             .ok_or_else(|| {
+                metrics::inc_counter_vec_by(
+                    &metrics::DUTY_FAILURES_TOTAL,
+                    &[
+                        metrics::SYNC_COMMITTEE_CONTRIBUTIONS,
+                        metrics::BEACON_NODE_ERROR,
+                    ],
+                    subnet_aggregators.len() as u64,
+                );
                 crit!(
                     log,
                     "No aggregate contribution found";
@@ -406,6 +485,10 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                 {
                     Ok(signed_contribution) => Some(signed_contribution),
                     Err(e) => {
+                        metrics::inc_counter_vec(
+                            &metrics::DUTY_FAILURES_TOTAL,
+                            &[metrics::SYNC_COMMITTEE_CONTRIBUTIONS, e.metrics_cause()],
+                        );
                         crit!(
                             log,
                             "Unable to sign sync committee contribution";
@@ -419,13 +502,22 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
         );
 
         // Execute all the futures in parallel, collecting any successful results.
+        let signing_timer = metrics::start_timer_vec(
+            &metrics::DUTY_SIGNING_TIMES,
+            &[metrics::SYNC_COMMITTEE_CONTRIBUTIONS],
+        );
         let signed_contributions = &join_all(signature_futures)
             .await
             .into_iter()
             .flatten()
             .collect::<Vec<_>>();
+        drop(signing_timer);
 
         // Publish to the beacon node.
+        let publish_timer = metrics::start_timer_vec(
+            &metrics::DUTY_PUBLISH_TIMES,
+            &[metrics::SYNC_COMMITTEE_CONTRIBUTIONS],
+        );
         self.beacon_nodes
             .first_success(
                 RequireSynced::No,
@@ -438,6 +530,14 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             )
             .await
             .map_err(|e| {
+                metrics::inc_counter_vec_by(
+                    &metrics::DUTY_FAILURES_TOTAL,
+                    &[
+                        metrics::SYNC_COMMITTEE_CONTRIBUTIONS,
+                        metrics::BEACON_NODE_ERROR,
+                    ],
+                    signed_contributions.len() as u64,
+                );
                 error!(
                     log,
                     "Unable to publish signed contributions and proofs";
@@ -445,6 +545,14 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                     "error" => %e,
                 );
             })?;
+        drop(publish_timer);
+
+        metrics::observe_duty_latency(
+            &self.slot_clock,
+            metrics::SYNC_COMMITTEE_CONTRIBUTIONS,
+            slot,
+            self.slot_clock.slot_duration(),
+        );
 
         info!(
             log,
@@ -497,7 +605,10 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
         // Near the end of the current period, push subscriptions for the next period to the
         // beacon node. We aggressively push every slot in the lead-up, as this is the main way
         // that we want to ensure that the BN is subscribed (well in advance).
-        let lookahead_slot = slot + SUBSCRIPTION_LOOKAHEAD_EPOCHS * E::slots_per_epoch();
+        let lookahead_epochs = self
+            .duties_service
+            .sync_committee_subscription_lookahead_epochs;
+        let lookahead_slot = slot + lookahead_epochs * E::slots_per_epoch();
 
         let lookahead_period = sync_period_of_slot::<E>(lookahead_slot, spec)?;
 
@@ -509,6 +620,11 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             return Ok(());
         }
 
+        // Subscribe on behalf of all enabled validators, regardless of doppelganger protection.
+        let local_pubkeys: HashSet<_> = self
+            .validator_store
+            .voting_pubkeys(DoppelgangerStatus::ignored);
+
         // Collect subscriptions.
         let mut subscriptions = vec![];
 
@@ -519,11 +635,11 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                 "duty_slot" => duty_slot,
                 "current_slot" => slot,
             );
-            match self
-                .duties_service
-                .sync_duties
-                .get_duties_for_slot::<E>(duty_slot, spec)
-            {
+            match self.duties_service.sync_duties.get_duties_for_slot::<E>(
+                duty_slot,
+                &local_pubkeys,
+                spec,
+            ) {
                 Some(duties) => subscriptions.extend(subscriptions_from_sync_duties(
                     duties.duties,
                     sync_committee_period,
@@ -572,6 +688,7 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             .run(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                ApiTopic::Subscriptions,
                 |beacon_node| async move {
                     beacon_node
                         .post_validator_sync_committee_subscriptions(subscriptions_slice)
@@ -1,5 +1,6 @@
 use crate::{
     doppelganger_service::DoppelgangerService,
+    graceful_shutdown::InFlightDuties,
     http_metrics::metrics,
     initialized_validators::InitializedValidators,
     signing_method::{Error as SigningError, SignableMessage, SigningContext, SigningMethod},
@@ -50,6 +51,45 @@ impl From<SigningError> for Error {
     }
 }
 
+impl Error {
+    /// Returns the `cause` label used to record this error in `metrics::DUTY_FAILURES_TOTAL`.
+    pub fn metrics_cause(&self) -> &'static str {
+        match self {
+            Error::Slashable(_) | Error::SameData => metrics::SLASHING_PROTECTION,
+            Error::DoppelgangerProtected(_) => metrics::DOPPELGANGER,
+            Error::UnknownToDoppelgangerService(_) | Error::UnknownPubkey(_) => {
+                metrics::UNREGISTERED
+            }
+            Error::UnableToSign(SigningError::Web3SignerRequestTimedOut(_)) => {
+                metrics::SIGNER_TIMEOUT
+            }
+            Error::GreaterThanCurrentSlot { .. }
+            | Error::GreaterThanCurrentEpoch { .. }
+            | Error::UnableToSignAttestation(_)
+            | Error::UnableToSign(_) => metrics::SIGNER_ERROR,
+        }
+    }
+}
+
+/// An error from exporting slashing protection data and backing it up.
+#[derive(Debug)]
+pub enum ExportError {
+    Interchange(InterchangeError),
+    Backup(String),
+}
+
+impl From<InterchangeError> for ExportError {
+    fn from(e: InterchangeError) -> Self {
+        ExportError::Interchange(e)
+    }
+}
+
+impl From<NotSafe> for ExportError {
+    fn from(e: NotSafe) -> Self {
+        ExportError::Interchange(e.into())
+    }
+}
+
 /// Number of epochs of slashing protection history to keep.
 ///
 /// This acts as a maximum safe-guard against clock drift.
@@ -97,7 +137,9 @@ pub struct ValidatorStore<T, E: EthSpec> {
     fee_recipient_process: Option<Address>,
     gas_limit: Option<u64>,
     builder_proposals: bool,
+    builder_boost_factor: Option<u64>,
     task_executor: TaskExecutor,
+    in_flight_duties: InFlightDuties,
     _phantom: PhantomData<E>,
 }
 
@@ -128,11 +170,21 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             fee_recipient_process: config.fee_recipient,
             gas_limit: config.gas_limit,
             builder_proposals: config.builder_proposals,
+            builder_boost_factor: config.builder_boost_factor,
             task_executor,
+            in_flight_duties: InFlightDuties::default(),
             _phantom: PhantomData,
         }
     }
 
+    /// The duties which are currently being signed or published.
+    ///
+    /// Services must register each duty here before starting it, and must not start a duty once
+    /// shutdown has begun.
+    pub fn in_flight_duties(&self) -> &InFlightDuties {
+        &self.in_flight_duties
+    }
+
     /// Register all local validators in doppelganger protection to try and prevent instances of
     /// duplicate validators operating on the network at the same time.
     ///
@@ -225,6 +277,28 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         Ok(validator_def)
     }
 
+    /// Register a validator which is about to be enabled at runtime with the slashing protection
+    /// database and doppelganger protection.
+    ///
+    /// Validators which were disabled when the validator client started are not registered with
+    /// either, so this must be called before enabling them. Doppelganger detection is restarted
+    /// since the key may have been in use elsewhere whilst it was disabled.
+    pub fn register_validator_for_enable(
+        &self,
+        validator_pubkey: PublicKeyBytes,
+    ) -> Result<(), String> {
+        self.slashing_protection
+            .register_validator(validator_pubkey)
+            .map_err(|e| format!("failed to register validator: {:?}", e))?;
+
+        if let Some(doppelganger_service) = &self.doppelganger_service {
+            doppelganger_service
+                .register_new_validator::<E, _>(validator_pubkey, &self.slot_clock)?;
+        }
+
+        Ok(())
+    }
+
     /// Returns `ProposalData` for the provided `pubkey` if it exists in `InitializedValidators`.
     /// `ProposalData` fields include defaulting logic described in `get_fee_recipient_defaulting`,
     /// `get_gas_limit_defaulting`, and `get_builder_proposals_defaulting`.
@@ -289,6 +363,30 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             .collect()
     }
 
+    /// Returns the number of epochs of doppelganger detection remaining for `validator_pubkey`.
+    ///
+    /// Returns `None` if doppelganger protection is disabled or the validator is unknown to it.
+    pub fn doppelganger_remaining_epochs(&self, validator_pubkey: &PublicKeyBytes) -> Option<u64> {
+        self.doppelganger_service
+            .as_ref()
+            .and_then(|doppelganger_service| {
+                doppelganger_service.remaining_epochs(validator_pubkey)
+            })
+    }
+
+    /// Skip any remaining doppelganger detection for `validator_pubkey`.
+    ///
+    /// See `DoppelgangerService::skip_detection` for the conditions under which this is safe.
+    pub fn skip_doppelganger_detection(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+    ) -> Result<(), String> {
+        self.doppelganger_service
+            .as_ref()
+            .ok_or("Doppelganger protection is not enabled")?
+            .skip_detection(validator_pubkey)
+    }
+
     /// Returns doppelganger statuses for all enabled validators.
     #[allow(clippy::needless_collect)] // Collect is required to avoid holding a lock.
     pub fn doppelganger_statuses(&self) -> Vec<DoppelgangerStatus> {
@@ -332,6 +430,10 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         self.validators.read().num_enabled()
     }
 
+    pub fn has_web3signer_validators(&self) -> bool {
+        self.validators.read().has_web3signer_validators()
+    }
+
     fn fork(&self, epoch: Epoch) -> Fork {
         self.spec.fork_at_epoch(epoch)
     }
@@ -463,6 +565,32 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             .unwrap_or(self.builder_proposals)
     }
 
+    /// Returns the `builder_boost_factor` for the given public key, if any. The priority order for
+    /// fetching this value is:
+    ///
+    /// 1. validator_definitions.yml
+    /// 2. process level flag
+    pub fn get_builder_boost_factor(&self, validator_pubkey: &PublicKeyBytes) -> Option<u64> {
+        self.validators
+            .read()
+            .builder_boost_factor(validator_pubkey)
+            .or(self.builder_boost_factor)
+    }
+
+    /// Returns the builder boost factor to request a block with for the given public key.
+    ///
+    /// A validator which doesn't have builder proposals enabled always requests a local payload,
+    /// i.e. a boost factor of zero. A validator which uses a builder requests its configured boost
+    /// factor, if any. Otherwise `None` is returned, leaving the choice of payload to the beacon
+    /// node.
+    pub fn determine_builder_boost_factor(&self, validator_pubkey: &PublicKeyBytes) -> Option<u64> {
+        if self.get_builder_proposals(validator_pubkey) {
+            self.get_builder_boost_factor(validator_pubkey)
+        } else {
+            Some(0)
+        }
+    }
+
     pub async fn sign_block<Payload: AbstractExecPayload<E>>(
         &self,
         validator_pubkey: PublicKeyBytes,
@@ -873,13 +1001,17 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
 
     /// Export slashing protection data while also disabling the given keys in the database.
     ///
+    /// The keys are only disabled once `backup` has saved the exported data. If `backup` fails the
+    /// database is left unchanged, so the keys remain able to sign.
+    ///
     /// If any key is unknown to the slashing protection database it will be silently omitted
     /// from the result. It is the caller's responsibility to check whether all keys provided
     /// had data returned for them.
     pub fn export_slashing_protection_for_keys(
         &self,
         pubkeys: &[PublicKeyBytes],
-    ) -> Result<Interchange, InterchangeError> {
+        backup: impl FnOnce(&Interchange) -> Result<(), String>,
+    ) -> Result<Interchange, ExportError> {
         self.slashing_protection.with_transaction(|txn| {
             let known_pubkeys = pubkeys
                 .iter()
@@ -896,6 +1028,34 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
                     )
                 })
                 .collect::<Result<Vec<PublicKeyBytes>, _>>()?;
+            let interchange = self.slashing_protection.export_interchange_info_in_txn(
+                self.genesis_validators_root,
+                Some(&known_pubkeys),
+                txn,
+            )?;
+            // Returning an error drops the transaction without committing it.
+            backup(&interchange).map_err(ExportError::Backup)?;
+            Ok(interchange)
+        })
+    }
+
+    /// Export slashing protection data for the given keys, without modifying the database.
+    ///
+    /// Keys which are unknown to the slashing protection database are omitted from the result.
+    pub fn export_slashing_protection_for_known_keys(
+        &self,
+        pubkeys: &[PublicKeyBytes],
+    ) -> Result<Interchange, InterchangeError> {
+        self.slashing_protection.with_transaction(|txn| {
+            let known_pubkeys = pubkeys
+                .iter()
+                .filter(|pubkey| {
+                    self.slashing_protection
+                        .get_validator_id_ignoring_status(txn, pubkey)
+                        .is_ok()
+                })
+                .copied()
+                .collect::<Vec<_>>();
             self.slashing_protection.export_interchange_info_in_txn(
                 self.genesis_validators_root,
                 Some(&known_pubkeys),
@@ -0,0 +1,157 @@
+use account_utils::{read_password, ZeroizeString};
+use clap::Arg;
+use eth2::lighthouse_vc::{
+    http_client::ValidatorClientHttpClient, std_types::SingleKeystoreResponse,
+};
+use eth2_keystore::Keystore;
+use sensitive_url::SensitiveUrl;
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use url::Host;
+
+pub const KEYSTORE_FLAG: &str = "keystore";
+pub const PASSWORD_FILE_FLAG: &str = "password-file";
+
+/// Returns the args used to connect to a validator client, with distinct flags for each validator
+/// client so that commands can connect to more than one.
+pub fn vc_args<'a, 'b>(
+    url_flag: &'static str,
+    token_flag: &'static str,
+    description: &'static str,
+) -> [Arg<'a, 'b>; 2] {
+    [
+        Arg::with_name(url_flag)
+            .long(url_flag)
+            .value_name("HTTP_ADDRESS")
+            .help(description)
+            .default_value("http://localhost:5062")
+            .takes_value(true),
+        Arg::with_name(token_flag)
+            .long(token_flag)
+            .value_name("PATH")
+            .help(
+                "The path to the file containing the API token of the validator client, \
+                usually the api-token.txt file in its validators directory.",
+            )
+            .required(true)
+            .takes_value(true),
+    ]
+}
+
+/// Returns the args for supplying keystores, and the password which unlocks them.
+pub fn keystore_args<'a, 'b>() -> [Arg<'a, 'b>; 2] {
+    [
+        Arg::with_name(KEYSTORE_FLAG)
+            .long(KEYSTORE_FLAG)
+            .value_name("KEYSTORE_PATH")
+            .help(
+                "The path to an EIP-2335 voting keystore. May be supplied multiple times to \
+                manage several validators at once.",
+            )
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .required(true),
+        Arg::with_name(PASSWORD_FILE_FLAG)
+            .long(PASSWORD_FILE_FLAG)
+            .value_name("PASSWORD_FILE_PATH")
+            .help("The path to a file containing the password which unlocks all of the keystores")
+            .takes_value(true)
+            .required(true),
+    ]
+}
+
+/// Returns an error unless `url` uses TLS or refers to the local machine, so that secrets such as
+/// mnemonics are never sent over the network in plain text.
+pub fn check_url_is_secure(url: &SensitiveUrl) -> Result<(), String> {
+    if url.full.scheme() == "https" {
+        return Ok(());
+    }
+
+    let is_loopback = match url.full.host() {
+        Some(Host::Domain(domain)) => domain == "localhost",
+        Some(Host::Ipv4(ip)) => IpAddr::V4(ip).is_loopback(),
+        Some(Host::Ipv6(ip)) => IpAddr::V6(ip).is_loopback(),
+        None => false,
+    };
+
+    if is_loopback {
+        Ok(())
+    } else {
+        Err(format!(
+            "Refusing to send secrets to {} without TLS. Use an https:// URL or connect via \
+            localhost, e.g. through an SSH tunnel",
+            url
+        ))
+    }
+}
+
+/// Connect to the validator client at `url`, authenticating with the token in `token_path`.
+///
+/// Returns the client and the keystores the validator client currently has, which confirms that
+/// the validator client is reachable and that the token is valid.
+pub async fn vc_http_client(
+    url: SensitiveUrl,
+    token_path: &Path,
+) -> Result<(ValidatorClientHttpClient, Vec<SingleKeystoreResponse>), String> {
+    let token = fs::read_to_string(token_path)
+        .map_err(|e| format!("Unable to read API token {:?}: {:?}", token_path, e))?;
+
+    let client = ValidatorClientHttpClient::new(url.clone(), token.trim().to_string())
+        .map_err(|e| format!("Unable to create client for {}: {:?}", url, e))?;
+    let keystores = client
+        .get_keystores()
+        .await
+        .map_err(|e| format!("Unable to list keystores of {}: {:?}", url, e))?
+        .data;
+
+    Ok((client, keystores))
+}
+
+/// Load each keystore in `keystore_paths`, checking that it can be unlocked by the password in
+/// `password_path`.
+pub fn load_keystores(
+    keystore_paths: &[PathBuf],
+    password_path: &Path,
+) -> Result<(Vec<Keystore>, ZeroizeString), String> {
+    let password = read_password(password_path)
+        .map_err(|e| format!("Unable to read password file {:?}: {:?}", password_path, e))?;
+
+    let keystores = keystore_paths
+        .iter()
+        .map(|path| {
+            let keystore = Keystore::from_json_file(path)
+                .map_err(|e| format!("Unable to read keystore {:?}: {:?}", path, e))?;
+            keystore
+                .decrypt_keypair(password.as_bytes())
+                .map_err(|e| format!("Unable to decrypt keystore {:?}: {:?}", path, e))?;
+            Ok(keystore)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let password = String::from_utf8(password.as_bytes().to_vec())
+        .map_err(|_| "Keystore password is not valid UTF-8".to_string())?;
+
+    Ok((keystores, password.into()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn secure_urls() {
+        let check = |url: &str| check_url_is_secure(&SensitiveUrl::parse(url).unwrap());
+
+        assert!(check("http://localhost:5062").is_ok());
+        assert!(check("http://127.0.0.1:5062").is_ok());
+        assert!(check("http://[::1]:5062").is_ok());
+        assert!(check("https://192.168.0.2:5062").is_ok());
+        assert!(check("https://vc.example.com").is_ok());
+
+        assert!(check("http://192.168.0.2:5062").is_err());
+        assert!(check("http://vc.example.com").is_err());
+        assert!(check("http://[2001:db8::1]:5062").is_err());
+    }
+}
@@ -0,0 +1,149 @@
+use crate::common::{check_url_is_secure, vc_args, vc_http_client};
+use account_utils::{mnemonic_from_phrase, ZeroizeString};
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use eth2::lighthouse_vc::types::{CreateValidatorsMnemonicRequest, ValidatorRequest};
+use sensitive_url::SensitiveUrl;
+use std::fs;
+use std::path::PathBuf;
+use types::{Address, EthSpec};
+
+pub const CMD: &str = "create";
+pub const VC_URL_FLAG: &str = "vc-url";
+pub const VC_TOKEN_FLAG: &str = "vc-token";
+pub const MNEMONIC_FLAG: &str = "mnemonic-path";
+pub const FIRST_INDEX_FLAG: &str = "first-index";
+pub const COUNT_FLAG: &str = "count";
+pub const DEPOSIT_GWEI_FLAG: &str = "deposit-gwei";
+pub const FEE_RECIPIENT_FLAG: &str = "suggested-fee-recipient";
+pub const GAS_LIMIT_FLAG: &str = "gas-limit";
+pub const BUILDER_PROPOSALS_FLAG: &str = "builder-proposals";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Creates validators on a validator client by deriving their keys from a mnemonic. \
+            The keys are derived by the validator client, which returns the deposit data of \
+            each new validator.",
+        )
+        .args(&vc_args(
+            VC_URL_FLAG,
+            VC_TOKEN_FLAG,
+            "The HTTP address of the validator client to create the validators on. Must use \
+            https:// unless the validator client is on the local machine",
+        ))
+        .arg(
+            Arg::with_name(MNEMONIC_FLAG)
+                .long(MNEMONIC_FLAG)
+                .value_name("MNEMONIC_PATH")
+                .help("The path to a file containing the mnemonic to derive the keys from")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(FIRST_INDEX_FLAG)
+                .long(FIRST_INDEX_FLAG)
+                .value_name("FIRST_INDEX")
+                .help("The first of consecutive key indexes to derive validators from")
+                .default_value("0")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(COUNT_FLAG)
+                .long(COUNT_FLAG)
+                .value_name("VALIDATOR_COUNT")
+                .help("The number of validators to create")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(DEPOSIT_GWEI_FLAG)
+                .long(DEPOSIT_GWEI_FLAG)
+                .value_name("DEPOSIT_GWEI")
+                .help(
+                    "The GWEI value of the deposit amount. Defaults to the minimum amount \
+                    required for an active validator (MAX_EFFECTIVE_BALANCE)",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(FEE_RECIPIENT_FLAG)
+                .long(FEE_RECIPIENT_FLAG)
+                .value_name("ETH1_ADDRESS")
+                .help("The fee recipient of the new validators")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(GAS_LIMIT_FLAG)
+                .long(GAS_LIMIT_FLAG)
+                .value_name("UINT64")
+                .help("The gas limit of the new validators")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(BUILDER_PROPOSALS_FLAG)
+                .long(BUILDER_PROPOSALS_FLAG)
+                .help("If present, the new validators will propose blocks using a builder"),
+        )
+}
+
+pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<(), String> {
+    let vc_url: SensitiveUrl = clap_utils::parse_required(matches, VC_URL_FLAG)?;
+    let vc_token_path: PathBuf = clap_utils::parse_required(matches, VC_TOKEN_FLAG)?;
+    let mnemonic_path: PathBuf = clap_utils::parse_required(matches, MNEMONIC_FLAG)?;
+    let first_index: u32 = clap_utils::parse_required(matches, FIRST_INDEX_FLAG)?;
+    let count: u32 = clap_utils::parse_required(matches, COUNT_FLAG)?;
+    let deposit_gwei = clap_utils::parse_optional(matches, DEPOSIT_GWEI_FLAG)?
+        .unwrap_or(env.eth2_config().spec.max_effective_balance);
+    let suggested_fee_recipient: Option<Address> =
+        clap_utils::parse_optional(matches, FEE_RECIPIENT_FLAG)?;
+    let gas_limit: Option<u64> = clap_utils::parse_optional(matches, GAS_LIMIT_FLAG)?;
+    let builder_proposals = matches.is_present(BUILDER_PROPOSALS_FLAG);
+
+    // The mnemonic controls the withdrawal keys as well as the signing keys.
+    check_url_is_secure(&vc_url)?;
+
+    first_index
+        .checked_add(count)
+        .ok_or("--first-index + --count must not exceed the maximum key index")?;
+
+    let phrase = fs::read_to_string(&mnemonic_path)
+        .map(ZeroizeString::from)
+        .map_err(|e| format!("Unable to read {:?}: {:?}", mnemonic_path, e))?;
+    let mnemonic = mnemonic_from_phrase(phrase.as_str().trim())?;
+
+    let validators = (0..count)
+        .map(|i| ValidatorRequest {
+            enable: true,
+            description: format!("created by validator manager, index {}", first_index + i),
+            graffiti: None,
+            suggested_fee_recipient,
+            gas_limit,
+            builder_proposals: builder_proposals.then_some(true),
+            deposit_gwei,
+        })
+        .collect();
+    let request = CreateValidatorsMnemonicRequest {
+        mnemonic: ZeroizeString::from(mnemonic.phrase().to_string()),
+        key_derivation_path_offset: first_index,
+        validators,
+    };
+
+    let created = env.runtime().block_on(async {
+        let (client, _) = vc_http_client(vc_url.clone(), &vc_token_path).await?;
+        client
+            .post_lighthouse_validators_mnemonic(&request)
+            .await
+            .map_err(|e| format!("Unable to create validators on {}: {:?}", vc_url, e))
+    })?;
+
+    eprintln!("Created {} validators on {}", created.data.len(), vc_url);
+    for validator in created.data {
+        println!(
+            "{:?}\t{}",
+            validator.voting_pubkey, validator.eth1_deposit_tx_data
+        );
+    }
+
+    Ok(())
+}
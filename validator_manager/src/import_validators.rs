@@ -0,0 +1,98 @@
+use crate::common::{
+    keystore_args, load_keystores, vc_args, vc_http_client, KEYSTORE_FLAG, PASSWORD_FILE_FLAG,
+};
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use eth2::lighthouse_vc::std_types::{
+    ImportKeystoreStatus, ImportKeystoresRequest, InterchangeJsonStr, KeystoreJsonStr,
+};
+use sensitive_url::SensitiveUrl;
+use slashing_protection::interchange::Interchange;
+use std::fs::File;
+use std::path::PathBuf;
+use types::EthSpec;
+
+pub const CMD: &str = "import";
+pub const VC_URL_FLAG: &str = "vc-url";
+pub const VC_TOKEN_FLAG: &str = "vc-token";
+pub const SLASHING_PROTECTION_FLAG: &str = "slashing-protection";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Imports EIP-2335 keystores into a validator client via the keymanager API, along \
+            with their slashing protection data.",
+        )
+        .args(&vc_args(
+            VC_URL_FLAG,
+            VC_TOKEN_FLAG,
+            "The HTTP address of the validator client to import the keystores into",
+        ))
+        .args(&keystore_args())
+        .arg(
+            Arg::with_name(SLASHING_PROTECTION_FLAG)
+                .long(SLASHING_PROTECTION_FLAG)
+                .value_name("INTERCHANGE_PATH")
+                .help(
+                    "The path to an EIP-3076 slashing protection interchange file to import \
+                    along with the keystores",
+                )
+                .takes_value(true),
+        )
+}
+
+pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<(), String> {
+    let vc_url: SensitiveUrl = clap_utils::parse_required(matches, VC_URL_FLAG)?;
+    let vc_token_path: PathBuf = clap_utils::parse_required(matches, VC_TOKEN_FLAG)?;
+    let keystore_paths: Vec<PathBuf> = matches
+        .values_of(KEYSTORE_FLAG)
+        .ok_or_else(|| format!("--{} is required", KEYSTORE_FLAG))?
+        .map(PathBuf::from)
+        .collect();
+    let password_path: PathBuf = clap_utils::parse_required(matches, PASSWORD_FILE_FLAG)?;
+    let slashing_protection_path: Option<PathBuf> =
+        clap_utils::parse_optional(matches, SLASHING_PROTECTION_FLAG)?;
+
+    let (keystores, password) = load_keystores(&keystore_paths, &password_path)?;
+    let slashing_protection = slashing_protection_path
+        .map(|path| {
+            File::open(&path)
+                .and_then(Interchange::from_json_reader)
+                .map(InterchangeJsonStr)
+                .map_err(|e| format!("Unable to read interchange file {:?}: {:?}", path, e))
+        })
+        .transpose()?;
+
+    let request = ImportKeystoresRequest {
+        passwords: vec![password; keystores.len()],
+        keystores: keystores.into_iter().map(KeystoreJsonStr).collect(),
+        slashing_protection,
+    };
+
+    let response = env.runtime().block_on(async {
+        let (client, _) = vc_http_client(vc_url.clone(), &vc_token_path).await?;
+        client
+            .post_keystores(&request)
+            .await
+            .map_err(|e| format!("Unable to import keystores into {}: {:?}", vc_url, e))
+    })?;
+
+    let mut num_errors = 0;
+    for (path, status) in keystore_paths.iter().zip(response.data) {
+        if status.status == ImportKeystoreStatus::Error {
+            num_errors += 1;
+        }
+        println!(
+            "{:?}\t{:?}\t{}",
+            path,
+            status.status,
+            status.message.unwrap_or_default()
+        );
+    }
+
+    if num_errors > 0 {
+        return Err(format!("Failed to import {} keystores", num_errors));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+//! Provides the `lighthouse validator-manager` command, which manages the validators of one or
+//! more running validator clients via their HTTP APIs.
+//!
+//! Unlike the account manager, which operates on the files in a local validator directory, every
+//! operation here is performed remotely, so that a fleet of validator clients can be managed
+//! from a single machine.
+mod common;
+pub mod create_validators;
+pub mod import_validators;
+pub mod move_validators;
+
+use clap::{App, ArgMatches};
+use environment::Environment;
+use types::EthSpec;
+
+pub const CMD: &str = "validator-manager";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .visible_aliases(&["vm"])
+        .about(
+            "Utilities for managing the validators of a Lighthouse validator client via its \
+            HTTP API.",
+        )
+        .subcommand(create_validators::cli_app())
+        .subcommand(import_validators::cli_app())
+        .subcommand(move_validators::cli_app())
+}
+
+/// Run the validator manager, returning an error if the operation did not succeed.
+pub fn run<T: EthSpec>(matches: &ArgMatches<'_>, env: Environment<T>) -> Result<(), String> {
+    match matches.subcommand() {
+        (create_validators::CMD, Some(matches)) => create_validators::cli_run(matches, env),
+        (import_validators::CMD, Some(matches)) => import_validators::cli_run(matches, env),
+        (move_validators::CMD, Some(matches)) => move_validators::cli_run(matches, env),
+        (unknown, _) => Err(format!(
+            "{} is not a valid {} command. See --help.",
+            unknown, CMD
+        )),
+    }
+}
@@ -0,0 +1,668 @@
+use crate::common::{
+    keystore_args, load_keystores, vc_args, vc_http_client, KEYSTORE_FLAG, PASSWORD_FILE_FLAG,
+};
+use account_utils::ZeroizeString;
+use clap::{App, ArgMatches};
+use environment::Environment;
+use eth2::lighthouse_vc::{
+    http_client::ValidatorClientHttpClient,
+    std_types::{
+        DeleteKeystoreStatus, DeleteKeystoresRequest, ImportKeystoreStatus, ImportKeystoresRequest,
+        InterchangeJsonStr, KeystoreJsonStr,
+    },
+};
+use eth2_keystore::Keystore;
+use sensitive_url::SensitiveUrl;
+use slashing_protection::interchange::Interchange;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use types::{EthSpec, PublicKeyBytes};
+
+pub const CMD: &str = "move";
+pub const SRC_VC_URL_FLAG: &str = "src-vc-url";
+pub const SRC_VC_TOKEN_FLAG: &str = "src-vc-token";
+pub const DEST_VC_URL_FLAG: &str = "dest-vc-url";
+pub const DEST_VC_TOKEN_FLAG: &str = "dest-vc-token";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Moves validators from one validator client to another. The validators are deleted \
+            from the source validator client and then imported into the destination along with \
+            their slashing protection data. Any validators which fail to import are deleted from \
+            the destination and restored to the source.",
+        )
+        .args(&vc_args(
+            SRC_VC_URL_FLAG,
+            SRC_VC_TOKEN_FLAG,
+            "The HTTP address of the validator client to move the validators from",
+        ))
+        .args(&vc_args(
+            DEST_VC_URL_FLAG,
+            DEST_VC_TOKEN_FLAG,
+            "The HTTP address of the validator client to move the validators to",
+        ))
+        .args(&keystore_args())
+}
+
+/// The arguments of the `move` command.
+pub struct MoveConfig {
+    pub src_vc_url: SensitiveUrl,
+    pub src_vc_token_path: PathBuf,
+    pub dest_vc_url: SensitiveUrl,
+    pub dest_vc_token_path: PathBuf,
+    pub keystore_paths: Vec<PathBuf>,
+    pub password_path: PathBuf,
+}
+
+impl MoveConfig {
+    fn from_cli(matches: &ArgMatches) -> Result<Self, String> {
+        Ok(Self {
+            src_vc_url: clap_utils::parse_required(matches, SRC_VC_URL_FLAG)?,
+            src_vc_token_path: clap_utils::parse_required(matches, SRC_VC_TOKEN_FLAG)?,
+            dest_vc_url: clap_utils::parse_required(matches, DEST_VC_URL_FLAG)?,
+            dest_vc_token_path: clap_utils::parse_required(matches, DEST_VC_TOKEN_FLAG)?,
+            keystore_paths: matches
+                .values_of(KEYSTORE_FLAG)
+                .ok_or_else(|| format!("--{} is required", KEYSTORE_FLAG))?
+                .map(PathBuf::from)
+                .collect(),
+            password_path: clap_utils::parse_required(matches, PASSWORD_FILE_FLAG)?,
+        })
+    }
+}
+
+pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<(), String> {
+    let config = MoveConfig::from_cli(matches)?;
+    env.runtime().block_on(run(config))
+}
+
+async fn run(config: MoveConfig) -> Result<(), String> {
+    let MoveConfig {
+        src_vc_url,
+        src_vc_token_path,
+        dest_vc_url,
+        dest_vc_token_path,
+        keystore_paths,
+        password_path,
+    } = config;
+
+    if src_vc_url.full == dest_vc_url.full {
+        return Err("The source and destination validator clients must be different".into());
+    }
+
+    // Check that every keystore can be decrypted before anything is deleted, so that the
+    // validators can always be imported into the destination.
+    let (keystores, password) = load_keystores(&keystore_paths, &password_path)?;
+    let pubkeys = keystores
+        .iter()
+        .map(|keystore| {
+            keystore
+                .public_key()
+                .map(|pubkey| pubkey.compress())
+                .ok_or_else(|| format!("Keystore {} has an invalid public key", keystore.pubkey()))
+        })
+        .collect::<Result<Vec<PublicKeyBytes>, _>>()?;
+
+    let (src_client, src_keystores) =
+        vc_http_client(src_vc_url.clone(), &src_vc_token_path).await?;
+    let (dest_client, dest_keystores) =
+        vc_http_client(dest_vc_url.clone(), &dest_vc_token_path).await?;
+
+    let src_pubkeys = src_keystores
+        .iter()
+        .filter(|keystore| keystore.readonly != Some(true))
+        .map(|keystore| keystore.validating_pubkey)
+        .collect::<HashSet<_>>();
+    let dest_pubkeys = dest_keystores
+        .iter()
+        .map(|keystore| keystore.validating_pubkey)
+        .collect::<HashSet<_>>();
+    for pubkey in &pubkeys {
+        if !src_pubkeys.contains(pubkey) {
+            return Err(format!(
+                "Validator {:?} is not a local keystore on {}",
+                pubkey, src_vc_url
+            ));
+        }
+        if dest_pubkeys.contains(pubkey) {
+            return Err(format!(
+                "Validator {:?} is already present on {}",
+                pubkey, dest_vc_url
+            ));
+        }
+    }
+
+    // Deleting the keystores disables them in the slashing protection database of the
+    // source, so the exported slashing protection data is final.
+    let delete_response = src_client
+        .delete_keystores(&DeleteKeystoresRequest {
+            pubkeys: pubkeys.clone(),
+        })
+        .await
+        .map_err(|e| {
+            format!(
+                "Unable to delete validators from {}, they may need to be re-imported: {:?}",
+                src_vc_url, e
+            )
+        })?;
+    let src_slashing_protection = delete_response.slashing_protection;
+
+    if delete_response.data.len() != pubkeys.len() {
+        return Err(format!(
+            "Unable to delete validators from {}, expected {} statuses but got {}. The move \
+            was aborted, check which validators remain on {} before re-importing any",
+            src_vc_url,
+            pubkeys.len(),
+            delete_response.data.len(),
+            src_vc_url
+        ));
+    }
+
+    // Abort the move unless every validator was deleted. The validators which were deleted
+    // are not active anywhere, so they can be safely restored to the source.
+    let mut deleted = vec![];
+    let mut not_deleted = vec![];
+    for (pubkey, status) in pubkeys.iter().zip(delete_response.data) {
+        if status.status == DeleteKeystoreStatus::Deleted {
+            deleted.push(*pubkey);
+        } else {
+            not_deleted.push(format!(
+                "{:?} {:?} {}",
+                pubkey,
+                status.status,
+                status.message.unwrap_or_default()
+            ));
+        }
+    }
+    if !not_deleted.is_empty() {
+        if !deleted.is_empty() {
+            restore(
+                &src_client,
+                &keystores,
+                &password,
+                &deleted,
+                &[&src_slashing_protection],
+            )
+            .await
+            .map_err(|e| {
+                format!(
+                    "Unable to delete validators from {} ({}), and unable to restore the \
+                    validators which were deleted: {}. Their slashing protection data was backed \
+                    up to the validators directory of {}",
+                    src_vc_url,
+                    not_deleted.join(", "),
+                    e,
+                    src_vc_url
+                )
+            })?;
+        }
+        return Err(format!(
+            "Unable to delete validators from {}, the move was aborted and {} deleted \
+            validators were restored: {}",
+            src_vc_url,
+            deleted.len(),
+            not_deleted.join(", ")
+        ));
+    }
+    eprintln!("Deleted {} validators from {}", pubkeys.len(), src_vc_url);
+
+    let request = ImportKeystoresRequest {
+        keystores: keystores.iter().cloned().map(KeystoreJsonStr).collect(),
+        passwords: vec![password.clone(); pubkeys.len()],
+        slashing_protection: Some(InterchangeJsonStr(src_slashing_protection.clone())),
+    };
+
+    // Any validator which was not reported as imported may or may not be active on the
+    // destination, e.g. if the response was lost.
+    let failed = match import(&dest_client, &request).await {
+        Ok(failed) => failed,
+        Err(e) => {
+            eprintln!("Unable to import validators into {}: {}", dest_vc_url, e);
+            pubkeys.clone()
+        }
+    };
+    let moved = pubkeys
+        .iter()
+        .filter(|pubkey| !failed.contains(pubkey))
+        .collect::<Vec<_>>();
+    for pubkey in &moved {
+        println!("{:?}", pubkey);
+    }
+
+    if failed.is_empty() {
+        eprintln!(
+            "Moved {} validators from {} to {}",
+            pubkeys.len(),
+            src_vc_url,
+            dest_vc_url
+        );
+        return Ok(());
+    }
+
+    // Restoring a validator to the source is only safe once it is certain not to be active on
+    // the destination, and with any slashing protection data the destination holds for it.
+    eprintln!(
+        "Unable to import {} validators into {}, restoring them to {}",
+        failed.len(),
+        dest_vc_url,
+        src_vc_url
+    );
+    let dest_delete_response = dest_client
+        .delete_keystores(&DeleteKeystoresRequest {
+            pubkeys: failed.clone(),
+        })
+        .await
+        .map_err(|e| {
+            format!(
+                "Unable to import {} validators into {}, and unable to check that they are \
+                inactive there: {:?}. They have NOT been restored to {}, check that they are \
+                inactive on {} before re-importing them anywhere",
+                failed.len(),
+                dest_vc_url,
+                e,
+                src_vc_url,
+                dest_vc_url
+            )
+        })?;
+
+    let mut restorable = vec![];
+    let mut unrestorable = vec![];
+    for (pubkey, status) in failed.iter().zip(dest_delete_response.data) {
+        match status.status {
+            DeleteKeystoreStatus::Deleted
+            | DeleteKeystoreStatus::NotActive
+            | DeleteKeystoreStatus::NotFound => restorable.push(*pubkey),
+            DeleteKeystoreStatus::Error => unrestorable.push(format!(
+                "{:?} {}",
+                pubkey,
+                status.message.unwrap_or_default()
+            )),
+        }
+    }
+    // Any validator missing a status is treated as possibly active on the destination.
+    unrestorable.extend(
+        failed
+            .iter()
+            .skip(restorable.len() + unrestorable.len())
+            .map(|pubkey| format!("{:?} missing status", pubkey)),
+    );
+
+    let restore_result = if restorable.is_empty() {
+        Ok(())
+    } else {
+        restore(
+            &src_client,
+            &keystores,
+            &password,
+            &restorable,
+            &[
+                &src_slashing_protection,
+                &dest_delete_response.slashing_protection,
+            ],
+        )
+        .await
+    };
+
+    let mut errors = vec![format!(
+        "Moved {} of {} validators to {}",
+        moved.len(),
+        pubkeys.len(),
+        dest_vc_url
+    )];
+    match restore_result {
+        Ok(()) => errors.push(format!(
+            "{} validators were restored to {}",
+            restorable.len(),
+            src_vc_url
+        )),
+        Err(e) => errors.push(format!(
+            "unable to restore {} validators to {}: {}. Their slashing protection data was \
+            backed up to the validators directories of {} and {}",
+            restorable.len(),
+            src_vc_url,
+            e,
+            src_vc_url,
+            dest_vc_url
+        )),
+    }
+    if !unrestorable.is_empty() {
+        errors.push(format!(
+            "{} validators could not be deleted from {} and have NOT been restored to {}, \
+            check that they are inactive on {} before re-importing them anywhere: {}",
+            unrestorable.len(),
+            dest_vc_url,
+            src_vc_url,
+            dest_vc_url,
+            unrestorable.join(", ")
+        ));
+    }
+    Err(errors.join("; "))
+}
+
+/// Import the keystores in `request` into the validator client of `client`, returning the
+/// public keys of the keystores which were not imported.
+async fn import(
+    client: &ValidatorClientHttpClient,
+    request: &ImportKeystoresRequest,
+) -> Result<Vec<PublicKeyBytes>, String> {
+    let response = client
+        .post_keystores(request)
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+
+    if response.data.len() != request.keystores.len() {
+        return Err(format!(
+            "expected {} statuses but got {}",
+            request.keystores.len(),
+            response.data.len()
+        ));
+    }
+
+    request
+        .keystores
+        .iter()
+        .zip(response.data)
+        .filter(|(_, status)| status.status != ImportKeystoreStatus::Imported)
+        .map(|(KeystoreJsonStr(keystore), status)| {
+            eprintln!(
+                "Unable to import {}: {:?} {}",
+                keystore.pubkey(),
+                status.status,
+                status.message.unwrap_or_default()
+            );
+            keystore
+                .public_key()
+                .map(|pubkey| pubkey.compress())
+                .ok_or_else(|| format!("Keystore {} has an invalid public key", keystore.pubkey()))
+        })
+        .collect()
+}
+
+/// Import the keystores of `pubkeys` back into the validator client of `client`, along with the
+/// slashing protection data from each of `slashing_protection` which relates to them.
+async fn restore(
+    client: &ValidatorClientHttpClient,
+    keystores: &[Keystore],
+    password: &ZeroizeString,
+    pubkeys: &[PublicKeyBytes],
+    slashing_protection: &[&Interchange],
+) -> Result<(), String> {
+    let keystores = keystores
+        .iter()
+        .filter(|keystore| {
+            keystore
+                .public_key()
+                .map_or(false, |pubkey| pubkeys.contains(&pubkey.compress()))
+        })
+        .cloned()
+        .map(KeystoreJsonStr)
+        .collect::<Vec<_>>();
+    let request = ImportKeystoresRequest {
+        passwords: vec![password.clone(); keystores.len()],
+        keystores,
+        slashing_protection: Some(InterchangeJsonStr(merge_slashing_protection(
+            slashing_protection,
+            pubkeys,
+        )?)),
+    };
+
+    let failed = import(client, &request).await?;
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("{} keystores were not imported", failed.len()))
+    }
+}
+
+/// Combine the slashing protection data for `pubkeys` from each of `interchanges` into a single
+/// minimal interchange.
+///
+/// The metadata of the first interchange is used, all of them must relate to the same chain.
+fn merge_slashing_protection(
+    interchanges: &[&Interchange],
+    pubkeys: &[PublicKeyBytes],
+) -> Result<Interchange, String> {
+    let metadata = interchanges
+        .first()
+        .map(|interchange| interchange.metadata.clone())
+        .ok_or("No slashing protection data to merge")?;
+
+    let mut merged = Interchange {
+        metadata,
+        data: vec![],
+    };
+    for interchange in interchanges {
+        if interchange.metadata != merged.metadata {
+            return Err(format!(
+                "Slashing protection data is for different chains: {:?} and {:?}",
+                merged.metadata, interchange.metadata
+            ));
+        }
+        merged.data.extend(
+            interchange
+                .data
+                .iter()
+                .filter(|data| pubkeys.contains(&data.pubkey))
+                .cloned(),
+        );
+    }
+
+    merged
+        .minify()
+        .map_err(|e| format!("Unable to merge slashing protection data: {:?}", e))
+}
+
+#[cfg(not(debug_assertions))]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use account_utils::random_password_string;
+    use eth2_keystore::KeystoreBuilder;
+    use slashing_protection::interchange::{
+        InterchangeData, InterchangeMetadata, SignedAttestation, SignedBlock,
+    };
+    use std::fs::{self, File};
+    use std::future::Future;
+    use std::sync::Arc;
+    use tempfile::{tempdir, TempDir};
+    use types::{Epoch, Hash256, Keypair, Slot};
+    use validator_client::http_api::test_utils::ApiTester;
+
+    fn run_test<F, V>(f: F)
+    where
+        F: FnOnce(ApiTester, ApiTester) -> V,
+        V: Future<Output = ()>,
+    {
+        let runtime = Arc::new(
+            tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .build()
+                .unwrap(),
+        );
+        let weak_runtime = Arc::downgrade(&runtime);
+        runtime.block_on(async {
+            let src = ApiTester::new(weak_runtime.clone()).await;
+            let dest = ApiTester::new(weak_runtime).await;
+            f(src, dest).await
+        });
+    }
+
+    /// Import `count` new keystores into `src`, and write them to `dir` along with their
+    /// password, returning the config to move them from `src` to `dest`.
+    async fn add_keystores(
+        src: &ApiTester,
+        dest: &ApiTester,
+        dir: &TempDir,
+        count: usize,
+    ) -> (MoveConfig, Vec<PublicKeyBytes>) {
+        let password = random_password_string();
+        let keystores = (0..count)
+            .map(|_| {
+                KeystoreBuilder::new(&Keypair::random(), password.as_ref(), String::new())
+                    .unwrap()
+                    .build()
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let statuses = src
+            .client
+            .post_keystores(&ImportKeystoresRequest {
+                keystores: keystores.iter().cloned().map(KeystoreJsonStr).collect(),
+                passwords: vec![password.clone(); count],
+                slashing_protection: None,
+            })
+            .await
+            .unwrap()
+            .data;
+        assert!(statuses
+            .iter()
+            .all(|status| status.status == ImportKeystoreStatus::Imported));
+
+        let keystore_paths = keystores
+            .iter()
+            .enumerate()
+            .map(|(i, keystore)| {
+                let path = dir.path().join(format!("keystore-{}.json", i));
+                keystore
+                    .to_json_writer(File::create(&path).unwrap())
+                    .unwrap();
+                path
+            })
+            .collect();
+        let password_path = dir.path().join("password.txt");
+        fs::write(&password_path, password.as_ref()).unwrap();
+
+        let config = MoveConfig {
+            src_vc_url: src.url.clone(),
+            src_vc_token_path: src.api_token_path.clone(),
+            dest_vc_url: dest.url.clone(),
+            dest_vc_token_path: dest.api_token_path.clone(),
+            keystore_paths,
+            password_path,
+        };
+        let pubkeys = keystores
+            .iter()
+            .map(|keystore| keystore.public_key().unwrap().compress())
+            .collect();
+        (config, pubkeys)
+    }
+
+    async fn pubkeys(tester: &ApiTester) -> HashSet<PublicKeyBytes> {
+        tester
+            .client
+            .get_keystores()
+            .await
+            .unwrap()
+            .data
+            .into_iter()
+            .map(|keystore| keystore.validating_pubkey)
+            .collect()
+    }
+
+    /// The path of the directory which the validator client creates when importing `pubkey`.
+    fn validator_dir_path(tester: &ApiTester, pubkey: &PublicKeyBytes) -> PathBuf {
+        tester.validator_dir.path().join(pubkey.as_hex_string())
+    }
+
+    #[test]
+    fn move_all_validators() {
+        run_test(|src, dest| async move {
+            let dir = tempdir().unwrap();
+            let (config, moved) = add_keystores(&src, &dest, &dir, 3).await;
+
+            run(config).await.unwrap();
+
+            assert!(pubkeys(&src).await.is_empty());
+            assert_eq!(pubkeys(&dest).await, moved.into_iter().collect());
+        })
+    }
+
+    #[test]
+    fn failed_import_restores_only_failed_validators() {
+        run_test(|src, dest| async move {
+            let dir = tempdir().unwrap();
+            let (config, pubkeys_to_move) = add_keystores(&src, &dest, &dir, 3).await;
+
+            // The destination is unable to create a directory for the second validator, so only
+            // the second validator should be restored to the source.
+            fs::create_dir(validator_dir_path(&dest, &pubkeys_to_move[1])).unwrap();
+
+            run(config).await.unwrap_err();
+
+            assert_eq!(pubkeys(&src).await, HashSet::from([pubkeys_to_move[1]]));
+            assert_eq!(
+                pubkeys(&dest).await,
+                HashSet::from([pubkeys_to_move[0], pubkeys_to_move[2]])
+            );
+        })
+    }
+
+    #[test]
+    fn failed_delete_aborts_move() {
+        run_test(|src, dest| async move {
+            let dir = tempdir().unwrap();
+            let (config, pubkeys_to_move) = add_keystores(&src, &dest, &dir, 2).await;
+
+            // The source is unable to delete the first validator, since its directory has gone.
+            fs::remove_dir_all(validator_dir_path(&src, &pubkeys_to_move[0])).unwrap();
+
+            let error = run(config).await.unwrap_err();
+            assert!(error.contains("aborted"), "{}", error);
+
+            // The second validator is restored, and nothing is imported into the destination.
+            assert_eq!(pubkeys(&src).await, HashSet::from([pubkeys_to_move[1]]));
+            assert!(pubkeys(&dest).await.is_empty());
+        })
+    }
+
+    #[test]
+    fn merge_slashing_protection_for_pubkeys() {
+        let metadata = InterchangeMetadata {
+            interchange_format_version: 5,
+            genesis_validators_root: Hash256::repeat_byte(1),
+        };
+        let data = |pubkey: PublicKeyBytes, slot: u64, target_epoch: u64| InterchangeData {
+            pubkey,
+            signed_blocks: vec![SignedBlock {
+                slot: Slot::new(slot),
+                signing_root: None,
+            }],
+            signed_attestations: vec![SignedAttestation {
+                source_epoch: Epoch::new(0),
+                target_epoch: Epoch::new(target_epoch),
+                signing_root: None,
+            }],
+        };
+        let moved = Keypair::random().pk.compress();
+        let other = Keypair::random().pk.compress();
+
+        let src = Interchange {
+            metadata: metadata.clone(),
+            data: vec![data(moved, 10, 2), data(other, 20, 3)],
+        };
+        let dest = Interchange {
+            metadata: metadata.clone(),
+            data: vec![data(moved, 5, 4)],
+        };
+
+        let merged = merge_slashing_protection(&[&src, &dest], &[moved]).unwrap();
+        assert_eq!(
+            merged,
+            Interchange {
+                metadata: metadata.clone(),
+                data: vec![data(moved, 10, 4)],
+            }
+        );
+
+        let other_chain = Interchange {
+            metadata: InterchangeMetadata {
+                genesis_validators_root: Hash256::repeat_byte(2),
+                ..metadata
+            },
+            data: vec![],
+        };
+        merge_slashing_protection(&[&src, &other_chain], &[moved]).unwrap_err();
+    }
+}